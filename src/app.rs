@@ -4,23 +4,47 @@
 //! of ChromaCat. It handles initialization, input processing, and orchestrates
 //! the pattern generation and rendering pipeline.
 
-use crate::cli::Cli;
+use crate::background::{self, Background};
+use crate::cli::{Cli, Command, PlaylistCommand, ThemesCommand};
+#[cfg(unix)]
+use crate::control::{self, ControlCommand, ControlServer};
+use crate::demo::{DemoArt, DemoArtGenerator};
 use crate::error::{ChromaCatError, Result};
-use crate::input::InputReader;
-use crate::pattern::PatternEngine;
-use crate::playlist::{load_default_playlist, Playlist};
-use crate::renderer::Renderer;
+use crate::exec::CommandRunner;
+use crate::frames::{self, FrameFormat, PatternFrame};
+use crate::guard::{self, TerminalGuard};
+use crate::motd::{self, SystemInfo};
+#[cfg(unix)]
+use crate::mqtt::{MqttBridge, MqttState};
+use crate::progress;
+use crate::input::{self, InputReader};
+use crate::pattern::{FixedStep, PatternEngine, PostProcess};
+use crate::playlist::{
+    self, load_default_playlist, AdaptiveSource, Favorites, Playlist, ScenePool, Sky,
+    RANDOM_SCENES,
+};
+use crate::renderer::{
+    LoopStage, PanelLayout, Recovery, RegionLayout, RenderBuffer, Renderer, ScreenshotTarget,
+    terminal,
+};
+use crate::screensaver;
+use crate::statusline::StatusLine;
 use crate::streaming::StreamingInput;
+use crate::sync::{SyncFollower, SyncLeader, SyncState, SYNC_INTERVAL};
+use crate::termcaps::{self, TermCaps};
+use crate::theme_packs;
 use crate::themes;
+use crate::wled::{LedMap, WledOutput, WledTarget};
 
-use crossterm::cursor::{Hide, Show};
+use crossterm::cursor::{Hide, MoveUp, Show};
 use crossterm::event::{self, Event};
-use crossterm::execute;
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
-};
+use crossterm::{execute, queue};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use log::{debug, info};
-use std::io::{stdout, Write};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::{stdout, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 /// Main application struct that coordinates ChromaCat functionality
@@ -33,8 +57,38 @@ pub struct ChromaCat {
     raw_mode: bool,
     /// Whether we're using the alternate screen
     alternate_screen: bool,
+    /// Process exit code to report once the run completes
+    exit_code: i32,
+    /// Terminal background color that minimum contrast is measured against
+    background_color: (f32, f32, f32),
+    /// Playlist for `chromacat themes preview`
+    preview: Option<Playlist>,
+    /// Control socket when running as `chromacat daemon`
+    #[cfg(unix)]
+    control: Option<ControlServer>,
+    /// Connection to followers when running with `--sync-leader`
+    sync_leader: Option<SyncLeader>,
+    /// Connection to the leader when running with `--sync-follow`
+    sync_follower: Option<SyncFollower>,
+    /// Connection to the broker when running with `--mqtt`
+    #[cfg(unix)]
+    mqtt: Option<MqttBridge>,
+    /// Random numbers for `--randomize` and the renderer's random scenes
+    rng: StdRng,
+    /// Scenes suiting the sky, drawn from with `--adaptive`
+    adaptive_pool: Option<ScenePool>,
+    /// Piped input read whole before the terminal was taken over
+    piped_input: Option<String>,
 }
 
+/// Largest input paged on its own when longer than the screen; larger
+/// input is printed a chunk at a time instead of being read into memory
+const MAX_PAGED_BYTES: u64 = 64 << 20;
+
+/// Largest difference from the leader's animation time that a follower
+/// tolerates before jumping to it
+const SYNC_TOLERANCE: f64 = 0.05;
+
 impl ChromaCat {
     /// Creates a new ChromaCat instance with the given CLI configuration
     pub fn new(cli: Cli) -> Self {
@@ -43,13 +97,47 @@ impl ChromaCat {
             term_size: (0, 0),
             raw_mode: false,
             alternate_screen: false,
+            exit_code: 0,
+            background_color: Background::Dark.color(),
+            preview: None,
+            #[cfg(unix)]
+            control: None,
+            sync_leader: None,
+            sync_follower: None,
+            #[cfg(unix)]
+            mqtt: None,
+            rng: StdRng::from_entropy(),
+            adaptive_pool: None,
+            piped_input: None,
         }
     }
 
+    /// Returns the exit code the process should report, e.g. the exit code
+    /// of a command run with `chromacat exec`
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
     /// Runs the ChromaCat application
     pub fn run(&mut self) -> Result<()> {
         debug!("Starting ChromaCat with configuration: {:?}", self.cli);
 
+        // Whatever happens from here on, leave the terminal as it was found
+        guard::install();
+        let _guard = TerminalGuard::new();
+
+        if self.cli.headless {
+            termcaps::set_headless();
+        } else if !termcaps::enable_ansi() {
+            // Legacy Windows consoles would show escape sequences as text
+            if self.cli.animation.animate {
+                return Err(ChromaCatError::TerminalError(
+                    "Animation needs a console that understands escape sequences, such as Windows Terminal".to_string(),
+                ));
+            }
+            self.cli.no_color = true;
+        }
+
         // Handle --list-art flag
         if self.cli.list_art {
             Cli::print_art_patterns();
@@ -58,83 +146,181 @@ impl ChromaCat {
 
         // Handle --list flag
         if self.cli.list_available {
-            Cli::print_available_options();
+            return self.cli.print_list();
+        }
+
+        // Handle --check-playlist
+        if let Some(path) = &self.cli.check_playlist {
+            self.exit_code = Self::check_playlist(path)?;
             return Ok(());
         }
+        if let Some(Command::Playlist { command }) = &self.cli.command {
+            return Self::run_playlist_command(command);
+        }
+        if let Some(Command::Themes { command }) = &self.cli.command {
+            if matches!(
+                command,
+                ThemesCommand::Install { .. } | ThemesCommand::Remove { .. }
+            ) {
+                return Self::run_themes_command(command);
+            }
+        }
 
         // Validate CLI arguments
         self.cli.validate()?;
 
-        // Initialize terminal
-        self.setup_terminal()?;
+        if let Some(seed) = self.cli.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        if self.cli.randomize {
+            self.randomize()?;
+        }
+        if let Some(source) = self.cli.playlist.adaptive {
+            self.adapt_to_sky(source)?;
+        }
 
         // Load custom theme file if specified
         if let Some(theme_file) = &self.cli.theme_file {
             themes::load_theme_file(theme_file)?;
         }
 
+        // Ask the terminal about its background before anything else is drawn
+        if self.cli.adapt_bg || self.cli.min_contrast.is_some() {
+            let color = background::detect_color();
+            info!("Detected terminal background: {:?}", color);
+            if self.cli.adapt_bg {
+                themes::set_background(color.map(|(r, g, b)| Background::from_rgb(r, g, b)))?;
+            }
+            // Themes are designed for dark backgrounds, so assume one
+            self.background_color = color.unwrap_or_else(|| Background::Dark.color());
+        }
+
+        // Subcommands manage the terminal themselves
+        if let Some(Command::Exec { command }) = &self.cli.command {
+            return self.run_exec(command.clone());
+        }
+        if let Some(mut options) = self.cli.create_progress_options() {
+            options.color_support = self
+                .cli
+                .profile
+                .unwrap_or_default()
+                .colors(TermCaps::current().color);
+            return progress::run_progress(&options, &self.cli.theme);
+        }
+        if let Some(Command::Motd { template, width }) = &self.cli.command {
+            return self.run_motd(template.as_deref(), *width);
+        }
+        if let Some(Command::Ctl { socket, command }) = &self.cli.command {
+            return Self::run_ctl(socket.as_deref(), command);
+        }
+        if let Some(Command::Daemon { socket, .. }) = &self.cli.command {
+            let socket = socket.clone();
+            self.start_daemon(socket)?;
+        }
+        if let Some(Command::Themes {
+            command: ThemesCommand::Preview { theme, seconds, .. },
+        }) = &self.cli.command
+        {
+            let theme = theme.clone();
+            let seconds = *seconds;
+            self.start_theme_preview(theme.as_deref(), seconds)?;
+        }
+        if self.cli.screensaver {
+            self.start_screensaver();
+            if let Some(idle) = self.cli.idle.filter(|_| !Self::is_headless()) {
+                if !screensaver::wait_for_idle(Duration::from_secs(idle))? {
+                    info!("Input arrived before the terminal was idle");
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(format) = self.cli.emit_frames {
+            return self.run_emit_frames(format);
+        }
+        if let Some(target) = &self.cli.output {
+            return self.run_led_output(target);
+        }
+        if let Some(target) = &self.cli.screenshot {
+            return self.run_screenshot(target);
+        }
+        if let Some(width) = self.cli.status_line {
+            return self.run_status_line(width);
+        }
+        if self.cli.animation.inline {
+            return self.run_inline();
+        }
+
+        if let Some(addr) = &self.cli.animation.sync_leader {
+            self.sync_leader = Some(SyncLeader::bind(addr)?);
+        }
+        if let Some(addr) = &self.cli.animation.sync_follow {
+            self.sync_follower = Some(SyncFollower::connect(addr)?);
+        }
+        #[cfg(unix)]
+        if let Some(url) = &self.cli.animation.mqtt {
+            let broker = url.parse().map_err(ChromaCatError::InputError)?;
+            self.mqtt = Some(MqttBridge::connect(broker, &self.cli.animation.mqtt_topic)?);
+        }
+
+        // Load the layout before taking over the screen, so errors stay visible
+        let layout = self.cli.animation.layout.as_deref().map(Self::load_layout).transpose()?;
+
+        // Piped input shown full screen is read before raw mode, so the
+        // terminal is left alone until the pipe ends
+        self.read_piped_input()?;
+
+        // Initialize terminal
+        self.setup_terminal()?;
+
         // Create theme and gradient
-        info!("Creating theme and gradient");
+        info!("Loading theme");
         let theme = themes::get_theme(&self.cli.theme)?;
-        let gradient = theme.create_gradient()?;
 
         // Create pattern configuration
         info!("Creating pattern configuration");
         let pattern_config = self.cli.create_pattern_config()?;
 
+        // Set up the renderer
+        let mut animation_config = self.cli.create_animation_config();
+        animation_config.layout = layout;
+
+        // The pattern covers the whole virtual canvas, not just the terminal
         info!("Initializing pattern engine");
-        let engine = PatternEngine::new(
-            gradient,
+        let canvas_scale = animation_config.camera.canvas_scale();
+        let mut engine = PatternEngine::from_theme(
+            &theme,
             pattern_config,
-            self.term_size.0 as usize,
-            self.term_size.1 as usize,
-        );
+            (self.term_size.0 as f64 * canvas_scale).round() as usize,
+            (self.term_size.1 as f64 * canvas_scale).round() as usize,
+        )?;
+        engine.set_postprocess(self.create_postprocess());
+        engine.set_light_map(self.cli.create_light_map()?);
 
-        // Set up the renderer
-        let animation_config = self.cli.create_animation_config();
         info!("Creating renderer with config: {:?}", animation_config);
 
-        // Load playlist if enabled
-        let playlist = if let Some(playlist_path) = &self.cli.playlist {
-            match Playlist::from_file(playlist_path) {
-                Ok(p) => {
-                    info!(
-                        "Loaded playlist from {} with {} entries",
-                        playlist_path.display(),
-                        p.entries.len()
-                    );
-                    if let Some(first) = p.entries.first() {
-                        info!(
-                            "First entry: pattern={}, theme={}, art={:?}",
-                            first.pattern, first.theme, first.art
-                        );
-                    }
-                    // Validate all entries
-                    for (index, entry) in p.entries.iter().enumerate() {
-                        if let Err(e) = entry.validate() {
-                            return Err(ChromaCatError::Other(format!(
-                                "Invalid playlist entry {} ({}): {}",
-                                index + 1,
-                                entry.name,
-                                e
-                            )));
-                        }
-                    }
-                    Some(p)
-                }
-                Err(e) => {
-                    return Err(ChromaCatError::Other(format!(
-                        "Failed to load playlist from {}: {}",
-                        playlist_path.display(),
-                        e
-                    )));
-                }
-            }
-        } else if self.cli.animate {
+        // Load playlist if enabled, remembering the file it came from so
+        // edits to it are picked up during playback
+        let mut playlist_source = None;
+        let playlist = if let Some(preview) = self.preview.take() {
+            Some(preview)
+        } else if let Some(playlist_path) = &self.cli.playlist.file {
+            playlist_source = Some(playlist_path.clone());
+            Some(Self::load_playlist(playlist_path)?)
+        } else if self.cli.playlist.favorites {
+            Some(Self::load_favorites()?)
+        } else if self.cli.playlist.random {
+            Some(Self::random_playlist(
+                self.cli.playlist.pool.as_deref(),
+                self.cli.theme_tag.as_deref(),
+            )?)
+        } else if let Some(pool) = self.adaptive_pool.take().filter(|_| self.cli.animation.animate) {
+            Some(pool.to_playlist(&mut rand::thread_rng(), RANDOM_SCENES)?)
+        } else if self.cli.animation.animate {
             // Try loading default playlist in animation mode
             match load_default_playlist()? {
                 Some(p) => {
                     info!("Loaded default playlist");
+                    playlist_source = Some(playlist::get_default_playlist_path());
                     Some(p)
                 }
                 None => {
@@ -153,47 +339,891 @@ impl ChromaCat {
             playlist,
             self.cli.demo
         )?;
+        if let Some(path) = &playlist_source {
+            renderer.watch_playlist(path);
+        }
+        // Random scenes carry on from the draw --randomize started with
+        renderer.set_rng(self.rng.clone());
+        renderer.set_diff(self.cli.create_diff_palettes()?);
+        renderer.set_columns(self.cli.create_columns()?);
+        renderer.set_data(self.cli.create_data_column());
+        match PanelLayout::load_default() {
+            Ok(layout) => renderer.set_panel_layout(layout),
+            Err(e) => log::warn!("Using the default panel layout: {}", e),
+        }
+        // Followers change scenes when the leader does
+        renderer.set_playlist_auto_advance(self.sync_follower.is_none());
+        if self.cli.playlist.record_session.is_some() {
+            renderer.start_recording();
+        }
+        if self.cli.animation.summary || self.cli.animation.stats.is_some() {
+            renderer.start_stats(self.cli.animation.stats.clone());
+        }
+
+        // Process input and render; a screen saver's locker takes over the
+        // blanked screen once it ends
+        let result = self
+            .process_input(&mut renderer)
+            .and_then(|_| self.check_not_empty())
+            .and_then(|_| self.lock_screen());
 
-        // Process input and render
-        let result = self.process_input(&mut renderer);
+        // Write out the session and stats before restoring the terminal, so
+        // they are kept even if the animation or the cleanup fails
+        let saved = self
+            .cli
+            .playlist
+            .record_session
+            .clone()
+            .map(|path| renderer.save_session(&path).map(|_| path))
+            .transpose();
+        let report = renderer.stats().map(|stats| stats.report());
+        let stats_saved = match (&report, &self.cli.animation.stats) {
+            (Some(report), Some(path)) => report.save(path),
+            _ => Ok(()),
+        };
 
         // Cleanup terminal
-        self.cleanup_terminal()?;
+        let cleanup = self.cleanup_terminal();
+
+        if let Ok(Some(path)) = &saved {
+            let scenes = renderer.session().map_or(0, |session| session.len());
+            if !self.cli.quiet {
+                eprintln!("Recorded {} scenes to {}", scenes, path.display());
+            }
+        }
+
+        // Report what the animation did
+        if let Some(report) = report.filter(|_| self.cli.animation.summary) {
+            eprintln!("{}", report.summary());
+        }
+
+        // The animation's own error comes first
+        result?;
+        cleanup?;
+        saved?;
+        stats_saved
+    }
+
+    /// Replaces the pattern, theme, and parameters with a random draw
+    fn randomize(&mut self) -> Result<()> {
+        let mut pool = ScenePool::default();
+        if let Some(tag) = &self.cli.theme_tag {
+            pool.set_theme_tag(tag)?;
+        }
+        let entry = pool.surprise(&mut self.rng)?;
+        let params = entry.params_string()?;
+        info!(
+            "Randomized to {} with {} theme: {}",
+            entry.pattern,
+            entry.theme,
+            params.as_deref().unwrap_or_default()
+        );
+        self.cli.pattern = entry.pattern;
+        self.cli.theme = entry.theme;
+        self.cli.params = params.into_iter().collect();
+        Ok(())
+    }
+
+    /// Reads the sky and starts on a scene that suits it; animations go on
+    /// to play scenes drawn from the same pool
+    fn adapt_to_sky(&mut self, source: AdaptiveSource) -> Result<()> {
+        let sky = Sky::detect(source, self.cli.playlist.location.as_deref());
+        let pool = sky.pool();
+        let (pattern, theme) = pool.pick(&mut self.rng)?;
+        info!("Adapting to {:?}: {} with {} theme", sky, pattern, theme);
+        self.cli.pattern = pattern;
+        self.cli.theme = theme;
+        self.cli.params.clear();
+        self.adaptive_pool = Some(pool);
+        Ok(())
+    }
+
+    /// Loads a region layout file and validates all of its regions
+    fn load_layout(path: &Path) -> Result<RegionLayout> {
+        let layout = RegionLayout::from_file(path).map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to load layout from {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        info!(
+            "Loaded layout from {} with {} regions",
+            path.display(),
+            layout.regions.len()
+        );
+        Ok(layout)
+    }
+
+    /// Loads a playlist file and validates all of its entries
+    fn load_playlist(path: &Path) -> Result<Playlist> {
+        let playlist = Playlist::from_file(path).map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to load playlist from {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        info!(
+            "Loaded playlist from {} with {} entries",
+            path.display(),
+            playlist.entries.len()
+        );
+        if let Some(first) = playlist.entries.first() {
+            info!(
+                "First entry: pattern={}, theme={}, art={:?}",
+                first.pattern, first.theme, first.art
+            );
+        }
+        Ok(playlist)
+    }
+
+    /// Loads the bookmarked favorites as a playlist playing each in turn
+    fn load_favorites() -> Result<Playlist> {
+        let favorites = Favorites::load_default()?;
+        if favorites.is_empty() {
+            return Err(ChromaCatError::InputError(format!(
+                "No favorites saved in {}; press B while animating to bookmark one",
+                favorites.path().display()
+            )));
+        }
+        info!("Playing {} favorites", favorites.len());
+        Ok(favorites.to_playlist())
+    }
+
+    /// Draws a playlist of random scenes from a pool file, the user's
+    /// pool, or every pattern and theme, keeping to themes tagged `tag`
+    fn random_playlist(pool: Option<&Path>, tag: Option<&str>) -> Result<Playlist> {
+        let mut pool = match pool {
+            Some(path) => ScenePool::from_file(path)?,
+            None => ScenePool::load_default()?,
+        };
+        if let Some(tag) = tag {
+            pool.set_theme_tag(tag)?;
+        }
+        pool.to_playlist(&mut rand::thread_rng(), RANDOM_SCENES)
+    }
+
+    /// Prints the command lines for a playlist's entries, or the playlist
+    /// for a command line
+    fn run_playlist_command(command: &PlaylistCommand) -> Result<()> {
+        match command {
+            PlaylistCommand::ToCli { playlist } => {
+                let playlist = Playlist::from_file(playlist)?;
+                for entry in &playlist.entries {
+                    println!("# {} ({}s)", entry.description(), entry.duration);
+                    match entry.to_command() {
+                        Ok(command) => println!("{}", command),
+                        Err(e) => println!("# {}", e),
+                    }
+                }
+            }
+            PlaylistCommand::FromCli { args, duration } => {
+                let playlist = Cli::playlist_from_args(args, *duration)?;
+                let yaml = serde_yaml::to_string(&playlist).map_err(|e| {
+                    ChromaCatError::Other(format!("Failed to serialize playlist: {}", e))
+                })?;
+                print!("{}", yaml);
+            }
+        }
+        Ok(())
+    }
+
+    /// Installs or removes a theme pack
+    fn run_themes_command(command: &ThemesCommand) -> Result<()> {
+        let dir = theme_packs::get_user_themes_dir();
+        match command {
+            ThemesCommand::Install { source } => {
+                let pack = theme_packs::install(source, &dir)?;
+                let names: Vec<&str> = pack.themes.iter().map(|t| t.name.as_str()).collect();
+                println!(
+                    "Installed theme pack '{}' to {}: {}",
+                    pack.name,
+                    pack.path.display(),
+                    names.join(", ")
+                );
+            }
+            ThemesCommand::Remove { name } => {
+                let path = theme_packs::remove(name, &dir)?;
+                println!("Removed {}", path.display());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Checks a playlist file, printing every problem found, and returns the
+    /// exit code: 0 if the playlist is ready to play, 1 otherwise
+    fn check_playlist(path: &Path) -> Result<i32> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ChromaCatError::InputError(format!(
+                "Failed to read playlist file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let issues = playlist::check_playlist(&contents);
+        if issues.is_empty() {
+            let playlist: Playlist = contents.parse()?;
+            let seconds: u64 = playlist.entries.iter().map(|e| e.duration).sum();
+            println!(
+                "{}: OK ({} entries, {}m {:02}s)",
+                path.display(),
+                playlist.entries.len(),
+                seconds / 60,
+                seconds % 60
+            );
+            return Ok(0);
+        }
+
+        for issue in &issues {
+            eprintln!("{}: {}", path.display(), issue);
+        }
+        eprintln!(
+            "{}: {} problem{} found",
+            path.display(),
+            issues.len(),
+            if issues.len() == 1 { "" } else { "s" }
+        );
+        Ok(1)
+    }
+
+    /// Starts listening for control commands; the daemon then runs like any
+    /// animated display
+    #[cfg(unix)]
+    fn start_daemon(&mut self, socket: Option<PathBuf>) -> Result<()> {
+        let path = socket.unwrap_or_else(control::default_socket_path);
+        let server = ControlServer::bind(&path)?;
+        info!("Listening for control commands on {}", server.path().display());
+        self.control = Some(server);
+        self.cli.animation.animate = true;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn start_daemon(&mut self, _socket: Option<PathBuf>) -> Result<()> {
+        Err(ChromaCatError::Other(
+            "chromacat daemon needs unix domain sockets".to_string(),
+        ))
+    }
+
+    /// Sets up a theme preview: the preview playlist plays once over the
+    /// logo art, then the run ends
+    fn start_theme_preview(&mut self, theme: Option<&str>, seconds: u64) -> Result<()> {
+        let preview = playlist::theme_preview(theme, seconds)?;
+        info!("Previewing {} playlist entries", preview.entries.len());
+        self.cli.animation.animate = true;
+        self.cli.demo = true;
+        self.cli.art.name = Some(DemoArt::Logo.as_str().to_string());
+        self.cli.animation.duration = seconds * preview.entries.len() as u64;
+        self.preview = Some(preview);
+        Ok(())
+    }
+
+    /// Sets up the screen saver: random scenes over the demo art, or over
+    /// the given files or playlist, animated until a key is pressed
+    fn start_screensaver(&mut self) {
+        self.cli.animation.animate = true;
+        self.cli.demo = self.cli.files.is_empty();
+        if self.cli.playlist.file.is_none() && !self.cli.playlist.favorites {
+            self.cli.playlist.random = true;
+        }
+    }
+
+    /// Runs the `--lock-cmd` locker on the blanked screen, unless the run
+    /// was interrupted, reporting its exit code if it fails
+    fn lock_screen(&mut self) -> Result<()> {
+        let Some(command) = &self.cli.lock_cmd else {
+            return Ok(());
+        };
+        if Self::is_headless() || guard::interrupted() {
+            return Ok(());
+        }
+
+        info!("Running locker: {}", command);
+        let status = screensaver::run_locker(command)?;
+        if !status.success() {
+            self.exit_code = status.code().unwrap_or(1);
+        }
+        Ok(())
+    }
+
+    /// Sends a command to a running daemon and prints its reply
+    #[cfg(unix)]
+    fn run_ctl(socket: Option<&Path>, args: &[String]) -> Result<()> {
+        let command = ControlCommand::from_args(args)
+            .map_err(ChromaCatError::InputError)?
+            .absolutize();
+        let path = socket
+            .map(Path::to_path_buf)
+            .unwrap_or_else(control::default_socket_path);
+
+        let reply = control::send_command(&path, &command)?;
+        if !reply.is_empty() {
+            println!("{}", reply);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn run_ctl(_socket: Option<&Path>, _args: &[String]) -> Result<()> {
+        Err(ChromaCatError::Other(
+            "chromacat ctl needs unix domain sockets".to_string(),
+        ))
+    }
+
+    /// Applies pending control commands to the renderer.
+    ///
+    /// Returns false once a `quit` command has been received.
+    #[cfg(unix)]
+    fn poll_control(&self, renderer: &mut Renderer) -> bool {
+        let Some(server) = &self.control else {
+            return true;
+        };
+
+        while let Some(request) = server.try_recv() {
+            if request.command == ControlCommand::Quit {
+                request.reply(Ok(String::new()));
+                return false;
+            }
+            let result = self
+                .apply_control(renderer, &request.command)
+                .map_err(|e| e.to_string());
+            request.reply(result);
+        }
+        true
+    }
+
+    #[cfg(not(unix))]
+    fn poll_control(&self, _renderer: &mut Renderer) -> bool {
+        true
+    }
+
+    /// Applies commands received over MQTT and publishes the state
+    #[cfg(unix)]
+    fn poll_mqtt(&self, renderer: &mut Renderer) {
+        let Some(bridge) = &self.mqtt else {
+            return;
+        };
+
+        while let Some(command) = bridge.try_recv() {
+            if let Err(e) = self.apply_control(renderer, &command) {
+                log::warn!("MQTT command '{}' failed: {}", command, e);
+            }
+        }
+        let scene = renderer.scene_progress();
+        bridge.publish_state(&MqttState {
+            pattern: renderer.pattern_id().to_string(),
+            theme: renderer.theme_name().to_string(),
+            scene: scene.map(|(name, _)| name.to_string()),
+            progress: scene.map(|(_, progress)| (progress * 100.0).round() as u8),
+            paused: renderer.is_paused(),
+        });
+    }
+
+    #[cfg(not(unix))]
+    fn poll_mqtt(&self, _renderer: &mut Renderer) {}
+
+    /// Applies one control command, returning the message for the client
+    #[cfg(unix)]
+    fn apply_control(
+        &self,
+        renderer: &mut Renderer,
+        command: &ControlCommand,
+    ) -> std::result::Result<String, crate::renderer::RendererError> {
+        info!("Control command: {}", command);
+        match command {
+            ControlCommand::SetTheme(name) => renderer.set_theme(name)?,
+            ControlCommand::SetPattern(id) => renderer.set_pattern(id)?,
+            ControlCommand::NextTheme => renderer.next_theme()?,
+            ControlCommand::NextPattern => renderer.next_pattern()?,
+            ControlCommand::NextScene => renderer.next_scene()?,
+            ControlCommand::PrevScene => renderer.previous_scene()?,
+            ControlCommand::LoadPlaylist(path) => {
+                renderer.load_playlist(Self::load_playlist(path)?)?
+            }
+            ControlCommand::Pause => renderer.set_paused(true),
+            ControlCommand::Resume => renderer.set_paused(false),
+            ControlCommand::Status => {
+                let state = if renderer.is_paused() { "paused" } else { "playing" };
+                return Ok(format!("{} {}", renderer.status(), state));
+            }
+            ControlCommand::Quit => {}
+        }
+        renderer.redraw()?;
+        Ok(String::new())
+    }
+
+    /// Shares or adopts animation state with other instances.
+    ///
+    /// A leader sends its state every [`SYNC_INTERVAL`] and whenever the
+    /// playlist entry changes; a follower applies the newest state received.
+    fn sync_frame(
+        &self,
+        renderer: &mut Renderer,
+        last_sync: &mut Option<(Instant, Option<usize>)>,
+        fixed_step: &mut Option<FixedStep>,
+    ) {
+        if let Some(leader) = &self.sync_leader {
+            let position = renderer.playlist_position();
+            let scene = position.map(|(index, _)| index);
+            let due = match last_sync {
+                Some((sent, sent_scene)) => sent.elapsed() >= SYNC_INTERVAL || *sent_scene != scene,
+                None => true,
+            };
+            if due {
+                leader.broadcast(&SyncState {
+                    time: renderer.animation_time(),
+                    scene: position.map(|(index, elapsed)| (index, elapsed.as_secs_f64())),
+                    paused: renderer.is_paused(),
+                });
+                *last_sync = Some((Instant::now(), scene));
+            }
+        }
+
+        if let Some(state) = self.sync_follower.as_ref().and_then(SyncFollower::latest) {
+            if (renderer.animation_time() - state.time).abs() > SYNC_TOLERANCE {
+                // Landing on the frame grid keeps followers drawing the same
+                // frames as each other
+                let time = match fixed_step {
+                    Some(step) => step.snap(state.time, renderer.animation_rate()),
+                    None => state.time,
+                };
+                renderer.set_animation_time(time);
+            }
+            if let Some((index, elapsed)) = state.scene {
+                if let Err(e) = renderer.seek_playlist(index, Duration::from_secs_f64(elapsed)) {
+                    renderer.recover(LoopStage::Transition, &e);
+                }
+            }
+            renderer.set_paused(state.paused);
+        }
+    }
+
+    /// Runs a command in a pseudo-terminal and colorizes its output
+    fn run_exec(&mut self, argv: Vec<String>) -> Result<()> {
+        info!("Running command: {:?}", argv);
+        let pattern_config = self.cli.create_pattern_config()?;
+
+        let mut runner = CommandRunner::new(argv, pattern_config, &self.cli.theme)?;
+        runner.set_colors_enabled(!self.cli.no_color);
+        runner.set_postprocess(self.create_postprocess());
+        runner.set_light_map(self.cli.create_light_map()?);
+
+        self.exit_code = runner.run()?;
+        Ok(())
+    }
+
+    /// Renders a single frame of the input and sends it to a screenshot target
+    ///
+    /// Animated runs capture the first frame of the viewport; static runs
+    /// capture the whole text as it would be printed.
+    fn run_screenshot(&self, target: &ScreenshotTarget) -> Result<()> {
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        let height = height.saturating_sub(2).max(1);
+
+        let text = self.read_text(width, height)?;
+        let engine = self.create_frame_engine(width as usize, height as usize)?;
+        let mut buffer = self.create_render_buffer((width, height))?;
+        buffer.prepare_text(&text)?;
+
+        let frame = if self.cli.animation.animate {
+            buffer.update_colors(&engine, 0)?;
+            buffer.capture(0, height as usize)
+        } else {
+            buffer.update_colors_static(&engine)?;
+            buffer.capture(0, buffer.line_count())
+        };
+
+        target.deliver(&frame, &mut stdout())?;
+        if let ScreenshotTarget::File(path) = target {
+            if !self.cli.quiet {
+                eprintln!("Saved {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the pattern's colors every frame to stdout or `--emit-to`,
+    /// until `--duration` ends, the run is interrupted, or the reader goes
+    /// away; with `--headless`, writes one frame
+    fn run_emit_frames(&self, format: FrameFormat) -> Result<()> {
+        let (width, height) = self.cli.frame_size.unwrap_or_else(|| {
+            let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+            (width as usize, height as usize)
+        });
+        let mut out: Box<dyn Write> = match &self.cli.emit_to {
+            Some(path) => Box::new(BufWriter::new(frames::open_sink(path)?)),
+            None => Box::new(BufWriter::new(stdout().lock())),
+        };
+        info!("Emitting {}x{} {} frames", width, height, format);
+
+        self.stream_frames(width, height, |frame, index| {
+            let written = format.write(frame, index, &mut out);
+            match written.and_then(|_| out.flush()) {
+                Ok(()) => Ok(true),
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    info!("Frame reader went away");
+                    Ok(false)
+                }
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    /// Sends the pattern's colors to a WLED controller every frame, until
+    /// `--duration` ends or the run is interrupted
+    fn run_led_output(&self, target: &WledTarget) -> Result<()> {
+        let map = match (&self.cli.led_map, self.cli.led_count) {
+            (Some(path), _) => LedMap::from_file(path)?,
+            (None, Some(count)) => LedMap::line(count as usize),
+            (None, None) => {
+                return Err(ChromaCatError::InputError(
+                    "--output needs --led-count or --led-map".to_string(),
+                ))
+            }
+        };
+        let output = WledOutput::connect(target, map.len())?;
+        info!(
+            "Sending {} LEDs to {} with {}",
+            map.len(),
+            target,
+            output.protocol()
+        );
+
+        self.stream_frames(map.width, map.height, |frame, _| {
+            // The controller may be restarting; later frames will reach it
+            if let Err(e) = output.send(&map.colors(frame)) {
+                debug!("WLED send failed: {}", e);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Samples the pattern on a `width` x `height` grid every frame and
+    /// passes each frame and its number to `deliver`, until `--duration`
+    /// ends, the run is interrupted, or `deliver` returns false. Headless
+    /// runs stop after the first frame.
+    fn stream_frames<F>(&self, width: usize, height: usize, mut deliver: F) -> Result<()>
+    where
+        F: FnMut(&PatternFrame, u64) -> Result<bool>,
+    {
+        let mut engine = self.create_frame_engine(width, height)?;
+        let animation = self.cli.create_animation_config();
+        animation.apply_smoothing(&mut engine);
+        let frame_duration = animation.frame_duration();
+        // Frames are computed at exact timestamps, so a slow reader or host
+        // gets the same frames, only later
+        let mut step = FixedStep::new(animation.fps);
+
+        let duration = Duration::from_secs(self.cli.animation.duration);
+        let started = Instant::now();
+        let mut last_frame = started;
+        for index in 0.. {
+            if self.cli.animation.duration > 0 && started.elapsed() >= duration {
+                break;
+            }
+            if guard::interrupted() {
+                break;
+            }
+            if !deliver(&PatternFrame::sample(&engine)?, index)? || Self::is_headless() {
+                break;
+            }
+
+            let next_frame = last_frame + frame_duration;
+            std::thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+            engine.advance(step.advance());
+            last_frame = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Prints the input, then animates the colors of its last lines in place,
+    /// like `lolcat -a`: the alternate screen is not used, so the text stays
+    /// in the scrollback. Runs until `--duration` ends, a key is pressed, or
+    /// the window is resized.
+    fn run_inline(&self) -> Result<()> {
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        let text = self.read_text(width, height.saturating_sub(1).max(1))?;
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+
+        // Lines that scroll off the screen can't be reached again, so only
+        // the last screenful is animated
+        let mut buffer = self.create_render_buffer((width, height))?;
+        buffer.prepare_text(text)?;
+        let total = buffer.line_count();
+        let rows = total.min(height.saturating_sub(1).max(1) as usize);
+        let start = total - rows;
+        buffer.resize((width, rows as u16))?;
+
+        let mut engine = self.create_frame_engine(width as usize, rows)?;
+        self.cli.create_animation_config().apply_smoothing(&mut engine);
+        let colors = !self.cli.no_color && atty::is(atty::Stream::Stdout);
+        let mut out = stdout();
 
+        buffer.update_colors_static(&engine)?;
+        buffer.render_region(&mut out, 0, start, colors, false)?;
+        buffer.update_colors(&engine, start)?;
+        buffer.render_region(&mut out, start, total, colors, false)?;
+        out.flush()?;
+
+        // Nothing to animate when the output isn't a terminal
+        if !colors || Self::is_headless() {
+            return Ok(());
+        }
+
+        enable_raw_mode()?;
+        execute!(out, Hide)?;
+        let result = self.animate_inline(&mut buffer, &mut engine, start);
+        execute!(out, Show)?;
+        disable_raw_mode()?;
         result
     }
 
-    /// Returns true if running in a test environment
-    fn is_test() -> bool {
-        std::env::var("RUST_TEST").is_ok()
+    /// Redraws the last lines of inline output until the animation ends
+    fn animate_inline(
+        &self,
+        buffer: &mut RenderBuffer,
+        engine: &mut PatternEngine,
+        start: usize,
+    ) -> Result<()> {
+        let frame_duration = self.cli.create_animation_config().frame_duration();
+        let rows = buffer.line_count() - start;
+        let started = Instant::now();
+        let mut last_frame = started;
+        let mut out = stdout();
+
+        loop {
+            if self.cli.animation.duration > 0 && started.elapsed() >= Duration::from_secs(self.cli.animation.duration) {
+                return Ok(());
+            }
+            if guard::interrupted() {
+                return Ok(());
+            }
+            if event::poll(frame_duration)? {
+                match event::read()? {
+                    Event::Key(_) => return Ok(()),
+                    // The terminal reflows the printed lines itself, so the
+                    // rows being animated are no longer where they were
+                    Event::Resize(..) => return Ok(()),
+                    _ => {}
+                }
+            }
+
+            let now = Instant::now();
+            engine.update(now.duration_since(last_frame).as_secs_f64());
+            last_frame = now;
+
+            let mut frame = Vec::new();
+            queue!(frame, MoveUp(rows as u16))?;
+            buffer.update_colors(engine, start)?;
+            buffer.render_region(&mut frame, start, start + rows, true, false)?;
+
+            // Raw mode doesn't return the cursor to the first column on a newline
+            let mut raw = Vec::with_capacity(frame.len() + rows);
+            for byte in frame {
+                if byte == b'\n' {
+                    raw.push(b'\r');
+                }
+                raw.push(byte);
+            }
+            out.write_all(&raw)?;
+            out.flush()?;
+        }
+    }
+
+    /// Prints a login banner from a template, colored once at
+    /// `--time-offset` without touching the terminal
+    fn run_motd(&self, template: Option<&Path>, width: usize) -> Result<()> {
+        let template = match template {
+            Some(path) => std::fs::read_to_string(path).map_err(|e| {
+                ChromaCatError::InputError(format!("Failed to read {}: {}", path.display(), e))
+            })?,
+            None => motd::DEFAULT_TEMPLATE.to_string(),
+        };
+        let text = motd::fill_template(&template, &SystemInfo::collect())
+            .map_err(ChromaCatError::InputError)?;
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+
+        let width = width.max(1);
+        let mut buffer = self.create_render_buffer((width as u16, 1))?;
+        buffer.prepare_text(text)?;
+        let engine = self.create_frame_engine(width, buffer.line_count().max(1))?;
+        buffer.update_colors_static(&engine)?;
+
+        let mut out = stdout();
+        buffer.render_region(&mut out, 0, buffer.line_count(), !self.cli.no_color, false)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Prints the input as a single gradient-colored status line
+    fn run_status_line(&self, width: u16) -> Result<()> {
+        let engine = self.create_frame_engine(width as usize, 1)?;
+        let mut line = StatusLine::new(engine, width as usize).with_format(self.cli.status_format);
+        line.set_colors_enabled(!self.cli.no_color);
+
+        let text = self.read_all_input()?;
+        println!("{}", line.render(&text)?);
+        Ok(())
+    }
+
+    /// Creates the post-processing settings, measuring minimum contrast
+    /// against the detected background
+    fn create_postprocess(&self) -> PostProcess {
+        let mut postprocess = self.cli.create_postprocess();
+        postprocess.color.background = self.background_color;
+        postprocess
+    }
+
+    /// Creates a pattern engine for rendering a single frame at `--time-offset`
+    fn create_frame_engine(&self, width: usize, height: usize) -> Result<PatternEngine> {
+        let theme = themes::get_theme(&self.cli.theme)?;
+        let mut engine = PatternEngine::from_theme(
+            &theme,
+            self.cli.create_pattern_config()?,
+            width,
+            height,
+        )?;
+        engine.set_postprocess(self.create_postprocess());
+        engine.set_light_map(self.cli.create_light_map()?);
+        engine.set_cycle(
+            self.cli.animation.cycle.map(Duration::from_secs_f64),
+            self.cli.animation.loop_mode,
+        );
+        // Match the time an animation would reach after this many seconds
+        engine.set_time(self.cli.time_offset * engine.config().common.speed);
+        Ok(engine)
+    }
+
+    /// Returns the text to render outside the renderer: demo art sized to
+    /// `width` x `height`, or all of the input
+    fn read_text(&self, width: u16, height: u16) -> Result<String> {
+        if !self.cli.demo {
+            return self.read_all_input();
+        }
+        let art = self
+            .cli
+            .demo_art()
+            .and_then(DemoArt::try_from_str)
+            .unwrap_or(DemoArt::All);
+        let settings = self
+            .cli
+            .create_art_settings()?
+            .with_size(width, height)
+            .with_headers(!self.cli.animation.animate);
+        Ok(DemoArtGenerator::new(settings).generate_at(art, self.cli.time_offset))
+    }
+
+    /// Creates a render buffer with the CLI's layout and drawing options, for
+    /// rendering without the full renderer
+    fn create_render_buffer(&self, size: (u16, u16)) -> Result<RenderBuffer> {
+        let config = self.cli.create_animation_config();
+        let mut buffer = RenderBuffer::with_wrap_mode(size, config.wrap_mode);
+        buffer.set_char_ramp(config.char_ramp);
+        buffer.set_resolution(config.resolution);
+        buffer.set_mask_mode(config.mask_mode);
+        buffer.set_effect(config.effect);
+        buffer.set_diff(self.cli.create_diff_palettes()?);
+        buffer.set_columns(self.cli.create_columns()?);
+        buffer.set_data(self.cli.create_data_column());
+        Ok(buffer)
+    }
+
+    /// Opens an input file, decoded as `--binary` says
+    fn open_file(&self, file: &Path) -> Result<InputReader> {
+        Ok(InputReader::from_file(file)?.with_binary_policy(self.cli.text.binary))
+    }
+
+    /// Opens stdin, decoded as `--binary` says
+    fn open_stdin(&self) -> Result<InputReader> {
+        Ok(InputReader::from_stdin()?.with_binary_policy(self.cli.text.binary))
+    }
+
+    /// Reads every input file in order, or stdin when no files are given
+    fn read_all_input(&self) -> Result<String> {
+        let mut text = String::new();
+        if self.cli.files.is_empty() {
+            self.open_stdin()?.read_to_string(&mut text)?;
+        } else {
+            for file in &self.cli.files {
+                self.open_file(file)?.read_to_string(&mut text)?;
+            }
+        }
+        Ok(text)
+    }
+
+    /// Returns true if no terminal is driven: in tests and with `--headless`
+    fn is_headless() -> bool {
+        std::env::var("RUST_TEST").is_ok() || termcaps::is_headless()
+    }
+
+    /// Reads piped stdin ahead of time when it is going to be animated or
+    /// paged. Keys then come from the controlling terminal, so there has to
+    /// be one.
+    fn read_piped_input(&mut self) -> Result<()> {
+        let full_screen = self.cli.animation.animate || self.cli.text.pager;
+        if !full_screen
+            || self.cli.demo
+            || !self.cli.files.is_empty()
+            || atty::is(atty::Stream::Stdin)
+        {
+            return Ok(());
+        }
+        if !Self::is_headless() && !terminal::keyboard_available() {
+            return Err(ChromaCatError::TerminalError(
+                "Animating piped input needs a terminal to read keys from, but there is none"
+                    .to_string(),
+            ));
+        }
+        debug!("Reading piped stdin before taking over the terminal");
+        let mut text = String::new();
+        self.open_stdin()?.read_to_string(&mut text)?;
+        self.piped_input = Some(text);
+        Ok(())
     }
 
     /// Sets up the terminal for rendering
     fn setup_terminal(&mut self) -> Result<()> {
         // Get terminal size
-        if Self::is_test() {
-            // Use fixed size for tests
+        if Self::is_headless() {
+            // Use fixed size without a terminal
             self.term_size = (80, 24);
         } else {
-            self.term_size = crossterm::terminal::size().map_err(|e| {
-                ChromaCatError::Other(format!("Failed to get terminal size: {}", e))
-            })?;
+            self.term_size = match crossterm::terminal::size() {
+                Ok(size) => size,
+                // Redirected output, as on CI runners, may have no console
+                Err(_) if !atty::is(atty::Stream::Stdout) => (80, 24),
+                Err(e) => {
+                    return Err(ChromaCatError::TerminalError(format!(
+                        "Failed to get terminal size: {}",
+                        e
+                    )))
+                }
+            };
         }
 
         // Skip terminal setup in test environment
-        if Self::is_test() {
+        if Self::is_headless() {
             return Ok(());
         }
 
-        if self.cli.animate {
+        if self.cli.animation.animate {
             // Enter raw mode for animation
-            enable_raw_mode()
-                .map_err(|e| ChromaCatError::Other(format!("Failed to enable raw mode: {}", e)))?;
+            enable_raw_mode().map_err(|e| {
+                ChromaCatError::TerminalError(format!("Failed to enable raw mode: {}", e))
+            })?;
             self.raw_mode = true;
 
             // Enter alternate screen
-            execute!(stdout(), EnterAlternateScreen, Hide)?;
+            guard::enter_alternate_screen(&mut stdout())?;
+            execute!(stdout(), Hide)?;
             self.alternate_screen = true;
+
+            if self.cli.animation.mouse {
+                guard::enable_mouse_capture(&mut stdout())?;
+            }
         }
 
         Ok(())
@@ -202,15 +1232,18 @@ impl ChromaCat {
     /// Restores terminal state
     fn cleanup_terminal(&mut self) -> Result<()> {
         let mut stdout = stdout();
+        guard::disable_mouse_capture(&mut stdout)?;
 
         if self.alternate_screen {
-            execute!(stdout, Show, LeaveAlternateScreen)?;
+            execute!(stdout, Show)?;
+            guard::leave_alternate_screen(&mut stdout)?;
             self.alternate_screen = false;
         }
 
         if self.raw_mode {
-            disable_raw_mode()
-                .map_err(|e| ChromaCatError::Other(format!("Failed to disable raw mode: {}", e)))?;
+            disable_raw_mode().map_err(|e| {
+                ChromaCatError::TerminalError(format!("Failed to disable raw mode: {}", e))
+            })?;
             self.raw_mode = false;
         }
 
@@ -218,19 +1251,41 @@ impl ChromaCat {
         Ok(())
     }
 
+    /// Fails with an input error if `--fail-on-empty` is given and no input
+    /// was read
+    fn check_not_empty(&self) -> Result<()> {
+        if self.cli.text.fail_on_empty && input::bytes_read() == 0 {
+            return Err(ChromaCatError::InputError(
+                "the input was empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Processes input from files or stdin
     fn process_input(&self, renderer: &mut Renderer) -> Result<()> {
         // Handle demo mode
         if self.cli.demo {
             info!("Running in demo mode");
+            let art_settings = self.cli.create_art_settings()?;
             let mut reader = InputReader::from_demo(
-                self.cli.animate,
-                self.cli.art.as_deref(),
-                None
+                self.cli.animation.animate,
+                self.cli.demo_art(),
+                None,
+                &art_settings,
             )?;
 
-            if self.cli.animate {
-                // For animated demo, we'll keep generating new content
+            if self.cli.animation.animate {
+                // The renderer regenerates the art as it animates and on resize
+                renderer.set_art_settings(art_settings);
+                renderer.set_art_transition(self.cli.art.transition);
+                renderer.set_countdown_theme(self.cli.art.countdown_theme.as_deref())?;
+                renderer.set_demo_art(
+                    self.cli
+                        .demo_art()
+                        .and_then(DemoArt::try_from_str)
+                        .unwrap_or(DemoArt::All),
+                );
                 let mut buffer = String::new();
                 reader.read_to_string(&mut buffer)?;
                 self.run_animation(renderer, &buffer)?;
@@ -238,7 +1293,11 @@ impl ChromaCat {
                 // For static demo, read all generated content
                 let mut buffer = String::new();
                 reader.read_to_string(&mut buffer)?;
-                renderer.render_static(&buffer)?;
+                if self.use_pager(&buffer)? {
+                    self.run_animation(renderer, &buffer)?;
+                } else {
+                    renderer.render_static(&buffer)?;
+                }
             }
             return Ok(());
         }
@@ -250,17 +1309,63 @@ impl ChromaCat {
             return Ok(());
         }
 
+        // Static output is only paged once all of it is known, so files are
+        // read up front when they may be paged, and streamed otherwise
+        let mut texts = None;
+        if !self.cli.animation.animate && self.may_page()? {
+            let mut read = Vec::with_capacity(self.cli.files.len());
+            for file in &self.cli.files {
+                let mut buffer = String::new();
+                self.open_file(file)?.read_to_string(&mut buffer)?;
+                read.push((file.display().to_string(), buffer));
+            }
+
+            // The pager shows all the files as one text
+            let text = self.join_files(&read);
+            if self.use_pager(&text)? {
+                if let Some(theme) = self.cli.theme_for_file(0) {
+                    renderer.set_theme(theme)?;
+                }
+                return self.run_animation(renderer, &text);
+            }
+            texts = Some(read);
+        }
+
         // Process each input file
-        for file in &self.cli.files {
+        for (index, file) in self.cli.files.iter().enumerate() {
+            if guard::interrupted() {
+                break;
+            }
             info!("Processing file: {}", file.display());
-            let mut reader = InputReader::from_file(file)?;
-            let mut buffer = String::new();
-            reader.read_to_string(&mut buffer)?;
 
-            if self.cli.animate {
+            if let Some(theme) = self.cli.theme_for_file(index) {
+                info!("Using theme {} for {}", theme, file.display());
+                renderer.set_theme(theme)?;
+            }
+
+            let name = file.display().to_string();
+            if self.cli.animation.animate {
+                let mut buffer = String::new();
+                self.open_file(file)?.read_to_string(&mut buffer)?;
+                if self.cli.text.file_headers {
+                    let header = Renderer::file_header(&name, self.term_size.0 as usize);
+                    buffer = format!("{}\n{}", header, buffer);
+                }
                 self.run_animation(renderer, &buffer)?;
-            } else {
-                renderer.render_static(&buffer)?;
+                continue;
+            }
+
+            if self.cli.text.file_headers {
+                renderer.render_file_header(&name)?;
+            }
+            match texts.as_mut() {
+                Some(texts) => renderer.render_static(&std::mem::take(&mut texts[index].1))?,
+                None => {
+                    // Large files are read a chunk at a time
+                    let len = std::fs::metadata(file)?.len();
+                    let mut reader = self.open_file(file)?;
+                    renderer.render_static_stream(reader.reader(), len)?;
+                }
             }
         }
 
@@ -273,22 +1378,22 @@ impl ChromaCat {
         if atty::is(atty::Stream::Stdin) {
             debug!("Processing stdin in terminal mode");
             // Terminal input - use normal processing
-            let mut reader = InputReader::from_stdin()?;
+            let mut reader = self.open_stdin()?;
             let mut buffer = String::new();
             reader.read_to_string(&mut buffer)?;
 
-            if self.cli.animate {
+            if self.cli.animation.animate || self.use_pager(&buffer)? {
                 self.run_animation(renderer, &buffer)?;
             } else {
                 renderer.render_static(&buffer)?;
             }
+        } else if let Some(text) = &self.piped_input {
+            // Piped input was read whole before raw mode; keys come from
+            // the controlling terminal
+            debug!("Animating piped stdin");
+            self.run_animation(renderer, text)?;
         } else {
             debug!("Processing stdin in streaming mode");
-            if self.cli.animate {
-                return Err(ChromaCatError::Other(
-                    "Animation mode is not supported for streaming input. Please use static mode for pipes and real-time logs.".to_string()
-                ));
-            }
             // Streaming input - use streaming processor
             self.process_streaming()?;
         }
@@ -296,6 +1401,57 @@ impl ChromaCat {
         Ok(())
     }
 
+    /// Joins the files read as the pager shows them: one after another,
+    /// each under a header when `--file-headers` is given
+    fn join_files(&self, texts: &[(String, String)]) -> String {
+        let mut text = String::new();
+        for (name, content) in texts {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            if self.cli.text.file_headers {
+                text.push_str(&Renderer::file_header(name, self.term_size.0 as usize));
+                text.push('\n');
+            }
+            text.push_str(content);
+        }
+        text
+    }
+
+    /// Returns whether the input files may be shown in the pager, and so
+    /// have to be read whole: always with `--pager`, and otherwise when the
+    /// output is a terminal and the files are small enough to keep in memory
+    fn may_page(&self) -> Result<bool> {
+        if self.cli.text.pager {
+            return Ok(true);
+        }
+        if self.cli.text.no_pager || Self::is_headless() || !atty::is(atty::Stream::Stdout) {
+            return Ok(false);
+        }
+        let mut total = 0;
+        for file in &self.cli.files {
+            total += std::fs::metadata(file)?.len();
+        }
+        Ok(total <= MAX_PAGED_BYTES)
+    }
+
+    /// Returns whether static text is shown in the pager: always with
+    /// `--pager`, and otherwise when it takes more rows than the terminal
+    /// has and is headed for one
+    fn use_pager(&self, text: &str) -> Result<bool> {
+        if self.cli.text.pager {
+            return Ok(true);
+        }
+        if self.cli.text.no_pager || Self::is_headless() || !atty::is(atty::Stream::Stdout) {
+            return Ok(false);
+        }
+        let mut buffer = self.create_render_buffer(self.term_size)?;
+        buffer.set_gutter(self.cli.create_animation_config().gutter);
+        buffer.prepare_text(text)?;
+        let rows = buffer.line_count() + buffer.gutter().header_rows();
+        Ok(rows > self.term_size.1 as usize)
+    }
+
     /// Processes streaming input (e.g., from pipes)
     fn process_streaming(&self) -> Result<()> {
         info!("Starting streaming input processing");
@@ -306,6 +1462,19 @@ impl ChromaCat {
 
         // Set color state
         processor.set_colors_enabled(!self.cli.no_color);
+        processor.set_color_support(
+            self.cli
+                .profile
+                .unwrap_or_default()
+                .colors(TermCaps::current().color),
+        );
+        processor.set_postprocess(self.create_postprocess());
+        processor.set_light_map(self.cli.create_light_map()?);
+        processor.set_diff(self.cli.create_diff_palettes()?);
+        processor.set_columns(self.cli.create_columns()?);
+        processor.set_data(self.cli.create_data_column());
+        processor.set_gutter(self.cli.create_animation_config().gutter);
+        processor.set_binary_policy(self.cli.text.binary);
 
         // Set custom buffer size if specified
         if let Some(buffer_size) = self.cli.buffer_size {
@@ -327,52 +1496,92 @@ impl ChromaCat {
 
     /// Runs the animation loop
     fn run_animation(&self, renderer: &mut Renderer, content: &str) -> Result<()> {
-        let frame_duration = renderer.frame_duration();
         let mut last_frame = Instant::now();
-        let mut paused = false;
         let start_time = Instant::now();
+        let mut last_sync: Option<(Instant, Option<usize>)> = None;
+        let mut fixed_step = self.cli.fixed_step();
 
         // Skip terminal setup and animation loop in test environment
-        if Self::is_test() {
+        if Self::is_headless() {
             renderer.render_frame(content, 0.016)?;
             return Ok(());
         }
 
         // Set up terminal
         enable_raw_mode()?;
+        renderer.probe_terminal();
+
+        // The pager starts on still text; Space sets it moving
+        if !self.cli.animation.animate {
+            renderer.render_frame(content, 0.0)?;
+            renderer.set_paused(true);
+        }
 
         // Main animation loop
+        let mut failure = None;
         'main: loop {
             // Add duration check
-            if self.cli.duration > 0
-                && start_time.elapsed() >= Duration::from_secs(self.cli.duration)
+            if self.cli.animation.duration > 0
+                && start_time.elapsed() >= Duration::from_secs(self.cli.animation.duration)
             {
                 break 'main;
             }
+            if guard::interrupted() {
+                break 'main;
+            }
+
+            if !self.poll_control(renderer) {
+                break 'main;
+            }
+            self.poll_mqtt(renderer);
+            self.sync_frame(renderer, &mut last_sync, &mut fixed_step);
 
             // Handle input with minimal polling delay
             if event::poll(Duration::from_millis(1))? {
-                match event::read()? {
+                let event = event::read()?;
+                // Any key wakes the screen saver
+                if self.cli.screensaver && screensaver::is_wake_event(&event) {
+                    break 'main;
+                }
+                match event {
                     Event::Key(key) => {
                         use crossterm::event::KeyCode;
                         match key.code {
-                            KeyCode::Esc | KeyCode::Char('q') => break 'main,
-                            KeyCode::Char(' ') => {
-                                paused = !paused;
+                            // Esc closes the favorites panel, the search
+                            // prompt, the quick switcher and the key help
+                            // before quitting
+                            KeyCode::Esc
+                                if !renderer.favorites_open()
+                                    && !renderer.search_open()
+                                    && !renderer.picker_open()
+                                    && !renderer.help_open() =>
+                            {
+                                break 'main
+                            }
+                            KeyCode::Char('q')
+                                if !renderer.search_open() && !renderer.picker_open() =>
+                            {
+                                break 'main
                             }
                             _ => match renderer.handle_key_event(key) {
                                 Ok(true) => continue 'main,
                                 Ok(false) => break 'main,
                                 Err(e) => {
-                                    eprintln!("Key handling error: {}", e);
+                                    renderer.recover(LoopStage::Key, &e);
                                     continue 'main;
                                 }
                             },
                         }
                     }
+                    Event::Mouse(mouse) => {
+                        if let Err(e) = renderer.handle_mouse_event(mouse) {
+                            renderer.recover(LoopStage::Key, &e);
+                        }
+                        continue 'main;
+                    }
                     Event::Resize(width, height) => {
                         if let Err(e) = renderer.handle_resize(width, height) {
-                            eprintln!("Resize error: {}", e);
+                            renderer.recover(LoopStage::Resize, &e);
                         }
                         continue 'main;
                     }
@@ -382,12 +1591,24 @@ impl ChromaCat {
 
             let now = Instant::now();
 
-            // Update and render frame
-            if !paused && now.duration_since(last_frame) >= frame_duration {
-                let delta_seconds = now.duration_since(last_frame).as_secs_f64();
+            // Update and render frame; a paused animation is only redrawn
+            // when stepped, and doesn't jump ahead when resumed
+            if renderer.is_paused() {
+                last_frame = now;
+                std::thread::sleep(Duration::from_millis(1));
+            } else if now.duration_since(last_frame) >= renderer.frame_duration() {
+                let delta_seconds = match fixed_step.as_mut() {
+                    Some(step) => step.advance().as_secs_f64(),
+                    None => now.duration_since(last_frame).as_secs_f64(),
+                };
 
                 if let Err(e) = renderer.render_frame(content, delta_seconds) {
-                    eprintln!("Render error: {}", e);
+                    // Frames that keep failing mean there is nothing left
+                    // to draw on
+                    if renderer.recover(LoopStage::Frame, &e) == Recovery::Abort {
+                        failure = Some(e);
+                        break 'main;
+                    }
                     continue 'main;
                 }
 
@@ -397,10 +1618,17 @@ impl ChromaCat {
             }
         }
 
-        // Clean up terminal
+        // Clean up terminal; the key that woke the screen saver, and
+        // anything typed after it, must not reach the shell
+        if self.cli.screensaver {
+            screensaver::discard_input()?;
+        }
         disable_raw_mode()?;
 
-        Ok(())
+        match failure {
+            Some(e) => Err(e.into()),
+            None => Ok(()),
+        }
     }
 }
 
@@ -0,0 +1,210 @@
+//! Terminal background detection
+//!
+//! Themes are designed for dark terminals, and their lightest colors all but
+//! vanish on a white background. With `--adapt-bg` the background is
+//! detected at startup so themes can be adapted to it: the terminal is asked
+//! for its background color with an OSC 11 query, and terminals that don't
+//! answer are classified from the `COLORFGBG` variable some of them export.
+
+use std::fmt;
+use std::str::FromStr;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer the background query
+#[cfg(unix)]
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Backgrounds brighter than this relative luminance count as light
+const LIGHT_THRESHOLD: f32 = 0.5;
+
+/// Brightness class of the terminal background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Background {
+    /// Dark background; themes are designed for this
+    #[default]
+    Dark,
+    /// Light background
+    Light,
+}
+
+impl Background {
+    /// Returns a list of all backgrounds
+    pub fn all() -> &'static [Background] {
+        &[Background::Dark, Background::Light]
+    }
+
+    /// Get string representation of the background
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Background::Dark => "dark",
+            Background::Light => "light",
+        }
+    }
+
+    /// Classifies a background color by its luminance
+    pub fn from_rgb(r: f32, g: f32, b: f32) -> Self {
+        if luminance(r, g, b) > LIGHT_THRESHOLD {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+
+    /// Detects the terminal background, asking the terminal first and
+    /// falling back to `COLORFGBG`
+    pub fn detect() -> Option<Self> {
+        detect_color().map(|(r, g, b)| Self::from_rgb(r, g, b))
+    }
+
+    /// Returns a typical color for the background: black or white
+    pub fn color(&self) -> (f32, f32, f32) {
+        match self {
+            Background::Dark => (0.0, 0.0, 0.0),
+            Background::Light => (1.0, 1.0, 1.0),
+        }
+    }
+
+    /// Classifies a `COLORFGBG` value such as `15;0`, whose last field is
+    /// the ANSI color index of the background
+    pub fn from_colorfgbg(value: &str) -> Option<Self> {
+        let index: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+        match index {
+            // White and the bright colors other than bright black
+            7 | 9..=15 => Some(Background::Light),
+            0..=6 | 8 => Some(Background::Dark),
+            _ => None,
+        }
+    }
+}
+
+/// Detects the terminal background color, asking the terminal first unless
+/// headless and falling back to black or white as `COLORFGBG` suggests
+pub fn detect_color() -> Option<(f32, f32, f32)> {
+    let queried = if crate::termcaps::is_headless() {
+        None
+    } else {
+        query_background_color()
+    };
+    queried.or_else(|| {
+        Background::from_colorfgbg(&std::env::var("COLORFGBG").ok()?).map(|b| b.color())
+    })
+}
+
+/// Relative luminance of a color, with Rec. 709 weights
+#[inline]
+pub fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Parses a terminal's answer to the OSC 11 query, such as
+/// `ESC ] 11 ; rgb:ffff/ffff/dddd BEL`, into channels from 0.0 to 1.0
+pub fn parse_osc11_reply(reply: &str) -> Option<(f32, f32, f32)> {
+    let start = reply.find("]11;")? + 4;
+    let spec = reply[start..].trim_end_matches(['\x07', '\\', '\x1b']);
+    let spec = spec.strip_prefix("rgb:").or_else(|| spec.strip_prefix("rgba:"))?;
+
+    // Each channel has one to four hex digits, scaled to its own range
+    let mut channels = spec.split('/').map(|c| {
+        let digits = c.get(..c.len().min(4))?;
+        let value = u32::from_str_radix(digits, 16).ok()?;
+        Some(value as f32 / ((1u32 << (4 * digits.len())) - 1) as f32)
+    });
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// Asks the controlling terminal for its background color
+#[cfg(unix)]
+fn query_background_color() -> Option<(f32, f32, f32)> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    // The answer arrives as input, which must not be echoed or line-buffered
+    let was_raw = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+    let reply = tty
+        .write_all(b"\x1b]11;?\x1b\\")
+        .and_then(|_| tty.flush())
+        .ok()
+        .and_then(|_| read_reply(&tty));
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+    parse_osc11_reply(&reply?)
+}
+
+/// Background queries need a terminal device
+#[cfg(not(unix))]
+fn query_background_color() -> Option<(f32, f32, f32)> {
+    None
+}
+
+/// Reads an OSC reply up to its terminator, giving up after the timeout
+#[cfg(unix)]
+fn read_reply(mut tty: &std::fs::File) -> Option<String> {
+    use std::io::Read;
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut reply = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fd` is a single valid pollfd that outlives the call
+        let ready = unsafe { libc::poll(&mut fd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+        if tty.read(&mut byte).ok()? == 0 {
+            return None;
+        }
+        reply.push(byte[0]);
+
+        // Replies end with BEL or ST (ESC \)
+        if byte[0] == 0x07 || reply.ends_with(b"\x1b\\") {
+            return String::from_utf8(reply).ok();
+        }
+        if reply.len() > 64 {
+            return None;
+        }
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dark" => Ok(Background::Dark),
+            "light" => Ok(Background::Light),
+            _ => Err(format!(
+                "Invalid background '{}'. Valid backgrounds: dark, light",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Catalog of themes and patterns
+//!
+//! This backs `--list`. The catalog can be narrowed to entries whose name or
+//! description contains a search term, or to the themes of one category or
+//! with one tag, and printed as JSON so scripts can discover ChromaCat's
+//! themes with their metadata, and its patterns and their parameters as
+//! described by [`crate::introspect`].
+
+use crate::error::{ChromaCatError, Result};
+use crate::introspect::{introspect, PatternInfo};
+use crate::themes;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Output format of `--list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    /// Colored tables for people
+    #[default]
+    Text,
+    /// JSON for scripts
+    Json,
+}
+
+impl ListFormat {
+    /// Returns a list of all list formats
+    pub fn all() -> &'static [ListFormat] {
+        &[ListFormat::Text, ListFormat::Json]
+    }
+
+    /// Get string representation of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListFormat::Text => "text",
+            ListFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for ListFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ListFormat::Text),
+            "json" => Ok(ListFormat::Json),
+            _ => Err(format!(
+                "Invalid list format '{}'. Valid formats: text, json",
+                s
+            )),
+        }
+    }
+}
+
+/// Narrows the catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilter {
+    /// Case-insensitive text that names or descriptions must contain
+    pub search: Option<String>,
+    /// Theme category to list, ignoring case; patterns have no category and
+    /// are left out
+    pub category: Option<String>,
+    /// Tag the themes listed must have, ignoring case; patterns have no tags
+    /// and are left out
+    pub tag: Option<String>,
+}
+
+impl ListFilter {
+    /// Returns true if the filter lets everything through
+    pub fn is_empty(&self) -> bool {
+        self.search.is_none() && self.category.is_none() && self.tag.is_none()
+    }
+
+    /// Returns true if an entry with this name and description matches the
+    /// search text
+    pub fn matches(&self, name: &str, description: &str) -> bool {
+        self.search.as_ref().is_none_or(|search| {
+            let search = search.to_lowercase();
+            name.to_lowercase().contains(&search) || description.to_lowercase().contains(&search)
+        })
+    }
+}
+
+/// A theme
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeEntry {
+    /// Name used with `--theme`
+    pub name: String,
+    /// Category the theme is listed under
+    pub category: String,
+    /// What the theme looks like
+    pub description: String,
+    /// Who made the theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Words describing the theme
+    pub tags: Vec<String>,
+    /// License the theme may be shared under
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+/// Themes and patterns matching a filter
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Catalog {
+    /// Patterns, sorted by id
+    pub patterns: Vec<PatternInfo>,
+    /// Themes, sorted by category and then by name
+    pub themes: Vec<ThemeEntry>,
+}
+
+impl Catalog {
+    /// Collects the themes and patterns matching a filter, failing if it
+    /// names a category that doesn't exist
+    pub fn collect(filter: &ListFilter) -> Result<Self> {
+        let categories = themes::list_categories();
+        let category = match &filter.category {
+            Some(wanted) => Some(
+                categories
+                    .iter()
+                    .find(|c| c.eq_ignore_ascii_case(wanted))
+                    .cloned()
+                    .ok_or_else(|| {
+                        ChromaCatError::InputError(format!(
+                            "Unknown theme category '{}'. Valid categories: {}",
+                            wanted,
+                            categories.join(", ")
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+
+        let patterns = match category.is_some() || filter.tag.is_some() {
+            true => Vec::new(),
+            false => introspect()
+                .patterns
+                .into_iter()
+                .filter(|p| filter.matches(&p.id, &p.description) || filter.matches(&p.name, ""))
+                .collect(),
+        };
+
+        let mut theme_entries = Vec::new();
+        for listed in &categories {
+            if category.as_ref().is_some_and(|c| c != listed) {
+                continue;
+            }
+            for name in themes::list_category(listed).unwrap_or_default() {
+                let Ok(theme) = themes::get_theme(&name) else {
+                    continue;
+                };
+                let tagged = filter.tag.as_ref().is_none_or(|tag| theme.has_tag(tag));
+                if tagged && filter.matches(&name, &theme.desc) {
+                    theme_entries.push(ThemeEntry {
+                        name,
+                        category: listed.clone(),
+                        description: theme.desc,
+                        author: theme.author,
+                        tags: theme.tags,
+                        license: theme.license,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            patterns,
+            themes: theme_entries,
+        })
+    }
+
+    /// Returns true if nothing matched
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty() && self.themes.is_empty()
+    }
+
+    /// Returns the catalog as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ChromaCatError::Other(format!("Failed to serialize catalog: {}", e)))
+    }
+}
@@ -4,14 +4,41 @@
 //! It handles all user input configuration and converts it into the internal configuration
 //! types used by the pattern engine and renderer.
 
-use crate::demo::DemoArt;
+use crate::demo::{self, ArtSettings, ArtTransition, DemoArt};
+use crate::columns::{ColumnDelimiter, Columns};
+use crate::data::{parse_data_range, DataColumn};
+use crate::diff::{DiffPalettes, DiffRules};
 use crate::error::{ChromaCatError, Result};
-use crate::pattern::{CommonParams, PatternConfig, REGISTRY, ParamType};
-use crate::renderer::AnimationConfig;
+use crate::frames::{parse_frame_size, FrameFormat};
+use crate::input::BinaryPolicy;
+use crate::pattern::color::{CONTRAST_RANGE, FACTOR_RANGE, GAMMA_RANGE};
+use crate::pattern::postprocess::DEFAULT_CHAR_RAMP;
+use crate::pattern::{
+    parse_pattern_pan, BlendMode, CharRamp, ColorAdjust, CommonParams, DitherMode, Grain, LightMap,
+    LightSource, LoopMode, FixedStep, PatternConfig, PatternLayer, PostProcess, REGISTRY, ParamType, MAX_CYCLE_SECONDS,
+    GRAIN_RANGE, PATTERN_ZOOM_RANGE,
+};
+use crate::motd::DEFAULT_MOTD_WIDTH;
+use crate::playlist::{
+    params_to_yaml, split_args, AdaptiveSource, LayerEntry, Playlist, PlaylistEntry,
+};
+use crate::progress::ProgressOptions;
+use crate::renderer::{
+    parse_cpu_budget, parse_pan, AnimationConfig, Camera, Gutter, LegendPosition, MaskMode,
+    RenderProfile, Resolution, ScreenshotTarget, TextEffect, WrapMode, MAX_CANVAS_SCALE,
+};
+use crate::statusline::StatusFormat;
+use crate::termcaps::ColorSupport;
+use crate::theme_packs;
 use crate::themes;
+use crate::wled::WledTarget;
+use crate::catalog::{Catalog, ListFilter, ListFormat};
 use crate::cli_format::{CliFormat, PadToWidth};
 
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -23,13 +50,16 @@ use std::time::Duration;
     about = format!("😺 {}Chroma{}Cat{} - Create magical color gradients for your text ✨", 
         CliFormat::TITLE_1, CliFormat::TITLE_2, CliFormat::RESET),
     long_about = None,
-    help_template = "{about}\n\nUsage: {usage}\n\n{options}",
+    help_template = "{about}\n\nUsage: {usage}\n\nCommands:\n{subcommands}\n\n{options}",
     styles = clap::builder::Styles::styled()
         .header(anstyle::AnsiColor::BrightMagenta.on_default())
         .usage(anstyle::AnsiColor::BrightCyan.on_default())
         .literal(anstyle::AnsiColor::BrightYellow.on_default())
 )]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(
         name = "FILES",
         help_heading = CliFormat::HEADING_INPUT,
@@ -38,20 +68,34 @@ pub struct Cli {
     )]
     pub files: Vec<PathBuf>,
 
+    #[command(flatten)]
+    pub text: TextArgs,
+
     #[arg(
         short = 'p',
         long,
         default_value = "diagonal",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         value_name = "TYPE",
-        help = CliFormat::highlight_description("Select pattern type for the color gradient")
+        help = CliFormat::highlight_description("Select pattern type for the color gradient; list several (plasma,ripple) to blend them with --blend")
     )]
     pub pattern: String,
 
+    #[arg(
+        long = "blend",
+        value_name = "MODE",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("How each extra pattern in -p combines with the ones before it: multiply (default), add, screen, min, max, or mask")
+    )]
+    pub blend: Option<BlendMode>,
+
     #[arg(
         short = 't',
         long,
         default_value = "rainbow",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         value_name = "NAME",
         help = CliFormat::highlight_description("Select color theme (use --list to see available)")
@@ -62,6 +106,7 @@ pub struct Cli {
         short = 'f',
         long,
         default_value = "1.0",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         value_name = "NUM",
         help = CliFormat::highlight_description("Base frequency (0.1-10.0)")
@@ -72,58 +117,40 @@ pub struct Cli {
         short = 'm',
         long,
         default_value = "1.0",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         value_name = "NUM",
         help = CliFormat::highlight_description("Pattern amplitude (0.1-2.0)")
     )]
     pub amplitude: f64,
 
-    #[arg(
-        short = 'a',
-        long,
-        help_heading = CliFormat::HEADING_ANIMATION,
-        help = CliFormat::highlight_description("Enable animation mode")
-    )]
-    pub animate: bool,
+    #[command(flatten)]
+    pub animation: AnimationArgs,
 
     #[arg(
         long,
-        default_value = "30",
+        value_name = "NAME",
+        global = true,
         help_heading = CliFormat::HEADING_ANIMATION,
-        value_name = "NUM",
-        help = CliFormat::highlight_description("Frames per second (1-144)")
+        help = CliFormat::highlight_description("Rendering profile: full, or minimal for slow terminals (15 fps, 256 colors, no art transitions, simpler patterns); picked by itself when frames keep running slow")
     )]
-    pub fps: u32,
+    pub profile: Option<RenderProfile>,
 
     #[arg(
-        long,
+        long = "time-offset",
+        value_name = "SECONDS",
         default_value = "0",
+        allow_negative_numbers = true,
+        global = true,
         help_heading = CliFormat::HEADING_ANIMATION,
-        value_name = "SECS",
-        help = CliFormat::highlight_description("Duration in seconds (0 = infinite)")
-    )]
-    pub duration: u64,
-
-    #[arg(
-        short = 's',
-        long,
-        default_value = "1.0",
-        help_heading = CliFormat::HEADING_ANIMATION,
-        value_name = "NUM",
-        help = CliFormat::highlight_description("Animation speed (0.0-1.0)")
-    )]
-    pub speed: f64,
-
-    #[arg(
-        long,
-        help_heading = CliFormat::HEADING_ANIMATION,
-        help = CliFormat::highlight_description("Enable smooth transitions")
+        help = CliFormat::highlight_description("Animation time of the frame rendered by --status-line, --screenshot, or motd, e.g. $(date +%s)")
     )]
-    pub smooth: bool,
+    pub time_offset: f64,
 
     #[arg(
         short = 'n',
         long = "no-color",
+        global = true,
         help_heading = CliFormat::HEADING_GENERAL,
         help = CliFormat::highlight_description("Disable colored output")
     )]
@@ -137,22 +164,115 @@ pub struct Cli {
     )]
     pub list_available: bool,
 
+    #[arg(
+        long = "filter",
+        value_name = "TEXT",
+        requires = "list_available",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("With --list, show only themes and patterns whose name or description contains TEXT")
+    )]
+    pub list_filter: Option<String>,
+
+    #[arg(
+        long = "category",
+        value_name = "NAME",
+        requires = "list_available",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("With --list, show only the themes in one category, e.g. space")
+    )]
+    pub list_category: Option<String>,
+
+    #[arg(
+        long = "format",
+        value_name = "FORMAT",
+        default_value = "text",
+        requires = "list_available",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("With --list, print text or json (themes, patterns, and parameters with their ranges)")
+    )]
+    pub list_format: ListFormat,
+
     #[arg(
         long = "theme-file",
         value_name = "FILE",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         help = CliFormat::highlight_description("Load custom theme from YAML file")
     )]
     pub theme_file: Option<PathBuf>,
 
+    #[arg(
+        long = "adapt-bg",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Detect the terminal background and adapt themes to it, darkening colors that would vanish on light backgrounds")
+    )]
+    pub adapt_bg: bool,
+
+    #[arg(
+        long = "light-theme",
+        value_name = "THEME",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Take lightness from a second theme while the main theme sets hue and saturation, for two-dimensional color fields")
+    )]
+    pub light_theme: Option<String>,
+
+    #[arg(
+        long = "light-source",
+        value_name = "SOURCE",
+        requires = "light_theme",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("What picks the lightness from --light-theme: a pattern (default: perlin) or mask to follow the text mask")
+    )]
+    pub light_source: Option<LightSource>,
+
     #[arg(
         long = "param",
         value_name = "KEY=VALUE",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         help = CliFormat::highlight_description("Pattern-specific parameter (can be used multiple times)")
     )]
     pub params: Vec<String>,
 
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with = "randomize",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Start from one of the pattern's named parameter sets, e.g. -p plasma --preset lava (see --pattern-help); --param overrides single values")
+    )]
+    pub preset: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Start with a random pattern, theme, and parameters, replacing -p, -t and --param (R draws again while animating)")
+    )]
+    pub randomize: bool,
+
+    #[arg(
+        long,
+        value_name = "TAG",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Choose only themes tagged TAG, e.g. warm or pastel: with --list, --random, and --randomize")
+    )]
+    pub theme_tag: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NUM",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Seed for --randomize and the R and r keys, to repeat the same draws")
+    )]
+    pub seed: Option<u64>,
+
     #[arg(
         long = "pattern-help",
         help_heading = CliFormat::HEADING_GENERAL,
@@ -162,6 +282,7 @@ pub struct Cli {
 
     #[arg(
         long = "no-aspect-correction",
+        global = true,
         help_heading = CliFormat::HEADING_GENERAL,
         help = CliFormat::highlight_description("Disable terminal character aspect ratio correction")
     )]
@@ -171,102 +292,1663 @@ pub struct Cli {
         long = "aspect-ratio",
         value_name = "RATIO",
         default_value = "0.5",
+        global = true,
         help_heading = CliFormat::HEADING_GENERAL,
         help = CliFormat::highlight_description("Set terminal character aspect ratio (width/height, default: 0.5)")
     )]
     pub aspect_ratio: f64,
 
+    #[arg(
+        long = "pattern-zoom",
+        value_name = "FACTOR",
+        default_value = "1.0",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Magnify any pattern around its center (0.1-10; below 1 zooms out)")
+    )]
+    pub pattern_zoom: f64,
+
+    #[arg(
+        long = "pattern-pan",
+        value_name = "X,Y",
+        value_parser = parse_pattern_pan,
+        allow_hyphen_values = true,
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Offset any pattern by a fraction of the screen on each axis (-2 to 2; 0,0 is centered)")
+    )]
+    pub pattern_pan: Option<(f64, f64)>,
+
     #[arg(
         long = "buffer-size",
         value_name = "BYTES",
+        global = true,
         help_heading = CliFormat::HEADING_CORE,
         help = CliFormat::highlight_description("Set input buffer size for streaming mode (default: 8192)")
     )]
     pub buffer_size: Option<usize>,
 
+    #[arg(
+        long = "posterize",
+        value_name = "LEVELS",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Snap pattern values to a number of color bands (2-64)")
+    )]
+    pub posterize: Option<u32>,
+
+    #[arg(
+        long = "dither",
+        value_name = "MODE",
+        default_value = "none",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Dither between posterized bands: none, ordered, or blue-noise")
+    )]
+    pub dither: DitherMode,
+
+    #[arg(
+        long = "brightness",
+        value_name = "FACTOR",
+        default_value = "1.0",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Scale the brightness of gradient colors (0.0-3.0)")
+    )]
+    pub brightness: f64,
+
+    #[arg(
+        long = "saturation",
+        value_name = "FACTOR",
+        default_value = "1.0",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Scale the saturation of gradient colors; 0 is greyscale (0.0-3.0)")
+    )]
+    pub saturation: f64,
+
+    #[arg(
+        long = "contrast",
+        value_name = "FACTOR",
+        default_value = "1.0",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Scale the contrast of gradient colors around mid-grey (0.0-3.0)")
+    )]
+    pub contrast: f64,
+
+    #[arg(
+        long = "gamma",
+        value_name = "GAMMA",
+        default_value = "1.0",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Gamma of gradient colors; above 1 lightens midtones, below 1 darkens them (0.1-5.0)")
+    )]
+    pub gamma: f64,
+
+    #[arg(
+        long = "min-contrast",
+        value_name = "RATIO",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Shift colors to keep at least this WCAG contrast ratio against the terminal background, e.g. 4.5 (1-21)")
+    )]
+    pub min_contrast: Option<f64>,
+
+    #[arg(
+        long = "grain",
+        value_name = "AMOUNT",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Overlay flickering film grain with occasional sparkles (0.0-1.0)")
+    )]
+    pub grain: Option<f64>,
+
+    #[arg(
+        long,
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Color unified diffs by line: added lines green, removed lines red, and context dimmed")
+    )]
+    pub diff: bool,
+
+    #[arg(
+        long = "diff-rules",
+        value_name = "FILE",
+        requires = "diff",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Rules file for --diff giving each kind of line a theme, colors, brightness, or saturation (default: ~/.config/chromacat/diff.yaml)")
+    )]
+    pub diff_rules: Option<PathBuf>,
+
+    #[arg(
+        long = "columns",
+        value_name = "DELIMITER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto",
+        conflicts_with = "diff",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Color tables by column, each with its own slice of the gradient: auto (default), tab, comma, semicolon, pipe, or space")
+    )]
+    pub columns: Option<ColumnDelimiter>,
+
+    #[arg(
+        long = "column-themes",
+        value_name = "NAMES",
+        value_delimiter = ',',
+        requires = "columns",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Comma-separated themes cycled across columns instead of slicing the main theme")
+    )]
+    pub column_themes: Vec<String>,
+
+    #[arg(
+        long = "data-column",
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with_all = ["diff", "columns"],
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Color each line by the number in field N (from 1) instead of the pattern, as a heatmap from the lowest value to the highest")
+    )]
+    pub data_column: Option<u16>,
+
+    #[arg(
+        long = "data-range",
+        value_name = "MIN,MAX",
+        value_parser = parse_data_range,
+        requires = "data_column",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Values mapped to the ends of the gradient for --data-column, instead of the lowest and highest found")
+    )]
+    pub data_range: Option<(f64, f64)>,
+
+    #[arg(
+        long = "char-ramp",
+        value_name = "CHARS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = DEFAULT_CHAR_RAMP,
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Shade blank cells with density characters, densest first (default: \"█▓▒░ \")")
+    )]
+    pub char_ramp: Option<String>,
+
+    #[arg(
+        long = "resolution",
+        value_name = "MODE",
+        default_value = "cell",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Draw the pattern in blank cells at higher resolution: cell, half (▀ blocks), or braille (2x4 dots)")
+    )]
+    pub resolution: Resolution,
+
+    #[arg(
+        long = "mask-mode",
+        value_name = "MODE",
+        default_value = "off",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Use the text as a stencil for the pattern: off, stencil (only glyphs), density (stronger where text is dense), or glow (fades out around glyphs)")
+    )]
+    pub mask_mode: MaskMode,
+
+    #[arg(
+        long = "effect",
+        value_name = "EFFECT",
+        default_value = "none",
+        global = true,
+        help_heading = CliFormat::HEADING_CORE,
+        help = CliFormat::highlight_description("Draw an effect around the text: none, shadow (drop shadow), outline, or glow")
+    )]
+    pub effect: TextEffect,
+
+    #[arg(
+        long = "screenshot",
+        value_name = "TARGET",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "inline",
+        conflicts_with_all = ["line_numbers", "ruler", "pager"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Render one frame as an image: inline (default), iterm, kitty, or a PNG file path")
+    )]
+    pub screenshot: Option<ScreenshotTarget>,
+
+    #[arg(
+        long = "emit-frames",
+        value_name = "FORMAT",
+        conflicts_with_all = ["screenshot", "status_line", "inline"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Write the pattern's colors every frame as raw rgb bytes or json lines instead of drawing text, for LED drivers and other programs")
+    )]
+    pub emit_frames: Option<FrameFormat>,
+
+    #[arg(
+        long = "emit-to",
+        value_name = "PATH",
+        requires = "emit_frames",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("File or FIFO --emit-frames writes to instead of stdout")
+    )]
+    pub emit_to: Option<PathBuf>,
+
+    #[arg(
+        long = "frame-size",
+        value_name = "COLSxROWS",
+        value_parser = parse_frame_size,
+        requires = "emit_frames",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Grid sampled by --emit-frames, e.g. 32x8 (defaults to the terminal size)")
+    )]
+    pub frame_size: Option<(usize, usize)>,
+
+    #[arg(
+        long = "output",
+        value_name = "URL",
+        conflicts_with_all = ["emit_frames", "screenshot", "status_line", "inline"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Drive an LED strip instead of the terminal, e.g. wled://192.168.1.50 for a WLED controller")
+    )]
+    pub output: Option<WledTarget>,
+
+    #[arg(
+        long = "led-count",
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "output",
+        conflicts_with = "led_map",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Number of LEDs on the --output strip, sampled along a line")
+    )]
+    pub led_count: Option<u16>,
+
+    #[arg(
+        long = "led-map",
+        value_name = "FILE",
+        requires = "output",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("YAML file placing the --output LEDs on a grid, for matrices and other shapes")
+    )]
+    pub led_map: Option<PathBuf>,
+
+    #[arg(
+        long = "status-line",
+        value_name = "WIDTH",
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with_all = ["line_numbers", "ruler", "pager"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Print one frame of the input as a single line exactly WIDTH columns wide, for tmux or zellij status bars")
+    )]
+    pub status_line: Option<u16>,
+
+    #[arg(
+        long = "status-format",
+        value_name = "FORMAT",
+        default_value = "ansi",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Color format for --status-line: ansi escapes or tmux #[fg=...] styles")
+    )]
+    pub status_format: StatusFormat,
+
+    #[arg(
+        short = 'q',
+        long,
+        conflicts_with = "verbose",
+        global = true,
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Print errors only, leaving out warnings and the summary at exit")
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        short = 'v',
+        long,
+        action = ArgAction::Count,
+        global = true,
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Say what ChromaCat is doing on stderr; -vv for debug detail")
+    )]
+    pub verbose: u8,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        global = true,
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Write warnings and other diagnostics to this file as they happen, instead of printing them once output is done; handy for watching an animation's warnings with tail -f")
+    )]
+    pub log_file: Option<PathBuf>,
+
+    #[arg(
+        long = "headless",
+        global = true,
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Render without a terminal: assume an 80x24 screen, draw one frame of animations and never change terminal modes, for testing in CI")
+    )]
+    pub headless: bool,
+
     #[arg(
         long,
+        conflicts_with = "fail_on_empty",
         help_heading = CliFormat::HEADING_GENERAL,
         help = CliFormat::highlight_description("Run in demo mode with generated patterns")
     )]
     pub demo: bool,
 
-    #[arg(
-        long,
-        value_name = "FILE",
-        help_heading = CliFormat::HEADING_PLAYLIST,
-        help = CliFormat::highlight_description("Load and play a sequence of patterns (uses default if not specified in animation mode)")
-    )]
-    pub playlist: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with_all = ["inline", "screenshot", "status_line", "headless"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Play random scenes full screen until any key is pressed, without passing the key on to the shell")
+    )]
+    pub screensaver: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        requires = "screensaver",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Start the screen saver only once the terminal has had no input for this long; a key pressed sooner cancels it")
+    )]
+    pub idle: Option<u64>,
+
+    #[arg(
+        long = "lock-cmd",
+        value_name = "COMMAND",
+        requires = "screensaver",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Shell command that locks the terminal, run on the blanked screen when the screen saver ends, e.g. vlock")
+    )]
+    pub lock_cmd: Option<String>,
+
+    #[command(flatten)]
+    pub playlist: PlaylistArgs,
+
+    #[arg(
+        long = "check-playlist",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Check a playlist for unknown patterns, themes, and art, bad parameters, and zero durations, reporting each problem with its line, then exit")
+    )]
+    pub check_playlist: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub art: ArtArgs,
+
+    /// List available demo art patterns
+    #[arg(
+        long = "list-art",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Show available art patterns")
+    )]
+    pub list_art: bool,
+}
+
+/// Options for reading and laying out text, taken without a command and by
+/// `cat` and `export`
+#[derive(Args, Debug, Clone)]
+pub struct TextArgs {
+    #[arg(
+        long = "file-headers",
+        help_heading = CliFormat::HEADING_INPUT,
+        help = CliFormat::highlight_description("Print a styled separator with the filename before each file")
+    )]
+    pub file_headers: bool,
+
+    #[arg(
+        long = "theme-per-file",
+        value_name = "NAMES",
+        value_delimiter = ',',
+        help_heading = CliFormat::HEADING_INPUT,
+        help = CliFormat::highlight_description("Comma-separated themes cycled across input files")
+    )]
+    pub theme_per_file: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "show",
+        help_heading = CliFormat::HEADING_INPUT,
+        help = CliFormat::highlight_description("What to do with binary data in the input: show (as text), skip, or hex (dump it)")
+    )]
+    pub binary: BinaryPolicy,
+
+    #[arg(
+        long,
+        help_heading = CliFormat::HEADING_INPUT,
+        help = CliFormat::highlight_description("Exit with status 3 when the input is empty, so scripts can tell nothing came through")
+    )]
+    pub fail_on_empty: bool,
+
+    #[arg(
+        long = "wrap",
+        value_name = "MODE",
+        default_value = "wrap",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Long line handling: wrap, truncate (with ellipsis), or scroll (Left/Right in animation mode)")
+    )]
+    pub wrap: WrapMode,
+
+    #[arg(
+        long = "line-numbers",
+        conflicts_with = "inline",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Number input lines in a dimmed gutter left of the text")
+    )]
+    pub line_numbers: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "inline",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Show a column ruler above the text")
+    )]
+    pub ruler: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["no_pager", "inline", "headless"],
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Show the output in a scrollable, searchable viewer, still unless animated; on by default for files longer than the screen")
+    )]
+    pub pager: bool,
+
+    #[arg(
+        long = "no-pager",
+        help_heading = CliFormat::HEADING_GENERAL,
+        help = CliFormat::highlight_description("Print long output straight to the terminal instead of paging it")
+    )]
+    pub no_pager: bool,
+}
+
+/// Animation options, taken without a command and by the subcommands that
+/// animate
+#[derive(Args, Debug, Clone)]
+pub struct AnimationArgs {
+    #[arg(
+        short = 'a',
+        long,
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Enable animation mode")
+    )]
+    pub animate: bool,
+
+    #[arg(
+        long,
+        default_value = "30",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        value_name = "NUM",
+        help = CliFormat::highlight_description("Frames per second (1-144)")
+    )]
+    pub fps: u32,
+
+    #[arg(
+        long = "cpu-budget",
+        value_name = "PERCENT",
+        value_parser = parse_cpu_budget,
+        conflicts_with = "fixed_step",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Lower the frame rate, then pattern complexity, to keep CPU usage under PERCENT of one core, e.g. 20%")
+    )]
+    pub cpu_budget: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "FRAMES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "8",
+        value_parser = clap::value_parser!(u32).range(2..),
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Draw the even and odd rows in alternate frames, halving the output per frame on slow links, with every row drawn once every FRAMES frames (default 8)")
+    )]
+    pub interlace: Option<u32>,
+
+    #[arg(
+        long,
+        default_value = "0",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        value_name = "SECS",
+        help = CliFormat::highlight_description("Duration in seconds (0 = infinite)")
+    )]
+    pub duration: u64,
+
+    #[arg(
+        short = 's',
+        long,
+        default_value = "1.0",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        value_name = "NUM",
+        help = CliFormat::highlight_description("Animation speed (0.0-1.0)")
+    )]
+    pub speed: f64,
+
+    #[arg(
+        long,
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Blur pattern motion between frames to reduce shimmer at low frame rates")
+    )]
+    pub smooth: bool,
+
+    #[arg(
+        long = "fixed-step",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Advance the animation by exactly one frame of time per frame drawn, so the output is the same however fast the host is; always on for --sync-follow")
+    )]
+    pub fixed_step: bool,
+
+    #[arg(
+        long = "canvas",
+        value_name = "SCALE",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Compute the pattern on a virtual canvas SCALE times the terminal size (1-16) and show a window into it; pan with h/j/k/l, zoom with z/x")
+    )]
+    pub canvas: Option<f64>,
+
+    #[arg(
+        long = "pan",
+        value_name = "X,Y",
+        value_parser = parse_pan,
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Initial window position on the canvas, 0-1 on each axis (0,0 is top left)")
+    )]
+    pub pan: Option<(f64, f64)>,
+
+    #[arg(
+        long = "drift",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Slowly pan and zoom across the pattern on its own (toggle with d)")
+    )]
+    pub drift: bool,
+
+    #[arg(
+        long = "debug-overlay",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Show frame timings, scene transitions, and recent log events over the animation (toggle with F12)")
+    )]
+    pub debug_overlay: bool,
+
+    #[arg(
+        long = "legend",
+        value_name = "POSITION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "bottom-right",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Show the gradient as a color scale with the theme name: top-left, top-right, bottom-left, or bottom-right (default; toggle with M)")
+    )]
+    pub legend: Option<LegendPosition>,
+
+    #[arg(
+        long = "inline",
+        conflicts_with = "headless",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("With --animate, animate the colors of the last printed lines in place instead of taking over the screen; stops after --duration or on any key")
+    )]
+    pub inline: bool,
+
+    #[arg(
+        long = "mouse",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Use the mouse: the wheel scrolls the text or the list under the pointer, double-click applies a list item, and dragging pans the pattern. The terminal can't select text meanwhile")
+    )]
+    pub mouse: bool,
+
+    #[arg(
+        long = "summary",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("On exit, print how long the animation ran, the frames drawn and average FPS, the scenes played, and the pattern and theme shown the longest")
+    )]
+    pub summary: bool,
+
+    #[arg(
+        long = "stats",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Write the statistics --summary prints to FILE as JSON, on exit and every minute while animating")
+    )]
+    pub stats: Option<PathBuf>,
+
+    #[arg(
+        long = "layout",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Split the screen into regions, each with its own pattern and theme, as described by a YAML layout file")
+    )]
+    pub layout: Option<PathBuf>,
+
+    #[arg(
+        long = "sync-leader",
+        value_name = "ADDR",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Share this animation's clock and playlist position with followers connecting to ADDR, e.g. 0.0.0.0:7878")
+    )]
+    pub sync_leader: Option<String>,
+
+    #[arg(
+        long = "sync-follow",
+        value_name = "HOST:PORT",
+        conflicts_with = "sync_leader",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Follow the clock and playlist position of a --sync-leader instance")
+    )]
+    pub sync_follow: Option<String>,
+
+    #[arg(
+        long = "mqtt",
+        value_name = "URL",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Publish the scene, theme, pattern and progress to the MQTT broker at mqtt://[USER[:PASSWORD]@]HOST[:PORT] and take commands from it")
+    )]
+    pub mqtt: Option<String>,
+
+    #[arg(
+        long = "mqtt-topic",
+        value_name = "PREFIX",
+        default_value = "chromacat",
+        requires = "mqtt",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Topic prefix for --mqtt: state goes to PREFIX/state, commands come from PREFIX/set")
+    )]
+    pub mqtt_topic: String,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Repeat the pattern's motion every SECONDS of animation time, showing progress through the cycle in the status bar")
+    )]
+    pub cycle: Option<f64>,
+
+    #[arg(
+        long = "loop-mode",
+        value_name = "MODE",
+        default_value = "loop",
+        requires = "cycle",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("What happens at the end of a --cycle: loop (start over), ping-pong (play it backwards), or once (hold the last frame)")
+    )]
+    pub loop_mode: LoopMode,
+
+    #[arg(
+        long = "loop-cache",
+        requires = "cycle",
+        help_heading = CliFormat::HEADING_ANIMATION,
+        help = CliFormat::highlight_description("Keep the pattern of each frame of the first --cycle in memory and replay it after, for almost no CPU once the loop is recorded")
+    )]
+    pub loop_cache: bool,
+}
+
+/// Options that pick the scenes an animation plays, taken without a command
+/// and by `cat`, `demo`, `play`, and `daemon`
+#[derive(Args, Debug, Clone)]
+pub struct PlaylistArgs {
+    #[arg(
+        id = "playlist",
+        long = "playlist",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Load and play a sequence of patterns (uses default if not specified in animation mode)")
+    )]
+    pub file: Option<PathBuf>,
+
+    #[arg(
+        long = "record-session",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Record every pattern, theme, parameter, and art change while animating, and save the session as a playlist that replays it on exit")
+    )]
+    pub record_session: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "playlist",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Play the favorites bookmarked with B in turn, as a playlist")
+    )]
+    pub favorites: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["playlist", "favorites"],
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Play random pattern and theme scenes drawn from the pool in ~/.config/chromacat/pool.yaml, or from every pattern and theme")
+    )]
+    pub random: bool,
+
+    #[arg(
+        long,
+        value_name = "SOURCE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "time",
+        conflicts_with_all = ["playlist", "favorites", "random", "randomize"],
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Pick scenes that suit the sky: time (default) for the time of day, or weather for the current weather (needs the weather feature)")
+    )]
+    pub adaptive: Option<AdaptiveSource>,
+
+    #[arg(
+        long,
+        value_name = "PLACE",
+        requires = "adaptive",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Where --adaptive=weather looks up the weather, e.g. a city or airport code (located by IP address otherwise)")
+    )]
+    pub location: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "random",
+        help_heading = CliFormat::HEADING_PLAYLIST,
+        help = CliFormat::highlight_description("Pool file for --random, with include and exclude lists and weights for patterns and themes")
+    )]
+    pub pool: Option<PathBuf>,
+}
+
+/// Demo art options, taken without a command and by `demo` and `daemon`
+#[derive(Args, Debug, Clone)]
+pub struct ArtArgs {
+    /// Demo art pattern to display
+    #[arg(
+        id = "art",
+        long = "art",
+        value_name = "TYPE",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Select demo art pattern to display")
+    )]
+    pub name: Option<String>,
+
+    /// Demo art parameters
+    #[arg(
+        id = "art_params",
+        long = "art-param",
+        value_name = "KEY=VALUE",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Demo art parameter, e.g. density=0.5 (can be used multiple times; see --list-art)")
+    )]
+    pub params: Vec<String>,
+
+    /// Transition between demo arts
+    #[arg(
+        id = "art_transition",
+        long = "art-transition",
+        value_name = "KIND",
+        default_value = "dissolve",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("How new demo art replaces the old when a playlist changes art: cut, dissolve, wipe, or typewriter")
+    )]
+    pub transition: ArtTransition,
+
+    /// Countdown length for the clock art
+    #[arg(
+        long = "countdown",
+        value_name = "TIME",
+        value_parser = demo::parse_countdown,
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Count down from a duration like 10m or 1h30m (uses the clock art)")
+    )]
+    pub countdown: Option<Duration>,
+
+    /// Theme the colors shift toward as the countdown runs out
+    #[arg(
+        long = "countdown-theme",
+        value_name = "NAME",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Shift the colors from the -t theme toward this one as the countdown runs out")
+    )]
+    pub countdown_theme: Option<String>,
+
+    /// Quote file for the quote art
+    #[arg(
+        long = "quote-file",
+        value_name = "FILE",
+        help_heading = CliFormat::HEADING_DEMO,
+        help = CliFormat::highlight_description("Show quotes from a fortune-style file, separated by % lines (uses the quote art)")
+    )]
+    pub quote_file: Option<PathBuf>,
+}
+
+/// Subcommands that run ChromaCat in a dedicated mode
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Colorize files, or stdin if none are given (the default without a command)
+    #[command(after_help = "Example: chromacat -t ocean cat notes.txt")]
+    Cat {
+        /// Input files
+        #[arg(value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        text: TextArgs,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+
+        #[command(flatten)]
+        playlist: PlaylistArgs,
+    },
+
+    /// Animate the built-in demo art
+    #[command(after_help = "Examples:\n  chromacat -p plasma demo logo\n  chromacat demo clock --still")]
+    Demo {
+        /// Art to show (see --list-art; a mix of everything by default)
+        #[arg(id = "demo_art", value_name = "ART", conflicts_with = "art")]
+        name: Option<String>,
+
+        /// Print the art once instead of animating it
+        #[arg(long)]
+        still: bool,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+
+        #[command(flatten)]
+        playlist: PlaylistArgs,
+
+        #[command(flatten)]
+        art: ArtArgs,
+    },
+
+    /// Count down with the clock art, shifting from a calm theme to a warning one and ringing the bell at the end
+    #[command(
+        after_help = "Examples:\n  chromacat timer 25m\n  chromacat -p plasma timer 5m --from forest --to sunset"
+    )]
+    Timer {
+        /// Countdown length, like 90, 45s, 10m or 1h30m
+        #[arg(id = "countdown", value_name = "TIME", value_parser = demo::parse_countdown)]
+        duration: Duration,
+
+        /// Theme at the start (takes the place of -t)
+        #[arg(long, default_value = "ocean", value_name = "NAME")]
+        from: String,
+
+        /// Theme the colors have shifted to when time is up
+        #[arg(long, default_value = "fire", value_name = "NAME")]
+        to: String,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+    },
+
+    /// Animate a playlist
+    #[command(after_help = "Example: chromacat -t ocean play show.yaml")]
+    Play {
+        /// Playlist file
+        #[arg(
+            id = "playlist_file",
+            value_name = "PLAYLIST",
+            conflicts_with_all = ["playlist", "favorites", "random", "adaptive"]
+        )]
+        file: PathBuf,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+
+        #[command(flatten)]
+        playlist: PlaylistArgs,
+    },
+
+    /// Render one frame of the input to a PNG file
+    #[command(after_help = "Example: chromacat -p plasma -t ocean export banner.png banner.txt")]
+    Export {
+        /// PNG file to write
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+
+        /// Input files (reads from stdin if none provided)
+        #[arg(value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        text: TextArgs,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+    },
+
+    /// Run a command in a pseudo-terminal and colorize its output live
+    #[command(after_help = "Example: chromacat -t ocean exec -- cargo build")]
+    Exec {
+        /// Command to run, followed by its arguments
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+
+    /// Show an animated gradient progress bar driven by values read from stdin
+    #[command(
+        after_help = "Each input line is `VALUE [MESSAGE]`, `PERCENT% [MESSAGE]` or just `MESSAGE`.\n\
+                      Example: seq 0 10 100 | chromacat -t ocean progress --label Copying"
+    )]
+    Progress {
+        /// Value that represents 100%
+        #[arg(long, default_value = "100", value_name = "NUM")]
+        total: f64,
+
+        /// Width of the bar in columns (fits the terminal by default)
+        #[arg(long, value_name = "COLS")]
+        width: Option<usize>,
+
+        /// Message shown next to the indicator
+        #[arg(long, value_name = "TEXT")]
+        label: Option<String>,
+
+        /// Show a spinner instead of a bar
+        #[arg(long)]
+        spinner: bool,
+
+        /// Frames per second the indicator is redrawn at
+        #[arg(long, default_value = "30", value_name = "FPS")]
+        fps: u32,
+    },
+
+    /// Print a colored login banner filled with the host name, uptime, load, and last login
+    #[command(
+        after_help = "Placeholders: {hostname} {user} {os} {kernel} {uptime} {load} {last_login} {date};\n\
+                      write {{ and }} for literal braces. The output never touches the terminal, so it\n\
+                      suits /etc/update-motd.d scripts. Example:\n\
+                      chromacat -t ocean -p diagonal motd --template /etc/chromacat/motd.tmpl"
+    )]
+    Motd {
+        /// Template file (a built-in banner by default)
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
+
+        /// Width in columns the pattern is spread over
+        #[arg(long, default_value_t = DEFAULT_MOTD_WIDTH, value_name = "COLS")]
+        width: usize,
+    },
+
+    /// Keep the demo art animating and accept commands from `chromacat ctl`
+    #[command(after_help = "Example: chromacat daemon --playlist show.yaml > /dev/tty2")]
+    Daemon {
+        /// Control socket path (defaults to $XDG_RUNTIME_DIR/chromacat.sock)
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        #[command(flatten)]
+        animation: AnimationArgs,
+
+        #[command(flatten)]
+        playlist: PlaylistArgs,
+
+        #[command(flatten)]
+        art: ArtArgs,
+    },
+
+    /// Send a command to a running daemon
+    #[command(after_help = Cli::ctl_help())]
+    Ctl {
+        /// Control socket path (defaults to $XDG_RUNTIME_DIR/chromacat.sock)
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        /// Command and its argument, e.g. `set-theme ocean`
+        #[arg(value_name = "COMMAND", required = true, num_args = 1..)]
+        command: Vec<String>,
+    },
+
+    /// Browse themes
+    Themes {
+        #[command(subcommand)]
+        command: ThemesCommand,
+    },
+
+    /// Browse patterns
+    Patterns {
+        #[command(subcommand)]
+        command: PatternsCommand,
+    },
+
+    /// Convert between playlists and command lines
+    Playlist {
+        #[command(subcommand)]
+        command: PlaylistCommand,
+    },
+}
+
+/// Subcommands of `chromacat themes`
+#[derive(Subcommand, Debug, Clone)]
+pub enum ThemesCommand {
+    /// List themes with a preview of each
+    List {
+        /// Show only themes whose name or description contains TEXT
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+
+        /// Show only the themes in one category, e.g. space
+        #[arg(long, value_name = "NAME")]
+        category: Option<String>,
+
+        /// Show only the themes tagged TAG, e.g. warm
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+
+        /// Show the installed theme packs and their themes instead
+        #[arg(long, conflicts_with_all = ["filter", "category", "tag"])]
+        user: bool,
+    },
+
+    /// Check a YAML theme pack and install it for every run, under the user
+    /// category
+    #[command(
+        after_help = "Examples:\n  chromacat themes install ./sunrise.yaml\n  chromacat themes install https://example.com/packs/sunrise.yaml"
+    )]
+    Install {
+        /// Theme pack file or http(s) URL; the pack is named after the file
+        #[arg(value_name = "PATH_OR_URL")]
+        source: String,
+    },
+
+    /// Remove an installed theme pack
+    Remove {
+        /// Name of the pack, as `themes list --user` shows it
+        #[arg(value_name = "PACK")]
+        name: String,
+    },
+
+    /// Show a fullscreen animated preview of a theme, or of every theme
+    #[command(after_help = "Examples:\n  chromacat themes preview ocean\n  chromacat themes preview --all --seconds 2")]
+    Preview {
+        /// Theme to preview
+        #[arg(
+            id = "preview_theme",
+            value_name = "THEME",
+            required_unless_present = "all",
+            conflicts_with = "all"
+        )]
+        theme: Option<String>,
+
+        /// Walk through every theme
+        #[arg(long)]
+        all: bool,
+
+        /// Seconds to show each pattern or theme
+        #[arg(long, default_value = "3", value_name = "SECONDS")]
+        seconds: u64,
+    },
+}
+
+/// Subcommands of `chromacat patterns`
+#[derive(Subcommand, Debug, Clone)]
+pub enum PatternsCommand {
+    /// List patterns
+    List {
+        /// Show only patterns whose name or description contains TEXT
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+    },
+
+    /// Show every pattern's parameters (same as --pattern-help)
+    Params,
+}
+
+/// Subcommands of `chromacat playlist`
+#[derive(Subcommand, Debug, Clone)]
+pub enum PlaylistCommand {
+    /// Print the command line that shows each entry of a playlist
+    #[command(
+        name = "to-cli",
+        after_help = "Example: chromacat playlist to-cli show.yaml"
+    )]
+    ToCli {
+        /// Playlist file
+        #[arg(value_name = "PLAYLIST")]
+        playlist: PathBuf,
+    },
+
+    /// Print a playlist with one entry that shows what a command line does
+    #[command(
+        name = "from-cli",
+        after_help = "Example: chromacat playlist from-cli \"-p plasma -t neon --param complexity=3.2\" > show.yaml"
+    )]
+    FromCli {
+        /// Options as they would be typed, with or without `chromacat` in front
+        #[arg(value_name = "ARGS", allow_hyphen_values = true)]
+        args: String,
+
+        /// Seconds the entry lasts
+        #[arg(long, default_value = "30", value_name = "SECONDS")]
+        duration: u64,
+    },
+}
+
+impl Cli {
+    /// Parses the command line from `args`, failing on options that are
+    /// neither shared nor taken by the subcommand they come before, and
+    /// expands the subcommand as [`Cli::expand_command`] does
+    pub fn try_parse_args<I, T>(args: I) -> std::result::Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let mut command = Self::command();
+        let matches = command.try_get_matches_from_mut(args)?;
+        if let Some((name, _)) = matches.subcommand() {
+            // Shared options may come anywhere; the others belong to the
+            // mode they're given for
+            let misplaced = command.get_arguments().find(|arg| {
+                !arg.is_global_set()
+                    && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+            });
+            if let Some(arg) = misplaced {
+                let flag = arg
+                    .get_long()
+                    .map_or_else(|| arg.get_id().to_string(), |long| format!("--{}", long));
+                let taken = command.find_subcommand(name).is_some_and(|subcommand| {
+                    subcommand
+                        .get_arguments()
+                        .any(|a| a.get_id() == arg.get_id())
+                });
+                let message = if taken {
+                    format!("{} is an option of '{}', so it goes after it", flag, name)
+                } else {
+                    format!("{} can't be used with '{}'", flag, name)
+                };
+                return Err(command.error(ErrorKind::ArgumentConflict, message));
+            }
+        }
+        let mut cli = Self::from_arg_matches(&matches)?;
+        cli.expand_command();
+        Ok(cli)
+    }
+
+    /// Parses the command line of this process as [`Cli::try_parse_args`]
+    /// does, exiting with clap's message on an error
+    pub fn parse_args() -> Self {
+        Self::try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Turns the subcommands that stand for flags (`cat`, `demo`, `timer`,
+    /// `play`, `export`, and `patterns params`) into those flags, and takes
+    /// the options of `daemon`, so the rest of the program sees one form
+    /// whichever was used. Other subcommands are left in place.
+    pub fn expand_command(&mut self) {
+        let Some(command) = self.command.take() else {
+            return;
+        };
+        match command {
+            Command::Cat {
+                files,
+                text,
+                animation,
+                playlist,
+            } => {
+                self.files.extend(files);
+                self.text = text;
+                self.animation = animation;
+                self.playlist = playlist;
+            }
+            Command::Demo {
+                name,
+                still,
+                animation,
+                playlist,
+                art,
+            } => {
+                self.animation = animation;
+                self.playlist = playlist;
+                self.art = art;
+                self.demo = true;
+                self.animation.animate |= !still;
+                self.art.name = name.or(self.art.name.take());
+            }
+            Command::Timer {
+                duration,
+                from,
+                to,
+                animation,
+            } => {
+                self.animation = animation;
+                self.demo = true;
+                self.animation.animate = true;
+                self.art.name = Some(DemoArt::Clock.as_str().to_string());
+                self.art.countdown = Some(duration);
+                self.theme = from;
+                self.art.countdown_theme = Some(to);
+            }
+            Command::Play {
+                file,
+                animation,
+                playlist,
+            } => {
+                self.animation = animation;
+                self.playlist = playlist;
+                self.playlist.file = Some(file);
+                self.animation.animate = true;
+            }
+            Command::Export {
+                output,
+                files,
+                text,
+                animation,
+            } => {
+                self.text = text;
+                self.animation = animation;
+                self.screenshot = Some(ScreenshotTarget::File(output));
+                self.files.extend(files);
+            }
+            Command::Daemon {
+                socket,
+                animation,
+                playlist,
+                art,
+            } => {
+                self.animation = animation.clone();
+                self.playlist = playlist.clone();
+                self.art = art.clone();
+                self.demo = true;
+                self.command = Some(Command::Daemon {
+                    socket,
+                    animation,
+                    playlist,
+                    art,
+                });
+            }
+            Command::Patterns {
+                command: PatternsCommand::Params,
+            } => self.pattern_help = true,
+            command => self.command = Some(command),
+        }
+    }
+
+    /// Prints the themes or patterns `themes list` or `patterns list` asks
+    /// for, returning false for any other command
+    pub fn print_command_list(&self) -> Result<bool> {
+        let (filter, themes) = match &self.command {
+            Some(Command::Themes {
+                command: ThemesCommand::List { user: true, .. },
+            }) => {
+                Self::print_theme_packs()?;
+                return Ok(true);
+            }
+            Some(Command::Themes {
+                command:
+                    ThemesCommand::List {
+                        filter,
+                        category,
+                        tag,
+                        user: false,
+                    },
+            }) => (
+                ListFilter {
+                    search: filter.clone(),
+                    category: category.clone(),
+                    tag: tag.clone(),
+                },
+                true,
+            ),
+            Some(Command::Patterns {
+                command: PatternsCommand::List { filter },
+            }) => (
+                ListFilter {
+                    search: filter.clone(),
+                    category: None,
+                    tag: None,
+                },
+                false,
+            ),
+            _ => return Ok(false),
+        };
+
+        match (filter.is_empty(), themes) {
+            (true, true) => Self::print_themes(),
+            (true, false) => Self::print_patterns(),
+            (false, _) => {
+                let mut catalog = Catalog::collect(&filter)?;
+                if themes {
+                    catalog.patterns.clear();
+                } else {
+                    catalog.themes.clear();
+                }
+                Self::print_catalog(&catalog);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Lists the commands accepted by `chromacat ctl`
+    fn ctl_help() -> String {
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut help = String::from("Commands:\n");
+        #[cfg(unix)]
+        for (usage, description) in crate::control::ControlCommand::usage() {
+            help.push_str(&format!("  {:<20} {}\n", usage, description));
+        }
+        help
+    }
 
-    /// Demo art pattern to display
-    #[arg(
-        long = "art",
-        value_name = "TYPE",
-        help_heading = CliFormat::HEADING_DEMO,
-        help = CliFormat::highlight_description("Select demo art pattern to display")
-    )]
-    pub art: Option<String>,
+    /// Returns the base pattern id from `-p`
+    pub fn base_pattern(&self) -> &str {
+        self.pattern.split(',').next().unwrap_or_default().trim()
+    }
 
-    /// List available demo art patterns
-    #[arg(
-        long = "list-art",
-        help_heading = CliFormat::HEADING_DEMO,
-        help = CliFormat::highlight_description("Show available art patterns")
-    )]
-    pub list_art: bool,
-}
+    /// Returns the ids of the patterns listed after the base pattern in `-p`
+    pub fn layer_patterns(&self) -> Vec<&str> {
+        self.pattern.split(',').skip(1).map(str::trim).collect()
+    }
 
-impl Cli {
     /// Creates pattern configuration from CLI arguments
     pub fn create_pattern_config(&self) -> Result<PatternConfig> {
         let common = CommonParams {
             frequency: self.frequency,
             amplitude: self.amplitude,
-            speed: self.speed,
+            speed: self.animation.speed,
             correct_aspect: !self.no_aspect_correction,
             aspect_ratio: self.aspect_ratio,
+            zoom: self.pattern_zoom,
+            pan_x: self.pattern_pan.map_or(0.0, |(x, _)| x),
+            pan_y: self.pattern_pan.map_or(0.0, |(_, y)| y),
             theme_name: Some(self.theme.clone()),
         };
 
-        // Get pattern params from registry
-        let pattern_params = if self.params.is_empty() {
-            // Use default parameters
-            REGISTRY.create_pattern_params(&self.pattern)
+        let default_params = |pattern: &str| {
+            REGISTRY.create_pattern_params(pattern)
                 .ok_or_else(|| ChromaCatError::PatternError {
-                    pattern: self.pattern.clone(),
+                    pattern: pattern.to_string(),
                     param: String::new(),
                     message: "Unknown pattern type".to_string(),
-                })?
+                })
+        };
+
+        // Get pattern params from registry
+        let pattern = self.base_pattern();
+        // A preset comes first, so --param can override its values
+        let params: Vec<String> = self
+            .preset_params()?
+            .into_iter()
+            .chain(self.params.iter().cloned())
+            .collect();
+        let pattern_params = if params.is_empty() {
+            // Use default parameters
+            default_params(pattern)?
         } else {
             // Parse provided parameters
-            let params_str = self.params.join(",");
-            REGISTRY.parse_params(&self.pattern, &params_str)
+            let params_str = params.join(",");
+            REGISTRY.parse_params(pattern, &params_str)
                 .map_err(|e| ChromaCatError::PatternError {
-                    pattern: self.pattern.clone(),
+                    pattern: pattern.to_string(),
                     param: "params".to_string(),
                     message: e,
                 })?
         };
 
+        // Extra patterns are blended on top with their default parameters
+        let blend = self.blend.unwrap_or_default();
+        let layers = self
+            .layer_patterns()
+            .into_iter()
+            .map(|pattern| Ok(PatternLayer::new(default_params(pattern)?, blend)))
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(PatternConfig {
             common,
             params: pattern_params,
+            layers,
         })
     }
 
+    /// Parses the options in `args`, as they would be typed after
+    /// `chromacat`, into a playlist with one entry lasting `duration`
+    /// seconds that shows the same pattern, layers, theme, parameters, and
+    /// demo art
+    pub fn playlist_from_args(args: &str, duration: u64) -> Result<Playlist> {
+        let mut args = split_args(args)?;
+        if args.first().is_some_and(|arg| arg.ends_with("chromacat")) {
+            args.remove(0);
+        }
+        let cli = Cli::try_parse_args(std::iter::once("chromacat".to_string()).chain(args))
+            .map_err(|e| ChromaCatError::InputError(e.to_string().trim_end().to_string()))?;
+        cli.create_pattern_config()?;
+        themes::get_theme(&cli.theme)?;
+
+        let mut entry = PlaylistEntry::new(cli.base_pattern(), &cli.theme, duration);
+        let params: Vec<String> = cli
+            .preset_params()?
+            .into_iter()
+            .chain(cli.params.iter().cloned())
+            .collect();
+        if let Some(params) = params_to_yaml(&params.join(",")) {
+            entry = entry.with_params(params);
+        }
+        for pattern in cli.layer_patterns() {
+            entry = entry.with_layer(LayerEntry::new(pattern, cli.blend.unwrap_or_default()));
+        }
+        if let Some(name) = &cli.art.name {
+            let art = DemoArt::try_from_str(name).ok_or_else(|| {
+                ChromaCatError::InputError(format!("Unknown demo art '{}'", name))
+            })?;
+            entry = entry.with_art(art);
+            if let Some(params) = params_to_yaml(&cli.art.params.join(",")) {
+                entry = entry.with_art_params(params);
+            }
+        }
+        Ok(Playlist::with_entries(vec![entry]))
+    }
+
+    /// Creates the second theme that shades colors, if `--light-theme` is set
+    pub fn create_light_map(&self) -> Result<Option<LightMap>> {
+        let Some(theme) = &self.light_theme else {
+            return Ok(None);
+        };
+        let source = self.light_source.clone().unwrap_or_default();
+        Ok(Some(LightMap::new(&themes::get_theme(theme)?, source)?))
+    }
+
+    /// Loads the palettes for diff lines if `--diff` is set, from
+    /// `--diff-rules` or the user's rules file
+    pub fn create_diff_palettes(&self) -> Result<Option<DiffPalettes>> {
+        if !self.diff {
+            return Ok(None);
+        }
+        let rules = match &self.diff_rules {
+            Some(path) => DiffRules::from_file(path)?,
+            None => DiffRules::load_default()?,
+        };
+        rules.palettes().map(Some)
+    }
+
+    /// Creates column coloring if `--columns` is set
+    pub fn create_columns(&self) -> Result<Option<Columns>> {
+        let Some(delimiter) = self.columns else {
+            return Ok(None);
+        };
+        let themes = self
+            .column_themes
+            .iter()
+            .map(|theme| themes::get_theme(theme))
+            .collect::<Result<Vec<_>>>()?;
+        Columns::new(delimiter, &themes).map(Some)
+    }
+
+    /// Creates data coloring if `--data-column` is set
+    pub fn create_data_column(&self) -> Option<DataColumn> {
+        self.data_column.map(|column| DataColumn::new(column as usize, self.data_range))
+    }
+
     /// Creates animation configuration from CLI arguments
     pub fn create_animation_config(&self) -> AnimationConfig {
         AnimationConfig {
-            fps: self.fps.clamp(1, 144),
-            cycle_duration: if self.duration == 0 {
+            fps: self.profile.unwrap_or_default().fps(self.animation.fps.clamp(1, 144)),
+            cycle_duration: if self.animation.duration == 0 {
                 Duration::from_secs(u64::MAX)
             } else {
-                Duration::from_secs(self.duration)
+                Duration::from_secs(self.animation.duration)
             },
-            infinite: self.duration == 0,
+            infinite: self.animation.duration == 0,
             show_progress: true,
-            smooth: self.smooth,
+            cycle: self.animation.cycle.map(Duration::from_secs_f64),
+            loop_mode: self.animation.loop_mode,
+            loop_cache: self.animation.loop_cache,
+            smooth: self.animation.smooth,
+            wrap_mode: self.text.wrap,
+            char_ramp: self.char_ramp.as_deref().map(CharRamp::new),
+            resolution: self.resolution,
+            mask_mode: self.mask_mode,
+            effect: self.effect,
+            gutter: Gutter::new(self.text.line_numbers, self.text.ruler),
+            camera: self.create_camera(),
+            // The layout file is loaded and validated when the renderer starts
+            layout: None,
+            debug_overlay: self.animation.debug_overlay,
+            legend: self.animation.legend,
+            // Fixed steps keep their frame rate, so the profile doesn't
+            // change by itself
+            profile: self
+                .profile
+                .or(self.fixed_step().map(|_| RenderProfile::Full)),
+            cpu_budget: self.animation.cpu_budget,
+            interlace: self.animation.interlace,
+        }
+    }
+
+    /// Returns the fixed frame timing the animation runs on, if
+    /// `--fixed-step` is given or this instance follows a sync leader
+    pub fn fixed_step(&self) -> Option<FixedStep> {
+        let fps = self.profile.unwrap_or_default().fps(self.animation.fps.clamp(1, 144));
+        (self.animation.fixed_step || self.animation.sync_follow.is_some())
+            .then(|| FixedStep::new(fps))
+    }
+
+    /// Creates the camera over the virtual canvas from CLI arguments
+    pub fn create_camera(&self) -> Camera {
+        let (pan_x, pan_y) = self.animation.pan.unwrap_or((0.5, 0.5));
+        Camera::new(self.animation.canvas.unwrap_or(1.0))
+            .with_pan(pan_x, pan_y)
+            .with_drift(self.animation.drift)
+    }
+
+    /// Returns the demo art to display, defaulting to the clock when a
+    /// countdown is requested and to quotes when a quote file is given
+    pub fn demo_art(&self) -> Option<&str> {
+        match (&self.art.name, self.art.countdown, &self.art.quote_file) {
+            (None, Some(_), _) => Some(DemoArt::Clock.as_str()),
+            (None, None, Some(_)) => Some(DemoArt::Quote.as_str()),
+            (art, _, _) => art.as_deref(),
+        }
+    }
+
+    /// Creates demo art settings from CLI arguments, loading the quote file
+    /// if one was given
+    pub fn create_art_settings(&self) -> Result<ArtSettings> {
+        let mut settings = ArtSettings::default()
+            .with_countdown(self.art.countdown)
+            .with_params(self.parse_art_params()?);
+
+        if let Some(path) = &self.art.quote_file {
+            let content = std::fs::read_to_string(path)?;
+            let quotes = demo::quote::parse_quotes(&content);
+            if quotes.is_empty() {
+                return Err(ChromaCatError::InputError(format!(
+                    "No quotes found in {}",
+                    path.display()
+                )));
+            }
+            settings = settings.with_quotes(quotes);
+        }
+
+        Ok(settings)
+    }
+
+    /// Parses `--art-param` values against the selected art, or against
+    /// every art when none is selected
+    fn parse_art_params(&self) -> Result<BTreeMap<String, String>> {
+        let art = self
+            .demo_art()
+            .and_then(DemoArt::try_from_str)
+            .unwrap_or(DemoArt::All);
+        demo::parse_art_params(art, &self.art.params.join(",")).map_err(ChromaCatError::InputError)
+    }
+
+    /// Creates pattern value post-processing settings from CLI arguments
+    pub fn create_postprocess(&self) -> PostProcess {
+        PostProcess {
+            levels: self.posterize,
+            dither: self.dither,
+            color: ColorAdjust {
+                brightness: self.brightness,
+                contrast: self.contrast,
+                saturation: self.saturation,
+                gamma: self.gamma,
+                min_contrast: self.min_contrast,
+                ..Default::default()
+            },
+            grain: Grain::new(self.grain.unwrap_or(0.0)),
+        }
+    }
+
+    /// Returns the parameters of the `--preset` for the base pattern, in
+    /// `--param` form
+    pub fn preset_params(&self) -> Result<Option<String>> {
+        let Some(name) = &self.preset else {
+            return Ok(None);
+        };
+        let pattern = self.base_pattern();
+        let preset = REGISTRY.find_preset(pattern, name).ok_or_else(|| {
+            let names: Vec<_> = REGISTRY
+                .get_pattern(pattern)
+                .map(|metadata| metadata.presets().iter().map(|p| p.name.as_str()).collect())
+                .unwrap_or_default();
+            let message = if names.is_empty() {
+                format!("Pattern '{}' has no presets", pattern)
+            } else {
+                format!("Unknown preset '{}'; {} has {}", name, pattern, names.join(", "))
+            };
+            ChromaCatError::PatternError {
+                pattern: pattern.to_string(),
+                param: "preset".to_string(),
+                message,
+            }
+        })?;
+        Ok(Some(preset.params_string()))
+    }
+
+    /// Creates progress indicator options from CLI arguments, if running the
+    /// `progress` subcommand
+    pub fn create_progress_options(&self) -> Option<ProgressOptions> {
+        match &self.command {
+            Some(Command::Progress {
+                total,
+                width,
+                label,
+                spinner,
+                fps,
+            }) => Some(ProgressOptions {
+                total: *total,
+                width: *width,
+                label: label.clone(),
+                spinner: *spinner,
+                fps: (*fps).clamp(1, 144),
+                colors_enabled: !self.no_color,
+                // Set from the terminal when the progress runs
+                color_support: ColorSupport::default(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the theme for the input file at `index`, cycling through
+    /// `--theme-per-file` when given
+    pub fn theme_for_file(&self, index: usize) -> Option<&str> {
+        if self.text.theme_per_file.is_empty() {
+            None
+        } else {
+            Some(&self.text.theme_per_file[index % self.text.theme_per_file.len()])
         }
     }
 
@@ -274,7 +1956,7 @@ impl Cli {
     pub fn validate(&self) -> Result<()> {
         // Skip validation if just listing options
         if self.list_available {
-            Self::print_available_options();
+            self.print_list()?;
             std::process::exit(0);
         }
 
@@ -285,10 +1967,10 @@ impl Cli {
         }
 
         // Validate animation parameters
-        if self.fps < 1 || self.fps > 144 {
+        if self.animation.fps < 1 || self.animation.fps > 144 {
             return Err(ChromaCatError::InvalidParameter {
                 name: "fps".to_string(),
-                value: self.fps as f64,
+                value: self.animation.fps as f64,
                 min: 1.0,
                 max: 144.0,
             });
@@ -304,20 +1986,36 @@ impl Cli {
             }
         }
 
+        self.preset_params()?;
+
         // Validate theme exists
         themes::get_theme(&self.theme)?;
+        if let Some(theme) = &self.light_theme {
+            themes::get_theme(theme)?;
+        }
+        for theme in self.text.theme_per_file.iter().chain(&self.column_themes) {
+            themes::get_theme(theme)?;
+        }
+        if let Some(theme) = &self.art.countdown_theme {
+            themes::get_theme(theme)?;
+        }
 
         // Validate common parameters
         self.validate_range("frequency", self.frequency, 0.1, 10.0)?;
         self.validate_range("amplitude", self.amplitude, 0.1, 2.0)?;
-        self.validate_range("speed", self.speed, 0.0, 1.0)?;
+        self.validate_range("speed", self.animation.speed, 0.0, 1.0)?;
 
         // Validate pattern exists and its parameters
+        if self.blend.is_some() && self.layer_patterns().is_empty() {
+            return Err(ChromaCatError::UsageError(
+                "--blend requires more than one pattern (-p base,layer)".to_string(),
+            ));
+        }
         if !self.params.is_empty() {
             let params_str = self.params.join(",");
-            REGISTRY.validate_params(&self.pattern, &params_str)
+            REGISTRY.validate_params(self.base_pattern(), &params_str)
                 .map_err(|e| ChromaCatError::PatternError {
-                    pattern: self.pattern.clone(),
+                    pattern: self.base_pattern().to_string(),
                     param: "params".to_string(),
                     message: e,
                 })?;
@@ -325,28 +2023,338 @@ impl Cli {
 
         // Validate aspect ratio
         self.validate_range("aspect-ratio", self.aspect_ratio, 0.1, 2.0)?;
+        let (min, max) = PATTERN_ZOOM_RANGE;
+        self.validate_range("pattern-zoom", self.pattern_zoom, min, max)?;
+
+        // Validate post-processing options
+        if let Some(levels) = self.posterize {
+            self.validate_range("posterize", levels as f64, 2.0, 64.0)?;
+        } else if self.dither != DitherMode::None {
+            return Err(ChromaCatError::UsageError(
+                "--dither requires --posterize".to_string(),
+            ));
+        }
+
+        let (min, max) = FACTOR_RANGE;
+        self.validate_range("brightness", self.brightness, min, max)?;
+        self.validate_range("saturation", self.saturation, min, max)?;
+        self.validate_range("contrast", self.contrast, min, max)?;
+        self.validate_range("gamma", self.gamma, GAMMA_RANGE.0, GAMMA_RANGE.1)?;
+        if let Some(ratio) = self.min_contrast {
+            self.validate_range("min-contrast", ratio, CONTRAST_RANGE.0, CONTRAST_RANGE.1)?;
+        }
+        if let Some(amount) = self.grain {
+            self.validate_range("grain", amount, GRAIN_RANGE.0, GRAIN_RANGE.1)?;
+        }
+
+        if let Some(ramp) = &self.char_ramp {
+            if ramp.is_empty() {
+                return Err(ChromaCatError::UsageError(
+                    "--char-ramp needs at least one character".to_string(),
+                ));
+            }
+            if ramp.chars().any(|c| unicode_width::UnicodeWidthChar::width(c) != Some(1)) {
+                return Err(ChromaCatError::UsageError(
+                    "--char-ramp characters must each be one column wide".to_string(),
+                ));
+            }
+            if self.resolution.is_subcell() {
+                return Err(ChromaCatError::UsageError(format!(
+                    "--char-ramp cannot be combined with --resolution {}",
+                    self.resolution
+                )));
+            }
+        }
+
+        // Effects paint blank cells, which ramps and sub-cell drawing already fill
+        if self.effect != TextEffect::None {
+            if self.char_ramp.is_some() {
+                return Err(ChromaCatError::UsageError(
+                    "--effect cannot be combined with --char-ramp".to_string(),
+                ));
+            }
+            if self.resolution.is_subcell() {
+                return Err(ChromaCatError::UsageError(format!(
+                    "--effect cannot be combined with --resolution {}",
+                    self.resolution
+                )));
+            }
+        }
 
         // Warn about demo mode overriding playlist
-        if self.demo && self.playlist.is_some() {
-            eprintln!("Warning: Demo mode is enabled, playlist will be ignored");
+        if self.demo && self.playlist.file.is_some() {
+            log::warn!("Demo mode is enabled, playlist will be ignored");
         }
 
         // Validate art selection if specified
-        if let Some(art) = &self.art {
+        if let Some(art) = &self.art.name {
             if !self.demo {
-                return Err(ChromaCatError::InputError(
+                return Err(ChromaCatError::UsageError(
                     "--art can only be used with --demo".to_string()
                 ));
             }
             
             if DemoArt::try_from_str(art).is_none() {
-                return Err(ChromaCatError::InputError(format!(
+                return Err(ChromaCatError::UsageError(format!(
                     "Invalid art type '{}'. Use --list-art to see available options.",
                     art
                 )));
             }
         }
 
+        if self.art.countdown.is_some() {
+            if !self.demo {
+                return Err(ChromaCatError::UsageError(
+                    "--countdown can only be used with --demo".to_string()
+                ));
+            }
+            if self.demo_art().and_then(DemoArt::try_from_str) != Some(DemoArt::Clock) {
+                return Err(ChromaCatError::UsageError(
+                    "--countdown is shown by the clock art; use --art clock".to_string()
+                ));
+            }
+        }
+        if self.art.countdown_theme.is_some() && self.art.countdown.is_none() {
+            return Err(ChromaCatError::UsageError(
+                "--countdown-theme requires --countdown".to_string()
+            ));
+        }
+
+        if !self.art.params.is_empty() {
+            if !self.demo {
+                return Err(ChromaCatError::UsageError(
+                    "--art-param can only be used with --demo".to_string()
+                ));
+            }
+            self.parse_art_params()?;
+        }
+
+        if let Some(scale) = self.animation.canvas {
+            self.validate_range("canvas", scale, 1.0, MAX_CANVAS_SCALE)?;
+        }
+        if self.animation.pan.is_some() && self.animation.canvas.is_none() {
+            return Err(ChromaCatError::UsageError(
+                "--pan requires --canvas".to_string()
+            ));
+        }
+        if (self.animation.canvas.is_some() || self.animation.drift)
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--canvas and --drift require --animate".to_string()
+            ));
+        }
+
+        if self.animation.layout.is_some()
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--layout requires --animate".to_string()
+            ));
+        }
+
+        if self.playlist.record_session.is_some()
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--record-session requires --animate".to_string()
+            ));
+        }
+
+        if self.playlist.favorites
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--favorites requires --animate".to_string()
+            ));
+        }
+
+        if self.playlist.random
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--random requires --animate".to_string()
+            ));
+        }
+
+        if self.theme_tag.is_some() && !self.playlist.random && !self.randomize {
+            return Err(ChromaCatError::UsageError(
+                "--theme-tag requires --list, --random, or --randomize".to_string(),
+            ));
+        }
+
+        if let Some(source) = self.playlist.adaptive {
+            if !source.is_available() {
+                return Err(ChromaCatError::UsageError(format!(
+                    "--adaptive={} is not available in this build; install with --features weather",
+                    source
+                )));
+            }
+            if self.playlist.location.is_some() && source != AdaptiveSource::Weather {
+                return Err(ChromaCatError::UsageError(
+                    "--location requires --adaptive=weather".to_string()
+                ));
+            }
+        }
+
+        if (self.animation.sync_leader.is_some() || self.animation.sync_follow.is_some())
+            && !self.animation.animate
+            && !matches!(self.command, Some(Command::Daemon { .. }))
+        {
+            return Err(ChromaCatError::UsageError(
+                "--sync-leader and --sync-follow require --animate".to_string()
+            ));
+        }
+
+        if self.animation.mqtt.is_some() {
+            if !cfg!(all(unix, feature = "mqtt")) {
+                return Err(ChromaCatError::UsageError(
+                    "--mqtt is not available in this build; install with --features mqtt".to_string()
+                ));
+            }
+            if !self.animation.animate && !matches!(self.command, Some(Command::Daemon { .. })) {
+                return Err(ChromaCatError::UsageError(
+                    "--mqtt requires --animate".to_string()
+                ));
+            }
+        }
+        #[cfg(unix)]
+        if let Some(url) = &self.animation.mqtt {
+            url.parse::<crate::mqtt::MqttBroker>()
+                .map_err(ChromaCatError::UsageError)?;
+        }
+
+        if self.output.is_some() && self.led_count.is_none() && self.led_map.is_none() {
+            return Err(ChromaCatError::UsageError(
+                "--output needs --led-count or --led-map".to_string()
+            ));
+        }
+
+        if self.status_line.is_some() {
+            if self.demo || self.screenshot.is_some() {
+                return Err(ChromaCatError::UsageError(
+                    "--status-line cannot be combined with --demo or --screenshot".to_string()
+                ));
+            }
+        } else if self.status_format != StatusFormat::default() {
+            return Err(ChromaCatError::UsageError(
+                "--status-format requires --status-line".to_string()
+            ));
+        }
+
+        if let Some(Command::Themes {
+            command: ThemesCommand::Preview { seconds: 0, .. },
+        }) = &self.command
+        {
+            return Err(ChromaCatError::UsageError(
+                "--seconds must be at least 1".to_string(),
+            ));
+        }
+
+        if let Some(Command::Progress { total, .. }) = &self.command {
+            if !(*total > 0.0 && total.is_finite()) {
+                return Err(ChromaCatError::InputError(format!(
+                    "--total must be a positive number, got {}",
+                    total
+                )));
+            }
+        }
+
+        if self.animation.mouse {
+            if !self.animation.animate {
+                return Err(ChromaCatError::UsageError(
+                    "--mouse requires --animate".to_string()
+                ));
+            }
+            if self.animation.inline {
+                return Err(ChromaCatError::UsageError(
+                    "--mouse cannot be combined with --inline".to_string()
+                ));
+            }
+        }
+
+        if self.animation.summary || self.animation.stats.is_some() {
+            if !self.animation.animate && !matches!(self.command, Some(Command::Daemon { .. })) {
+                return Err(ChromaCatError::UsageError(
+                    "--summary and --stats require --animate".to_string()
+                ));
+            }
+            if self.animation.inline {
+                return Err(ChromaCatError::UsageError(
+                    "--summary and --stats cannot be combined with --inline".to_string()
+                ));
+            }
+        }
+
+        if self.animation.inline {
+            if !self.animation.animate {
+                return Err(ChromaCatError::UsageError(
+                    "--inline requires --animate".to_string()
+                ));
+            }
+            if self.command.is_some()
+                || self.animation.layout.is_some()
+                || self.animation.sync_leader.is_some()
+                || self.animation.sync_follow.is_some()
+                || self.animation.mqtt.is_some()
+                || self.screenshot.is_some()
+                || self.status_line.is_some()
+            {
+                return Err(ChromaCatError::UsageError(
+                    "--inline cannot be combined with subcommands, --layout, --sync-leader, --sync-follow, --mqtt, --screenshot or --status-line".to_string()
+                ));
+            }
+        }
+
+        if self.time_offset != 0.0 {
+            if !self.time_offset.is_finite() {
+                return Err(ChromaCatError::UsageError(
+                    "--time-offset must be a finite number of seconds".to_string()
+                ));
+            }
+            if self.status_line.is_none()
+                && self.screenshot.is_none()
+                && !matches!(self.command, Some(Command::Motd { .. }))
+            {
+                return Err(ChromaCatError::UsageError(
+                    "--time-offset requires --status-line, --screenshot, or motd".to_string()
+                ));
+            }
+        }
+
+        if let Some(cycle) = self.animation.cycle {
+            if !cycle.is_finite() || cycle <= 0.0 || cycle > MAX_CYCLE_SECONDS {
+                return Err(ChromaCatError::UsageError(format!(
+                    "--cycle must be between 0 and {} seconds",
+                    MAX_CYCLE_SECONDS
+                )));
+            }
+        }
+
+        if let Some(path) = &self.art.quote_file {
+            if !self.demo {
+                return Err(ChromaCatError::UsageError(
+                    "--quote-file can only be used with --demo".to_string()
+                ));
+            }
+            if !path.exists() {
+                return Err(ChromaCatError::UsageError(format!(
+                    "Quote file not found: {}",
+                    path.display()
+                )));
+            }
+            if self.demo_art().and_then(DemoArt::try_from_str) != Some(DemoArt::Quote) {
+                return Err(ChromaCatError::UsageError(
+                    "--quote-file is shown by the quote art; use --art quote".to_string()
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -363,6 +2371,106 @@ impl Cli {
         Ok(())
     }
 
+    /// Returns the `--filter`, `--category`, and `--theme-tag` narrowing of
+    /// `--list`
+    pub fn list_filter(&self) -> ListFilter {
+        ListFilter {
+            search: self.list_filter.clone(),
+            category: self.list_category.clone(),
+            tag: self.theme_tag.clone(),
+        }
+    }
+
+    /// Prints the themes and patterns selected for `--list` in the chosen
+    /// format
+    pub fn print_list(&self) -> Result<()> {
+        let filter = self.list_filter();
+        match self.list_format {
+            ListFormat::Json => println!("{}", Catalog::collect(&filter)?.to_json()?),
+            ListFormat::Text if filter.is_empty() => Self::print_available_options(),
+            ListFormat::Text => Self::print_catalog(&Catalog::collect(&filter)?),
+        }
+        Ok(())
+    }
+
+    /// Prints the entries of a filtered catalog
+    fn print_catalog(catalog: &Catalog) {
+        if catalog.is_empty() {
+            println!("{}", CliFormat::general("No themes or patterns match"));
+            return;
+        }
+
+        if !catalog.patterns.is_empty() {
+            println!("\n{}", CliFormat::core("Matching Patterns:"));
+            println!("{}", CliFormat::separator(&"─".repeat(85)));
+            for pattern in &catalog.patterns {
+                println!("  {} {}",
+                    CliFormat::param(&format!("{:<12}", pattern.id)),
+                    CliFormat::description(&pattern.description)
+                );
+            }
+        }
+
+        if !catalog.themes.is_empty() {
+            println!("\n{}", CliFormat::core("🎨 Matching Themes"));
+            println!("{}", CliFormat::separator(&"─".repeat(85)));
+            let mut category = None;
+            for entry in &catalog.themes {
+                if category != Some(&entry.category) {
+                    println!("\n  {}", CliFormat::param(&entry.category));
+                    category = Some(&entry.category);
+                }
+                if let Ok(theme) = themes::get_theme(&entry.name) {
+                    let tags = match entry.tags.is_empty() {
+                        true => String::new(),
+                        false => format!(" [{}]", entry.tags.join(", ")),
+                    };
+                    println!(
+                        "    {} {} {}{}",
+                        CliFormat::param_value(&format!("{:<15}", entry.name)),
+                        Self::create_theme_preview(&theme),
+                        CliFormat::description(&entry.description),
+                        CliFormat::separator(&tags)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Prints the installed theme packs and their themes
+    fn print_theme_packs() -> Result<()> {
+        let dir = theme_packs::get_user_themes_dir();
+        let packs = theme_packs::list_packs(&dir)?;
+        if packs.is_empty() {
+            println!(
+                "{}",
+                CliFormat::general(
+                    "No theme packs installed; add one with chromacat themes install"
+                )
+            );
+            return Ok(());
+        }
+
+        println!("\n{}", CliFormat::core("🎨 Installed Theme Packs"));
+        println!("{}", CliFormat::separator(&"─".repeat(85)));
+        for pack in &packs {
+            println!(
+                "\n  {} {}",
+                CliFormat::param(&pack.name),
+                CliFormat::separator(&pack.path.display().to_string())
+            );
+            for theme in &pack.themes {
+                println!(
+                    "    {} {} {}",
+                    CliFormat::param_value(&format!("{:<15}", theme.name)),
+                    Self::create_theme_preview(theme),
+                    CliFormat::description(&theme.desc)
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Prints available themes and patterns
     pub fn print_available_options() {
         // Title and introduction
@@ -373,7 +2481,12 @@ impl Cli {
             It supports various patterns, themes, and animated effects to make your terminal more colorful."
         ));
 
-        // Patterns section
+        Self::print_patterns();
+        Self::print_themes();
+        Self::print_usage_examples();
+    }
+
+    fn print_patterns() {
         println!("\n{}", CliFormat::core("Available Patterns:"));
         println!("{}", CliFormat::separator(&"─".repeat(85)));
         
@@ -387,9 +2500,6 @@ impl Cli {
         }
 
         println!("\n{}", CliFormat::general("Use --pattern-help for detailed pattern parameters"));
-
-        Self::print_themes();
-        Self::print_usage_examples();
     }
 
     pub fn print_pattern_help() {
@@ -398,7 +2508,9 @@ impl Cli {
         println!("{}", CliFormat::separator(&"═".repeat(90)));
         println!("\n{}", CliFormat::highlight_description(
             "Each pattern supports specific parameters that can be customized using the --param flag. \
-            Multiple parameters can be specified using comma separation: --param key1=value1,key2=value2"
+            Multiple parameters can be specified using comma separation: --param key1=value1,key2=value2. \
+            Every pattern also takes correct_aspect=true|false and aspect_ratio=0.1-2.0, which replace \
+            --no-aspect-correction and --aspect-ratio for that pattern"
         ));
 
         for pattern_id in REGISTRY.list_patterns() {
@@ -436,6 +2548,18 @@ impl Cli {
                     }
                 }
 
+                // Presets
+                let presets = metadata.presets();
+                if !presets.is_empty() {
+                    println!("\n  {}", CliFormat::param("Presets:"));
+                    for preset in presets {
+                        println!("    {} {}",
+                            CliFormat::param_value(&preset.name.pad_to_width(18)),
+                            CliFormat::description(&preset.description)
+                        );
+                    }
+                }
+
                 // Example usage
                 println!("\n  {} {}",
                     CliFormat::param("Example:"),
@@ -542,6 +2666,15 @@ impl Cli {
                 CliFormat::param_value(art.display_name()),
                 CliFormat::description(art.description())
             );
+            for param in art.params() {
+                println!("  {} {} {} - {} (default {})",
+                    " ".repeat(12),
+                    CliFormat::param(&format!("{}=", param.name).pad_to_width(12)),
+                    CliFormat::param_value(&param.range().pad_to_width(36)),
+                    CliFormat::description(param.description),
+                    param.default
+                );
+            }
         }
 
         println!("\n{}", CliFormat::param("Special Values:"));
@@ -560,6 +2693,10 @@ impl Cli {
             CliFormat::param("Specific art:"),
             CliFormat::description("chromacat --demo --art matrix")
         );
+        println!("  {} {}", 
+            CliFormat::param("Art parameters:"),
+            CliFormat::description("chromacat --demo --art matrix --art-param density=0.4,speed=2")
+        );
         println!("  {} {}", 
             CliFormat::param("With playlist:"),
             CliFormat::description("chromacat --demo --playlist my-playlist.yaml")
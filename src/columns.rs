@@ -0,0 +1,354 @@
+//! Column-aware coloring
+//!
+//! With `--columns`, tabular input such as CSV files or the output of
+//! `ps aux` is colored a column at a time: each column takes its own slice
+//! of the theme's gradient, or its own theme from `--column-themes`, so a
+//! field is easy to follow down the table. Only the colors change; the text,
+//! including the padding that lines columns up, passes through untouched.
+//!
+//! The delimiter is detected from the first lines unless one is given: a
+//! tab, comma, semicolon, or pipe splits fields if it appears the same
+//! number of times on every sampled line, and runs of spaces split them
+//! otherwise. Delimiters inside double quotes don't split fields. The first
+//! line sets how many columns there are, and any fields past that belong to
+//! the last column, so free-form text such as the COMMAND column of
+//! `ps aux` stays in one piece.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::error::Result;
+use crate::escape::{self, strip_sgr};
+use crate::pattern::{ColorAdjust, Palette};
+use crate::themes::ThemeDefinition;
+
+/// Lines sampled when detecting the delimiter
+const DETECT_LINES: usize = 20;
+
+/// What separates the fields of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnDelimiter {
+    /// Detect the delimiter from the input
+    #[default]
+    Auto,
+    /// Tab-separated values
+    Tab,
+    /// Comma-separated values
+    Comma,
+    /// Semicolon-separated values
+    Semicolon,
+    /// Pipe-separated values, as in Markdown tables
+    Pipe,
+    /// Runs of spaces, as in aligned command output
+    Space,
+}
+
+impl ColumnDelimiter {
+    /// Returns all delimiters
+    pub fn all() -> &'static [ColumnDelimiter] {
+        &[
+            ColumnDelimiter::Auto,
+            ColumnDelimiter::Tab,
+            ColumnDelimiter::Comma,
+            ColumnDelimiter::Semicolon,
+            ColumnDelimiter::Pipe,
+            ColumnDelimiter::Space,
+        ]
+    }
+
+    /// Returns the name used on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnDelimiter::Auto => "auto",
+            ColumnDelimiter::Tab => "tab",
+            ColumnDelimiter::Comma => "comma",
+            ColumnDelimiter::Semicolon => "semicolon",
+            ColumnDelimiter::Pipe => "pipe",
+            ColumnDelimiter::Space => "space",
+        }
+    }
+
+    /// Returns the character that separates fields, or None for runs of
+    /// spaces and detection
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            ColumnDelimiter::Tab => Some('\t'),
+            ColumnDelimiter::Comma => Some(','),
+            ColumnDelimiter::Semicolon => Some(';'),
+            ColumnDelimiter::Pipe => Some('|'),
+            ColumnDelimiter::Auto | ColumnDelimiter::Space => None,
+        }
+    }
+
+    /// Picks the delimiter of a text from its first lines, ignoring colors
+    pub fn detect(text: &str) -> ColumnDelimiter {
+        let lines: Vec<String> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(DETECT_LINES)
+            .map(strip_sgr)
+            .collect();
+        if lines.is_empty() {
+            return ColumnDelimiter::Space;
+        }
+
+        const CANDIDATES: &[ColumnDelimiter] = &[
+            ColumnDelimiter::Tab,
+            ColumnDelimiter::Comma,
+            ColumnDelimiter::Semicolon,
+            ColumnDelimiter::Pipe,
+        ];
+        CANDIDATES
+            .iter()
+            .copied()
+            .find(|&delimiter| {
+                let first = count_fields(&lines[0], delimiter);
+                first > 1
+                    && lines[1..]
+                        .iter()
+                        .all(|line| count_fields(line, delimiter) == first)
+            })
+            .unwrap_or(ColumnDelimiter::Space)
+    }
+}
+
+impl fmt::Display for ColumnDelimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ColumnDelimiter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ColumnDelimiter::all()
+            .iter()
+            .copied()
+            .find(|delimiter| delimiter.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                let valid: Vec<_> = ColumnDelimiter::all().iter().map(|d| d.as_str()).collect();
+                format!(
+                    "Invalid column delimiter '{}'. Valid delimiters: {}",
+                    s,
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
+/// Counts the fields of a line
+fn count_fields(line: &str, delimiter: ColumnDelimiter) -> usize {
+    let mut tracker = ColumnTracker::new(delimiter, usize::MAX);
+    let last = line.graphemes(true).map(|g| tracker.next(g)).max();
+    // A line of only spaces has no fields
+    match last {
+        Some(column) if tracker.seen_field || delimiter.as_char().is_some() => column + 1,
+        _ => 0,
+    }
+}
+
+/// Returns the text of field `index` of a line split by `delimiter`,
+/// without the delimiter, surrounding spaces, or quotes, or None if the line
+/// has fewer fields
+pub fn field(line: &str, delimiter: ColumnDelimiter, index: usize) -> Option<String> {
+    let mut tracker = ColumnTracker::new(delimiter, usize::MAX);
+    let mut text = String::new();
+    let mut found = false;
+    for grapheme in line.graphemes(true) {
+        let column = tracker.next(grapheme);
+        if column > index {
+            break;
+        }
+        if column == index {
+            found = true;
+            text.push_str(grapheme);
+        }
+    }
+    if !found {
+        return None;
+    }
+    let separators: &[char] = match delimiter.as_char() {
+        Some(delimiter) => &[delimiter, '"'],
+        None => &['"'],
+    };
+    Some(
+        text.trim()
+            .trim_end_matches(separators)
+            .trim()
+            .trim_matches('"')
+            .to_string(),
+    )
+}
+
+/// Follows which column each grapheme of a line belongs to
+#[derive(Debug, Clone)]
+pub struct ColumnTracker {
+    /// Delimiter, with detection already done
+    delimiter: ColumnDelimiter,
+    /// Highest column a grapheme can belong to
+    last: usize,
+    /// Column of the current field
+    column: usize,
+    /// Whether the tracker is inside a quoted field
+    in_quotes: bool,
+    /// Whether a field has started on the line, when splitting on spaces
+    seen_field: bool,
+    /// Whether spaces have followed the current field
+    in_gap: bool,
+}
+
+impl ColumnTracker {
+    /// Creates a tracker for a line split by `delimiter` into at most
+    /// `columns` columns
+    pub fn new(delimiter: ColumnDelimiter, columns: usize) -> Self {
+        Self {
+            delimiter,
+            last: columns.saturating_sub(1),
+            column: 0,
+            in_quotes: false,
+            seen_field: false,
+            in_gap: false,
+        }
+    }
+
+    /// Returns the column of the next grapheme. Delimiters and the spaces
+    /// after a field belong to the field before them.
+    pub fn next(&mut self, grapheme: &str) -> usize {
+        let column = self.column;
+        match self.delimiter.as_char() {
+            Some(delimiter) => {
+                if grapheme == "\"" {
+                    self.in_quotes = !self.in_quotes;
+                } else if !self.in_quotes
+                    && grapheme.starts_with(delimiter)
+                    && self.column < self.last
+                {
+                    self.column += 1;
+                }
+                column
+            }
+            None => {
+                if grapheme.chars().all(char::is_whitespace) {
+                    self.in_gap = self.seen_field;
+                } else {
+                    if self.in_gap && self.column < self.last {
+                        self.column += 1;
+                    }
+                    self.in_gap = false;
+                    self.seen_field = true;
+                }
+                self.column
+            }
+        }
+    }
+}
+
+/// How columns are found and colored, before the input is known
+#[derive(Debug, Clone)]
+pub struct Columns {
+    /// Delimiter to split on, or Auto to detect it
+    delimiter: ColumnDelimiter,
+    /// Palettes of the themes the columns cycle through, if any
+    themes: Vec<Arc<Palette>>,
+    /// Palette drawing with the engine's theme, narrowed for each column
+    /// when there are no themes
+    slice: Palette,
+}
+
+impl Columns {
+    /// Creates column coloring that splits on `delimiter` and cycles through
+    /// `themes`, or slices the main theme when there are none
+    pub fn new(delimiter: ColumnDelimiter, themes: &[ThemeDefinition]) -> Result<Self> {
+        let themes = themes
+            .iter()
+            .map(|theme| Palette::new(Some(theme), ColorAdjust::default()).map(Arc::new))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            delimiter,
+            themes,
+            slice: Palette::new(None, ColorAdjust::default())?,
+        })
+    }
+
+    /// Returns the delimiter, which may be Auto
+    pub fn delimiter(&self) -> ColumnDelimiter {
+        self.delimiter
+    }
+
+    /// Finds the columns of a text from its first lines and builds the
+    /// palette of each
+    pub fn palettes_for(&self, text: &str) -> ColumnPalettes {
+        let delimiter = match self.delimiter {
+            ColumnDelimiter::Auto => ColumnDelimiter::detect(text),
+            delimiter => delimiter,
+        };
+        let count = text
+            .lines()
+            .map(strip_sgr)
+            .map(|line| count_fields(&line, delimiter))
+            .find(|&count| count > 0)
+            .unwrap_or(1);
+
+        let palettes = (0..count)
+            .map(|i| {
+                if self.themes.is_empty() {
+                    let (start, end) = (i as f64 / count as f64, (i + 1) as f64 / count as f64);
+                    Arc::new(self.slice.clone().with_range(start, end))
+                } else {
+                    self.themes[i % self.themes.len()].clone()
+                }
+            })
+            .collect();
+        ColumnPalettes {
+            delimiter,
+            palettes,
+        }
+    }
+}
+
+/// Palettes of the columns found in a text
+#[derive(Debug, Clone)]
+pub struct ColumnPalettes {
+    /// Delimiter the text is split on
+    delimiter: ColumnDelimiter,
+    /// Palette of each column
+    palettes: Vec<Arc<Palette>>,
+}
+
+impl ColumnPalettes {
+    /// Returns the delimiter the text is split on
+    pub fn delimiter(&self) -> ColumnDelimiter {
+        self.delimiter
+    }
+
+    /// Returns the number of columns
+    pub fn count(&self) -> usize {
+        self.palettes.len()
+    }
+
+    /// Returns the palette of a column
+    pub fn get(&self, column: usize) -> Option<&Arc<Palette>> {
+        self.palettes.get(column)
+    }
+
+    /// Creates a tracker for the next line
+    pub fn tracker(&self) -> ColumnTracker {
+        ColumnTracker::new(self.delimiter, self.count())
+    }
+
+    /// Returns the column of every visible grapheme of a line, skipping
+    /// escape sequences
+    pub fn columns_of(&self, line: &str) -> Vec<usize> {
+        let mut tracker = self.tracker();
+        let mut columns = Vec::new();
+        for token in escape::tokenize(line) {
+            if let escape::Token::Text(text) = token {
+                columns.extend(text.graphemes(true).map(|g| tracker.next(g)));
+            }
+        }
+        columns
+    }
+}
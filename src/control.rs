@@ -0,0 +1,311 @@
+//! Control socket for `chromacat daemon`
+//!
+//! The daemon listens on a unix socket and applies commands sent by
+//! `chromacat ctl` (or any script that can write to a socket) to the running
+//! renderer, so an always-on display can be driven without restarting it.
+//!
+//! The protocol is line based: each request is one command line such as
+//! `set-theme ocean`, and each reply is one line, either `ok`, `ok <message>`
+//! or `error: <message>`. A connection may send several requests.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, warn};
+
+use crate::error::{ChromaCatError, Result};
+
+/// How long a connection waits for the renderer to answer a request
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A command understood by the daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Switch to a theme by name
+    SetTheme(String),
+    /// Switch to a pattern by id
+    SetPattern(String),
+    /// Switch to the next theme
+    NextTheme,
+    /// Switch to the next pattern
+    NextPattern,
+    /// Advance to the next playlist scene
+    NextScene,
+    /// Go back to the previous playlist scene
+    PrevScene,
+    /// Replace the playlist with one loaded from a file
+    LoadPlaylist(PathBuf),
+    /// Pause the animation
+    Pause,
+    /// Resume the animation
+    Resume,
+    /// Report the current pattern, theme and scene
+    Status,
+    /// Stop the daemon
+    Quit,
+}
+
+impl ControlCommand {
+    /// Returns the names and usage of all commands for help output
+    pub fn usage() -> &'static [(&'static str, &'static str)] {
+        &[
+            ("set-theme NAME", "Switch to a theme"),
+            ("set-pattern ID", "Switch to a pattern"),
+            ("next-theme", "Switch to the next theme"),
+            ("next-pattern", "Switch to the next pattern"),
+            ("next-scene", "Advance to the next playlist scene"),
+            ("prev-scene", "Go back to the previous playlist scene"),
+            ("load-playlist FILE", "Replace the playlist"),
+            ("pause", "Pause the animation"),
+            ("resume", "Resume the animation"),
+            ("status", "Show the current pattern, theme and scene"),
+            ("quit", "Stop the daemon"),
+        ]
+    }
+
+    /// Parses a command from command line words, e.g. `["set-theme", "ocean"]`
+    pub fn from_args<S: AsRef<str>>(args: &[S]) -> std::result::Result<Self, String> {
+        let line = args.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+        line.parse()
+    }
+
+    /// Resolves a relative playlist path against the current directory, since
+    /// the daemon may run elsewhere
+    pub fn absolutize(self) -> Self {
+        match self {
+            ControlCommand::LoadPlaylist(path) if path.is_relative() => {
+                let path = std::env::current_dir().map(|dir| dir.join(&path)).unwrap_or(path);
+                ControlCommand::LoadPlaylist(path)
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlCommand::SetTheme(name) => write!(f, "set-theme {}", name),
+            ControlCommand::SetPattern(id) => write!(f, "set-pattern {}", id),
+            ControlCommand::NextTheme => write!(f, "next-theme"),
+            ControlCommand::NextPattern => write!(f, "next-pattern"),
+            ControlCommand::NextScene => write!(f, "next-scene"),
+            ControlCommand::PrevScene => write!(f, "prev-scene"),
+            ControlCommand::LoadPlaylist(path) => write!(f, "load-playlist {}", path.display()),
+            ControlCommand::Pause => write!(f, "pause"),
+            ControlCommand::Resume => write!(f, "resume"),
+            ControlCommand::Status => write!(f, "status"),
+            ControlCommand::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for ControlCommand {
+    type Err = String;
+
+    /// Parses one request line; the argument is the rest of the line, so
+    /// playlist paths may contain spaces
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, arg) = match s.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (s, ""),
+        };
+
+        let command = match name {
+            "set-theme" => ControlCommand::SetTheme(arg.to_string()),
+            "set-pattern" => ControlCommand::SetPattern(arg.to_string()),
+            "load-playlist" => ControlCommand::LoadPlaylist(arg.into()),
+            "next-theme" => ControlCommand::NextTheme,
+            "next-pattern" => ControlCommand::NextPattern,
+            "next-scene" => ControlCommand::NextScene,
+            "prev-scene" => ControlCommand::PrevScene,
+            "pause" => ControlCommand::Pause,
+            "resume" => ControlCommand::Resume,
+            "status" => ControlCommand::Status,
+            "quit" => ControlCommand::Quit,
+            "" => return Err("Empty command".to_string()),
+            _ => {
+                let names: Vec<&str> = Self::usage()
+                    .iter()
+                    .filter_map(|(usage, _)| usage.split_whitespace().next())
+                    .collect();
+                return Err(format!(
+                    "Unknown command '{}'. Available: {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+        };
+
+        let takes_arg = matches!(
+            command,
+            ControlCommand::SetTheme(_) | ControlCommand::SetPattern(_) | ControlCommand::LoadPlaylist(_)
+        );
+        match (takes_arg, arg.is_empty()) {
+            (true, true) => Err(format!("{} needs an argument", name)),
+            (false, false) => Err(format!("{} takes no argument", name)),
+            _ => Ok(command),
+        }
+    }
+}
+
+/// A command received by the server, waiting for the renderer's answer
+pub struct ControlRequest {
+    /// Command to apply
+    pub command: ControlCommand,
+    /// Channel back to the connection that sent the command
+    reply: Sender<std::result::Result<String, String>>,
+}
+
+impl ControlRequest {
+    /// Answers the request with a message on success or an error
+    pub fn reply(self, result: std::result::Result<String, String>) {
+        // The client may have disconnected; nothing to do then
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Listens on the control socket and queues requests for the render loop
+pub struct ControlServer {
+    /// Socket path, removed when the server is dropped
+    path: PathBuf,
+    /// Requests from all connections, in arrival order
+    requests: Receiver<ControlRequest>,
+}
+
+impl ControlServer {
+    /// Binds the socket and starts accepting connections in the background.
+    ///
+    /// A stale socket left by a daemon that exited uncleanly is replaced, but
+    /// binding fails if another daemon is still answering on the path.
+    pub fn bind(path: &Path) -> Result<Self> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(ChromaCatError::Other(format!(
+                    "A daemon is already listening on {}",
+                    path.display()
+                )));
+            }
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        // Only the owner may drive the display
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let sender = sender.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = serve_connection(stream, sender) {
+                                debug!("Control connection ended: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Control socket accept failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+        })
+    }
+
+    /// Returns the socket path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the next pending request without blocking
+    pub fn try_recv(&self) -> Option<ControlRequest> {
+        self.requests.try_recv().ok()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Reads request lines from one connection and writes back the replies
+fn serve_connection(stream: UnixStream, sender: Sender<ControlRequest>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = match line.parse::<ControlCommand>() {
+            Ok(command) => {
+                let (reply, answer) = mpsc::channel();
+                if sender.send(ControlRequest { command, reply }).is_err() {
+                    Err("The daemon is shutting down".to_string())
+                } else {
+                    answer
+                        .recv_timeout(REPLY_TIMEOUT)
+                        .unwrap_or_else(|_| Err("The daemon did not answer".to_string()))
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(message) if message.is_empty() => writeln!(writer, "ok")?,
+            Ok(message) => writeln!(writer, "ok {}", message)?,
+            Err(message) => writeln!(writer, "error: {}", message)?,
+        }
+    }
+    Ok(())
+}
+
+/// Returns the default socket path: `$XDG_RUNTIME_DIR/chromacat.sock`, or a
+/// per-user file in the temporary directory
+pub fn default_socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("chromacat.sock"),
+        _ => {
+            let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+            std::env::temp_dir().join(format!("chromacat-{}.sock", user))
+        }
+    }
+}
+
+/// Sends one command to a daemon and returns its reply message
+pub fn send_command(path: &Path, command: &ControlCommand) -> Result<String> {
+    let mut stream = UnixStream::connect(path).map_err(|e| {
+        ChromaCatError::Other(format!(
+            "Cannot reach a daemon at {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    writeln!(stream, "{}", command)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    let reply = reply.trim_end();
+
+    if let Some(message) = reply.strip_prefix("error: ") {
+        Err(ChromaCatError::Other(message.to_string()))
+    } else if let Some(message) = reply.strip_prefix("ok") {
+        Ok(message.trim_start().to_string())
+    } else {
+        Err(ChromaCatError::Other(format!("Unexpected reply from daemon: '{}'", reply)))
+    }
+}
@@ -0,0 +1,144 @@
+//! Data-driven coloring
+//!
+//! With `--data-column N`, the number in field N of each line picks the
+//! line's color instead of the pattern, turning logs and metrics into a
+//! heatmap: the lowest value takes one end of the gradient and the highest
+//! the other. Fields are split like `--columns` splits them, with the
+//! delimiter detected from the first lines. A unit after the number is
+//! ignored, so `45ms` reads as 45 and `12.5%` as 12.5, and lines without
+//! a number in the field keep the pattern's colors.
+//!
+//! Values are scaled to the range given with `--data-range`, or else to the
+//! lowest and highest values seen. A whole text is scanned before it is
+//! colored; streamed input widens the range as new values arrive.
+
+use crate::columns::{self, ColumnDelimiter};
+use crate::escape::strip_sgr;
+
+/// Parses a `MIN,MAX` data range
+pub fn parse_data_range(s: &str) -> Result<(f64, f64), String> {
+    let (min, max) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Data range '{}' must be in format MIN,MAX", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| format!("Data range bound '{}' must be a number", v.trim()))
+    };
+    let (min, max) = (parse(min)?, parse(max)?);
+    if min >= max {
+        return Err(format!(
+            "Data range minimum {} must be below its maximum {}",
+            min, max
+        ));
+    }
+    Ok((min, max))
+}
+
+/// Reads the leading number of a field, ignoring a unit after it
+pub fn parse_number(field: &str) -> Option<f64> {
+    let field = field.trim();
+    let end = field
+        .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+        .unwrap_or(field.len());
+    (1..=end)
+        .rev()
+        .find_map(|i| field[..i].parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+}
+
+/// Which field drives the colors, before the input is known
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataColumn {
+    /// Index of the field, from 0
+    index: usize,
+    /// Values mapped to the ends of the gradient, if fixed
+    range: Option<(f64, f64)>,
+}
+
+impl DataColumn {
+    /// Creates data coloring from field `column`, counted from 1, scaled to
+    /// `range` or to the values found
+    pub fn new(column: usize, range: Option<(f64, f64)>) -> Self {
+        Self {
+            index: column.saturating_sub(1),
+            range,
+        }
+    }
+
+    /// Returns the field's number, counted from 1
+    pub fn column(&self) -> usize {
+        self.index + 1
+    }
+
+    /// Returns the fixed range, if any
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+
+    /// Detects the delimiter of a text and, without a fixed range, scales
+    /// to the values in it
+    pub fn reader_for(&self, text: &str) -> DataReader {
+        let mut reader = DataReader {
+            index: self.index,
+            delimiter: ColumnDelimiter::detect(text),
+            scale: self.range,
+            fixed: self.range.is_some(),
+        };
+        for line in text.lines() {
+            reader.value(line);
+        }
+        reader
+    }
+}
+
+/// Reads the values of lines, with the delimiter and scale settled
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataReader {
+    /// Index of the field, from 0
+    index: usize,
+    /// Delimiter the lines are split on
+    delimiter: ColumnDelimiter,
+    /// Lowest and highest values, once there are any
+    scale: Option<(f64, f64)>,
+    /// Whether the scale was given rather than found
+    fixed: bool,
+}
+
+impl DataReader {
+    /// Returns the delimiter the lines are split on
+    pub fn delimiter(&self) -> ColumnDelimiter {
+        self.delimiter
+    }
+
+    /// Returns the values mapped to the ends of the gradient so far
+    pub fn scale(&self) -> Option<(f64, f64)> {
+        self.scale
+    }
+
+    /// Returns the raw number in a line's field, ignoring colors
+    pub fn number(&self, line: &str) -> Option<f64> {
+        let line = strip_sgr(line);
+        parse_number(&columns::field(&line, self.delimiter, self.index)?)
+    }
+
+    /// Returns where a line's number falls in the scale, from 0 to 1,
+    /// widening a scale that wasn't given to take it in. A scale that is
+    /// still a single value puts it in the middle.
+    pub fn value(&mut self, line: &str) -> Option<f64> {
+        let number = self.number(line)?;
+        let (min, max) = match self.scale {
+            Some((min, max)) if self.fixed => (min, max),
+            Some((min, max)) => (min.min(number), max.max(number)),
+            None => (number, number),
+        };
+        self.scale = Some((min, max));
+        if max > min {
+            Some(((number - min) / (max - min)).clamp(0.0, 1.0))
+        } else {
+            Some(0.5)
+        }
+    }
+}
@@ -4,8 +4,13 @@
 //! demo art system. It defines the available art patterns and handles their
 //! generation with configurable parameters.
 
+use super::params::ArtParam;
+use super::quote::Quote;
+use crate::pattern::ParamType;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// Available demo art patterns.
 ///
@@ -42,6 +47,14 @@ pub enum DemoArt {
     Mandala,
     /// Cityscape with sky and moon
     Cityscape,
+    /// Aquarium with swimming fish
+    Aquarium,
+    /// Large block-digit clock or countdown
+    Clock,
+    /// Live CPU and memory dashboard
+    Sysmon,
+    /// Centered quote with an optional border
+    Quote,
     /// All demo patterns in sequence
     All,
 }
@@ -55,7 +68,7 @@ impl DemoArt {
         use DemoArt::*;
         &[
             Logo, Matrix, Waves, Spiral, Code, Ascii, Boxes, Plasma, Vortex, Cells, Fluid, Maze,
-            Mandala, Cityscape,
+            Mandala, Cityscape, Aquarium, Clock, Sysmon, Quote,
         ]
     }
 
@@ -77,6 +90,10 @@ impl DemoArt {
             Maze => "maze",
             Mandala => "mandala",
             Cityscape => "cityscape",
+            Aquarium => "aquarium",
+            Clock => "clock",
+            Sysmon => "sysmon",
+            Quote => "quote",
             All => "all",
         }
     }
@@ -99,6 +116,10 @@ impl DemoArt {
             Maze => "Intricate Maze",
             Mandala => "Mandala Pattern",
             Cityscape => "Night Cityscape",
+            Aquarium => "ASCII Aquarium",
+            Clock => "Desk Clock",
+            Sysmon => "System Monitor",
+            Quote => "Quote of the Moment",
             All => "All Patterns",
         }
     }
@@ -120,11 +141,47 @@ impl DemoArt {
             Fluid => "Fluid simulation with dynamic motion",
             Maze => "Intricate maze pattern with box-drawing characters",
             Mandala => "Symmetrical mandala pattern",
-            Cityscape => "Multi-layered cityscape with night sky and moon",
+            Cityscape => "Night skyline with lit windows, twinkling stars, and optional rain",
+            Aquarium => "Fish swimming at different depths among bubbles and seaweed",
+            Clock => "Current time in large block digits, or a countdown with --countdown",
+            Sysmon => "Live per-core CPU bars, memory usage, and a scrolling load sparkline",
+            Quote => "A random quote, word-wrapped and framed (use --quote-file for your own)",
             All => "All available demo patterns in sequence",
         }
     }
 
+    /// Whether the art changes over time when generated with
+    /// [`DemoArtGenerator::generate_at`](super::DemoArtGenerator::generate_at).
+    pub fn is_animated(&self) -> bool {
+        matches!(
+            self,
+            DemoArt::Matrix
+                | DemoArt::Waves
+                | DemoArt::Spiral
+                | DemoArt::Plasma
+                | DemoArt::Vortex
+                | DemoArt::Cells
+                | DemoArt::Fluid
+                | DemoArt::Mandala
+                | DemoArt::Cityscape
+                | DemoArt::Aquarium
+                | DemoArt::Clock
+                | DemoArt::Sysmon
+        )
+    }
+
+    /// Get the configurable parameters of this art pattern.
+    pub fn params(&self) -> &'static [ArtParam] {
+        match self {
+            DemoArt::Matrix => MATRIX_PARAMS,
+            DemoArt::Spiral => SPIRAL_PARAMS,
+            DemoArt::Mandala => MANDALA_PARAMS,
+            DemoArt::Cityscape => CITYSCAPE_PARAMS,
+            DemoArt::Quote => QUOTE_PARAMS,
+            _ => &[],
+        }
+    }
+
     // Add a try_from_str method to maintain backward compatibility
     pub fn try_from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
@@ -142,6 +199,10 @@ impl DemoArt {
             "maze" => Some(Self::Maze),
             "mandala" => Some(Self::Mandala),
             "cityscape" => Some(Self::Cityscape),
+            "aquarium" => Some(Self::Aquarium),
+            "clock" => Some(Self::Clock),
+            "sysmon" => Some(Self::Sysmon),
+            "quote" => Some(Self::Quote),
             "all" => Some(Self::All),
             _ => None,
         }
@@ -190,6 +251,10 @@ impl FromStr for DemoArt {
             "maze" => Ok(Self::Maze),
             "mandala" => Ok(Self::Mandala),
             "cityscape" => Ok(Self::Cityscape),
+            "aquarium" => Ok(Self::Aquarium),
+            "clock" => Ok(Self::Clock),
+            "sysmon" => Ok(Self::Sysmon),
+            "quote" => Ok(Self::Quote),
             "all" => Ok(Self::All),
             _ => Err(format!("Invalid art type: {}", s)),
         }
@@ -202,6 +267,59 @@ impl std::fmt::Display for DemoArt {
     }
 }
 
+const MATRIX_PARAMS: &[ArtParam] = &[
+    ArtParam {
+        name: "density",
+        description: "Fraction of columns with falling digits",
+        param_type: ParamType::Number { min: 0.05, max: 1.0 },
+        default: "1.0",
+    },
+    ArtParam {
+        name: "speed",
+        description: "Falling speed multiplier",
+        param_type: ParamType::Number { min: 0.1, max: 5.0 },
+        default: "1.0",
+    },
+];
+
+const SPIRAL_PARAMS: &[ArtParam] = &[ArtParam {
+    name: "arms",
+    description: "Number of spiral arms",
+    param_type: ParamType::Number { min: 1.0, max: 12.0 },
+    default: "1",
+}];
+
+const MANDALA_PARAMS: &[ArtParam] = &[ArtParam {
+    name: "symmetry",
+    description: "Number of symmetric petals",
+    param_type: ParamType::Number { min: 2.0, max: 24.0 },
+    default: "6",
+}];
+
+const CITYSCAPE_PARAMS: &[ArtParam] = &[ArtParam {
+    name: "rain",
+    description: "Show falling rain",
+    param_type: ParamType::Boolean,
+    default: "false",
+}];
+
+const QUOTE_PARAMS: &[ArtParam] = &[
+    ArtParam {
+        name: "border",
+        description: "Border drawn around the quote",
+        param_type: ParamType::Enum {
+            options: &["rounded", "heavy", "double", "ascii", "none"],
+        },
+        default: "rounded",
+    },
+    ArtParam {
+        name: "padding",
+        description: "Columns of space between the border and the text",
+        param_type: ParamType::Number { min: 0.0, max: 10.0 },
+        default: "3",
+    },
+];
+
 /// Generator settings for demo art patterns
 #[derive(Debug, Clone)]
 pub struct ArtSettings {
@@ -213,6 +331,12 @@ pub struct ArtSettings {
     pub include_headers: bool,
     /// Random seed for consistent generation
     pub seed: u64,
+    /// Countdown length shown by the clock art instead of the time of day
+    pub countdown: Option<Duration>,
+    /// Quotes for the quote art; the embedded collection is used when empty
+    pub quotes: Vec<Quote>,
+    /// Art parameter values by name, validated against the art's metadata
+    pub params: BTreeMap<String, String>,
 }
 
 impl Default for ArtSettings {
@@ -222,6 +346,9 @@ impl Default for ArtSettings {
             height: 24,
             include_headers: true,
             seed: 42,
+            countdown: None,
+            quotes: Vec::new(),
+            params: BTreeMap::new(),
         }
     }
 }
@@ -236,6 +363,13 @@ impl ArtSettings {
         }
     }
 
+    /// Set the output dimensions, keeping the same minimums as [`new`](Self::new).
+    pub fn with_size(mut self, width: u16, height: u16) -> Self {
+        self.width = width.max(40);
+        self.height = height.max(10);
+        self
+    }
+
     /// Set whether to include section headers.
     pub fn with_headers(mut self, include_headers: bool) -> Self {
         self.include_headers = include_headers;
@@ -247,4 +381,57 @@ impl ArtSettings {
         self.seed = seed;
         self
     }
+
+    /// Set whether arts that support weather show falling rain.
+    pub fn with_rain(self, rain: bool) -> Self {
+        self.with_param("rain", rain.to_string())
+    }
+
+    /// Set a countdown for the clock art to show instead of the time of day.
+    pub fn with_countdown(mut self, countdown: Option<Duration>) -> Self {
+        self.countdown = countdown;
+        self
+    }
+
+    /// Set the quotes the quote art picks from.
+    pub fn with_quotes(mut self, quotes: Vec<Quote>) -> Self {
+        self.quotes = quotes;
+        self
+    }
+
+    /// Set whether the quote art draws a border.
+    pub fn with_quote_border(self, border: bool) -> Self {
+        self.with_param("border", if border { "rounded" } else { "none" })
+    }
+
+    /// Set an art parameter value.
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set several art parameter values, replacing any with the same name.
+    pub fn with_params(mut self, params: BTreeMap<String, String>) -> Self {
+        self.params.extend(params);
+        self
+    }
+
+    /// Get a parameter value for an art, falling back to its default.
+    pub fn param(&self, art: DemoArt, name: &str) -> &str {
+        self.params
+            .get(name)
+            .map(String::as_str)
+            .or_else(|| art.params().iter().find(|p| p.name == name).map(|p| p.default))
+            .unwrap_or_default()
+    }
+
+    /// Get a numeric parameter value for an art.
+    pub fn number(&self, art: DemoArt, name: &str) -> f64 {
+        self.param(art, name).parse().unwrap_or_default()
+    }
+
+    /// Get a boolean parameter value for an art.
+    pub fn flag(&self, art: DemoArt, name: &str) -> bool {
+        self.param(art, name) == "true"
+    }
 }
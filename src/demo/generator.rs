@@ -4,9 +4,29 @@
 //! It handles creating the visual patterns with appropriate sizing and formatting.
 
 use super::art::{ArtSettings, DemoArt};
+use super::quote::{self, Quote};
+use super::sysmon::SystemMonitor;
+use super::text;
+use chrono::{DateTime, Local, TimeDelta};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::f64::consts::PI;
+use unicode_width::UnicodeWidthStr;
+
+/// 5x5 block font for the clock art; '#' marks a filled cell
+const CLOCK_FONT: [(char, [&str; 5]); 11] = [
+    ('0', ["#####", "#   #", "#   #", "#   #", "#####"]),
+    ('1', ["  #  ", " ##  ", "  #  ", "  #  ", " ### "]),
+    ('2', ["#####", "    #", "#####", "#    ", "#####"]),
+    ('3', ["#####", "    #", " ####", "    #", "#####"]),
+    ('4', ["#   #", "#   #", "#####", "    #", "    #"]),
+    ('5', ["#####", "#    ", "#####", "    #", "#####"]),
+    ('6', ["#####", "#    ", "#####", "#   #", "#####"]),
+    ('7', ["#####", "    #", "   # ", "  #  ", "  #  "]),
+    ('8', ["#####", "#   #", "#####", "#   #", "#####"]),
+    ('9', ["#####", "#   #", "#####", "    #", "#####"]),
+    (':', [" ", "#", " ", "#", " "]),
+];
 
 /// Generator for demo art patterns
 pub struct DemoArtGenerator {
@@ -16,6 +36,12 @@ pub struct DemoArtGenerator {
     rng: StdRng,
     /// Cached generated content
     generated: Option<String>,
+    /// Wall-clock time that art time zero corresponds to
+    origin: DateTime<Local>,
+    /// System sampler for the sysmon art, created on first use
+    monitor: Option<SystemMonitor>,
+    /// Quote picked for the quote art, chosen once per generator
+    quote_choice: Option<usize>,
 }
 
 impl DemoArtGenerator {
@@ -25,9 +51,19 @@ impl DemoArtGenerator {
             rng: StdRng::seed_from_u64(settings.seed),
             settings,
             generated: None,
+            origin: Local::now(),
+            monitor: None,
+            quote_choice: None,
         }
     }
 
+    /// Change the output size, keeping other settings and any state such as
+    /// the chosen quote or the system monitor history.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.settings = self.settings.clone().with_size(width, height);
+        self.generated = None;
+    }
+
     /// Generate content for the specified art type.
     pub fn generate(&mut self, art: DemoArt) -> String {
         // Return cached content if available
@@ -35,6 +71,32 @@ impl DemoArtGenerator {
             return content.clone();
         }
 
+        // Start at a random point in the animation; the clock must show
+        // the actual time though
+        let time = match art {
+            DemoArt::Clock => 0.0,
+            _ => self.rng.gen_range(0.0..2.0 * PI),
+        };
+        let output = self.compose(art, time);
+
+        // Cache the generated content
+        self.generated = Some(output.clone());
+        output
+    }
+
+    /// Generate the frame of an art type at time `t` (in seconds).
+    ///
+    /// Unlike [`generate`](Self::generate) this is not cached. The random
+    /// generator is reseeded on every call, so random layout (buildings, cell
+    /// seeds, rain columns) stays put between frames while the time-driven
+    /// parts move. Calling this twice with the same `t` yields the same frame.
+    pub fn generate_at(&mut self, art: DemoArt, t: f64) -> String {
+        self.rng = StdRng::seed_from_u64(self.settings.seed);
+        self.compose(art, t)
+    }
+
+    /// Build the output for an art type, with section headers for `All`.
+    fn compose(&mut self, art: DemoArt, time: f64) -> String {
         let mut output = String::new();
 
         match art {
@@ -48,7 +110,7 @@ impl DemoArtGenerator {
                             width = self.settings.width as usize
                         ));
                     }
-                    output.push_str(&self.generate_art(*art_type));
+                    output.push_str(&self.generate_art(*art_type, time));
                     output.push_str("\n\n");
                 }
                 // Add final divider
@@ -57,50 +119,84 @@ impl DemoArtGenerator {
             }
             _ => {
                 // Generate single pattern without header
-                output.push_str(&self.generate_art(art));
+                output.push_str(&self.generate_art(art, time));
             }
         }
 
-        // Cache the generated content
-        self.generated = Some(output.clone());
         output
     }
 
-    /// Generate a specific art pattern.
-    fn generate_art(&mut self, art: DemoArt) -> String {
+    /// Generate a specific art pattern at the given time.
+    fn generate_art(&mut self, art: DemoArt, time: f64) -> String {
         match art {
-            DemoArt::Matrix => self.generate_matrix(),
-            DemoArt::Waves => self.generate_waves(),
-            DemoArt::Spiral => self.generate_spiral(),
+            DemoArt::Matrix => self.generate_matrix(time),
+            DemoArt::Waves => self.generate_waves(time),
+            DemoArt::Spiral => self.generate_spiral(time),
             DemoArt::Code => self.generate_code(),
             DemoArt::Ascii => self.generate_ascii(),
             DemoArt::Boxes => self.generate_boxes(),
-            DemoArt::Plasma => self.generate_plasma(),
-            DemoArt::Vortex => self.generate_vortex(),
-            DemoArt::Cells => self.generate_cells(),
-            DemoArt::Fluid => self.generate_fluid(),
+            DemoArt::Plasma => self.generate_plasma(time),
+            DemoArt::Vortex => self.generate_vortex(time),
+            DemoArt::Cells => self.generate_cells(time),
+            DemoArt::Fluid => self.generate_fluid(time),
             DemoArt::Maze => self.generate_maze(),
-            DemoArt::Mandala => self.generate_mandala(),
+            DemoArt::Mandala => self.generate_mandala(time),
             DemoArt::Logo => self.generate_logo(),
-            DemoArt::Cityscape => self.generate_cityscape(),
+            DemoArt::Cityscape => self.generate_cityscape(time),
+            DemoArt::Aquarium => self.generate_aquarium(time),
+            DemoArt::Clock => self.generate_clock(time),
+            DemoArt::Sysmon => self.generate_sysmon(time),
+            DemoArt::Quote => self.generate_quote(),
             DemoArt::All => unreachable!(),
         }
     }
 
     /// Generate matrix digital rain effect.
-    fn generate_matrix(&mut self) -> String {
+    ///
+    /// Each column carries a drop with its own speed and trail length that
+    /// falls as `time` advances; digits flicker a few times per second.
+    fn generate_matrix(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
-        let chars = "10 ";
-        let chars: Vec<char> = chars.chars().collect();
+        let chars = ['1', '0'];
+        let height = self.settings.height as f64;
+        let density = self.settings.number(DemoArt::Matrix, "density");
+        let time = time * self.settings.number(DemoArt::Matrix, "speed");
+
+        // (speed in rows per second, trail length, starting offset)
+        let drops: Vec<(f64, f64, f64)> = (0..self.settings.width)
+            .map(|_| {
+                let speed = self.rng.gen_range(4.0..14.0);
+                let length = self.rng.gen_range(height * 0.3..height * 0.9);
+                let offset = self.rng.gen_range(0.0..height + length);
+                (speed, length, offset)
+            })
+            .collect();
+        let flicker = (time * 6.0).floor() as u64;
+
+        // Columns left dark when density is below 1
+        let active: Vec<bool> = (0..self.settings.width as u64)
+            .map(|x| (Self::cell_noise(x, 0, u64::MAX) % 1000) as f64 / 1000.0 < density)
+            .collect();
 
-        for _ in 0..self.settings.height {
-            for _ in 0..self.settings.width {
-                output.push(if self.rng.gen_bool(0.7) {
-                    chars[self.rng.gen_range(0..2)]
+        for y in 0..self.settings.height {
+            for (x, &(speed, length, offset)) in drops.iter().enumerate() {
+                // Head position wraps around once the whole trail has left the screen
+                let head = (offset + time * speed).rem_euclid(height + length);
+                let behind = head - y as f64;
+
+                let ch = if active[x] && (0.0..length).contains(&behind) {
+                    let noise = Self::cell_noise(x as u64, y as u64, flicker);
+                    // Trails thin out towards their tail
+                    if (noise % 1000) as f64 / 1000.0 < 1.0 - behind / length * 0.6 {
+                        chars[(noise >> 10) as usize % chars.len()]
+                    } else {
+                        ' '
+                    }
                 } else {
                     ' '
-                });
+                };
+                output.push(ch);
             }
             output.push('\n');
         }
@@ -108,8 +204,19 @@ impl DemoArtGenerator {
         output
     }
 
+    /// Cheap deterministic hash used for per-cell variation between frames.
+    fn cell_noise(x: u64, y: u64, step: u64) -> u64 {
+        let mut h = x
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            .wrapping_add(y.wrapping_mul(0xC2B2_AE3D_27D4_EB4F))
+            .wrapping_add(step.wrapping_mul(0x1656_67B1_9E37_79F9));
+        h ^= h >> 31;
+        h = h.wrapping_mul(0x94D0_49BB_1331_11EB);
+        h ^ (h >> 29)
+    }
+
     /// Generate wave interference pattern.
-    fn generate_waves(&mut self) -> String {
+    fn generate_waves(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let wave_chars = ['█', '▓', '▒', '░', ' '];
@@ -123,8 +230,7 @@ impl DemoArtGenerator {
             (0.03, 0.06, 0.2, 0.6), // Background wave
         ];
 
-        // Add time variation for animation-like effect
-        let time_offset = self.rng.gen_range(0.0..2.0 * PI);
+        let time_offset = time;
 
         for y in 0..self.settings.height {
             for x in 0..self.settings.width {
@@ -154,7 +260,7 @@ impl DemoArtGenerator {
     }
 
     /// Generate spiral vortex pattern.
-    fn generate_spiral(&mut self) -> String {
+    fn generate_spiral(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
@@ -162,6 +268,7 @@ impl DemoArtGenerator {
 
         let center_x = self.settings.width as f64 / 2.0;
         let center_y = self.settings.height as f64 / 2.0;
+        let arms = self.settings.number(DemoArt::Spiral, "arms").round();
 
         for y in 0..self.settings.height {
             for x in 0..self.settings.width {
@@ -170,7 +277,7 @@ impl DemoArtGenerator {
                 let r = (dx * dx + dy * dy).sqrt();
                 let theta = dy.atan2(dx);
 
-                let spiral = (r * 0.15 - theta).sin();
+                let spiral = (r * 0.15 - theta * arms + time).sin();
                 let value = (spiral + 1.0) / 2.0;
                 let idx = (value * char_count as f64) as usize;
                 output.push(chars[idx.min(char_count)]);
@@ -204,33 +311,11 @@ impl DemoArtGenerator {
         ];
 
         // Center the code box
-        let mut output = String::new();
-        let padding_top = (self.settings.height as usize - code.len()) / 2;
-
-        // Add top padding
-        for _ in 0..padding_top {
-            output.push_str(&" ".repeat(self.settings.width as usize));
-            output.push('\n');
-        }
-
-        // Add code with centering
-        for line in code {
-            let padding = (self.settings.width as usize - line.chars().count()) / 2;
-            output.push_str(&" ".repeat(padding));
-            output.push_str(line);
-            output.push_str(
-                &" ".repeat(self.settings.width as usize - padding - line.chars().count()),
-            );
-            output.push('\n');
-        }
-
-        // Fill remaining space
-        while output.lines().count() < self.settings.height as usize {
-            output.push_str(&" ".repeat(self.settings.width as usize));
-            output.push('\n');
-        }
-
-        output
+        text::center_block(
+            &code,
+            self.settings.width as usize,
+            self.settings.height as usize,
+        )
     }
 
     /// Generate ASCII art showcase.
@@ -297,16 +382,7 @@ impl DemoArtGenerator {
 
             // Add the art with centering
             for line in art_lines {
-                let line_len = line.chars().count().min(self.settings.width as usize);
-                let side_padding = (self.settings.width as usize - line_len) / 2;
-
-                output.push_str(&" ".repeat(side_padding));
-                output.push_str(line);
-
-                // Calculate remaining space safely
-                let remaining =
-                    (self.settings.width as usize).saturating_sub(side_padding + line_len);
-                output.push_str(&" ".repeat(remaining));
+                output.push_str(&text::center(line, self.settings.width as usize));
                 output.push('\n');
             }
 
@@ -374,7 +450,7 @@ impl DemoArtGenerator {
     }
 
     /// Generate mandala pattern.
-    fn generate_mandala(&mut self) -> String {
+    fn generate_mandala(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
@@ -382,14 +458,16 @@ impl DemoArtGenerator {
 
         let center_x = self.settings.width as f64 / 2.0;
         let center_y = self.settings.height as f64 / 2.0;
+        // Whole petals only, so the pattern meets itself around the circle
+        let symmetry = self.settings.number(DemoArt::Mandala, "symmetry").round();
 
         for y in 0..self.settings.height {
             for x in 0..self.settings.width {
                 let dx = x as f64 - center_x;
                 let dy = (y as f64 - center_y) * 2.0;
                 let distance = (dx * dx + dy * dy).sqrt() * 0.15;
-                let angle = dy.atan2(dx) * 6.0;
-                let value = (distance + angle).sin().abs();
+                let angle = dy.atan2(dx) * symmetry;
+                let value = (distance - time + angle).sin().abs();
                 let idx = (value * char_count as f64) as usize;
                 output.push(chars[idx.min(char_count)]);
             }
@@ -419,37 +497,16 @@ impl DemoArtGenerator {
             r#"               ✨ ChromaCat - Terminal Artistry ✨ '---'           "#,
         ];
 
-        let mut output = String::new();
-        let padding_top = (self.settings.height as usize - logo.len()) / 2;
-
-        // Add top padding
-        for _ in 0..padding_top {
-            output.push_str(&" ".repeat(self.settings.width as usize));
-            output.push('\n');
-        }
-
-        // Add logo with centering
-        for line in logo {
-            let padding = (self.settings.width as usize - line.chars().count()) / 2;
-            output.push_str(&" ".repeat(padding));
-            output.push_str(line);
-            output.push_str(
-                &" ".repeat(self.settings.width as usize - padding - line.chars().count()),
-            );
-            output.push('\n');
-        }
-
-        // Fill remaining space
-        while output.lines().count() < self.settings.height as usize {
-            output.push_str(&" ".repeat(self.settings.width as usize));
-            output.push('\n');
-        }
-
-        output
+        // Center the logo
+        text::center_block(
+            &logo,
+            self.settings.width as usize,
+            self.settings.height as usize,
+        )
     }
 
     /// Generate complex plasma effect with organic motion
-    fn generate_plasma(&mut self) -> String {
+    fn generate_plasma(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
@@ -457,7 +514,7 @@ impl DemoArtGenerator {
 
         let freq_x = 0.1;
         let freq_y = 0.08;
-        let freq_t = self.rng.gen_range(0.0..=1.0) * PI;
+        let freq_t = time;
 
         for y in 0..self.settings.height {
             for x in 0..self.settings.width {
@@ -480,7 +537,7 @@ impl DemoArtGenerator {
     }
 
     /// Generate a mesmerizing vortex tunnel effect
-    fn generate_vortex(&mut self) -> String {
+    fn generate_vortex(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
@@ -488,7 +545,7 @@ impl DemoArtGenerator {
 
         let center_x = self.settings.width as f64 / 2.0;
         let center_y = self.settings.height as f64 / 2.0;
-        let time_offset = self.rng.gen_range(0.0..=2.0 * PI);
+        let time_offset = time * 2.0;
 
         for y in 0..self.settings.height {
             for x in 0..self.settings.width {
@@ -513,7 +570,7 @@ impl DemoArtGenerator {
     }
 
     /// Generate a cellular automaton pattern
-    fn generate_cells(&mut self) -> String {
+    fn generate_cells(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
@@ -528,8 +585,9 @@ impl DemoArtGenerator {
             }
         }
 
-        // Run cellular automaton rules
-        let generations = 5;
+        // Run cellular automaton rules, advancing a few generations per
+        // second and restarting from the seed once the cycle completes
+        let generations = 5 + (time.max(0.0) * 4.0) as usize % 60;
         for _ in 0..generations {
             let mut new_grid = grid.clone();
             for y in 1..self.settings.height - 1 {
@@ -589,14 +647,12 @@ impl DemoArtGenerator {
     }
 
     /// Generate a fluid simulation effect
-    fn generate_fluid(&mut self) -> String {
+    fn generate_fluid(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let chars = ['█', '▓', '▒', '░', ' '];
         let char_count = chars.len() - 1;
 
-        let time = self.rng.gen_range(0.0..=2.0 * PI);
-
         // Multiple frequency layers for more organic motion
         let frequencies = [
             (0.03, 0.02, 1.2), // Slow-moving large features
@@ -883,8 +939,11 @@ impl DemoArtGenerator {
         output
     }
 
-    /// Generate a multi-layered cityscape with night sky
-    fn generate_cityscape(&mut self) -> String {
+    /// Generate a multi-layered cityscape with night sky.
+    ///
+    /// The skyline is fixed by the seed; over time stars twinkle, windows
+    /// switch on and off, and rain falls when enabled in the settings.
+    fn generate_cityscape(&mut self, time: f64) -> String {
         let mut output =
             String::with_capacity((self.settings.width * self.settings.height) as usize);
         let mut canvas =
@@ -994,6 +1053,7 @@ impl DemoArtGenerator {
 
         // Add stars with more variety and careful placement
         let star_chars = ['✦', '✧', '*', '⋆', '·'];
+        let mut stars = Vec::new();
         for _ in 0..(self.settings.width * self.settings.height) / 60 {
             let x = self.rng.gen_range(0..self.settings.width as usize);
             let y = self.rng.gen_range(0..gradient_start);
@@ -1011,11 +1071,13 @@ impl DemoArtGenerator {
                     _ => star_chars[4],       // 40% ·
                 };
                 canvas[y][x] = star;
+                stars.push((x, y, star));
             }
         }
 
         // Generate buildings
         let mut x = 0;
+        let mut lit_windows = Vec::new();
 
         while x < self.settings.width as usize {
             // Building parameters
@@ -1061,8 +1123,8 @@ impl DemoArtGenerator {
             }
 
             // Add windows in a grid pattern
-            let window_rows = (height - 2) / 3;
-            let window_cols = (width - 2) / 3;
+            let window_rows = height.saturating_sub(2) / 3;
+            let window_cols = width.saturating_sub(2) / 3;
 
             for wy in 0..window_rows {
                 for wx in 0..window_cols {
@@ -1081,6 +1143,8 @@ impl DemoArtGenerator {
                                     _ => [['░', '░'], ['░', '░']], // Fully lit
                                 };
 
+                                lit_windows.push((window_x, window_y));
+
                                 // Draw 2x2 window
                                 for dy in 0..2 {
                                     for dx in 0..2 {
@@ -1184,6 +1248,30 @@ impl DemoArtGenerator {
             x += width + 1;
         }
 
+        // Stars twinkle by dimming briefly at their own pace
+        let twinkle = (time * 2.0).floor() as u64;
+        for (x, y, star) in stars {
+            if canvas[y][x] == star && Self::cell_noise(x as u64, y as u64, twinkle).is_multiple_of(6) {
+                canvas[y][x] = if star == '·' { ' ' } else { '·' };
+            }
+        }
+
+        // Every few seconds some residents switch their lights off
+        let evening = (time / 4.0).floor() as u64;
+        for (wx, wy) in lit_windows {
+            if Self::cell_noise(wx as u64, wy as u64, evening).is_multiple_of(5) {
+                for row in canvas.iter_mut().skip(wy).take(2) {
+                    for cell in row.iter_mut().skip(wx).take(2) {
+                        *cell = '█';
+                    }
+                }
+            }
+        }
+
+        if self.settings.flag(DemoArt::Cityscape, "rain") {
+            self.draw_rain(&mut canvas, time);
+        }
+
         // Convert canvas to string
         for row in canvas {
             for ch in row {
@@ -1194,4 +1282,281 @@ impl DemoArtGenerator {
 
         output
     }
+
+    /// Overlay falling rain streaks on a canvas
+    fn draw_rain(&mut self, canvas: &mut [Vec<char>], time: f64) {
+        let height = canvas.len();
+        let width = canvas.first().map_or(0, |row| row.len());
+        if height == 0 || width == 0 {
+            return;
+        }
+
+        // Each drop falls down its own column at its own speed
+        for _ in 0..(width / 3).max(1) {
+            let x = self.rng.gen_range(0..width);
+            let speed = self.rng.gen_range(18.0..30.0);
+            let offset = self.rng.gen_range(0.0..height as f64);
+            let head = (offset + time * speed).rem_euclid(height as f64) as usize;
+
+            for (i, ch) in ['│', '╵'].into_iter().enumerate() {
+                if let Some(y) = head.checked_sub(i) {
+                    canvas[y][x] = ch;
+                }
+            }
+        }
+    }
+
+    /// Generate an aquarium with fish, bubbles, and swaying seaweed
+    fn generate_aquarium(&mut self, time: f64) -> String {
+        let width = self.settings.width as usize;
+        let height = self.settings.height as usize;
+        let mut output = String::with_capacity(width * height);
+        let mut canvas = vec![vec![' '; width]; height];
+
+        // Fish sprites facing right; left-facing ones are drawn mirrored
+        let sprites: [(&str, &str); 4] = [
+            ("><>", "<><"),
+            ("><(('>", "<'))><"),
+            ("><((((º>", "<º))))><"),
+            (">=(°>", "<°)=<"),
+        ];
+
+        // Rippling water surface
+        let ripple = ['~', '≈', '~', '-'];
+        let shift = (time * 4.0) as usize;
+        for (x, cell) in canvas[0].iter_mut().enumerate() {
+            *cell = ripple[(x + shift) % ripple.len()];
+        }
+
+        // Sandy bottom with scattered pebbles
+        let floor = height - 1;
+        for cell in canvas[floor].iter_mut() {
+            *cell = match self.rng.gen_range(0..10) {
+                0 => 'o',
+                1 => '.',
+                _ => '▒',
+            };
+        }
+
+        // Seaweed swaying from the bottom
+        for _ in 0..(width / 10).max(2) {
+            let x = self.rng.gen_range(0..width);
+            let tall = self.rng.gen_range(3..(height / 2).max(4));
+            let phase = self.rng.gen_range(0.0..2.0 * PI);
+            for i in 1..=tall.min(floor - 1) {
+                let y = floor - i;
+                let sway = (time * 1.5 + i as f64 * 0.7 + phase).sin();
+                canvas[y][x] = if sway > 0.0 { ')' } else { '(' };
+            }
+        }
+
+        // Bubbles rising from the bottom with a slight wobble
+        let water = (floor - 1) as f64;
+        for _ in 0..(width / 8).max(3) {
+            let x = self.rng.gen_range(0..width) as f64;
+            let speed = self.rng.gen_range(1.5..4.0);
+            let offset = self.rng.gen_range(0.0..water);
+            let rise = (offset + time * speed).rem_euclid(water);
+            let y = floor - 1 - rise as usize;
+            let bx = (x + (time * 3.0 + offset).sin()).clamp(0.0, width as f64 - 1.0) as usize;
+            if y > 0 {
+                // Bubbles grow as they near the surface
+                canvas[y][bx] = match rise / water {
+                    r if r < 0.3 => '.',
+                    r if r < 0.7 => 'o',
+                    _ => 'O',
+                };
+            }
+        }
+
+        // Fish at different depths; deeper fish swim more slowly
+        let fish_count = (width * height / 200).max(3);
+        for _ in 0..fish_count {
+            let (right, left) = sprites[self.rng.gen_range(0..sprites.len())];
+            let depth = self.rng.gen_range(2..floor.max(3) - 1);
+            let depth_factor = 1.0 - 0.5 * depth as f64 / floor as f64;
+            let speed = self.rng.gen_range(4.0..12.0) * depth_factor;
+            let facing_right = self.rng.gen_bool(0.5);
+            let sprite: Vec<char> = if facing_right { right } else { left }.chars().collect();
+            let span = (width + sprite.len()) as f64;
+            let offset = self.rng.gen_range(0.0..span);
+            let travelled = (offset + time * speed).rem_euclid(span) as isize;
+
+            // Leftmost column of the sprite, which may be off either edge
+            let start = if facing_right {
+                travelled - sprite.len() as isize
+            } else {
+                width as isize - travelled
+            };
+            for (i, &ch) in sprite.iter().enumerate() {
+                let x = start + i as isize;
+                if (0..width as isize).contains(&x) {
+                    canvas[depth][x as usize] = ch;
+                }
+            }
+        }
+
+        for row in canvas {
+            output.extend(row);
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Generate a centered, word-wrapped quote.
+    ///
+    /// The quote is picked at random the first time and kept for the life
+    /// of the generator, so each new scene shows a fresh quote while frames
+    /// within a scene stay the same.
+    fn generate_quote(&mut self) -> String {
+        let width = self.settings.width as usize;
+        let height = self.settings.height as usize;
+
+        let quotes: Vec<Quote> = if self.settings.quotes.is_empty() {
+            quote::embedded_quotes()
+        } else {
+            self.settings.quotes.clone()
+        };
+        let index = *self
+            .quote_choice
+            .get_or_insert_with(|| rand::thread_rng().gen_range(0..quotes.len()));
+        let chosen = &quotes[index % quotes.len()];
+
+        // Wrap the text to fit inside the frame, then right-align the
+        // attribution beneath it
+        let padding = self.settings.number(DemoArt::Quote, "padding") as usize;
+        let text_width = width.saturating_sub(padding * 2 + 2).clamp(10, 60);
+        let mut lines = quote::wrap_text(&chosen.text, text_width);
+        if let Some(author) = &chosen.author {
+            let attribution = quote::wrap_text(&format!("— {}", author), text_width);
+            let block = lines
+                .iter()
+                .chain(&attribution)
+                .map(|l| l.width())
+                .max()
+                .unwrap_or(0);
+            lines.push(String::new());
+            for line in attribution {
+                lines.push(format!("{}{}", " ".repeat(block - line.width()), line));
+            }
+        }
+        let border = self
+            .settings
+            .param(DemoArt::Quote, "border")
+            .parse()
+            .unwrap_or_default();
+        let framed = quote::frame(&lines, padding, border);
+
+        let top = height.saturating_sub(framed.len()) / 2;
+        let mut output = String::with_capacity(width * height);
+        for y in 0..height {
+            let line = y
+                .checked_sub(top)
+                .and_then(|i| framed.get(i))
+                .map_or("", String::as_str);
+            output.push_str(&text::center(line, width));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Generate a live system monitor dashboard from the latest sample.
+    fn generate_sysmon(&mut self, time: f64) -> String {
+        let width = self.settings.width as usize;
+        let monitor = self.monitor.get_or_insert_with(SystemMonitor::new);
+        monitor.sample(time, width);
+        monitor.render(width, self.settings.height as usize)
+    }
+
+    /// Generate a block-digit clock, or a countdown when one is configured.
+    ///
+    /// `time` is measured from when the generator was created, so the
+    /// displayed time stays in step with the animation clock.
+    fn generate_clock(&mut self, time: f64) -> String {
+        let width = self.settings.width as usize;
+        let height = self.settings.height as usize;
+        let time = time.max(0.0);
+
+        let (digits, caption, visible) = match self.settings.countdown {
+            Some(countdown) => {
+                // Round up so the display only reads zero once time is up
+                let remaining = (countdown.as_secs_f64() - time).max(0.0).ceil() as u64;
+                let (h, m, s) = (remaining / 3600, remaining / 60 % 60, remaining % 60);
+                let digits = if h > 0 {
+                    format!("{}:{:02}:{:02}", h, m, s)
+                } else {
+                    format!("{:02}:{:02}", m, s)
+                };
+                if remaining == 0 {
+                    // Blink once time is up
+                    let blink = ((time * 2.0).floor() as u64).is_multiple_of(2);
+                    (digits, "time's up!".to_string(), blink)
+                } else {
+                    let end = self.origin + TimeDelta::milliseconds((countdown.as_secs_f64() * 1000.0) as i64);
+                    (digits, format!("until {}", end.format("%H:%M:%S")), true)
+                }
+            }
+            None => {
+                let now = self.origin + TimeDelta::milliseconds((time * 1000.0) as i64);
+                (
+                    now.format("%H:%M:%S").to_string(),
+                    now.format("%A, %B %-d %Y").to_string(),
+                    true,
+                )
+            }
+        };
+
+        let glyphs: Vec<&[&str; 5]> = digits
+            .chars()
+            .filter_map(|c| CLOCK_FONT.iter().find(|(g, _)| *g == c).map(|(_, rows)| rows))
+            .collect();
+        let text_width = glyphs.iter().map(|g| g[0].len() + 1).sum::<usize>().saturating_sub(1);
+
+        // Scale the digits up as far as the terminal allows
+        let scale = (1..=6)
+            .rev()
+            .find(|s| text_width * s <= width && 5 * s + 2 <= height)
+            .unwrap_or(1);
+
+        let mut canvas = vec![vec![' '; width]; height];
+        let block_height = 5 * scale;
+        let top = height.saturating_sub(block_height + 2) / 2;
+        let left = width.saturating_sub(text_width * scale) / 2;
+
+        if visible {
+            let mut x = left;
+            for glyph in glyphs {
+                for (row, line) in glyph.iter().enumerate() {
+                    for (col, pixel) in line.chars().enumerate() {
+                        if pixel != '#' {
+                            continue;
+                        }
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                let (cx, cy) = (x + col * scale + dx, top + row * scale + dy);
+                                if cy < height && cx < width {
+                                    canvas[cy][cx] = '█';
+                                }
+                            }
+                        }
+                    }
+                }
+                x += (glyph[0].len() + 1) * scale;
+            }
+        }
+
+        // Caption centered one row below the digits
+        let caption_y = top + block_height + 1;
+        let mut output = String::with_capacity(width * height);
+        for (y, row) in canvas.into_iter().enumerate() {
+            if y == caption_y {
+                output.push_str(&text::center(&caption, width));
+            } else {
+                output.extend(row);
+            }
+            output.push('\n');
+        }
+        output
+    }
 }
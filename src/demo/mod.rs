@@ -36,6 +36,10 @@
 //! - Fluid simulations
 //! - Fractal trees
 //! - Mandala designs
+//! - An aquarium with swimming fish
+//! - A block-digit desk clock and countdown timer
+//! - A live system monitor dashboard
+//! - Framed quotes from an embedded list or a quote file
 //! - And more...
 //!
 //! Each pattern can be customized through ChromaCat's theme system
@@ -43,9 +47,19 @@
 
 pub mod art;
 pub mod generator;
+pub mod params;
+pub mod quote;
+mod sysmon;
+pub mod text;
+pub mod transition;
 
 pub use art::{ArtSettings, DemoArt};
 pub use generator::DemoArtGenerator;
+pub use params::{parse_art_params, ArtParam};
+pub use quote::{BorderStyle, Quote};
+pub use transition::{ArtTransition, ART_TRANSITION_DURATION};
+
+use std::time::Duration;
 
 /// Terminal size requirements for demo art
 pub const MIN_TERMINAL_WIDTH: u16 = 40;
@@ -82,3 +96,46 @@ pub fn parse_art(s: &str) -> Result<DemoArt> {
     DemoArt::try_from_str(s)
         .ok_or_else(|| Error::InvalidPattern(s.to_string()))
 }
+
+/// Parses a countdown length such as `90`, `45s`, `10m` or `1h30m`.
+///
+/// A bare number is taken as seconds.
+pub fn parse_countdown(s: &str) -> std::result::Result<Duration, String> {
+    let invalid = || format!("Invalid countdown '{}'. Use e.g. 90, 45s, 10m or 1h30m", s);
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return match secs {
+            0 => Err(invalid()),
+            secs => Ok(Duration::from_secs(secs)),
+        };
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() || total == 0 {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(total))
+}
@@ -0,0 +1,110 @@
+//! Demo art parameters
+//!
+//! Arts describe their configurable knobs with [`ArtParam`] metadata, in the
+//! same spirit as pattern parameters. Values are given as comma-separated
+//! `name=value` pairs (`--art-param density=0.5,border=heavy`), validated
+//! against the art's metadata, and stored on [`ArtSettings`](super::ArtSettings)
+//! where generators read them back with their defaults filled in.
+
+use super::art::DemoArt;
+use crate::pattern::ParamType;
+use std::collections::BTreeMap;
+
+/// Metadata for a configurable demo art parameter
+#[derive(Debug, Clone)]
+pub struct ArtParam {
+    /// Name used in `name=value` pairs
+    pub name: &'static str,
+    /// Description for help output
+    pub description: &'static str,
+    /// Accepted values
+    pub param_type: ParamType,
+    /// Value used when the parameter is not given
+    pub default: &'static str,
+}
+
+impl ArtParam {
+    /// Validates a value for this parameter
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match &self.param_type {
+            ParamType::Number { min, max } => {
+                let number = value
+                    .parse::<f64>()
+                    .map_err(|_| format!("{} must be a number", self.name))?;
+                if !(*min..=*max).contains(&number) {
+                    return Err(format!("{} must be between {} and {}", self.name, min, max));
+                }
+                Ok(())
+            }
+            ParamType::Boolean => match value {
+                "true" | "false" => Ok(()),
+                _ => Err(format!("{} must be true or false", self.name)),
+            },
+            ParamType::Enum { options } => {
+                if options.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("{} must be one of: {}", self.name, options.join(", ")))
+                }
+            }
+            ParamType::Composite => Ok(()),
+        }
+    }
+
+    /// Describes the accepted values for help output
+    pub fn range(&self) -> String {
+        match &self.param_type {
+            ParamType::Number { min, max } => format!("{} to {}", min, max),
+            ParamType::Boolean => "true/false".to_string(),
+            ParamType::Enum { options } => options.join(", "),
+            ParamType::Composite => String::new(),
+        }
+    }
+}
+
+/// Parses comma-separated `name=value` pairs for an art.
+///
+/// Each name must be a parameter of `art`; for [`DemoArt::All`] any art's
+/// parameter is accepted.
+pub fn parse_art_params(art: DemoArt, input: &str) -> Result<BTreeMap<String, String>, String> {
+    let available: Vec<&ArtParam> = if art == DemoArt::All {
+        DemoArt::all_types().iter().flat_map(|a| a.params()).collect()
+    } else {
+        art.params().iter().collect()
+    };
+
+    let mut params = BTreeMap::new();
+    for pair in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("Art parameter '{}' must be in format key=value", pair))?;
+        let (name, value) = (name.trim(), value.trim());
+
+        let candidates: Vec<&&ArtParam> = available.iter().filter(|p| p.name == name).collect();
+        if candidates.is_empty() {
+            return Err(if available.is_empty() {
+                format!("The {} art has no parameters", art.as_str())
+            } else {
+                let mut names: Vec<&str> = available.iter().map(|p| p.name).collect();
+                names.sort_unstable();
+                names.dedup();
+                format!(
+                    "Unknown parameter '{}' for the {} art. Available: {}",
+                    name,
+                    art.as_str(),
+                    names.join(", ")
+                )
+            });
+        }
+
+        // With `all`, the value only needs to suit one art sharing the name
+        candidates
+            .iter()
+            .map(|p| p.validate(value))
+            .find(Result::is_ok)
+            .unwrap_or_else(|| candidates[0].validate(value))?;
+        params.insert(name.to_string(), value.to_string());
+    }
+
+    Ok(params)
+}
@@ -0,0 +1,249 @@
+//! Quotes for the quote demo art
+//!
+//! Provides an embedded collection of quotes, a parser for fortune-style
+//! quote files, and the layout helpers that word-wrap a quote and frame it
+//! with padding and an optional decorative border.
+
+use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
+
+/// A quote with an optional attribution
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    /// Quote text; paragraphs are separated by newlines
+    pub text: String,
+    /// Who said it, shown below the text
+    pub author: Option<String>,
+}
+
+impl Quote {
+    /// Creates a quote with an optional attribution
+    pub fn new(text: impl Into<String>, author: Option<&str>) -> Self {
+        Self {
+            text: text.into(),
+            author: author.map(str::to_string),
+        }
+    }
+}
+
+/// Border drawn around a framed quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    /// Thin lines with rounded corners
+    #[default]
+    Rounded,
+    /// Thick lines
+    Heavy,
+    /// Double lines
+    Double,
+    /// Plain ASCII `+`, `-` and `|`
+    Ascii,
+    /// Blank space instead of a border
+    None,
+}
+
+impl BorderStyle {
+    /// Returns all border styles
+    pub fn all() -> &'static [BorderStyle] {
+        &[
+            BorderStyle::Rounded,
+            BorderStyle::Heavy,
+            BorderStyle::Double,
+            BorderStyle::Ascii,
+            BorderStyle::None,
+        ]
+    }
+
+    /// Returns the name used for this style in art parameters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BorderStyle::Rounded => "rounded",
+            BorderStyle::Heavy => "heavy",
+            BorderStyle::Double => "double",
+            BorderStyle::Ascii => "ascii",
+            BorderStyle::None => "none",
+        }
+    }
+
+    /// Corner, edge and side characters: top-left, top-right, bottom-left,
+    /// bottom-right, horizontal, vertical
+    fn chars(&self) -> [char; 6] {
+        match self {
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            BorderStyle::Heavy => ['┏', '┓', '┗', '┛', '━', '┃'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+            BorderStyle::None => [' '; 6],
+        }
+    }
+}
+
+impl std::fmt::Display for BorderStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BorderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BorderStyle::all()
+            .iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = BorderStyle::all().iter().map(|s| s.as_str()).collect();
+                format!("Invalid border style '{}'. Valid styles: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Quotes used when no quote file is given
+const EMBEDDED_QUOTES: &[(&str, &str)] = &[
+    ("Color is my day-long obsession, joy and torment.", "Claude Monet"),
+    ("The best color in the whole world is the one that looks good on you.", "Coco Chanel"),
+    ("Simplicity is prerequisite for reliability.", "Edsger W. Dijkstra"),
+    ("Programs must be written for people to read, and only incidentally for machines to execute.", "Harold Abelson"),
+    ("Any sufficiently advanced technology is indistinguishable from magic.", "Arthur C. Clarke"),
+    ("The purpose of art is washing the dust of daily life off our souls.", "Pablo Picasso"),
+    ("Make it work, make it right, make it fast.", "Kent Beck"),
+    ("Colors, like features, follow the changes of the emotions.", "Pablo Picasso"),
+    ("Talk is cheap. Show me the code.", "Linus Torvalds"),
+    ("Mystery creates wonder and wonder is the basis of man's desire to understand.", "Neil Armstrong"),
+    ("Life is a great big canvas, and you should throw all the paint on it you can.", "Danny Kaye"),
+    ("The computer was born to solve problems that did not exist before.", "Bill Gates"),
+    ("Time spent with cats is never wasted.", "Sigmund Freud"),
+    ("Everything you can imagine is real.", "Pablo Picasso"),
+    ("Premature optimization is the root of all evil.", "Donald Knuth"),
+    ("In the middle of difficulty lies opportunity.", "Albert Einstein"),
+];
+
+/// Returns the embedded quote collection
+pub fn embedded_quotes() -> Vec<Quote> {
+    EMBEDDED_QUOTES
+        .iter()
+        .map(|(text, author)| Quote::new(*text, Some(author)))
+        .collect()
+}
+
+/// Parses quotes from text in fortune format.
+///
+/// Quotes are separated by lines containing only `%` or by blank lines. A
+/// final line starting with `--`, `—` or `~` is taken as the attribution.
+pub fn parse_quotes(content: &str) -> Vec<Quote> {
+    let mut quotes = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+
+    let mut flush = |lines: &mut Vec<&str>| {
+        if lines.is_empty() {
+            return;
+        }
+        let author = lines
+            .last()
+            .and_then(|last| {
+                ["--", "—", "~"]
+                    .iter()
+                    .find_map(|prefix| last.strip_prefix(prefix))
+            })
+            .map(|author| author.trim().to_string());
+        if author.is_some() {
+            lines.pop();
+        }
+
+        let text = lines.join("\n");
+        if !text.trim().is_empty() {
+            quotes.push(Quote {
+                text,
+                author: author.filter(|a| !a.is_empty()),
+            });
+        }
+        lines.clear();
+    };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line == "%" {
+            flush(&mut lines);
+        } else {
+            lines.push(line);
+        }
+    }
+    flush(&mut lines);
+
+    quotes
+}
+
+/// Word-wraps text to lines no wider than `width` columns.
+///
+/// Newlines in the text start a new line; words wider than `width` are
+/// split across lines.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split_whitespace() {
+            let word_width = word.width();
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+
+            if word_width > width {
+                // Hard-break words that cannot fit on any line
+                for ch in word.chars() {
+                    let ch_width = ch.to_string().width();
+                    if line_width + ch_width > width && line_width > 0 {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0;
+                    }
+                    line.push(ch);
+                    line_width += ch_width;
+                }
+                continue;
+            }
+
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+            line.push_str(word);
+            line_width += word_width;
+        }
+
+        if !line.is_empty() || paragraph.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+/// Frames lines in a box with `padding` columns of space on each side and a
+/// blank row above and below, drawn with the given border style.
+///
+/// Every returned line has the same display width.
+pub fn frame(lines: &[String], padding: usize, border: BorderStyle) -> Vec<String> {
+    let inner = lines.iter().map(|l| l.width()).max().unwrap_or(0) + padding * 2;
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, side] = border.chars();
+    let edge = horizontal.to_string().repeat(inner);
+    let top = format!("{}{}{}", top_left, edge, top_right);
+    let bottom = format!("{}{}{}", bottom_left, edge, bottom_right);
+
+    let row = |content: &str| {
+        let fill = inner - padding - content.width();
+        format!("{}{}{}{}{}", side, " ".repeat(padding), content, " ".repeat(fill), side)
+    };
+
+    let mut framed = Vec::with_capacity(lines.len() + 4);
+    framed.push(top);
+    framed.push(row(""));
+    framed.extend(lines.iter().map(|l| row(l)));
+    framed.push(row(""));
+    framed.push(bottom);
+    framed
+}
@@ -0,0 +1,198 @@
+//! Live system monitor art
+//!
+//! Samples CPU and memory usage with `sysinfo` and draws them as block
+//! character bars plus a scrolling sparkline of total CPU load. Samples are
+//! taken as art time advances, no more often than sysinfo can measure CPU
+//! usage, so regenerating a frame at the same time reuses the last sample.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use sysinfo::{System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+use super::text;
+
+/// Characters for sparkline cells, from empty to full
+const SPARK_LEVELS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Live CPU and memory sampler with a rolling load history
+pub(crate) struct SystemMonitor {
+    /// sysinfo handle, refreshed on every sample
+    system: System,
+    /// Total CPU usage samples (0.0-1.0), oldest first
+    history: VecDeque<f32>,
+    /// Art time of the last sample
+    last_sample: Option<f64>,
+    /// When CPU usage was last refreshed
+    refreshed_at: Instant,
+    /// Whether CPU usage has been measured over a full interval yet
+    measured: bool,
+}
+
+impl SystemMonitor {
+    /// Creates a monitor with no samples yet
+    pub(crate) fn new() -> Self {
+        // CPU usage is measured between refreshes, so take a baseline now
+        let mut system = System::new();
+        system.refresh_cpu_usage();
+
+        Self {
+            system,
+            history: VecDeque::new(),
+            last_sample: None,
+            refreshed_at: Instant::now(),
+            measured: false,
+        }
+    }
+
+    /// Refreshes usage if enough art time has passed since the last sample
+    pub(crate) fn sample(&mut self, time: f64, capacity: usize) {
+        let due = self.last_sample.is_none_or(|last| {
+            (time - last).abs() >= MINIMUM_CPU_UPDATE_INTERVAL.as_secs_f64()
+        });
+        if !due {
+            return;
+        }
+
+        // Refreshing sooner than sysinfo can measure gives bogus readings,
+        // so keep the previous CPU figures until enough time has passed
+        if self.refreshed_at.elapsed() >= MINIMUM_CPU_UPDATE_INTERVAL {
+            self.system.refresh_cpu_usage();
+            self.refreshed_at = Instant::now();
+            self.measured = true;
+        }
+        self.system.refresh_memory();
+        self.last_sample = Some(time);
+
+        let usage = if self.measured {
+            self.system.global_cpu_usage().clamp(0.0, 100.0) / 100.0
+        } else {
+            0.0
+        };
+        self.history.push_back(usage);
+        while self.history.len() > capacity.max(1) {
+            self.history.pop_front();
+        }
+    }
+
+    /// Draws the dashboard to exactly `width` x `height` cells
+    pub(crate) fn render(&self, width: usize, height: usize) -> String {
+        let mut rows: Vec<String> = Vec::with_capacity(height);
+
+        // Per-core bars, split into columns when there are many cores
+        let cpus = self.system.cpus();
+        let core_rows = height.saturating_sub(9).max(1);
+        let columns = cpus.len().div_ceil(core_rows).max(1);
+        let column_width = width / columns;
+        rows.push(Self::heading("CPU", width));
+        for row in 0..cpus.len().min(core_rows) {
+            let mut line = String::new();
+            for (index, cpu) in cpus.iter().enumerate().skip(row).step_by(core_rows) {
+                let usage = if self.measured {
+                    cpu.cpu_usage().clamp(0.0, 100.0)
+                } else {
+                    0.0
+                };
+                let label = format!("{:>3} ", index);
+                let value = format!(" {:>3.0}%", usage);
+                let bar_width = column_width.saturating_sub(label.len() + value.len() + 2);
+                line.push_str(&text::pad(
+                    &format!("{}{}{} ", label, Self::bar(usage / 100.0, bar_width), value),
+                    column_width,
+                ));
+            }
+            rows.push(line);
+        }
+
+        // Memory and swap
+        rows.push(Self::heading("MEMORY", width));
+        rows.push(Self::usage_line(
+            "RAM ",
+            self.system.used_memory(),
+            self.system.total_memory(),
+            width,
+        ));
+        rows.push(Self::usage_line(
+            "SWAP",
+            self.system.used_swap(),
+            self.system.total_swap(),
+            width,
+        ));
+
+        // Load sparkline fills whatever height is left
+        let load = System::load_average();
+        rows.push(Self::heading(
+            &format!(
+                "LOAD {:.2} {:.2} {:.2}",
+                load.one, load.five, load.fifteen
+            ),
+            width,
+        ));
+        let spark_height = height.saturating_sub(rows.len()).max(1);
+        rows.extend(self.sparkline(width, spark_height));
+
+        let mut output = String::with_capacity(width * height * 3);
+        for row in rows.iter().take(height) {
+            output.push_str(&text::pad(row, width));
+            output.push('\n');
+        }
+        for _ in rows.len()..height {
+            output.push_str(&" ".repeat(width));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Draws the most recent samples right-aligned, newest on the right
+    fn sparkline(&self, width: usize, height: usize) -> Vec<String> {
+        let samples: Vec<f32> = self
+            .history
+            .iter()
+            .skip(self.history.len().saturating_sub(width))
+            .copied()
+            .collect();
+        let padding = width - samples.len();
+
+        (0..height)
+            .map(|row| {
+                // Eighths of a cell that are filled at and above this row
+                let floor = (height - 1 - row) * 8;
+                let mut line = " ".repeat(padding);
+                for &value in &samples {
+                    let eighths = ((value * (height * 8) as f32).round() as usize).max(1);
+                    line.push(SPARK_LEVELS[eighths.saturating_sub(floor).min(8)]);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Formats a used/total line with a bar and GiB figures
+    fn usage_line(label: &str, used: u64, total: u64, width: usize) -> String {
+        const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+        let fraction = if total == 0 {
+            0.0
+        } else {
+            used as f32 / total as f32
+        };
+        let value = format!(
+            " {:>5.1}/{:.1} GiB {:>3.0}%",
+            used as f64 / GIB,
+            total as f64 / GIB,
+            fraction * 100.0
+        );
+        let bar_width = width.saturating_sub(label.len() + value.len() + 1);
+        format!("{} {}{}", label, Self::bar(fraction, bar_width), value)
+    }
+
+    /// Draws a horizontal bar `width` cells wide
+    fn bar(fraction: f32, width: usize) -> String {
+        let filled = ((fraction.clamp(0.0, 1.0) * width as f32).round() as usize).min(width);
+        format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+    }
+
+    /// Draws a section heading rule
+    fn heading(title: &str, width: usize) -> String {
+        format!("── {} {}", title, "─".repeat(width))
+    }
+}
@@ -0,0 +1,59 @@
+//! Width-aware text layout for demo art
+//!
+//! Art is laid out in terminal columns rather than characters: box drawing
+//! and block glyphs take one column, but CJK text and most emoji take two.
+//! These helpers clip, pad and center lines by display width, and leave a
+//! line or block that is larger than its space clipped instead of
+//! underflowing.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Returns the longest start of `line` that fits in `width` columns. A wide
+/// glyph that would straddle the edge is left out, so the result may be a
+/// column short.
+pub fn clip(line: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, grapheme) in line.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &line[..index];
+        }
+    }
+    line
+}
+
+/// Clips `line` to `width` columns and pads it with spaces to exactly that
+/// width
+pub fn pad(line: &str, width: usize) -> String {
+    let line = clip(line, width);
+    format!("{}{}", line, " ".repeat(width - line.width()))
+}
+
+/// Clips `line` to `width` columns and centers it in exactly that width,
+/// the extra column on the right when the space doesn't split evenly
+pub fn center(line: &str, width: usize) -> String {
+    let line = clip(line, width);
+    let left = (width - line.width()) / 2;
+    let right = width - left - line.width();
+    format!("{}{}{}", " ".repeat(left), line, " ".repeat(right))
+}
+
+/// Centers `lines` in a `width` by `height` block, each of its lines ending
+/// with a newline. Lines too wide are clipped on the right; a block too tall
+/// keeps its middle lines.
+pub fn center_block(lines: &[&str], width: usize, height: usize) -> String {
+    let top = height.saturating_sub(lines.len()) / 2;
+    let skip = lines.len().saturating_sub(height) / 2;
+    let mut output = String::with_capacity((width + 1) * height);
+    for y in 0..height {
+        let line = y
+            .checked_sub(top)
+            .and_then(|i| lines.get(skip + i))
+            .copied()
+            .unwrap_or("");
+        output.push_str(&center(line, width));
+        output.push('\n');
+    }
+    output
+}
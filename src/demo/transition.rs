@@ -0,0 +1,150 @@
+//! Content transitions between demo arts
+//!
+//! When a playlist or favorite switches to different art, the new text can
+//! replace the old one gradually instead of all at once. A transition maps
+//! a progress between 0 and 1 to a frame mixing the outgoing and incoming
+//! art, cell by cell.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long a transition between arts lasts
+pub const ART_TRANSITION_DURATION: Duration = Duration::from_millis(1200);
+
+/// How incoming demo art replaces the outgoing art
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArtTransition {
+    /// Swap the whole frame at once
+    Cut,
+    /// Flip cells to the new art one by one in scattered order
+    #[default]
+    Dissolve,
+    /// Replace the frame line by line from the top
+    Wipe,
+    /// Clear the old art and type the new art out character by character
+    Typewriter,
+}
+
+impl ArtTransition {
+    /// Returns all transitions
+    pub fn all() -> &'static [ArtTransition] {
+        &[
+            ArtTransition::Cut,
+            ArtTransition::Dissolve,
+            ArtTransition::Wipe,
+            ArtTransition::Typewriter,
+        ]
+    }
+
+    /// Returns the name used for this transition on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtTransition::Cut => "cut",
+            ArtTransition::Dissolve => "dissolve",
+            ArtTransition::Wipe => "wipe",
+            ArtTransition::Typewriter => "typewriter",
+        }
+    }
+
+    /// Builds the frame shown `progress` (0 to 1) of the way from `from` to
+    /// `to`
+    pub fn frame(&self, from: &str, to: &str, progress: f64) -> String {
+        let progress = progress.clamp(0.0, 1.0);
+        if progress >= 1.0 || *self == ArtTransition::Cut {
+            return to.to_string();
+        }
+
+        let from: Vec<Vec<char>> = from.lines().map(|line| line.chars().collect()).collect();
+        let to: Vec<Vec<char>> = to.lines().map(|line| line.chars().collect()).collect();
+        let rows = from.len().max(to.len());
+        let cell = |grid: &[Vec<char>], row: usize, col: usize| {
+            grid.get(row)
+                .and_then(|line| line.get(col))
+                .copied()
+                .unwrap_or(' ')
+        };
+        let width = |row: usize| {
+            let len = |grid: &[Vec<char>]| grid.get(row).map_or(0, Vec::len);
+            len(&from).max(len(&to))
+        };
+
+        // Characters of the new art typed so far, in reading order
+        let mut typed = match self {
+            ArtTransition::Typewriter => {
+                let total: usize = to.iter().map(Vec::len).sum();
+                (total as f64 * progress) as usize
+            }
+            _ => 0,
+        };
+
+        let mut lines = Vec::with_capacity(rows);
+        for row in 0..rows {
+            let line: String = match self {
+                ArtTransition::Wipe if (row as f64) < rows as f64 * progress => to
+                    .get(row)
+                    .map(|line| line.iter().collect())
+                    .unwrap_or_default(),
+                ArtTransition::Wipe => from
+                    .get(row)
+                    .map(|line| line.iter().collect())
+                    .unwrap_or_default(),
+                ArtTransition::Typewriter => {
+                    let len = to.get(row).map_or(0, Vec::len);
+                    let shown = typed.min(len);
+                    typed -= shown;
+                    to.get(row)
+                        .map(|line| line[..shown].iter().collect())
+                        .unwrap_or_default()
+                }
+                _ => (0..width(row))
+                    .map(|col| {
+                        if dissolve_threshold(row, col) < progress {
+                            cell(&to, row, col)
+                        } else {
+                            cell(&from, row, col)
+                        }
+                    })
+                    .collect(),
+            };
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for ArtTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ArtTransition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArtTransition::all()
+            .iter()
+            .find(|transition| transition.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = ArtTransition::all().iter().map(|t| t.as_str()).collect();
+                format!(
+                    "Invalid art transition '{}'. Valid transitions: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Progress at which a cell flips during a dissolve, scattered evenly over
+/// [0, 1) by hashing its position
+fn dissolve_threshold(row: usize, col: usize) -> f64 {
+    let mut hash = (row as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (col as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^= hash >> 33;
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,405 @@
+//! Diff-aware coloring
+//!
+//! With `--diff`, the lines of unified diffs are colored by what they are:
+//! added lines with a green gradient, removed lines with a red one, and
+//! unchanged context with the theme dimmed, so changes stand out while the
+//! pattern still flows through them. A classifier walks the input a line at
+//! a time and follows hunk headers, so `+` and `-` lines only count inside
+//! hunks; anything that isn't part of a diff, such as the commit messages of
+//! `git log -p`, keeps the normal theme.
+//!
+//! Each kind of line is drawn with a [`Palette`] built from a rule. The
+//! built-in rules can be overridden by `~/.config/chromacat/diff.yaml` or a
+//! file given with `--diff-rules`, where every rule is optional:
+//!
+//! ```yaml
+//! added:
+//!   theme: matrix
+//! removed:
+//!   colors: [[0.6, 0.1, 0.1], [1.0, 0.4, 0.3]]
+//! context:
+//!   brightness: 0.4
+//!   saturation: 0.5
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::{ChromaCatError, Result};
+use crate::escape::strip_sgr;
+use crate::pattern::color::FACTOR_RANGE;
+use crate::pattern::{ColorAdjust, Palette};
+use crate::playlist::get_config_dir;
+use crate::themes::{self, ColorStop, Distribution, Easing, Repeat, RepeatMode, ThemeDefinition};
+
+/// Diff rules filename in the config directory
+pub const DIFF_RULES_FILE: &str = "diff.yaml";
+
+/// Returns the path to the user's diff rules file
+pub fn get_diff_rules_path() -> PathBuf {
+    get_config_dir().join(DIFF_RULES_FILE)
+}
+
+/// What a line of a diff is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Line added by a hunk
+    Added,
+    /// Line removed by a hunk
+    Removed,
+    /// Unchanged line inside a hunk
+    Context,
+    /// Hunk header, such as `@@ -1,4 +1,5 @@`
+    Hunk,
+    /// File header, such as `diff --git`, `index`, `---`, or `+++`
+    Header,
+    /// Anything outside a diff
+    Other,
+}
+
+impl LineKind {
+    /// Returns a list of all line kinds
+    pub fn all() -> &'static [LineKind] {
+        &[
+            LineKind::Added,
+            LineKind::Removed,
+            LineKind::Context,
+            LineKind::Hunk,
+            LineKind::Header,
+            LineKind::Other,
+        ]
+    }
+
+    /// Get string representation of the line kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineKind::Added => "added",
+            LineKind::Removed => "removed",
+            LineKind::Context => "context",
+            LineKind::Hunk => "hunk",
+            LineKind::Header => "header",
+            LineKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for LineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Classifies the lines of a diff in order, keeping track of the hunk it
+/// is in
+#[derive(Debug, Clone, Default)]
+pub struct DiffClassifier {
+    /// Lines of the old file left in the current hunk
+    old_left: u64,
+    /// Lines of the new file left in the current hunk
+    new_left: u64,
+}
+
+impl DiffClassifier {
+    /// Creates a classifier that starts outside any diff
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classifies the next line, ignoring any color escapes in it
+    pub fn classify(&mut self, line: &str) -> LineKind {
+        let line = strip_sgr(line);
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if self.old_left > 0 || self.new_left > 0 {
+            match line.chars().next() {
+                Some('+') if self.new_left > 0 => {
+                    self.new_left -= 1;
+                    return LineKind::Added;
+                }
+                Some('-') if self.old_left > 0 => {
+                    self.old_left -= 1;
+                    return LineKind::Removed;
+                }
+                // Some tools drop the space of empty context lines
+                Some(' ') | None => {
+                    self.old_left = self.old_left.saturating_sub(1);
+                    self.new_left = self.new_left.saturating_sub(1);
+                    return LineKind::Context;
+                }
+                // "\ No newline at end of file"
+                Some('\\') => return LineKind::Header,
+                _ => {
+                    self.old_left = 0;
+                    self.new_left = 0;
+                }
+            }
+        }
+
+        if let Some((old, new)) = parse_hunk_header(line) {
+            self.old_left = old;
+            self.new_left = new;
+            return LineKind::Hunk;
+        }
+        // "\ No newline at end of file" follows the hunk's last line
+        const HEADERS: &[&str] = &["diff ", "index ", "--- ", "+++ ", "\\ "];
+        if HEADERS.iter().any(|prefix| line.starts_with(prefix)) {
+            return LineKind::Header;
+        }
+        LineKind::Other
+    }
+}
+
+/// Reads the old and new line counts from a hunk header such as
+/// `@@ -12,7 +12,8 @@ fn main()`; a missing count means one line
+fn parse_hunk_header(line: &str) -> Option<(u64, u64)> {
+    let ranges = line.strip_prefix("@@ ")?;
+    let ranges = &ranges[..ranges.find(" @@")?];
+    let (old, new) = ranges.split_once(' ')?;
+    let count = |range: &str, sign: char| -> Option<u64> {
+        let range = range.strip_prefix(sign)?;
+        match range.split_once(',') {
+            Some((start, count)) => {
+                start.parse::<u64>().ok()?;
+                count.parse().ok()
+            }
+            None => range.parse::<u64>().ok().map(|_| 1),
+        }
+    };
+    Some((count(old, '-')?, count(new, '+')?))
+}
+
+/// Classifies every line of a text, as split on newlines
+pub fn classify_lines(text: &str) -> Vec<LineKind> {
+    let mut classifier = DiffClassifier::new();
+    text.split('\n').map(|line| classifier.classify(line)).collect()
+}
+
+/// How one kind of line is colored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiffRule {
+    /// Theme to draw with instead of the selected one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Gradient colors to draw with, as `[r, g, b]` from 0 to 1; these win
+    /// over `theme`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<ColorStop>,
+    /// Brightness factor on top of the theme's colors
+    pub brightness: f64,
+    /// Saturation factor on top of the theme's colors
+    pub saturation: f64,
+}
+
+impl Default for DiffRule {
+    /// Leaves lines colored like the rest of the text
+    fn default() -> Self {
+        Self {
+            theme: None,
+            colors: Vec::new(),
+            brightness: 1.0,
+            saturation: 1.0,
+        }
+    }
+}
+
+impl DiffRule {
+    /// Creates a rule drawing with a gradient through `colors`
+    fn with_colors(colors: &[[f32; 3]]) -> Self {
+        Self {
+            colors: colors
+                .iter()
+                .map(|&[r, g, b]| ColorStop {
+                    r,
+                    g,
+                    b,
+                    position: None,
+                    name: None,
+                })
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Checks the theme and factors, naming the `kind` of line in errors
+    fn validate(&self, kind: LineKind) -> Result<()> {
+        if let Some(theme) = &self.theme {
+            themes::get_theme(theme)?;
+        }
+        let (min, max) = FACTOR_RANGE;
+        for (name, value) in [("brightness", self.brightness), ("saturation", self.saturation)] {
+            if !(min..=max).contains(&value) {
+                return Err(ChromaCatError::InputError(format!(
+                    "Diff rule {} for {} lines must be between {} and {}",
+                    name, kind, min, max
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the palette for the rule, or None if lines keep the normal
+    /// colors
+    fn palette(&self, kind: LineKind) -> Result<Option<Palette>> {
+        let theme = if !self.colors.is_empty() {
+            Some(ThemeDefinition {
+                name: format!("diff-{}", kind),
+                desc: format!("Colors of {} diff lines", kind),
+                colors: self.colors.clone(),
+                dist: Distribution::Even,
+                repeat: Repeat::Named(RepeatMode::None),
+                speed: 1.0,
+                ease: Easing::Linear,
+                light: None,
+                dark: None,
+                author: None,
+                tags: Vec::new(),
+                license: None,
+            })
+        } else {
+            self.theme.as_deref().map(themes::get_theme).transpose()?
+        };
+        let adjust = ColorAdjust {
+            brightness: self.brightness,
+            saturation: self.saturation,
+            ..ColorAdjust::default()
+        };
+        if theme.is_none() && adjust.is_identity() {
+            return Ok(None);
+        }
+        Palette::new(theme.as_ref(), adjust).map(Some)
+    }
+}
+
+/// How each kind of diff line is colored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiffRules {
+    /// Added lines
+    pub added: DiffRule,
+    /// Removed lines
+    pub removed: DiffRule,
+    /// Unchanged lines inside hunks
+    pub context: DiffRule,
+    /// Hunk headers
+    pub hunk: DiffRule,
+    /// File headers
+    pub header: DiffRule,
+}
+
+impl Default for DiffRules {
+    /// Green additions, red removals, dimmed context, and blue hunk headers
+    fn default() -> Self {
+        Self {
+            added: DiffRule::with_colors(&[[0.1, 0.55, 0.2], [0.3, 0.85, 0.35], [0.65, 1.0, 0.55]]),
+            removed: DiffRule::with_colors(&[[0.6, 0.08, 0.1], [0.9, 0.25, 0.2], [1.0, 0.5, 0.45]]),
+            context: DiffRule {
+                brightness: 0.55,
+                ..DiffRule::default()
+            },
+            hunk: DiffRule::with_colors(&[[0.25, 0.55, 0.9], [0.45, 0.8, 1.0]]),
+            header: DiffRule::default(),
+        }
+    }
+}
+
+impl DiffRules {
+    /// Loads rules from a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ChromaCatError::InputError(format!(
+                "Failed to read diff rules {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        contents.parse()
+    }
+
+    /// Loads the user's rules from the config directory, or the built-in
+    /// rules if there are none
+    pub fn load_default() -> Result<Self> {
+        let path = get_diff_rules_path();
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Returns the rule for a kind of line, if it has one
+    pub fn rule(&self, kind: LineKind) -> Option<&DiffRule> {
+        match kind {
+            LineKind::Added => Some(&self.added),
+            LineKind::Removed => Some(&self.removed),
+            LineKind::Context => Some(&self.context),
+            LineKind::Hunk => Some(&self.hunk),
+            LineKind::Header => Some(&self.header),
+            LineKind::Other => None,
+        }
+    }
+
+    /// Builds the palette of every kind of line
+    pub fn palettes(&self) -> Result<DiffPalettes> {
+        let mut palettes = Vec::new();
+        for &kind in LineKind::all() {
+            if let Some(palette) = self.rule(kind).map(|rule| rule.palette(kind)).transpose()? {
+                palettes.extend(palette.map(|palette| (kind, Arc::new(palette))));
+            }
+        }
+        Ok(DiffPalettes { palettes })
+    }
+}
+
+impl FromStr for DiffRules {
+    type Err = ChromaCatError;
+
+    fn from_str(contents: &str) -> std::result::Result<Self, Self::Err> {
+        let rules: DiffRules = serde_yaml::from_str(contents)
+            .map_err(|e| ChromaCatError::InputError(format!("Invalid diff rules: {}", e)))?;
+        for &kind in LineKind::all() {
+            if let Some(rule) = rules.rule(kind) {
+                rule.validate(kind)?;
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// Palettes for the kinds of diff line that aren't drawn normally
+#[derive(Debug, Clone, Default)]
+pub struct DiffPalettes {
+    /// Palette of each kind of line that has one
+    palettes: Vec<(LineKind, Arc<Palette>)>,
+}
+
+impl DiffPalettes {
+    /// Returns the palette for a kind of line, or None to draw it normally
+    pub fn get(&self, kind: LineKind) -> Option<&Arc<Palette>> {
+        self.palettes
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, palette)| palette)
+    }
+
+    /// Returns the palette of every line of a text, as split on newlines
+    pub fn for_text(&self, text: &str) -> Vec<Option<Arc<Palette>>> {
+        self.for_text_from(text, &mut DiffClassifier::new())
+    }
+
+    /// Returns the palette of every line of a text that continues a diff
+    /// `classifier` has followed so far, leaving it at the end of the text
+    pub fn for_text_from(
+        &self,
+        text: &str,
+        classifier: &mut DiffClassifier,
+    ) -> Vec<Option<Arc<Palette>>> {
+        text.split('\n')
+            .map(|line| self.get(classifier.classify(line)).cloned())
+            .collect()
+    }
+}
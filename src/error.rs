@@ -2,6 +2,20 @@ use crate::renderer::RendererError;
 use std::fmt;
 use std::io;
 
+/// Exit code for an error of no particular kind
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Exit code for a command line that doesn't make sense, such as an unknown
+/// pattern or flags that can't go together; clap uses it for parse errors
+pub const EXIT_USAGE: i32 = 2;
+
+/// Exit code for input that can't be read, or is empty with
+/// `--fail-on-empty`
+pub const EXIT_INPUT: i32 = 3;
+
+/// Exit code for a terminal that can't be drawn to or set up
+pub const EXIT_TERMINAL: i32 = 4;
+
 /// Custom error types for ChromaCat
 #[derive(Debug)]
 pub enum ChromaCatError {
@@ -38,6 +52,29 @@ pub enum ChromaCatError {
     Other(String),
     /// Invalid art type specified
     InvalidArt(String),
+    /// Options that don't make sense together
+    UsageError(String),
+    /// Terminal can't be set up or drawn to
+    TerminalError(String),
+}
+
+impl ChromaCatError {
+    /// Returns the process exit code reporting this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::InvalidParameter { .. }
+            | Self::InvalidTheme(_)
+            | Self::GradientError(_)
+            | Self::PatternError { .. }
+            | Self::ParseError(_)
+            | Self::InvalidPattern(_)
+            | Self::InvalidArt(_)
+            | Self::UsageError(_) => EXIT_USAGE,
+            Self::IoError(_) | Self::InputError(_) | Self::PlaylistError(_) => EXIT_INPUT,
+            Self::RenderError(_) | Self::TerminalError(_) => EXIT_TERMINAL,
+            Self::Other(_) => EXIT_FAILURE,
+        }
+    }
 }
 
 impl std::error::Error for ChromaCatError {}
@@ -78,6 +115,8 @@ impl fmt::Display for ChromaCatError {
             Self::PlaylistError(msg) => write!(f, "Playlist error: {}", msg),
             Self::Other(msg) => write!(f, "{}", msg),
             Self::InvalidArt(msg) => write!(f, "Invalid art type: {}", msg),
+            Self::UsageError(msg) => write!(f, "{}", msg),
+            Self::TerminalError(msg) => write!(f, "Terminal error: {}", msg),
         }
     }
 }
@@ -116,7 +155,7 @@ impl From<RendererError> for ChromaCatError {
     fn from(err: RendererError) -> Self {
         match err {
             RendererError::IoError(e) => Self::IoError(e),
-            RendererError::TerminalError(msg) => Self::RenderError(msg),
+            RendererError::TerminalError(msg) => Self::TerminalError(msg),
             RendererError::BufferError(msg) => Self::RenderError(msg),
             RendererError::InvalidConfig(msg) => Self::RenderError(msg),
             RendererError::PatternError(msg) => Self::PatternError {
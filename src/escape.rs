@@ -0,0 +1,146 @@
+//! Escape sequence tokenizer for input text
+//!
+//! Input may already contain terminal escape sequences, for example colors from
+//! `ls --color` or OSC 8 hyperlinks from `ls --hyperlink`. This module splits
+//! text into printable runs and escape sequences so ChromaCat can drop existing
+//! SGR color codes (which it replaces with its own) while passing every other
+//! sequence through untouched.
+//!
+//! Recognized sequences:
+//! - CSI: `ESC [` parameters and a final byte (SGR when the final byte is `m`)
+//! - OSC: `ESC ]` terminated by BEL or ST (`ESC \`)
+//! - DCS, SOS, PM and APC strings terminated by ST
+//! - Two-byte escapes such as `ESC 7` or `ESC =`
+
+/// Escape character that introduces every sequence
+const ESC: char = '\x1b';
+/// Bell character, an alternative OSC terminator
+const BEL: char = '\x07';
+
+/// A piece of tokenized input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// Printable text without escape sequences
+    Text(&'a str),
+    /// Select Graphic Rendition sequence (colors and text attributes)
+    Sgr(&'a str),
+    /// Any other escape sequence, which must be preserved verbatim
+    Passthrough(&'a str),
+}
+
+impl<'a> Token<'a> {
+    /// Returns the raw text of the token
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Token::Text(s) | Token::Sgr(s) | Token::Passthrough(s) => s,
+        }
+    }
+}
+
+/// Iterator over the tokens of a string
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Creates a tokenizer over the given input
+    pub fn new(input: &'a str) -> Self {
+        Self { rest: input }
+    }
+
+    /// Returns the byte length of the escape sequence at the start of `s`,
+    /// or None if the sequence is not terminated within `s`.
+    fn sequence_len(s: &str) -> Option<usize> {
+        let mut chars = s.char_indices().skip(1);
+        let (_, introducer) = chars.next()?;
+
+        match introducer {
+            // CSI: parameter and intermediate bytes, then a final byte
+            '[' => chars
+                .find(|(_, c)| ('\x40'..='\x7e').contains(c))
+                .map(|(i, c)| i + c.len_utf8()),
+            // OSC: terminated by BEL or ST
+            ']' => Self::string_len(s, true),
+            // DCS, SOS, PM, APC: terminated by ST
+            'P' | 'X' | '^' | '_' => Self::string_len(s, false),
+            // Two-byte escape
+            c => Some(1 + c.len_utf8()),
+        }
+    }
+
+    /// Returns the length of a control string including its terminator
+    fn string_len(s: &str, allow_bel: bool) -> Option<usize> {
+        let body = &s[2..];
+        let mut iter = body.char_indices().peekable();
+        while let Some((i, c)) = iter.next() {
+            if c == BEL && allow_bel {
+                return Some(2 + i + 1);
+            }
+            if c == ESC && matches!(iter.peek(), Some((_, '\\'))) {
+                return Some(2 + i + 2);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        if !self.rest.starts_with(ESC) {
+            let end = self.rest.find(ESC).unwrap_or(self.rest.len());
+            let (text, rest) = self.rest.split_at(end);
+            self.rest = rest;
+            return Some(Token::Text(text));
+        }
+
+        // Unterminated sequences extend to the end of the input
+        let len = Self::sequence_len(self.rest).unwrap_or(self.rest.len());
+        let (seq, rest) = self.rest.split_at(len);
+        self.rest = rest;
+
+        if seq.starts_with("\x1b[") && seq.ends_with('m') {
+            Some(Token::Sgr(seq))
+        } else {
+            Some(Token::Passthrough(seq))
+        }
+    }
+}
+
+/// Tokenizes a string into text runs and escape sequences
+pub fn tokenize(input: &str) -> Tokenizer<'_> {
+    Tokenizer::new(input)
+}
+
+/// Returns true if the string is an escape sequence rather than printable text
+#[inline]
+pub fn is_escape(s: &str) -> bool {
+    s.starts_with(ESC)
+}
+
+/// Splits off a trailing escape sequence that has not been terminated yet.
+///
+/// Used when input arrives in chunks: the returned tail should be prepended
+/// to the next chunk instead of being tokenized early.
+pub fn split_incomplete(input: &str) -> (&str, &str) {
+    match input.rfind(ESC) {
+        Some(start) if Tokenizer::sequence_len(&input[start..]).is_none() => {
+            input.split_at(start)
+        }
+        _ => (input, ""),
+    }
+}
+
+/// Removes SGR sequences while keeping text and all other escape sequences
+pub fn strip_sgr(input: &str) -> String {
+    tokenize(input)
+        .filter(|token| !matches!(token, Token::Sgr(_)))
+        .map(|token| token.as_str())
+        .collect()
+}
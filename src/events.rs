@@ -0,0 +1,316 @@
+//! Diagnostics, and recent log events kept for the debug overlay
+//!
+//! ChromaCat logs through the `log` facade, printed by `env_logger` when
+//! `RUST_LOG` asks for it. Log output can't be read while an animation
+//! fills the screen, so once capturing starts the most recent events are
+//! also kept in memory, whatever `RUST_LOG` says, for the debug overlay to
+//! show as they happen.
+//!
+//! Without `RUST_LOG`, ChromaCat's own events at the level `--quiet` and
+//! `-v` select are diagnostics for the user, such as `Warning: ...`. While
+//! output is being drawn they are held back, so they can't land in the
+//! middle of it, and printed once it is done; with `--log-file` they are
+//! written to the file as they happen instead. Either way a count of the
+//! warnings and errors ends the run.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Most events kept
+pub const MAX_EVENTS: usize = 64;
+
+/// Most diagnostics held back; later ones are only counted
+pub const MAX_HELD: usize = 256;
+
+/// Whether diagnostics are being held back
+static HOLDING: AtomicBool = AtomicBool::new(false);
+
+/// Diagnostics held back, oldest first
+static HELD: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Diagnostics held back beyond [`MAX_HELD`]
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// File diagnostics are written to, from `--log-file`
+static LOG_FILE: Mutex<Option<(File, String)>> = Mutex::new(None);
+
+/// Diagnostics shown, as a [`Verbosity`]
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Warnings reported so far
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Errors reported so far
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether events are being kept
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+/// Events kept, oldest first
+static EVENTS: Mutex<VecDeque<Event>> = Mutex::new(VecDeque::new());
+
+/// When logging started, which event times count from
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// One logged event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// Time since logging started
+    pub elapsed: Duration,
+    /// Severity
+    pub level: Level,
+    /// Module that logged it
+    pub target: String,
+    /// Formatted message
+    pub message: String,
+}
+
+/// How much ChromaCat says on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Errors only, with `--quiet`
+    Quiet = 0,
+    /// Warnings and errors
+    #[default]
+    Normal = 1,
+    /// What ChromaCat is doing too, with `-v`
+    Verbose = 2,
+    /// Everything, with `-vv`
+    Debug = 3,
+}
+
+impl Verbosity {
+    /// Returns the verbosity `--quiet` and a count of `-v` flags ask for
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+
+    /// Returns the least severe level shown
+    pub fn level(&self) -> LevelFilter {
+        match self {
+            Verbosity::Quiet => LevelFilter::Error,
+            Verbosity::Normal => LevelFilter::Warn,
+            Verbosity::Verbose => LevelFilter::Info,
+            Verbosity::Debug => LevelFilter::Debug,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Verbosity::Quiet,
+            1 => Verbosity::Normal,
+            2 => Verbosity::Verbose,
+            _ => Verbosity::Debug,
+        }
+    }
+}
+
+/// Prints what `RUST_LOG` selects, reports ChromaCat's own events as
+/// diagnostics otherwise, and keeps events while capturing
+struct CaptureLogger {
+    /// Logger printing to stderr
+    inner: env_logger::Logger,
+    /// Whether `RUST_LOG` chose what to print
+    from_env: bool,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        is_capturing() || self.inner.enabled(metadata) || metadata.level() <= verbosity().level()
+    }
+
+    fn log(&self, record: &Record) {
+        let own = record.target().starts_with("chromacat");
+        if self.inner.matches(record) {
+            self.inner.log(record);
+            if own {
+                count(record.level());
+            }
+        } else if own && !self.from_env && record.level() <= verbosity().level() {
+            report(record.level(), &record.args().to_string());
+        }
+        if is_capturing() && record.level() <= Level::Debug {
+            push(Event {
+                elapsed: START.get_or_init(Instant::now).elapsed(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs the logger; call once at startup in place of `env_logger::init`
+pub fn init() {
+    START.get_or_init(Instant::now);
+    let from_env = std::env::var_os(env_logger::DEFAULT_FILTER_ENV).is_some();
+    let inner = env_logger::Builder::from_default_env().build();
+    let filter = inner.filter();
+    if log::set_boxed_logger(Box::new(CaptureLogger { inner, from_env })).is_ok() {
+        log::set_max_level(filter.max(verbosity().level()));
+    }
+}
+
+/// Shows diagnostics at `verbosity` from now on
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::SeqCst);
+    if log::max_level() < verbosity.level() {
+        log::set_max_level(verbosity.level());
+    }
+}
+
+/// Returns which diagnostics are shown
+pub fn verbosity() -> Verbosity {
+    Verbosity::from_u8(VERBOSITY.load(Ordering::Relaxed))
+}
+
+/// Writes diagnostics to the file at `path` from now on, instead of
+/// printing them
+pub fn set_log_file(path: &Path) -> io::Result<()> {
+    let file = File::create(path)?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some((file, path.display().to_string()));
+    Ok(())
+}
+
+/// Holds diagnostics back until [`release`] or [`finish`], so they don't
+/// land in the middle of output
+pub fn hold() {
+    HOLDING.store(true, Ordering::SeqCst);
+}
+
+/// Stops holding diagnostics back, returning those held so far
+pub fn release() -> Vec<String> {
+    HOLDING.store(false, Ordering::SeqCst);
+    std::mem::take(&mut *HELD.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Shows a diagnostic at `level`: written to the log file if there is one,
+/// held back while holding, and printed to stderr otherwise
+pub fn report(level: Level, message: &str) {
+    count(level);
+    let line = match level {
+        Level::Error => format!("Error: {}", message),
+        Level::Warn => format!("Warning: {}", message),
+        _ => message.to_string(),
+    };
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((file, _)) = log_file.as_mut() {
+        let _ = writeln!(file, "{}", line);
+    } else if HOLDING.load(Ordering::SeqCst) {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        if held.len() < MAX_HELD {
+            held.push(line);
+        } else {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+/// Counts a warning or error towards the summary
+fn count(level: Level) {
+    match level {
+        Level::Error => ERRORS.fetch_add(1, Ordering::Relaxed),
+        Level::Warn => WARNINGS.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+/// Returns the numbers of warnings and errors reported so far
+pub fn counts() -> (usize, usize) {
+    (
+        WARNINGS.load(Ordering::Relaxed),
+        ERRORS.load(Ordering::Relaxed),
+    )
+}
+
+/// Returns the line summing up `warnings` and `errors`, if there were any,
+/// pointing at the log file they went to
+pub fn summary(warnings: usize, errors: usize, log_file: Option<&str>) -> Option<String> {
+    let plural = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+    let mut parts = Vec::new();
+    if warnings > 0 {
+        parts.push(plural(warnings, "warning"));
+    }
+    if errors > 0 {
+        parts.push(plural(errors, "error"));
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(match log_file {
+        Some(path) => format!("chromacat: {} (see {})", parts.join(", "), path),
+        None => format!("chromacat: {}", parts.join(", ")),
+    })
+}
+
+/// Prints the diagnostics held back and the summary; call once output is
+/// done and the terminal is restored
+pub fn finish() {
+    for line in release() {
+        eprintln!("{}", line);
+    }
+    let dropped = DROPPED.swap(0, Ordering::Relaxed);
+    if dropped > 0 {
+        eprintln!("... and {} more", dropped);
+    }
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    let path = log_file.as_mut().map(|(file, path)| {
+        let _ = file.flush();
+        path.clone()
+    });
+    let (warnings, errors) = counts();
+    if verbosity() > Verbosity::Quiet {
+        if let Some(summary) = summary(warnings, errors, path.as_deref()) {
+            eprintln!("{}", summary);
+        }
+    }
+}
+
+/// Starts keeping events, including debug events `RUST_LOG` leaves out
+pub fn start_capture() {
+    CAPTURING.store(true, Ordering::SeqCst);
+    if log::max_level() < LevelFilter::Debug {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+/// Returns whether events are being kept
+pub fn is_capturing() -> bool {
+    CAPTURING.load(Ordering::Relaxed)
+}
+
+/// Keeps an event, dropping the oldest beyond [`MAX_EVENTS`]
+pub fn push(event: Event) {
+    let mut events = EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    if events.len() == MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(event);
+}
+
+/// Returns up to `count` of the latest events, oldest first
+pub fn recent(count: usize) -> Vec<Event> {
+    let events = EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    events
+        .iter()
+        .skip(events.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
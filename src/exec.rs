@@ -0,0 +1,234 @@
+//! Command runner for ChromaCat
+//!
+//! This module implements `chromacat exec`, which runs a command inside a
+//! pseudo-terminal and colorizes its output as it is produced. Because the
+//! child sees a real terminal it keeps its own interactive behavior (progress
+//! bars, prompts, line editing), while keystrokes are forwarded to it from our
+//! own terminal.
+
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use log::{debug, info};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::{LightMap, PatternConfig, PostProcess};
+use crate::streaming::StreamingInput;
+
+/// Size of the buffer used when reading from the pseudo-terminal
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// How often to check for child exit and terminal resizes
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs a command in a pseudo-terminal and colorizes its output
+pub struct CommandRunner {
+    /// Program followed by its arguments
+    argv: Vec<String>,
+    /// Colorizer shared with streaming mode
+    processor: StreamingInput,
+    /// Whether to forward our stdin to the child
+    forward_input: bool,
+}
+
+impl CommandRunner {
+    /// Creates a new runner for the given command line
+    ///
+    /// # Arguments
+    /// * `argv` - Program followed by its arguments
+    /// * `config` - Pattern configuration for color generation
+    /// * `theme_name` - Name of the color theme to use
+    pub fn new(argv: Vec<String>, config: PatternConfig, theme_name: &str) -> Result<Self> {
+        if argv.is_empty() {
+            return Err(ChromaCatError::InputError(
+                "exec requires a command to run".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            argv,
+            processor: StreamingInput::new(config, theme_name)?,
+            forward_input: atty::is(atty::Stream::Stdin),
+        })
+    }
+
+    /// Enables or disables color output
+    pub fn set_colors_enabled(&mut self, enabled: bool) {
+        self.processor.set_colors_enabled(enabled);
+    }
+
+    /// Sets the value post-processing applied before gradient lookup
+    pub fn set_postprocess(&mut self, postprocess: PostProcess) {
+        self.processor.set_postprocess(postprocess);
+    }
+
+    /// Sets the second theme that shades colors
+    pub fn set_light_map(&mut self, light: Option<LightMap>) {
+        self.processor.set_light_map(light);
+    }
+
+    /// Runs the command to completion, writing colorized output to stdout
+    ///
+    /// # Returns
+    /// The child's exit code
+    pub fn run(&mut self) -> Result<i32> {
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(Self::terminal_size())
+            .map_err(|e| ChromaCatError::Other(format!("Failed to open pty: {}", e)))?;
+
+        let mut cmd = CommandBuilder::new(&self.argv[0]);
+        cmd.args(&self.argv[1..]);
+        cmd.cwd(std::env::current_dir()?);
+
+        info!("Spawning command in pty: {:?}", self.argv);
+        let mut child = pair.slave.spawn_command(cmd).map_err(|e| {
+            ChromaCatError::Other(format!("Failed to run '{}': {}", self.argv[0], e))
+        })?;
+        // Close our copy of the slave so reads see EOF once the child exits
+        drop(pair.slave);
+
+        let output = Self::spawn_reader(pair.master.as_ref())?;
+
+        if self.forward_input {
+            let writer = pair
+                .master
+                .take_writer()
+                .map_err(|e| ChromaCatError::Other(format!("Failed to open pty input: {}", e)))?;
+            Self::spawn_input_forwarder(writer);
+            enable_raw_mode()?;
+        }
+
+        let result = self.pump_output(&output, pair.master.as_ref(), child.as_mut());
+
+        if self.forward_input {
+            disable_raw_mode()?;
+        }
+
+        let status = result?;
+        debug!("Command finished: {}", status);
+        Ok(status.exit_code() as i32)
+    }
+
+    /// Copies output to stdout until the child exits and its output is drained
+    fn pump_output(
+        &mut self,
+        output: &Receiver<Vec<u8>>,
+        master: &dyn MasterPty,
+        child: &mut (dyn portable_pty::Child + Send + Sync),
+    ) -> Result<portable_pty::ExitStatus> {
+        let mut stdout = io::stdout();
+        let mut carry = Vec::new();
+        let mut size = Self::terminal_size();
+        let mut status = None;
+
+        loop {
+            match output.recv_timeout(POLL_INTERVAL) {
+                Ok(bytes) => {
+                    carry.extend_from_slice(&bytes);
+                    self.process_bytes(&mut carry, &mut stdout)?;
+                }
+                // Reader hit EOF: everything the child wrote has been seen
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    // Some platforms keep the pty open after the child exits
+                    if status.is_some() {
+                        break;
+                    }
+                }
+            }
+
+            if status.is_none() {
+                status = child.try_wait()?;
+            }
+
+            // Propagate terminal resizes to the child
+            let new_size = Self::terminal_size();
+            if new_size != size {
+                size = new_size;
+                let _ = master.resize(size);
+            }
+        }
+
+        // Flush whatever is left, including invalid UTF-8
+        if !carry.is_empty() {
+            let rest = String::from_utf8_lossy(&carry).into_owned();
+            self.processor.process_chunk(&rest, &mut stdout)?;
+        }
+
+        match status {
+            Some(status) => Ok(status),
+            None => Ok(child.wait()?),
+        }
+    }
+
+    /// Colorizes the valid UTF-8 prefix of `carry`, keeping a split code point
+    fn process_bytes<W: Write>(&mut self, carry: &mut Vec<u8>, writer: &mut W) -> Result<()> {
+        let valid = match std::str::from_utf8(carry) {
+            Ok(text) => text.len(),
+            // Invalid bytes in the middle: decode lossily and move on
+            Err(e) if e.error_len().is_some() => carry.len(),
+            Err(e) => e.valid_up_to(),
+        };
+
+        let text = String::from_utf8_lossy(&carry[..valid]).into_owned();
+        carry.drain(..valid);
+        self.processor.process_chunk(&text, writer)
+    }
+
+    /// Reads pty output on a background thread
+    fn spawn_reader(master: &dyn MasterPty) -> Result<Receiver<Vec<u8>>> {
+        let mut reader = master
+            .try_clone_reader()
+            .map_err(|e| ChromaCatError::Other(format!("Failed to read from pty: {}", e)))?;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut buf = [0u8; READ_CHUNK_SIZE];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if tx.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Forwards keystrokes from our stdin to the child on a background thread
+    fn spawn_input_forwarder(mut writer: Box<dyn Write + Send>) {
+        thread::spawn(move || {
+            let mut stdin = io::stdin();
+            let mut buf = [0u8; 1024];
+            loop {
+                match stdin.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if writer.write_all(&buf[..n]).is_err() || writer.flush().is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the current terminal size as a pty size
+    fn terminal_size() -> PtySize {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        PtySize {
+            rows,
+            cols,
+            ..PtySize::default()
+        }
+    }
+}
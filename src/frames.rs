@@ -0,0 +1,179 @@
+//! Raw frame output for external programs
+//!
+//! `--emit-frames FORMAT` turns ChromaCat into a frame source: instead of
+//! drawing text, it samples the pattern on a grid of `--frame-size` cells
+//! every frame and writes the colors to stdout, or to the file or FIFO given
+//! with `--emit-to`, for LED matrix drivers, Processing sketches and other
+//! programs that do their own drawing. Two formats are written:
+//!
+//! - `rgb`: per frame, the width and height as 16-bit big-endian numbers,
+//!   then three bytes per cell, row by row
+//! - `json`: one JSON object per line, e.g.
+//!   `{"frame":0,"time":0.000,"width":2,"height":1,"cells":[[[255,0,0],[0,0,255]]]}`,
+//!   where `cells` holds one array of `[r, g, b]` cells per row
+//!
+//! Output stops without an error when the reader goes away.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::PatternEngine;
+
+/// Largest frame side, in cells
+pub const MAX_FRAME_SIDE: usize = u16::MAX as usize;
+
+/// How frames are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// A small binary header followed by raw RGB bytes
+    Rgb,
+    /// One JSON object per line with the cells as nested arrays
+    Json,
+}
+
+impl FrameFormat {
+    /// Returns a list of all frame formats
+    pub fn all() -> &'static [FrameFormat] {
+        &[FrameFormat::Rgb, FrameFormat::Json]
+    }
+
+    /// Get string representation of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrameFormat::Rgb => "rgb",
+            FrameFormat::Json => "json",
+        }
+    }
+
+    /// Writes `frame`, the `index`th one, in this format
+    pub fn write<W: Write>(&self, frame: &PatternFrame, index: u64, out: &mut W) -> io::Result<()> {
+        match self {
+            FrameFormat::Rgb => frame.write_rgb(out),
+            FrameFormat::Json => frame.write_json(index, out),
+        }
+    }
+}
+
+impl fmt::Display for FrameFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for FrameFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        FrameFormat::all()
+            .iter()
+            .find(|format| format.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| format!("Invalid frame format '{}'. Valid formats: rgb, json", s))
+    }
+}
+
+/// Parses a `COLSxROWS` frame size
+pub fn parse_frame_size(s: &str) -> std::result::Result<(usize, usize), String> {
+    let (width, height) = s
+        .to_lowercase()
+        .split_once('x')
+        .map(|(w, h)| (w.trim().parse::<usize>(), h.trim().parse::<usize>()))
+        .ok_or_else(|| format!("Frame size '{}' must be in format COLSxROWS", s))?;
+    match (width, height) {
+        (Ok(width), Ok(height))
+            if (1..=MAX_FRAME_SIDE).contains(&width) && (1..=MAX_FRAME_SIDE).contains(&height) =>
+        {
+            Ok((width, height))
+        }
+        _ => Err(format!(
+            "Frame size '{}' must have a width and height from 1 to {}",
+            s, MAX_FRAME_SIDE
+        )),
+    }
+}
+
+/// Opens the file or FIFO frames are written to. Opening a FIFO waits until
+/// a reader opens the other end.
+pub fn open_sink(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(|e| {
+            ChromaCatError::InputError(format!("Failed to open {}: {}", path.display(), e))
+        })
+}
+
+/// The pattern's colors on a grid of cells at one moment
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternFrame {
+    /// Width in cells
+    pub width: usize,
+    /// Height in cells
+    pub height: usize,
+    /// Animation time the frame was sampled at
+    pub time: f64,
+    /// Cell colors in row-major order
+    pub cells: Vec<[u8; 3]>,
+}
+
+impl PatternFrame {
+    /// Samples every cell of the engine's grid at its current time
+    pub fn sample(engine: &PatternEngine) -> Result<Self> {
+        let (width, height) = engine.size();
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let value = engine.get_value_at(x, y)?;
+                let color = engine.cell_color(value, engine.light_at(x, y), x, y);
+                cells.push([
+                    (color.r * 255.0) as u8,
+                    (color.g * 255.0) as u8,
+                    (color.b * 255.0) as u8,
+                ]);
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            time: engine.time(),
+            cells,
+        })
+    }
+
+    /// Writes the size header and the raw RGB bytes
+    pub fn write_rgb<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(&(self.width as u16).to_be_bytes())?;
+        out.write_all(&(self.height as u16).to_be_bytes())?;
+        out.write_all(&self.cells.concat())
+    }
+
+    /// Writes the frame as one line of JSON
+    pub fn write_json<W: Write>(&self, index: u64, out: &mut W) -> io::Result<()> {
+        let mut line = String::with_capacity(32 + self.cells.len() * 14);
+        line.push_str(&format!(
+            "{{\"frame\":{},\"time\":{:.3},\"width\":{},\"height\":{},\"cells\":[",
+            index, self.time, self.width, self.height
+        ));
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                line.push(',');
+            }
+            line.push('[');
+            for (x, [r, g, b]) in row.iter().enumerate() {
+                if x > 0 {
+                    line.push(',');
+                }
+                line.push_str(&format!("[{},{},{}]", r, g, b));
+            }
+            line.push(']');
+        }
+        line.push_str("]}\n");
+        out.write_all(line.as_bytes())
+    }
+}
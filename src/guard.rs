@@ -0,0 +1,249 @@
+//! Terminal restoration guarantees
+//!
+//! Animations put the terminal in raw mode, switch to the alternate screen,
+//! and hide the cursor. However a run ends, whether it returns, fails,
+//! panics, or is stopped by SIGINT, SIGTERM, or SIGHUP, the terminal must
+//! come back the way it was, with colors reset.
+//!
+//! Entering and leaving the alternate screen goes through this module so it
+//! knows what to undo. A [`TerminalGuard`] undoes anything left over when it
+//! is dropped, the panic hook does the same before the panic message is
+//! printed, and signals are recorded so loops can stop at the next frame or
+//! line. Runs blocked waiting for input don't get to check, so the terminal
+//! is restored and the process exits after a short grace period.
+
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::terminal::{
+    disable_raw_mode, is_raw_mode_enabled, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::execute;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Once;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// Escape sequence resetting colors and showing the cursor
+const RESET_SEQUENCE: &str = "\x1b[0m\x1b[?25h";
+
+/// Escape sequence leaving the alternate screen
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+/// Escape sequence turning off every mouse reporting mode
+const DISABLE_MOUSE_CAPTURE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1003l\x1b[?1002l\x1b[?1000l";
+
+/// How long a run has to stop on its own after a signal before the process
+/// exits anyway
+#[cfg(unix)]
+pub const GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Whether the alternate screen is active
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether mouse events are being captured
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+/// Number of the first signal received, or 0
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// Write end of the pipe waking the signal thread, or -1
+#[cfg(unix)]
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Restores the terminal when dropped, if anything is left to restore
+#[derive(Debug, Default)]
+pub struct TerminalGuard {
+    _private: (),
+}
+
+impl TerminalGuard {
+    /// Creates a guard for the rest of the current scope
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if needs_restore() {
+            restore_terminal();
+        }
+    }
+}
+
+/// Installs the panic hook and signal handlers; later calls do nothing
+pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        install_panic_hook();
+        install_signal_handlers();
+    });
+}
+
+/// Switches to the alternate screen, unless it is already active
+pub fn enter_alternate_screen<W: Write>(out: &mut W) -> io::Result<()> {
+    if !ALTERNATE_SCREEN.swap(true, Ordering::SeqCst) {
+        execute!(out, EnterAlternateScreen)?;
+    }
+    Ok(())
+}
+
+/// Leaves the alternate screen, if it is active
+pub fn leave_alternate_screen<W: Write>(out: &mut W) -> io::Result<()> {
+    if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+        execute!(out, LeaveAlternateScreen)?;
+    }
+    Ok(())
+}
+
+/// Starts reporting mouse events, unless they already are
+pub fn enable_mouse_capture<W: Write>(out: &mut W) -> io::Result<()> {
+    if !MOUSE_CAPTURE.swap(true, Ordering::SeqCst) {
+        execute!(out, EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Stops reporting mouse events, if they are
+pub fn disable_mouse_capture<W: Write>(out: &mut W) -> io::Result<()> {
+    if MOUSE_CAPTURE.swap(false, Ordering::SeqCst) {
+        execute!(out, DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Returns true if the alternate screen is active
+pub fn in_alternate_screen() -> bool {
+    ALTERNATE_SCREEN.load(Ordering::SeqCst)
+}
+
+/// Returns true if raw mode, the alternate screen or mouse capture are
+/// still active
+pub fn needs_restore() -> bool {
+    in_alternate_screen()
+        || MOUSE_CAPTURE.load(Ordering::SeqCst)
+        || is_raw_mode_enabled().unwrap_or(false)
+}
+
+/// Returns the number of the signal that asked the run to stop, if any
+pub fn interruption() -> Option<i32> {
+    match SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+/// Returns true if a signal asked the run to stop
+#[inline]
+pub fn interrupted() -> bool {
+    SIGNAL.load(Ordering::Relaxed) != 0
+}
+
+/// Leaves the alternate screen and raw mode, stops mouse capture, shows the
+/// cursor, and resets colors. Safe to call at any time, including while
+/// another thread holds the stdout lock.
+pub fn restore_terminal() {
+    let mut sequence = String::from(RESET_SEQUENCE);
+    if MOUSE_CAPTURE.swap(false, Ordering::SeqCst) {
+        sequence.push_str(DISABLE_MOUSE_CAPTURE);
+    }
+    if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+        sequence.push_str(LEAVE_ALTERNATE_SCREEN);
+    }
+    if atty::is(atty::Stream::Stdout) {
+        write_unlocked(sequence.as_bytes());
+    }
+    let _ = disable_raw_mode();
+}
+
+/// Writes straight to the stdout file descriptor, bypassing the lock and
+/// buffer of [`io::stdout`]
+#[cfg(unix)]
+fn write_unlocked(bytes: &[u8]) {
+    // SAFETY: the pointer and length describe a valid, live byte slice
+    unsafe {
+        libc::write(libc::STDOUT_FILENO, bytes.as_ptr().cast(), bytes.len());
+    }
+}
+
+/// Writes to stdout; only the panicking thread writes here, and the stdout
+/// lock is reentrant
+#[cfg(not(unix))]
+fn write_unlocked(bytes: &[u8]) {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(bytes);
+    let _ = stdout.flush();
+}
+
+/// Restores the terminal before the default hook prints the panic, so the
+/// message lands on the normal screen
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        previous(info);
+    }));
+}
+
+/// Records SIGINT, SIGTERM, and SIGHUP, and exits after the grace period if
+/// the run hasn't stopped by then
+#[cfg(unix)]
+fn install_signal_handlers() {
+    let mut fds = [0 as libc::c_int; 2];
+    // SAFETY: `fds` has room for the two descriptors pipe() writes
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return;
+    }
+    let [read_fd, write_fd] = fds;
+    SIGNAL_PIPE.store(write_fd, Ordering::SeqCst);
+
+    let spawned = std::thread::Builder::new()
+        .name("signals".to_string())
+        .spawn(move || {
+            let mut byte = 0u8;
+            loop {
+                // SAFETY: reads one byte into a live local
+                let read = unsafe { libc::read(read_fd, (&mut byte as *mut u8).cast(), 1) };
+                if read == 1 {
+                    break;
+                }
+                if read < 0 && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+
+            std::thread::sleep(GRACE_PERIOD);
+            restore_terminal();
+            std::process::exit(128 + SIGNAL.load(Ordering::SeqCst));
+        });
+    if spawned.is_err() {
+        return;
+    }
+
+    for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        // SAFETY: the handler only touches atomics and calls write(), which
+        // are async-signal-safe
+        unsafe {
+            libc::signal(signal, handle_signal as *const () as libc::sighandler_t);
+        }
+    }
+}
+
+/// Signals can't be caught here; the panic hook still restores the terminal
+#[cfg(not(unix))]
+fn install_signal_handlers() {}
+
+/// Records the signal and wakes the signal thread
+#[cfg(unix)]
+extern "C" fn handle_signal(signal: libc::c_int) {
+    let _ = SIGNAL.compare_exchange(0, signal, Ordering::SeqCst, Ordering::SeqCst);
+    let fd = SIGNAL_PIPE.load(Ordering::SeqCst);
+    if fd >= 0 {
+        let byte = 1u8;
+        // SAFETY: write() is async-signal-safe and `byte` outlives the call
+        unsafe {
+            libc::write(fd, (&byte as *const u8).cast(), 1);
+        }
+    }
+}
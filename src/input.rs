@@ -1,40 +1,349 @@
 use crate::demo::{ArtSettings, DemoArt, DemoArtGenerator};
 use crate::error::Result;
 use crossterm::terminal::size;
+use std::fmt::{self, Write as _};
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, Read};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-/// Handles reading input from either stdin, a file, or demo mode
+/// Byte order mark some editors start UTF-8 files with
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Most bytes decoded at once, so binary data without newlines isn't read
+/// whole
+const MAX_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// Share of control bytes above which a chunk counts as binary
+const BINARY_CONTROL_SHARE: f64 = 0.25;
+
+/// Bytes read from all input so far
+static BYTES_READ: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the number of bytes read from all input so far, for
+/// `--fail-on-empty`
+pub fn bytes_read() -> u64 {
+    BYTES_READ.load(Ordering::Relaxed)
+}
+
+/// What happens to chunks of binary data in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryPolicy {
+    /// Shown like text, with invalid bytes replaced
+    #[default]
+    Show,
+    /// Left out
+    Skip,
+    /// Shown as a hex dump
+    Hex,
+}
+
+impl BinaryPolicy {
+    /// Returns every policy
+    pub fn all() -> &'static [BinaryPolicy] {
+        &[BinaryPolicy::Show, BinaryPolicy::Skip, BinaryPolicy::Hex]
+    }
+
+    /// Returns the name used for this policy on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryPolicy::Show => "show",
+            BinaryPolicy::Skip => "skip",
+            BinaryPolicy::Hex => "hex",
+        }
+    }
+}
+
+impl fmt::Display for BinaryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BinaryPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        BinaryPolicy::all()
+            .iter()
+            .find(|policy| policy.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = BinaryPolicy::all().iter().map(|p| p.as_str()).collect();
+                format!(
+                    "Invalid binary policy '{}'. Valid policies: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Returns whether `bytes` look like binary data rather than text: they
+/// hold a NUL byte, or more than a quarter of them are control bytes other
+/// than whitespace, backspace and escape
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return true;
+    }
+    let controls = bytes
+        .iter()
+        .filter(|&&b| (b < 0x20 && !b"\t\n\r\x08\x0c\x1b".contains(&b)) || b == 0x7f)
+        .count();
+    controls as f64 > bytes.len() as f64 * BINARY_CONTROL_SHARE
+}
+
+/// Formats `bytes`, found `offset` bytes into the input, as a hex dump of
+/// 16 bytes a line the way `hexdump -C` does
+pub fn hex_dump(bytes: &[u8], offset: u64) -> String {
+    let mut dump = String::new();
+    for (index, row) in bytes.chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x} ", offset + index as u64 * 16);
+        for column in 0..16 {
+            if column % 8 == 0 {
+                dump.push(' ');
+            }
+            match row.get(column) {
+                Some(byte) => {
+                    let _ = write!(dump, "{:02x} ", byte);
+                }
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(row.iter().map(|&b| match b {
+            0x20..=0x7e => b as char,
+            _ => '.',
+        }));
+        dump.push_str("|\n");
+    }
+    dump
+}
+
+/// Returns the number of bytes at the end of `bytes` that start a UTF-8
+/// character without finishing it
+fn split_char_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xc0 != 0x80 {
+            let needed = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            return if needed > back { back } else { 0 };
+        }
+    }
+    0
+}
+
+/// Reads input of any encoding as UTF-8 text, a line at a time. A UTF-8
+/// byte order mark is dropped, lines that aren't valid UTF-8 have their
+/// invalid bytes replaced, and binary chunks are shown, skipped or dumped
+/// as hex. Once the input ends, one warning line on stderr says what was
+/// changed, if anything was.
+pub struct DecodedInput<R> {
+    /// Raw input
+    inner: R,
+    /// Name of the input in the warning
+    name: String,
+    /// What happens to binary chunks
+    policy: BinaryPolicy,
+    /// Raw bytes of a character split across chunks, kept for the next
+    raw: Vec<u8>,
+    /// Decoded text of the current chunk
+    text: Vec<u8>,
+    /// Bytes of `text` already read
+    position: usize,
+    /// Raw bytes decoded so far
+    offset: u64,
+    /// Lines that weren't valid UTF-8
+    lossy: usize,
+    /// Binary chunks found
+    binary: usize,
+    /// Whether the input has ended and the warning was given
+    ended: bool,
+}
+
+impl<R: BufRead> DecodedInput<R> {
+    /// Decodes `inner`, named `name` in the warning, showing binary chunks
+    pub fn new(inner: R, name: impl Into<String>) -> Self {
+        Self {
+            inner,
+            name: name.into(),
+            policy: BinaryPolicy::default(),
+            raw: Vec::new(),
+            text: Vec::new(),
+            position: 0,
+            offset: 0,
+            lossy: 0,
+            binary: 0,
+            ended: false,
+        }
+    }
+
+    /// Sets what happens to binary chunks
+    pub fn set_binary_policy(&mut self, policy: BinaryPolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the number of lines so far that weren't valid UTF-8
+    pub fn lossy_lines(&self) -> usize {
+        self.lossy
+    }
+
+    /// Returns the number of binary chunks found so far
+    pub fn binary_chunks(&self) -> usize {
+        self.binary
+    }
+
+    /// Describes what decoding changed, if anything
+    pub fn warning(&self) -> Option<String> {
+        let mut changes = Vec::new();
+        if self.lossy > 0 {
+            changes.push(format!(
+                "{} {} not valid UTF-8, invalid bytes replaced",
+                self.lossy,
+                if self.lossy == 1 {
+                    "line was"
+                } else {
+                    "lines were"
+                }
+            ));
+        }
+        if self.binary > 0 {
+            changes.push(format!(
+                "{} binary {} {}",
+                self.binary,
+                if self.binary == 1 { "chunk" } else { "chunks" },
+                match self.policy {
+                    BinaryPolicy::Show => "shown as text (--binary skip or hex leaves it out)",
+                    BinaryPolicy::Skip => "skipped",
+                    BinaryPolicy::Hex => "shown as hex",
+                }
+            ));
+        }
+        (!changes.is_empty()).then(|| format!("{}: {}", self.name, changes.join("; ")))
+    }
+
+    /// Decodes the next chunk of input into `text`, returning false at the
+    /// end of the input
+    fn decode_next(&mut self) -> io::Result<bool> {
+        let carried = self.raw.len();
+        let read = (&mut self.inner)
+            .take(MAX_CHUNK_BYTES)
+            .read_until(b'\n', &mut self.raw)?;
+        BYTES_READ.fetch_add(read as u64, Ordering::Relaxed);
+        if read == 0 && self.raw.is_empty() {
+            return Ok(false);
+        }
+        if self.offset == 0 && carried == 0 && self.raw.starts_with(UTF8_BOM) {
+            self.raw.drain(..UTF8_BOM.len());
+            self.offset += UTF8_BOM.len() as u64;
+        }
+
+        // A character cut off by the chunk limit waits for the rest of it
+        let split = match read > 0 && !self.raw.ends_with(b"\n") {
+            true => split_char_len(&self.raw),
+            false => 0,
+        };
+        let rest = self.raw.split_off(self.raw.len() - split);
+        let chunk = std::mem::replace(&mut self.raw, rest);
+        let offset = self.offset;
+        self.offset += chunk.len() as u64;
+
+        let text = if is_binary(&chunk) {
+            self.binary += 1;
+            match self.policy {
+                BinaryPolicy::Show => String::from_utf8_lossy(&chunk).into_owned(),
+                BinaryPolicy::Skip => String::new(),
+                BinaryPolicy::Hex => hex_dump(&chunk, offset),
+            }
+        } else {
+            String::from_utf8(chunk).unwrap_or_else(|e| {
+                self.lossy += 1;
+                String::from_utf8_lossy(e.as_bytes()).into_owned()
+            })
+        };
+        self.text = text.into_bytes();
+        self.position = 0;
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Read for DecodedInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<R: BufRead> BufRead for DecodedInput<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position >= self.text.len() {
+            if !self.decode_next()? {
+                if !self.ended {
+                    self.ended = true;
+                    if let Some(warning) = self.warning() {
+                        log::warn!("{}", warning);
+                    }
+                }
+                return Ok(&[]);
+            }
+        }
+        Ok(&self.text[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.text.len());
+    }
+}
+
+/// Handles reading input from either stdin, a file, or demo mode, decoded
+/// into UTF-8 text
 pub struct InputReader {
-    source: Box<dyn BufRead>,
+    source: DecodedInput<Box<dyn BufRead>>,
 }
 
 impl InputReader {
     /// Creates a new InputReader from a file path
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path)?;
+        let file = File::open(path.as_ref())?;
         Ok(Self {
-            source: Box::new(BufReader::new(file)),
+            source: DecodedInput::new(
+                Box::new(BufReader::new(file)),
+                path.as_ref().display().to_string(),
+            ),
         })
     }
 
     /// Creates a new InputReader from stdin
     pub fn from_stdin() -> Result<Self> {
         Ok(Self {
-            source: Box::new(BufReader::new(io::stdin())),
+            source: DecodedInput::new(Box::new(BufReader::new(io::stdin())), "stdin"),
         })
     }
 
     /// Creates a new InputReader in demo mode
+    ///
+    /// `base` carries art options such as rain or a countdown; its size and
+    /// header settings are replaced to fit the terminal.
     pub fn from_demo(
         is_animated: bool,
         art_type: Option<&str>,
         playlist_art: Option<&DemoArt>,
+        base: &ArtSettings,
     ) -> Result<Self> {
         // Get terminal size
         let (width, height) = size()?;
-        let settings = ArtSettings::new(width, height.saturating_sub(2)) // Subtract 2 for status bar
+        let settings = base
+            .clone()
+            .with_size(width, height.saturating_sub(2)) // Subtract 2 for status bar
             .with_headers(!is_animated); // Only show headers in static mode
 
         let generator = DemoArtGenerator::new(settings);
@@ -51,13 +360,19 @@ impl InputReader {
         };
 
         Ok(Self {
-            source: Box::new(DemoInput::new(generator, art_type)),
+            source: DecodedInput::new(Box::new(DemoInput::new(generator, art_type)), "demo"),
         })
     }
 
+    /// Sets what happens to binary chunks in the input
+    pub fn with_binary_policy(mut self, policy: BinaryPolicy) -> Self {
+        self.source.set_binary_policy(policy);
+        self
+    }
+
     /// Returns a mutable reference to the underlying reader
     pub fn reader(&mut self) -> &mut dyn BufRead {
-        &mut *self.source
+        &mut self.source
     }
 
     /// Reads all content into a String
@@ -0,0 +1,132 @@
+//! Structured description of the patterns and their parameters
+//!
+//! Everything the pattern registry knows is exposed here as plain,
+//! serializable data: pattern ids and descriptions, and for each parameter
+//! its name, value type, accepted range or options, and default, along with
+//! the named presets the pattern ships with. Front ends such as editors,
+//! completion scripts, or a web playground can build their controls from
+//! [`introspect`] instead of parsing help text.
+
+use crate::pattern::{ParamType, PatternMetadata, PatternParam, Preset, REGISTRY};
+use serde::Serialize;
+
+/// Everything ChromaCat can describe about itself
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Introspection {
+    /// ChromaCat version the description belongs to
+    pub version: &'static str,
+    /// Patterns, sorted by id
+    pub patterns: Vec<PatternInfo>,
+}
+
+/// A pattern and its parameters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PatternInfo {
+    /// Identifier used with `--pattern`
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// What the pattern draws
+    pub description: String,
+    /// Parameters accepted with `--param`
+    pub params: Vec<ParamInfo>,
+    /// Named parameter sets accepted with `--preset`
+    pub presets: Vec<Preset>,
+}
+
+/// A pattern parameter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamInfo {
+    /// Name used with `--param`
+    pub name: String,
+    /// What the parameter controls
+    pub description: String,
+    /// Value type and accepted values
+    #[serde(flatten)]
+    pub kind: ParamKind,
+    /// Value used when the parameter is not given
+    pub default: ParamValue,
+}
+
+/// Value type of a parameter, with the values it accepts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ParamKind {
+    /// A number from `min` to `max`, inclusive
+    Number { min: f64, max: f64 },
+    /// `true` or `false`
+    Boolean,
+    /// One of a fixed set of words
+    Enum { options: Vec<String> },
+}
+
+/// A parameter value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    /// Value of a number parameter
+    Number(f64),
+    /// Value of a boolean parameter
+    Boolean(bool),
+    /// Value of an enum parameter
+    Text(String),
+}
+
+impl ParamInfo {
+    /// Describes a parameter; composite parameters group others and have no
+    /// value of their own
+    pub fn new(param: &dyn PatternParam) -> Option<Self> {
+        let default = param.default_value();
+        let (kind, default) = match param.param_type() {
+            ParamType::Number { min, max } => (
+                ParamKind::Number { min, max },
+                ParamValue::Number(default.parse().ok()?),
+            ),
+            ParamType::Boolean => (ParamKind::Boolean, ParamValue::Boolean(default.parse().ok()?)),
+            ParamType::Enum { options } => (
+                ParamKind::Enum {
+                    options: options.iter().map(|o| o.to_string()).collect(),
+                },
+                ParamValue::Text(default),
+            ),
+            ParamType::Composite => return None,
+        };
+        Some(Self {
+            name: param.name().to_string(),
+            description: param.description().to_string(),
+            kind,
+            default,
+        })
+    }
+}
+
+impl From<&PatternMetadata> for PatternInfo {
+    fn from(metadata: &PatternMetadata) -> Self {
+        Self {
+            id: metadata.id.to_string(),
+            name: metadata.name.to_string(),
+            description: metadata.description.to_string(),
+            params: metadata
+                .params()
+                .sub_params()
+                .iter()
+                .filter_map(|param| ParamInfo::new(param.as_ref()))
+                .collect(),
+            presets: metadata.presets().to_vec(),
+        }
+    }
+}
+
+/// Describes every registered pattern and its parameters
+pub fn introspect() -> Introspection {
+    let mut ids = REGISTRY.list_patterns();
+    ids.sort_unstable();
+    Introspection {
+        version: env!("CARGO_PKG_VERSION"),
+        patterns: ids
+            .into_iter()
+            .filter_map(|id| REGISTRY.get_pattern(id))
+            .map(PatternInfo::from)
+            .collect(),
+    }
+}
@@ -5,19 +5,43 @@
 pub mod pattern;
 
 pub mod app;
+pub mod background;
+pub mod catalog;
 pub mod cli;
 pub mod cli_format;
+pub mod columns;
+#[cfg(unix)]
+pub mod control;
+pub mod data;
 pub mod demo;
+pub mod diff;
 pub mod error;
+pub mod escape;
+pub mod events;
+pub mod exec;
+pub mod frames;
 pub mod gradient;
+pub mod guard;
 pub mod input;
+pub mod introspect;
+pub mod motd;
+#[cfg(unix)]
+pub mod mqtt;
 pub mod playlist;
+pub mod progress;
 pub mod renderer;
+pub mod screensaver;
+pub mod statusline;
 pub mod streaming;
+pub mod sync;
+pub mod termcaps;
+pub mod theme_packs;
 pub mod themes;
+pub mod wled;
 
 pub use app::ChromaCat;
 pub use error::{ChromaCatError, Result};
+pub use introspect::introspect;
 
 // Re-export commonly used types for convenience
 pub use pattern::{PatternConfig, PatternParams};
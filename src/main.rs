@@ -1,31 +1,70 @@
 use chromacat::cli::Cli;
 use chromacat::ChromaCat;
-use chromacat::error::Result;
-use clap::Parser;
+use chromacat::error::{Result, EXIT_FAILURE};
+use chromacat::events::{self, Verbosity};
+use chromacat::guard;
 use std::process;
 
 fn main() -> Result<()> {
-    // Initialize logging
-    env_logger::init();
+    // Initialize logging, keeping recent events for the debug overlay
+    events::init();
 
     // Parse command line arguments
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+    events::set_verbosity(Verbosity::from_flags(cli.quiet, cli.verbose));
+    if let Some(path) = &cli.log_file {
+        if let Err(e) = events::set_log_file(path) {
+            eprintln!("Error: Failed to open log file {}: {}", path.display(), e);
+            process::exit(EXIT_FAILURE);
+        }
+    }
 
     if cli.pattern_help {
         Cli::print_pattern_help();
         return Ok(());
     }
 
+    match cli.print_command_list() {
+        Ok(true) => return Ok(()),
+        Ok(false) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(e.exit_code());
+        }
+    }
+
     if cli.list_available {
-        Cli::print_available_options();
+        if let Err(e) = cli.print_list() {
+            eprintln!("Error: {}", e);
+            process::exit(e.exit_code());
+        }
         return Ok(());
     }
 
-    // Create and run ChromaCat
+    // Create and run ChromaCat, holding diagnostics back until its output
+    // is done and the terminal is restored, which skips destructors on exit
+    events::hold();
     let mut cat = ChromaCat::new(cli);
-    if let Err(e) = cat.run() {
+    let result = cat.run();
+    let code = cat.exit_code();
+    drop(cat);
+    events::finish();
+
+    // Report what kind of error ended the run, as the exit codes in
+    // `chromacat::error` describe
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
-        process::exit(1);
+        process::exit(e.exit_code());
+    }
+
+    // Report the signal that stopped the run the way shells expect
+    if let Some(signal) = guard::interruption() {
+        process::exit(128 + signal);
+    }
+
+    // Propagate non-zero exit codes, e.g. from `chromacat exec`
+    if code != 0 {
+        process::exit(code);
     }
 
     Ok(())
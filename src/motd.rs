@@ -0,0 +1,168 @@
+//! Login banners
+//!
+//! `chromacat motd` fills a template with facts about the machine, such as
+//! `{hostname}` and `{uptime}`, and prints it colored once with the chosen
+//! theme and pattern. The pattern is sampled at `--time-offset` and the
+//! width is fixed, so the same template always comes out the same way, and
+//! nothing is asked of the terminal: the output is plain text with color
+//! escapes, fit for `/etc/update-motd.d` scripts and other places where no
+//! terminal is attached.
+
+use std::process::Command;
+use std::time::Duration;
+
+use sysinfo::System;
+
+/// Width the banner is colored at unless one is given
+pub const DEFAULT_MOTD_WIDTH: usize = 80;
+
+/// Template used when none is given
+pub const DEFAULT_TEMPLATE: &str = "\
+Welcome to {hostname}
+{os}, kernel {kernel}
+
+Up {uptime}, load {load}
+Last login: {last_login}
+";
+
+/// Placeholders a template can use
+pub const PLACEHOLDERS: &[&str] = &[
+    "hostname",
+    "user",
+    "os",
+    "kernel",
+    "uptime",
+    "load",
+    "last_login",
+    "date",
+];
+
+/// Facts about the machine that fill a template
+#[derive(Debug, Clone, Default)]
+pub struct SystemInfo {
+    /// Host name
+    pub hostname: String,
+    /// User running the command
+    pub user: String,
+    /// Operating system name and version
+    pub os: String,
+    /// Kernel version
+    pub kernel: String,
+    /// Time since boot
+    pub uptime: Duration,
+    /// Load averages over 1, 5, and 15 minutes
+    pub load: (f64, f64, f64),
+    /// The user's previous login, as reported by `last`
+    pub last_login: Option<String>,
+    /// Current local date and time
+    pub date: String,
+}
+
+impl SystemInfo {
+    /// Gathers the facts about this machine
+    pub fn collect() -> Self {
+        let unknown = || "unknown".to_string();
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("LOGNAME"))
+            .unwrap_or_else(|_| unknown());
+        let load = System::load_average();
+        Self {
+            hostname: System::host_name().unwrap_or_else(unknown),
+            os: System::long_os_version().unwrap_or_else(unknown),
+            kernel: System::kernel_version().unwrap_or_else(unknown),
+            uptime: Duration::from_secs(System::uptime()),
+            load: (load.one, load.five, load.fifteen),
+            last_login: last_login(&user),
+            date: chrono::Local::now().format("%a %b %e %H:%M %Z").to_string(),
+            user,
+        }
+    }
+
+    /// Returns the text for a placeholder, or None if there is no such
+    /// placeholder
+    pub fn value(&self, placeholder: &str) -> Option<String> {
+        let value = match placeholder {
+            "hostname" => self.hostname.clone(),
+            "user" => self.user.clone(),
+            "os" => self.os.clone(),
+            "kernel" => self.kernel.clone(),
+            "uptime" => format_uptime(self.uptime),
+            "load" => format!("{:.2} {:.2} {:.2}", self.load.0, self.load.1, self.load.2),
+            "last_login" => self.last_login.clone().unwrap_or_else(|| "never".to_string()),
+            "date" => self.date.clone(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Fills the `{placeholder}`s of a template; `{{` and `}}` stand for
+/// literal braces
+pub fn fill_template(template: &str, info: &SystemInfo) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len() * 2);
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err("Unmatched '}' in template; write '}}' for a literal brace".to_string());
+        }
+
+        let end = tail
+            .find('}')
+            .ok_or_else(|| "Unclosed '{' in template; write '{{' for a literal brace".to_string())?;
+        let name = tail[1..end].trim();
+        let value = info.value(name).ok_or_else(|| {
+            format!(
+                "Invalid placeholder '{{{}}}'. Valid placeholders: {}",
+                name,
+                PLACEHOLDERS.join(", ")
+            )
+        })?;
+        out.push_str(&value);
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Formats an uptime like `3 days, 4 hours, 12 minutes`, leaving out
+/// leading zero units
+pub fn format_uptime(uptime: Duration) -> String {
+    let minutes = uptime.as_secs() / 60;
+    let units = [
+        (minutes / (24 * 60), "day"),
+        (minutes / 60 % 24, "hour"),
+        (minutes % 60, "minute"),
+    ];
+    let parts: Vec<_> = units
+        .iter()
+        .skip_while(|(count, unit)| *count == 0 && *unit != "minute")
+        .map(|&(count, unit)| format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" }))
+        .collect();
+    parts.join(", ")
+}
+
+/// Looks up the previous login of `user` with `last`, skipping sessions
+/// that are still open, such as the one the banner is being shown for
+fn last_login(user: &str) -> Option<String> {
+    let output = Command::new("last").args(["-n", "2", user]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Long user names may be cut short in the first column
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.contains("still logged in"))
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            user.starts_with(name)
+                .then(|| fields.collect::<Vec<_>>().join(" "))
+        })
+}
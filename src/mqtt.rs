@@ -0,0 +1,560 @@
+//! MQTT state publishing for home automation
+//!
+//! With `--mqtt mqtt://HOST[:PORT]`, an animated display reports what it
+//! shows to an MQTT broker and takes commands from it, so wall displays can
+//! join Home Assistant scenes and automations. Under the `--mqtt-topic`
+//! prefix (`chromacat` by default) it uses three topics:
+//!
+//! - `<prefix>/state`: the pattern, theme, playlist scene and progress as
+//!   JSON, e.g. `{"pattern":"plasma","theme":"neon","scene":"Intro",
+//!   "progress":42,"paused":false}`, retained and sent whenever it changes
+//! - `<prefix>/set`: commands in the `chromacat ctl` syntax, such as
+//!   `next-scene` or `set-theme ocean`
+//! - `<prefix>/availability`: `online` while connected, and `offline`, set
+//!   by the broker as the last will, once the display goes away
+//!
+//! The client speaks just enough MQTT 3.1.1 for this, at QoS 0, and
+//! reconnects in the background whenever the broker goes away. Connecting
+//! needs ChromaCat built with the `mqtt` feature.
+
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::control::ControlCommand;
+use crate::error::{ChromaCatError, Result};
+
+/// Port used when the broker URL doesn't give one
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// Shortest time between two state updates that only move the progress
+pub const STATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Seconds the broker waits without hearing from the client before it
+/// gives up on the connection
+const KEEP_ALIVE_SECONDS: u16 = 30;
+
+/// Largest packet read from the broker
+const MAX_PACKET_SIZE: usize = 64 * 1024;
+
+/// Packet types, in the high nibble of the first byte
+const CONNECT: u8 = 0x10;
+const PUBLISH: u8 = 0x30;
+const SUBSCRIBE: u8 = 0x82;
+
+/// An MQTT broker from an `mqtt://[USER[:PASSWORD]@]HOST[:PORT]` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttBroker {
+    /// Host name or address
+    pub host: String,
+    /// TCP port
+    pub port: u16,
+    /// User name to log in with, if any
+    pub username: Option<String>,
+    /// Password to log in with, if any
+    pub password: Option<String>,
+}
+
+impl MqttBroker {
+    /// Returns the `HOST:PORT` address to connect to
+    pub fn addr(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for MqttBroker {
+    /// Shows the broker without its password, for logs
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mqtt://")?;
+        if let Some(username) = &self.username {
+            write!(f, "{}@", username)?;
+        }
+        f.write_str(&self.addr())
+    }
+}
+
+impl FromStr for MqttBroker {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let rest = match s.split_once("://") {
+            Some(("mqtt", rest)) => rest,
+            Some((scheme, _)) => {
+                return Err(format!(
+                    "Unsupported broker scheme '{}'; use mqtt://HOST[:PORT]",
+                    scheme
+                ))
+            }
+            None => s,
+        };
+        let rest = rest.trim_end_matches('/');
+
+        let (auth, host) = match rest.rsplit_once('@') {
+            Some((auth, host)) => (Some(auth), host),
+            None => (None, rest),
+        };
+        let (username, password) = match auth.map(|auth| auth.split_once(':')) {
+            Some(Some((user, password))) => (Some(user.to_string()), Some(password.to_string())),
+            Some(None) => (auth.map(str::to_string), None),
+            None => (None, None),
+        };
+
+        let (host, port) = match host.strip_prefix('[') {
+            // Bracketed IPv6 address, e.g. [::1]:1883
+            Some(bracketed) => {
+                let (host, port) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| format!("Invalid broker address '{}'", s))?;
+                (host, port.strip_prefix(':'))
+            }
+            None => match host.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host, None),
+            },
+        };
+        let port = match port {
+            Some(port) => port
+                .parse::<u16>()
+                .ok()
+                .filter(|&port| port > 0)
+                .ok_or_else(|| format!("Invalid broker port '{}'", port))?,
+            None => DEFAULT_MQTT_PORT,
+        };
+        if host.is_empty() {
+            return Err(format!("Broker URL '{}' has no host", s));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            username,
+            password,
+        })
+    }
+}
+
+/// The topics a display uses under its prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttTopics {
+    /// Where the state is published
+    pub state: String,
+    /// Where commands are read from
+    pub command: String,
+    /// Where `online` and `offline` are published
+    pub availability: String,
+}
+
+impl MqttTopics {
+    /// Returns the topics under `prefix`
+    pub fn new(prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+        Self {
+            state: format!("{}/state", prefix),
+            command: format!("{}/set", prefix),
+            availability: format!("{}/availability", prefix),
+        }
+    }
+}
+
+/// What the display shows, as published on the state topic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MqttState {
+    /// Pattern id
+    pub pattern: String,
+    /// Theme name
+    pub theme: String,
+    /// Playlist entry name, when a playlist is playing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene: Option<String>,
+    /// Percent of the playlist entry played
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u8>,
+    /// Whether the animation is paused
+    pub paused: bool,
+}
+
+impl MqttState {
+    /// Returns the state as the JSON payload sent to the broker
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Returns whether the state differs from `other` in more than its
+    /// progress
+    fn changed_from(&self, other: &MqttState) -> bool {
+        self.pattern != other.pattern
+            || self.theme != other.theme
+            || self.scene != other.scene
+            || self.paused != other.paused
+    }
+}
+
+/// Reads a command published on the command topic.
+///
+/// Commands use the `chromacat ctl` syntax; ones that only make sense on the
+/// machine running the display, like `load-playlist` and `quit`, are refused.
+pub fn parse_command(payload: &[u8]) -> std::result::Result<ControlCommand, String> {
+    let line = std::str::from_utf8(payload).map_err(|_| "Command is not UTF-8".to_string())?;
+    let command: ControlCommand = line.parse()?;
+    match command {
+        ControlCommand::LoadPlaylist(_) | ControlCommand::Status | ControlCommand::Quit => {
+            let name = command.to_string();
+            let name = name.split_whitespace().next().unwrap_or_default();
+            Err(format!("{} is not accepted over MQTT", name))
+        }
+        command => Ok(command),
+    }
+}
+
+/// Appends a packet's remaining length in MQTT's variable length encoding
+fn push_length(packet: &mut Vec<u8>, mut length: usize) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+}
+
+/// Appends a length-prefixed string or binary field
+fn push_field(packet: &mut Vec<u8>, field: &[u8]) {
+    let field = &field[..field.len().min(u16::MAX as usize)];
+    packet.extend_from_slice(&(field.len() as u16).to_be_bytes());
+    packet.extend_from_slice(field);
+}
+
+/// Builds a packet from its first byte and its body
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![kind];
+    push_length(&mut packet, body.len());
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Builds the CONNECT packet, with a retained `offline` on `will_topic` as
+/// the last will
+pub fn connect_packet(client_id: &str, broker: &MqttBroker, will_topic: &str) -> Vec<u8> {
+    // Clean session, with a retained QoS 0 will
+    let mut flags = 0x02 | 0x04 | 0x20;
+    if broker.username.is_some() {
+        flags |= 0x80;
+    }
+    if broker.password.is_some() {
+        flags |= 0x40;
+    }
+
+    let mut body = Vec::new();
+    push_field(&mut body, b"MQTT");
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&KEEP_ALIVE_SECONDS.to_be_bytes());
+    push_field(&mut body, client_id.as_bytes());
+    push_field(&mut body, will_topic.as_bytes());
+    push_field(&mut body, b"offline");
+    if let Some(username) = &broker.username {
+        push_field(&mut body, username.as_bytes());
+    }
+    if let Some(password) = &broker.password {
+        push_field(&mut body, password.as_bytes());
+    }
+    packet(CONNECT, &body)
+}
+
+/// Builds a QoS 0 PUBLISH packet
+pub fn publish_packet(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_field(&mut body, topic.as_bytes());
+    body.extend_from_slice(payload);
+    packet(PUBLISH | u8::from(retain), &body)
+}
+
+/// Builds a SUBSCRIBE packet for one topic at QoS 0
+pub fn subscribe_packet(packet_id: u16, topic: &str) -> Vec<u8> {
+    let mut body = packet_id.to_be_bytes().to_vec();
+    push_field(&mut body, topic.as_bytes());
+    body.push(0);
+    packet(SUBSCRIBE, &body)
+}
+
+/// Reads one packet, returning its first byte and its body
+pub fn read_packet<R: Read>(reader: &mut R) -> io::Result<(u8, Vec<u8>)> {
+    let mut byte = [0u8];
+    reader.read_exact(&mut byte)?;
+    let kind = byte[0];
+
+    let mut length = 0usize;
+    for shift in (0..4).map(|i| 7 * i) {
+        reader.read_exact(&mut byte)?;
+        length |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        if shift == 21 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Malformed packet length",
+            ));
+        }
+    }
+    if length > MAX_PACKET_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Packet of {} bytes is too large", length),
+        ));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok((kind, body))
+}
+
+/// Splits a PUBLISH packet into its topic and payload; returns `None` for
+/// any other packet or a malformed one
+pub fn parse_publish(kind: u8, body: &[u8]) -> Option<(String, Vec<u8>)> {
+    if kind & 0xf0 != PUBLISH {
+        return None;
+    }
+    let length = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+    let topic = std::str::from_utf8(body.get(2..2 + length)?).ok()?;
+    // Packets above QoS 0 carry an id before the payload
+    let start = if kind & 0x06 != 0 {
+        4 + length
+    } else {
+        2 + length
+    };
+    Some((topic.to_string(), body.get(start..)?.to_vec()))
+}
+
+/// Publishes the display's state to a broker and receives commands from it,
+/// over a connection kept up in the background
+pub struct MqttBridge {
+    /// State payloads waiting to be published
+    states: Sender<String>,
+    /// Commands received, in arrival order
+    commands: Receiver<ControlCommand>,
+    /// Last state published and when
+    last: Mutex<Option<(Instant, MqttState)>>,
+}
+
+impl MqttBridge {
+    /// Starts talking to `broker`, using the topics under `prefix`
+    #[cfg(feature = "mqtt")]
+    pub fn connect(broker: MqttBroker, prefix: &str) -> Result<Self> {
+        use std::net::ToSocketAddrs;
+
+        // Catch typos now rather than retrying forever
+        broker.addr().to_socket_addrs().map_err(|e| {
+            ChromaCatError::InputError(format!("Invalid MQTT broker '{}': {}", broker, e))
+        })?;
+
+        let topics = MqttTopics::new(prefix);
+        let (states, pending) = std::sync::mpsc::channel();
+        let (sender, commands) = std::sync::mpsc::channel();
+        std::thread::spawn(move || connection::run(broker, topics, pending, sender));
+
+        Ok(Self {
+            states,
+            commands,
+            last: Mutex::new(None),
+        })
+    }
+
+    /// Without the `mqtt` feature there is no broker to talk to
+    #[cfg(not(feature = "mqtt"))]
+    pub fn connect(_broker: MqttBroker, _prefix: &str) -> Result<Self> {
+        Err(ChromaCatError::Other(
+            "ChromaCat was built without the mqtt feature".to_string(),
+        ))
+    }
+
+    /// Returns the next command received, without blocking
+    pub fn try_recv(&self) -> Option<ControlCommand> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Publishes `state` if it changed; a change in progress alone is sent
+    /// at most once per [`STATE_INTERVAL`]. Returns whether it was sent.
+    pub fn publish_state(&self, state: &MqttState) -> bool {
+        let Ok(mut last) = self.last.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        let due = match &*last {
+            Some((sent, last)) => {
+                state.changed_from(last)
+                    || (state.progress != last.progress
+                        && now.duration_since(*sent) >= STATE_INTERVAL)
+            }
+            None => true,
+        };
+        if due {
+            // The connection thread only stops once the bridge is dropped
+            let _ = self.states.send(state.to_json());
+            *last = Some((now, state.clone()));
+        }
+        due
+    }
+}
+
+/// The background connection to the broker
+#[cfg(feature = "mqtt")]
+mod connection {
+    use super::*;
+    use log::{debug, info, warn};
+    use std::io::Write;
+    use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::thread;
+
+    /// Longest wait for the broker to accept a connection
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Delay between connection attempts
+    const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+    /// Packet types only the connection reads or sends
+    const CONNACK: u8 = 0x20;
+    const PINGREQ: u8 = 0xc0;
+
+    /// Connects and reconnects until the bridge is dropped, keeping the
+    /// newest state to publish again after a reconnect
+    pub(super) fn run(
+        broker: MqttBroker,
+        topics: MqttTopics,
+        states: Receiver<String>,
+        commands: Sender<ControlCommand>,
+    ) {
+        let mut state = None;
+        loop {
+            match session(&broker, &topics, &states, &commands, &mut state) {
+                Ok(()) => return,
+                Err(e) => warn!("MQTT broker {} unavailable: {}", broker, e),
+            }
+
+            let retry = Instant::now() + RECONNECT_DELAY;
+            loop {
+                let wait = retry.saturating_duration_since(Instant::now());
+                match states.recv_timeout(wait) {
+                    Ok(json) => state = Some(json),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        }
+    }
+
+    /// Runs one connection; returns `Ok` once the bridge is dropped
+    fn session(
+        broker: &MqttBroker,
+        topics: &MqttTopics,
+        states: &Receiver<String>,
+        commands: &Sender<ControlCommand>,
+        state: &mut Option<String>,
+    ) -> io::Result<()> {
+        let mut stream = open(broker)?;
+        let client_id = format!("chromacat-{}", std::process::id());
+        stream.write_all(&connect_packet(&client_id, broker, &topics.availability))?;
+
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        let (kind, body) = read_packet(&mut stream)?;
+        if kind & 0xf0 != CONNACK || body.get(1) != Some(&0) {
+            let code = body.get(1).copied().unwrap_or_default();
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("connection refused (code {})", code),
+            ));
+        }
+        stream.set_read_timeout(None)?;
+
+        stream.write_all(&subscribe_packet(1, &topics.command))?;
+        stream.write_all(&publish_packet(&topics.availability, b"online", true))?;
+        if let Some(json) = state {
+            stream.write_all(&publish_packet(&topics.state, json.as_bytes(), true))?;
+        }
+        info!("Connected to MQTT broker {}", broker);
+
+        let mut reader = stream.try_clone()?;
+        let command_topic = topics.command.clone();
+        let sender = commands.clone();
+        thread::spawn(move || read_commands(&mut reader, &command_topic, &sender));
+
+        // Pings keep the connection up between state changes
+        let ping = Duration::from_secs(u64::from(KEEP_ALIVE_SECONDS) / 2);
+        loop {
+            match states.recv_timeout(ping) {
+                Ok(json) => {
+                    stream.write_all(&publish_packet(&topics.state, json.as_bytes(), true))?;
+                    *state = Some(json);
+                }
+                Err(RecvTimeoutError::Timeout) => stream.write_all(&[PINGREQ, 0])?,
+                Err(RecvTimeoutError::Disconnected) => {
+                    let _ =
+                        stream.write_all(&publish_packet(&topics.availability, b"offline", true));
+                    let _ = stream.shutdown(Shutdown::Both);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Connects to the first of the broker's addresses that answers
+    fn open(broker: &MqttBroker) -> io::Result<TcpStream> {
+        let mut last_error = None;
+        for addr in broker.addr().to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_nodelay(true)?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no address for the broker")
+        }))
+    }
+
+    /// Passes commands on until the connection ends, then shuts it down so
+    /// the writing side notices too
+    fn read_commands(stream: &mut TcpStream, topic: &str, commands: &Sender<ControlCommand>) {
+        loop {
+            let (kind, body) = match read_packet(stream) {
+                Ok(packet) => packet,
+                Err(e) => {
+                    debug!("MQTT connection ended: {}", e);
+                    break;
+                }
+            };
+            // Subscription acks and ping responses need no answer
+            let Some((received, payload)) = parse_publish(kind, &body) else {
+                continue;
+            };
+            if received != topic {
+                continue;
+            }
+            match parse_command(&payload) {
+                Ok(command) => {
+                    if commands.send(command).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => warn!("Ignoring MQTT command: {}", e),
+            }
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+}
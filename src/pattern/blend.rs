@@ -0,0 +1,139 @@
+//! Pattern compositing
+//!
+//! A pattern configuration can stack extra pattern layers on top of its
+//! base pattern. Each layer is evaluated at the same coordinates and time as
+//! the base, and its value field is combined with the result so far by the
+//! layer's blend mode, before the gradient lookup. Layers share the base
+//! pattern's common parameters, so zoom, pan, and speed move them together.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::pattern::config::PatternParams;
+
+/// How a layer's value combines with the value below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Product of both values, darkening where either is low
+    #[default]
+    Multiply,
+    /// Sum of both values, clipped at 1
+    Add,
+    /// Inverse of the product of the inverses, lightening where either is high
+    Screen,
+    /// Lower of the two values
+    Min,
+    /// Higher of the two values
+    Max,
+    /// Value below where the layer is above one half, 0 elsewhere
+    Mask,
+}
+
+impl BlendMode {
+    /// Returns a list of all blend modes
+    pub fn all() -> &'static [BlendMode] {
+        &[
+            BlendMode::Multiply,
+            BlendMode::Add,
+            BlendMode::Screen,
+            BlendMode::Min,
+            BlendMode::Max,
+            BlendMode::Mask,
+        ]
+    }
+
+    /// Get string representation of the blend mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlendMode::Multiply => "multiply",
+            BlendMode::Add => "add",
+            BlendMode::Screen => "screen",
+            BlendMode::Min => "min",
+            BlendMode::Max => "max",
+            BlendMode::Mask => "mask",
+        }
+    }
+
+    /// Combines the value `below` with a layer's value, both 0 to 1
+    #[inline]
+    pub fn apply(&self, below: f64, layer: f64) -> f64 {
+        let value = match self {
+            BlendMode::Multiply => below * layer,
+            BlendMode::Add => below + layer,
+            BlendMode::Screen => 1.0 - (1.0 - below) * (1.0 - layer),
+            BlendMode::Min => below.min(layer),
+            BlendMode::Max => below.max(layer),
+            BlendMode::Mask => {
+                if layer > 0.5 {
+                    below
+                } else {
+                    0.0
+                }
+            }
+        };
+        value.clamp(0.0, 1.0)
+    }
+}
+
+impl fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BlendMode::all()
+            .iter()
+            .copied()
+            .find(|mode| mode.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                let names: Vec<_> = BlendMode::all().iter().map(|mode| mode.as_str()).collect();
+                format!(
+                    "Invalid blend mode '{}'. Valid modes: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Enable serialization for playlist integration
+impl Serialize for BlendMode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Enable deserialization for playlist loading
+impl<'de> Deserialize<'de> for BlendMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A pattern drawn on top of the patterns below it
+#[derive(Debug, Clone)]
+pub struct PatternLayer {
+    /// Pattern-specific parameters of the layer's pattern
+    pub params: PatternParams,
+    /// How the layer combines with the value below it
+    pub blend: BlendMode,
+}
+
+impl PatternLayer {
+    /// Creates a layer blending `params` with the patterns below it
+    pub fn new(params: PatternParams, blend: BlendMode) -> Self {
+        Self { params, blend }
+    }
+}
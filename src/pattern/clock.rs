@@ -0,0 +1,281 @@
+//! Animation clock
+//!
+//! Animation time used to be kept as a float that every frame added a scaled
+//! delta to, so rounding crept in over long runs and engines fed the same
+//! frames could drift apart. [`AnimationClock`] counts whole nanoseconds
+//! instead, carrying the sub-nanosecond remainder of scaled steps forward,
+//! so a run of frames adds up to exactly the wall time that passed, times
+//! the rate.
+//!
+//! A clock can also be given a cycle length. The time it reports then wraps
+//! around, bounces back and forth, or stops at the end of the cycle,
+//! depending on its [`LoopMode`].
+//!
+//! [`FixedStep`] drives a clock frame by frame instead of by the wall time
+//! between frames: frame `n` lands at exactly `n / fps` seconds, so
+//! recordings and synced followers get the same frames on a slow host as on
+//! a fast one.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Nanoseconds per second
+const NANOS_PER_SECOND: f64 = 1e9;
+
+/// Longest cycle accepted on the command line, a day
+pub const MAX_CYCLE_SECONDS: f64 = 86_400.0;
+
+/// What animation time does at the end of a cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Start the cycle over
+    #[default]
+    Loop,
+    /// Run the cycle backwards, then forwards again
+    PingPong,
+    /// Hold the end of the cycle
+    Once,
+}
+
+impl LoopMode {
+    /// Returns all loop modes
+    pub fn all() -> &'static [LoopMode] {
+        &[LoopMode::Loop, LoopMode::PingPong, LoopMode::Once]
+    }
+
+    /// Returns the name used for this mode on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopMode::Loop => "loop",
+            LoopMode::PingPong => "ping-pong",
+            LoopMode::Once => "once",
+        }
+    }
+}
+
+impl fmt::Display for LoopMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LoopMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LoopMode::all()
+            .iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = LoopMode::all().iter().map(|m| m.as_str()).collect();
+                format!("Invalid loop mode '{}'. Valid modes: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Monotonic, pausable, speed-scalable animation time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationClock {
+    /// Animation time in nanoseconds
+    nanos: i64,
+    /// Fraction of a nanosecond left over from scaling, added to the next
+    /// advance
+    carry: f64,
+    /// Seconds of animation time per second of wall time
+    rate: f64,
+    /// Whether [`advance`](Self::advance) is ignored
+    paused: bool,
+    /// Length of a cycle in nanoseconds, if time repeats
+    cycle: Option<i64>,
+    /// What happens at the end of a cycle
+    loop_mode: LoopMode,
+}
+
+impl Default for AnimationClock {
+    fn default() -> Self {
+        Self {
+            nanos: 0,
+            carry: 0.0,
+            rate: 1.0,
+            paused: false,
+            cycle: None,
+            loop_mode: LoopMode::Loop,
+        }
+    }
+}
+
+impl AnimationClock {
+    /// Creates a running clock at zero, advancing at wall speed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by `wall` time at the current rate, unless paused
+    pub fn advance(&mut self, wall: Duration) {
+        if !self.paused {
+            self.add_scaled(wall.as_nanos() as f64);
+        }
+    }
+
+    /// Moves the clock by `wall_seconds` at the current rate, even while
+    /// paused; negative values go back, stopping at zero
+    pub fn step(&mut self, wall_seconds: f64) {
+        self.add_scaled(wall_seconds * NANOS_PER_SECOND);
+        if self.nanos < 0 {
+            self.nanos = 0;
+            self.carry = 0.0;
+        }
+    }
+
+    /// Returns the animation time in seconds, within the cycle if there is
+    /// one
+    #[inline]
+    pub fn seconds(&self) -> f64 {
+        self.cycle_nanos() as f64 / NANOS_PER_SECOND
+    }
+
+    /// Returns how far into the cycle the animation time is, from 0 to 1,
+    /// if there is a cycle
+    pub fn cycle_progress(&self) -> Option<f64> {
+        self.cycle
+            .map(|cycle| self.cycle_nanos() as f64 / cycle as f64)
+    }
+
+    /// Makes the animation time repeat every `cycle`, as `mode` says; `None`
+    /// or a zero cycle lets it run on forever
+    pub fn set_cycle(&mut self, cycle: Option<Duration>, mode: LoopMode) {
+        self.cycle = cycle
+            .map(|cycle| cycle.as_nanos().min(i64::MAX as u128) as i64)
+            .filter(|&nanos| nanos > 0);
+        self.loop_mode = mode;
+    }
+
+    /// Returns the cycle length, if time repeats
+    pub fn cycle(&self) -> Option<Duration> {
+        self.cycle.map(|nanos| Duration::from_nanos(nanos as u64))
+    }
+
+    /// Returns what happens at the end of a cycle
+    pub fn loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
+    /// Returns the total animation time accumulated, ignoring the cycle, or
+    /// zero if it was set before zero
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.nanos.max(0) as u64)
+    }
+
+    /// Sets the animation time in seconds
+    pub fn set_seconds(&mut self, seconds: f64) {
+        self.nanos = (seconds * NANOS_PER_SECOND).round() as i64;
+        self.carry = 0.0;
+    }
+
+    /// Returns the seconds of animation time per second of wall time
+    #[inline]
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Sets the seconds of animation time per second of wall time
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = if rate.is_finite() { rate.max(0.0) } else { 0.0 };
+    }
+
+    /// Stops or restarts the clock
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Returns whether the clock is stopped
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Maps the accumulated time into the cycle
+    fn cycle_nanos(&self) -> i64 {
+        let Some(cycle) = self.cycle else {
+            return self.nanos;
+        };
+        match self.loop_mode {
+            LoopMode::Loop => self.nanos.rem_euclid(cycle),
+            LoopMode::PingPong => {
+                let phase = self.nanos.rem_euclid(cycle.saturating_mul(2));
+                if phase <= cycle {
+                    phase
+                } else {
+                    2 * cycle - phase
+                }
+            }
+            LoopMode::Once => self.nanos.clamp(0, cycle),
+        }
+    }
+
+    /// Adds `wall_nanos` scaled by the rate, keeping the fraction
+    fn add_scaled(&mut self, wall_nanos: f64) {
+        let scaled = wall_nanos * self.rate + self.carry;
+        let whole = scaled.trunc();
+        self.carry = scaled - whole;
+        self.nanos = self.nanos.saturating_add(whole as i64);
+    }
+}
+
+/// Frame timing at exact timestamps, `n / fps` seconds for frame `n`,
+/// whatever the time between frames on the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedStep {
+    /// Frames per second
+    fps: u32,
+    /// Frames stepped so far
+    frame: u64,
+}
+
+impl FixedStep {
+    /// Creates a step at frame zero; `fps` is at least one
+    pub fn new(fps: u32) -> Self {
+        Self {
+            fps: fps.max(1),
+            frame: 0,
+        }
+    }
+
+    /// Returns the frames per second
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Returns the frames stepped so far
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the wall time frame `frame` is shown at, rounded down to
+    /// the nanosecond
+    pub fn wall_time(&self, frame: u64) -> Duration {
+        let nanos = frame as u128 * 1_000_000_000 / self.fps as u128;
+        Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+    }
+
+    /// Moves to the next frame, returning the wall time between the two.
+    /// The steps add up to exactly [`wall_time`](Self::wall_time), with no
+    /// rounding creeping in.
+    pub fn advance(&mut self) -> Duration {
+        let from = self.wall_time(self.frame);
+        self.frame += 1;
+        self.wall_time(self.frame) - from
+    }
+
+    /// Moves `seconds` of animation time, at `rate` seconds per wall
+    /// second, onto the nearest frame, and carries on stepping from there
+    pub fn snap(&mut self, seconds: f64, rate: f64) -> f64 {
+        if rate <= 0.0 || !rate.is_finite() || !seconds.is_finite() {
+            return seconds;
+        }
+        self.frame = (seconds / rate * self.fps as f64).round().max(0.0) as u64;
+        self.wall_time(self.frame).as_secs_f64() * rate
+    }
+}
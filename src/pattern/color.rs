@@ -0,0 +1,153 @@
+//! Color adjustments applied after gradient lookup
+//!
+//! A theme tuned for a dark terminal can look washed out on a light one, and
+//! a bright theme can glare on a dim screen. These adjustments tune every
+//! color taken from the gradient without editing the theme: brightness
+//! scales the channels, contrast stretches them around mid-grey, saturation
+//! moves them toward or away from the color's luminance, and gamma reshapes
+//! the curve from dark to light. They are applied in that order.
+//!
+//! Last, colors can be held to a minimum WCAG contrast ratio against the
+//! terminal background, so colorized logs and code stay readable wherever
+//! the gradient passes close to the background color.
+
+use colorgrad::Color;
+
+/// Lowest and highest brightness, contrast, and saturation factors
+pub const FACTOR_RANGE: (f64, f64) = (0.0, 3.0);
+
+/// Lowest and highest gamma
+pub const GAMMA_RANGE: (f64, f64) = (0.1, 5.0);
+
+/// Lowest and highest minimum contrast ratio; WCAG ratios run from 1 to 21
+pub const CONTRAST_RANGE: (f64, f64) = (1.0, 21.0);
+
+/// Steps of the search for the smallest shift reaching the minimum contrast
+const CONTRAST_SEARCH_STEPS: usize = 12;
+
+/// Adjustments applied to every gradient color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorAdjust {
+    /// Channel multiplier; 1.0 leaves colors unchanged
+    pub brightness: f64,
+    /// Distance from mid-grey multiplier; 0.0 gives flat grey
+    pub contrast: f64,
+    /// Distance from luminance multiplier; 0.0 gives greyscale
+    pub saturation: f64,
+    /// Midtone curve; values above 1.0 lighten, below 1.0 darken
+    pub gamma: f64,
+    /// Lowest WCAG contrast ratio allowed against the background
+    pub min_contrast: Option<f64>,
+    /// Terminal background color the contrast is measured against
+    pub background: (f32, f32, f32),
+}
+
+impl ColorAdjust {
+    /// Returns true if colors pass through unchanged
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        self.is_unscaled() && self.min_contrast.is_none()
+    }
+
+    /// Returns true if brightness, contrast, saturation, and gamma are all
+    /// left unchanged
+    #[inline]
+    fn is_unscaled(&self) -> bool {
+        self.brightness == 1.0 && self.contrast == 1.0 && self.saturation == 1.0 && self.gamma == 1.0
+    }
+
+    /// Applies the adjustments to a color, keeping its alpha
+    #[inline]
+    pub fn apply(&self, color: Color) -> Color {
+        if self.is_identity() {
+            return color;
+        }
+
+        let mut channels = [color.r as f64, color.g as f64, color.b as f64];
+        if !self.is_unscaled() {
+            for c in &mut channels {
+                *c = (*c * self.brightness - 0.5) * self.contrast + 0.5;
+            }
+
+            // Rec. 709 luma weights
+            let luma = 0.2126 * channels[0] + 0.7152 * channels[1] + 0.0722 * channels[2];
+            let inverse_gamma = 1.0 / self.gamma;
+            channels = channels.map(|c| {
+                let c = (luma + (c - luma) * self.saturation).clamp(0.0, 1.0);
+                c.powf(inverse_gamma)
+            });
+        }
+        if let Some(min_contrast) = self.min_contrast {
+            channels = self.enforce_contrast(channels, min_contrast);
+        }
+
+        let [r, g, b] = channels.map(|c| c as f32);
+        Color::new(r, g, b, color.a)
+    }
+
+    /// Moves a color toward white on dark backgrounds, or black on light
+    /// ones, just far enough to reach the minimum contrast
+    fn enforce_contrast(&self, rgb: [f64; 3], min_contrast: f64) -> [f64; 3] {
+        let (r, g, b) = self.background;
+        let background = [r as f64, g as f64, b as f64];
+        if contrast_ratio(rgb, background) >= min_contrast {
+            return rgb;
+        }
+
+        let (white, black) = ([1.0; 3], [0.0; 3]);
+        let target = if contrast_ratio(white, background) >= contrast_ratio(black, background) {
+            white
+        } else {
+            black
+        };
+        if contrast_ratio(target, background) < min_contrast {
+            return target;
+        }
+
+        // The color starts below the minimum and the target is above it, so
+        // the contrast crosses the minimum exactly once on the way: bisect
+        // for the smallest shift that is enough
+        let mix = |t: f64| [0, 1, 2].map(|i| rgb[i] + (target[i] - rgb[i]) * t);
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..CONTRAST_SEARCH_STEPS {
+            let t = (low + high) / 2.0;
+            if contrast_ratio(mix(t), background) >= min_contrast {
+                high = t;
+            } else {
+                low = t;
+            }
+        }
+        mix(high)
+    }
+}
+
+/// Returns the WCAG contrast ratio between two colors, from 1 to 21
+pub fn contrast_ratio(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// WCAG relative luminance of an sRGB color
+fn relative_luminance(rgb: [f64; 3]) -> f64 {
+    let [r, g, b] = rgb.map(|c| {
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 1.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            gamma: 1.0,
+            min_contrast: None,
+            background: (0.0, 0.0, 0.0),
+        }
+    }
+}
@@ -1,8 +1,18 @@
+use crate::define_param;
+use crate::pattern::blend::PatternLayer;
+use crate::pattern::params::PatternParam;
 use crate::pattern::patterns::{
     CheckerboardParams, DiagonalParams, DiamondParams, HorizontalParams,
     PerlinParams, PlasmaParams, RippleParams, SpiralParams, WaveParams,
     PixelRainParams, FireParams, AuroraParams, KaleidoscopeParams,
 };
+use crate::pattern::space::PatternSpace;
+
+/// Smallest and largest pattern zoom factor
+pub const PATTERN_ZOOM_RANGE: (f64, f64) = (0.1, 10.0);
+
+/// Largest pattern pan offset in either direction, in normalized units
+pub const MAX_PATTERN_PAN: f64 = 2.0;
 
 /// Common parameters that apply to all pattern types
 #[derive(Debug, Clone)]
@@ -17,6 +27,12 @@ pub struct CommonParams {
     pub correct_aspect: bool,
     /// Aspect ratio (width/height)
     pub aspect_ratio: f64,
+    /// Pattern magnification around the pan point (0.1-10.0)
+    pub zoom: f64,
+    /// Horizontal offset of the pattern, as a fraction of the area's width
+    pub pan_x: f64,
+    /// Vertical offset of the pattern, as a fraction of the area's height
+    pub pan_y: f64,
     /// Current theme name
     pub theme_name: Option<String>,
 }
@@ -29,11 +45,96 @@ impl Default for CommonParams {
             speed: 1.0,
             correct_aspect: true,
             aspect_ratio: 0.5,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
             theme_name: None,
         }
     }
 }
 
+/// Parses a pattern pan offset in the format "X,Y", each within
+/// [`MAX_PATTERN_PAN`] of zero
+pub fn parse_pattern_pan(s: &str) -> Result<(f64, f64), String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Pattern pan '{}' must be in format X,Y", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.abs() <= MAX_PATTERN_PAN)
+            .ok_or_else(|| {
+                format!(
+                    "Pattern pan offset '{}' must be a number from -{} to {}",
+                    v.trim(),
+                    MAX_PATTERN_PAN,
+                    MAX_PATTERN_PAN
+                )
+            })
+    };
+    Ok((parse(x)?, parse(y)?))
+}
+
+define_param!(bool Aspect, CorrectParam, "correct_aspect", "Aspect correction for this pattern, instead of --no-aspect-correction", true);
+define_param!(num Aspect, RatioParam, "aspect_ratio", "Character aspect ratio for this pattern, instead of --aspect-ratio", 0.1, 2.0, 0.5);
+
+/// Aspect settings a pattern uses instead of those in [`CommonParams`].
+/// Every pattern takes them as the `correct_aspect` and `aspect_ratio`
+/// parameters, so that shapes meant for square cells, like checkerboard
+/// squares, can go without the correction circles need.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AspectOverride {
+    /// Whether aspect correction is applied, if not as configured
+    pub correct_aspect: Option<bool>,
+    /// Character aspect ratio (width / height), if not as configured
+    pub aspect_ratio: Option<f64>,
+}
+
+impl AspectOverride {
+    const CORRECT_PARAM: AspectCorrectParam = AspectCorrectParam;
+    const RATIO_PARAM: AspectRatioParam = AspectRatioParam;
+
+    /// Returns true for the names of the parameters every pattern takes
+    pub fn is_param(name: &str) -> bool {
+        name == Self::CORRECT_PARAM.name() || name == Self::RATIO_PARAM.name()
+    }
+
+    /// Checks a value of one of the aspect parameters
+    pub fn validate(name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "correct_aspect" => Self::CORRECT_PARAM.validate(value),
+            "aspect_ratio" => Self::RATIO_PARAM.validate(value),
+            _ => Err(format!("Invalid parameter name: {}", name)),
+        }
+    }
+
+    /// Sets one of the aspect parameters from its `--param` value
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        Self::validate(name, value)?;
+        match name {
+            "correct_aspect" => self.correct_aspect = value.parse().ok(),
+            _ => self.aspect_ratio = value.parse().ok(),
+        }
+        Ok(())
+    }
+
+    /// Returns the parameters that are set as `key=value` pairs
+    pub fn pairs(&self) -> Vec<String> {
+        let correct = self.correct_aspect.map(|v| format!("correct_aspect={}", v));
+        let ratio = self.aspect_ratio.map(|v| format!("aspect_ratio={}", v));
+        correct.into_iter().chain(ratio).collect()
+    }
+
+    /// Returns `space` with the aspect settings that are set replaced
+    pub fn apply(&self, space: PatternSpace) -> PatternSpace {
+        space.with_aspect(
+            self.correct_aspect.unwrap_or(space.corrects_aspect()),
+            self.aspect_ratio.unwrap_or(space.char_aspect_ratio()),
+        )
+    }
+}
+
 /// Available pattern types with their specific parameters
 #[derive(Debug, Clone)]
 pub enum PatternParams {
@@ -70,6 +171,50 @@ impl Default for PatternParams {
     }
 }
 
+macro_rules! each_pattern_params {
+    ($params:expr, $p:ident => $body:expr) => {
+        match $params {
+            PatternParams::Horizontal($p) => $body,
+            PatternParams::Diagonal($p) => $body,
+            PatternParams::Plasma($p) => $body,
+            PatternParams::Ripple($p) => $body,
+            PatternParams::Wave($p) => $body,
+            PatternParams::Spiral($p) => $body,
+            PatternParams::Checkerboard($p) => $body,
+            PatternParams::Diamond($p) => $body,
+            PatternParams::Perlin($p) => $body,
+            PatternParams::PixelRain($p) => $body,
+            PatternParams::Fire($p) => $body,
+            PatternParams::Aurora($p) => $body,
+            PatternParams::Kaleidoscope($p) => $body,
+        }
+    };
+}
+
+impl PatternParams {
+    /// Returns the aspect settings the pattern uses instead of the common
+    /// ones
+    pub fn aspect(&self) -> &AspectOverride {
+        each_pattern_params!(self, p => &p.aspect)
+    }
+
+    /// Returns true if the pattern draws on the noise table, so that its
+    /// look depends on the engine's seed
+    pub fn uses_noise(&self) -> bool {
+        match self {
+            Self::Perlin(_) | Self::Aurora(_) => true,
+            Self::Fire(params) => params.wind,
+            Self::Kaleidoscope(params) => params.distortion > 0.001,
+            _ => false,
+        }
+    }
+
+    /// Returns a mutable reference to the pattern's aspect settings
+    pub fn aspect_mut(&mut self) -> &mut AspectOverride {
+        each_pattern_params!(self, p => &mut p.aspect)
+    }
+}
+
 /// Complete pattern configuration
 #[derive(Debug, Clone, Default)]
 pub struct PatternConfig {
@@ -77,6 +222,8 @@ pub struct PatternConfig {
     pub common: CommonParams,
     /// Pattern-specific parameters
     pub params: PatternParams,
+    /// Patterns blended on top of the base pattern, bottom first
+    pub layers: Vec<PatternLayer>,
 }
 
 impl PatternConfig {
@@ -85,9 +232,16 @@ impl PatternConfig {
         Self {
             common: CommonParams::default(),
             params: pattern_type,
+            layers: Vec::new(),
         }
     }
 
+    /// Adds a pattern layer on top of the existing ones
+    pub fn with_layer(mut self, layer: PatternLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
     /// Returns a reference to the pattern parameters
     pub fn params(&self) -> &PatternParams {
         &self.params
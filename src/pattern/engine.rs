@@ -1,9 +1,17 @@
 use colorgrad::Gradient;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::error::Result;
-use crate::pattern::config::PatternConfig;
+use crate::pattern::clock::{AnimationClock, LoopMode};
+use crate::pattern::config::{PatternConfig, PatternParams};
+use crate::pattern::light::{LightMap, LightSource};
+use crate::pattern::palette::Palette;
 use crate::pattern::patterns::Patterns;
+use crate::pattern::postprocess::PostProcess;
+use crate::pattern::registry::REGISTRY;
+use crate::pattern::space::PatternSpace;
+use crate::themes::ThemeDefinition;
 
 /// Pattern generation engine that coordinates pattern generation, animation,
 /// and color mapping.
@@ -12,14 +20,33 @@ pub struct PatternEngine {
     config: PatternConfig,
     /// Thread-safe reference to the color gradient
     gradient: Arc<Box<dyn Gradient + Send + Sync>>,
-    /// Current animation time in seconds
-    time: f64,
+    /// Theme the gradient was built from, whose distribution, repeat mode,
+    /// and easing shape the gradient lookup
+    theme: Option<Arc<ThemeDefinition>>,
+    /// Animation time, advancing at the pattern's speed times the
+    /// multiplier
+    clock: AnimationClock,
     /// Width of the pattern area in pixels
     width: usize,
     /// Height of the pattern area in pixels
     height: usize,
     /// Pattern generator instance
     patterns: Patterns,
+    /// Value post-processing applied before gradient lookup
+    postprocess: PostProcess,
+    /// Pattern instances at earlier moments of the current frame, averaged
+    /// with the current one in smooth mode
+    subframes: Vec<Patterns>,
+    /// Seconds of wall-clock time the sub-frames are spread across
+    shutter: f64,
+    /// Factor applied on top of the pattern's own speed, changed at runtime
+    speed_multiplier: f64,
+    /// Highest `complexity` of the patterns that have one, if capped
+    max_complexity: Option<f64>,
+    /// Second theme shading the colors, if any
+    light: Option<Arc<LightMap>>,
+    /// Seed of the permutation table noise-based patterns draw on
+    seed: u32,
 }
 
 impl PatternEngine {
@@ -30,31 +57,120 @@ impl PatternEngine {
         width: usize,
         height: usize,
     ) -> Self {
-        let mut patterns = Patterns::new(width, height, 0.0, 0);
-        patterns.set_aspect_correction(config.common.correct_aspect);
-        patterns.set_char_aspect_ratio(config.common.aspect_ratio);
+        let space = Self::space_for(&config, width, height);
+        let seed = config_seed(&config).unwrap_or(0);
+        let patterns = Patterns::with_space(space, 0.0, seed);
+
+        let mut clock = AnimationClock::new();
+        clock.set_rate(config.common.speed);
 
         Self {
             config,
             gradient: Arc::new(gradient),
-            time: 0.0,
+            theme: None,
+            clock,
             width,
             height,
             patterns,
+            postprocess: PostProcess::default(),
+            subframes: Vec::new(),
+            shutter: 0.0,
+            speed_multiplier: 1.0,
+            max_complexity: None,
+            light: None,
+            seed,
         }
     }
 
-    /// Updates the animation time based on delta seconds
+    /// Creates an engine drawing with a theme, honoring its animation settings
+    pub fn from_theme(
+        theme: &ThemeDefinition,
+        config: PatternConfig,
+        width: usize,
+        height: usize,
+    ) -> Result<Self> {
+        let mut engine = Self::new(theme.create_gradient()?, config, width, height);
+        engine.theme = Some(Arc::new(theme.clone()));
+        Ok(engine)
+    }
+
+    /// Updates the animation time based on delta seconds, unless paused
     #[inline]
     pub fn update(&mut self, delta_seconds: f64) {
-        self.time += delta_seconds * self.config.common.speed;
-        self.patterns = Patterns::new(self.width, self.height, self.time, 0);
+        self.advance(Duration::try_from_secs_f64(delta_seconds).unwrap_or(Duration::ZERO));
+    }
+
+    /// Advances the animation clock by `wall` time at the current speed,
+    /// unless paused
+    pub fn advance(&mut self, wall: Duration) {
+        if !self.clock.is_paused() {
+            self.clock.advance(wall);
+            self.refresh_patterns();
+        }
+    }
+
+    /// Moves the animation time by delta seconds at the current speed, even
+    /// while paused; negative deltas go back, stopping at zero
+    pub fn step(&mut self, delta_seconds: f64) {
+        self.clock.step(delta_seconds);
+        self.refresh_patterns();
+    }
+
+    /// Returns the animation clock
+    #[inline]
+    pub fn clock(&self) -> &AnimationClock {
+        &self.clock
+    }
+
+    /// Makes the animation time repeat every `cycle` as `mode` says; see
+    /// [`AnimationClock::set_cycle`]
+    pub fn set_cycle(&mut self, cycle: Option<Duration>, mode: LoopMode) {
+        self.clock.set_cycle(cycle, mode);
+        self.refresh_patterns();
+    }
+
+    /// Scales the pattern's speed, e.g. to speed an animation up while it
+    /// plays; the factor survives pattern and theme changes
+    pub fn set_speed_multiplier(&mut self, multiplier: f64) {
+        self.speed_multiplier = multiplier.max(0.0);
+        self.clock
+            .set_rate(self.config.common.speed * self.speed_multiplier);
+    }
+
+    /// Returns the factor applied on top of the pattern's speed
+    #[inline]
+    pub fn speed_multiplier(&self) -> f64 {
+        self.speed_multiplier
+    }
+
+    /// Caps the `complexity` of the patterns that have one, to keep slow
+    /// machines up to speed; the cap survives pattern changes
+    pub fn set_max_complexity(&mut self, max: Option<f64>) {
+        self.max_complexity = max;
+        self.update_pattern_config(self.config.clone());
+    }
+
+    /// Stops or restarts the animation clock
+    pub fn set_paused(&mut self, paused: bool) {
+        self.clock.set_paused(paused);
+    }
+
+    /// Returns whether the animation clock is stopped
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.clock.is_paused()
     }
 
     /// Gets the current animation time
     #[inline]
     pub fn time(&self) -> f64 {
-        self.time
+        self.clock.seconds()
+    }
+
+    /// Returns the pattern size as (width, height)
+    #[inline]
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
     }
 
     /// Gets a reference to the color gradient
@@ -62,11 +178,195 @@ impl PatternEngine {
         &**self.gradient
     }
 
-    /// Calculates the pattern value at the specified coordinates
+    /// Evaluates the base pattern and blends each layer on top of it
+    #[inline(always)]
+    fn composite(&self, patterns: &Patterns, x: f64, y: f64) -> f64 {
+        let base = patterns.generate_at(x, y, &self.config.params);
+        self.config.layers.iter().fold(base, |below, layer| {
+            layer.blend.apply(below, patterns.generate_at(x, y, &layer.params))
+        })
+    }
+
+    /// Returns the coordinate mapping patterns are sampled through, built
+    /// from the current size and aspect settings
+    pub fn space(&self) -> PatternSpace {
+        Self::space_for(&self.config, self.width, self.height)
+    }
+
+    fn space_for(config: &PatternConfig, width: usize, height: usize) -> PatternSpace {
+        let common = &config.common;
+        PatternSpace::new(width, height)
+            .with_aspect(common.correct_aspect, common.aspect_ratio)
+            .with_view(common.zoom, common.pan_x, common.pan_y)
+    }
+
+    /// Calculates the pattern value at the specified pixel coordinates
     #[inline(always)]
     pub fn get_value_at(&self, x: usize, y: usize) -> Result<f64> {
-        let value = self.patterns.generate(x, y, &self.config.params);
-        Ok(value)
+        let space = self.patterns.space();
+        let (nx, ny) = space.normalize(x as f64, y as f64);
+        let (px, py) = space.to_pattern(nx, ny);
+        Ok(self.sample(px, py, (x, y)))
+    }
+
+    /// Evaluates the pattern at pattern coordinates, averaging sub-frames
+    /// and post-processing as if drawn at `pixel`
+    #[inline(always)]
+    fn sample(&self, x: f64, y: f64, pixel: (usize, usize)) -> f64 {
+        let mut value = self.composite(&self.patterns, x, y);
+        if !self.subframes.is_empty() {
+            let sum: f64 = self
+                .subframes
+                .iter()
+                .map(|patterns| self.composite(patterns, x, y))
+                .sum();
+            value = (value + sum) / (self.subframes.len() + 1) as f64;
+        }
+        self.postprocess.apply(value, pixel.0, pixel.1)
+    }
+
+    /// Averages every value over `samples` pattern evaluations spread evenly
+    /// across the `shutter` seconds leading up to the current time, like a
+    /// camera's motion blur. This hides the shimmer of fast patterns at low
+    /// frame rates; one sample turns it off.
+    pub fn set_smoothing(&mut self, samples: usize, shutter: f64) {
+        self.shutter = shutter.max(0.0);
+        self.rebuild_subframes(samples.saturating_sub(1));
+    }
+
+    /// Returns the number of pattern evaluations averaged per value
+    pub fn smoothing_samples(&self) -> usize {
+        self.subframes.len() + 1
+    }
+
+    /// Recreates `count` sub-frames at the current time and size
+    fn rebuild_subframes(&mut self, count: usize) {
+        // The clock rate includes the speed set while playing
+        let span = self.shutter * self.clock.rate();
+        self.subframes = (1..=count)
+            .map(|i| {
+                let time = self.time() - span * i as f64 / (count + 1) as f64;
+                Patterns::with_space(self.space(), time, self.seed)
+            })
+            .collect();
+    }
+
+    /// Looks up a pattern value in the gradient, with the color adjustments
+    /// applied
+    #[inline]
+    pub fn color_at(&self, value: f64) -> colorgrad::Color {
+        self.postprocess.color.apply(self.gradient_at(value))
+    }
+
+    /// Looks up a pattern value in the gradient, taking the lightness from
+    /// the light map at `light` when both are present, with the color
+    /// adjustments applied
+    #[inline]
+    pub fn color_with_light(&self, value: f64, light: Option<f64>) -> colorgrad::Color {
+        self.postprocess.color.apply(self.lit(self.gradient_at(value), light))
+    }
+
+    /// Color of the cell at (x, y) for a pattern value: the light-mapped,
+    /// adjusted gradient color with grain applied
+    #[inline]
+    pub fn cell_color(&self, value: f64, light: Option<f64>, x: usize, y: usize) -> colorgrad::Color {
+        let color = self.color_with_light(value, light);
+        self.postprocess.grain.apply(color, x, y, self.time())
+    }
+
+    /// Like [`cell_color`](Self::cell_color), but drawing with `palette`
+    /// when one is given
+    #[inline]
+    pub fn cell_color_in(
+        &self,
+        palette: Option<&Palette>,
+        value: f64,
+        light: Option<f64>,
+        x: usize,
+        y: usize,
+    ) -> colorgrad::Color {
+        let Some(palette) = palette else {
+            return self.cell_color(value, light, x, y);
+        };
+        let value = palette.map_value(value);
+        let color = palette
+            .gradient_at(value, self.time())
+            .unwrap_or_else(|| self.gradient_at(value));
+        let color = self.postprocess.color.apply(self.lit(color, light));
+        self.postprocess.grain.apply(palette.adjust().apply(color), x, y, self.time())
+    }
+
+    /// Shades a gradient color by the light map at `light`, when both are
+    /// present
+    #[inline]
+    fn lit(&self, color: colorgrad::Color, light: Option<f64>) -> colorgrad::Color {
+        match (&self.light, light) {
+            (Some(map), Some(light)) => map.apply(color, light, self.time()),
+            _ => color,
+        }
+    }
+
+    #[inline]
+    fn gradient_at(&self, value: f64) -> colorgrad::Color {
+        let position = match &self.theme {
+            Some(theme) => theme.position_at(value as f32, self.time() as f32),
+            None => value as f32,
+        };
+        self.gradient.at(position)
+    }
+
+    /// Sets the second theme that shades colors; see
+    /// [`LightMap`](crate::pattern::LightMap)
+    pub fn set_light_map(&mut self, light: Option<LightMap>) {
+        self.light = light.map(Arc::new);
+    }
+
+    /// Returns the second theme that shades colors, if any
+    pub fn light_map(&self) -> Option<&LightMap> {
+        self.light.as_deref()
+    }
+
+    /// Returns the light map's secondary pattern value at the specified
+    /// pixel coordinates, or None without one
+    #[inline]
+    pub fn light_at(&self, x: usize, y: usize) -> Option<f64> {
+        let space = self.patterns.space();
+        let (nx, ny) = space.normalize(x as f64, y as f64);
+        self.light_at_normalized(nx, ny)
+    }
+
+    /// Returns the lightness value for a glyph of plain text at the
+    /// specified pixel coordinates: full for a light map following the
+    /// text mask, which covers every glyph, or the secondary pattern value
+    #[inline]
+    pub fn glyph_light_at(&self, x: usize, y: usize) -> Option<f64> {
+        match self.light.as_deref()?.source() {
+            LightSource::Mask => Some(1.0),
+            LightSource::Pattern(_) => self.light_at(x, y),
+        }
+    }
+
+    /// Returns the light map's secondary pattern value at normalized
+    /// coordinates, or None without one
+    #[inline]
+    pub fn light_at_normalized(&self, x: f64, y: f64) -> Option<f64> {
+        match self.light.as_deref()?.source() {
+            LightSource::Pattern(params) => {
+                let (px, py) = self.patterns.space().to_pattern(x, y);
+                Some(self.patterns.generate_at(px, py, params))
+            }
+            LightSource::Mask => None,
+        }
+    }
+
+    /// Returns the value post-processing settings
+    pub fn postprocess(&self) -> &PostProcess {
+        &self.postprocess
+    }
+
+    /// Sets the value post-processing applied before gradient lookup
+    pub fn set_postprocess(&mut self, postprocess: PostProcess) {
+        self.postprocess = postprocess;
     }
 
     /// Returns a reference to the current pattern configuration
@@ -76,6 +376,12 @@ impl PatternEngine {
 
     /// Gets a pattern value using normalized coordinates relative to viewport center
     ///
+    /// Sampling is continuous: positions between pixel corners give values
+    /// between theirs, which is what sub-cell resolutions and the camera's
+    /// zoom and pan rely on. At pixel positions this matches
+    /// [`get_value_at`](Self::get_value_at). See [`PatternSpace`] for the
+    /// coordinate conventions.
+    ///
     /// # Arguments
     /// * `x` - Normalized x coordinate (-0.5 to 0.5)
     /// * `y` - Normalized y coordinate (-0.5 to 0.5)
@@ -83,54 +389,165 @@ impl PatternEngine {
     /// # Returns
     /// Pattern value between 0.0 and 1.0
     pub fn get_value_at_normalized(&self, x: f64, y: f64) -> Result<f64> {
-        let width_f = self.width as f64;
-        let height_f = self.height as f64;
-
-        let pattern_x = ((x + 0.5) * width_f) as usize;
-        let pattern_y = ((y + 0.5) * height_f) as usize;
-        self.get_value_at(pattern_x, pattern_y)
+        let space = self.patterns.space();
+        let (px, py) = space.to_pattern(x, y);
+        Ok(self.sample(px, py, space.to_pixel(x, y)))
     }
 
     /// Creates a new PatternEngine instance with different dimensions
     #[cold]
     pub fn recreate(&self, new_width: usize, new_height: usize) -> Self {
-        Self {
+        let mut engine = Self {
             config: self.config.clone(),
             gradient: Arc::clone(&self.gradient),
-            time: self.time,
+            theme: self.theme.clone(),
+            clock: self.clock,
             width: new_width,
             height: new_height,
-            patterns: Patterns::new(new_width, new_height, self.time, 0), // Maintain same seed
-        }
+            patterns: Patterns::with_space(
+                Self::space_for(&self.config, new_width, new_height),
+                self.time(),
+                self.seed,
+            ), // Maintain same seed
+            postprocess: self.postprocess.clone(),
+            subframes: Vec::new(),
+            shutter: self.shutter,
+            speed_multiplier: self.speed_multiplier,
+            max_complexity: self.max_complexity,
+            light: self.light.clone(),
+            seed: self.seed,
+        };
+        engine.rebuild_subframes(self.subframes.len());
+        engine
     }
 
     /// Sets the animation time directly
     pub fn set_time(&mut self, time: f64) {
-        self.time = time; // Remove normalization
-                          // Update patterns with new time
-        self.patterns = Patterns::new(self.width, self.height, self.time, 0);
+        self.clock.set_seconds(time);
+        self.refresh_patterns();
+    }
+
+    /// Rebuilds the pattern instances at the clock's time
+    fn refresh_patterns(&mut self) {
+        self.patterns = Patterns::with_space(self.space(), self.time(), self.seed);
+        self.rebuild_subframes(self.subframes.len());
     }
 
     /// Updates the gradient while maintaining animation state
     pub fn update_gradient(&mut self, gradient: Box<dyn Gradient + Send + Sync>) {
         self.gradient = Arc::new(gradient);
+        self.theme = None;
+    }
+
+    /// Switches to a theme's gradient and animation settings while
+    /// maintaining animation state
+    pub fn set_theme(&mut self, theme: &ThemeDefinition) -> Result<()> {
+        self.gradient = Arc::new(theme.create_gradient()?);
+        self.theme = Some(Arc::new(theme.clone()));
+        Ok(())
     }
 
     /// Updates pattern configuration while maintaining animation state
-    pub fn update_pattern_config(&mut self, config: PatternConfig) {
+    pub fn update_pattern_config(&mut self, mut config: PatternConfig) {
+        if let Some(max) = self.max_complexity {
+            cap_complexity(&mut config.params, max);
+        }
+        let seed = config_seed(&config).unwrap_or(self.seed);
         self.config = config;
+        self.clock
+            .set_rate(self.config.common.speed * self.speed_multiplier);
+        if self.space() != *self.patterns.space() || seed != self.seed {
+            self.seed = seed;
+            self.refresh_patterns();
+        }
+    }
+
+    /// Returns the seed of the noise that noise-based patterns draw on
+    #[inline]
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns true if the pattern or one of its layers draws on the noise,
+    /// so [`reseed`](Self::reseed) changes how it looks
+    pub fn uses_noise(&self) -> bool {
+        let layers = self.config.layers.iter().map(|layer| &layer.params);
+        std::iter::once(&self.config.params)
+            .chain(layers)
+            .any(PatternParams::uses_noise)
+    }
+
+    /// Rebuilds the noise from `seed`, keeping the animation time. Perlin
+    /// patterns take it as their `seed` parameter too, so copies of the
+    /// configuration draw the same noise.
+    pub fn reseed(&mut self, seed: u32) {
+        let layers = self.config.layers.iter_mut().map(|layer| &mut layer.params);
+        for params in std::iter::once(&mut self.config.params).chain(layers) {
+            if let PatternParams::Perlin(perlin) = params {
+                perlin.seed = seed;
+            }
+        }
+        self.seed = seed;
+        self.refresh_patterns();
     }
 }
 
 impl Clone for PatternEngine {
     fn clone(&self) -> Self {
-        Self {
+        let mut engine = Self {
             config: self.config.clone(),
             gradient: Arc::clone(&self.gradient),
-            time: self.time,
+            theme: self.theme.clone(),
+            clock: self.clock,
             width: self.width,
             height: self.height,
-            patterns: Patterns::new(self.width, self.height, self.time, 0), // Maintain same seed
+            patterns: Patterns::with_space(self.space(), self.time(), self.seed), // Maintain same seed
+            postprocess: self.postprocess.clone(),
+            subframes: Vec::new(),
+            shutter: self.shutter,
+            speed_multiplier: self.speed_multiplier,
+            max_complexity: self.max_complexity,
+            light: self.light.clone(),
+            seed: self.seed,
+        };
+        engine.rebuild_subframes(self.subframes.len());
+        engine
+    }
+}
+
+/// Returns the noise seed `config` asks for: the `seed` of its first Perlin
+/// pattern, if it has one
+fn config_seed(config: &PatternConfig) -> Option<u32> {
+    let layers = config.layers.iter().map(|layer| &layer.params);
+    std::iter::once(&config.params)
+        .chain(layers)
+        .find_map(|params| match params {
+            PatternParams::Perlin(perlin) => Some(perlin.seed),
+            _ => None,
+        })
+}
+
+/// Lowers the `complexity` parameter of `params` to `max`, if it has one
+/// above it
+fn cap_complexity(params: &mut PatternParams, max: f64) {
+    let Some(id) = REGISTRY.get_pattern_id(params) else {
+        return;
+    };
+    let formatted = REGISTRY.format_params(params);
+    let mut capped = false;
+    let pairs: Vec<String> = formatted
+        .split(',')
+        .map(|pair| match pair.split_once('=') {
+            Some(("complexity", value)) if value.parse::<f64>().is_ok_and(|v| v > max) => {
+                capped = true;
+                format!("complexity={}", max)
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    if capped {
+        if let Ok(lowered) = REGISTRY.parse_params(id, &pairs.join(",")) {
+            *params = lowered;
         }
     }
 }
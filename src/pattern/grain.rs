@@ -0,0 +1,95 @@
+//! Film grain and sparkle
+//!
+//! Grain is applied to colors after every other adjustment, so it works
+//! the same on top of any pattern and theme. Each cell's brightness is
+//! nudged by noise that changes a set number of times per second of
+//! animation time, like film grain, and now and then a cell catches a
+//! glint that flashes toward white and fades over a few grain frames.
+//! Noise is a hash of the cell and the grain frame, so a paused or static
+//! frame keeps the same grain.
+
+use colorgrad::Color;
+
+/// Lowest and highest grain amount
+pub const GRAIN_RANGE: (f64, f64) = (0.0, 1.0);
+
+/// Grain amount the toggle key uses when none was given
+pub const DEFAULT_GRAIN: f64 = 0.15;
+
+/// Grain frames per second of animation time
+const GRAIN_FPS: f64 = 24.0;
+
+/// Largest brightness change noise makes, at full grain
+const NOISE_STRENGTH: f64 = 0.25;
+
+/// Chance that a cell glints in a given glint period, at full grain
+const SPARKLE_RATE: f64 = 0.01;
+
+/// Grain frames a glint takes to fade out
+const SPARKLE_FRAMES: u64 = 4;
+
+/// How far toward white a glint starts
+const SPARKLE_STRENGTH: f64 = 0.8;
+
+/// Noise and sparkle applied to colors
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Grain {
+    /// Strength of the noise and how often glints appear; 0.0 turns it off
+    amount: f64,
+}
+
+impl Grain {
+    /// Creates grain of the given amount, clamped to [`GRAIN_RANGE`]
+    pub fn new(amount: f64) -> Self {
+        let (min, max) = GRAIN_RANGE;
+        Self {
+            amount: amount.clamp(min, max),
+        }
+    }
+
+    /// Returns the grain amount
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    /// Returns true if colors pass through unchanged
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.amount <= 0.0
+    }
+
+    /// Applies grain to the color of the cell at (x, y) at animation `time`
+    #[inline]
+    pub fn apply(&self, color: Color, x: usize, y: usize, time: f64) -> Color {
+        if self.is_none() {
+            return color;
+        }
+
+        let frame = (time.max(0.0) * GRAIN_FPS) as u64;
+        let offset = (hash(x, y, frame) * 2.0 - 1.0) * self.amount * NOISE_STRENGTH;
+        let mut channels = [color.r as f64, color.g as f64, color.b as f64].map(|c| c + offset);
+
+        // Glints last a few frames; which cells glint changes every period
+        let period = frame / SPARKLE_FRAMES;
+        if hash(y, x, period ^ 0x5EED) < SPARKLE_RATE * self.amount {
+            let age = (frame % SPARKLE_FRAMES) as f64 / SPARKLE_FRAMES as f64;
+            let glint = SPARKLE_STRENGTH * (1.0 - age);
+            channels = channels.map(|c| c + (1.0 - c) * glint);
+        }
+
+        let [r, g, b] = channels.map(|c| c.clamp(0.0, 1.0) as f32);
+        Color::new(r, g, b, color.a)
+    }
+}
+
+/// Hashes a cell and frame to a value from 0 to 1
+#[inline]
+fn hash(x: usize, y: usize, frame: u64) -> f64 {
+    let mut h = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ frame.wrapping_mul(0x1656_67B1_9E37_79F9);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,169 @@
+//! Dual-theme color mapping
+//!
+//! Normally a pattern value picks a color from one theme. With a light map
+//! the color lookup takes two value channels: the pattern value still picks
+//! the hue and saturation from the main theme, while a second value picks a
+//! color from another theme whose lightness replaces the main color's. The
+//! second value comes from a secondary pattern sampled at the same point,
+//! or from the text mask, so one theme can paint the field while the other
+//! shades it.
+
+use colorgrad::{Color, Gradient};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::pattern::config::PatternParams;
+use crate::pattern::patterns::PerlinParams;
+use crate::pattern::registry::REGISTRY;
+use crate::themes::ThemeDefinition;
+
+/// Where the lightness channel of a light map comes from
+#[derive(Debug, Clone)]
+pub enum LightSource {
+    /// A secondary pattern sampled at the same point as the main one
+    Pattern(PatternParams),
+    /// How strongly the text mask covers the cell
+    Mask,
+}
+
+impl LightSource {
+    /// Returns the pattern id, or "mask"
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightSource::Pattern(params) => REGISTRY.get_pattern_id(params).unwrap_or("pattern"),
+            LightSource::Mask => "mask",
+        }
+    }
+}
+
+impl Default for LightSource {
+    /// Soft Perlin noise, which shades any pattern without fighting it
+    fn default() -> Self {
+        LightSource::Pattern(PatternParams::Perlin(PerlinParams::default()))
+    }
+}
+
+impl fmt::Display for LightSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LightSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        if name == "mask" {
+            return Ok(LightSource::Mask);
+        }
+        REGISTRY
+            .create_pattern_params(&name)
+            .map(LightSource::Pattern)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid light source '{}'. Valid sources: mask, {}",
+                    s,
+                    REGISTRY.list_patterns().join(", ")
+                )
+            })
+    }
+}
+
+/// A second theme and the value channel that picks lightness from it
+#[derive(Clone)]
+pub struct LightMap {
+    /// Where the lightness value comes from
+    source: LightSource,
+    /// Theme lightness is taken from
+    theme: Arc<ThemeDefinition>,
+    /// Gradient built from the theme
+    gradient: Arc<Box<dyn Gradient + Send + Sync>>,
+}
+
+impl LightMap {
+    /// Creates a light map taking lightness from `theme` at values from
+    /// `source`
+    pub fn new(theme: &ThemeDefinition, source: LightSource) -> Result<Self> {
+        Ok(Self {
+            source,
+            gradient: Arc::new(theme.create_gradient()?),
+            theme: Arc::new(theme.clone()),
+        })
+    }
+
+    /// Returns where the lightness value comes from
+    pub fn source(&self) -> &LightSource {
+        &self.source
+    }
+
+    /// Returns the name of the theme lightness is taken from
+    pub fn theme_name(&self) -> &str {
+        &self.theme.name
+    }
+
+    /// Returns true if the lightness value comes from the text mask
+    pub fn uses_mask(&self) -> bool {
+        matches!(self.source, LightSource::Mask)
+    }
+
+    /// Gives `color` the lightness of the light theme's color at `light`
+    /// (0 to 1), keeping its hue, saturation, and alpha
+    #[inline]
+    pub fn apply(&self, color: Color, light: f64, time: f64) -> Color {
+        let position = self.theme.position_at(light as f32, time as f32);
+        let (_, _, lightness) = to_hsl(&self.gradient.at(position));
+        let (hue, saturation, _) = to_hsl(&color);
+        let [r, g, b] = from_hsl(hue, saturation, lightness);
+        Color::new(r, g, b, color.a)
+    }
+}
+
+/// Converts a color to hue (0-6, in sextants), saturation, and lightness
+fn to_hsl(color: &Color) -> (f32, f32, f32) {
+    let (r, g, b) = (color.r, color.g, color.b);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let chroma = max - min;
+    if chroma <= f32::EPSILON {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    (hue, saturation.min(1.0), lightness)
+}
+
+/// Converts hue (0-6, in sextants), saturation, and lightness to RGB
+fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> [f32; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let offset = lightness - chroma / 2.0;
+    [r + offset, g + offset, b + offset].map(|c| c.clamp(0.0, 1.0))
+}
+
+impl fmt::Debug for LightMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LightMap")
+            .field("source", &self.source)
+            .field("theme", &self.theme.name)
+            .finish()
+    }
+}
@@ -1,20 +1,41 @@
 //! Pattern generation and configuration for ChromaCat
 
+pub mod blend;
+pub mod clock;
 pub mod config;
 pub mod engine;
+pub mod grain;
+pub mod light;
+pub mod palette;
 pub mod params;
 pub mod patterns;
+pub mod color;
+pub mod postprocess;
+pub mod presets;
 pub mod utils;
 pub mod registry;
+pub mod space;
 
-pub use config::{CommonParams, PatternConfig, PatternParams};
+pub use blend::{BlendMode, PatternLayer};
+pub use clock::{AnimationClock, FixedStep, LoopMode, MAX_CYCLE_SECONDS};
+pub use config::{
+    parse_pattern_pan, AspectOverride, CommonParams, PatternConfig, PatternParams, MAX_PATTERN_PAN,
+    PATTERN_ZOOM_RANGE,
+};
 pub use engine::PatternEngine;
+pub use grain::{Grain, DEFAULT_GRAIN, GRAIN_RANGE};
+pub use light::{LightMap, LightSource};
+pub use palette::Palette;
 pub use params::{ParamType, PatternParam};
+pub use color::ColorAdjust;
+pub use postprocess::{CharRamp, DitherMode, PostProcess};
+pub use presets::Preset;
 pub use patterns::{
     CheckerboardParams, DiagonalParams, DiamondParams, HorizontalParams,
     PerlinParams, PlasmaParams, RippleParams, SpiralParams, WaveParams,
 };
 pub use registry::{PatternMetadata, PatternRegistry, REGISTRY};
+pub use space::{PatternSpace, DEFAULT_CHAR_ASPECT_RATIO};
 
 // Re-export common pattern functionality
 pub use patterns::Patterns;
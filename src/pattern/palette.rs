@@ -0,0 +1,94 @@
+//! Alternate palettes
+//!
+//! A palette recolors pattern values for part of the output, such as the
+//! lines of a diff, without touching the pattern: values still come from the
+//! engine at the same point and time, and the engine's light map, color
+//! adjustments, and grain still apply. The palette swaps in its own theme,
+//! or keeps the engine's, and adds color adjustments of its own on top. It
+//! can also narrow the values to part of the gradient, so cells drawn with
+//! it only take colors from that stretch of the theme.
+
+use colorgrad::{Color, Gradient};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::pattern::color::ColorAdjust;
+use crate::themes::ThemeDefinition;
+
+/// A theme and the gradient built from it
+type ThemeGradient = (Arc<ThemeDefinition>, Arc<Box<dyn Gradient + Send + Sync>>);
+
+/// A theme and color adjustments that replace an engine's for some cells
+#[derive(Clone)]
+pub struct Palette {
+    /// Theme used instead of the engine's, with its gradient
+    theme: Option<ThemeGradient>,
+    /// Adjustments applied after the engine's own
+    adjust: ColorAdjust,
+    /// Part of the gradient that pattern values from 0 to 1 are spread over
+    range: (f64, f64),
+}
+
+impl Palette {
+    /// Creates a palette drawing with `theme`, or with the engine's theme if
+    /// there is none, and adjusting colors by `adjust`
+    pub fn new(theme: Option<&ThemeDefinition>, adjust: ColorAdjust) -> Result<Self> {
+        let theme = match theme {
+            Some(theme) => Some((Arc::new(theme.clone()), Arc::new(theme.create_gradient()?))),
+            None => None,
+        };
+        Ok(Self {
+            theme,
+            adjust,
+            range: (0.0, 1.0),
+        })
+    }
+
+    /// Narrows pattern values to the part of the gradient from `start` to
+    /// `end`
+    pub fn with_range(mut self, start: f64, end: f64) -> Self {
+        self.range = (start, end);
+        self
+    }
+
+    /// Returns the part of the gradient pattern values are spread over
+    pub fn range(&self) -> (f64, f64) {
+        self.range
+    }
+
+    /// Maps a pattern value into the palette's part of the gradient
+    #[inline]
+    pub fn map_value(&self, value: f64) -> f64 {
+        let (start, end) = self.range;
+        start + value * (end - start)
+    }
+
+    /// Returns the name of the palette's own theme, if it has one
+    pub fn theme_name(&self) -> Option<&str> {
+        self.theme.as_ref().map(|(theme, _)| theme.name.as_str())
+    }
+
+    /// Returns the adjustments applied after the engine's own
+    pub fn adjust(&self) -> &ColorAdjust {
+        &self.adjust
+    }
+
+    /// Looks up a pattern value in the palette's own theme at `time`, or
+    /// returns None to use the engine's
+    #[inline]
+    pub fn gradient_at(&self, value: f64, time: f64) -> Option<Color> {
+        let (theme, gradient) = self.theme.as_ref()?;
+        Some(gradient.at(theme.position_at(value as f32, time as f32)))
+    }
+}
+
+impl fmt::Debug for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Palette")
+            .field("theme", &self.theme_name())
+            .field("adjust", &self.adjust)
+            .field("range", &self.range)
+            .finish()
+    }
+}
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -26,6 +27,8 @@ pub struct AuroraParams {
     pub height: f64,
     /// Controls vertical spacing between bands (0.1-1.0)
     pub spread: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl AuroraParams {
@@ -46,6 +49,7 @@ impl Default for AuroraParams {
             layers: 3,
             height: 0.5,
             spread: 0.3,
+            aspect: AspectOverride::default(),
         }
     }
 }
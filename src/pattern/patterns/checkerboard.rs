@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use crate::pattern::utils::PatternUtils;
 use std::any::Any;
@@ -41,6 +42,8 @@ pub struct CheckerboardParams {
     pub antialiasing: f64,
     /// Overall animation speed (0.1-2.0)
     pub time_scale: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl CheckerboardParams {
@@ -71,6 +74,7 @@ impl Default for CheckerboardParams {
             orbit_speed: 1.0,
             antialiasing: 0.5,
             time_scale: 0.5,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -14,6 +15,8 @@ pub struct DiagonalParams {
     pub angle: i32,
     /// Animation frequency (0.1-10.0)
     pub frequency: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl DiagonalParams {
@@ -26,6 +29,7 @@ impl Default for DiagonalParams {
         Self {
             angle: 45,
             frequency: 1.0,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -10,6 +11,8 @@ define_param!(num Diamond, SharpnessParam, "sharpness", "Edge sharpness", 0.1, 5
 define_param!(num Diamond, RotationParam, "rotation", "Pattern rotation", 0.0, 360.0, 0.0);
 define_param!(num Diamond, SpeedParam, "speed", "Animation speed", 0.0, 5.0, 1.0);
 define_param!(enum Diamond, AnimationModeParam, "mode", "Animation mode", &["zoom", "scroll", "static"], "zoom");
+define_param!(num Diamond, CenterXParam, "center_x", "Pattern center, as a fraction of the width", 0.0, 1.0, 0.5);
+define_param!(num Diamond, CenterYParam, "center_y", "Pattern center, as a fraction of the height", 0.0, 1.0, 0.5);
 
 /// Parameters for configuring diamond pattern effects
 #[derive(Debug, Clone)]
@@ -26,6 +29,12 @@ pub struct DiamondParams {
     pub speed: f64,
     /// Animation mode (zoom/scroll/static)
     pub mode: String,
+    /// Pattern center as a fraction of the width from the left (0.0-1.0)
+    pub center_x: f64,
+    /// Pattern center as a fraction of the height from the top (0.0-1.0)
+    pub center_y: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl DiamondParams {
@@ -35,6 +44,8 @@ impl DiamondParams {
     const ROTATION_PARAM: DiamondRotationParam = DiamondRotationParam;
     const SPEED_PARAM: DiamondSpeedParam = DiamondSpeedParam;
     const MODE_PARAM: DiamondAnimationModeParam = DiamondAnimationModeParam;
+    const CENTER_X_PARAM: DiamondCenterXParam = DiamondCenterXParam;
+    const CENTER_Y_PARAM: DiamondCenterYParam = DiamondCenterYParam;
 }
 
 impl Default for DiamondParams {
@@ -46,6 +57,9 @@ impl Default for DiamondParams {
             rotation: 0.0,
             speed: 1.0,
             mode: "zoom".to_string(),
+            center_x: 0.5,
+            center_y: 0.5,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -57,7 +71,9 @@ define_param!(validate DiamondParams,
     SHARPNESS_PARAM: DiamondSharpnessParam,
     ROTATION_PARAM: DiamondRotationParam,
     SPEED_PARAM: DiamondSpeedParam,
-    MODE_PARAM: DiamondAnimationModeParam
+    MODE_PARAM: DiamondAnimationModeParam,
+    CENTER_X_PARAM: DiamondCenterXParam,
+    CENTER_Y_PARAM: DiamondCenterYParam
 );
 
 impl PatternParam for DiamondParams {
@@ -75,8 +91,15 @@ impl PatternParam for DiamondParams {
 
     fn default_value(&self) -> String {
         format!(
-            "size={},offset={},sharpness={},rotation={},speed={},mode={}",
-            self.size, self.offset, self.sharpness, self.rotation, self.speed, self.mode
+            "size={},offset={},sharpness={},rotation={},speed={},mode={},center_x={},center_y={}",
+            self.size,
+            self.offset,
+            self.sharpness,
+            self.rotation,
+            self.speed,
+            self.mode,
+            self.center_x,
+            self.center_y
         )
     }
 
@@ -118,6 +141,14 @@ impl PatternParam for DiamondParams {
                     Self::MODE_PARAM.validate(kv[1])?;
                     params.mode = kv[1].to_string();
                 }
+                "center_x" => {
+                    Self::CENTER_X_PARAM.validate(kv[1])?;
+                    params.center_x = kv[1].parse().unwrap();
+                }
+                "center_y" => {
+                    Self::CENTER_Y_PARAM.validate(kv[1])?;
+                    params.center_y = kv[1].parse().unwrap();
+                }
                 invalid_param => {
                     return Err(format!("Invalid parameter name: {}", invalid_param));
                 }
@@ -135,6 +166,8 @@ impl PatternParam for DiamondParams {
             Box::new(Self::ROTATION_PARAM),
             Box::new(Self::SPEED_PARAM),
             Box::new(Self::MODE_PARAM),
+            Box::new(Self::CENTER_X_PARAM),
+            Box::new(Self::CENTER_Y_PARAM),
         ]
     }
 
@@ -159,6 +192,13 @@ impl super::Patterns {
             (sin_val, cos_val)
         };
 
+        // Measure from the pattern's center
+        let (center_x, center_y) = self
+            .space_with(&params.aspect)
+            .place(params.center_x, params.center_y);
+        let x_norm = x_norm - center_x;
+        let y_norm = y_norm - center_y;
+
         // Optimize coordinate rotation
         let x_rot = x_norm * cos_rot - y_norm * sin_rot;
         let y_rot = x_norm * sin_rot + y_norm * cos_rot;
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 
@@ -27,6 +28,8 @@ pub struct FireParams {
     pub wind: bool,
     /// Strength of wind effect (0.0-1.0)
     pub wind_strength: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl FireParams {
@@ -47,6 +50,7 @@ impl Default for FireParams {
             height: 1.0,
             wind: true,
             wind_strength: 0.3,
+            aspect: AspectOverride::default(),
         }
     }
 }
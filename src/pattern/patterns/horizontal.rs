@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 
@@ -10,6 +11,8 @@ define_param!(bool Horizontal, InvertParam, "invert", "Invert gradient direction
 pub struct HorizontalParams {
     /// Invert the gradient direction (false = left to right, true = right to left)
     pub invert: bool,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl HorizontalParams {
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -28,6 +29,8 @@ pub struct KaleidoscopeParams {
     pub color_flow: f64,
     /// Amount of pattern distortion (0.0-1.0). Adds organic movement to the geometric base.
     pub distortion: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl KaleidoscopeParams {
@@ -48,6 +51,7 @@ impl Default for KaleidoscopeParams {
             complexity: 2.0,
             color_flow: 1.0,
             distortion: 0.3,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -197,7 +201,7 @@ impl super::Patterns {
 
         // Transform input coordinates with zoom and aspect ratio correction
         let x = x_norm * params.zoom;
-        let y = y_pos * params.zoom * self.char_aspect_ratio; // Apply aspect ratio to y
+        let y = y_pos * params.zoom * self.space.char_aspect_ratio(); // Apply aspect ratio to y
 
         // Calculate polar coordinates for radial effects
         let (angle, distance) = {
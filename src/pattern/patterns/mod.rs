@@ -27,67 +27,82 @@ pub use aurora::AuroraParams;
 pub use kaleidoscope::KaleidoscopeParams;
 
 use crate::pattern::utils::PatternUtils;
-use crate::pattern::config::PatternParams;
+use crate::pattern::config::{AspectOverride, PatternParams};
+use crate::pattern::space::PatternSpace;
 
 /// Core pattern generation struct that handles various visual effects
 pub struct Patterns {
     /// Utility functions for pattern calculations
     utils: PatternUtils,
-    /// Width of the pattern area in pixels
-    width: usize,
-    /// Height of the pattern area in pixels
-    height: usize,
+    /// Pattern area and its coordinate mapping
+    space: PatternSpace,
     /// Current animation time in seconds
     time: f64,
-    /// Terminal character aspect ratio (width/height)
-    char_aspect_ratio: f64,
-    /// Whether to apply aspect ratio correction
-    correct_aspect: bool,
 }
 
 impl Patterns {
     /// Creates a new Patterns instance
     pub fn new(width: usize, height: usize, time: f64, seed: u32) -> Self {
+        Self::with_space(PatternSpace::new(width, height), time, seed)
+    }
+
+    /// Creates a Patterns instance sampling through the given coordinate
+    /// mapping
+    pub fn with_space(space: PatternSpace, time: f64, seed: u32) -> Self {
         Self {
             utils: PatternUtils::new(seed),
-            width,
-            height,
+            space,
             time,
-            char_aspect_ratio: 0.5, // Default terminal character aspect ratio
-            correct_aspect: true,  // Enable by default
         }
     }
 
-    /// Helper method to normalize coordinates with optional aspect ratio correction
+    /// Returns the coordinate mapping patterns are sampled through
+    pub fn space(&self) -> &PatternSpace {
+        &self.space
+    }
+
+    /// Converts a pixel position to pattern coordinates; see
+    /// [`PatternSpace`]
     pub fn normalize_coords(&self, x: usize, y: usize) -> (f64, f64) {
-        let x_norm = x as f64 / self.width as f64;
-        let y_norm = y as f64 / self.height as f64;
+        let (x_norm, y_norm) = self.space.normalize(x as f64, y as f64);
+        self.space.to_pattern(x_norm, y_norm)
+    }
 
-        if self.correct_aspect {
-            // Apply aspect ratio correction
-            let x_centered = (x_norm - 0.5) * self.char_aspect_ratio;
-            let y_centered = y_norm - 0.5;
-            (x_centered, y_centered)
-        } else {
-            // No correction
-            (x_norm - 0.5, y_norm - 0.5)
-        }
+    /// Returns the coordinate mapping of a pattern with the given aspect
+    /// settings
+    pub fn space_with(&self, aspect: &AspectOverride) -> PatternSpace {
+        aspect.apply(self.space)
     }
 
     /// Set whether to apply aspect ratio correction
     pub fn set_aspect_correction(&mut self, enabled: bool) {
-        self.correct_aspect = enabled;
+        self.space = self
+            .space
+            .with_aspect(enabled, self.space.char_aspect_ratio());
     }
 
     /// Set the character aspect ratio
     pub fn set_char_aspect_ratio(&mut self, ratio: f64) {
-        self.char_aspect_ratio = ratio.clamp(0.1, 2.0);
+        self.space = self.space.with_aspect(self.space.corrects_aspect(), ratio);
     }
 
-    /// Generate a pattern value at the given coordinates
+    /// Generate a pattern value at the given pixel coordinates
     pub fn generate(&self, x: usize, y: usize, params: &PatternParams) -> f64 {
         let (x_norm, y_norm) = self.normalize_coords(x, y);
-        
+        self.generate_at(x_norm, y_norm, params)
+    }
+
+    /// Generate a pattern value at the given pattern coordinates; see
+    /// [`PatternSpace::to_pattern`]
+    pub fn generate_at(&self, x_norm: f64, y_norm: f64, params: &PatternParams) -> f64 {
+        // Rescale the horizontal axis for a pattern with its own aspect
+        // settings
+        let aspect = params.aspect();
+        let x_norm = if *aspect == AspectOverride::default() {
+            x_norm
+        } else {
+            x_norm / self.space.x_scale() * self.space_with(aspect).x_scale()
+        };
         match params {
             PatternParams::Horizontal(p) => self.horizontal(x_norm + 0.5, p.clone()),
             PatternParams::Diagonal(p) => self.diagonal(x_norm, y_norm, p.clone()),
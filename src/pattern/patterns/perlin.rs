@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 
@@ -19,6 +20,8 @@ pub struct PerlinParams {
     pub scale: f64,
     /// Random seed for noise generation
     pub seed: u32,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl PerlinParams {
@@ -35,6 +38,7 @@ impl Default for PerlinParams {
             persistence: 0.5,
             scale: 1.0,
             seed: 0,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -26,6 +27,8 @@ pub struct PixelRainParams {
     pub glitch_freq: f64,
     /// Speed variation between streams (0.0-1.0)
     pub speed_var: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl PixelRainParams {
@@ -46,6 +49,7 @@ impl Default for PixelRainParams {
             glitch: true,
             glitch_freq: 1.0,
             speed_var: 0.5, // Default speed variation
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -36,6 +37,8 @@ pub struct PlasmaParams {
     pub frequency: f64,
     /// Color blending mode
     pub blend_mode: PlasmaBlendMode,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl PlasmaParams {
@@ -52,6 +55,7 @@ impl Default for PlasmaParams {
             scale: 1.0,
             frequency: 1.0,
             blend_mode: PlasmaBlendMode::default(),
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -1,11 +1,12 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
 
 // Define parameters with proper CLI names and bounds
-define_param!(num Ripple, CenterXParam, "center_x", "X-coordinate of the ripple center", 0.0, 1.0, 0.5);
-define_param!(num Ripple, CenterYParam, "center_y", "Y-coordinate of the ripple center", 0.0, 1.0, 0.5);
+define_param!(num Ripple, CenterXParam, "center_x", "Ripple center, as a fraction of the width", 0.0, 1.0, 0.5);
+define_param!(num Ripple, CenterYParam, "center_y", "Ripple center, as a fraction of the height", 0.0, 1.0, 0.5);
 define_param!(num Ripple, WavelengthParam, "wavelength", "Distance between ripple waves", 0.1, 5.0, 1.0);
 define_param!(num Ripple, DampingParam, "damping", "How quickly ripples fade out", 0.0, 1.0, 0.5);
 define_param!(num Ripple, FrequencyParam, "frequency", "Speed of ripple animation", 0.1, 10.0, 1.0);
@@ -13,9 +14,9 @@ define_param!(num Ripple, FrequencyParam, "frequency", "Speed of ripple animatio
 /// Parameters for configuring ripple pattern effects
 #[derive(Debug, Clone)]
 pub struct RippleParams {
-    /// X-coordinate of the ripple center (0.0-1.0)
+    /// Ripple center as a fraction of the width from the left (0.0-1.0)
     pub center_x: f64,
-    /// Y-coordinate of the ripple center (0.0-1.0)
+    /// Ripple center as a fraction of the height from the top (0.0-1.0)
     pub center_y: f64,
     /// Distance between ripple waves (0.1-5.0)
     pub wavelength: f64,
@@ -23,6 +24,8 @@ pub struct RippleParams {
     pub damping: f64,
     /// Speed of ripple animation (0.1-10.0)
     pub frequency: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl RippleParams {
@@ -41,6 +44,7 @@ impl Default for RippleParams {
             wavelength: 1.0,
             damping: 0.5,
             frequency: 1.0,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -141,10 +145,11 @@ impl super::Patterns {
     #[inline(always)]
     pub fn ripple(&self, x_norm: f64, y_norm: f64, params: RippleParams) -> f64 {
         // Pre-calculate coordinates relative to center
-        let x_pos = x_norm + 0.5;
-        let y_pos = y_norm + 0.5;
-        let dx = x_pos - params.center_x;
-        let dy = y_pos - params.center_y;
+        let (center_x, center_y) = self
+            .space_with(&params.aspect)
+            .place(params.center_x, params.center_y);
+        let dx = x_norm - center_x;
+        let dy = y_norm - center_y;
 
         // Calculate distance once
         let dist_sq = dx * dx + dy * dy;
@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -9,6 +10,8 @@ define_param!(num Spiral, RotationParam, "rotation", "Base rotation angle in deg
 define_param!(num Spiral, ExpansionParam, "expansion", "How quickly spiral expands", 0.1, 2.0, 1.0);
 define_param!(bool Spiral, ClockwiseParam, "clockwise", "Direction of spiral rotation", true);
 define_param!(num Spiral, FrequencyParam, "frequency", "Animation speed", 0.1, 10.0, 1.0);
+define_param!(num Spiral, CenterXParam, "center_x", "Spiral center, as a fraction of the width", 0.0, 1.0, 0.5);
+define_param!(num Spiral, CenterYParam, "center_y", "Spiral center, as a fraction of the height", 0.0, 1.0, 0.5);
 
 /// Parameters for configuring spiral pattern effects
 #[derive(Debug, Clone)]
@@ -23,6 +26,12 @@ pub struct SpiralParams {
     pub clockwise: bool,
     /// Speed of spiral animation (0.1-10.0)
     pub frequency: f64,
+    /// Spiral center as a fraction of the width from the left (0.0-1.0)
+    pub center_x: f64,
+    /// Spiral center as a fraction of the height from the top (0.0-1.0)
+    pub center_y: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl SpiralParams {
@@ -31,6 +40,8 @@ impl SpiralParams {
     const EXPANSION_PARAM: SpiralExpansionParam = SpiralExpansionParam;
     const CLOCKWISE_PARAM: SpiralClockwiseParam = SpiralClockwiseParam;
     const FREQUENCY_PARAM: SpiralFrequencyParam = SpiralFrequencyParam;
+    const CENTER_X_PARAM: SpiralCenterXParam = SpiralCenterXParam;
+    const CENTER_Y_PARAM: SpiralCenterYParam = SpiralCenterYParam;
 }
 
 impl Default for SpiralParams {
@@ -41,6 +52,9 @@ impl Default for SpiralParams {
             expansion: 1.0,
             clockwise: true,
             frequency: 1.0,
+            center_x: 0.5,
+            center_y: 0.5,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -51,7 +65,9 @@ define_param!(validate SpiralParams,
     ROTATION_PARAM: SpiralRotationParam,
     EXPANSION_PARAM: SpiralExpansionParam,
     CLOCKWISE_PARAM: SpiralClockwiseParam,
-    FREQUENCY_PARAM: SpiralFrequencyParam
+    FREQUENCY_PARAM: SpiralFrequencyParam,
+    CENTER_X_PARAM: SpiralCenterXParam,
+    CENTER_Y_PARAM: SpiralCenterYParam
 );
 
 impl PatternParam for SpiralParams {
@@ -69,8 +85,14 @@ impl PatternParam for SpiralParams {
 
     fn default_value(&self) -> String {
         format!(
-            "density={},rotation={},expansion={},clockwise={},frequency={}",
-            self.density, self.rotation, self.expansion, self.clockwise, self.frequency
+            "density={},rotation={},expansion={},clockwise={},frequency={},center_x={},center_y={}",
+            self.density,
+            self.rotation,
+            self.expansion,
+            self.clockwise,
+            self.frequency,
+            self.center_x,
+            self.center_y
         )
     }
 
@@ -108,6 +130,14 @@ impl PatternParam for SpiralParams {
                     Self::FREQUENCY_PARAM.validate(kv[1])?;
                     params.frequency = kv[1].parse().unwrap();
                 }
+                "center_x" => {
+                    Self::CENTER_X_PARAM.validate(kv[1])?;
+                    params.center_x = kv[1].parse().unwrap();
+                }
+                "center_y" => {
+                    Self::CENTER_Y_PARAM.validate(kv[1])?;
+                    params.center_y = kv[1].parse().unwrap();
+                }
                 invalid_param => {
                     return Err(format!("Invalid parameter name: {}", invalid_param));
                 }
@@ -124,6 +154,8 @@ impl PatternParam for SpiralParams {
             Box::new(Self::EXPANSION_PARAM),
             Box::new(Self::CLOCKWISE_PARAM),
             Box::new(Self::FREQUENCY_PARAM),
+            Box::new(Self::CENTER_X_PARAM),
+            Box::new(Self::CENTER_Y_PARAM),
         ]
     }
 
@@ -148,6 +180,13 @@ impl super::Patterns {
         let time_sin = self.utils.fast_sin(time_slow);
         let time_sin_half = self.utils.fast_sin(time_slow * 0.5);
 
+        // Measure from the spiral's center
+        let (center_x, center_y) = self
+            .space_with(&params.aspect)
+            .place(params.center_x, params.center_y);
+        let x_norm = x_norm - center_x;
+        let y_norm = y_norm - center_y;
+
         // Calculate angle and distance with better precision
         let angle = y_norm.atan2(x_norm);
         let dist_sq = x_norm * x_norm + y_norm * y_norm;
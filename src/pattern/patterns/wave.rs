@@ -1,4 +1,5 @@
 use crate::define_param;
+use crate::pattern::config::AspectOverride;
 use crate::pattern::params::{ParamType, PatternParam};
 use std::any::Any;
 use std::f64::consts::PI;
@@ -19,6 +20,8 @@ pub struct WaveParams {
     pub phase: f64,
     pub offset: f64,
     pub base_freq: f64,
+    /// Aspect settings used instead of the common ones
+    pub aspect: AspectOverride,
 }
 
 impl Default for WaveParams {
@@ -29,6 +32,7 @@ impl Default for WaveParams {
             phase: 0.0,
             offset: 0.5,
             base_freq: 1.0,
+            aspect: AspectOverride::default(),
         }
     }
 }
@@ -0,0 +1,158 @@
+//! Post-processing of pattern values
+//!
+//! Pattern values can be reshaped after generation and before the gradient
+//! lookup. Posterizing snaps values to a fixed number of levels for a banded,
+//! retro look, and dithering breaks up the resulting bands with a per-cell
+//! threshold. A character ramp maps values to density glyphs so blank cells
+//! can be shaded like ASCII art. Colors taken from the gradient can then be
+//! tuned with [`ColorAdjust`].
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::color::ColorAdjust;
+use super::grain::Grain;
+
+/// 4x4 Bayer matrix used for ordered dithering
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Default character ramp, from densest to lightest
+pub const DEFAULT_CHAR_RAMP: &str = "█▓▒░ ";
+
+/// Dithering strategy applied when posterizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DitherMode {
+    /// Hard thresholds between levels
+    #[default]
+    None,
+    /// Regular crosshatch from a 4x4 Bayer matrix
+    Ordered,
+    /// Irregular, evenly spread noise without visible structure
+    BlueNoise,
+}
+
+impl DitherMode {
+    /// Returns a list of all dither modes
+    pub fn all() -> &'static [DitherMode] {
+        &[DitherMode::None, DitherMode::Ordered, DitherMode::BlueNoise]
+    }
+
+    /// Get string representation of the dither mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DitherMode::None => "none",
+            DitherMode::Ordered => "ordered",
+            DitherMode::BlueNoise => "blue-noise",
+        }
+    }
+
+    /// Returns the dither threshold for a cell, centered on zero (-0.5 to 0.5)
+    pub fn threshold(&self, x: usize, y: usize) -> f64 {
+        match self {
+            DitherMode::None => 0.0,
+            DitherMode::Ordered => (BAYER_4X4[y % 4][x % 4] as f64 + 0.5) / 16.0 - 0.5,
+            // Interleaved gradient noise: a cheap blue-noise approximation
+            // with no low-frequency clumping
+            DitherMode::BlueNoise => {
+                let v = 52.982_918_9 * (0.067_110_56 * x as f64 + 0.005_837_15 * y as f64).fract();
+                v.fract() - 0.5
+            }
+        }
+    }
+}
+
+impl fmt::Display for DitherMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for DitherMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(DitherMode::None),
+            "ordered" | "bayer" => Ok(DitherMode::Ordered),
+            "blue-noise" | "bluenoise" | "noise" => Ok(DitherMode::BlueNoise),
+            _ => Err(format!(
+                "Invalid dither mode '{}'. Valid modes: none, ordered, blue-noise",
+                s
+            )),
+        }
+    }
+}
+
+/// Settings for reshaping pattern values before gradient lookup, and the
+/// colors looked up from them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostProcess {
+    /// Number of discrete levels to snap values to (None disables posterizing)
+    pub levels: Option<u32>,
+    /// Dithering applied between levels
+    pub dither: DitherMode,
+    /// Adjustments applied to gradient colors
+    pub color: ColorAdjust,
+    /// Noise and sparkle applied on top of the adjusted colors
+    pub grain: Grain,
+}
+
+impl PostProcess {
+    /// Returns true if values and colors pass through unchanged
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        self.levels.is_none() && self.color.is_identity() && self.grain.is_none()
+    }
+
+    /// Applies posterizing and dithering to a value for the cell at (x, y)
+    #[inline]
+    pub fn apply(&self, value: f64, x: usize, y: usize) -> f64 {
+        let Some(levels) = self.levels else {
+            return value;
+        };
+
+        let steps = levels.max(2) as f64 - 1.0;
+        let scaled = value.clamp(0.0, 1.0) * steps + self.dither.threshold(x, y);
+        (scaled.round().clamp(0.0, steps)) / steps
+    }
+}
+
+/// Maps pattern values to density characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharRamp {
+    /// Characters from densest to lightest
+    chars: Vec<char>,
+}
+
+impl CharRamp {
+    /// Creates a ramp from a string of characters ordered densest to lightest
+    pub fn new(chars: &str) -> Self {
+        let chars: Vec<char> = chars.chars().collect();
+        if chars.is_empty() {
+            Self::default()
+        } else {
+            Self { chars }
+        }
+    }
+
+    /// Returns the characters of the ramp
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns the glyph for a value; higher values map to denser glyphs
+    #[inline]
+    pub fn glyph(&self, value: f64) -> char {
+        let last = self.chars.len() - 1;
+        let index = ((1.0 - value.clamp(0.0, 1.0)) * last as f64).round() as usize;
+        self.chars[index.min(last)]
+    }
+}
+
+impl Default for CharRamp {
+    fn default() -> Self {
+        Self {
+            chars: DEFAULT_CHAR_RAMP.chars().collect(),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Named parameter presets
+//!
+//! Patterns ship presets: named, complete parameter sets such as plasma's
+//! "lava" or "electric". They are defined in `presets.yaml` next to this
+//! module and compiled in, chosen with `--preset`, cycled while animating,
+//! and listed by `--pattern-help` and `--list --format json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Preset definitions, keyed by pattern id
+const PRESETS_YAML: &str = include_str!("presets.yaml");
+
+/// A named set of parameters for one pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    /// Name used with `--preset`
+    pub name: String,
+    /// What the pattern looks like with the preset
+    pub description: String,
+    /// Parameter values, by name
+    pub params: serde_yaml::Mapping,
+}
+
+impl Preset {
+    /// Returns the parameters in `--param` form, e.g. `scale=0.6,frequency=0.4`
+    pub fn params_string(&self) -> String {
+        self.params
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                format!("{}={}", name.as_str().unwrap_or_default(), value)
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Every pattern's presets, in the order they are defined
+    static ref PRESETS: HashMap<String, Vec<Preset>> =
+        serde_yaml::from_str(PRESETS_YAML).expect("presets.yaml is valid");
+}
+
+/// Returns a pattern's presets, in the order they are defined
+pub fn presets(pattern: &str) -> &'static [Preset] {
+    PRESETS.get(pattern).map(Vec::as_slice).unwrap_or_default()
+}
@@ -1,6 +1,9 @@
-use crate::pattern::config::PatternParams;
-use crate::pattern::params::PatternParam;
+use crate::pattern::config::{AspectOverride, PatternParams};
+use crate::pattern::params::{ParamType, PatternParam};
+use crate::pattern::presets::{self, Preset};
 use crate::pattern::patterns::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
 use std::collections::HashMap;
 use std::sync::Arc; // Import all pattern types
 
@@ -33,6 +36,11 @@ impl PatternMetadata {
     pub fn params(&self) -> &dyn PatternParam {
         &**self.default_params
     }
+
+    /// Gets the named parameter presets this pattern ships with
+    pub fn presets(&self) -> &'static [Preset] {
+        presets::presets(self.id)
+    }
 }
 
 /// Macro to define pattern registration entries
@@ -78,6 +86,16 @@ macro_rules! define_pattern_registry {
                     $(PatternParams::$variant(_) => Some($id),)*
                 }
             }
+
+            /// Formats parameters as the `key=value` pairs `--param` accepts
+            pub fn format_params(&self, params: &PatternParams) -> String {
+                let formatted = match params {
+                    $(PatternParams::$variant(params) => params.default_value(),)*
+                };
+                let mut pairs = vec![formatted];
+                pairs.extend(params.aspect().pairs());
+                pairs.join(",")
+            }
         }
     };
 }
@@ -178,23 +196,99 @@ impl PatternRegistry {
     /// Validates parameters for a pattern
     pub fn validate_params(&self, id: &str, params: &str) -> Result<(), String> {
         if let Some(metadata) = self.get_pattern(id) {
-            metadata.default_params.validate(params)
+            let (own, aspect) = split_aspect_params(params);
+            for (name, value) in &aspect {
+                AspectOverride::validate(name, value)?;
+            }
+            if own.is_empty() && !aspect.is_empty() {
+                return Ok(());
+            }
+            metadata.default_params.validate(&own)
         } else {
             Err(format!("Unknown pattern: {}", id))
         }
     }
 
+    /// Finds a pattern's preset by name, ignoring case
+    pub fn find_preset(&self, id: &str, name: &str) -> Option<&'static Preset> {
+        presets::presets(id)
+            .iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Draws a value for every parameter of a pattern, each within the range
+    /// the pattern declares, as `(name, value)` pairs in `--param` form.
+    /// Ranges whose bounds and default are whole numbers get whole values.
+    pub fn random_params<R: Rng + ?Sized>(
+        &self,
+        id: &str,
+        rng: &mut R,
+    ) -> Result<Vec<(String, String)>, String> {
+        let metadata = self
+            .get_pattern(id)
+            .ok_or_else(|| format!("Unknown pattern: {}", id))?;
+
+        let mut values = Vec::new();
+        for param in metadata.params().sub_params() {
+            let value = match param.param_type() {
+                ParamType::Number { min, max } => {
+                    let default = param.default_value().parse().unwrap_or(0.5);
+                    if [min, max, default].iter().all(|v: &f64| v.fract() == 0.0) {
+                        rng.gen_range(min as i64..=max as i64).to_string()
+                    } else {
+                        // Two decimals read better, unless rounding leaves
+                        // the range
+                        let value = rng.gen_range(min..=max);
+                        let rounded = (value * 100.0).round() / 100.0;
+                        if (min..=max).contains(&rounded) {
+                            rounded.to_string()
+                        } else {
+                            value.to_string()
+                        }
+                    }
+                }
+                ParamType::Boolean => rng.gen_bool(0.5).to_string(),
+                ParamType::Enum { options } => match options.choose(rng) {
+                    Some(option) => option.to_string(),
+                    None => continue,
+                },
+                ParamType::Composite => continue,
+            };
+            values.push((param.name().to_string(), value));
+        }
+        Ok(values)
+    }
+
     /// Parses parameters for a pattern
     pub fn parse_params(&self, id: &str, params: &str) -> Result<PatternParams, String> {
         if let Some(metadata) = self.get_pattern(id) {
-            let parsed = metadata.default_params.parse(params)?;
-            self.pattern_to_params(id, parsed)
+            let (own, aspect) = split_aspect_params(params);
+            let parsed = metadata.default_params.parse(&own)?;
+            let mut params = self.pattern_to_params(id, parsed)?;
+            for (name, value) in &aspect {
+                params.aspect_mut().set(name, value)?;
+            }
+            Ok(params)
         } else {
             Err(format!("Unknown pattern: {}", id))
         }
     }
 }
 
+/// Splits `key=value` pairs into the pattern's own, joined again, and the
+/// aspect parameters every pattern takes
+fn split_aspect_params(params: &str) -> (String, Vec<(&str, &str)>) {
+    let mut own = Vec::new();
+    let mut aspect = Vec::new();
+    for part in params.split(',') {
+        match part.trim().split_once('=') {
+            Some((name, value)) if AspectOverride::is_param(name) => aspect.push((name, value)),
+            _ => own.push(part),
+        }
+    }
+    (own.join(","), aspect)
+}
+
 // Create a lazy static instance for global access
 lazy_static::lazy_static! {
     pub static ref REGISTRY: PatternRegistry = PatternRegistry::new();
@@ -0,0 +1,156 @@
+//! Pattern coordinate conventions
+//!
+//! Every render path samples patterns through the same two steps:
+//!
+//! 1. **Normalized coordinates** place a point on the pattern area with
+//!    `(0, 0)` at its center and the edges at `-0.5` and `0.5` on both axes,
+//!    whatever its size in pixels. Pixel positions convert with
+//!    [`PatternSpace::normalize`]; fractional pixels are allowed, which is
+//!    how sub-cell resolutions sample between cells.
+//! 2. **Pattern coordinates** are what pattern functions receive: normalized
+//!    coordinates divided by the pattern's zoom and offset by its pan, then
+//!    with the horizontal axis scaled by the character aspect ratio when
+//!    aspect correction is on, so shapes keep their proportions in terminal
+//!    cells that are about twice as tall as they are wide. See
+//!    [`PatternSpace::to_pattern`].
+//!
+//! The pattern's zoom and pan come from its common parameters and work for
+//! every pattern. A pattern may use its own aspect settings instead, given
+//! by its `correct_aspect` and `aspect_ratio` parameters; its horizontal
+//! coordinate is then rescaled before the pattern sees it. Points patterns
+//! take as parameters, like a ripple's `center_x` and `center_y`, are
+//! fractions of the area's width and height from its top left corner,
+//! placed before zoom and pan so they move with the pattern. The renderer's camera is separate and comes first: it maps
+//! normalized screen coordinates to normalized coordinates on the pattern
+//! canvas, and the result is sampled with
+//! [`PatternEngine::get_value_at_normalized`](super::PatternEngine::get_value_at_normalized).
+
+/// Character aspect ratio (width / height) assumed unless configured
+pub const DEFAULT_CHAR_ASPECT_RATIO: f64 = 0.5;
+
+/// Maps positions on a pattern area to the coordinates patterns are
+/// computed in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternSpace {
+    /// Width of the pattern area in pixels
+    width: usize,
+    /// Height of the pattern area in pixels
+    height: usize,
+    /// Whether the horizontal axis is scaled by the aspect ratio
+    correct_aspect: bool,
+    /// Character width divided by character height
+    char_aspect_ratio: f64,
+    /// Pattern magnification around the pan point
+    zoom: f64,
+    /// Normalized point shown at the center of the area
+    pan: (f64, f64),
+}
+
+impl PatternSpace {
+    /// Creates a space for a `width` x `height` pixel area, with aspect
+    /// correction for the default character shape
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            correct_aspect: true,
+            char_aspect_ratio: DEFAULT_CHAR_ASPECT_RATIO,
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+        }
+    }
+
+    /// Sets whether aspect correction is applied and the character aspect
+    /// ratio it uses, clamped to 0.1-2
+    pub fn with_aspect(mut self, correct: bool, char_aspect_ratio: f64) -> Self {
+        self.correct_aspect = correct;
+        self.char_aspect_ratio = char_aspect_ratio.clamp(0.1, 2.0);
+        self
+    }
+
+    /// Sets the pattern's zoom, clamped to
+    /// [`PATTERN_ZOOM_RANGE`](super::PATTERN_ZOOM_RANGE), and the normalized
+    /// point shown at the center of the area
+    pub fn with_view(mut self, zoom: f64, pan_x: f64, pan_y: f64) -> Self {
+        let (min, max) = super::PATTERN_ZOOM_RANGE;
+        self.zoom = zoom.clamp(min, max);
+        self.pan = (pan_x, pan_y);
+        self
+    }
+
+    /// Returns the pattern area size as (width, height) in pixels
+    #[inline]
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns whether aspect correction is applied
+    #[inline]
+    pub fn corrects_aspect(&self) -> bool {
+        self.correct_aspect
+    }
+
+    /// Returns the character aspect ratio (width / height)
+    #[inline]
+    pub fn char_aspect_ratio(&self) -> f64 {
+        self.char_aspect_ratio
+    }
+
+    /// Returns the factor the horizontal axis is scaled by, which is 1
+    /// without aspect correction
+    #[inline]
+    pub fn x_scale(&self) -> f64 {
+        if self.correct_aspect {
+            self.char_aspect_ratio
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the pattern's zoom
+    #[inline]
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Returns the normalized point shown at the center of the area
+    #[inline]
+    pub fn pan(&self) -> (f64, f64) {
+        self.pan
+    }
+
+    /// Converts a position in pixels to normalized coordinates
+    #[inline]
+    pub fn normalize(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            x / self.width.max(1) as f64 - 0.5,
+            y / self.height.max(1) as f64 - 0.5,
+        )
+    }
+
+    /// Converts normalized coordinates to the pixel containing them,
+    /// clamped to the area
+    #[inline]
+    pub fn to_pixel(&self, x: f64, y: f64) -> (usize, usize) {
+        let pixel = |value: f64, size: usize| {
+            (((value + 0.5) * size as f64).max(0.0) as usize).min(size.saturating_sub(1))
+        };
+        (pixel(x, self.width), pixel(y, self.height))
+    }
+
+    /// Converts normalized coordinates to pattern coordinates
+    #[inline]
+    pub fn to_pattern(&self, x: f64, y: f64) -> (f64, f64) {
+        let x = x / self.zoom + self.pan.0;
+        let y = y / self.zoom + self.pan.1;
+        (x * self.x_scale(), y)
+    }
+
+    /// Converts a point given as fractions of the area's width and height,
+    /// as pattern parameters place them, to the pattern coordinates shown
+    /// there without zoom or pan
+    #[inline]
+    pub fn place(&self, x: f64, y: f64) -> (f64, f64) {
+        ((x - 0.5) * self.x_scale(), y - 0.5)
+    }
+}
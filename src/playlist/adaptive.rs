@@ -0,0 +1,261 @@
+//! Adaptive scenes that follow the sky outside
+//!
+//! With `--adaptive`, random scenes are drawn from a pool matched to the
+//! current sky rather than from every pattern and theme: rain brings ripples
+//! in ocean colors, a clear night aurora curtains in galaxy colors, and a
+//! sunny afternoon bright plasma. The sky comes from the time of day, or,
+//! with `--adaptive=weather`, from the weather reported for `--location` by
+//! wttr.in. Looking the weather up needs ChromaCat built with the `weather`
+//! feature and `curl` on the path; when the lookup fails, the time of day
+//! picks the scenes instead.
+
+use super::pool::{PoolChoices, ScenePool};
+use crate::error::{ChromaCatError, Result};
+use chrono::Timelike;
+use std::fmt;
+use std::str::FromStr;
+
+/// Seconds an adaptive scene lasts, longer than a random one, since the
+/// mood only changes with the weather
+pub const ADAPTIVE_SCENE_SECONDS: u64 = 45;
+
+/// Where adaptive scenes take the sky from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AdaptiveSource {
+    /// The local time of day
+    #[default]
+    Time,
+    /// The current weather, falling back to the time of day
+    Weather,
+}
+
+impl AdaptiveSource {
+    /// Returns every source
+    pub fn all() -> &'static [AdaptiveSource] {
+        &[AdaptiveSource::Time, AdaptiveSource::Weather]
+    }
+
+    /// Returns the name used for this source on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdaptiveSource::Time => "time",
+            AdaptiveSource::Weather => "weather",
+        }
+    }
+
+    /// Returns whether this build can look up the source
+    pub fn is_available(&self) -> bool {
+        match self {
+            AdaptiveSource::Time => true,
+            AdaptiveSource::Weather => cfg!(feature = "weather"),
+        }
+    }
+}
+
+impl fmt::Display for AdaptiveSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AdaptiveSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        AdaptiveSource::all()
+            .iter()
+            .find(|source| source.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = AdaptiveSource::all().iter().map(|s| s.as_str()).collect();
+                format!(
+                    "Invalid adaptive source '{}'. Valid sources: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Part of the day, which sets the mood when the weather doesn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPart {
+    /// 5:00 to 8:00
+    Dawn,
+    /// 8:00 to 18:00
+    Day,
+    /// 18:00 to 21:00
+    Dusk,
+    /// 21:00 to 5:00
+    Night,
+}
+
+impl DayPart {
+    /// Returns the part of the day an hour from 0 to 23 falls in
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=7 => DayPart::Dawn,
+            8..=17 => DayPart::Day,
+            18..=20 => DayPart::Dusk,
+            _ => DayPart::Night,
+        }
+    }
+
+    /// Returns the part of the day it is now, in local time
+    pub fn now() -> Self {
+        Self::from_hour(chrono::Local::now().hour())
+    }
+}
+
+/// Kind of weather, as far as the scenes care
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    /// Clear skies or sun
+    Clear,
+    /// Clouds or overcast
+    Cloudy,
+    /// Rain, drizzle, or showers
+    Rain,
+    /// Snow, sleet, or ice
+    Snow,
+    /// Thunder and lightning
+    Storm,
+    /// Fog, mist, or haze
+    Fog,
+}
+
+impl Weather {
+    /// Reads a weather report's description, such as `Light rain shower`
+    /// or `Partly cloudy`; returns `None` for one it doesn't recognize
+    pub fn from_description(description: &str) -> Option<Self> {
+        let description = description.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| description.contains(word));
+        if has(&["thunder", "storm"]) {
+            Some(Weather::Storm)
+        } else if has(&["snow", "sleet", "blizzard", "ice", "hail"]) {
+            Some(Weather::Snow)
+        } else if has(&["rain", "drizzle", "shower"]) {
+            Some(Weather::Rain)
+        } else if has(&["fog", "mist", "haze", "smoke"]) {
+            Some(Weather::Fog)
+        } else if has(&["cloud", "overcast"]) {
+            Some(Weather::Cloudy)
+        } else if has(&["clear", "sunny", "fair"]) {
+            Some(Weather::Clear)
+        } else {
+            None
+        }
+    }
+}
+
+/// The sky the scenes are matched to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sky {
+    /// Part of the day
+    pub part: DayPart,
+    /// Weather, when it is known
+    pub weather: Option<Weather>,
+}
+
+impl Sky {
+    /// Reads the sky from `source`. Weather is looked up for `location`,
+    /// or for wherever wttr.in places the machine; without it, only the
+    /// time of day is known.
+    pub fn detect(source: AdaptiveSource, location: Option<&str>) -> Self {
+        let weather = match source {
+            AdaptiveSource::Time => None,
+            AdaptiveSource::Weather => match fetch_weather(location) {
+                Ok(weather) => Some(weather),
+                Err(e) => {
+                    log::warn!("Falling back to the time of day: {}", e);
+                    None
+                }
+            },
+        };
+        Self {
+            part: DayPart::now(),
+            weather,
+        }
+    }
+
+    /// Returns the patterns and themes that suit this sky
+    pub fn scenes(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        match (self.weather, self.part) {
+            (Some(Weather::Storm), _) => (
+                &["plasma", "rain", "ripple"],
+                &["lightning", "nebula", "cyberpunk"],
+            ),
+            (Some(Weather::Rain), _) => (&["ripple", "rain", "wave"], &["ocean", "ice", "calm"]),
+            (Some(Weather::Snow), _) => (
+                &["perlin", "aurora", "diamond"],
+                &["ice", "pastel", "serenity"],
+            ),
+            (Some(Weather::Fog), _) => (&["perlin", "wave"], &["serenity", "monochrome", "calm"]),
+            (Some(Weather::Cloudy), DayPart::Night) => (&["perlin", "wave"], &["cosmos", "calm"]),
+            (Some(Weather::Cloudy), _) => (
+                &["perlin", "wave", "diagonal"],
+                &["calm", "serenity", "dream"],
+            ),
+            (_, DayPart::Night) => (
+                &["aurora", "perlin", "spiral"],
+                &["galaxy", "cosmos", "nebula", "borealis"],
+            ),
+            (_, DayPart::Dawn) => (
+                &["horizontal", "wave", "aurora"],
+                &["pastel", "dream", "sunset"],
+            ),
+            (_, DayPart::Dusk) => (
+                &["horizontal", "wave", "diagonal"],
+                &["sunset", "autumn", "vaporwave"],
+            ),
+            (_, DayPart::Day) => (
+                &["plasma", "kaleidoscope", "spiral", "ripple"],
+                &["energy", "desert", "forest", "heat"],
+            ),
+        }
+    }
+
+    /// Returns a scene pool of the patterns and themes that suit this sky
+    pub fn pool(&self) -> ScenePool {
+        let (patterns, themes) = self.scenes();
+        let choices = |names: &[&str]| PoolChoices {
+            include: names.iter().map(|name| name.to_string()).collect(),
+            ..PoolChoices::default()
+        };
+        ScenePool {
+            scene_seconds: Some(ADAPTIVE_SCENE_SECONDS),
+            patterns: choices(patterns),
+            themes: choices(themes),
+        }
+    }
+}
+
+/// Asks wttr.in for the current weather at `location`
+#[cfg(feature = "weather")]
+fn fetch_weather(location: Option<&str>) -> Result<Weather> {
+    let location = location.unwrap_or_default().trim().replace(' ', "+");
+    let url = format!("https://wttr.in/{}?format=%C", location);
+    let output = std::process::Command::new("curl")
+        .args(["--silent", "--fail", "--max-time", "5", &url])
+        .output()
+        .map_err(|e| ChromaCatError::Other(format!("Failed to run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(ChromaCatError::Other(format!(
+            "Weather lookup failed ({})",
+            output.status
+        )));
+    }
+
+    let description = String::from_utf8_lossy(&output.stdout);
+    log::info!("Weather: {}", description.trim());
+    Weather::from_description(&description)
+        .ok_or_else(|| ChromaCatError::Other(format!("Unknown weather '{}'", description.trim())))
+}
+
+/// Without the `weather` feature there is no weather to look up
+#[cfg(not(feature = "weather"))]
+fn fetch_weather(_location: Option<&str>) -> Result<Weather> {
+    Err(ChromaCatError::Other(
+        "ChromaCat was built without the weather feature".to_string(),
+    ))
+}
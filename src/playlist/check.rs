@@ -0,0 +1,350 @@
+//! Playlist checking
+//!
+//! Loading a playlist stops at the first problem, which is fine when it is
+//! about to play but slow going when writing a long show. Checking reads the
+//! whole file and reports every problem it finds, each with the line it is
+//! on: unknown patterns, themes, and art, parameters that don't exist or are
+//! out of range, and durations too short to show anything.
+
+use super::entry::{params_to_string, parse_until, Playlist, PlaylistEntry};
+use crate::demo::{self, DemoArt};
+use crate::pattern::REGISTRY;
+use crate::themes;
+use chrono::Local;
+use std::fmt;
+
+/// Largest edit distance at which a name is suggested for a misspelled one
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// A problem found in a playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistIssue {
+    /// Line of the file the problem is on, counting from 1
+    pub line: Option<usize>,
+    /// Index of the entry the problem is in, counting from 0
+    pub entry: Option<usize>,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for PlaylistIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if let Some(entry) = self.entry {
+            write!(f, "entry {}: ", entry + 1)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Checks playlist YAML, returning every problem found; an empty list means
+/// the playlist is ready to play
+pub fn check_playlist(contents: &str) -> Vec<PlaylistIssue> {
+    let playlist: Playlist = match serde_yaml::from_str(contents) {
+        Ok(playlist) => playlist,
+        Err(e) => {
+            return vec![PlaylistIssue {
+                line: e.location().map(|l| l.line()),
+                entry: None,
+                message: format!("Invalid playlist format: {}", e),
+            }]
+        }
+    };
+    if playlist.entries.is_empty() {
+        return vec![PlaylistIssue {
+            line: None,
+            entry: None,
+            message: "The playlist has no entries".to_string(),
+        }];
+    }
+
+    // Entries run from their first line to the next entry's; entries
+    // written in flow style can't be told apart and get no lines
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut starts = entry_lines(&lines);
+    if starts.len() != playlist.entries.len() {
+        starts.clear();
+    }
+
+    let mut issues = Vec::new();
+    for (index, entry) in playlist.entries.iter().enumerate() {
+        let source = starts.get(index).map(|&start| EntrySource {
+            lines: &lines[start..starts.get(index + 1).copied().unwrap_or(lines.len())],
+            offset: start,
+        });
+        for (key, message) in check_entry(entry) {
+            issues.push(PlaylistIssue {
+                line: source
+                    .as_ref()
+                    .map(|s| s.find(&key).unwrap_or(s.offset + 1)),
+                entry: Some(index),
+                message,
+            });
+        }
+    }
+
+    let file = EntrySource {
+        lines: &lines,
+        offset: 0,
+    };
+    if let Some(until) = &playlist.until {
+        if let Err(message) = parse_until(until, Local::now()) {
+            issues.push(PlaylistIssue {
+                line: file.find("until"),
+                entry: None,
+                message,
+            });
+        }
+    }
+    if let Some(finale) = &playlist.finale {
+        let start = file.find("finale").map(|line| line - 1);
+        if playlist.until.is_none() {
+            issues.push(PlaylistIssue {
+                line: start.map(|start| start + 1),
+                entry: None,
+                message: "The finale needs an until time to start at".to_string(),
+            });
+        }
+        // The finale runs to the next line that isn't indented
+        let source = start.map(|start| {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| !line.trim().is_empty() && !line.starts_with([' ', '\t', '#']))
+                .map_or(lines.len(), |n| start + 1 + n);
+            EntrySource {
+                lines: &lines[start..end],
+                offset: start,
+            }
+        });
+        for (key, message) in check_entry(finale) {
+            issues.push(PlaylistIssue {
+                line: source
+                    .as_ref()
+                    .map(|s| s.find(&key).unwrap_or(s.offset + 1)),
+                entry: None,
+                message: format!("Finale: {}", message),
+            });
+        }
+    }
+    issues
+}
+
+/// Describes a list of problems on one line each, under a count of them
+pub fn describe_issues(issues: &[PlaylistIssue]) -> String {
+    let mut description = format!(
+        "{} problem{} found",
+        issues.len(),
+        if issues.len() == 1 { "" } else { "s" }
+    );
+    for issue in issues {
+        description.push_str(&format!("\n  {}", issue));
+    }
+    description
+}
+
+/// Checks one entry, returning each problem with the key it is under, such
+/// as `theme` or `params.speed`
+fn check_entry(entry: &PlaylistEntry) -> Vec<(String, String)> {
+    let mut issues = Vec::new();
+
+    let patterns = REGISTRY.list_patterns();
+    let pattern_exists = patterns.contains(&entry.pattern.as_str());
+    if !pattern_exists {
+        issues.push((
+            "pattern".to_string(),
+            unknown("pattern", &entry.pattern, &patterns),
+        ));
+    }
+
+    if themes::get_theme(&entry.theme).is_err() {
+        let names = themes::all_themes().into_iter().map(|t| t.name);
+        issues.push(("theme".to_string(), unknown("theme", &entry.theme, names)));
+    }
+
+    if entry.duration == 0 {
+        issues.push((
+            "duration".to_string(),
+            "Duration must be at least 1 second".to_string(),
+        ));
+    }
+
+    let art = entry.art.unwrap_or(DemoArt::All);
+    issues.extend(check_params("params", &entry.params, |param| {
+        if pattern_exists {
+            REGISTRY.validate_params(&entry.pattern, param)
+        } else {
+            Ok(())
+        }
+    }));
+    issues.extend(check_params("art_params", &entry.art_params, |param| {
+        demo::parse_art_params(art, param).map(|_| ())
+    }));
+
+    for (index, layer) in entry.layers.iter().enumerate() {
+        let layer_exists = patterns.contains(&layer.pattern.as_str());
+        let key = format!("layers.{}", index);
+        if !layer_exists {
+            issues.push((
+                format!("{}.pattern", key),
+                format!(
+                    "Layer {}: {}",
+                    index + 1,
+                    unknown("pattern", &layer.pattern, &patterns)
+                ),
+            ));
+        }
+        let layer_issues = check_params(&format!("{}.params", key), &layer.params, |param| {
+            if layer_exists {
+                REGISTRY.validate_params(&layer.pattern, param)
+            } else {
+                Ok(())
+            }
+        });
+        issues.extend(
+            layer_issues
+                .into_iter()
+                .map(|(key, message)| (key, format!("Layer {}: {}", index + 1, message))),
+        );
+    }
+
+    issues
+}
+
+/// Checks a parameter mapping one parameter at a time, so each problem gets
+/// its own line
+fn check_params(
+    section: &str,
+    value: &Option<serde_yaml::Value>,
+    validate: impl Fn(&str) -> Result<(), String>,
+) -> Vec<(String, String)> {
+    let Some(value) = value else {
+        return Vec::new();
+    };
+    let serde_yaml::Value::Mapping(map) = value else {
+        return vec![(
+            section.to_string(),
+            format!("{} must be a mapping of key-value pairs", section),
+        )];
+    };
+
+    let mut issues = Vec::new();
+    for (key, value) in map {
+        let name = key.as_str().unwrap_or_default();
+        let mut pair = serde_yaml::Mapping::new();
+        pair.insert(key.clone(), value.clone());
+        let result = params_to_string(&serde_yaml::Value::Mapping(pair))
+            .map_err(|e| e.to_string())
+            .and_then(|param| validate(&param));
+        if let Err(message) = result {
+            issues.push((format!("{}.{}", section, name), message));
+        }
+    }
+    issues
+}
+
+/// Describes an unknown name, suggesting the closest known one
+fn unknown<S: AsRef<str>>(kind: &str, name: &str, known: impl IntoIterator<Item = S>) -> String {
+    let closest = known
+        .into_iter()
+        .map(|candidate| {
+            let distance = edit_distance(&name.to_lowercase(), candidate.as_ref());
+            (distance, candidate.as_ref().to_string())
+        })
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min();
+    match closest {
+        Some((_, suggestion)) => {
+            format!("Unknown {} '{}'; did you mean '{}'?", kind, name, suggestion)
+        }
+        None => format!("Unknown {} '{}'", kind, name),
+    }
+}
+
+/// Number of single-character edits turning one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the index of the first line of each entry: the items of the
+/// `entries` sequence, found by their `-` at the sequence's indentation
+fn entry_lines(lines: &[&str]) -> Vec<usize> {
+    let Some(header) = lines.iter().position(|l| l.trim_end() == "entries:") else {
+        return Vec::new();
+    };
+
+    let mut starts = Vec::new();
+    let mut indent = None;
+    for (index, line) in lines.iter().enumerate().skip(header + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let depth = line.len() - trimmed.len();
+        if depth == 0 && !trimmed.starts_with('-') {
+            break;
+        }
+        if trimmed.starts_with('-') && *indent.get_or_insert(depth) == depth {
+            starts.push(index);
+        }
+    }
+    starts
+}
+
+/// The lines of one entry
+struct EntrySource<'a> {
+    lines: &'a [&'a str],
+    /// Index of the entry's first line in the file
+    offset: usize,
+}
+
+impl EntrySource<'_> {
+    /// Returns the line, counting from 1, holding a key path such as
+    /// `theme`, `params.speed`, or `layers.1.pattern`, where numbers pick
+    /// items of a sequence
+    fn find(&self, path: &str) -> Option<usize> {
+        let mut from = 0;
+        for key in path.split('.') {
+            if let Ok(index) = key.parse::<usize>() {
+                from = self.item(from + 1, index)?;
+                continue;
+            }
+            let found = self.lines[from..].iter().position(|line| {
+                let line = line.trim_start().trim_start_matches('-').trim_start();
+                line.strip_prefix(key)
+                    .is_some_and(|rest| rest.trim_start().starts_with(':'))
+            })?;
+            from += found;
+        }
+        Some(self.offset + from + 1)
+    }
+
+    /// Returns the index of the `index`th item of the sequence starting
+    /// at or after line `from`
+    fn item(&self, from: usize, index: usize) -> Option<usize> {
+        let mut indent = None;
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(from)
+            .filter(|(_, line)| {
+                let trimmed = line.trim_start();
+                let depth = line.len() - trimmed.len();
+                trimmed.starts_with('-') && *indent.get_or_insert(depth) == depth
+            })
+            .nth(index)
+            .map(|(line, _)| line)
+    }
+}
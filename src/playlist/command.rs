@@ -0,0 +1,164 @@
+//! Playlist entries as command lines
+//!
+//! `chromacat playlist to-cli` prints the command line that shows each entry
+//! of a playlist, and `chromacat playlist from-cli` turns a command line back
+//! into a playlist. The command lines are quoted and split the way a POSIX
+//! shell does, so they can be pasted into one.
+
+use super::entry::{params_to_string, PlaylistEntry};
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::BlendMode;
+
+/// Quotes `arg` for a POSIX shell when it holds anything but plain
+/// characters
+pub fn quote_arg(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_.,=:/+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Joins `args` into a command line, quoting those that need it
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a command line into its arguments the way a POSIX shell does,
+/// honoring single quotes, double quotes, and backslashes
+pub fn split_args(line: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                args.extend(current.take());
+            }
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err(unclosed_quote(line)),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err(unclosed_quote(line)),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err(unclosed_quote(line)),
+                    }
+                }
+            }
+            '\\' => {
+                let arg = current.get_or_insert_with(String::new);
+                if let Some(c) = chars.next() {
+                    arg.push(c);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+/// Converts parameters in `--param` form into a YAML mapping, reading
+/// numbers and `true` or `false` as such; returns None if there are none
+pub fn params_to_yaml(params: &str) -> Option<serde_yaml::Value> {
+    let mut mapping = serde_yaml::Mapping::new();
+    for pair in params.split(',') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let value = if let Ok(flag) = value.parse::<bool>() {
+            serde_yaml::Value::Bool(flag)
+        } else if let Ok(number) = value.parse::<f64>() {
+            serde_yaml::Value::Number(number.into())
+        } else {
+            serde_yaml::Value::String(value.to_string())
+        };
+        mapping.insert(serde_yaml::Value::String(key.to_string()), value);
+    }
+    (!mapping.is_empty()).then_some(serde_yaml::Value::Mapping(mapping))
+}
+
+fn unclosed_quote(line: &str) -> ChromaCatError {
+    ChromaCatError::InputError(format!("Unclosed quote in command line: {}", line))
+}
+
+impl PlaylistEntry {
+    /// Returns the command line that shows the entry, e.g.
+    /// `chromacat -a -p plasma -t neon --param complexity=3.2`. Layers are
+    /// passed in `-p`, which blends them all the same way with their default
+    /// parameters, so an entry whose layers differ from that has no command
+    /// line.
+    pub fn to_command(&self) -> Result<String> {
+        let mut args: Vec<String> = vec!["chromacat".to_string()];
+        if self.art.is_none() {
+            args.push("-a".to_string());
+        }
+
+        let mut patterns = vec![self.pattern.clone()];
+        for layer in &self.layers {
+            if layer.params.is_some() {
+                return Err(ChromaCatError::InputError(format!(
+                    "Layer '{}' has parameters, which can't be set on the command line",
+                    layer.pattern
+                )));
+            }
+            if layer.blend != self.layers[0].blend {
+                return Err(ChromaCatError::InputError(
+                    "Layers blended in different ways can't be set on the command line".to_string(),
+                ));
+            }
+            patterns.push(layer.pattern.clone());
+        }
+        args.extend([
+            "-p".to_string(),
+            patterns.join(","),
+            "-t".to_string(),
+            self.theme.clone(),
+        ]);
+        if let Some(layer) = self
+            .layers
+            .first()
+            .filter(|l| l.blend != BlendMode::default())
+        {
+            args.push("--blend".to_string());
+            args.push(layer.blend.as_str().to_string());
+        }
+
+        if let Some(params) = self.params_string()?.filter(|p| !p.is_empty()) {
+            args.push("--param".to_string());
+            args.push(params);
+        }
+        if let Some(params) = &self.art_params {
+            args.push("--art-param".to_string());
+            args.push(params_to_string(params)?);
+        }
+        if let Some(art) = self.art {
+            args.push("demo".to_string());
+            args.push(art.as_str().to_string());
+        }
+        Ok(join_args(&args))
+    }
+}
@@ -5,14 +5,20 @@
 //! Each entry represents a single step in the playlist that can be rendered with
 //! specific visual effects and timing.
 
-use crate::demo::DemoArt;
+use super::check::{check_playlist, describe_issues};
+use crate::demo::{self, ArtSettings, DemoArt};
 use crate::error::{ChromaCatError, Result};
-use crate::pattern::{PatternConfig, REGISTRY};
+use crate::pattern::{BlendMode, PatternConfig, PatternLayer, REGISTRY};
 use crate::themes;
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+
+/// Seconds before a playlist's `until` over which scenes get shorter, unless
+/// the playlist sets its own `ramp`
+pub const DEFAULT_RAMP_SECONDS: u64 = 300;
 
 /// A single entry in a playlist, describing a pattern configuration and duration.
 ///
@@ -21,7 +27,8 @@ use std::time::Duration;
 /// - The pattern type and theme to use
 /// - How long to display it
 /// - Pattern-specific parameters (optional)
-/// - Demo art to display (optional)
+/// - Demo art to display, with art-specific parameters (optional)
+/// - Patterns blended on top of the main one (optional)
 ///
 /// # Example
 /// ```yaml
@@ -33,11 +40,18 @@ use std::time::Duration;
 /// params:
 ///   speed: 2.0
 ///   density: 1.5
+/// art_params:
+///   density: 0.5
+/// layers:
+///   - pattern: "ripple"
+///     blend: "screen"
+///     params:
+///       wavelength: 0.5
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistEntry {
     /// Optional name for this sequence
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub name: String,
 
     /// Pattern type to use (must be a valid registered pattern)
@@ -50,12 +64,57 @@ pub struct PlaylistEntry {
     pub duration: u64,
 
     /// Pattern-specific parameters as key-value pairs
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub params: Option<serde_yaml::Value>,
 
     /// Demo art to display (only used in demo mode)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub art: Option<DemoArt>,
+
+    /// Demo art parameters as key-value pairs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub art_params: Option<serde_yaml::Value>,
+
+    /// Patterns blended on top of the main pattern, bottom first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerEntry>,
+}
+
+/// A pattern blended on top of a playlist entry's main pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerEntry {
+    /// Pattern type to use (must be a valid registered pattern)
+    pub pattern: String,
+
+    /// How the layer combines with the patterns below it
+    #[serde(default)]
+    pub blend: BlendMode,
+
+    /// Pattern-specific parameters as key-value pairs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_yaml::Value>,
+}
+
+impl LayerEntry {
+    /// Creates a layer blending `pattern` with its default parameters
+    pub fn new(pattern: impl Into<String>, blend: BlendMode) -> Self {
+        Self {
+            pattern: pattern.into(),
+            blend,
+            params: None,
+        }
+    }
+
+    /// Builds the pattern layer this entry describes
+    pub fn to_layer(&self) -> Result<PatternLayer> {
+        let params = match &self.params {
+            Some(params) => REGISTRY.parse_params(&self.pattern, &params_to_string(params)?)?,
+            None => REGISTRY
+                .create_pattern_params(&self.pattern)
+                .ok_or_else(|| ChromaCatError::InvalidPattern(self.pattern.clone()))?,
+        };
+        Ok(PatternLayer::new(params, self.blend))
+    }
 }
 
 impl PlaylistEntry {
@@ -83,6 +142,8 @@ impl PlaylistEntry {
             duration,
             params: None,
             art: None,
+            art_params: None,
+            layers: Vec::new(),
         }
     }
 
@@ -104,6 +165,18 @@ impl PlaylistEntry {
         self
     }
 
+    /// Adds demo art parameters to the entry.
+    pub fn with_art_params(mut self, params: serde_yaml::Value) -> Self {
+        self.art_params = Some(params);
+        self
+    }
+
+    /// Blends another pattern on top of the entry's existing ones.
+    pub fn with_layer(mut self, layer: LayerEntry) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
     /// Returns a human-readable description of this entry.
     pub fn description(&self) -> String {
         let mut desc = if self.name.is_empty() {
@@ -119,6 +192,11 @@ impl PlaylistEntry {
         desc
     }
 
+    /// Returns the entry's parameters in `--param` form, if it has any
+    pub fn params_string(&self) -> Result<Option<String>> {
+        self.params.as_ref().map(params_to_string).transpose()
+    }
+
     /// Validates that all references and parameters exist and are valid.
     pub fn validate(&self) -> Result<()> {
         // Check pattern exists
@@ -138,6 +216,17 @@ impl PlaylistEntry {
             REGISTRY.validate_params(&self.pattern, &param_str)?;
         }
 
+        // Check layer patterns and their parameters
+        for layer in &self.layers {
+            if !REGISTRY.list_patterns().contains(&layer.pattern.as_str()) {
+                return Err(ChromaCatError::InvalidPattern(format!(
+                    "Layer pattern '{}' does not exist",
+                    layer.pattern
+                )));
+            }
+            layer.to_layer()?;
+        }
+
         // Validate art type if present
         if let Some(art) = &self.art {
             // Ensure the art type is valid by checking against available types
@@ -149,9 +238,24 @@ impl PlaylistEntry {
             }
         }
 
+        // Validate art parameters against the entry's art
+        self.art_settings(&ArtSettings::default())?;
+
         Ok(())
     }
 
+    /// Applies this entry's art parameters on top of `base` settings.
+    pub fn art_settings(&self, base: &ArtSettings) -> Result<ArtSettings> {
+        let Some(params) = &self.art_params else {
+            return Ok(base.clone());
+        };
+
+        let art = self.art.unwrap_or(DemoArt::All);
+        let parsed = demo::parse_art_params(art, &params_to_string(params)?)
+            .map_err(ChromaCatError::InputError)?;
+        Ok(base.clone().with_params(parsed))
+    }
+
     /// Converts this entry into a pattern configuration that can be rendered.
     pub fn to_pattern_config(&self) -> Result<PatternConfig> {
         // Start with default parameters for the pattern
@@ -160,6 +264,11 @@ impl PlaylistEntry {
             params: REGISTRY
                 .create_pattern_params(&self.pattern)
                 .ok_or_else(|| ChromaCatError::InvalidPattern(self.pattern.clone()))?,
+            layers: self
+                .layers
+                .iter()
+                .map(LayerEntry::to_layer)
+                .collect::<Result<_>>()?,
         };
 
         // Set theme name
@@ -181,23 +290,82 @@ impl PlaylistEntry {
 }
 
 /// A complete playlist containing multiple entries to be played in sequence.
+///
+/// A playlist can count down to an event: with `until`, scenes get shorter
+/// over the `ramp` before it, and the `finale` entry starts exactly when it
+/// arrives and plays on.
+///
+/// ```yaml
+/// until: "2026-12-31 23:59:30"
+/// ramp: 600
+/// finale:
+///   pattern: "plasma"
+///   theme: "rave"
+///   duration: 60
+/// entries:
+///   - pattern: "wave"
+///     theme: "ocean"
+///     duration: 60
+/// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Playlist {
     /// List of entries to play in sequence
     pub entries: Vec<PlaylistEntry>,
+
+    /// When the show counts down to: `YYYY-MM-DD HH:MM[:SS]` in local
+    /// time, an RFC 3339 timestamp, or `HH:MM[:SS]` for the next time the
+    /// clock reads it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+
+    /// Seconds before `until` over which scenes get shorter; five minutes
+    /// when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ramp: Option<u64>,
+
+    /// Entry that starts exactly at `until` and plays on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finale: Option<PlaylistEntry>,
+
+    /// Shell command run whenever a new scene starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_scene_change: Option<String>,
+
+    /// Shell command run when the last scene ends and the playlist starts
+    /// over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_playlist_end: Option<String>,
 }
 
 impl Playlist {
     /// Creates a new empty playlist
     pub fn new() -> Self {
-        Self {
-            entries: Vec::new(),
-        }
+        Self::default()
     }
 
     /// Creates a playlist with the given entries
     pub fn with_entries(entries: Vec<PlaylistEntry>) -> Self {
-        Self { entries }
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// Returns when the show counts down to, if it does
+    pub fn deadline(&self) -> Result<Option<SystemTime>> {
+        self.until
+            .as_deref()
+            .map(|until| {
+                parse_until(until, Local::now())
+                    .map(SystemTime::from)
+                    .map_err(ChromaCatError::InputError)
+            })
+            .transpose()
+    }
+
+    /// Returns the window before `until` over which scenes get shorter
+    pub fn ramp(&self) -> Duration {
+        Duration::from_secs(self.ramp.unwrap_or(DEFAULT_RAMP_SECONDS))
     }
 
     /// Loads a playlist from a file.
@@ -216,18 +384,83 @@ impl FromStr for Playlist {
     fn from_str(contents: &str) -> std::result::Result<Self, Self::Err> {
         let playlist: Playlist = serde_yaml::from_str(contents)
             .map_err(|e| ChromaCatError::InputError(format!("Invalid playlist format: {}", e)))?;
+        if playlist.entries.is_empty() {
+            return Ok(playlist);
+        }
 
-        // Validate all entries
-        for entry in &playlist.entries {
-            entry.validate()?;
+        // Every entry is checked before anything plays, so all the problems
+        // are reported together instead of each failing when its scene
+        // comes up
+        let issues = check_playlist(contents);
+        if !issues.is_empty() {
+            return Err(ChromaCatError::InputError(format!(
+                "Invalid playlist, {}",
+                describe_issues(&issues)
+            )));
+        }
+
+        // Validate anything the check leaves to the entries themselves
+        for (index, entry) in playlist.entries.iter().enumerate() {
+            entry.validate().map_err(|e| {
+                ChromaCatError::InputError(format!("Invalid playlist entry {}: {}", index + 1, e))
+            })?;
+        }
+        if let Some(finale) = &playlist.finale {
+            finale.validate().map_err(|e| {
+                ChromaCatError::InputError(format!("Invalid playlist finale: {}", e))
+            })?;
         }
 
         Ok(playlist)
     }
 }
 
+/// Parses a playlist's `until`: a local date and time, an RFC 3339
+/// timestamp, or a time of day, which means the next time after `now` the
+/// clock reads it
+pub fn parse_until(
+    until: &str,
+    now: DateTime<Local>,
+) -> std::result::Result<DateTime<Local>, String> {
+    let until = until.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(until) {
+        return Ok(time.with_timezone(&Local));
+    }
+
+    let local = |naive: NaiveDateTime| {
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| format!("'{}' does not exist in the local time zone", until))
+    };
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(until, format) {
+            return local(naive);
+        }
+    }
+    for format in ["%H:%M:%S", "%H:%M"] {
+        if let Ok(time) = NaiveTime::parse_from_str(until, format) {
+            let today = local(now.date_naive().and_time(time))?;
+            return if today > now {
+                Ok(today)
+            } else {
+                local((now.date_naive() + TimeDelta::days(1)).and_time(time))
+            };
+        }
+    }
+    Err(format!(
+        "Invalid until '{}'. Use e.g. 2026-12-31 23:59, 23:59, or an RFC 3339 timestamp",
+        until
+    ))
+}
+
 /// Converts YAML parameters to the string format expected by the registry.
-fn params_to_string(params: &serde_yaml::Value) -> Result<String> {
+pub(super) fn params_to_string(params: &serde_yaml::Value) -> Result<String> {
     let mut param_strings = Vec::new();
 
     match params {
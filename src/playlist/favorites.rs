@@ -0,0 +1,113 @@
+//! Favorite pattern and theme combinations
+//!
+//! Favorites are bookmarked from a running animation and kept in
+//! `~/.config/chromacat/favorites.yaml`. The file is an ordinary playlist, so
+//! the same list can be picked from by number while animating or played
+//! through with `--favorites`.
+
+use super::entry::{Playlist, PlaylistEntry};
+use super::get_config_dir;
+use crate::error::{ChromaCatError, Result};
+use std::path::{Path, PathBuf};
+
+/// Favorites filename in the config directory
+pub const FAVORITES_FILE: &str = "favorites.yaml";
+
+/// Seconds each favorite lasts when the favorites are played as a playlist
+pub const FAVORITE_SECONDS: u64 = 30;
+
+/// Returns the path to the user's favorites file
+pub fn get_favorites_path() -> PathBuf {
+    get_config_dir().join(FAVORITES_FILE)
+}
+
+/// Bookmarked combinations, in the order they were saved
+#[derive(Debug, Clone)]
+pub struct Favorites {
+    /// File the favorites are saved to
+    path: PathBuf,
+    /// Saved combinations
+    playlist: Playlist,
+}
+
+impl Favorites {
+    /// Loads the favorites kept at `path`; a missing file holds none
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let playlist = if path.exists() {
+            Playlist::from_file(&path)?
+        } else {
+            Playlist::new()
+        };
+        Ok(Self { path, playlist })
+    }
+
+    /// Loads the user's favorites from the config directory
+    pub fn load_default() -> Result<Self> {
+        Self::load(get_favorites_path())
+    }
+
+    /// Returns the file the favorites are saved to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the saved combinations
+    pub fn entries(&self) -> &[PlaylistEntry] {
+        &self.playlist.entries
+    }
+
+    /// Returns the favorite at `index`, counting from zero
+    pub fn get(&self, index: usize) -> Option<&PlaylistEntry> {
+        self.playlist.entries.get(index)
+    }
+
+    /// Returns the number of favorites
+    pub fn len(&self) -> usize {
+        self.playlist.entries.len()
+    }
+
+    /// Returns true if nothing has been bookmarked
+    pub fn is_empty(&self) -> bool {
+        self.playlist.entries.is_empty()
+    }
+
+    /// Bookmarks a combination and returns its index, or `None` if the same
+    /// pattern, theme, parameters, and art are already saved
+    pub fn add(&mut self, mut entry: PlaylistEntry) -> Option<usize> {
+        let exists = self.playlist.entries.iter().any(|saved| {
+            saved.pattern == entry.pattern
+                && saved.theme == entry.theme
+                && saved.params == entry.params
+                && saved.art == entry.art
+        });
+        if exists {
+            return None;
+        }
+        entry.duration = FAVORITE_SECONDS;
+        self.playlist.entries.push(entry);
+        Some(self.playlist.entries.len() - 1)
+    }
+
+    /// Writes the favorites to their file, creating the directory if needed
+    pub fn save(&self) -> Result<()> {
+        let yaml = serde_yaml::to_string(&self.playlist).map_err(|e| {
+            ChromaCatError::Other(format!("Failed to serialize favorites: {}", e))
+        })?;
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, yaml).map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to write favorites to {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Returns the favorites as a playlist that plays each in turn
+    pub fn to_playlist(&self) -> Playlist {
+        self.playlist.clone()
+    }
+}
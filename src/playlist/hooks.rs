@@ -0,0 +1,236 @@
+//! Commands run as a playlist plays
+//!
+//! A playlist can run a shell command whenever its scene changes and when
+//! it reaches its end and starts over, so a show can play sounds or drive
+//! lights in step with the picture:
+//!
+//! ```yaml
+//! on_scene_change: "paplay ~/sounds/ding.wav"
+//! on_playlist_end: "~/bin/lights cycle"
+//! entries:
+//!   - pattern: "plasma"
+//!     theme: "neon"
+//!     duration: 30
+//! ```
+//!
+//! Commands run in the background with their output discarded, so they
+//! never hold up or draw over the animation. The scene that starts is
+//! described in the `CHROMACAT_SCENE`, `CHROMACAT_PATTERN`,
+//! `CHROMACAT_THEME`, and `CHROMACAT_INDEX` environment variables.
+//!
+//! Hooks are rate limited: a hook runs at most once per
+//! [`MIN_HOOK_INTERVAL`] and with at most [`MAX_RUNNING_HOOKS`] copies still
+//! running, so skipping quickly through scenes doesn't start a pile of
+//! commands. A hook that fails [`MAX_HOOK_FAILURES`] times in a row is
+//! turned off for the rest of the show.
+
+use super::entry::{Playlist, PlaylistEntry};
+use log::{debug, info, warn};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Shortest time between two runs of the same hook
+pub const MIN_HOOK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most copies of one hook left running at once
+pub const MAX_RUNNING_HOOKS: usize = 4;
+
+/// Failures in a row after which a hook is turned off
+pub const MAX_HOOK_FAILURES: u32 = 3;
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A new scene started
+    SceneChange,
+    /// The last scene ended and the playlist starts over
+    PlaylistEnd,
+}
+
+impl HookEvent {
+    /// Returns the playlist key the hook is set with
+    pub fn key(&self) -> &'static str {
+        match self {
+            HookEvent::SceneChange => "on_scene_change",
+            HookEvent::PlaylistEnd => "on_playlist_end",
+        }
+    }
+}
+
+/// What became of a request to run a hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutcome {
+    /// The command was started
+    Started,
+    /// No hook is set for the event, or it has been turned off
+    Unset,
+    /// The hook ran too recently or too many copies are still running
+    Skipped,
+    /// The command could not be started
+    Failed(String),
+    /// The hook failed too often and has been turned off
+    Disabled(String),
+}
+
+/// One hook's command and how its runs went
+#[derive(Debug)]
+struct Hook {
+    /// Shell command to run
+    command: String,
+    /// When the command was last started
+    last_run: Option<Instant>,
+    /// Failures since the last success
+    failures: u32,
+    /// Runs that haven't finished yet
+    running: Vec<Child>,
+    /// Whether the hook was turned off after failing
+    disabled: bool,
+}
+
+impl Hook {
+    fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            last_run: None,
+            failures: 0,
+            running: Vec::new(),
+            disabled: false,
+        }
+    }
+
+    /// Collects the runs that finished, counting the failures
+    fn reap(&mut self) {
+        let command = &self.command;
+        let failures = &mut self.failures;
+        self.running.retain_mut(|child| match child.try_wait() {
+            Ok(Some(status)) if status.success() => {
+                *failures = 0;
+                false
+            }
+            Ok(Some(status)) => {
+                warn!("Hook '{}' failed ({})", command, status);
+                *failures += 1;
+                false
+            }
+            Ok(None) => true,
+            Err(e) => {
+                warn!("Hook '{}' could not be checked: {}", command, e);
+                false
+            }
+        });
+    }
+}
+
+/// Runs a playlist's hooks, rate limiting them and turning off those that
+/// keep failing
+#[derive(Debug, Default)]
+pub struct HookRunner {
+    /// Hook run when a scene starts
+    scene_change: Option<Hook>,
+    /// Hook run when the playlist starts over
+    playlist_end: Option<Hook>,
+}
+
+impl HookRunner {
+    /// Creates a runner for the hooks a playlist sets
+    pub fn new(playlist: &Playlist) -> Self {
+        let hook = |command: &Option<String>| {
+            command
+                .as_deref()
+                .map(str::trim)
+                .filter(|command| !command.is_empty())
+                .map(Hook::new)
+        };
+        Self {
+            scene_change: hook(&playlist.on_scene_change),
+            playlist_end: hook(&playlist.on_playlist_end),
+        }
+    }
+
+    /// Returns whether no hooks are set
+    pub fn is_empty(&self) -> bool {
+        self.scene_change.is_none() && self.playlist_end.is_none()
+    }
+
+    /// Runs the hook for `event` with `entry`, the `index`th scene, as the
+    /// scene that starts
+    pub fn fire(&mut self, event: HookEvent, entry: &PlaylistEntry, index: usize) -> HookOutcome {
+        self.fire_at(event, entry, index, Instant::now())
+    }
+
+    /// Runs the hook for `event` as [`fire`](Self::fire) does, with `now` as
+    /// the time the rate limit is measured against
+    pub fn fire_at(
+        &mut self,
+        event: HookEvent,
+        entry: &PlaylistEntry,
+        index: usize,
+        now: Instant,
+    ) -> HookOutcome {
+        let hook = match event {
+            HookEvent::SceneChange => self.scene_change.as_mut(),
+            HookEvent::PlaylistEnd => self.playlist_end.as_mut(),
+        };
+        let Some(hook) = hook.filter(|hook| !hook.disabled) else {
+            return HookOutcome::Unset;
+        };
+
+        hook.reap();
+        if hook.failures >= MAX_HOOK_FAILURES {
+            hook.disabled = true;
+            let message = format!(
+                "{} turned off after {} failures",
+                event.key(),
+                hook.failures
+            );
+            warn!("Hook {}", message);
+            return HookOutcome::Disabled(message);
+        }
+        let recent = hook
+            .last_run
+            .is_some_and(|last| now.saturating_duration_since(last) < MIN_HOOK_INTERVAL);
+        if recent || hook.running.len() >= MAX_RUNNING_HOOKS {
+            debug!("Hook {} skipped", event.key());
+            return HookOutcome::Skipped;
+        }
+
+        hook.last_run = Some(now);
+        match shell(&hook.command)
+            .env("CHROMACAT_SCENE", &entry.name)
+            .env("CHROMACAT_PATTERN", &entry.pattern)
+            .env("CHROMACAT_THEME", &entry.theme)
+            .env("CHROMACAT_INDEX", (index + 1).to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => {
+                info!("Running {} hook: {}", event.key(), hook.command);
+                hook.running.push(child);
+                HookOutcome::Started
+            }
+            Err(e) => {
+                warn!("Hook '{}' could not start: {}", hook.command, e);
+                hook.failures += 1;
+                HookOutcome::Failed(format!("{} could not start: {}", event.key(), e))
+            }
+        }
+    }
+}
+
+/// Builds a command running `command` through the platform's shell
+fn shell(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
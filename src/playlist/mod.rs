@@ -16,15 +16,35 @@
 //!       density: 1.5
 //! ```
 
+use crate::demo::DemoArt;
 use crate::error::Result;
+use crate::themes;
 use std::path::PathBuf;
 
+mod adaptive;
+mod check;
+mod command;
 mod entry;
+mod favorites;
+mod hooks;
 mod player;
+mod pool;
+mod recorder;
 
 // Re-export the types from the submodules
-pub use self::entry::{Playlist, PlaylistEntry};
-pub use self::player::PlaylistPlayer;
+pub use self::adaptive::{AdaptiveSource, DayPart, Sky, Weather, ADAPTIVE_SCENE_SECONDS};
+pub use self::check::{check_playlist, describe_issues, PlaylistIssue};
+pub use self::command::{join_args, params_to_yaml, quote_arg, split_args};
+pub use self::entry::{parse_until, LayerEntry, Playlist, PlaylistEntry, DEFAULT_RAMP_SECONDS};
+pub use self::favorites::{get_favorites_path, Favorites, FAVORITES_FILE, FAVORITE_SECONDS};
+pub use self::hooks::{
+    HookEvent, HookOutcome, HookRunner, MAX_HOOK_FAILURES, MAX_RUNNING_HOOKS, MIN_HOOK_INTERVAL,
+};
+pub use self::player::{PlaylistPlayer, MIN_PACE};
+pub use self::pool::{
+    get_pool_path, PoolChoices, ScenePool, DEFAULT_SCENE_SECONDS, POOL_FILE, RANDOM_SCENES,
+};
+pub use self::recorder::{SessionRecorder, SessionScene};
 
 /// Default directory for ChromaCat configuration
 pub const CONFIG_DIR: &str = ".config/chromacat";
@@ -32,6 +52,9 @@ pub const CONFIG_DIR: &str = ".config/chromacat";
 /// Default playlist filename
 pub const DEFAULT_PLAYLIST: &str = "playlist.yaml";
 
+/// Patterns a theme preview cycles through
+pub const PREVIEW_PATTERNS: &[&str] = &["plasma", "wave", "ripple", "spiral", "aurora"];
+
 /// Returns the path to the user's ChromaCat config directory
 pub fn get_config_dir() -> PathBuf {
     dirs::home_dir()
@@ -44,6 +67,37 @@ pub fn get_default_playlist_path() -> PathBuf {
     get_config_dir().join(DEFAULT_PLAYLIST)
 }
 
+/// Builds a playlist previewing themes on the logo art, `seconds` per entry.
+/// A single theme is shown with each of the preview patterns; without one,
+/// every theme is shown in turn while the patterns take turns.
+pub fn theme_preview(theme: Option<&str>, seconds: u64) -> Result<Playlist> {
+    let entry = |pattern: &str, theme: &str| {
+        PlaylistEntry::new(pattern, theme, seconds)
+            .with_name(theme)
+            .with_art(DemoArt::Logo)
+    };
+
+    let entries = match theme {
+        Some(name) => {
+            themes::get_theme(name)?;
+            PREVIEW_PATTERNS
+                .iter()
+                .map(|pattern| entry(pattern, name))
+                .collect()
+        }
+        None => {
+            let mut names: Vec<String> = themes::all_themes().into_iter().map(|t| t.name).collect();
+            names.sort();
+            names
+                .iter()
+                .zip(PREVIEW_PATTERNS.iter().cycle())
+                .map(|(name, pattern)| entry(pattern, name))
+                .collect()
+        }
+    };
+    Ok(Playlist::with_entries(entries))
+}
+
 /// Loads the default playlist if it exists
 pub fn load_default_playlist() -> Result<Option<Playlist>> {
     let path = get_default_playlist_path();
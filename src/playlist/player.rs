@@ -5,11 +5,24 @@
 //! - Manual navigation (next/previous)
 //! - Pause/resume control
 //! - Progress tracking
+//! - Reloading the playlist file when it is edited during playback
+//! - Counting down to a playlist's `until` time: scenes get shorter as it
+//!   approaches, and the finale starts the moment it arrives
+//! - Running the playlist's hooks when scenes change and when it starts over
 
+use super::check::check_playlist;
 use super::entry::{Playlist, PlaylistEntry};
+use super::hooks::{HookEvent, HookOutcome, HookRunner};
 use crate::error::{ChromaCatError, Result};
 use crate::pattern::PatternConfig;
-use std::time::Duration;
+use crate::renderer::Severity;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Shortest scenes get before the countdown ends, as a fraction of their
+/// duration
+pub const MIN_PACE: f64 = 0.25;
 
 /// Controls playback of a playlist, managing transitions between entries.
 ///
@@ -27,6 +40,22 @@ pub struct PlaylistPlayer {
     time_in_current: Duration,
     /// Whether playback is currently paused
     paused: bool,
+    /// File the playlist was loaded from, checked for edits at scene changes
+    source: Option<PathBuf>,
+    /// Modification time of the source when it was last read
+    modified: Option<SystemTime>,
+    /// Outcome of the latest reload or hook, waiting to be shown
+    notice: Option<(String, Severity)>,
+    /// When the countdown ends, if the playlist has one
+    deadline: Option<SystemTime>,
+    /// Fraction of their duration scenes play for as the countdown nears
+    pace: f64,
+    /// Whether the countdown has ended
+    landed: bool,
+    /// Whether the finale is playing
+    on_finale: bool,
+    /// Runs the playlist's hooks
+    hooks: HookRunner,
 }
 
 impl PlaylistPlayer {
@@ -36,11 +65,95 @@ impl PlaylistPlayer {
     /// * `playlist` - The playlist to play
     pub fn new(playlist: Playlist) -> Self {
         Self {
+            deadline: read_deadline(&playlist),
+            hooks: HookRunner::new(&playlist),
             playlist,
             current_index: 0,
             time_in_current: Duration::ZERO,
             paused: false,
+            source: None,
+            modified: None,
+            notice: None,
+            pace: 1.0,
+            landed: false,
+            on_finale: false,
+        }
+    }
+
+    /// Watches the file the playlist was loaded from. Edits to it are picked
+    /// up at the next scene change, so a show can be tuned while it plays.
+    pub fn watch(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        self.modified = modified_time(&path);
+        self.source = Some(path);
+    }
+
+    /// Reloads the watched file if it changed since it was last read.
+    ///
+    /// A file with problems is reported through [`take_notice`] and leaves
+    /// the current playlist playing. The current index is kept when the new
+    /// playlist is long enough, and otherwise starts over.
+    ///
+    /// # Returns
+    /// * `true` if a new playlist was loaded
+    /// * `false` if nothing is watched, nothing changed, or the file is invalid
+    ///
+    /// [`take_notice`]: PlaylistPlayer::take_notice
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(path) = &self.source else {
+            return false;
+        };
+        let modified = modified_time(path);
+        if modified.is_none() || modified == self.modified {
+            return false;
         }
+        // Remembered even when the file is invalid, so its problems are
+        // reported once rather than at every scene change
+        self.modified = modified;
+
+        match read_playlist(path) {
+            Ok(playlist) => {
+                let count = playlist.entries.len();
+                info!(
+                    "Reloaded playlist from {} with {} entries",
+                    path.display(),
+                    count
+                );
+                self.notice = Some((
+                    format!("Reloaded playlist: {} scenes", count),
+                    Severity::Info,
+                ));
+                if self.current_index >= count {
+                    self.current_index = 0;
+                    self.time_in_current = Duration::ZERO;
+                }
+                // A new until time in the future is counted down to again
+                self.deadline = read_deadline(&playlist);
+                if self
+                    .deadline
+                    .is_some_and(|deadline| deadline > SystemTime::now())
+                {
+                    self.landed = false;
+                }
+                self.hooks = HookRunner::new(&playlist);
+                self.playlist = playlist;
+                true
+            }
+            Err(message) => {
+                warn!("Playlist {} not reloaded: {}", path.display(), message);
+                self.notice = Some((
+                    format!("Playlist not reloaded: {}", message),
+                    Severity::Warn,
+                ));
+                false
+            }
+        }
+    }
+
+    /// Takes the message describing the latest reload or failed hook, and
+    /// how serious it is, if one is waiting
+    pub fn take_notice(&mut self) -> Option<(String, Severity)> {
+        self.notice.take()
     }
 
     /// Gets the current pattern configuration for rendering.
@@ -60,6 +173,9 @@ impl PlaylistPlayer {
     /// * `Some(&PlaylistEntry)` - Reference to current entry
     /// * `None` - If playlist is empty
     pub fn current_entry(&self) -> Option<&PlaylistEntry> {
+        if self.on_finale {
+            return self.playlist.finale.as_ref();
+        }
         self.playlist.entries.get(self.current_index)
     }
 
@@ -75,21 +191,60 @@ impl PlaylistPlayer {
     /// * `true` if the current entry changed
     /// * `false` if staying on same entry or playlist is empty/paused
     pub fn update(&mut self, delta: Duration) -> bool {
+        self.update_at(delta, SystemTime::now())
+    }
+
+    /// Updates player state as [`update`](Self::update) does, with `now` as
+    /// the wall-clock time the countdown is measured against.
+    ///
+    /// Over the playlist's ramp before its `until` time, scenes play for a
+    /// shrinking fraction of their duration, down to [`MIN_PACE`]. Once the
+    /// time arrives the finale starts, whatever scene is playing, and plays
+    /// until the show is moved on by hand.
+    pub fn update_at(&mut self, delta: Duration, now: SystemTime) -> bool {
         if self.paused {
             return false;
         }
 
+        if let Some(deadline) = self.deadline.filter(|_| !self.landed) {
+            match deadline.duration_since(now) {
+                Ok(remaining) if !remaining.is_zero() => {
+                    let ramp = self.playlist.ramp().as_secs_f64();
+                    self.pace = (remaining.as_secs_f64() / ramp).clamp(MIN_PACE, 1.0);
+                }
+                _ => {
+                    self.landed = true;
+                    self.pace = 1.0;
+                    if self.playlist.finale.is_some() {
+                        info!("Countdown over, playing the finale");
+                        self.on_finale = true;
+                        self.time_in_current = Duration::ZERO;
+                        self.run_hook(HookEvent::SceneChange);
+                        return true;
+                    }
+                }
+            }
+        }
+
         let current_duration = match self.current_entry() {
-            Some(current) => current.get_duration(),
+            Some(current) => current.get_duration().mul_f64(self.pace),
             None => return false,
         };
 
         self.time_in_current += delta;
 
+        if self.on_finale {
+            return false;
+        }
         if self.time_in_current >= current_duration {
             // Move to next entry
             self.current_index = (self.current_index + 1) % self.playlist.entries.len();
             self.time_in_current = Duration::ZERO;
+            self.reload_if_changed();
+            if self.current_index == 0 {
+                self.run_hook(HookEvent::PlaylistEnd);
+            }
+            self.run_hook(HookEvent::SceneChange);
             true
         } else {
             false
@@ -106,7 +261,24 @@ impl PlaylistPlayer {
             return 0.0;
         };
 
-        self.time_in_current.as_secs_f64() / current.duration as f64
+        let duration = current.duration as f64 * self.pace;
+        (self.time_in_current.as_secs_f64() / duration).min(1.0)
+    }
+
+    /// Returns the fraction of their duration scenes play for: 1.0, or less
+    /// as the countdown nears its end
+    pub fn pace(&self) -> f64 {
+        self.pace
+    }
+
+    /// Returns when the countdown ends, while it is still running
+    pub fn deadline(&self) -> Option<SystemTime> {
+        self.deadline.filter(|_| !self.landed)
+    }
+
+    /// Returns whether the finale is playing
+    pub fn on_finale(&self) -> bool {
+        self.on_finale
     }
 
     /// Toggles between paused and playing states.
@@ -130,8 +302,11 @@ impl PlaylistPlayer {
     /// Does nothing if playlist is empty.
     pub fn next_entry(&mut self) {
         if !self.playlist.entries.is_empty() {
+            self.on_finale = false;
             self.current_index = (self.current_index + 1) % self.playlist.entries.len();
             self.time_in_current = Duration::ZERO;
+            self.reload_if_changed();
+            self.run_hook(HookEvent::SceneChange);
         }
     }
 
@@ -141,15 +316,56 @@ impl PlaylistPlayer {
     /// Does nothing if playlist is empty.
     pub fn previous_entry(&mut self) {
         if !self.playlist.entries.is_empty() {
+            self.on_finale = false;
             self.current_index = if self.current_index == 0 {
                 self.playlist.entries.len() - 1
             } else {
                 self.current_index - 1
             };
             self.time_in_current = Duration::ZERO;
+            self.reload_if_changed();
+            self.run_hook(HookEvent::SceneChange);
         }
     }
 
+    /// Jumps to an entry and a position within it, e.g. to follow another
+    /// player.
+    ///
+    /// The index wraps around the playlist length. Does nothing if playlist
+    /// is empty.
+    pub fn seek(&mut self, index: usize, elapsed: Duration) {
+        if !self.playlist.entries.is_empty() {
+            self.on_finale = false;
+            self.current_index = index % self.playlist.entries.len();
+            self.time_in_current = elapsed;
+        }
+    }
+
+    /// Runs the hook for `event` with the current entry, reporting a hook
+    /// that failed through [`take_notice`](Self::take_notice)
+    fn run_hook(&mut self, event: HookEvent) {
+        // The entry is borrowed field by field so the hooks can run with it
+        let entry = if self.on_finale {
+            self.playlist.finale.as_ref()
+        } else {
+            self.playlist.entries.get(self.current_index)
+        };
+        let Some(entry) = entry else {
+            return;
+        };
+        match self.hooks.fire(event, entry, self.current_index) {
+            HookOutcome::Failed(message) | HookOutcome::Disabled(message) => {
+                self.notice = Some((format!("Hook {}", message), Severity::Warn));
+            }
+            HookOutcome::Started | HookOutcome::Unset | HookOutcome::Skipped => {}
+        }
+    }
+
+    /// Gets time spent playing the current entry.
+    pub fn elapsed(&self) -> Duration {
+        self.time_in_current
+    }
+
     /// Gets the total number of entries in the playlist.
     pub fn entry_count(&self) -> usize {
         self.playlist.entries.len()
@@ -162,3 +378,31 @@ impl PlaylistPlayer {
         self.current_index
     }
 }
+
+/// Returns when a file was last modified, or `None` if it can't be read
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Returns when a playlist counts down to, reporting an `until` that can't
+/// be read
+fn read_deadline(playlist: &Playlist) -> Option<SystemTime> {
+    playlist.deadline().unwrap_or_else(|e| {
+        warn!("Playlist countdown ignored: {}", e);
+        None
+    })
+}
+
+/// Reads and checks a playlist file, describing its first problem if it has
+/// any
+fn read_playlist(path: &Path) -> std::result::Result<Playlist, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let issues = check_playlist(&contents);
+    if let Some(first) = issues.first() {
+        return Err(match issues.len() {
+            1 => first.to_string(),
+            n => format!("{} (and {} more)", first, n - 1),
+        });
+    }
+    contents.parse().map_err(|e: ChromaCatError| e.to_string())
+}
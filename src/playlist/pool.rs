@@ -0,0 +1,281 @@
+//! Weighted pools for random scenes
+//!
+//! Random mode draws each scene's pattern and theme from a pool. By default
+//! every pattern and theme is equally likely; a pool file narrows the
+//! choices with include and exclude lists and makes favorites more likely
+//! with weights. Themes can also be chosen by their tags:
+//!
+//! ```yaml
+//! scene_seconds: 20
+//! patterns:
+//!   exclude: [checkerboard]
+//!   weights:
+//!     plasma: 3
+//!     aurora: 2
+//! themes:
+//!   tags: [warm, vivid]
+//!   exclude: [heat]
+//! ```
+
+use super::entry::{Playlist, PlaylistEntry};
+use super::get_config_dir;
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::REGISTRY;
+use crate::themes;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Pool filename in the config directory
+pub const POOL_FILE: &str = "pool.yaml";
+
+/// Seconds each random scene lasts unless the pool says otherwise
+pub const DEFAULT_SCENE_SECONDS: u64 = 20;
+
+/// Number of scenes drawn for random mode before the sequence repeats
+pub const RANDOM_SCENES: usize = 100;
+
+/// Returns the path to the user's pool file
+pub fn get_pool_path() -> PathBuf {
+    get_config_dir().join(POOL_FILE)
+}
+
+/// Which names of one kind can be drawn, and how likely each is
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolChoices {
+    /// Names that can be drawn; empty means all of them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Tags of which a name must have one to be drawn; empty means any.
+    /// Only themes have tags.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Names that are never drawn
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Relative weights; names without one weigh 1
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub weights: BTreeMap<String, f64>,
+}
+
+impl PoolChoices {
+    /// Returns the names that can be drawn out of `all`, with their weights
+    pub fn weighted(&self, all: &[String]) -> Vec<(String, f64)> {
+        all.iter()
+            .filter(|name| self.include.is_empty() || self.include.contains(name))
+            .filter(|name| !self.exclude.contains(name))
+            .map(|name| (name.clone(), self.weights.get(name).copied().unwrap_or(1.0)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect()
+    }
+
+    /// Checks every name against `all` and every weight, naming the `kind`
+    /// of name in errors
+    fn validate(&self, kind: &str, all: &[String]) -> Result<()> {
+        let names = self
+            .include
+            .iter()
+            .chain(&self.exclude)
+            .chain(self.weights.keys());
+        for name in names {
+            if !all.contains(name) {
+                return Err(ChromaCatError::InputError(format!(
+                    "Pool {} '{}' does not exist",
+                    kind, name
+                )));
+            }
+        }
+        for (name, weight) in &self.weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(ChromaCatError::InputError(format!(
+                    "Pool weight for {} '{}' must be a non-negative number",
+                    kind, name
+                )));
+            }
+        }
+        if self.weighted(all).is_empty() {
+            return Err(ChromaCatError::InputError(format!(
+                "Pool leaves no {}s to choose from",
+                kind
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Patterns and themes random scenes are drawn from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenePool {
+    /// Seconds each scene lasts; [`DEFAULT_SCENE_SECONDS`] when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_seconds: Option<u64>,
+    /// Pattern choices
+    pub patterns: PoolChoices,
+    /// Theme choices
+    pub themes: PoolChoices,
+}
+
+impl ScenePool {
+    /// Loads a pool from a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ChromaCatError::InputError(format!(
+                "Failed to read pool file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        contents.parse()
+    }
+
+    /// Loads the user's pool from the config directory, or the default pool
+    /// of every pattern and theme if there is none
+    pub fn load_default() -> Result<Self> {
+        let path = get_pool_path();
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Returns the patterns that can be drawn, with their weights
+    pub fn pattern_weights(&self) -> Vec<(String, f64)> {
+        self.patterns.weighted(&all_patterns())
+    }
+
+    /// Returns the themes that can be drawn, with their weights
+    pub fn theme_weights(&self) -> Vec<(String, f64)> {
+        self.themes.weighted(&tagged_theme_names(&self.themes.tags))
+    }
+
+    /// Narrows the themes to those tagged `tag`, in place of any tags the
+    /// pool file gives, failing if that leaves none
+    pub fn set_theme_tag(&mut self, tag: &str) -> Result<()> {
+        self.themes.tags = vec![tag.to_string()];
+        self.check_themes_left()
+    }
+
+    /// Fails if no theme can be drawn
+    fn check_themes_left(&self) -> Result<()> {
+        if self.theme_weights().is_empty() {
+            return Err(ChromaCatError::InputError(format!(
+                "Pool leaves no themes tagged {} to choose from",
+                self.themes.tags.join(" or ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Draws a pattern and theme
+    pub fn pick<R: Rng>(&self, rng: &mut R) -> Result<(String, String)> {
+        Ok((
+            draw(&self.pattern_weights(), rng, "pattern")?,
+            draw(&self.theme_weights(), rng, "theme")?,
+        ))
+    }
+
+    /// Draws a pattern and theme, with each of the pattern's parameters
+    /// drawn within its range, so every draw explores a new combination
+    pub fn surprise<R: Rng>(&self, rng: &mut R) -> Result<PlaylistEntry> {
+        let seconds = self.scene_seconds.unwrap_or(DEFAULT_SCENE_SECONDS).max(1);
+        let (pattern, theme) = self.pick(rng)?;
+        let values = REGISTRY
+            .random_params(&pattern, rng)
+            .map_err(ChromaCatError::InvalidPattern)?;
+
+        let mut entry = PlaylistEntry::new(pattern, theme, seconds);
+        if !values.is_empty() {
+            let mut params = serde_yaml::Mapping::new();
+            for (name, value) in values {
+                // Numbers and booleans stay typed when the entry is saved
+                let value =
+                    serde_yaml::from_str(&value).unwrap_or(serde_yaml::Value::String(value));
+                params.insert(serde_yaml::Value::String(name), value);
+            }
+            entry.params = Some(serde_yaml::Value::Mapping(params));
+        }
+        Ok(entry)
+    }
+
+    /// Draws `count` scenes into a playlist, never showing the same
+    /// combination twice in a row when there is another to show
+    pub fn to_playlist<R: Rng>(&self, rng: &mut R, count: usize) -> Result<Playlist> {
+        let seconds = self.scene_seconds.unwrap_or(DEFAULT_SCENE_SECONDS).max(1);
+        let single = self.pattern_weights().len() * self.theme_weights().len() <= 1;
+        let mut entries: Vec<PlaylistEntry> = Vec::with_capacity(count);
+        while entries.len() < count {
+            let (pattern, theme) = self.pick(rng)?;
+            let repeat = entries
+                .last()
+                .is_some_and(|last| last.pattern == pattern && last.theme == theme);
+            if repeat && !single {
+                continue;
+            }
+            entries.push(PlaylistEntry::new(pattern, theme, seconds));
+        }
+        Ok(Playlist::with_entries(entries))
+    }
+}
+
+impl FromStr for ScenePool {
+    type Err = ChromaCatError;
+
+    fn from_str(contents: &str) -> std::result::Result<Self, Self::Err> {
+        let pool: ScenePool = serde_yaml::from_str(contents)
+            .map_err(|e| ChromaCatError::InputError(format!("Invalid pool format: {}", e)))?;
+        pool.patterns.validate("pattern", &all_patterns())?;
+        pool.themes.validate("theme", &all_theme_names())?;
+        if !pool.patterns.tags.is_empty() {
+            return Err(ChromaCatError::InputError(
+                "Pool patterns have no tags to choose by".to_string(),
+            ));
+        }
+        if !pool.themes.tags.is_empty() {
+            pool.check_themes_left()?;
+        }
+        Ok(pool)
+    }
+}
+
+/// Draws one name in proportion to its weight
+fn draw<R: Rng>(choices: &[(String, f64)], rng: &mut R, kind: &str) -> Result<String> {
+    let index = WeightedIndex::new(choices.iter().map(|(_, weight)| *weight))
+        .map_err(|_| ChromaCatError::InputError(format!("Pool leaves no {}s to choose from", kind)))?;
+    Ok(choices[index.sample(rng)].0.clone())
+}
+
+/// Every registered pattern id, sorted so draws are reproducible
+fn all_patterns() -> Vec<String> {
+    let mut patterns: Vec<String> = REGISTRY.list_patterns().into_iter().map(String::from).collect();
+    patterns.sort();
+    patterns
+}
+
+/// Every theme name, sorted so draws are reproducible
+fn all_theme_names() -> Vec<String> {
+    let mut names: Vec<String> = themes::all_themes().into_iter().map(|t| t.name).collect();
+    names.sort();
+    names
+}
+
+/// Names of the themes with any of `tags`, or of every theme without tags,
+/// sorted so draws are reproducible
+fn tagged_theme_names(tags: &[String]) -> Vec<String> {
+    if tags.is_empty() {
+        return all_theme_names();
+    }
+    let mut names: Vec<String> = themes::all_themes()
+        .into_iter()
+        .filter(|theme| tags.iter().any(|tag| theme.has_tag(tag)))
+        .map(|theme| theme.name)
+        .collect();
+    names.sort();
+    names
+}
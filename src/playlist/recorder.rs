@@ -0,0 +1,152 @@
+//! Session recording
+//!
+//! While an animation plays, every change of pattern, theme, parameters, or
+//! demo art can be logged with the time it happened. When the session ends
+//! the log is written out as a playlist whose entries last as long as each
+//! look was on screen, so `--playlist` plays the performance back.
+
+use super::command::{join_args, params_to_yaml};
+use super::entry::{Playlist, PlaylistEntry};
+use crate::demo::DemoArt;
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::REGISTRY;
+use std::path::Path;
+use std::time::Duration;
+
+/// What was on screen at one point of a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionScene {
+    /// Pattern id
+    pub pattern: String,
+    /// Theme name
+    pub theme: String,
+    /// Pattern parameters as `key=value` pairs, as accepted by `--param`
+    pub params: String,
+    /// Demo art being shown, if any
+    pub art: Option<DemoArt>,
+}
+
+impl SessionScene {
+    /// Converts the scene into a playlist entry lasting `duration` seconds,
+    /// keeping only the parameters that differ from the pattern's defaults
+    pub fn to_entry(&self, duration: u64) -> PlaylistEntry {
+        let mut entry = PlaylistEntry::new(&self.pattern, &self.theme, duration);
+        if let Some(params) = params_to_yaml(&self.changed_params().join(",")) {
+            entry = entry.with_params(params);
+        }
+        if let Some(art) = self.art {
+            entry = entry.with_art(art);
+        }
+        entry
+    }
+
+    /// Returns the command line that shows the scene, passing only the
+    /// parameters that differ from the pattern's defaults, e.g.
+    /// `chromacat -a -p plasma -t neon --param complexity=3.2`. `flags` go
+    /// before the `demo` subcommand that shows demo art.
+    pub fn to_command(&self, flags: &[String]) -> String {
+        let mut args: Vec<String> = vec!["chromacat".to_string()];
+        if self.art.is_none() {
+            args.push("-a".to_string());
+        }
+        args.extend([
+            "-p".to_string(),
+            self.pattern.clone(),
+            "-t".to_string(),
+            self.theme.clone(),
+        ]);
+        let changed = self.changed_params();
+        if !changed.is_empty() {
+            args.push("--param".to_string());
+            args.push(changed.join(","));
+        }
+        args.extend(flags.iter().cloned());
+        if let Some(art) = self.art {
+            args.push("demo".to_string());
+            args.push(art.as_str().to_string());
+        }
+        join_args(&args)
+    }
+
+    /// Returns the `key=value` parameter pairs that differ from the
+    /// pattern's defaults
+    fn changed_params(&self) -> Vec<&str> {
+        let defaults = REGISTRY
+            .get_pattern(&self.pattern)
+            .map(|metadata| metadata.params().default_value())
+            .unwrap_or_default();
+        let defaults: Vec<&str> = defaults.split(',').collect();
+        self.params
+            .split(',')
+            .filter(|pair| !pair.is_empty() && !defaults.contains(pair))
+            .collect()
+    }
+}
+
+/// Logs the scenes of a session with the time each one started
+#[derive(Debug, Clone, Default)]
+pub struct SessionRecorder {
+    /// Scenes in the order they were shown, with their start times
+    scenes: Vec<(Duration, SessionScene)>,
+}
+
+impl SessionRecorder {
+    /// Creates an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs the scene on screen `at` into the session; nothing is logged if
+    /// it is the scene already being shown
+    pub fn record(&mut self, at: Duration, scene: SessionScene) {
+        if self.scenes.last().is_some_and(|(_, last)| *last == scene) {
+            return;
+        }
+        // A scene replaced within the same instant was never really shown
+        if self.scenes.last().is_some_and(|(start, _)| *start == at) {
+            self.scenes.pop();
+        }
+        self.scenes.push((at, scene));
+    }
+
+    /// Returns the number of scenes logged
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Returns true if nothing has been logged
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Builds a playlist of the session ending `end` into it. Playlist
+    /// durations are whole seconds, so each entry is rounded to the nearest
+    /// second, but lasts at least one.
+    pub fn to_playlist(&self, end: Duration) -> Playlist {
+        let entries = self
+            .scenes
+            .iter()
+            .enumerate()
+            .map(|(index, (start, scene))| {
+                let until = self.scenes.get(index + 1).map_or(end, |(next, _)| *next);
+                let seconds = until.saturating_sub(*start).as_secs_f64().round() as u64;
+                scene.to_entry(seconds.max(1))
+            })
+            .collect();
+        Playlist::with_entries(entries)
+    }
+
+    /// Writes the session ending `end` into it as playlist YAML
+    pub fn save(&self, end: Duration, path: &Path) -> Result<()> {
+        let yaml = serde_yaml::to_string(&self.to_playlist(end)).map_err(|e| {
+            ChromaCatError::Other(format!("Failed to serialize session: {}", e))
+        })?;
+        std::fs::write(path, yaml).map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to write session to {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
@@ -0,0 +1,443 @@
+//! Gradient-animated progress indicators
+//!
+//! This module provides [`GradientBar`] and [`GradientSpinner`], small widgets
+//! that render a single line of gradient-colored progress output. They can be
+//! embedded by other programs or driven from the command line through
+//! `chromacat progress`, which reads progress values from stdin.
+//!
+//! Both widgets are advanced with [`tick`](GradientBar::tick) using the frame
+//! delta, which shifts the gradient over time for a shimmering effect, and
+//! produce their output with `render`.
+
+use std::fmt::Write as FmtWrite;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use colorgrad::Gradient;
+use crossterm::cursor::{Hide, Show};
+use crossterm::execute;
+use unicode_width::UnicodeWidthStr;
+
+use crate::error::Result;
+use crate::escape::strip_sgr;
+use crate::renderer::AnimationConfig;
+use crate::termcaps::ColorSupport;
+use crate::themes;
+
+/// Partial block characters used for sub-cell precision, from 1/8 to 7/8
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Character used for filled cells
+const FULL_BLOCK: char = '█';
+
+/// Character used for the unfilled part of the bar
+const EMPTY_BLOCK: char = '░';
+
+/// Default spinner animation frames
+pub const DEFAULT_SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Writes a foreground color escape for the gradient at `t`, as near as a
+/// terminal with the given support can show it
+fn push_color(out: &mut String, gradient: &dyn Gradient, t: f32, support: ColorSupport) {
+    let color = gradient.at(t.rem_euclid(1.0));
+    let _ = write!(
+        out,
+        "{}",
+        support.fg(
+            (color.r * 255.0) as u8,
+            (color.g * 255.0) as u8,
+            (color.b * 255.0) as u8
+        )
+    );
+}
+
+/// A horizontal progress bar filled with an animated gradient
+pub struct GradientBar {
+    /// Gradient used for the filled part of the bar
+    gradient: Box<dyn Gradient + Send + Sync>,
+    /// Width of the bar itself in columns, excluding label and percentage
+    width: usize,
+    /// Value that represents 100%
+    total: f64,
+    /// Current progress value
+    position: f64,
+    /// Optional message shown before the bar
+    label: Option<String>,
+    /// Gradient offset that advances over time
+    phase: f64,
+    /// Gradient cycles per second
+    speed: f64,
+    /// Whether to emit color escape sequences
+    colors_enabled: bool,
+    /// Colors the terminal can show
+    color_support: ColorSupport,
+}
+
+impl GradientBar {
+    /// Creates a new progress bar with the given gradient and bar width
+    pub fn new(gradient: Box<dyn Gradient + Send + Sync>, width: usize) -> Self {
+        Self {
+            gradient,
+            width: width.max(1),
+            total: 100.0,
+            position: 0.0,
+            label: None,
+            phase: 0.0,
+            speed: 0.25,
+            colors_enabled: true,
+            color_support: ColorSupport::default(),
+        }
+    }
+
+    /// Creates a new progress bar using a named theme
+    pub fn from_theme(theme_name: &str, width: usize) -> Result<Self> {
+        let gradient = themes::get_theme(theme_name)?.create_gradient()?;
+        Ok(Self::new(gradient, width))
+    }
+
+    /// Sets the value that represents 100%
+    pub fn with_total(mut self, total: f64) -> Self {
+        self.total = total.max(f64::EPSILON);
+        self
+    }
+
+    /// Sets the gradient animation speed in cycles per second
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Enables or disables color output
+    pub fn set_colors_enabled(&mut self, enabled: bool) {
+        self.colors_enabled = enabled;
+    }
+
+    /// Writes colors as near as a terminal with the given support can show
+    /// them
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+
+    /// Sets the message shown before the bar
+    pub fn set_label(&mut self, label: Option<&str>) {
+        self.label = label.map(str::to_string);
+    }
+
+    /// Sets the current progress value, clamped to `0..=total`
+    pub fn set_position(&mut self, position: f64) {
+        self.position = position.clamp(0.0, self.total);
+    }
+
+    /// Advances the current progress value
+    pub fn inc(&mut self, delta: f64) {
+        self.set_position(self.position + delta);
+    }
+
+    /// Returns the current progress value
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Returns progress as a fraction between 0.0 and 1.0
+    pub fn fraction(&self) -> f64 {
+        (self.position / self.total).clamp(0.0, 1.0)
+    }
+
+    /// Returns true once the bar is full
+    pub fn is_complete(&self) -> bool {
+        self.fraction() >= 1.0
+    }
+
+    /// Advances the gradient animation by the elapsed frame time
+    pub fn tick(&mut self, delta_seconds: f64) {
+        self.phase = (self.phase + delta_seconds * self.speed).rem_euclid(1.0);
+    }
+
+    /// Renders the bar as a single line without a trailing newline
+    pub fn render(&self) -> String {
+        let mut out = String::with_capacity(self.width * 24);
+
+        if let Some(label) = &self.label {
+            out.push_str(label);
+            out.push(' ');
+        }
+
+        // Eighths of a cell give smooth movement on slow progress
+        let eighths = (self.fraction() * self.width as f64 * 8.0).round() as usize;
+        let full = eighths / 8;
+        let partial = eighths % 8;
+
+        for i in 0..self.width {
+            let ch = if i < full {
+                FULL_BLOCK
+            } else if i == full && partial > 0 {
+                PARTIAL_BLOCKS[partial - 1]
+            } else {
+                EMPTY_BLOCK
+            };
+
+            if self.colors_enabled {
+                if ch == EMPTY_BLOCK {
+                    out.push_str("\x1b[2;37m");
+                } else {
+                    out.push_str("\x1b[22m");
+                    let t = i as f64 / self.width as f64 - self.phase;
+                    push_color(
+                        &mut out,
+                        self.gradient.as_ref(),
+                        t as f32,
+                        self.color_support,
+                    );
+                }
+            }
+            out.push(ch);
+        }
+
+        if self.colors_enabled {
+            out.push_str("\x1b[0m");
+        }
+        let _ = write!(out, " {:>3.0}%", self.fraction() * 100.0);
+        out
+    }
+}
+
+/// A single-character spinner that cycles through frames and colors
+pub struct GradientSpinner {
+    /// Gradient the spinner color moves along
+    gradient: Box<dyn Gradient + Send + Sync>,
+    /// Animation frames
+    frames: Vec<String>,
+    /// Optional message shown after the spinner
+    label: Option<String>,
+    /// Elapsed animation time in seconds
+    elapsed: f64,
+    /// Frames shown per second
+    frame_rate: f64,
+    /// Whether to emit color escape sequences
+    colors_enabled: bool,
+    /// Colors the terminal can show
+    color_support: ColorSupport,
+}
+
+impl GradientSpinner {
+    /// Creates a new spinner with the default frames
+    pub fn new(gradient: Box<dyn Gradient + Send + Sync>) -> Self {
+        Self {
+            gradient,
+            frames: DEFAULT_SPINNER_FRAMES.iter().map(|f| f.to_string()).collect(),
+            label: None,
+            elapsed: 0.0,
+            frame_rate: 12.0,
+            colors_enabled: true,
+            color_support: ColorSupport::default(),
+        }
+    }
+
+    /// Creates a new spinner using a named theme
+    pub fn from_theme(theme_name: &str) -> Result<Self> {
+        let gradient = themes::get_theme(theme_name)?.create_gradient()?;
+        Ok(Self::new(gradient))
+    }
+
+    /// Replaces the animation frames; empty frame lists are ignored
+    pub fn with_frames<S: AsRef<str>>(mut self, frames: &[S]) -> Self {
+        if !frames.is_empty() {
+            self.frames = frames.iter().map(|f| f.as_ref().to_string()).collect();
+        }
+        self
+    }
+
+    /// Enables or disables color output
+    pub fn set_colors_enabled(&mut self, enabled: bool) {
+        self.colors_enabled = enabled;
+    }
+
+    /// Writes colors as near as a terminal with the given support can show
+    /// them
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+
+    /// Sets the message shown after the spinner
+    pub fn set_label(&mut self, label: Option<&str>) {
+        self.label = label.map(str::to_string);
+    }
+
+    /// Advances the animation by the elapsed frame time
+    pub fn tick(&mut self, delta_seconds: f64) {
+        self.elapsed += delta_seconds.max(0.0);
+    }
+
+    /// Returns the index of the frame currently shown
+    pub fn frame_index(&self) -> usize {
+        (self.elapsed * self.frame_rate) as usize % self.frames.len()
+    }
+
+    /// Renders the spinner as a single line without a trailing newline
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if self.colors_enabled {
+            let t = (self.elapsed * self.frame_rate / self.frames.len() as f64) as f32;
+            push_color(&mut out, self.gradient.as_ref(), t, self.color_support);
+        }
+        out.push_str(&self.frames[self.frame_index()]);
+        if self.colors_enabled {
+            out.push_str("\x1b[0m");
+        }
+        if let Some(label) = &self.label {
+            out.push(' ');
+            out.push_str(label);
+        }
+        out
+    }
+}
+
+/// Options for the `progress` subcommand
+#[derive(Debug, Clone)]
+pub struct ProgressOptions {
+    /// Value that represents 100%
+    pub total: f64,
+    /// Width of the bar in columns, or None to fit the terminal
+    pub width: Option<usize>,
+    /// Initial label shown next to the indicator
+    pub label: Option<String>,
+    /// Show a spinner instead of a bar
+    pub spinner: bool,
+    /// Redraw rate in frames per second
+    pub fps: u32,
+    /// Whether to emit color escape sequences
+    pub colors_enabled: bool,
+    /// Colors the terminal can show
+    pub color_support: ColorSupport,
+}
+
+/// An update parsed from one line of progress input
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressUpdate {
+    /// New progress value, if the line starts with a number
+    pub value: Option<f64>,
+    /// New label, if the line has text besides the value
+    pub label: Option<String>,
+}
+
+impl ProgressUpdate {
+    /// Parses a line of the form `VALUE [MESSAGE]` or just `MESSAGE`.
+    /// Values may end with `%` and are then scaled to `total`.
+    pub fn parse(line: &str, total: f64) -> Self {
+        let line = line.trim();
+        let (first, rest) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(first, rest)| (first, rest.trim()));
+
+        let value = match first.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok().map(|p| p / 100.0 * total),
+            None => first.parse::<f64>().ok(),
+        };
+
+        let label = match value {
+            Some(_) if rest.is_empty() => None,
+            Some(_) => Some(rest.to_string()),
+            None if line.is_empty() => None,
+            None => Some(line.to_string()),
+        };
+
+        Self { value, label }
+    }
+}
+
+/// Runs the `progress` subcommand: reads updates from stdin and redraws an
+/// animated indicator on a single line until input ends
+pub fn run_progress(options: &ProgressOptions, theme_name: &str) -> Result<()> {
+    let term_width = crossterm::terminal::size().map_or(80, |(w, _)| w as usize);
+    let width = options
+        .width
+        .unwrap_or_else(|| term_width.saturating_sub(8).min(60));
+
+    let mut bar = GradientBar::from_theme(theme_name, width)?.with_total(options.total);
+    let mut spinner = GradientSpinner::from_theme(theme_name)?;
+    bar.set_colors_enabled(options.colors_enabled);
+    spinner.set_colors_enabled(options.colors_enabled);
+    bar.set_color_support(options.color_support);
+    spinner.set_color_support(options.color_support);
+    bar.set_label(options.label.as_deref());
+    spinner.set_label(options.label.as_deref());
+
+    // Read stdin on a separate thread so the animation keeps running
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let frame_duration = AnimationConfig::new(options.fps, Duration::ZERO).frame_duration();
+    let mut stdout = io::stdout();
+    execute!(stdout, Hide)?;
+    let result = draw_until_done(&mut bar, &mut spinner, options, &rx, frame_duration);
+    execute!(stdout, Show)?;
+    result
+}
+
+/// Applies updates and redraws at the frame rate until the input closes
+fn draw_until_done(
+    bar: &mut GradientBar,
+    spinner: &mut GradientSpinner,
+    options: &ProgressOptions,
+    updates: &Receiver<String>,
+    frame_duration: Duration,
+) -> Result<()> {
+    let mut stdout = io::stdout();
+    let mut last_frame = Instant::now();
+    let mut last_width: usize = 0;
+
+    loop {
+        let timeout = frame_duration.saturating_sub(last_frame.elapsed());
+        let finished = match updates.recv_timeout(timeout) {
+            Ok(line) => {
+                let update = ProgressUpdate::parse(&line, options.total);
+                if let Some(value) = update.value {
+                    bar.set_position(value);
+                }
+                if update.label.is_some() {
+                    bar.set_label(update.label.as_deref());
+                    spinner.set_label(update.label.as_deref());
+                }
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        // Redraw at most once per frame, plus a final frame at the end
+        let elapsed = last_frame.elapsed();
+        if !finished && elapsed < frame_duration {
+            continue;
+        }
+        last_frame = Instant::now();
+        bar.tick(elapsed.as_secs_f64());
+        spinner.tick(elapsed.as_secs_f64());
+
+        let line = if options.spinner {
+            spinner.render()
+        } else {
+            bar.render()
+        };
+
+        // Pad over leftovers from a longer previous line
+        let visible = strip_sgr(&line).width();
+        let padding = last_width.saturating_sub(visible);
+        write!(stdout, "\r{}{}", line, " ".repeat(padding))?;
+        stdout.flush()?;
+        last_width = visible;
+
+        if finished {
+            writeln!(stdout)?;
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Frame assembly for animated output
+//!
+//! Animated frames are built up in one byte buffer and written to the
+//! terminal at once, so a frame never reaches a slow link such as an SSH
+//! session as a trickle of small writes. Terminals that support synchronized
+//! updates are also told to hold the frame back until all of it has arrived,
+//! so it is never shown half drawn.
+
+use std::io::{self, Write};
+
+/// Starts a synchronized update (DEC private mode 2026)
+pub const BEGIN_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026h";
+
+/// Ends a synchronized update, showing everything written since it began
+pub const END_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026l";
+
+/// Bytes set aside per terminal cell, enough for a color change and a glyph
+const BYTES_PER_CELL: usize = 24;
+
+/// Collects everything written for one frame and sends it on in a single
+/// write when the frame is finished.
+///
+/// Flushing the assembler does nothing, so code that flushes as it draws
+/// can draw into it unchanged.
+#[derive(Debug, Default)]
+pub struct FrameAssembler {
+    /// Bytes of the frame being assembled, kept allocated between frames
+    frame: Vec<u8>,
+    /// Whether frames are wrapped in a synchronized update
+    synchronized: bool,
+}
+
+impl FrameAssembler {
+    /// Creates an assembler with room for a frame filling a terminal of the
+    /// given size, wrapping frames in synchronized updates if asked to
+    pub fn new((width, height): (u16, u16), synchronized: bool) -> Self {
+        Self {
+            frame: Vec::with_capacity(width as usize * height as usize * BYTES_PER_CELL),
+            synchronized,
+        }
+    }
+
+    /// Returns whether frames are wrapped in a synchronized update
+    #[inline]
+    pub fn synchronized(&self) -> bool {
+        self.synchronized
+    }
+
+    /// Returns the bytes of the frame assembled so far
+    #[inline]
+    pub fn pending(&self) -> &[u8] {
+        &self.frame
+    }
+
+    /// Writes the assembled frame to `out` in one write and flushes it,
+    /// leaving the assembler empty for the next frame. Nothing is written
+    /// when nothing was drawn.
+    pub fn finish<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        if self.frame.is_empty() {
+            return Ok(());
+        }
+        if self.synchronized {
+            self.frame.extend_from_slice(END_SYNCHRONIZED_UPDATE);
+        }
+        let written = out.write_all(&self.frame).and_then(|_| out.flush());
+        self.frame.clear();
+        written
+    }
+}
+
+impl Write for FrameAssembler {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.frame.is_empty() && self.synchronized && !buf.is_empty() {
+            self.frame.extend_from_slice(BEGIN_SYNCHRONIZED_UPDATE);
+        }
+        self.frame.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -12,28 +12,168 @@ use crossterm::{
 };
 use std::fmt::Write as FmtWrite;
 use std::io::Write;
-use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
+use std::sync::Arc;
 
+use super::camera::Camera;
+use super::effects::TextEffect;
 use super::error::RendererError;
-use crate::pattern::PatternEngine;
-
-/// A cell in the character buffer containing both the character and its color
+use super::gutter::{self, Gutter, GUTTER_COLOR};
+use super::layout::{self, WrapMode};
+use super::loop_cache::LoopCache;
+use super::mask::{MaskMode, TextMask};
+use super::raster::{Resolution, SubCell};
+use super::regions::Compositor;
+use crate::guard;
+use super::screenshot::{Frame, FrameCell};
+use crate::columns::{ColumnPalettes, Columns};
+use crate::data::{DataColumn, DataReader};
+use crate::diff::{DiffClassifier, DiffPalettes};
+use crate::pattern::{CharRamp, Palette, PatternEngine};
+use crate::termcaps::ColorSupport;
+
+/// Seconds of animation time pattern samples are reused across, so scrolling
+/// between two frames only recolors the cells instead of resampling them
+const SAMPLE_TIME_BUCKET: f64 = 0.001;
+
+/// A cell in the character buffer containing one terminal column and its color.
+///
+/// Graphemes wider than one column occupy a leading cell holding the text
+/// followed by continuation cells of width 0, so cells always map 1:1 to
+/// terminal columns and colors stay aligned on mixed-width text.
 #[derive(Debug, Clone, PartialEq)]
 struct BufferCell {
-    /// The character to display
+    /// The character to display (first scalar of the grapheme)
     ch: char,
+    /// Full grapheme cluster when it spans more than one scalar value,
+    /// e.g. emoji ZWJ sequences or characters with combining marks
+    cluster: Option<Box<str>>,
+    /// Display width in columns; 0 marks the continuation of a wide grapheme
+    width: u8,
+    /// Escape sequences from the input emitted before this cell, such as
+    /// OSC 8 hyperlinks
+    prefix: Option<Box<str>>,
+    /// Glyph drawn in place of a blank cell, from a character ramp or
+    /// sub-cell rasterization
+    shade: Option<char>,
     /// The color of the character
     color: Color,
+    /// Background color, painted only by sub-cell rendering
+    bg: Option<Color>,
     /// Whether this cell has been modified since last swap
     dirty: bool,
 }
 
+impl BufferCell {
+    /// Stores a grapheme in this cell
+    fn set_grapheme(&mut self, grapheme: &str, width: usize) {
+        let mut chars = grapheme.chars();
+        self.ch = chars.next().unwrap_or(' ');
+        self.cluster = chars.next().map(|_| grapheme.into());
+        self.width = width.min(u8::MAX as usize) as u8;
+    }
+
+    /// Marks this cell as the trailing half of a wide grapheme
+    fn set_continuation(&mut self) {
+        self.ch = ' ';
+        self.cluster = None;
+        self.width = 0;
+    }
+
+    /// Returns true if this cell continues a wide grapheme to its left
+    #[inline]
+    fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    /// Returns true if this cell holds a plain space that may be shaded
+    #[inline]
+    fn is_blank(&self) -> bool {
+        self.ch == ' ' && self.cluster.is_none() && self.width == 1
+    }
+
+    /// Updates the shade glyph from a character ramp, marking the cell dirty on change
+    #[inline]
+    fn update_shade(&mut self, ramp: Option<&CharRamp>, value: f64) {
+        let shade = ramp.filter(|_| self.is_blank()).map(|r| r.glyph(value));
+        if self.shade != shade || self.bg.is_some() {
+            self.shade = shade;
+            self.bg = None;
+            self.dirty = true;
+        }
+    }
+
+    /// Removes any shade or sub-cell drawing from a blank cell
+    #[inline]
+    fn clear_shade(&mut self) {
+        if self.shade.is_some() || self.bg.is_some() {
+            self.shade = None;
+            self.bg = None;
+            self.dirty = true;
+        }
+    }
+
+    /// Draws a blank cell at `pos` from sub-cell samples, marking it dirty
+    /// on change
+    #[inline]
+    fn update_subcell(
+        &mut self,
+        subcell: SubCell,
+        engine: &PatternEngine,
+        palette: Option<&Palette>,
+        pos: (usize, usize),
+    ) {
+        let color = gradient_color(engine, palette, subcell.fg, None, pos);
+        let bg = subcell.bg.map(|value| gradient_color(engine, palette, value, None, pos));
+        if self.shade != Some(subcell.glyph) || self.color != color || self.bg != bg {
+            self.shade = Some(subcell.glyph);
+            self.color = color;
+            self.bg = bg;
+            self.dirty = true;
+        }
+    }
+
+    /// Appends escapes switching the background to this cell's, if it differs
+    /// from `current`
+    #[inline]
+    fn push_bg_to(
+        &self,
+        out: &mut String,
+        current: &mut Option<Color>,
+        support: ColorSupport,
+    ) -> std::fmt::Result {
+        if *current != self.bg {
+            match self.bg {
+                Some(Color::Rgb { r, g, b }) => write!(out, "{}", support.bg(r, g, b))?,
+                _ => out.push_str("\x1b[49m"),
+            }
+            *current = self.bg;
+        }
+        Ok(())
+    }
+
+    /// Appends the cell's escape prefix and text to the output line
+    #[inline]
+    fn push_to(&self, out: &mut String) {
+        if let Some(prefix) = &self.prefix {
+            out.push_str(prefix);
+        }
+        match &self.cluster {
+            Some(cluster) => out.push_str(cluster),
+            None => out.push(self.shade.unwrap_or(self.ch)),
+        }
+    }
+}
+
 impl Default for BufferCell {
     fn default() -> Self {
         Self {
             ch: ' ',
+            cluster: None,
+            width: 1,
+            prefix: None,
+            shade: None,
             color: Color::Reset,
+            bg: None,
             dirty: false,
         }
     }
@@ -54,6 +194,143 @@ pub struct RenderBuffer {
     original_text: String,
     /// Line wrapping information
     line_info: Vec<(usize, usize)>, // (start, length) pairs
+    /// Input line each row was laid out from
+    line_sources: Vec<usize>,
+    /// How lines longer than the terminal width are laid out
+    wrap_mode: WrapMode,
+    /// First visible column when scrolling horizontally
+    h_offset: usize,
+    /// Character ramp used to shade blank cells by pattern value
+    char_ramp: Option<CharRamp>,
+    /// Pattern samples drawn per blank cell
+    resolution: Resolution,
+    /// View into the virtual canvas used by animated colors
+    camera: Camera,
+    /// Screen regions colored by their own patterns in animated frames
+    regions: Option<Compositor>,
+    /// Strength of the pattern in each cell, derived from the text
+    mask: TextMask,
+    /// Effect drawn around glyphs after coloring
+    effect: TextEffect,
+    /// Palettes of the kinds of diff line, when coloring diffs
+    diff: Option<DiffPalettes>,
+    /// How table columns are found and colored, when coloring by column
+    columns: Option<Columns>,
+    /// Palettes cells are drawn with instead of the engine's theme
+    palettes: CellPalettes,
+    /// Which field's numbers color the lines, when coloring by data
+    data: Option<DataColumn>,
+    /// Reader of the data in the text, with its delimiter and scale
+    data_reader: Option<DataReader>,
+    /// Value of each row's data, from 0 to 1, used in place of the pattern
+    data_values: Vec<Option<f64>>,
+    /// Line numbers and ruler drawn around the text
+    gutter: Gutter,
+    /// Columns the line numbers take
+    gutter_width: usize,
+    /// Number of input lines before the text, added to line numbers
+    line_offset: usize,
+    /// Number of the last input line of the text
+    last_line: usize,
+    /// Whether the ruler needs drawing in animated frames
+    ruler_dirty: bool,
+    /// Diff followed up to the start of the text, when it continues earlier
+    /// output
+    diff_start: DiffClassifier,
+    /// Diff followed up to the end of the text
+    diff_end: DiffClassifier,
+    /// Columns found in earlier output that the text continues
+    kept_columns: Option<ColumnPalettes>,
+    /// Data reader of earlier output that the text continues
+    kept_data: Option<DataReader>,
+    /// Part of a taller text's height the rows cover in static colors, as
+    /// fractions from 0 to 1
+    span: (f64, f64),
+    /// Pattern samples of the viewport taken for animated colors
+    samples: SampleCache,
+    /// Pattern samples of each frame of the animation cycle, when replaying
+    /// it from memory
+    loop_cache: Option<LoopCache<SampleCache>>,
+    /// Frames between ones drawing every row, when interlacing
+    interlace: Option<u32>,
+    /// Frame within the interlacing period
+    interlace_frame: u32,
+    /// Rows the next animated render draws, even or odd ones, if not all
+    field: Option<usize>,
+    /// Colors the terminal can show, which colors are written as
+    color_support: ColorSupport,
+}
+
+/// Pattern samples of the viewport's cells at one animation time. Animated
+/// colors are screen-space, so the samples stay the same when the text
+/// scrolls and only the text under them changes.
+#[derive(Debug, Default)]
+struct SampleCache {
+    /// Time bucket, viewport size and sub-cell grid the samples were taken
+    /// for
+    key: Option<(i64, usize, usize, usize)>,
+    /// Pattern value and light of each cell, row by row, once sampled
+    cells: Vec<Option<(f64, Option<f64>)>>,
+    /// Sub-cell samples of each cell, once it has been drawn blank at
+    /// sub-cell resolution
+    subcells: Vec<Vec<f64>>,
+}
+
+impl SampleCache {
+    /// Keeps the samples if they were taken at `time` for a viewport of the
+    /// given size and sub-cell grid, and forgets them otherwise
+    fn keep_if(&mut self, time: f64, (width, height): (usize, usize), grid: usize) {
+        let key = ((time / SAMPLE_TIME_BUCKET).floor() as i64, width, height, grid);
+        if self.key != Some(key) {
+            self.key = Some(key);
+            self.cells.clear();
+            self.cells.resize(width * height, None);
+            self.subcells.clear();
+            self.subcells.resize(width * height, Vec::new());
+        }
+    }
+
+    /// Forgets every sample
+    fn clear(&mut self) {
+        self.key = None;
+    }
+
+    /// Marks the samples as taken at `time`, so a frame replayed from the
+    /// loop cache keeps them
+    fn retime(&mut self, time: f64) {
+        if let Some(key) = self.key.as_mut() {
+            key.0 = (time / SAMPLE_TIME_BUCKET).floor() as i64;
+        }
+    }
+
+    /// Returns the number of samples held
+    fn len(&self) -> usize {
+        self.cells.len() + self.subcells.iter().map(Vec::len).sum::<usize>()
+    }
+}
+
+/// Palettes that replace the engine's theme for some cells of the laid-out
+/// text
+#[derive(Debug, Default)]
+struct CellPalettes {
+    /// Palette of each row, from the input line it was laid out from
+    rows: Vec<Option<Arc<Palette>>>,
+    /// Palettes of the table columns found in the text
+    columns: Option<ColumnPalettes>,
+    /// Column of each cell of each row, when coloring by column
+    cells: Vec<Vec<usize>>,
+}
+
+impl CellPalettes {
+    /// Returns the palette of the cell at column `x` of `row`, if it has one
+    #[inline]
+    fn get(&self, row: usize, x: usize) -> Option<&Palette> {
+        if let Some(columns) = &self.columns {
+            let column = *self.cells.get(row)?.get(x)?;
+            return columns.get(column).map(|palette| palette.as_ref());
+        }
+        self.rows.get(row)?.as_deref()
+    }
 }
 
 impl RenderBuffer {
@@ -70,130 +347,451 @@ impl RenderBuffer {
             term_size,
             original_text: String::with_capacity(1024), // Pre-allocate reasonable size
             line_info: Vec::with_capacity(height),
+            line_sources: Vec::with_capacity(height),
+            wrap_mode: WrapMode::default(),
+            h_offset: 0,
+            char_ramp: None,
+            resolution: Resolution::default(),
+            camera: Camera::default(),
+            regions: None,
+            mask: TextMask::default(),
+            effect: TextEffect::default(),
+            diff: None,
+            columns: None,
+            palettes: CellPalettes::default(),
+            data: None,
+            data_reader: None,
+            data_values: Vec::new(),
+            gutter: Gutter::default(),
+            gutter_width: 0,
+            line_offset: 0,
+            last_line: 0,
+            ruler_dirty: true,
+            diff_start: DiffClassifier::new(),
+            diff_end: DiffClassifier::new(),
+            kept_columns: None,
+            kept_data: None,
+            span: (0.0, 1.0),
+            samples: SampleCache::default(),
+            loop_cache: None,
+            interlace: None,
+            interlace_frame: 0,
+            field: None,
+            color_support: ColorSupport::default(),
         }
     }
 
-    /// Checks if buffer contains any content
+    /// Creates a new render buffer that lays out lines with the given wrap mode
+    pub fn with_wrap_mode(term_size: (u16, u16), wrap_mode: WrapMode) -> Self {
+        Self {
+            wrap_mode,
+            ..Self::new(term_size)
+        }
+    }
+
+    /// Returns the active wrap mode
     #[inline]
-    pub fn has_content(&self) -> bool {
-        !self.line_info.is_empty()
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.wrap_mode
     }
 
-    /// Returns the number of lines in the buffer
+    /// Changes the wrap mode and re-lays out the current content
+    pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode) -> Result<(), RendererError> {
+        if self.wrap_mode != wrap_mode {
+            self.wrap_mode = wrap_mode;
+            self.h_offset = 0;
+            let text = std::mem::take(&mut self.original_text);
+            self.prepare_text(&text)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the character ramp used to shade blank cells, if any
     #[inline]
-    pub fn line_count(&self) -> usize {
-        self.line_info.len()
+    pub fn char_ramp(&self) -> Option<&CharRamp> {
+        self.char_ramp.as_ref()
     }
 
-    /// Prepares text content by handling wrapping and line breaks.
-    /// Efficiently processes text into lines while respecting terminal width and Unicode.
-    pub fn prepare_text(&mut self, text: &str) -> Result<(), RendererError> {
-        self.original_text = text.to_string();
-        self.line_info.clear();
+    /// Sets the character ramp used to shade blank cells.
+    /// Shades are refreshed on the next color update.
+    pub fn set_char_ramp(&mut self, char_ramp: Option<CharRamp>) {
+        self.char_ramp = char_ramp;
+    }
 
-        let max_width = self.term_size.0.max(1) as usize;
-        let mut buffer_pos = 0;
+    /// Returns the resolution blank cells are drawn at
+    #[inline]
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
 
-        // Pre-calculate required capacity
-        let estimated_lines =
-            (text.len() / max_width) + text.chars().filter(|&c| c == '\n').count() + 1;
-        self.ensure_buffer_capacity(estimated_lines);
+    /// Sets the resolution blank cells are drawn at. At sub-cell resolutions
+    /// blank cells show the pattern itself, taking precedence over a
+    /// character ramp. Cells are redrawn on the next color update.
+    pub fn set_resolution(&mut self, resolution: Resolution) {
+        self.resolution = resolution;
+    }
 
-        // Process each line with efficient wrapping
-        for input_line in text.split('\n') {
-            if input_line.is_empty() {
-                self.line_info.push((buffer_pos, 0));
+    /// Returns how the text masks the pattern
+    #[inline]
+    pub fn mask_mode(&self) -> MaskMode {
+        self.mask.mode()
+    }
 
-                // Clear the entire line in the back buffer
-                while buffer_pos >= self.back.len() {
-                    self.back.push(vec![BufferCell::default(); max_width]);
-                    self.front.push(vec![BufferCell::default(); max_width]);
-                }
+    /// Sets how the text masks the pattern, rebuilding the mask for the
+    /// current text. Cells are redrawn on the next color update.
+    pub fn set_mask_mode(&mut self, mode: MaskMode) {
+        self.mask = TextMask::new(mode, &[]);
+        self.rebuild_mask();
+    }
 
-                // Mark entire line as dirty to ensure it gets cleared
-                for x in 0..max_width {
-                    self.back[buffer_pos][x] = BufferCell::default();
-                    self.back[buffer_pos][x].dirty = true;
-                }
+    /// Returns the effect drawn around glyphs
+    #[inline]
+    pub fn effect(&self) -> TextEffect {
+        self.effect
+    }
 
-                buffer_pos += 1;
-                continue;
-            }
+    /// Sets the effect drawn around glyphs. Cells are redrawn on the next
+    /// color update.
+    pub fn set_effect(&mut self, effect: TextEffect) {
+        self.effect = effect;
+    }
 
-            let mut line_width = 0;
-            let mut line_start = buffer_pos;
-            let mut last_break = None;
-            let mut segment_start = 0;
+    /// Returns the camera over the virtual canvas
+    #[inline]
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
 
-            let graphemes: Vec<_> = input_line.graphemes(true).collect();
-            let mut i = 0;
+    /// Returns the camera for panning and zooming
+    #[inline]
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
 
-            while i < graphemes.len() {
-                let grapheme = &graphemes[i];
-                let width = grapheme.width();
+    /// Sets the camera used when coloring animated frames. The pattern
+    /// engine should be sized to the camera's canvas.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
 
-                // Handle line wrapping
-                if line_width + width > max_width {
-                    // Find break point
-                    let break_pos = last_break.unwrap_or(i);
-                    let length = if last_break.is_some() {
-                        break_pos - segment_start
-                    } else {
-                        i - segment_start
-                    };
+    /// Returns the region layout being drawn, if any
+    pub fn regions(&self) -> Option<&Compositor> {
+        self.regions.as_ref()
+    }
 
-                    // Record the line segment
-                    if length > 0 {
-                        self.line_info.push((line_start, length));
-                    }
+    /// Returns the region layout for animating and resizing
+    pub fn regions_mut(&mut self) -> Option<&mut Compositor> {
+        self.regions.as_mut()
+    }
 
-                    // Start new line
-                    buffer_pos += 1; // Only advance one line
-                    line_start = buffer_pos;
+    /// Sets the region layout used when coloring animated frames. Cells
+    /// outside every region, and all cells without a layout, use the main
+    /// engine seen through the camera.
+    pub fn set_regions(&mut self, regions: Option<Compositor>) {
+        self.regions = regions;
+    }
 
-                    if last_break.is_some() {
-                        segment_start = break_pos + 1;
-                        i = break_pos + 1;
-                    } else {
-                        segment_start = i;
-                    }
+    /// Replays the pattern samples of a repeating animation from memory
+    /// once a cycle of `frames` frames has been drawn, or stops doing so
+    /// with None. The engine's clock must have a cycle; frames drawn
+    /// without one, or with regions, are sampled as usual.
+    pub fn set_loop_cache(&mut self, frames: Option<usize>) {
+        self.loop_cache = frames.map(LoopCache::new);
+    }
 
-                    line_width = 0;
-                    last_break = None;
-                    continue;
-                }
+    /// Returns how many frames of the cycle the loop cache has recorded,
+    /// and how many there are, if it is on
+    pub fn loop_cache_progress(&self) -> Option<(usize, usize)> {
+        self.loop_cache
+            .as_ref()
+            .map(|cache| (cache.recorded(), cache.frames()))
+    }
 
-                // Store character in back buffer
-                if let Some(ch) = grapheme.chars().next() {
-                    let y = buffer_pos;
-                    let x = line_width;
+    /// Returns the frames between ones drawing every row, if interlacing
+    pub fn interlace(&self) -> Option<u32> {
+        self.interlace
+    }
 
-                    // Grow buffer if needed
-                    while y >= self.back.len() {
-                        self.back.push(vec![BufferCell::default(); max_width]);
-                        self.front.push(vec![BufferCell::default(); max_width]);
-                    }
+    /// Draws only the even or the odd rows in each animation frame, taking
+    /// turns, with every row drawn once every `full_every` frames; None
+    /// draws every row each frame
+    pub fn set_interlace(&mut self, full_every: Option<u32>) {
+        self.interlace = full_every.map(|every| every.max(2));
+        self.interlace_frame = 0;
+        self.field = None;
+    }
+
+    /// Starts an animation frame, so the next animated render draws only
+    /// this frame's rows when interlacing. Rows left out keep their changes
+    /// for the next frame that draws them.
+    pub fn begin_interlaced_frame(&mut self) {
+        let Some(every) = self.interlace else {
+            return;
+        };
+        self.interlace_frame = (self.interlace_frame + 1) % every;
+        self.field = (self.interlace_frame != 0).then_some(self.interlace_frame as usize % 2);
+    }
+
+    /// Writes colors as near as a terminal with the given support can show
+    /// them
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+
+    /// Colors the lines of unified diffs by kind with `palettes`, or stops
+    /// when None; takes effect when text is next prepared
+    pub fn set_diff(&mut self, palettes: Option<DiffPalettes>) {
+        self.diff = palettes;
+    }
+
+    /// Colors table columns each with their own palette, or stops when
+    /// None; takes effect when text is next prepared
+    pub fn set_columns(&mut self, columns: Option<Columns>) {
+        self.columns = columns;
+    }
+
+    /// Colors each line by the number in one of its fields rather than by
+    /// the pattern, or stops when None; takes effect when text is next
+    /// prepared
+    pub fn set_data(&mut self, data: Option<DataColumn>) {
+        self.data = data;
+    }
+
+    /// Returns the reader of the data in the text, when coloring by data
+    pub fn data_reader(&self) -> Option<&DataReader> {
+        self.data_reader.as_ref()
+    }
+
+    /// Returns the palette the cell at column `x` of `row` is drawn with
+    /// instead of the engine's theme, if any
+    #[inline]
+    pub fn cell_palette(&self, row: usize, x: usize) -> Option<&Palette> {
+        self.palettes.get(row, x)
+    }
+
+    /// Returns the line numbers and ruler drawn around the text
+    pub fn gutter(&self) -> Gutter {
+        self.gutter
+    }
+
+    /// Draws line numbers and a ruler around the text as `gutter` says;
+    /// takes effect when text is next prepared
+    pub fn set_gutter(&mut self, gutter: Gutter) {
+        self.gutter = gutter;
+    }
+
+    /// Numbers lines as if `offset` input lines came before the text, for
+    /// text that continues earlier output
+    pub fn set_line_offset(&mut self, offset: usize) {
+        self.line_offset = offset;
+    }
+
+    /// Returns the number of input lines before the text
+    pub fn line_offset(&self) -> usize {
+        self.line_offset
+    }
+
+    /// Makes the next text continue the one laid out now, as when a large
+    /// input is laid out a chunk at a time: its lines are numbered on from
+    /// the last one, a diff is followed on from where it left off, and the
+    /// columns and data scale found so far are kept
+    pub fn continue_text(&mut self) {
+        self.line_offset = self.last_line;
+        self.diff_start = self.diff_end.clone();
+        if self.kept_columns.is_none() {
+            self.kept_columns = self.palettes.columns.clone();
+        }
+        self.kept_data = self.data_reader.clone();
+    }
+
+    /// Makes the next text start afresh, undoing
+    /// [`continue_text`](Self::continue_text) and [`set_span`](Self::set_span)
+    pub fn restart_text(&mut self) {
+        self.line_offset = 0;
+        self.diff_start = DiffClassifier::new();
+        self.kept_columns = None;
+        self.kept_data = None;
+        self.span = (0.0, 1.0);
+    }
+
+    /// Spreads static colors over the rows as if they were the part from
+    /// `start` to `end` of a taller text, both fractions of its height, so
+    /// the gradient runs on from one chunk of a large input to the next
+    pub fn set_span(&mut self, start: f64, end: f64) {
+        self.span = (start, end);
+    }
+
+    /// Returns the columns the line numbers take
+    #[inline]
+    pub fn gutter_width(&self) -> usize {
+        self.gutter_width
+    }
+
+    /// Returns the columns left for the text beside the line numbers
+    #[inline]
+    pub fn content_width(&self) -> usize {
+        (self.term_size.0 as usize)
+            .saturating_sub(self.gutter_width)
+            .max(1)
+    }
+
+    /// Returns the first visible column when scrolling horizontally
+    #[inline]
+    pub fn h_offset(&self) -> usize {
+        self.h_offset
+    }
+
+    /// Sets the first visible column, clamped to the widest line.
+    /// Has no effect unless the wrap mode allows horizontal scrolling.
+    pub fn set_h_offset(&mut self, offset: usize) {
+        if !self.wrap_mode.allows_horizontal_scroll() {
+            return;
+        }
+
+        let offset = offset.min(self.max_h_offset());
+        if offset != self.h_offset {
+            self.h_offset = offset;
+            self.mark_all_dirty();
+        }
+    }
+
+    /// Returns the largest useful horizontal offset for the current content
+    #[inline]
+    pub fn max_h_offset(&self) -> usize {
+        self.max_line_length()
+            .saturating_sub(self.content_width())
+    }
+
+    /// Checks if buffer contains any content
+    #[inline]
+    pub fn has_content(&self) -> bool {
+        !self.line_info.is_empty()
+    }
+
+    /// Returns the number of lines in the buffer
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_info.len()
+    }
+
+    /// Returns the input line a row was laid out from
+    #[inline]
+    pub fn source_line(&self, row: usize) -> Option<usize> {
+        self.line_sources.get(row).copied()
+    }
+
+    /// Returns the first row laid out from an input line
+    pub fn first_row_of(&self, line: usize) -> Option<usize> {
+        let row = self.line_sources.partition_point(|&source| source < line);
+        (self.line_sources.get(row) == Some(&line)).then_some(row)
+    }
+
+    /// Returns the text the rows were laid out from
+    pub fn text(&self) -> &str {
+        &self.original_text
+    }
 
-                    self.back[y][x].ch = ch;
-                    self.back[y][x].dirty = true;
+    /// Prepares text content by laying out lines according to the wrap mode.
+    /// Efficiently processes text into rows while respecting terminal width and Unicode.
+    pub fn prepare_text(&mut self, text: &str) -> Result<(), RendererError> {
+        self.original_text = text.to_string();
+        self.line_info.clear();
+        self.line_sources.clear();
+        let mut classifier = self.diff_start.clone();
+        let line_palettes = self
+            .diff
+            .as_ref()
+            .map(|diff| diff.for_text_from(text, &mut classifier))
+            .unwrap_or_default();
+        self.diff_end = classifier;
+        let columns = match &self.kept_columns {
+            Some(kept) => Some(kept.clone()),
+            None => self.columns.as_ref().map(|columns| columns.palettes_for(text)),
+        };
+        self.palettes = CellPalettes {
+            columns,
+            ..CellPalettes::default()
+        };
+        self.data_reader = match self.kept_data.clone() {
+            Some(kept) => Some(kept),
+            None => self.data.as_ref().map(|data| data.reader_for(text)),
+        };
+        self.data_values.clear();
+
+        // A final newline ends the last line rather than starting another
+        let lines = text.strip_suffix('\n').unwrap_or(text).split('\n').count();
+        self.last_line = self.line_offset + lines;
+        self.gutter_width = self.gutter.width(self.last_line);
+        self.ruler_dirty = true;
+
+        let max_width = self.content_width();
+        let mut row_idx = 0;
+
+        for (source, input_line) in text.split('\n').enumerate() {
+            // Windows line endings end a line like any other
+            let input_line = input_line.strip_suffix('\r').unwrap_or(input_line);
+            let line_glyphs = layout::glyphs(input_line);
+            let mut tracker = self.palettes.columns.as_ref().map(ColumnPalettes::tracker);
+            let data_value = self
+                .data_reader
+                .as_mut()
+                .and_then(|reader| reader.value(input_line));
+
+            for row in self.wrap_mode.layout(&line_glyphs, max_width) {
+                let row_width = layout::row_width(&row);
+                // One spare cell holds escapes that trail the last glyph
+                self.ensure_row(row_idx, (row_width + 1).max(max_width));
+
+                // Reset the row so content from a previous layout doesn't linger
+                let cells = &mut self.back[row_idx];
+                for cell in cells.iter_mut() {
+                    *cell = BufferCell::default();
+                    cell.dirty = true;
                 }
 
-                // Update tracking
-                if grapheme.chars().all(char::is_whitespace) {
-                    last_break = Some(i);
+                // Store each glyph at its starting column, padding wide glyphs
+                // with continuation cells so every cell is one column
+                let mut x = 0;
+                let mut pending = String::new();
+                let mut columns = Vec::new();
+                for glyph in row {
+                    // Escape sequences ride along with the next visible cell
+                    if glyph.is_escape() {
+                        pending.push_str(glyph.text);
+                        continue;
+                    }
+                    let column = tracker.as_mut().map(|tracker| tracker.next(glyph.text));
+                    // Other zero-width glyphs (control characters) occupy no column
+                    if glyph.width == 0 {
+                        continue;
+                    }
+                    if let Some(column) = column {
+                        columns.resize(x + glyph.width, column);
+                    }
+                    if !pending.is_empty() {
+                        cells[x].prefix = Some(std::mem::take(&mut pending).into());
+                    }
+                    cells[x].set_grapheme(glyph.text, glyph.width);
+                    for cell in &mut cells[x + 1..x + glyph.width] {
+                        cell.set_continuation();
+                    }
+                    x += glyph.width;
+                }
+                if !pending.is_empty() {
+                    cells[x].prefix = Some(pending.into());
                 }
-                line_width += width;
-                i += 1;
-            }
 
-            // Record the final line segment
-            if line_width > 0 {
-                self.line_info.push((line_start, line_width));
+                self.line_info.push((row_idx, row_width));
+                self.line_sources.push(source);
+                self.palettes.rows.push(line_palettes.get(source).cloned().flatten());
+                self.palettes.cells.push(columns);
+                self.data_values.push(data_value);
+                row_idx += 1;
             }
-
-            buffer_pos += 1; // Move to next line
         }
 
+        self.clamp_h_offset();
+        self.rebuild_mask();
         Ok(())
     }
 
@@ -204,7 +802,54 @@ impl RenderBuffer {
         engine: &PatternEngine,
         viewport_start: usize,
     ) -> Result<(), RendererError> {
-        let width = self.term_size.0 as usize;
+        self.samples.clear();
+        let slot = self.replay_loop_frame(engine);
+        self.update_colors_scrolled(engine, viewport_start)?;
+        if let (Some(slot), Some(cache)) = (slot, self.loop_cache.as_mut()) {
+            let cells = self.samples.len();
+            cache.store(slot, std::mem::take(&mut self.samples), cells);
+        }
+        Ok(())
+    }
+
+    /// Takes the samples of the current frame out of the loop cache if it
+    /// recorded them, returning the slot the frame belongs in
+    fn replay_loop_frame(&mut self, engine: &PatternEngine) -> Option<usize> {
+        let progress = engine.clock().cycle_progress()?;
+        if self.loop_cache.is_none() || self.regions.is_some() {
+            return None;
+        }
+
+        // Anything that changes the samples, other than time, starts over
+        let key = format!(
+            "{}x{} {} {:?} {:?} {:?}",
+            self.content_width(),
+            self.term_size.1,
+            self.resolution.grid().0 * self.resolution.grid().1,
+            self.camera,
+            engine.config(),
+            engine.light_map().map(|light| light as *const _),
+        );
+        let cache = self.loop_cache.as_mut()?;
+        cache.keep_if(key);
+        let slot = cache.slot(progress);
+        if let Some(samples) = cache.take(slot) {
+            self.samples = samples;
+            self.samples.retime(engine.time());
+        }
+        Some(slot)
+    }
+
+    /// Updates colors after the viewport scrolled, reusing the pattern
+    /// samples of the last update when the animation time hasn't moved on,
+    /// so only the text newly under each cell is recolored. Anything else
+    /// that changes the pattern needs [`update_colors`](Self::update_colors).
+    pub fn update_colors_scrolled(
+        &mut self,
+        engine: &PatternEngine,
+        viewport_start: usize,
+    ) -> Result<(), RendererError> {
+        let width = self.content_width();
         let height = self.term_size.1 as usize;
 
         // Pre-calculate constants for coordinate normalization
@@ -213,6 +858,18 @@ impl RenderBuffer {
 
         // Pre-allocate pattern value buffer to reduce pattern calculation overhead
         let mut pattern_values = vec![0.0f64; width];
+        let mut light_values = vec![None; width];
+        let mut cell_regions = vec![None; width];
+        let resolution = self.resolution;
+        let (grid_x, grid_y) = resolution.grid();
+        let mut samples = vec![0.0f64; grid_x * grid_y];
+        let camera = &self.camera;
+        let regions = self.regions.as_ref();
+        let mask = &self.mask;
+        let palettes = &self.palettes;
+        let data_values = &self.data_values;
+        let cache = &mut self.samples;
+        cache.keep_if(engine.time(), (width, height), samples.len());
 
         // Process each line in the buffer
         for (buffer_y, line) in self.back.iter_mut().enumerate() {
@@ -230,43 +887,131 @@ impl RenderBuffer {
 
             // Calculate normalized y coordinate once per line
             let norm_y = viewport_y / height_f - 0.5;
+            let row = buffer_y - viewport_start;
+
+            // Lines colored by their data leave the pattern out; their
+            // values follow the text, so they aren't cached by position
+            let data_value = data_values.get(buffer_y).copied().flatten();
+            if let Some(data_value) = data_value {
+                pattern_values.fill(data_value);
+                light_values.fill(None);
+                cell_regions.fill(None);
+            }
 
-            // Calculate pattern values for entire line at once
-            for (x, value) in pattern_values.iter_mut().enumerate().take(width) {
-                let norm_x = (x as f64 / width_f) - 0.5;
-                *value = engine.get_value_at_normalized(norm_x, norm_y)?;
+            // Calculate pattern values for entire line at once. Cells inside
+            // a region take its pattern; the rest see the main engine
+            // through the camera.
+            for (x, value) in pattern_values
+                .iter_mut()
+                .enumerate()
+                .take(width)
+                .filter(|_| data_value.is_none())
+            {
+                let region = regions.and_then(|r| r.region_at(x, row));
+                cell_regions[x] = region;
+                let cached = &mut cache.cells[row * width + x];
+                if let Some(sample) = *cached {
+                    (*value, light_values[x]) = sample;
+                    continue;
+                }
+                (*value, light_values[x]) = match region {
+                    Some(region) => (region.value_at(x as f64, viewport_y)?, None),
+                    None => {
+                        let norm_x = (x as f64 / width_f) - 0.5;
+                        let (canvas_x, canvas_y) = camera.map(norm_x, norm_y);
+                        (
+                            engine.get_value_at_normalized(canvas_x, canvas_y)?,
+                            engine.light_at_normalized(canvas_x, canvas_y),
+                        )
+                    }
+                };
+                *cached = Some((*value, light_values[x]));
             }
 
-            // Apply colors using pre-calculated pattern values
+            // Apply colors using pre-calculated pattern values. Colors are
+            // screen-space, so the visible columns start at the scroll offset.
             for (x, &pattern_value) in pattern_values.iter().enumerate().take(width) {
-                let gradient_color = engine.gradient().at(pattern_value as f32);
-                let color = Color::Rgb {
-                    r: (gradient_color.r * 255.0) as u8,
-                    g: (gradient_color.g * 255.0) as u8,
-                    b: (gradient_color.b * 255.0) as u8,
+                let Some(cell) = line.get_mut(x + self.h_offset) else {
+                    break;
                 };
+                let region = cell_regions[x];
+                let cell_engine = region.map_or(engine, |r| r.engine());
+                let palette = palettes.get(buffer_y, x + self.h_offset);
+
+                // Blank cells the mask leaves no strength stay empty
+                let strength = mask.at(x + self.h_offset, buffer_y);
+                if strength <= 0.0 && cell.is_blank() {
+                    cell.clear_shade();
+                    continue;
+                }
+                let pattern_value = pattern_value * strength;
+
+                // Blank cells show the pattern at sub-cell resolution
+                if resolution.is_subcell() && cell.is_blank() && data_value.is_none() {
+                    let cached = &mut cache.subcells[row * width + x];
+                    if cached.len() != samples.len() {
+                        cached.clear();
+                        for i in 0..samples.len() {
+                            let sub_x = x as f64 + (i % grid_x) as f64 / grid_x as f64;
+                            let sub_y = viewport_y + (i / grid_x) as f64 / grid_y as f64;
+                            cached.push(match region {
+                                Some(region) => region.value_at(sub_x, sub_y)?,
+                                None => {
+                                    let (canvas_x, canvas_y) =
+                                        camera.map(sub_x / width_f - 0.5, sub_y / height_f - 0.5);
+                                    engine.get_value_at_normalized(canvas_x, canvas_y)?
+                                }
+                            });
+                        }
+                    }
+                    for (sample, &value) in samples.iter_mut().zip(cached.iter()) {
+                        *sample = strength * value;
+                    }
+                    if let Some(subcell) = resolution.rasterize(&samples) {
+                        cell.update_subcell(subcell, cell_engine, palette, (x, buffer_y));
+                    }
+                    continue;
+                }
+
+                let light = cell_light(cell_engine, light_values[x], strength);
+                let color =
+                    gradient_color(cell_engine, palette, pattern_value, light, (x, buffer_y));
 
                 // Only mark as dirty if color actually changed
-                if line[x].color != color {
-                    line[x].color = color;
-                    line[x].dirty = true;
+                if cell.color != color {
+                    cell.color = color;
+                    cell.dirty = true;
                 }
+                cell.update_shade(self.char_ramp.as_ref(), pattern_value);
             }
         }
 
+        self.apply_effect(viewport_start..viewport_start + height);
         Ok(())
     }
 
     /// Updates colors in static mode, creating a flowing effect by advancing the pattern per line.
     pub fn update_colors_static(&mut self, engine: &PatternEngine) -> Result<(), RendererError> {
-        let width = self.term_size.0 as usize;
+        let width = self.content_width();
         let width_f = width as f64;
         let height_f = self.line_info.len() as f64;
+        // Place of a row in the taller text the rows are part of
+        let (span_start, span_end) = self.span;
+        let span_y = |y: f64| span_start + y / height_f * (span_end - span_start);
 
-        // Pre-allocate pattern value buffer
-        let mut pattern_values = vec![0.0f64; width];
+        // Pre-allocate pattern value buffer, wide enough for unwrapped lines
+        let mut pattern_values = vec![0.0f64; width.max(self.max_line_length())];
+        let mut light_values = vec![None; pattern_values.len()];
+        let resolution = self.resolution;
+        let (grid_x, grid_y) = resolution.grid();
+        let mut samples = vec![0.0f64; grid_x * grid_y];
 
         for y in 0..self.line_info.len() {
+            // Large inputs take a while; stop early when interrupted
+            if guard::interrupted() {
+                break;
+            }
+
             let (start, len) = self.line_info[y];
 
             // Skip empty lines
@@ -275,69 +1020,112 @@ impl RenderBuffer {
             }
 
             // Ensure buffer has enough rows
-            while start >= self.back.len() {
-                self.back.push(vec![BufferCell::default(); width]);
-                self.front.push(vec![BufferCell::default(); width]);
-            }
+            self.ensure_row(start, len.max(width));
 
             // Calculate normalized y coordinate with more dramatic progression
             // Multiply by 2.0 to make the pattern advance twice as fast
-            let norm_y = ((y as f64 * 2.0) / height_f) - 0.5;
-
-            // Calculate pattern values for entire line at once
-            for (x, value) in pattern_values.iter_mut().enumerate().take(len.min(width)) {
+            let norm_y = span_y(y as f64) * 2.0 - 0.5;
+
+            // Calculate pattern values for entire line at once, unless the
+            // line is colored by its data
+            let data_value = self.data_values.get(start).copied().flatten();
+            for (x, value) in pattern_values.iter_mut().enumerate().take(len) {
+                if let Some(data_value) = data_value {
+                    (*value, light_values[x]) = (data_value, None);
+                    continue;
+                }
                 let norm_x = (x as f64 / width_f) - 0.5;
                 *value = engine.get_value_at_normalized(norm_x, norm_y)?;
+                light_values[x] = engine.light_at_normalized(norm_x, norm_y);
             }
 
             // Apply colors using pre-calculated pattern values
-            for (x, &pattern_value) in pattern_values.iter().enumerate().take(len.min(width)) {
-                let gradient_color = engine.gradient().at(pattern_value as f32);
-                let color = Color::Rgb {
-                    r: (gradient_color.r * 255.0) as u8,
-                    g: (gradient_color.g * 255.0) as u8,
-                    b: (gradient_color.b * 255.0) as u8,
-                };
-
+            for (x, &pattern_value) in pattern_values.iter().enumerate().take(len) {
                 let cell = &mut self.back[start][x];
+                let palette = self.palettes.get(start, x);
+
+                // Blank cells the mask leaves no strength stay empty
+                let strength = self.mask.at(x, start);
+                if strength <= 0.0 && cell.is_blank() {
+                    cell.clear_shade();
+                    continue;
+                }
+                let pattern_value = pattern_value * strength;
+
+                // Blank cells show the pattern at sub-cell resolution
+                if resolution.is_subcell() && cell.is_blank() && data_value.is_none() {
+                    for (i, sample) in samples.iter_mut().enumerate() {
+                        let sub_x = x as f64 + (i % grid_x) as f64 / grid_x as f64;
+                        let sub_y = y as f64 + (i / grid_x) as f64 / grid_y as f64;
+                        *sample = strength
+                            * engine.get_value_at_normalized(
+                                sub_x / width_f - 0.5,
+                                span_y(sub_y) * 2.0 - 0.5,
+                            )?;
+                    }
+                    if let Some(subcell) = resolution.rasterize(&samples) {
+                        cell.update_subcell(subcell, engine, palette, (x, y));
+                    }
+                    continue;
+                }
+
+                let light = cell_light(engine, light_values[x], strength);
+                let color = gradient_color(engine, palette, pattern_value, light, (x, y));
                 if cell.color != color {
                     cell.color = color;
                     cell.dirty = true;
                 }
+                cell.update_shade(self.char_ramp.as_ref(), pattern_value);
             }
         }
 
+        self.apply_effect(0..self.line_info.len());
         Ok(())
     }
 
     /// Renders a region of the buffer to the terminal with optimized color handling
     /// and double buffering to eliminate flicker.
-    pub fn render_region(
+    pub fn render_region<W: Write>(
         &mut self,
-        stdout: &mut std::io::StdoutLock,
+        stdout: &mut W,
         start: usize,
         end: usize,
         colors_enabled: bool,
         is_animated: bool,
     ) -> Result<(), RendererError> {
-        let width = self.term_size.0 as usize;
+        let width = self.content_width();
+        let header_rows = self.gutter.header_rows();
+        let support = self.color_support;
 
         if is_animated {
             // Animation mode: Use cursor movement and selective updates
             queue!(stdout, Hide)?;
 
+            if self.gutter.ruler && self.ruler_dirty {
+                let ruler = self.ruler_line(colors_enabled)?;
+                queue!(stdout, MoveTo(0, 0), Print(ruler))?;
+                self.ruler_dirty = false;
+            }
+
             // Track if any updates were made
+            let field = self.field.take();
             let mut any_updates = false;
             let mut needs_color_reset = false;
             let mut last_color = None;
+            let mut last_bg = None;
 
             // Process each line in the visible region
             for (display_y, line_idx) in (start..end.min(self.line_info.len())).enumerate() {
                 let (line_start, line_len) = self.line_info[line_idx];
 
-                // Skip lines that haven't changed
+                // Skip lines that haven't changed, or that another frame
+                // of the interlace draws
+                if field.is_some_and(|field| display_y % 2 != field) {
+                    continue;
+                }
                 if !self.back[line_start]
                     .iter()
+                    .skip(self.h_offset)
                     .take(width)
                     .any(|cell| cell.dirty)
                 {
@@ -347,25 +1135,65 @@ impl RenderBuffer {
                 any_updates = true;
 
                 // Move cursor only when we need to update
-                queue!(stdout, MoveTo(0, display_y as u16))?;
+                queue!(stdout, MoveTo(0, (display_y + header_rows) as u16))?;
 
                 // Build line content
                 let mut line_buffer = String::with_capacity(width * 4);
+                if self.gutter_width > 0 {
+                    if last_bg.is_some() {
+                        line_buffer.push_str("\x1b[49m");
+                        last_bg = None;
+                    }
+                    let label = self
+                        .row_label(line_idx)
+                        .unwrap_or_else(|| " ".repeat(self.gutter_width));
+                    push_gutter(&mut line_buffer, &label, colors_enabled, &mut last_color, support)?;
+                    needs_color_reset |= colors_enabled;
+                }
 
                 // Always process the full width for consistent display
-                for x in 0..width {
-                    let back_cell = &mut self.back[line_start][x];
+                let row = &mut self.back[line_start];
+                let view_end = self.h_offset + width;
+                for col in self.h_offset..view_end {
+                    let Some(back_cell) = row.get_mut(col) else {
+                        if last_bg.is_some() {
+                            line_buffer.push_str("\x1b[49m");
+                            last_bg = None;
+                        }
+                        line_buffer.push(' ');
+                        continue;
+                    };
+
+                    // The leading cell already printed this column
+                    if back_cell.is_continuation() && col > self.h_offset {
+                        back_cell.dirty = false;
+                        continue;
+                    }
 
                     // Only update color if it changed
                     if colors_enabled && last_color != Some(back_cell.color) {
                         if let Color::Rgb { r, g, b } = back_cell.color {
-                            write!(line_buffer, "\x1b[38;2;{};{};{}m", r, g, b)?;
+                            write!(line_buffer, "{}", support.fg(r, g, b))?;
                             needs_color_reset = true;
                         }
                         last_color = Some(back_cell.color);
                     }
+                    if colors_enabled {
+                        back_cell.push_bg_to(&mut line_buffer, &mut last_bg, support)?;
+                        needs_color_reset |= last_bg.is_some();
+                    }
 
-                    line_buffer.push(if x < line_len { back_cell.ch } else { ' ' });
+                    // Pad with a space when a wide grapheme is cut by either
+                    // edge of the viewport so the row stays exactly `width` wide
+                    let clipped = back_cell.is_continuation()
+                        || col + back_cell.width as usize > view_end;
+                    if clipped {
+                        line_buffer.push(' ');
+                    } else if col <= line_len {
+                        back_cell.push_to(&mut line_buffer);
+                    } else {
+                        line_buffer.push(back_cell.shade.unwrap_or(' '));
+                    }
 
                     // Clear dirty flag after processing
                     back_cell.dirty = false;
@@ -384,24 +1212,66 @@ impl RenderBuffer {
             // Static mode: Simple line-by-line output
             let mut needs_color_reset = false;
 
+            // The ruler heads the text, not text continuing earlier output
+            if self.gutter.ruler && start == 0 && self.line_offset == 0 {
+                writeln!(stdout, "{}", self.ruler_line(colors_enabled)?)?;
+            }
+
             for line_idx in start..end.min(self.line_info.len()) {
+                if guard::interrupted() {
+                    break;
+                }
+
                 let (line_start, line_len) = self.line_info[line_idx];
 
                 let mut line_buffer = String::with_capacity(width * 4);
                 let mut last_color = None;
+                let mut last_bg = None;
+                if let Some(label) = self.row_label(line_idx) {
+                    push_gutter(&mut line_buffer, &label, colors_enabled, &mut last_color, support)?;
+                    needs_color_reset |= colors_enabled;
+                }
+
+                // Effects may paint blank cells past the end of the text
+                let painted_len = match colors_enabled {
+                    true => self.back[line_start]
+                        .iter()
+                        .rposition(|cell| cell.bg.is_some())
+                        .map_or(line_len, |last| line_len.max(last + 1)),
+                    false => line_len,
+                };
 
-                for x in 0..line_len.min(width) {
+                for x in 0..painted_len {
                     let back_cell = &self.back[line_start][x];
+                    if back_cell.is_continuation() {
+                        continue;
+                    }
 
                     if colors_enabled && last_color != Some(back_cell.color) {
                         if let Color::Rgb { r, g, b } = back_cell.color {
-                            write!(line_buffer, "\x1b[38;2;{};{};{}m", r, g, b)?;
+                            write!(line_buffer, "{}", support.fg(r, g, b))?;
                             needs_color_reset = true;
                         }
                         last_color = Some(back_cell.color);
                     }
+                    if colors_enabled {
+                        back_cell.push_bg_to(&mut line_buffer, &mut last_bg, support)?;
+                    }
 
-                    line_buffer.push(back_cell.ch);
+                    back_cell.push_to(&mut line_buffer);
+                }
+
+                // Don't carry a background color past the end of the line
+                if last_bg.is_some() {
+                    line_buffer.push_str("\x1b[49m");
+                }
+
+                // Escapes trailing the last glyph, e.g. closing hyperlinks
+                if let Some(prefix) = self.back[line_start]
+                    .get(line_len)
+                    .and_then(|cell| cell.prefix.as_deref())
+                {
+                    line_buffer.push_str(prefix);
                 }
 
                 line_buffer.push('\n');
@@ -414,19 +1284,58 @@ impl RenderBuffer {
         }
 
         // Swap buffers after rendering
-        for y in start..end {
-            if y < self.back.len() {
-                for x in 0..width {
-                    self.front[y][x] = self.back[y][x].clone();
-                }
-            }
+        for y in start..end.min(self.back.len()) {
+            self.front[y].clone_from(&self.back[y]);
         }
 
         Ok(())
     }
 
+    /// Captures `rows` visible lines starting at line `start`, as last
+    /// colored, into a frame as wide as the terminal
+    pub fn capture(&self, start: usize, rows: usize) -> Frame {
+        let width = self.term_size.0 as usize;
+        let mut frame = Frame::new(width, rows);
+        let rgb = |color: Color| match color {
+            Color::Rgb { r, g, b } => Some([r, g, b]),
+            _ => None,
+        };
+
+        for (y, line_idx) in (start..(start + rows).min(self.line_info.len())).enumerate() {
+            let (line_start, line_len) = self.line_info[line_idx];
+            let row = &self.back[line_start];
+            for x in 0..width {
+                let col = x + self.h_offset;
+                let Some(cell) = row.get(col) else {
+                    break;
+                };
+                let ch = if cell.is_continuation() {
+                    ' '
+                } else if col < line_len {
+                    cell.shade.unwrap_or(cell.ch)
+                } else {
+                    cell.shade.unwrap_or(' ')
+                };
+                *frame.cell_mut(x, y) = FrameCell {
+                    ch,
+                    fg: rgb(cell.color),
+                    bg: cell.bg.and_then(rgb),
+                };
+            }
+        }
+
+        frame
+    }
+
     /// Resizes the buffer for new terminal dimensions while maintaining content.
     pub fn resize(&mut self, new_size: (u16, u16)) -> Result<(), RendererError> {
+        // Reprocess text for new dimensions
+        let text = self.original_text.clone();
+        self.resize_with_text(new_size, &text)
+    }
+
+    /// Resizes the buffer for new terminal dimensions, replacing its content.
+    pub fn resize_with_text(&mut self, new_size: (u16, u16), text: &str) -> Result<(), RendererError> {
         let new_width = new_size.0 as usize;
         let new_height = new_size.1 as usize;
 
@@ -436,11 +1345,7 @@ impl RenderBuffer {
         self.back = new_buffer;
         self.term_size = new_size;
 
-        // Reprocess text for new dimensions
-        let text = self.original_text.clone();
-        self.prepare_text(&text)?;
-
-        Ok(())
+        self.prepare_text(text)
     }
 
     /// Returns the maximum line length in the buffer
@@ -459,18 +1364,109 @@ impl RenderBuffer {
         self.line_info.len()
     }
 
-    // Add this method to manage buffer capacity
-    fn ensure_buffer_capacity(&mut self, required_lines: usize) {
-        let width = self.term_size.0 as usize;
-        let current_capacity = self.back.len();
+    /// Ensures the given row exists in both buffers and is at least `width` cells wide
+    fn ensure_row(&mut self, row: usize, width: usize) {
+        while row >= self.back.len() {
+            self.back.push(vec![BufferCell::default(); width]);
+            self.front.push(vec![BufferCell::default(); width]);
+        }
+
+        if self.back[row].len() < width {
+            self.back[row].resize(width, BufferCell::default());
+            self.front[row].resize(width, BufferCell::default());
+        }
+    }
+
+    /// Paints the text effect into the blank cells of a range of rows, using
+    /// the colors their neighboring glyphs were just given
+    fn apply_effect(&mut self, rows: std::ops::Range<usize>) {
+        if self.effect == TextEffect::None {
+            return;
+        }
+
+        let line_count = self.line_info.len();
+        for y in rows.start..rows.end.min(line_count) {
+            for x in 0..self.back[y].len() {
+                let cell = &self.back[y][x];
+                if !cell.is_blank() || cell.shade.is_some() {
+                    continue;
+                }
+
+                let back = &self.back;
+                let bg = self.effect.background(|dx, dy| {
+                    let nx = x.checked_add_signed(dx)?;
+                    let ny = y.checked_add_signed(dy).filter(|&ny| ny < line_count)?;
+                    back[ny]
+                        .get(nx)
+                        .filter(|cell| !cell.is_blank())
+                        .map(|cell| cell.color)
+                });
+
+                let cell = &mut self.back[y][x];
+                if cell.bg != bg {
+                    cell.bg = bg;
+                    cell.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Rebuilds the text mask from the glyphs of the laid-out rows
+    fn rebuild_mask(&mut self) {
+        let mode = self.mask.mode();
+        if mode == MaskMode::Off {
+            return;
+        }
+        let occupancy: Vec<Vec<bool>> = self.back[..self.line_info.len()]
+            .iter()
+            .map(|row| row.iter().map(|cell| !cell.is_blank()).collect())
+            .collect();
+        self.mask = TextMask::new(mode, &occupancy);
+    }
+
+    /// Marks every cell dirty so the next render redraws the full viewport,
+    /// e.g. after something was drawn over it
+    pub fn mark_all_dirty(&mut self) {
+        for cell in self.back.iter_mut().flatten() {
+            cell.dirty = true;
+        }
+        self.ruler_dirty = true;
+    }
+
+    /// Returns the line number of a row, blank if the row continues a
+    /// wrapped line, or None if there are no line numbers or the row is past
+    /// the last line
+    fn row_label(&self, row: usize) -> Option<String> {
+        if self.gutter_width == 0 {
+            return None;
+        }
+        let source = *self.line_sources.get(row)?;
+        let line = self.line_offset + source + 1;
+        if line > self.last_line {
+            return None;
+        }
+        let first_row = row == 0 || self.line_sources[row - 1] != source;
+        Some(gutter::label(first_row.then_some(line), self.gutter_width))
+    }
 
-        if required_lines > current_capacity {
-            // Grow by doubling, but not more than needed
-            let new_capacity = (current_capacity * 2).min(required_lines + 64);
-            self.back
-                .resize(new_capacity, vec![BufferCell::default(); width]);
-            self.front
-                .resize(new_capacity, vec![BufferCell::default(); width]);
+    /// Returns the ruler row over the visible columns, indented past the
+    /// line numbers
+    fn ruler_line(&self, colors_enabled: bool) -> Result<String, std::fmt::Error> {
+        let mut line = " ".repeat(self.gutter_width);
+        let ruler = gutter::ruler(self.h_offset, self.content_width());
+        push_gutter(&mut line, &ruler, colors_enabled, &mut None, self.color_support)?;
+        if colors_enabled {
+            line.push_str("\x1b[0m");
+        }
+        Ok(line)
+    }
+
+    /// Keeps the horizontal offset within the current content
+    fn clamp_h_offset(&mut self) {
+        if !self.wrap_mode.allows_horizontal_scroll() {
+            self.h_offset = 0;
+        } else {
+            self.h_offset = self.h_offset.min(self.max_h_offset());
         }
     }
 
@@ -480,6 +1476,54 @@ impl RenderBuffer {
     }
 }
 
+/// Appends gutter text in the gutter color, noting the color as the last one
+/// written
+fn push_gutter(
+    out: &mut String,
+    text: &str,
+    colors_enabled: bool,
+    last_color: &mut Option<Color>,
+    support: ColorSupport,
+) -> std::fmt::Result {
+    if colors_enabled && *last_color != Some(GUTTER_COLOR) {
+        if let Color::Rgb { r, g, b } = GUTTER_COLOR {
+            write!(out, "{}", support.fg(r, g, b))?;
+        }
+        *last_color = Some(GUTTER_COLOR);
+    }
+    out.push_str(text);
+    Ok(())
+}
+
+/// Returns the lightness value for a cell: the text mask strength when the
+/// engine's light map follows the mask, or the sampled secondary pattern
+#[inline]
+fn cell_light(engine: &PatternEngine, sampled: Option<f64>, strength: f64) -> Option<f64> {
+    match engine.light_map() {
+        Some(light) if light.uses_mask() => Some(strength),
+        _ => sampled,
+    }
+}
+
+/// Maps a pattern value through the engine's gradient, or the palette's, to
+/// a terminal color, shaded by the light map at `light` if there is one,
+/// with grain for the cell at `pos`
+#[inline]
+fn gradient_color(
+    engine: &PatternEngine,
+    palette: Option<&Palette>,
+    value: f64,
+    light: Option<f64>,
+    pos: (usize, usize),
+) -> Color {
+    let color = engine.cell_color_in(palette, value, light, pos.0, pos.1);
+    Color::Rgb {
+        r: (color.r * 255.0) as u8,
+        g: (color.g * 255.0) as u8,
+        b: (color.b * 255.0) as u8,
+    }
+}
+
 impl Default for RenderBuffer {
     fn default() -> Self {
         Self::new((80, 24)) // Default terminal size
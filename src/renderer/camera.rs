@@ -0,0 +1,200 @@
+//! Virtual canvas and the camera that views it
+//!
+//! With `--canvas SCALE` the pattern is computed on a canvas SCALE times the
+//! terminal size in each direction, and the terminal shows a window into it.
+//! The camera decides which part: its pan position moves the window across
+//! the canvas and its zoom changes how much of the canvas the window covers.
+//! Several terminals given different `--pan` positions together show one
+//! large pattern, and drift mode moves the camera slowly on its own.
+//!
+//! The camera works in normalized coordinates (-0.5 to 0.5 across the
+//! terminal or canvas), so it is independent of the terminal size.
+
+use std::f64::consts::TAU;
+
+/// Largest zoom factor
+pub const MAX_ZOOM: f64 = 8.0;
+
+/// Largest canvas scale
+pub const MAX_CANVAS_SCALE: f64 = 16.0;
+
+/// Fraction of the visible window moved by one pan step
+const PAN_STEP: f64 = 0.1;
+
+/// Zoom factor applied by one zoom step
+const ZOOM_STEP: f64 = 1.25;
+
+/// Seconds for the drifting camera to sweep across the canvas and back
+const DRIFT_PAN_PERIOD: f64 = 90.0;
+
+/// Seconds for one drifting zoom in and out
+const DRIFT_ZOOM_PERIOD: f64 = 50.0;
+
+/// How far drift mode zooms in beyond the set zoom
+const DRIFT_ZOOM_DEPTH: f64 = 0.5;
+
+/// A view into the virtual canvas
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    /// Canvas size as a multiple of the terminal size in each direction
+    canvas_scale: f64,
+    /// Magnification; 1.0 shows one terminal's worth of canvas
+    zoom: f64,
+    /// Position of the window across the pannable range, 0.0 to 1.0 per axis
+    pan: (f64, f64),
+    /// Whether the camera moves on its own
+    drift: bool,
+    /// Seconds of drift elapsed
+    drift_time: f64,
+}
+
+impl Camera {
+    /// Creates a camera over a canvas `canvas_scale` times the terminal
+    /// size, showing its center
+    pub fn new(canvas_scale: f64) -> Self {
+        Self {
+            canvas_scale: canvas_scale.clamp(1.0, MAX_CANVAS_SCALE),
+            zoom: 1.0,
+            pan: (0.5, 0.5),
+            drift: false,
+            drift_time: 0.0,
+        }
+    }
+
+    /// Sets the pan position, 0.0 to 1.0 across the pannable range per axis
+    pub fn with_pan(mut self, x: f64, y: f64) -> Self {
+        self.pan = (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets whether the camera drifts on its own
+    pub fn with_drift(mut self, drift: bool) -> Self {
+        self.drift = drift;
+        self
+    }
+
+    /// Returns the canvas size as a multiple of the terminal size
+    pub fn canvas_scale(&self) -> f64 {
+        self.canvas_scale
+    }
+
+    /// Returns the zoom factor
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Returns the pan position
+    pub fn pan(&self) -> (f64, f64) {
+        self.pan
+    }
+
+    /// Returns true if the camera moves on its own
+    pub fn is_drifting(&self) -> bool {
+        self.drift
+    }
+
+    /// Returns true if the terminal shows the pattern exactly as without a camera
+    pub fn is_identity(&self) -> bool {
+        self.view_fraction() == 1.0
+    }
+
+    /// Sets the zoom, between showing the whole canvas and [`MAX_ZOOM`]
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.clamp(1.0 / self.canvas_scale, MAX_ZOOM);
+    }
+
+    /// Zooms in (positive steps) or out (negative steps)
+    pub fn zoom_by(&mut self, steps: i32) {
+        self.set_zoom(self.zoom * ZOOM_STEP.powi(steps));
+    }
+
+    /// Moves the window by a number of pan steps along each axis
+    pub fn pan_by(&mut self, dx: i32, dy: i32) {
+        // A step is a fixed share of the window, whatever the zoom
+        let fraction = self.view_fraction();
+        let range = 1.0 - fraction;
+        if range <= f64::EPSILON {
+            return;
+        }
+        let step = PAN_STEP * fraction / range;
+        self.pan = (
+            (self.pan.0 + dx as f64 * step).clamp(0.0, 1.0),
+            (self.pan.1 + dy as f64 * step).clamp(0.0, 1.0),
+        );
+    }
+
+    /// Toggles drift mode
+    pub fn toggle_drift(&mut self) {
+        self.drift = !self.drift;
+    }
+
+    /// Returns to the initial zoom and centered pan
+    pub fn reset(&mut self) {
+        self.zoom = 1.0;
+        self.pan = (0.5, 0.5);
+        self.drift_time = 0.0;
+    }
+
+    /// Advances drift by the elapsed frame time
+    pub fn advance(&mut self, delta_seconds: f64) {
+        if self.drift {
+            self.drift_time += delta_seconds;
+        }
+    }
+
+    /// Maps a normalized terminal position to a normalized canvas position
+    #[inline]
+    pub fn map(&self, x: f64, y: f64) -> (f64, f64) {
+        let fraction = self.view_fraction();
+        let (pan_x, pan_y) = self.current_pan();
+        let range = 1.0 - fraction;
+        (
+            x * fraction + (pan_x - 0.5) * range,
+            y * fraction + (pan_y - 0.5) * range,
+        )
+    }
+
+    /// Share of the canvas width and height the window covers
+    #[inline]
+    fn view_fraction(&self) -> f64 {
+        let zoom = if self.drift {
+            let wave = 0.5 - 0.5 * (self.drift_time * TAU / DRIFT_ZOOM_PERIOD).cos();
+            (self.zoom * (1.0 + DRIFT_ZOOM_DEPTH * wave)).min(MAX_ZOOM)
+        } else {
+            self.zoom
+        };
+        1.0 / (self.canvas_scale * zoom)
+    }
+
+    /// Pan position, following a slow figure-eight while drifting
+    #[inline]
+    fn current_pan(&self) -> (f64, f64) {
+        if self.drift {
+            let phase = self.drift_time * TAU / DRIFT_PAN_PERIOD;
+            (0.5 + 0.5 * phase.sin(), 0.5 + 0.5 * (2.0 * phase).sin())
+        } else {
+            self.pan
+        }
+    }
+}
+
+/// Parses a pan position given as `X,Y`, each from 0.0 to 1.0
+pub fn parse_pan(s: &str) -> Result<(f64, f64), String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Pan position '{}' must be in format X,Y", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| (0.0..=1.0).contains(v))
+            .ok_or_else(|| format!("Pan coordinate '{}' must be a number from 0 to 1", v.trim()))
+    };
+    Ok((parse(x)?, parse(y)?))
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
@@ -0,0 +1,13 @@
+//! Copying text to the system clipboard through the terminal
+//!
+//! The text goes out in an OSC 52 escape sequence, which most terminal
+//! emulators, tmux with `set-clipboard on`, and terminals reached over SSH
+//! put on the clipboard of the machine the terminal runs on. Terminals
+//! without OSC 52 support ignore the sequence.
+
+use super::screenshot::base64;
+
+/// Returns the OSC 52 sequence that puts `text` on the clipboard
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
@@ -3,9 +3,22 @@
 //! This module defines configuration options for animation and rendering
 //! behavior, including frame rates, timing, and display options.
 
+use super::camera::Camera;
+use super::effects::TextEffect;
+use super::gutter::Gutter;
 use super::error::RendererError;
+use super::layout::WrapMode;
+use super::legend::LegendPosition;
+use super::mask::MaskMode;
+use super::profile::RenderProfile;
+use super::raster::Resolution;
+use super::regions::RegionLayout;
+use crate::pattern::{CharRamp, LoopMode, PatternEngine};
 use std::time::Duration;
 
+/// Pattern evaluations averaged per frame in smooth mode
+pub const SMOOTH_SAMPLES: usize = 4;
+
 /// Configuration for animation rendering
 #[derive(Debug, Clone)]
 pub struct AnimationConfig {
@@ -17,8 +30,42 @@ pub struct AnimationConfig {
     pub infinite: bool,
     /// Whether to show animation progress bar
     pub show_progress: bool,
-    /// Enable smooth transitions between frames
+    /// Length of the pattern's time cycle, if its time repeats
+    pub cycle: Option<Duration>,
+    /// What pattern time does at the end of a cycle
+    pub loop_mode: LoopMode,
+    /// Replay the pattern samples of each cycle from memory after the first
+    pub loop_cache: bool,
+    /// Blur pattern motion across each frame interval
     pub smooth: bool,
+    /// How lines wider than the terminal are handled
+    pub wrap_mode: WrapMode,
+    /// Character ramp for shading blank cells by pattern value
+    pub char_ramp: Option<CharRamp>,
+    /// Pattern samples drawn per blank cell
+    pub resolution: Resolution,
+    /// How the text masks the pattern
+    pub mask_mode: MaskMode,
+    /// Effect drawn around glyphs
+    pub effect: TextEffect,
+    /// Line numbers and ruler drawn around the text
+    pub gutter: Gutter,
+    /// View into the virtual canvas the pattern is computed on
+    pub camera: Camera,
+    /// Screen regions with their own patterns and themes
+    pub layout: Option<RegionLayout>,
+    /// Draw frame timings, transition state, and log events over the animation
+    pub debug_overlay: bool,
+    /// Corner to draw the gradient legend in, if it is shown from the start
+    pub legend: Option<LegendPosition>,
+    /// Rendering profile; without one, the minimal profile is picked once
+    /// frames keep running slow
+    pub profile: Option<RenderProfile>,
+    /// Percentage of one core to keep CPU usage under, if any
+    pub cpu_budget: Option<f64>,
+    /// Frames between ones drawing every row, when drawing alternate rows
+    /// in alternate frames
+    pub interlace: Option<u32>,
 }
 
 impl AnimationConfig {
@@ -33,7 +80,23 @@ impl AnimationConfig {
             cycle_duration: duration,
             infinite: duration.is_zero(),
             show_progress: true,
+            cycle: None,
+            loop_mode: LoopMode::default(),
+            loop_cache: false,
             smooth: false,
+            wrap_mode: WrapMode::default(),
+            char_ramp: None,
+            resolution: Resolution::default(),
+            mask_mode: MaskMode::default(),
+            effect: TextEffect::default(),
+            gutter: Gutter::default(),
+            camera: Camera::default(),
+            layout: None,
+            debug_overlay: false,
+            legend: None,
+            profile: None,
+            cpu_budget: None,
+            interlace: None,
         }
     }
 
@@ -42,6 +105,25 @@ impl AnimationConfig {
         Duration::from_nanos(1_000_000_000u64 / self.fps as u64)
     }
 
+    /// Returns the pattern evaluations averaged per frame, more than one
+    /// only in smooth mode
+    pub fn smoothing_samples(&self) -> usize {
+        if self.smooth {
+            SMOOTH_SAMPLES
+        } else {
+            1
+        }
+    }
+
+    /// Applies smooth mode to an engine, spreading its samples across one
+    /// frame interval
+    pub fn apply_smoothing(&self, engine: &mut PatternEngine) {
+        engine.set_smoothing(
+            self.smoothing_samples(),
+            self.frame_duration().as_secs_f64(),
+        );
+    }
+
     /// Validates configuration values
     ///
     /// # Returns
@@ -71,7 +153,23 @@ impl Default for AnimationConfig {
             cycle_duration: Duration::from_secs(5),
             infinite: false,
             show_progress: true,
+            cycle: None,
+            loop_mode: LoopMode::default(),
+            loop_cache: false,
             smooth: false,
+            wrap_mode: WrapMode::default(),
+            char_ramp: None,
+            resolution: Resolution::default(),
+            mask_mode: MaskMode::default(),
+            effect: TextEffect::default(),
+            gutter: Gutter::default(),
+            camera: Camera::default(),
+            layout: None,
+            debug_overlay: false,
+            legend: None,
+            profile: None,
+            cpu_budget: None,
+            interlace: None,
         }
     }
 }
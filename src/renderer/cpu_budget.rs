@@ -0,0 +1,145 @@
+//! Keeping the animation under a CPU usage target
+//!
+//! `--cpu-budget 20%` measures the CPU time ChromaCat uses, as a percentage
+//! of one core the way `top` shows it, and steps the animation down while it
+//! is over the budget: each step lowers the frame rate, and from the third
+//! step on pattern `complexity` is capped too. Once usage falls well under
+//! the budget the animation steps back up, so an always-on display takes
+//! what it needs and no more.
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::profile::MINIMAL_COMPLEXITY;
+
+/// Share of the frame rate kept at each step down, from none taken
+pub const BUDGET_STEPS: [f64; 5] = [1.0, 0.75, 0.5, 0.33, 0.25];
+
+/// First step at which pattern complexity is capped
+const COMPLEXITY_STEP: usize = 3;
+
+/// Share of the budget usage must fall under before stepping back up, so
+/// the animation doesn't flip between two steps
+const STEP_UP_BELOW: f64 = 0.6;
+
+/// Parses a CPU budget such as `20%` or `20`, as a percentage of one core
+pub fn parse_cpu_budget(s: &str) -> Result<f64, String> {
+    let percent = s
+        .trim()
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .map_err(|_| format!("CPU budget '{}' must be a percentage, like 20%", s))?;
+    if percent > 0.0 && percent.is_finite() {
+        Ok(percent)
+    } else {
+        Err(format!("CPU budget '{}' must be above 0%", s))
+    }
+}
+
+/// Measures the CPU time of this process
+struct CpuMeter {
+    /// sysinfo handle holding this process only
+    system: System,
+    /// This process
+    pid: Option<Pid>,
+}
+
+impl CpuMeter {
+    /// Starts measuring from now
+    fn new() -> Self {
+        let mut meter = Self {
+            system: System::new(),
+            pid: sysinfo::get_current_pid().ok(),
+        };
+        meter.sample();
+        meter
+    }
+
+    /// Returns the CPU usage since the last sample, as a percentage of one
+    /// core, if it can be measured
+    fn sample(&mut self) -> Option<f64> {
+        let pid = self.pid?;
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            false,
+            ProcessRefreshKind::nothing().with_cpu(),
+        );
+        self.system
+            .process(pid)
+            .map(|process| process.cpu_usage() as f64)
+    }
+}
+
+/// Steps the animation down and back up to keep CPU usage under a budget
+pub struct CpuBudget {
+    /// Percentage of one core to stay under
+    budget: f64,
+    /// Index into [`BUDGET_STEPS`]
+    step: usize,
+    /// Usage measured last
+    usage: Option<f64>,
+    /// Measures this process
+    meter: CpuMeter,
+}
+
+impl CpuBudget {
+    /// Keeps usage under `budget` percent of one core, starting with
+    /// nothing taken away
+    pub fn new(budget: f64) -> Self {
+        Self {
+            budget,
+            step: 0,
+            usage: None,
+            meter: CpuMeter::new(),
+        }
+    }
+
+    /// Returns the percentage of one core to stay under
+    pub fn budget(&self) -> f64 {
+        self.budget
+    }
+
+    /// Returns how many steps the animation has been taken down
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the usage measured last, as a percentage of one core
+    pub fn usage(&self) -> Option<f64> {
+        self.usage
+    }
+
+    /// Measures usage since the last call and steps accordingly, returning
+    /// whether the step changed
+    pub fn update(&mut self) -> bool {
+        match self.meter.sample() {
+            Some(usage) => self.adjust(usage),
+            None => false,
+        }
+    }
+
+    /// Steps down if `usage` is over the budget, or back up if it is well
+    /// under, returning whether the step changed
+    pub fn adjust(&mut self, usage: f64) -> bool {
+        self.usage = Some(usage);
+        let step = if usage > self.budget {
+            (self.step + 1).min(BUDGET_STEPS.len() - 1)
+        } else if usage < self.budget * STEP_UP_BELOW {
+            self.step.saturating_sub(1)
+        } else {
+            self.step
+        };
+        let changed = step != self.step;
+        self.step = step;
+        changed
+    }
+
+    /// Returns the frame rate to run at instead of `fps`
+    pub fn fps(&self, fps: u32) -> u32 {
+        ((fps as f64 * BUDGET_STEPS[self.step]).round() as u32).max(1)
+    }
+
+    /// Returns the highest pattern `complexity`, if it is capped
+    pub fn max_complexity(&self) -> Option<f64> {
+        (self.step >= COMPLEXITY_STEP).then_some(MINIMAL_COMPLEXITY)
+    }
+}
@@ -0,0 +1,181 @@
+//! Debug overlay drawn over the animation
+//!
+//! Shows where frame time goes, stage by stage, what the scene transitions
+//! are doing, and the latest log events, so stutters can be diagnosed while
+//! they happen. Like the favorites panel, it is drawn after the text on
+//! every frame.
+
+use crossterm::style::Color;
+use std::io::Write;
+use std::time::Duration;
+
+use super::error::RendererError;
+use super::favorites_panel::{ACCENT_COLOR, MUTED_COLOR, TEXT_COLOR};
+use super::panels::draw_lines;
+use super::regions::Rect;
+use crate::events::Event;
+
+/// Number of log events listed
+pub const DEBUG_EVENTS: usize = 8;
+
+/// Weight of the latest frame in the running averages
+const AVERAGE_WEIGHT: f64 = 0.1;
+
+/// Color of warnings and errors
+const WARNING_COLOR: Color = Color::Rgb {
+    r: 229,
+    g: 192,
+    b: 123,
+};
+
+/// Most transition states described
+const MAX_TRANSITIONS: usize = 3;
+
+/// Width and height of the panel: the frame total, the stages, the
+/// transitions, the events, and the key that closes it. The width leaves
+/// the text beside the panel in view.
+pub const DEBUG_PANEL_SIZE: (usize, usize) = (
+    64,
+    FrameStage::ALL.len() + MAX_TRANSITIONS + DEBUG_EVENTS + 2,
+);
+
+/// A stage of drawing an animated frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameStage {
+    /// Advancing the pattern clock, camera, regions, and demo art
+    Update,
+    /// Computing the colors of the visible cells
+    Colors,
+    /// Turning cells into escape sequences, with overlays and status bar
+    Draw,
+    /// Writing the frame to the terminal
+    Write,
+}
+
+impl FrameStage {
+    /// Every stage, in the order a frame goes through them
+    pub const ALL: [FrameStage; 4] = [
+        FrameStage::Update,
+        FrameStage::Colors,
+        FrameStage::Draw,
+        FrameStage::Write,
+    ];
+
+    /// Returns the stage's name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrameStage::Update => "update",
+            FrameStage::Colors => "colors",
+            FrameStage::Draw => "draw",
+            FrameStage::Write => "write",
+        }
+    }
+}
+
+/// Running timings of each frame stage
+#[derive(Debug, Clone, Default)]
+pub struct FrameTimings {
+    /// Running average of each stage, in seconds
+    average: [f64; 4],
+    /// Slowest time of each stage in the last full second
+    peak: [f64; 4],
+    /// Slowest time of each stage so far this second
+    window: [f64; 4],
+}
+
+impl FrameTimings {
+    /// Records how long a stage took in the latest frame
+    pub fn record(&mut self, stage: FrameStage, duration: Duration) {
+        let i = stage as usize;
+        let seconds = duration.as_secs_f64();
+        let average = self.average[i];
+        self.average[i] = if average > 0.0 {
+            average + (seconds - average) * AVERAGE_WEIGHT
+        } else {
+            seconds
+        };
+        self.window[i] = self.window[i].max(seconds);
+    }
+
+    /// Starts a new second of peak tracking, keeping the one just ended
+    pub fn roll(&mut self) {
+        self.peak = std::mem::take(&mut self.window);
+    }
+
+    /// Returns the running average time of a stage
+    pub fn average(&self, stage: FrameStage) -> Duration {
+        Duration::from_secs_f64(self.average[stage as usize])
+    }
+
+    /// Returns the slowest time of a stage in the last full second
+    pub fn peak(&self, stage: FrameStage) -> Duration {
+        Duration::from_secs_f64(self.peak[stage as usize])
+    }
+
+    /// Returns the running average time of a whole frame
+    pub fn total(&self) -> Duration {
+        Duration::from_secs_f64(self.average.iter().sum())
+    }
+}
+
+/// Draws the overlay in `area`: timings measured against the frame budget,
+/// one line per transition state, and the latest events
+pub fn render_debug_overlay<W: Write>(
+    out: &mut W,
+    timings: &FrameTimings,
+    budget: Duration,
+    transitions: &[String],
+    events: &[Event],
+    area: Rect,
+) -> Result<(), RendererError> {
+    // The panel keeps one size, so nothing it drew on an earlier frame is
+    // left showing around it
+    if area.width < 16 || area.height < 3 {
+        return Ok(());
+    }
+
+    let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+    let total = timings.total();
+    let total_color = if total > budget { WARNING_COLOR } else { ACCENT_COLOR };
+    let mut lines: Vec<(String, Color)> = vec![(
+        format!(" Frame {:5.2}ms of {:.2}ms", millis(total), millis(budget)),
+        total_color,
+    )];
+    for stage in FrameStage::ALL {
+        lines.push((
+            format!(
+                " {:<7}{:5.2}ms  peak {:5.2}ms",
+                stage.as_str(),
+                millis(timings.average(stage)),
+                millis(timings.peak(stage)),
+            ),
+            TEXT_COLOR,
+        ));
+    }
+    for row in 0..MAX_TRANSITIONS {
+        let transition = transitions.get(row).map(String::as_str).unwrap_or_default();
+        lines.push((format!(" {}", transition), ACCENT_COLOR));
+    }
+    for _ in events.len()..DEBUG_EVENTS {
+        lines.push((String::new(), MUTED_COLOR));
+    }
+    for event in events.iter().take(DEBUG_EVENTS) {
+        let module = event.target.rsplit("::").next().unwrap_or_default();
+        let color = match event.level {
+            log::Level::Error | log::Level::Warn => WARNING_COLOR,
+            _ => MUTED_COLOR,
+        };
+        lines.push((
+            format!(
+                " {:8.3} {:<5} {}: {}",
+                event.elapsed.as_secs_f64(),
+                event.level,
+                module,
+                event.message.replace(char::is_control, " ")
+            ),
+            color,
+        ));
+    }
+    lines.push((" F12 close".to_string(), MUTED_COLOR));
+    draw_lines(out, &lines, area)
+}
@@ -0,0 +1,137 @@
+//! Text effects drawn around glyphs
+//!
+//! Effects are applied after coloring. They paint the background of blank
+//! cells next to glyphs in a shade of the neighboring glyph's color: a drop
+//! shadow one cell down and to the right, an outline around every glyph, or
+//! a soft glow that fades with distance. Cells drawn by a character ramp or
+//! at sub-cell resolution are left alone.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::style::Color;
+
+/// Brightness of a drop shadow relative to the glyph casting it
+const SHADOW_SHADE: f32 = 0.25;
+/// Brightness of an outline relative to the glyph it surrounds
+const OUTLINE_SHADE: f32 = 0.45;
+/// Glow brightness by distance from the nearest glyph, starting at one cell
+const GLOW_SHADES: [f32; 2] = [0.4, 0.15];
+
+/// An effect drawn around glyphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEffect {
+    /// No effect
+    #[default]
+    None,
+    /// A darker copy of each glyph one cell down and to the right
+    Shadow,
+    /// A darker band in every blank cell touching a glyph
+    Outline,
+    /// Blank cells near glyphs lit in the glyph's color, fading with distance
+    Glow,
+}
+
+impl TextEffect {
+    /// Returns a list of all text effects
+    pub fn all() -> &'static [TextEffect] {
+        &[
+            TextEffect::None,
+            TextEffect::Shadow,
+            TextEffect::Outline,
+            TextEffect::Glow,
+        ]
+    }
+
+    /// Get string representation of the effect
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextEffect::None => "none",
+            TextEffect::Shadow => "shadow",
+            TextEffect::Outline => "outline",
+            TextEffect::Glow => "glow",
+        }
+    }
+
+    /// Returns the background of a blank cell, given a lookup of the color of
+    /// the glyph at an offset (dx, dy) from it, if there is a glyph there
+    #[inline]
+    pub fn background<F>(&self, glyph_at: F) -> Option<Color>
+    where
+        F: Fn(isize, isize) -> Option<Color>,
+    {
+        match self {
+            TextEffect::None => None,
+            TextEffect::Shadow => glyph_at(-1, -1).and_then(|c| scale(c, SHADOW_SHADE)),
+            TextEffect::Outline => NEIGHBORS
+                .iter()
+                .find_map(|&(dx, dy)| glyph_at(dx, dy))
+                .and_then(|c| scale(c, OUTLINE_SHADE)),
+            TextEffect::Glow => {
+                // Cells are about twice as tall as wide, so the glow reaches
+                // up to two columns but only one row
+                for (distance, shade) in GLOW_SHADES.iter().enumerate() {
+                    let reach = distance as isize + 1;
+                    let color = (-1..=1)
+                        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+                        .find_map(|(dx, dy)| glyph_at(dx, dy));
+                    if let Some(color) = color {
+                        return scale(color, *shade);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Offsets of the eight cells around a cell
+const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Scales an RGB color's brightness; other colors have no shade
+#[inline]
+fn scale(color: Color, factor: f32) -> Option<Color> {
+    match color {
+        Color::Rgb { r, g, b } => {
+            let channel = |c: u8| (c as f32 * factor).round() as u8;
+            Some(Color::Rgb {
+                r: channel(r),
+                g: channel(g),
+                b: channel(b),
+            })
+        }
+        _ => None,
+    }
+}
+
+impl fmt::Display for TextEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for TextEffect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "off" => Ok(TextEffect::None),
+            "shadow" => Ok(TextEffect::Shadow),
+            "outline" => Ok(TextEffect::Outline),
+            "glow" => Ok(TextEffect::Glow),
+            _ => Err(format!(
+                "Invalid effect '{}'. Valid effects: none, shadow, outline, glow",
+                s
+            )),
+        }
+    }
+}
@@ -56,6 +56,8 @@ impl From<ChromaCatError> for RendererError {
             ChromaCatError::PlaylistError(msg) => Self::Other(format!("Playlist error: {}", msg)),
             ChromaCatError::Other(msg) => Self::Other(msg),
             ChromaCatError::InvalidArt(msg) => Self::Other(format!("Invalid art type: {}", msg)),
+            ChromaCatError::UsageError(msg) => Self::Other(msg),
+            ChromaCatError::TerminalError(msg) => Self::TerminalError(msg),
         }
     }
 }
@@ -0,0 +1,110 @@
+//! Error handling for the animation event loop
+//!
+//! A long unattended show shouldn't end because one scene names a theme
+//! that failed to load. Errors raised while the loop runs are reported here,
+//! and the policy decides what happens next: retry the failed step on a
+//! later frame, skip past it and keep the current scene on screen, or give
+//! up when the same step keeps failing and there is nothing left to show.
+//! The renderer tells the viewer about each recovered error with a toast in
+//! the status bar.
+
+use std::fmt;
+
+/// Times a failed scene change is retried before the scene is skipped
+pub const TRANSITION_RETRIES: u32 = 2;
+
+/// Frames in a row that may fail before the loop gives up; a frame that
+/// can't be drawn this many times over means the terminal is gone
+pub const MAX_FRAME_FAILURES: u32 = 60;
+
+/// Step of the event loop an error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopStage {
+    /// Switching to another playlist scene
+    Transition,
+    /// Computing or drawing a frame
+    Frame,
+    /// Handling a key press or a mouse event
+    Key,
+    /// Adapting to a new terminal size
+    Resize,
+}
+
+impl LoopStage {
+    /// Get string representation of the stage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopStage::Transition => "scene change",
+            LoopStage::Frame => "frame",
+            LoopStage::Key => "key",
+            LoopStage::Resize => "resize",
+        }
+    }
+}
+
+impl fmt::Display for LoopStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// What the event loop does after an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Try the failed step again on the next frame
+    Retry,
+    /// Move past the failed step, keeping what is on screen
+    Skip,
+    /// Stop the loop and report the error
+    Abort,
+}
+
+/// Decides how the event loop recovers from errors
+#[derive(Debug, Clone, Default)]
+pub struct ErrorPolicy {
+    /// Failed attempts at the pending scene change
+    transition_failures: u32,
+    /// Frames in a row that failed
+    frame_failures: u32,
+}
+
+impl ErrorPolicy {
+    /// Creates a policy with no failures recorded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a failure in `stage` and returns how to recover from it
+    pub fn on_error(&mut self, stage: LoopStage) -> Recovery {
+        match stage {
+            LoopStage::Transition => {
+                self.transition_failures += 1;
+                if self.transition_failures > TRANSITION_RETRIES {
+                    self.transition_failures = 0;
+                    Recovery::Skip
+                } else {
+                    Recovery::Retry
+                }
+            }
+            LoopStage::Frame => {
+                self.frame_failures += 1;
+                if self.frame_failures >= MAX_FRAME_FAILURES {
+                    Recovery::Abort
+                } else {
+                    Recovery::Skip
+                }
+            }
+            // A failed key press or resize leaves the display as it was
+            LoopStage::Key | LoopStage::Resize => Recovery::Skip,
+        }
+    }
+
+    /// Records that `stage` succeeded, clearing its failures
+    pub fn on_success(&mut self, stage: LoopStage) {
+        match stage {
+            LoopStage::Transition => self.transition_failures = 0,
+            LoopStage::Frame => self.frame_failures = 0,
+            LoopStage::Key | LoopStage::Resize => {}
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Favorites panel drawn over the animation
+//!
+//! Lists the bookmarked combinations with the number that selects each one.
+//! The panel is drawn after the text on every frame, so the animation keeps
+//! running underneath it.
+
+use crossterm::style::Color;
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+use super::error::RendererError;
+use super::panels::draw_lines;
+use super::regions::Rect;
+use crate::playlist::PlaylistEntry;
+
+/// Number of favorites that can be picked with the number keys
+pub const MAX_LISTED_FAVORITES: usize = 9;
+
+/// Panel background
+pub(super) const PANEL_BACKGROUND: Color = Color::Rgb {
+    r: 40,
+    g: 44,
+    b: 52,
+};
+
+/// Title and number color
+pub(super) const ACCENT_COLOR: Color = Color::Rgb {
+    r: 97,
+    g: 175,
+    b: 239,
+};
+
+/// Entry color
+pub(super) const TEXT_COLOR: Color = Color::Rgb {
+    r: 171,
+    g: 178,
+    b: 191,
+};
+
+/// Hint and parameter color
+pub(super) const MUTED_COLOR: Color = Color::Rgb {
+    r: 92,
+    g: 99,
+    b: 112,
+};
+
+/// Describes a favorite on one line: pattern, theme, art, and parameters
+pub fn describe_favorite(entry: &PlaylistEntry) -> String {
+    let mut line = format!("{} · {}", entry.pattern, entry.theme);
+    if let Some(art) = entry.art {
+        line.push_str(&format!(" · {}", art.as_str()));
+    }
+    if let Some(serde_yaml::Value::Mapping(params)) = &entry.params {
+        let params: Vec<String> = params
+            .iter()
+            .filter_map(|(key, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    _ => return None,
+                };
+                Some(format!("{}={}", key.as_str()?, value))
+            })
+            .collect();
+        if !params.is_empty() {
+            line.push_str(&format!("  {}", params.join(",")));
+        }
+    }
+    line
+}
+
+/// Lists the favorites with their numbers, under a title and above the
+/// keys that work on them
+fn panel_lines(favorites: &[PlaylistEntry]) -> Vec<(String, Color)> {
+    let mut lines: Vec<(String, Color)> = vec![(" Favorites".to_string(), ACCENT_COLOR)];
+    if favorites.is_empty() {
+        lines.push((" Nothing bookmarked yet".to_string(), TEXT_COLOR));
+    }
+    for (index, entry) in favorites.iter().take(MAX_LISTED_FAVORITES).enumerate() {
+        lines.push((format!(" {}  {}", index + 1, describe_favorite(entry)), TEXT_COLOR));
+    }
+    if favorites.len() > MAX_LISTED_FAVORITES {
+        lines.push((
+            format!(" … {} more", favorites.len() - MAX_LISTED_FAVORITES),
+            MUTED_COLOR,
+        ));
+    }
+    lines.push((" B bookmark · 1-9 select · F close".to_string(), MUTED_COLOR));
+    lines
+}
+
+/// Returns the width and height the panel takes to list `favorites`
+pub fn favorites_panel_size(favorites: &[PlaylistEntry]) -> (usize, usize) {
+    let lines = panel_lines(favorites);
+    let width = lines
+        .iter()
+        .map(|(line, _)| UnicodeWidthStr::width(line.as_str()) + 1)
+        .max()
+        .unwrap_or(0);
+    (width, lines.len())
+}
+
+/// Draws the panel in `area`
+pub fn render_favorites_panel<W: Write>(
+    out: &mut W,
+    favorites: &[PlaylistEntry],
+    area: Rect,
+) -> Result<(), RendererError> {
+    if area.width < 8 || area.height < 3 {
+        return Ok(());
+    }
+    draw_lines(out, &panel_lines(favorites), area)
+}
+
+/// Truncates or pads `line` to exactly `width` columns
+pub(super) fn fit(line: &str, width: usize) -> String {
+    let mut fitted = String::with_capacity(line.len());
+    let mut used = 0;
+    for ch in line.chars() {
+        let ch_width = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+        if used + ch_width > width {
+            break;
+        }
+        fitted.push(ch);
+        used += ch_width;
+    }
+    fitted.push_str(&" ".repeat(width - used));
+    fitted
+}
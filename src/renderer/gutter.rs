@@ -0,0 +1,81 @@
+//! Line-number gutter and column ruler
+//!
+//! The gutter sits left of the text and numbers each input line, leaving
+//! the rows that continue a wrapped line blank. The ruler is a row above the
+//! text marking every fifth and tenth column. Both are drawn in a fixed dim
+//! color instead of the pattern, and neither takes part in it: the text is
+//! laid out and colored in the columns that are left, so the gradient over
+//! the text looks the same with or without them.
+
+use crossterm::style::Color;
+
+/// Color of line numbers and the ruler
+pub const GUTTER_COLOR: Color = Color::Rgb {
+    r: 110,
+    g: 110,
+    b: 120,
+};
+
+/// Fewest digits line numbers are padded to
+const MIN_DIGITS: usize = 3;
+
+/// Separates line numbers from the text
+const SEPARATOR: &str = " │ ";
+
+/// Columns the separator takes
+const SEPARATOR_WIDTH: usize = 3;
+
+/// Line numbers and ruler drawn around the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Gutter {
+    /// Whether input lines are numbered
+    pub line_numbers: bool,
+    /// Whether a column ruler heads the text
+    pub ruler: bool,
+}
+
+impl Gutter {
+    /// Creates a gutter with line numbers and a ruler as given
+    pub fn new(line_numbers: bool, ruler: bool) -> Self {
+        Self {
+            line_numbers,
+            ruler,
+        }
+    }
+
+    /// Returns the columns line numbers take when the highest is `last_line`
+    pub fn width(&self, last_line: usize) -> usize {
+        if !self.line_numbers {
+            return 0;
+        }
+        last_line.to_string().len().max(MIN_DIGITS) + SEPARATOR_WIDTH
+    }
+
+    /// Returns the rows the ruler takes above the text
+    pub fn header_rows(&self) -> usize {
+        usize::from(self.ruler)
+    }
+}
+
+/// Formats the gutter of a row `width` columns wide: the line number, or
+/// blank for rows that continue a wrapped line
+pub fn label(line: Option<usize>, width: usize) -> String {
+    let digits = width.saturating_sub(SEPARATOR_WIDTH);
+    match line {
+        Some(line) => format!("{:>digits$}{}", line, SEPARATOR),
+        None => format!("{:digits$}{}", "", SEPARATOR),
+    }
+}
+
+/// Formats a ruler `width` columns wide whose first column is column
+/// `first` of the text, counting from zero: a `+` every fifth column and the
+/// tens digit every tenth
+pub fn ruler(first: usize, width: usize) -> String {
+    (first + 1..=first + width)
+        .map(|column| match column % 10 {
+            0 => char::from_digit((column / 10 % 10) as u32, 10).unwrap_or('|'),
+            5 => '+',
+            _ => '-',
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+//! Parameter inspection: learning what each parameter does
+//!
+//! Inspecting a parameter sweeps it smoothly from the bottom of its range to
+//! the top and back over a few seconds while everything else holds still,
+//! so the parameter's effect on the picture is easy to see. The sweep is a
+//! temporary override: once it ends, the parameter returns to the value it
+//! had before.
+
+use std::f64::consts::TAU;
+
+use crate::pattern::{ParamType, REGISTRY};
+
+/// Seconds one sweep across a parameter's range and back takes
+pub const SWEEP_SECONDS: f64 = 4.0;
+
+/// A parameter being swept across its range
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSweep {
+    /// Pattern the parameter belongs to
+    pattern: String,
+    /// The pattern's parameters when the sweep started
+    base: String,
+    /// Parameter being swept
+    name: &'static str,
+    /// What the parameter does
+    description: &'static str,
+    /// Bottom of the parameter's range
+    min: f64,
+    /// Top of the parameter's range
+    max: f64,
+    /// Whether the parameter takes whole numbers
+    whole: bool,
+    /// Seconds swept so far
+    elapsed: f64,
+}
+
+impl ParamSweep {
+    /// Starts sweeping the first numeric parameter of `pattern` after the
+    /// one named `after`, or the first one when `after` is `None`; `base`
+    /// holds the pattern's current parameters. Returns `None` when there
+    /// are no numeric parameters left.
+    pub fn next(pattern: &str, base: &str, after: Option<&str>) -> Option<Self> {
+        let metadata = REGISTRY.get_pattern(pattern)?;
+        let params = metadata.params().sub_params();
+        let start = match after {
+            Some(name) => params.iter().position(|param| param.name() == name)? + 1,
+            None => 0,
+        };
+
+        params
+            .iter()
+            .skip(start)
+            .find_map(|param| match param.param_type() {
+                ParamType::Number { min, max } if max > min => {
+                    let default = param.default_value().parse().unwrap_or(0.5);
+                    Some(Self {
+                        pattern: pattern.to_string(),
+                        base: base.to_string(),
+                        name: param.name(),
+                        description: param.description(),
+                        min,
+                        max,
+                        whole: [min, max, default].iter().all(|v: &f64| v.fract() == 0.0),
+                        elapsed: 0.0,
+                    })
+                }
+                _ => None,
+            })
+    }
+
+    /// Returns the pattern the parameter belongs to
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the name of the parameter being swept
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns what the parameter does
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Returns the parameter's range
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    /// Moves the sweep on by `delta_seconds`; returns false once it is over
+    pub fn advance(&mut self, delta_seconds: f64) -> bool {
+        self.elapsed += delta_seconds.max(0.0);
+        !self.is_finished()
+    }
+
+    /// Returns whether the sweep is over
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= SWEEP_SECONDS
+    }
+
+    /// Returns the swept value, easing out of the bottom of the range, up to
+    /// the top, and back down; parameters taking whole numbers step
+    pub fn value(&self) -> f64 {
+        let progress = (self.elapsed / SWEEP_SECONDS).clamp(0.0, 1.0);
+        let eased = (1.0 - (progress * TAU).cos()) / 2.0;
+        let value = self.min + (self.max - self.min) * eased;
+        if self.whole {
+            value.round()
+        } else {
+            value
+        }
+    }
+
+    /// Returns the pattern's parameters with the swept value in place
+    pub fn params(&self) -> String {
+        let value = format!("{}={}", self.name, self.value());
+        let mut params: Vec<String> = self
+            .base
+            .split(',')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if name == self.name => value.clone(),
+                _ => pair.to_string(),
+            })
+            .collect();
+        if !params.contains(&value) {
+            params.push(value);
+        }
+        params.join(",")
+    }
+
+    /// Returns the pattern's parameters from before the sweep
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+}
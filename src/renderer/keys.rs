@@ -0,0 +1,462 @@
+//! The keys the interactive viewer answers to
+//!
+//! Every key binding is listed once in [`KEY_BINDINGS`]. Key presses are
+//! looked up there before they are handled, and the help panel `?` opens is
+//! drawn from the same list, so the help always shows the keys as they are.
+//! The bindings are built in; there is no way to remap them.
+//! A key can be bound more than once: the first binding that applies in the
+//! viewer's current state handles it, so `Esc` stops a parameter sweep,
+//! then closes the favorites, and otherwise quits.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Color;
+use std::io::Write;
+
+use super::error::RendererError;
+use super::favorites_panel::{ACCENT_COLOR, MUTED_COLOR, TEXT_COLOR};
+use super::panels::draw_lines;
+use super::regions::Rect;
+
+/// Width of the help panel
+pub const HELP_PANEL_WIDTH: usize = 58;
+
+/// Width of the key column in the help panel
+const KEY_COLUMN_WIDTH: usize = 14;
+
+/// What a key does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Pauses or resumes the animation
+    Pause,
+    /// Steps one frame forward or back
+    StepFrame,
+    /// Scrubs the animation forward or back
+    Scrub,
+    /// Changes the animation speed
+    Speed,
+    /// Switches to the next theme
+    NextTheme,
+    /// Switches to the next pattern
+    NextPattern,
+    /// Draws new noise for noise-based patterns
+    Reseed,
+    /// Switches to a random pattern and theme
+    Surprise,
+    /// Switches to the pattern's next preset
+    NextPreset,
+    /// Sweeps the next pattern parameter
+    InspectParam,
+    /// Stops sweeping a parameter
+    StopInspecting,
+    /// Saves a screenshot
+    Screenshot,
+    /// Copies the command line for what is on screen
+    CopyCommand,
+    /// Pans the camera over the canvas
+    PanCamera,
+    /// Zooms the camera
+    ZoomCamera,
+    /// Resets the camera
+    ResetCamera,
+    /// Turns camera drift on or off
+    ToggleDrift,
+    /// Pans the pattern
+    PanPattern,
+    /// Zooms the pattern
+    ZoomPattern,
+    /// Resets the pattern zoom and pan
+    ResetPattern,
+    /// Turns film grain on or off
+    ToggleGrain,
+    /// Bookmarks what is on screen
+    Bookmark,
+    /// Opens or closes the favorites
+    ToggleFavorites,
+    /// Loads a favorite from the open favorites
+    PickFavorite,
+    /// Closes the favorites
+    CloseFavorites,
+    /// Shows or hides the gradient legend
+    ToggleLegend,
+    /// Shows or hides the message history
+    ToggleHistory,
+    /// Moves the open panels to the next corner
+    MovePanels,
+    /// Shows or hides the debug overlay
+    ToggleDebug,
+    /// Scrolls long lines sideways
+    ScrollSideways,
+    /// Goes to the next or previous playlist scene
+    ChangeScene,
+    /// Scrolls the text
+    Scroll,
+    /// Opens the search prompt
+    Search,
+    /// Jumps to the next or previous search match
+    NextMatch,
+    /// Opens the quick switcher
+    QuickSwitch,
+    /// Opens or closes this help
+    Help,
+    /// Quits
+    Quit,
+}
+
+/// One or more keys and what they do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// Keys that trigger the action
+    pub keys: &'static [KeyCode],
+    /// Whether Shift must be held
+    pub shift: bool,
+    /// How the keys are written in the help
+    pub label: &'static str,
+    /// What the keys do
+    pub action: KeyAction,
+    /// What the help says the keys do
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    /// Returns whether `key` triggers this binding. Other modifiers are
+    /// ignored, as terminals differ in what they report with letters.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        self.keys.contains(&key.code)
+            && (!self.shift || key.modifiers.contains(KeyModifiers::SHIFT))
+    }
+}
+
+const fn bind(
+    keys: &'static [KeyCode],
+    label: &'static str,
+    action: KeyAction,
+    description: &'static str,
+) -> KeyBinding {
+    KeyBinding {
+        keys,
+        shift: false,
+        label,
+        action,
+        description,
+    }
+}
+
+/// Every key binding, in the order they are tried and listed in the help
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    bind(
+        &[KeyCode::Char(' ')],
+        "Space",
+        KeyAction::Pause,
+        "Pause or resume",
+    ),
+    bind(
+        &[KeyCode::Char('.'), KeyCode::Char(',')],
+        ". ,",
+        KeyAction::StepFrame,
+        "Step one frame forward or back",
+    ),
+    bind(
+        &[KeyCode::Char(']'), KeyCode::Char('[')],
+        "] [",
+        KeyAction::Scrub,
+        "Scrub forward or back",
+    ),
+    bind(
+        &[KeyCode::Char('+'), KeyCode::Char('='), KeyCode::Char('-')],
+        "+ -",
+        KeyAction::Speed,
+        "Speed up or slow down",
+    ),
+    bind(
+        &[KeyCode::Char('t'), KeyCode::Char('T')],
+        "T",
+        KeyAction::NextTheme,
+        "Next theme",
+    ),
+    bind(
+        &[KeyCode::Char('p'), KeyCode::Char('P')],
+        "P",
+        KeyAction::NextPattern,
+        "Next pattern",
+    ),
+    bind(
+        &[KeyCode::Char('r')],
+        "r",
+        KeyAction::Reseed,
+        "New noise, for noise-based patterns",
+    ),
+    bind(
+        &[KeyCode::Char('R')],
+        "R",
+        KeyAction::Surprise,
+        "Random pattern and theme",
+    ),
+    bind(
+        &[KeyCode::Char('v'), KeyCode::Char('V')],
+        "V",
+        KeyAction::NextPreset,
+        "Next preset of the pattern",
+    ),
+    bind(
+        &[KeyCode::Char('i'), KeyCode::Char('I')],
+        "I",
+        KeyAction::InspectParam,
+        "Sweep the next parameter",
+    ),
+    bind(
+        &[KeyCode::Esc],
+        "Esc",
+        KeyAction::StopInspecting,
+        "Stop sweeping the parameter",
+    ),
+    bind(
+        &[KeyCode::Char('s'), KeyCode::Char('S')],
+        "S",
+        KeyAction::Screenshot,
+        "Save a screenshot",
+    ),
+    bind(
+        &[KeyCode::Char('y'), KeyCode::Char('Y')],
+        "Y",
+        KeyAction::CopyCommand,
+        "Copy the command line for what is on screen",
+    ),
+    bind(
+        &[
+            KeyCode::Char('h'),
+            KeyCode::Char('j'),
+            KeyCode::Char('k'),
+            KeyCode::Char('l'),
+        ],
+        "h j k l",
+        KeyAction::PanCamera,
+        "Pan the camera",
+    ),
+    bind(
+        &[KeyCode::Char('z'), KeyCode::Char('x')],
+        "z x",
+        KeyAction::ZoomCamera,
+        "Zoom the camera in or out",
+    ),
+    bind(
+        &[KeyCode::Char('0')],
+        "0",
+        KeyAction::ResetCamera,
+        "Reset the camera",
+    ),
+    bind(
+        &[KeyCode::Char('d'), KeyCode::Char('D')],
+        "D",
+        KeyAction::ToggleDrift,
+        "Camera drift on or off",
+    ),
+    KeyBinding {
+        keys: &[KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down],
+        shift: true,
+        label: "Shift+arrows",
+        action: KeyAction::PanPattern,
+        description: "Pan the pattern",
+    },
+    bind(
+        &[KeyCode::Char('>'), KeyCode::Char('<')],
+        "> <",
+        KeyAction::ZoomPattern,
+        "Zoom the pattern in or out",
+    ),
+    bind(
+        &[KeyCode::Char(')')],
+        ")",
+        KeyAction::ResetPattern,
+        "Reset the pattern zoom and pan",
+    ),
+    bind(
+        &[KeyCode::Char('g'), KeyCode::Char('G')],
+        "G",
+        KeyAction::ToggleGrain,
+        "Film grain on or off",
+    ),
+    bind(
+        &[KeyCode::Char('b'), KeyCode::Char('B')],
+        "B",
+        KeyAction::Bookmark,
+        "Bookmark what is on screen",
+    ),
+    bind(
+        &[KeyCode::Char('f'), KeyCode::Char('F')],
+        "F",
+        KeyAction::ToggleFavorites,
+        "Open or close the favorites",
+    ),
+    bind(
+        &[
+            KeyCode::Char('1'),
+            KeyCode::Char('2'),
+            KeyCode::Char('3'),
+            KeyCode::Char('4'),
+            KeyCode::Char('5'),
+            KeyCode::Char('6'),
+            KeyCode::Char('7'),
+            KeyCode::Char('8'),
+            KeyCode::Char('9'),
+        ],
+        "1-9",
+        KeyAction::PickFavorite,
+        "Load a favorite, while they are open",
+    ),
+    bind(
+        &[KeyCode::Esc],
+        "Esc",
+        KeyAction::CloseFavorites,
+        "Close the favorites",
+    ),
+    bind(
+        &[KeyCode::Char('m'), KeyCode::Char('M')],
+        "M",
+        KeyAction::ToggleLegend,
+        "Show or hide the gradient legend",
+    ),
+    bind(
+        &[KeyCode::Char('e'), KeyCode::Char('E')],
+        "E",
+        KeyAction::ToggleHistory,
+        "Show or hide the latest messages",
+    ),
+    bind(
+        &[KeyCode::Char('o'), KeyCode::Char('O')],
+        "O",
+        KeyAction::MovePanels,
+        "Move the open panels to the next corner",
+    ),
+    bind(
+        &[KeyCode::F(12)],
+        "F12",
+        KeyAction::ToggleDebug,
+        "Show or hide the debug overlay",
+    ),
+    // Horizontal scrolling takes precedence over playlist navigation
+    bind(
+        &[KeyCode::Right, KeyCode::Left],
+        "→ ←",
+        KeyAction::ScrollSideways,
+        "Scroll long lines sideways",
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Left],
+        "→ ←",
+        KeyAction::ChangeScene,
+        "Next or previous playlist scene",
+    ),
+    bind(
+        &[
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::PageUp,
+            KeyCode::PageDown,
+            KeyCode::Home,
+            KeyCode::End,
+        ],
+        "↑ ↓ PgUp PgDn",
+        KeyAction::Scroll,
+        "Scroll the text",
+    ),
+    bind(
+        &[KeyCode::Char('/')],
+        "/",
+        KeyAction::Search,
+        "Search the text",
+    ),
+    bind(
+        &[KeyCode::Char('n'), KeyCode::Char('N')],
+        "n N",
+        KeyAction::NextMatch,
+        "Next or previous search match",
+    ),
+    bind(
+        &[KeyCode::Char(':')],
+        ":",
+        KeyAction::QuickSwitch,
+        "Switch pattern, theme or art by name",
+    ),
+    bind(
+        &[KeyCode::Char('?')],
+        "?",
+        KeyAction::Help,
+        "Show or hide this help",
+    ),
+    bind(
+        &[KeyCode::Char('q'), KeyCode::Esc],
+        "q Esc",
+        KeyAction::Quit,
+        "Quit",
+    ),
+];
+
+/// Returns the actions bound to `key`, in the order they are tried
+pub fn actions_for(key: &KeyEvent) -> impl Iterator<Item = KeyAction> + '_ {
+    KEY_BINDINGS
+        .iter()
+        .filter(|binding| binding.matches(key))
+        .map(|binding| binding.action)
+}
+
+/// Returns the help's lines, one per key binding
+pub fn help_lines() -> Vec<String> {
+    KEY_BINDINGS
+        .iter()
+        .map(|binding| {
+            format!(
+                " {:<width$}{}",
+                binding.label,
+                binding.description,
+                width = KEY_COLUMN_WIDTH
+            )
+        })
+        .collect()
+}
+
+/// Returns the width and height the help panel wants, enough to show every
+/// binding at once
+pub fn help_panel_size() -> (usize, usize) {
+    (HELP_PANEL_WIDTH, KEY_BINDINGS.len() + 2)
+}
+
+/// Returns the furthest the help can be scrolled in a panel `rows` high
+pub fn max_help_scroll(rows: usize) -> usize {
+    KEY_BINDINGS.len().saturating_sub(rows.saturating_sub(2))
+}
+
+/// Draws the key bindings in `area`, starting `scroll` bindings down, with
+/// a title showing which of them are in view
+pub fn render_help_panel<W: Write>(
+    out: &mut W,
+    scroll: usize,
+    area: Rect,
+) -> Result<(), RendererError> {
+    if area.width < 8 || area.height < 3 {
+        return Ok(());
+    }
+
+    let rows = area.height - 2;
+    let help = help_lines();
+    let first = scroll.min(max_help_scroll(area.height));
+    let last = (first + rows).min(help.len());
+    let title = if first == 0 && last == help.len() {
+        " Keys".to_string()
+    } else {
+        format!(" Keys {}-{} of {}", first + 1, last, help.len())
+    };
+
+    let mut lines: Vec<(String, Color)> = vec![(title, ACCENT_COLOR)];
+    lines.extend(
+        help[first..last]
+            .iter()
+            .map(|line| (line.clone(), TEXT_COLOR)),
+    );
+    lines.resize(rows + 1, (String::new(), TEXT_COLOR));
+    lines.push((
+        " ↑↓ PgUp PgDn scroll · ? or Esc close".to_string(),
+        MUTED_COLOR,
+    ));
+
+    draw_lines(out, &lines, area)
+}
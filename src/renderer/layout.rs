@@ -0,0 +1,228 @@
+//! Line layout strategies for the render buffer
+//!
+//! This module decides how logical input lines are mapped onto visual rows
+//! before they are stored in the render buffer. Each strategy works on
+//! grapheme clusters and their display widths, so layout decisions are made
+//! in terminal columns rather than bytes or chars. Escape sequences found in
+//! the input are kept as zero-width glyphs so they survive layout.
+//!
+//! Available strategies:
+//! - `Wrap`: word-wraps long lines at the viewport width (default)
+//! - `Truncate`: cuts long lines and marks the cut with an ellipsis
+//! - `Scroll`: keeps lines intact so they can be scrolled horizontally
+
+use crate::escape::{self, Token};
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Marker appended to truncated lines
+pub const ELLIPSIS: &str = "…";
+
+/// How lines longer than the viewport are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// Wrap long lines onto additional rows, preferring whitespace breaks
+    #[default]
+    Wrap,
+    /// Cut long lines at the viewport edge with an ellipsis marker
+    Truncate,
+    /// Keep long lines intact and allow horizontal scrolling
+    Scroll,
+}
+
+/// A single grapheme cluster with its display width in columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph<'a> {
+    /// The grapheme cluster text
+    pub text: &'a str,
+    /// Number of terminal columns the grapheme occupies
+    pub width: usize,
+}
+
+impl<'a> Glyph<'a> {
+    /// Creates a glyph from a grapheme cluster, measuring its width
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            width: text.width(),
+        }
+    }
+
+    /// Creates a zero-width glyph for an escape sequence passed through verbatim
+    pub fn escape(sequence: &'a str) -> Self {
+        Self {
+            text: sequence,
+            width: 0,
+        }
+    }
+
+    /// Returns true if the glyph is whitespace and can be used as a break point
+    #[inline]
+    pub fn is_whitespace(&self) -> bool {
+        self.text.chars().all(char::is_whitespace)
+    }
+
+    /// Returns true if the glyph is an escape sequence rather than visible text
+    #[inline]
+    pub fn is_escape(&self) -> bool {
+        escape::is_escape(self.text)
+    }
+}
+
+/// Splits a line into glyphs, dropping SGR color codes from the input
+pub fn glyphs(line: &str) -> Vec<Glyph<'_>> {
+    let mut glyphs = Vec::with_capacity(line.len());
+    for token in escape::tokenize(line) {
+        match token {
+            Token::Text(text) => glyphs.extend(text.graphemes(true).map(Glyph::new)),
+            Token::Sgr(_) => {}
+            Token::Passthrough(sequence) => glyphs.push(Glyph::escape(sequence)),
+        }
+    }
+    glyphs
+}
+
+/// Returns the total display width of a run of glyphs
+pub fn row_width(row: &[Glyph<'_>]) -> usize {
+    row.iter().map(|g| g.width).sum()
+}
+
+impl WrapMode {
+    /// Returns a list of all wrap modes
+    pub fn all() -> &'static [WrapMode] {
+        &[WrapMode::Wrap, WrapMode::Truncate, WrapMode::Scroll]
+    }
+
+    /// Get string representation of the wrap mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WrapMode::Wrap => "wrap",
+            WrapMode::Truncate => "truncate",
+            WrapMode::Scroll => "scroll",
+        }
+    }
+
+    /// Returns true if rows may be wider than the viewport
+    #[inline]
+    pub fn allows_horizontal_scroll(&self) -> bool {
+        matches!(self, WrapMode::Scroll)
+    }
+
+    /// Lays out a single logical line into visual rows for the given width.
+    ///
+    /// # Arguments
+    /// * `line` - Glyphs of the logical line (without the trailing newline)
+    /// * `max_width` - Available width in terminal columns
+    ///
+    /// # Returns
+    /// One or more rows of glyphs. An empty line produces a single empty row.
+    pub fn layout<'a>(&self, line: &[Glyph<'a>], max_width: usize) -> Vec<Vec<Glyph<'a>>> {
+        let max_width = max_width.max(1);
+        match self {
+            WrapMode::Wrap => Self::wrap(line, max_width),
+            WrapMode::Truncate => vec![Self::truncate(line, max_width)],
+            WrapMode::Scroll => vec![line.to_vec()],
+        }
+    }
+
+    /// Word-wraps a line, falling back to hard breaks for long words
+    fn wrap<'a>(line: &[Glyph<'a>], max_width: usize) -> Vec<Vec<Glyph<'a>>> {
+        let mut rows = Vec::new();
+        let mut row: Vec<Glyph<'a>> = Vec::new();
+        let mut row_width = 0;
+        // Index in `row` just after the last whitespace glyph
+        let mut last_break: Option<usize> = None;
+
+        'glyphs: for glyph in line {
+            while row_width + glyph.width > max_width && !row.is_empty() {
+                if glyph.is_whitespace() {
+                    // Break on the whitespace itself and drop it
+                    rows.push(std::mem::take(&mut row));
+                    row_width = 0;
+                    last_break = None;
+                    continue 'glyphs;
+                }
+
+                match last_break {
+                    Some(split) => {
+                        // Move the partial word onto the next row
+                        let carry = row.split_off(split);
+                        while row.last().is_some_and(|g| g.is_whitespace()) {
+                            row.pop();
+                        }
+                        rows.push(std::mem::replace(&mut row, carry));
+                        row_width = self::row_width(&row);
+                    }
+                    _ => {
+                        rows.push(std::mem::take(&mut row));
+                        row_width = 0;
+                    }
+                }
+                last_break = None;
+            }
+
+            row.push(*glyph);
+            row_width += glyph.width;
+            if glyph.is_whitespace() {
+                last_break = Some(row.len());
+            }
+        }
+
+        if !row.is_empty() || rows.is_empty() {
+            rows.push(row);
+        }
+
+        rows
+    }
+
+    /// Truncates a line to fit, replacing the overflow with an ellipsis
+    fn truncate<'a>(line: &[Glyph<'a>], max_width: usize) -> Vec<Glyph<'a>> {
+        if row_width(line) <= max_width {
+            return line.to_vec();
+        }
+
+        let ellipsis = Glyph::new(ELLIPSIS);
+        let budget = max_width.saturating_sub(ellipsis.width);
+        let mut row = Vec::new();
+        let mut width = 0;
+
+        let mut cut = line.len();
+        for (i, glyph) in line.iter().enumerate() {
+            if width + glyph.width > budget {
+                cut = i;
+                break;
+            }
+            width += glyph.width;
+            row.push(*glyph);
+        }
+
+        row.push(ellipsis);
+        // Keep escape sequences from the hidden part, e.g. hyperlink terminators
+        row.extend(line[cut..].iter().filter(|g| g.is_escape()));
+        row
+    }
+}
+
+impl fmt::Display for WrapMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for WrapMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "wrap" => Ok(WrapMode::Wrap),
+            "truncate" => Ok(WrapMode::Truncate),
+            "scroll" => Ok(WrapMode::Scroll),
+            _ => Err(format!(
+                "Invalid wrap mode '{}'. Valid modes: wrap, truncate, scroll",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Gradient legend drawn over the animation
+//!
+//! The legend is a small strip in a corner of the screen showing the active
+//! gradient, the theme it comes from, and which end of the gradient low and
+//! high pattern values land on. It makes ChromaCat's colors readable as a
+//! scale, e.g. when the input is heat values or metrics. Like the other
+//! panels, it is drawn after the text on every frame.
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use super::error::RendererError;
+use super::favorites_panel::{fit, MUTED_COLOR, PANEL_BACKGROUND, TEXT_COLOR};
+use super::regions::Rect;
+use crate::pattern::PatternEngine;
+
+/// Width and height of the legend: theme name, gradient strip, and value
+/// labels, narrow enough to leave the text beside it in view
+pub const LEGEND_SIZE: (usize, usize) = (32, 3);
+
+/// Corner of the screen the legend, or another panel, is drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LegendPosition {
+    /// Top left corner
+    TopLeft,
+    /// Top right corner
+    TopRight,
+    /// Bottom left corner, above the status bar
+    BottomLeft,
+    /// Bottom right corner, above the status bar
+    #[default]
+    BottomRight,
+}
+
+impl LegendPosition {
+    /// Returns every position
+    pub fn all() -> &'static [LegendPosition] {
+        &[
+            LegendPosition::TopLeft,
+            LegendPosition::TopRight,
+            LegendPosition::BottomLeft,
+            LegendPosition::BottomRight,
+        ]
+    }
+
+    /// Returns the name used for this position on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LegendPosition::TopLeft => "top-left",
+            LegendPosition::TopRight => "top-right",
+            LegendPosition::BottomLeft => "bottom-left",
+            LegendPosition::BottomRight => "bottom-right",
+        }
+    }
+
+    /// Returns whether the corner is on the right edge
+    pub fn is_right(&self) -> bool {
+        matches!(self, LegendPosition::TopRight | LegendPosition::BottomRight)
+    }
+
+    /// Returns whether the corner is on the top edge
+    pub fn is_top(&self) -> bool {
+        matches!(self, LegendPosition::TopLeft | LegendPosition::TopRight)
+    }
+}
+
+impl fmt::Display for LegendPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LegendPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LegendPosition::all()
+            .iter()
+            .find(|position| position.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = LegendPosition::all().iter().map(|p| p.as_str()).collect();
+                format!(
+                    "Invalid legend position '{}'. Valid positions: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Serialize for LegendPosition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Enable deserialization for panel layout loading
+impl<'de> Deserialize<'de> for LegendPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Draws the legend for `engine`'s gradient, named after `theme`, in `area`
+pub fn render_legend<W: Write>(
+    out: &mut W,
+    engine: &PatternEngine,
+    theme: &str,
+    area: Rect,
+) -> Result<(), RendererError> {
+    let legend_width = area.width;
+    if legend_width < 12 || area.height < LEGEND_SIZE.1 {
+        return Ok(());
+    }
+    let (column, row) = (area.x as u16, area.y as u16);
+
+    queue!(
+        out,
+        MoveTo(column, row),
+        SetBackgroundColor(PANEL_BACKGROUND),
+        SetForegroundColor(TEXT_COLOR),
+        Print(fit(&format!(" {}", theme), legend_width)),
+        MoveTo(column, row + 1),
+        Print(" "),
+    )?;
+
+    // Pattern values from 0 to 1 run left to right across the strip
+    let strip = legend_width - 2;
+    for i in 0..strip {
+        let color = engine.color_at(i as f64 / (strip - 1) as f64);
+        queue!(
+            out,
+            SetForegroundColor(Color::Rgb {
+                r: (color.r.clamp(0.0, 1.0) * 255.0) as u8,
+                g: (color.g.clamp(0.0, 1.0) * 255.0) as u8,
+                b: (color.b.clamp(0.0, 1.0) * 255.0) as u8,
+            }),
+            Print('█'),
+        )?;
+    }
+
+    let labels = format!(" 0.0{:^1$}1.0 ", "pattern value", legend_width - 8);
+    queue!(
+        out,
+        Print(" "),
+        MoveTo(column, row + 2),
+        SetForegroundColor(MUTED_COLOR),
+        Print(fit(&labels, legend_width)),
+        ResetColor,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Replaying a repeating animation from memory
+//!
+//! With `--cycle`, pattern time repeats, so every frame of the steady state
+//! has been drawn before. `--loop-cache` keeps the pattern samples of each
+//! frame of the first cycle, one slot per frame interval, and replays them
+//! from then on without evaluating the pattern again. Anything that changes
+//! what the samples would be, such as resizing the terminal or changing the
+//! pattern or its parameters, starts the recording over.
+
+use std::time::Duration;
+
+/// Most pattern samples kept across all slots, about 100 MB; frames past
+/// it are drawn as usual
+pub const MAX_LOOP_CACHE_CELLS: usize = 4_000_000;
+
+/// Returns the number of frames in a `cycle` shown at `fps`
+pub fn cycle_frames(cycle: Duration, fps: u32) -> usize {
+    (cycle.as_secs_f64() * fps as f64).ceil().max(1.0) as usize
+}
+
+/// Frames of one animation cycle, recorded once and replayed after
+#[derive(Debug)]
+pub struct LoopCache<T> {
+    /// Recorded frames and their sample counts, one per frame interval of
+    /// the cycle
+    slots: Vec<Option<(T, usize)>>,
+    /// What the recorded frames were drawn for
+    key: Option<String>,
+    /// Samples held across the recorded frames
+    cells: usize,
+}
+
+impl<T> LoopCache<T> {
+    /// Creates an empty cache for a cycle of `frames` frames
+    pub fn new(frames: usize) -> Self {
+        Self {
+            slots: (0..frames.max(1)).map(|_| None).collect(),
+            key: None,
+            cells: 0,
+        }
+    }
+
+    /// Returns the number of frames in the cycle
+    pub fn frames(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of frames recorded so far
+    pub fn recorded(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Returns the slot of the frame `progress` of the way through the cycle
+    pub fn slot(&self, progress: f64) -> usize {
+        let last = self.slots.len() - 1;
+        ((progress.clamp(0.0, 1.0) * self.slots.len() as f64) as usize).min(last)
+    }
+
+    /// Keeps the recorded frames if they were drawn for `key`, and forgets
+    /// them otherwise
+    pub fn keep_if(&mut self, key: String) {
+        if self.key.as_ref() != Some(&key) {
+            self.clear();
+            self.key = Some(key);
+        }
+    }
+
+    /// Forgets every recorded frame
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.key = None;
+        self.cells = 0;
+    }
+
+    /// Takes the frame recorded in `slot` out to be drawn, if there is one;
+    /// [`store`](Self::store) puts it back
+    pub fn take(&mut self, slot: usize) -> Option<T> {
+        let (frame, cells) = self.slots.get_mut(slot)?.take()?;
+        self.cells -= cells;
+        Some(frame)
+    }
+
+    /// Records `frame` of `cells` samples in `slot`, returning false if the
+    /// cache is full and it wasn't kept
+    pub fn store(&mut self, slot: usize, frame: T, cells: usize) -> bool {
+        let Some(stored) = self.slots.get_mut(slot) else {
+            return false;
+        };
+        let replaced = stored.as_ref().map_or(0, |(_, cells)| *cells);
+        if self.cells - replaced + cells > MAX_LOOP_CACHE_CELLS {
+            return false;
+        }
+        self.cells = self.cells - replaced + cells;
+        *stored = Some((frame, cells));
+        true
+    }
+}
@@ -0,0 +1,210 @@
+//! Text masks: the input text as a stencil for the pattern
+//!
+//! A mask gives every cell a strength from 0.0 to 1.0 derived from where
+//! the text has glyphs, and pattern values are scaled by it before the
+//! gradient lookup. Blank cells with no strength are left empty even when a
+//! character ramp or sub-cell resolution would otherwise fill them, so a
+//! fire pattern drawn with `--char-ramp` can burn only where there is text,
+//! or glow outward from it.
+//!
+//! Masks are built once per text layout from the glyph occupancy of the
+//! laid-out rows, using separable passes so their cost stays linear in the
+//! size of the text.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Cells the glow reaches to the left and right of a glyph
+const GLOW_RADIUS_X: usize = 4;
+/// Rows the glow reaches above and below a glyph; cells are about twice as
+/// tall as they are wide
+const GLOW_RADIUS_Y: usize = 2;
+
+/// Cells to each side included in the density average
+const DENSITY_RADIUS_X: usize = 2;
+/// Rows above and below included in the density average
+const DENSITY_RADIUS_Y: usize = 1;
+
+/// How the text shapes the pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaskMode {
+    /// The pattern is drawn everywhere at full strength
+    #[default]
+    Off,
+    /// The pattern is drawn only in cells holding glyphs
+    Stencil,
+    /// Strength follows how much text surrounds each cell
+    Density,
+    /// Glyphs are drawn at full strength, fading out into nearby blank cells
+    Glow,
+}
+
+impl MaskMode {
+    /// Returns a list of all mask modes
+    pub fn all() -> &'static [MaskMode] {
+        &[
+            MaskMode::Off,
+            MaskMode::Stencil,
+            MaskMode::Density,
+            MaskMode::Glow,
+        ]
+    }
+
+    /// Get string representation of the mask mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaskMode::Off => "off",
+            MaskMode::Stencil => "stencil",
+            MaskMode::Density => "density",
+            MaskMode::Glow => "glow",
+        }
+    }
+}
+
+impl fmt::Display for MaskMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for MaskMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(MaskMode::Off),
+            "stencil" => Ok(MaskMode::Stencil),
+            "density" => Ok(MaskMode::Density),
+            "glow" => Ok(MaskMode::Glow),
+            _ => Err(format!(
+                "Invalid mask mode '{}'. Valid modes: off, stencil, density, glow",
+                s
+            )),
+        }
+    }
+}
+
+/// Per-cell strengths for one text layout
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextMask {
+    /// Mode the mask was built with
+    mode: MaskMode,
+    /// Strength of each cell, by row and column
+    strength: Vec<Vec<f32>>,
+}
+
+impl TextMask {
+    /// Builds a mask from glyph occupancy, one entry per cell of each row
+    pub fn new(mode: MaskMode, occupancy: &[Vec<bool>]) -> Self {
+        let strength = match mode {
+            MaskMode::Off => Vec::new(),
+            MaskMode::Stencil => occupancy
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&occupied| f32::from(u8::from(occupied)))
+                        .collect()
+                })
+                .collect(),
+            MaskMode::Density => density(occupancy),
+            MaskMode::Glow => glow(occupancy),
+        };
+        Self { mode, strength }
+    }
+
+    /// Returns the mode the mask was built with
+    pub fn mode(&self) -> MaskMode {
+        self.mode
+    }
+
+    /// Returns the strength of the cell at (`x`, `y`); cells outside the
+    /// text have none unless masking is off
+    #[inline]
+    pub fn at(&self, x: usize, y: usize) -> f64 {
+        if self.mode == MaskMode::Off {
+            return 1.0;
+        }
+        self.strength
+            .get(y)
+            .and_then(|row| row.get(x))
+            .map_or(0.0, |&s| s as f64)
+    }
+}
+
+/// Averages occupancy over a small window around each cell
+fn density(occupancy: &[Vec<bool>]) -> Vec<Vec<f32>> {
+    let area = ((2 * DENSITY_RADIUS_X + 1) * (2 * DENSITY_RADIUS_Y + 1)) as f32;
+
+    // Count glyphs along each row, then sum the counts down each column
+    let counts: Vec<Vec<f32>> = occupancy
+        .iter()
+        .map(|row| {
+            (0..row.len())
+                .map(|x| {
+                    let (start, end) = window(x, DENSITY_RADIUS_X, row.len());
+                    row[start..end].iter().filter(|&&o| o).count() as f32
+                })
+                .collect()
+        })
+        .collect();
+
+    (0..counts.len())
+        .map(|y| {
+            let (start, end) = window(y, DENSITY_RADIUS_Y, counts.len());
+            (0..counts[y].len())
+                .map(|x| {
+                    let total: f32 = counts[start..end].iter().filter_map(|row| row.get(x)).sum();
+                    (total / area).min(1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Strength falling off linearly with the distance to the nearest glyph
+fn glow(occupancy: &[Vec<bool>]) -> Vec<Vec<f32>> {
+    let falloff = |distance: usize, radius: usize| 1.0 - distance as f32 / (radius + 1) as f32;
+
+    // The falloff of the larger axis distance is the smaller of the two
+    // axis falloffs, so rows and columns can be handled one at a time
+    let rows: Vec<Vec<f32>> = occupancy
+        .iter()
+        .map(|row| {
+            (0..row.len())
+                .map(|x| {
+                    let (start, end) = window(x, GLOW_RADIUS_X, row.len());
+                    (start..end)
+                        .filter(|&i| row[i])
+                        .map(|i| falloff(i.abs_diff(x), GLOW_RADIUS_X))
+                        .fold(0.0, f32::max)
+                })
+                .collect()
+        })
+        .collect();
+
+    (0..rows.len())
+        .map(|y| {
+            let (start, end) = window(y, GLOW_RADIUS_Y, rows.len());
+            (0..rows[y].len())
+                .map(|x| {
+                    (start..end)
+                        .filter_map(|i| {
+                            rows[i]
+                                .get(x)
+                                .map(|&s| s.min(falloff(i.abs_diff(y), GLOW_RADIUS_Y)))
+                        })
+                        .fold(0.0, f32::max)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the index range within `radius` of `center`, clipped to `len`
+#[inline]
+fn window(center: usize, radius: usize, len: usize) -> (usize, usize) {
+    (
+        center.saturating_sub(radius),
+        (center + radius + 1).min(len),
+    )
+}
@@ -6,6 +6,7 @@
 //!
 //! The rendering system is built around several key components:
 //! - Terminal state management and interaction
+//! - Render targets: the terminal, or a headless screen kept in memory
 //! - Double buffered text and color handling
 //! - Pattern-based color generation
 //! - Scrolling and viewport control
@@ -13,40 +14,139 @@
 //! - Frame timing and synchronization
 //! - Playlist management and transitions
 
+mod assembler;
 mod buffer;
+mod camera;
+mod clipboard;
 mod config;
+mod cpu_budget;
+mod debug_overlay;
+mod effects;
 mod error;
+mod event_loop;
+mod favorites_panel;
+mod inspect;
+pub mod gutter;
+mod keys;
+mod layout;
+mod legend;
+mod loop_cache;
+mod mask;
+mod mouse;
+mod notifications;
+mod panels;
+mod picker;
+mod profile;
+mod raster;
+mod regions;
+mod resize;
+mod screenshot;
 mod scroll;
+mod search;
+mod stats;
 mod status_bar;
+mod target;
 pub mod terminal;
+mod timer;
 
+pub use assembler::{FrameAssembler, BEGIN_SYNCHRONIZED_UPDATE, END_SYNCHRONIZED_UPDATE};
 pub use buffer::RenderBuffer;
-pub use config::AnimationConfig;
+pub use camera::{parse_pan, Camera, MAX_CANVAS_SCALE, MAX_ZOOM};
+pub use clipboard::osc52_sequence;
+pub use config::{AnimationConfig, SMOOTH_SAMPLES};
+pub use cpu_budget::{parse_cpu_budget, CpuBudget, BUDGET_STEPS};
+pub use debug_overlay::{FrameStage, FrameTimings, DEBUG_EVENTS};
+pub use effects::TextEffect;
 pub use error::RendererError;
+pub use event_loop::{ErrorPolicy, LoopStage, Recovery, MAX_FRAME_FAILURES, TRANSITION_RETRIES};
+pub use favorites_panel::{describe_favorite, MAX_LISTED_FAVORITES};
+pub use gutter::{Gutter, GUTTER_COLOR};
+pub use inspect::{ParamSweep, SWEEP_SECONDS};
+pub use keys::{actions_for, help_lines, KeyAction, KeyBinding, KEY_BINDINGS};
+pub use layout::WrapMode;
+pub use legend::LegendPosition;
+pub use loop_cache::{cycle_frames, LoopCache, MAX_LOOP_CACHE_CELLS};
+pub use mask::{MaskMode, TextMask};
+pub use mouse::{ClickTracker, DOUBLE_CLICK_INTERVAL, WHEEL_LINES};
+pub use notifications::{
+    Notice, Notifications, Severity, HISTORY_LISTED, MAX_HISTORY, MAX_QUEUED, TOAST_DURATION,
+};
+pub use panels::{get_panels_path, PanelKind, PanelLayout, DEFAULT_STACK_BELOW, PANELS_FILE};
+pub use picker::{fuzzy_score, PickItem, PickKind, Picker, MAX_LISTED_HITS};
+pub use profile::{RenderProfile, SlowFrames, MINIMAL_COMPLEXITY, MINIMAL_FPS, SLOW_SECONDS};
+pub use raster::{Resolution, SubCell};
+pub use regions::{Compositor, Extent, Rect, Region, RegionLayout, RegionSpec};
+pub use resize::{ResizeWatcher, CHECK_INTERVAL};
+pub use screenshot::{Frame, FrameCell, ScreenshotTarget, CELL_HEIGHT, CELL_WIDTH};
 pub use scroll::{Action, ScrollState};
+pub use search::Search;
+pub use stats::{SessionStats, StatsReport, STATS_SAVE_INTERVAL};
 pub use status_bar::StatusBar;
+pub use target::{HeadlessTarget, RenderTarget, Rgb};
 pub use terminal::TerminalState;
+pub use timer::{CountdownShift, SHIFT_STEPS};
 
-use crate::pattern::PatternEngine;
-use crate::playlist::{Playlist, PlaylistPlayer};
-use crate::{themes, PatternConfig};
+use crate::columns::Columns;
+use crate::data::DataColumn;
+use crate::diff::DiffPalettes;
+use crate::demo::{ArtSettings, ArtTransition, DemoArt, DemoArtGenerator, ART_TRANSITION_DURATION};
+use crate::pattern::{Grain, PatternEngine, DEFAULT_GRAIN, MAX_PATTERN_PAN, PATTERN_ZOOM_RANGE};
+use crate::playlist::{
+    Favorites, Playlist, PlaylistEntry, PlaylistPlayer, ScenePool, SessionRecorder, SessionScene,
+};
+use crate::{events, guard, themes, PatternConfig};
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
-use log::info;
-use std::io::Write;
-use std::time::{Duration, Instant};
-use crate::input::InputReader;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use crossterm::queue;
+use crossterm::style::Print;
+use crossterm::terminal::{Clear, ClearType};
+use log::{info, trace, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt::Write as FmtWrite;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant, SystemTime};
+use unicode_width::UnicodeWidthStr;
 
-/// Coordinates all rendering functionality for ChromaCat
-pub struct Renderer {
+/// Number of columns moved per Left/Right key press in scroll wrap mode
+const HORIZONTAL_SCROLL_STEP: usize = 4;
+
+/// Speed multipliers `+` and `-` move between
+const SPEED_STEPS: &[f64] = &[0.1, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 5.0, 10.0];
+
+/// Seconds of animation time moved per `[`/`]` key press
+const SCRUB_SECONDS: f64 = 1.0;
+
+/// Fraction of the screen the pattern moves per Shift+arrow key press
+const PATTERN_PAN_STEP: f64 = 0.05;
+
+/// Factor the pattern zoom changes by per `<`/`>` key press
+const PATTERN_ZOOM_STEP: f64 = 1.25;
+
+/// Rows of static output printed between checks for a terminal resize
+const STATIC_RESIZE_ROWS: usize = 64;
+
+/// Most input lines of a large input laid out and colored at a time
+const STATIC_CHUNK_LINES: usize = 2048;
+
+/// Most bytes of a large input laid out and colored at a time, short of
+/// splitting a line
+const STATIC_CHUNK_BYTES: usize = 1 << 20;
+
+/// Coordinates all rendering functionality for ChromaCat, drawing to the
+/// terminal unless given another [`RenderTarget`]
+pub struct Renderer<T: RenderTarget = TerminalState> {
     /// Pattern generation engine
     engine: PatternEngine,
     /// Animation configuration
     config: AnimationConfig,
     /// Double buffered text and colors
     buffer: RenderBuffer,
-    /// Terminal state manager
-    terminal: TerminalState,
+    /// Terminal state manager, or whatever else is drawn to
+    terminal: T,
+    /// Animated frame being drawn, written to the terminal in one go
+    frame: FrameAssembler,
     /// Scrolling state manager
     scroll: ScrollState,
     /// Status bar renderer
@@ -73,6 +173,88 @@ pub struct Renderer {
     content: String,
     /// Whether running in demo mode
     demo_mode: bool,
+    /// Demo art being displayed, regenerated as it animates and on resize
+    live_art: Option<LiveArt>,
+    /// Art options applied whenever demo art is generated
+    art_settings: ArtSettings,
+    /// How new demo art replaces the art on screen
+    art_transition: ArtTransition,
+    /// Whether the playlist advances on its own timer rather than being
+    /// moved by [`seek_playlist`](Self::seek_playlist)
+    playlist_auto_advance: bool,
+    /// Decides how the event loop recovers from errors
+    error_policy: ErrorPolicy,
+    /// Whether a scene change failed and is waiting to be retried
+    pending_transition: bool,
+    /// Log of the scenes shown, with when recording started
+    session: Option<(SessionRecorder, Instant)>,
+    /// Counts of the frames and scenes shown, once they are being kept
+    stats: Option<SessionStats>,
+    /// File the stats are written to, with when they were last written
+    stats_file: Option<(std::path::PathBuf, Instant)>,
+    /// Bookmarked combinations, loaded the first time they are needed
+    favorites: Option<Favorites>,
+    /// Whether the favorites panel is drawn over the animation
+    favorites_open: bool,
+    /// Grain amount the grain toggle turns on
+    grain_amount: f64,
+    /// Query being typed after `/`, while the search prompt is open
+    search_prompt: Option<String>,
+    /// Last search, which `n` and `N` move through
+    search: Option<Search>,
+    /// Quick switcher opened with `:`, while it is taking key presses
+    picker: Option<Picker>,
+    /// Bindings scrolled past in the key help opened with `?`, while it is
+    /// open
+    help_scroll: Option<usize>,
+    /// Whether the debug overlay is drawn over the animation
+    debug_overlay: bool,
+    /// Whether the gradient legend is drawn over the animation
+    legend_shown: bool,
+    /// Whether the message history is drawn over the animation
+    history_shown: bool,
+    /// Tells double-clicks from single ones
+    clicks: ClickTracker,
+    /// Cell the pointer was last dragged over, while dragging the pattern
+    drag_from: Option<(u16, u16)>,
+    /// Corners the panels are drawn in
+    panels: PanelLayout,
+    /// How long each stage of the latest frames took
+    timings: FrameTimings,
+    /// Random numbers for the scenes R draws
+    rng: StdRng,
+    /// Pattern and index of the preset V last applied
+    current_preset: Option<(String, usize)>,
+    /// Parameter I is sweeping across its range, with the animation held
+    sweep: Option<ParamSweep>,
+    /// Theme shift following the clock art's countdown, if any
+    countdown_shift: Option<CountdownShift>,
+    /// Whether the bell has rung for the end of the countdown
+    countdown_rung: bool,
+    /// Whether the next frame clears the screen first, to remove a panel
+    /// that was closed
+    clear_screen: bool,
+    /// How much the animation gives up for speed
+    profile: RenderProfile,
+    /// Whether the minimal profile is picked once frames keep running slow,
+    /// as no profile was asked for
+    auto_profile: bool,
+    /// Frames that overran their time lately
+    slow_frames: SlowFrames,
+    /// CPU usage target the animation steps down to stay under, if any
+    cpu_budget: Option<CpuBudget>,
+}
+
+/// Demo art that is regenerated as time passes or the viewport changes
+struct LiveArt {
+    /// Generator sized to the viewport
+    generator: DemoArtGenerator,
+    /// Art being displayed
+    art: DemoArt,
+    /// Seconds of art animation elapsed
+    time: f64,
+    /// Frame of the art being replaced, and seconds into the transition
+    outgoing: Option<(String, f64)>,
 }
 
 impl Renderer {
@@ -83,10 +265,37 @@ impl Renderer {
         playlist: Option<Playlist>,
         demo_mode: bool,
     ) -> Result<Self, RendererError> {
-        let terminal = TerminalState::new()?;
+        Self::with_target(TerminalState::new()?, engine, config, playlist, demo_mode)
+    }
+
+    /// Builds a separator line announcing a file, padded to the given width
+    pub fn file_header(name: &str, width: usize) -> String {
+        let mut header = format!("━━ {} ", name);
+        let used = UnicodeWidthStr::width(header.as_str());
+        header.push_str(&"━".repeat(width.saturating_sub(used).max(2)));
+        header
+    }
+}
+
+impl<T: RenderTarget> Renderer<T> {
+    /// Creates a renderer drawing to `terminal` instead of the terminal
+    pub fn with_target(
+        terminal: T,
+        engine: PatternEngine,
+        config: AnimationConfig,
+        playlist: Option<Playlist>,
+        demo_mode: bool,
+    ) -> Result<Self, RendererError> {
         let term_size = terminal.size();
-        let buffer = RenderBuffer::new(term_size);
-        let scroll = ScrollState::new(term_size.1.saturating_sub(2));
+        let mut buffer = RenderBuffer::with_wrap_mode(term_size, config.wrap_mode);
+        buffer.set_color_support(terminal.caps().color);
+        buffer.set_char_ramp(config.char_ramp.clone());
+        buffer.set_resolution(config.resolution);
+        buffer.set_camera(config.camera.clone());
+        buffer.set_mask_mode(config.mask_mode);
+        buffer.set_effect(config.effect);
+        buffer.set_gutter(config.gutter);
+        let scroll = ScrollState::new(text_rows(term_size.1, &config));
         let mut status_bar = StatusBar::new(term_size);
 
         // Initialize available themes and patterns
@@ -105,20 +314,20 @@ impl Renderer {
         let playlist_player = playlist.map(PlaylistPlayer::new);
 
         // Get the initial engine configuration based on playlist or defaults
-        let (initial_engine, initial_theme, initial_pattern) =
+        let (mut initial_engine, initial_theme, initial_pattern) =
             if let Some(player) = &playlist_player {
                 if let Some(entry) = player.current_entry() {
                     // Get configuration from first playlist entry
                     let entry_config = entry.to_pattern_config()?;
-                    let entry_gradient = themes::get_theme(&entry.theme)?.create_gradient()?;
-
-                    // Create new engine with playlist entry's configuration
-                    let new_engine = PatternEngine::new(
-                        entry_gradient,
-                        entry_config,
-                        term_size.0 as usize,
-                        term_size.1 as usize,
-                    );
+                    let entry_theme = themes::get_theme(&entry.theme)?;
+
+                    // Create new engine with playlist entry's configuration,
+                    // sized like the one it replaces
+                    let (width, height) = engine.size();
+                    let mut new_engine =
+                        PatternEngine::from_theme(&entry_theme, entry_config, width, height)?;
+                    new_engine.set_postprocess(engine.postprocess().clone());
+                    new_engine.set_light_map(engine.light_map().cloned());
 
                     (new_engine, entry.theme.clone(), entry.pattern.clone())
                 } else {
@@ -148,6 +357,13 @@ impl Renderer {
                 (engine, theme, pattern)
             };
 
+        config.apply_smoothing(&mut initial_engine);
+        initial_engine.set_cycle(config.cycle, config.loop_mode);
+        buffer.set_interlace(config.interlace);
+        if config.loop_cache {
+            buffer.set_loop_cache(config.cycle.map(|cycle| cycle_frames(cycle, config.fps)));
+        }
+
         // Set initial theme and pattern in status bar
         status_bar.set_theme(&initial_theme);
         status_bar.set_pattern(&initial_pattern);
@@ -163,14 +379,43 @@ impl Renderer {
             .position(|p| p == &initial_pattern)
             .unwrap_or(0);
 
+        // Regions are placed on the text area, above the status bar
+        if let Some(layout) = &config.layout {
+            let mut regions = Compositor::new(
+                layout,
+                term_size.0 as usize,
+                term_size.1.saturating_sub(2) as usize,
+            )?;
+            regions.set_postprocess(initial_engine.postprocess());
+            regions.set_cycle(config.cycle, config.loop_mode);
+            regions.set_smoothing(
+                config.smoothing_samples(),
+                config.frame_duration().as_secs_f64(),
+            );
+            buffer.set_regions(Some(regions));
+        }
+
+        let grain = initial_engine.postprocess().grain;
+        let grain_amount = if grain.is_none() { DEFAULT_GRAIN } else { grain.amount() };
+
         // Initialize timing state
         let now = Instant::now();
         let fps = config.fps as f64;
+        let debug_overlay = config.debug_overlay;
+        let legend = config.legend;
+        let profile = config.profile;
+        let cpu_budget = config.cpu_budget.map(CpuBudget::new);
+        let mut panels = PanelLayout::default();
+        panels.legend = legend.unwrap_or_default();
+        if debug_overlay {
+            events::start_capture();
+        }
 
-        Ok(Self {
+        let mut renderer = Self {
             engine: initial_engine,
             config,
             buffer,
+            frame: FrameAssembler::new(term_size, terminal.synchronized_output()),
             terminal,
             scroll,
             status_bar,
@@ -185,13 +430,107 @@ impl Renderer {
             playlist_player,
             content: String::new(),
             demo_mode,
-        })
+            live_art: None,
+            art_settings: ArtSettings::default(),
+            art_transition: ArtTransition::default(),
+            playlist_auto_advance: true,
+            error_policy: ErrorPolicy::new(),
+            pending_transition: false,
+            session: None,
+            stats: None,
+            stats_file: None,
+            favorites: None,
+            favorites_open: false,
+            grain_amount,
+            search_prompt: None,
+            picker: None,
+            help_scroll: None,
+            search: None,
+            debug_overlay,
+            legend_shown: legend.is_some(),
+            history_shown: false,
+            clicks: ClickTracker::new(),
+            drag_from: None,
+            panels,
+            timings: FrameTimings::default(),
+            rng: StdRng::from_entropy(),
+            current_preset: None,
+            sweep: None,
+            countdown_shift: None,
+            countdown_rung: false,
+            clear_screen: false,
+            profile: RenderProfile::Full,
+            auto_profile: profile.is_none(),
+            slow_frames: SlowFrames::new(),
+            cpu_budget,
+        };
+        if let Some(profile) = profile {
+            renderer.set_profile(profile);
+        }
+        Ok(renderer)
     }
 
-    /// Returns the frame duration based on configured FPS
+    /// Returns what the renderer draws to
+    #[inline]
+    pub fn target(&self) -> &T {
+        &self.terminal
+    }
+
+    /// Returns the frame duration based on configured FPS, capped by the
+    /// rendering profile and lowered to stay under the CPU budget
     #[inline]
     pub fn frame_duration(&self) -> Duration {
-        self.config.frame_duration()
+        let mut fps = self.profile.fps(self.config.fps);
+        if let Some(budget) = &self.cpu_budget {
+            fps = budget.fps(fps);
+        }
+        Duration::from_nanos(1_000_000_000u64 / fps.max(1) as u64)
+    }
+
+    /// Returns the CPU usage target the animation stays under, if any
+    pub fn cpu_budget(&self) -> Option<&CpuBudget> {
+        self.cpu_budget.as_ref()
+    }
+
+    /// Returns the rendering profile in use
+    #[inline]
+    pub fn profile(&self) -> RenderProfile {
+        self.profile
+    }
+
+    /// Switches to `profile`: the frame rate, colors, and art transitions
+    /// follow it, and pattern complexity is capped from here on
+    pub fn set_profile(&mut self, profile: RenderProfile) {
+        info!("Rendering with the {} profile", profile);
+        self.profile = profile;
+        self.buffer
+            .set_color_support(profile.colors(self.terminal.caps().color));
+        self.engine.set_max_complexity(self.max_complexity());
+        self.buffer.mark_all_dirty();
+    }
+
+    /// Follows a change of step of the CPU budget
+    fn apply_cpu_budget(&mut self) {
+        let Some(budget) = &self.cpu_budget else {
+            return;
+        };
+        info!(
+            "CPU usage {:.0}% of a {:.0}% budget; now at {} fps",
+            budget.usage().unwrap_or_default(),
+            budget.budget(),
+            budget.fps(self.profile.fps(self.config.fps))
+        );
+        self.engine.set_max_complexity(self.max_complexity());
+    }
+
+    /// Returns the highest pattern `complexity` the profile and the CPU
+    /// budget allow, if either caps it
+    fn max_complexity(&self) -> Option<f64> {
+        let budget = self.cpu_budget.as_ref().and_then(|b| b.max_complexity());
+        match (self.profile.max_complexity(), budget) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
     }
 
     /// Returns whether animation is set to run indefinitely
@@ -208,26 +547,123 @@ impl Renderer {
 
     /// Renders static text with pattern-based colors
     pub fn render_static(&mut self, text: &str) -> Result<(), RendererError> {
+        self.buffer.restart_text();
+        self.render_static_chunk(text)
+    }
+
+    /// Renders static input of `len` bytes read from `reader` a chunk of
+    /// lines at a time, so memory use stays the same however large it is.
+    /// The gradient runs on across chunks by their place in the input, and
+    /// starts over for each chunk when the length isn't known (zero).
+    pub fn render_static_stream<R: BufRead>(
+        &mut self,
+        mut reader: R,
+        len: u64,
+    ) -> Result<(), RendererError> {
+        self.buffer.restart_text();
+        let mut chunk = String::new();
+        let mut read = 0u64;
+        loop {
+            chunk.clear();
+            let mut lines = 0;
+            while lines < STATIC_CHUNK_LINES && chunk.len() < STATIC_CHUNK_BYTES {
+                if reader.read_line(&mut chunk)? == 0 {
+                    break;
+                }
+                lines += 1;
+            }
+            // Empty input still renders, like empty text
+            if chunk.is_empty() && read > 0 {
+                return Ok(());
+            }
+
+            let start = read;
+            read += chunk.len() as u64;
+            if len > 0 && len >= read {
+                self.buffer
+                    .set_span(start as f64 / len as f64, read as f64 / len as f64);
+            }
+
+            // The newline ending a chunk only ends its last line; the last
+            // chunk keeps it, so the output ends as it would whole
+            let last = reader.fill_buf()?.is_empty();
+            let text = match last {
+                true => chunk.as_str(),
+                false => chunk.strip_suffix('\n').unwrap_or(&chunk),
+            };
+            self.render_static_chunk(text)?;
+            if last || guard::interrupted() {
+                return Ok(());
+            }
+            self.buffer.continue_text();
+        }
+    }
+
+    /// Renders static text that continues whatever the buffer was last told
+    /// it continues
+    fn render_static_chunk(&mut self, text: &str) -> Result<(), RendererError> {
+        // Static output scrolls rather than being redrawn, so a resize only
+        // changes how the lines still to be printed are laid out
+        let mut watcher = self.terminal.follows_window().then(ResizeWatcher::new);
+        let watched = watcher.as_ref().and_then(ResizeWatcher::size);
+
         // Prepare the full content
-        self.buffer.prepare_text(text)?;
+        match watched.filter(|&size| size != self.terminal.size()) {
+            Some(size) => self.resize_static(size, text)?,
+            None => self.buffer.prepare_text(text)?,
+        }
 
-        // Update colors
-        self.buffer.update_colors_static(&self.engine)?;
+        let mut rest = text;
+        loop {
+            // Update colors
+            self.buffer.update_colors_static(&self.engine)?;
 
-        // Get a stdout lock for efficient writing
-        let mut stdout = self.terminal.stdout();
+            let colors_enabled = self.terminal.colors_enabled();
+            let total = self.buffer.total_lines();
 
-        // Render the entire buffer content
-        self.buffer.render_region(
-            &mut stdout,
-            0,
-            self.buffer.total_lines(),
-            self.terminal.colors_enabled(),
-            false,
-        )?;
+            // Output that isn't a terminal can't be resized; render it whole
+            if watched.is_none() {
+                self.buffer
+                    .render_region(&mut self.terminal, 0, total, colors_enabled, false)?;
+                self.terminal.flush()?;
+                return Ok(());
+            }
 
-        stdout.flush()?;
-        Ok(())
+            // Render in blocks of whole input lines, checking for a resize
+            // between them
+            let mut row = 0;
+            let mut resized = None;
+            while row < total && resized.is_none() {
+                let mut end = (row + STATIC_RESIZE_ROWS).min(total);
+                while end < total
+                    && self.buffer.source_line(end) == self.buffer.source_line(end - 1)
+                {
+                    end += 1;
+                }
+                self.buffer
+                    .render_region(&mut self.terminal, row, end, colors_enabled, false)?;
+                self.terminal.flush()?;
+                row = end;
+                resized = watcher.as_mut().and_then(ResizeWatcher::poll);
+            }
+
+            // Lay out the lines not yet printed for the new size
+            let (Some(size), Some(line)) = (resized, self.buffer.source_line(row)) else {
+                return Ok(());
+            };
+            let offset: usize = rest.split('\n').take(line).map(|l| l.len() + 1).sum();
+            rest = &rest[offset..];
+            self.buffer.set_line_offset(self.buffer.line_offset() + line);
+            self.resize_static(size, rest)?;
+        }
+    }
+
+    /// Adopts a new terminal size for static output without redrawing,
+    /// laying out `text` for it
+    fn resize_static(&mut self, (width, height): (u16, u16), text: &str) -> Result<(), RendererError> {
+        info!("Terminal resized to {}x{}", width, height);
+        self.terminal.set_size(width, height);
+        self.buffer.resize_with_text((width, height), text)
     }
 
     /// Renders a single animation frame
@@ -235,8 +671,12 @@ impl Renderer {
         let frame_time = Duration::from_secs_f64(delta_seconds);
 
         // Handle playlist updates if active
-        let needs_update = if let Some(player) = &mut self.playlist_player {
-            info!(
+        let needs_update = if let Some(player) = self
+            .playlist_player
+            .as_mut()
+            .filter(|_| self.playlist_auto_advance)
+        {
+            trace!(
                 "Updating playlist: current_entry={:?}, time={:?}",
                 player.current_entry().map(|e| &e.pattern),
                 frame_time
@@ -246,9 +686,16 @@ impl Renderer {
             false
         };
 
-        if needs_update {
+        if needs_update || self.pending_transition {
             info!("Playlist entry changed, updating configuration");
-            self.update_playlist_entry()?;
+            self.change_scene();
+        }
+
+        // Show how reloading an edited playlist went
+        if let Some((notice, severity)) =
+            self.playlist_player.as_mut().and_then(|p| p.take_notice())
+        {
+            self.status_bar.notify(&notice, severity);
         }
 
         // Update playlist status display
@@ -259,19 +706,40 @@ impl Renderer {
                 } else {
                     "Playing"
                 };
-                self.status_bar.set_custom_text(Some(&format!(
+                let mut text = format!(
                     "{} - {} [{:.0}%]",
                     status,
                     entry.name,
                     player.current_progress() * 100.0
-                )));
+                );
+                // Time left until the playlist's finale, if it counts down
+                if let Some(deadline) = player.deadline() {
+                    let left = deadline
+                        .duration_since(SystemTime::now())
+                        .unwrap_or_default()
+                        .as_secs();
+                    let _ = write!(
+                        text,
+                        " T-{}:{:02}:{:02}",
+                        left / 3600,
+                        left / 60 % 60,
+                        left % 60
+                    );
+                }
+                self.status_bar.set_custom_text(Some(&text));
             }
         }
 
         // First-time initialization
         if !self.buffer.has_content() {
             self.terminal.enter_alternate_screen()?;
-            self.buffer.prepare_text(text)?;
+            match self.live_art.as_mut() {
+                Some(live) => {
+                    self.content = live.generator.generate_at(live.art, live.time);
+                    self.buffer.prepare_text(&self.content)?;
+                }
+                None => self.buffer.prepare_text(text)?,
+            }
             self.scroll.set_total_lines(self.buffer.line_count());
             let visible_range = self.scroll.get_visible_range();
             self.buffer.update_colors(&self.engine, visible_range.0)?;
@@ -281,217 +749,1880 @@ impl Renderer {
             return Ok(());
         }
 
+        self.record_scene();
+
         // Update pattern animation
-        self.engine.update(delta_seconds);
+        let started = Instant::now();
+        if !self.advance_sweep(delta_seconds)? {
+            self.engine.advance(frame_time);
+        }
+        if self.config.show_progress {
+            self.status_bar
+                .set_progress(self.engine.clock().cycle_progress());
+        }
+        self.buffer.camera_mut().advance(delta_seconds);
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.update(delta_seconds);
+        }
+        self.advance_live_art(delta_seconds)?;
+        let time_up = self.advance_countdown()?;
+        let updated = Instant::now();
+        self.timings.record(FrameStage::Update, updated - started);
 
         // Update colors and render
         let visible_range = self.scroll.get_visible_range();
         self.buffer.update_colors(&self.engine, visible_range.0)?;
+        let colored = Instant::now();
+        self.timings.record(FrameStage::Colors, colored - updated);
 
-        let mut stdout = self.terminal.stdout();
+        self.buffer.begin_interlaced_frame();
+        let mut frame = self.start_frame()?;
         self.buffer.render_region(
-            &mut stdout,
+            &mut frame,
             visible_range.0,
             visible_range.1,
             self.terminal.colors_enabled(),
             true,
         )?;
+        self.render_overlays(&mut frame)?;
 
         // Update FPS counter
         self.frame_count += 1;
+        self.record_stats();
         let now = Instant::now();
         if now.duration_since(self.last_fps_update) >= Duration::from_secs(1) {
             self.current_fps = self.frame_count as f64;
             self.frame_count = 0;
             self.last_fps_update = now;
             self.status_bar.set_fps(self.current_fps);
+            self.timings.roll();
+            if self.slow_frames.roll() && self.auto_profile && self.profile == RenderProfile::Full {
+                self.set_profile(RenderProfile::Minimal);
+                self.status_bar.notify(
+                    "Frames are running slow; switched to the minimal profile",
+                    Severity::Warn,
+                );
+            }
+            if self.cpu_budget.as_mut().is_some_and(CpuBudget::update) {
+                self.apply_cpu_budget();
+            }
+            self.autosave_stats(now);
         }
 
         // Update status bar
-        self.status_bar.render(&mut stdout, &self.scroll)?;
+        self.status_bar.render(&mut frame, &self.scroll)?;
+        if time_up {
+            queue!(frame, Print('\x07'))?;
+        }
+        let drawn = Instant::now();
+        self.timings.record(FrameStage::Draw, drawn - colored);
 
-        stdout.flush()?;
+        self.finish_frame(frame)?;
+        self.timings.record(FrameStage::Write, drawn.elapsed());
+        self.slow_frames
+            .frame(started.elapsed(), self.frame_duration());
         self.last_frame = Some(now);
+        self.error_policy.on_success(LoopStage::Frame);
+
+        Ok(())
+    }
+
+    /// Reports an error raised in the event loop, showing it as a toast, and
+    /// returns how the loop should recover from it
+    pub fn recover(&mut self, stage: LoopStage, error: &RendererError) -> Recovery {
+        let recovery = self.error_policy.on_error(stage);
+        warn!("{} failed ({:?}): {}", stage, recovery, error);
+        self.status_bar
+            .notify(&format!("{} failed: {}", stage, error), Severity::Error);
+        recovery
+    }
+
+    /// Returns the toast shown in the status bar, if any
+    pub fn toast(&self) -> Option<&str> {
+        self.status_bar.toast()
+    }
+
+    /// Sets the art options (rain, countdown, ...) used for demo art
+    pub fn set_art_settings(&mut self, settings: ArtSettings) {
+        self.art_settings = settings;
+    }
+
+    /// Shifts the colors from the current theme toward theme `name` as the
+    /// clock art's countdown runs out, or stops shifting when None
+    pub fn set_countdown_theme(&mut self, name: Option<&str>) -> Result<(), RendererError> {
+        self.countdown_shift = match name {
+            Some(name) => Some(CountdownShift::new(
+                themes::get_theme(self.status_bar.current_theme())?,
+                themes::get_theme(name)?,
+            )),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Returns how far the clock art's countdown has run, from 0 to 1, when
+    /// one is on screen
+    pub fn countdown_progress(&self) -> Option<f64> {
+        let countdown = self.art_settings.countdown?.as_secs_f64();
+        let live = self
+            .live_art
+            .as_ref()
+            .filter(|live| live.art == DemoArt::Clock)?;
+        Some(if countdown > 0.0 {
+            (live.time / countdown).min(1.0)
+        } else {
+            1.0
+        })
+    }
+
+    /// Moves the countdown's theme shift on; returns true on the frame the
+    /// countdown ends, when the bell rings
+    fn advance_countdown(&mut self) -> Result<bool, RendererError> {
+        let Some(progress) = self.countdown_progress() else {
+            return Ok(false);
+        };
+        if let Some(theme) = self
+            .countdown_shift
+            .as_mut()
+            .map(|shift| shift.theme_at(progress))
+            .transpose()?
+            .flatten()
+        {
+            self.engine.set_theme(&theme)?;
+        }
+
+        let time_up = progress >= 1.0 && !self.countdown_rung;
+        if time_up {
+            self.countdown_rung = true;
+            self.status_bar.show_toast("Time's up!");
+        }
+        Ok(time_up)
+    }
+
+    /// Sets the demo art being displayed. The renderer then owns the art:
+    /// animated art is regenerated on every frame, and all art is
+    /// regenerated to fit when the terminal is resized.
+    pub fn set_demo_art(&mut self, art: DemoArt) {
+        self.start_live_art(art, self.art_settings.clone());
+    }
+
+    /// Colors the lines of unified diffs by kind with `palettes`, or stops
+    /// when None
+    pub fn set_diff(&mut self, palettes: Option<DiffPalettes>) {
+        self.buffer.set_diff(palettes);
+    }
+
+    /// Colors table columns each with their own palette, or stops when None
+    pub fn set_columns(&mut self, columns: Option<Columns>) {
+        self.buffer.set_columns(columns);
+    }
+
+    /// Colors each line by the number in one of its fields rather than by
+    /// the pattern, or stops when None
+    pub fn set_data(&mut self, data: Option<DataColumn>) {
+        self.buffer.set_data(data);
+    }
+
+    /// Sets how new demo art replaces the art on screen
+    pub fn set_art_transition(&mut self, transition: ArtTransition) {
+        self.art_transition = transition;
+    }
+
+    /// Returns true while demo art is transitioning to new art
+    pub fn in_art_transition(&self) -> bool {
+        self.live_art
+            .as_ref()
+            .is_some_and(|live| live.outgoing.is_some())
+    }
+
+    /// Starts generating demo art with the given settings. Different art
+    /// replacing art already on screen transitions in.
+    fn start_live_art(&mut self, art: DemoArt, settings: ArtSettings) {
+        let (width, height) = self.terminal.size();
+        let settings = settings
+            .with_size(width, height.saturating_sub(2))
+            .with_headers(false);
+        info!("Displaying demo art: {}", art.display_name());
+        let replacing = self.live_art.as_ref().is_some_and(|live| live.art != art);
+        let outgoing = (replacing
+            && self.profile.art_transition(self.art_transition) != ArtTransition::Cut
+            && self.buffer.has_content())
+        .then(|| (self.content.clone(), 0.0));
+        self.live_art = Some(LiveArt {
+            generator: DemoArtGenerator::new(settings),
+            art,
+            time: 0.0,
+            outgoing,
+        });
+    }
+
+    /// Advances animated demo art and transitions, and swaps in the new
+    /// frame's text
+    fn advance_live_art(&mut self, delta_seconds: f64) -> Result<(), RendererError> {
+        let duration = self.art_transition_seconds();
+        let Some(live) = self.live_art.as_mut() else {
+            return Ok(());
+        };
+        let transitioning = live.outgoing.is_some();
+        if let Some((_, elapsed)) = live.outgoing.as_mut() {
+            *elapsed += delta_seconds;
+            if *elapsed >= duration {
+                live.outgoing = None;
+            }
+        }
+        if live.art.is_animated() {
+            live.time += delta_seconds;
+        } else if !transitioning {
+            return Ok(());
+        }
+        self.refresh_live_art()
+    }
+
+    /// Returns how long art transitions take, shortened along with the
+    /// scenes as a playlist's countdown nears its end
+    fn art_transition_seconds(&self) -> f64 {
+        let pace = self.playlist_player.as_ref().map_or(1.0, |p| p.pace());
+        ART_TRANSITION_DURATION.as_secs_f64() * pace
+    }
+
+    /// Regenerates demo art at its current time, mixed with the outgoing art
+    /// during a transition, re-preparing the buffer only if the frame changed
+    fn refresh_live_art(&mut self) -> Result<(), RendererError> {
+        let duration = self.art_transition_seconds();
+        let Some(live) = self.live_art.as_mut() else {
+            return Ok(());
+        };
 
+        let mut frame = live.generator.generate_at(live.art, live.time);
+        if let Some((outgoing, elapsed)) = &live.outgoing {
+            let progress = elapsed / duration;
+            frame = self.art_transition.frame(outgoing, &frame, progress);
+        }
+        if frame != self.content {
+            self.content = frame;
+            self.buffer.prepare_text(&self.content)?;
+            self.scroll.set_total_lines(self.buffer.line_count());
+        }
         Ok(())
     }
 
     /// Handles terminal resize events
     pub fn handle_resize(&mut self, new_width: u16, new_height: u16) -> Result<(), RendererError> {
         self.terminal.resize(new_width, new_height)?;
-        self.scroll.update_viewport(new_height.saturating_sub(2));
+        self.scroll.update_viewport(text_rows(new_height, &self.config));
         self.buffer.resize((new_width, new_height))?;
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.resize(new_width as usize, new_height.saturating_sub(2) as usize);
+        }
         self.status_bar.resize((new_width, new_height));
+
+        // Demo art is redrawn for the new size; other text is reflowed by
+        // the buffer, which can change how many rows it takes up
+        if let Some(live) = self.live_art.as_mut() {
+            live.generator.resize(new_width, new_height.saturating_sub(2));
+        }
+        self.refresh_live_art()?;
+        self.scroll.set_total_lines(self.buffer.line_count());
         self.scroll.validate_viewport();
+
         self.draw_full_screen()?;
         Ok(())
     }
 
+    /// Captures the visible part of the current frame
+    pub fn capture_frame(&self) -> Frame {
+        let (start, end) = self.scroll.get_visible_range();
+        let rows = text_rows(self.terminal.size().1, &self.config) as usize;
+        self.buffer.capture(start, rows.max(end - start))
+    }
+
+    /// Saves the current frame as a timestamped PNG in the working directory
+    fn save_screenshot(&self) -> Result<std::path::PathBuf, RendererError> {
+        let path = std::path::PathBuf::from(format!(
+            "chromacat-{}.png",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::write(&path, self.capture_frame().to_png()?)?;
+        info!("Saved screenshot to {}", path.display());
+        Ok(path)
+    }
+
     /// Handles keyboard input events
     pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool, RendererError> {
-        match key.code {
-            KeyCode::Char('t') | KeyCode::Char('T') => {
-                self.next_theme()?;
-                self.draw_full_screen()?;
-                Ok(true)
+        if self.search_prompt.is_some() {
+            return self.handle_search_key(key);
+        }
+        if self.picker.is_some() {
+            return self.handle_picker_key(key);
+        }
+        if self.help_scroll.is_some() && self.handle_help_key(key)? {
+            return Ok(true);
+        }
+        // The first binding that applies in the current state handles the key
+        for action in keys::actions_for(&key) {
+            if self.run_key_action(action, key)? {
+                return Ok(true);
             }
-            KeyCode::Char('p') | KeyCode::Char('P') => {
-                self.next_pattern()?;
-                self.draw_full_screen()?;
+        }
+        match self.scroll.handle_key_event(key) {
+            Action::Continue => {
+                let visible_range = self.scroll.get_visible_range();
+                self.buffer
+                    .update_colors_scrolled(&self.engine, visible_range.0)?;
+                let mut frame = self.start_frame()?;
+                self.buffer.render_region(
+                    &mut frame,
+                    visible_range.0,
+                    visible_range.1,
+                    self.terminal.colors_enabled(),
+                    true,
+                )?;
+                self.render_overlays(&mut frame)?;
+                self.status_bar.render(&mut frame, &self.scroll)?;
+                self.finish_frame(frame)?;
                 Ok(true)
             }
-            // Playlist controls
-            KeyCode::Char(' ') if self.playlist_player.is_some() => {
-                if let Some(player) = &mut self.playlist_player {
-                    player.toggle_pause();
-                    if let Some(entry) = player.current_entry() {
-                        let status = if player.is_paused() {
-                            "Paused"
-                        } else {
-                            "Playing"
-                        };
-                        self.status_bar.set_custom_text(Some(&format!(
-                            "{} - {} [{:.0}%]",
-                            status,
-                            entry.name,
-                            player.current_progress() * 100.0
-                        )));
-                    }
-                }
-                Ok(true)
+            Action::Exit => Ok(false),
+            Action::NoChange => Ok(true),
+        }
+    }
+
+    /// Handles a mouse event: the wheel scrolls what is under the pointer,
+    /// clicks pick from the quick switcher and the favorites, and dragging
+    /// over the animation pans the pattern
+    pub fn handle_mouse_event(&mut self, event: MouseEvent) -> Result<(), RendererError> {
+        let (column, row) = (event.column, event.row);
+        let over = self.panel_at(column as usize, row as usize);
+        match event.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = event.kind == MouseEventKind::ScrollUp;
+                self.scroll_under_pointer(over.map(|(kind, _)| kind), up)?;
             }
-            KeyCode::Right if self.playlist_player.is_some() => {
-                if let Some(player) = &mut self.playlist_player {
-                    player.next_entry();
-                    self.update_playlist_entry()?;
+            MouseEventKind::Down(MouseButton::Left) => {
+                let double = self.clicks.click(column, row, Instant::now());
+                match over {
+                    Some((kind, area)) => self.click_panel(kind, row as usize - area.y, double)?,
+                    None => self.drag_from = Some((column, row)),
                 }
-                Ok(true)
             }
-            KeyCode::Left if self.playlist_player.is_some() => {
-                if let Some(player) = &mut self.playlist_player {
-                    player.previous_entry();
-                    self.update_playlist_entry()?;
-                }
-                Ok(true)
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let Some((from_column, from_row)) = self.drag_from.replace((column, row)) else {
+                    return Ok(());
+                };
+                // The pattern follows the pointer, so it pans the other way
+                let (width, height) = self.panel_viewport();
+                let dx = (from_column as f64 - column as f64) / width.max(1) as f64;
+                let dy = (from_row as f64 - row as f64) / height.max(1) as f64;
+                self.pan_pattern(dx / PATTERN_PAN_STEP, dy / PATTERN_PAN_STEP);
+                self.redraw()?;
             }
-            _ => match self.scroll.handle_key_event(key) {
-                Action::Continue => {
-                    let visible_range = self.scroll.get_visible_range();
-                    self.buffer.update_colors(&self.engine, visible_range.0)?;
-                    let mut stdout = self.terminal.stdout();
-                    self.buffer.render_region(
-                        &mut stdout,
-                        visible_range.0,
-                        visible_range.1,
-                        self.terminal.colors_enabled(),
-                        true,
-                    )?;
-                    self.status_bar.render(&mut stdout, &self.scroll)?;
-                    stdout.flush()?;
-                    Ok(true)
-                }
-                Action::Exit => Ok(false),
-                Action::NoChange => Ok(true),
-            },
+            MouseEventKind::Up(_) => self.drag_from = None,
+            _ => {}
         }
-    }
-
-    // Private helper methods
-
-    fn draw_full_screen(&mut self) -> Result<(), RendererError> {
-        let mut stdout = self.terminal.stdout();
-        let visible_range = self.scroll.get_visible_range();
-
-        self.buffer.render_region(
-            &mut stdout,
-            visible_range.0,
-            visible_range.1,
-            self.terminal.colors_enabled(),
-            true,
-        )?;
-        self.status_bar.render(&mut stdout, &self.scroll)?;
-
-        stdout.flush()?;
         Ok(())
     }
 
-    fn update_playlist_entry(&mut self) -> Result<(), RendererError> {
-        if let Some(player) = &mut self.playlist_player {
-            if let Some(entry) = player.current_entry() {
-                let new_config = entry.to_pattern_config()?;
-                let new_gradient = themes::get_theme(&entry.theme)?.create_gradient()?;
-
-                self.engine.update_gradient(new_gradient);
-                self.engine.update_pattern_config(new_config);
-
-                // Update art type for demo mode
-                if self.demo_mode {
-                    if let Some(art) = entry.art {
-                        // Create new input reader with the entry's art type
-                        let mut reader = InputReader::from_demo(true, None, Some(&art))?;
-                        let mut new_content = String::new();
-                        reader.read_to_string(&mut new_content)?;
-                        self.content = new_content;
-                        
-                        // Prepare the new content for rendering
-                        self.buffer.prepare_text(&self.content)?;
-                        self.scroll.set_total_lines(self.buffer.line_count());
-                    }
+    /// Scrolls the quick switcher's hits or the key help when the pointer
+    /// is over them, and otherwise the text, by a wheel notch
+    fn scroll_under_pointer(
+        &mut self,
+        over: Option<PanelKind>,
+        up: bool,
+    ) -> Result<(), RendererError> {
+        let delta = if up { -1 } else { 1 };
+        match over {
+            Some(PanelKind::Switcher) => {
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.move_selection(delta);
                 }
-
-                // Update status bar
-                self.status_bar.set_pattern(&entry.pattern);
-                self.status_bar.set_theme(&entry.theme);
             }
+            Some(PanelKind::Help) => {
+                let rows = self
+                    .panel_area(PanelKind::Help)
+                    .map_or(0, |area| area.height);
+                let scroll = self
+                    .help_scroll
+                    .unwrap_or(0)
+                    .saturating_add_signed(delta * WHEEL_LINES as isize);
+                self.help_scroll = Some(scroll.min(keys::max_help_scroll(rows)));
+            }
+            _ if up => self.scroll.scroll_up(WHEEL_LINES as i32),
+            _ => self.scroll.scroll_down(WHEEL_LINES as i32),
         }
-        Ok(())
-    }
-
-    /// Switches to the next available theme
-    fn next_theme(&mut self) -> Result<(), RendererError> {
-        // Increment theme index
-        self.current_theme_index = (self.current_theme_index + 1) % self.available_themes.len();
-        let new_theme = &self.available_themes[self.current_theme_index];
-
-        // Update theme
-        let new_gradient = themes::get_theme(new_theme)?.create_gradient()?;
-        self.engine.update_gradient(new_gradient);
-
-        // Update status bar
-        self.status_bar.set_theme(new_theme);
-
-        Ok(())
+        self.draw_full_screen()
     }
 
-    /// Switches to the next available pattern
-    fn next_pattern(&mut self) -> Result<(), RendererError> {
-        // Increment pattern index
-        self.current_pattern_index =
-            (self.current_pattern_index + 1) % self.available_patterns.len();
-        let new_pattern = &self.available_patterns[self.current_pattern_index];
-
-        // Create new pattern config
-        let new_config = PatternConfig {
-            common: self.engine.config().common.clone(),
-            params: crate::pattern::REGISTRY
-                .create_pattern_params(new_pattern)
-                .ok_or_else(|| RendererError::InvalidPattern(new_pattern.clone()))?,
+    /// Handles a click on `row` of the panel `kind`, counting from its top:
+    /// a hit in the quick switcher is selected, and applied on a
+    /// double-click, and a double-clicked favorite is loaded
+    fn click_panel(
+        &mut self,
+        kind: PanelKind,
+        row: usize,
+        double: bool,
+    ) -> Result<(), RendererError> {
+        // The first row of both lists is their title
+        let Some(index) = row.checked_sub(1) else {
+            return Ok(());
         };
-
-        // Update engine
-        self.engine.update_pattern_config(new_config);
-
-        // Update status bar
-        self.status_bar.set_pattern(new_pattern);
-
-        Ok(())
-    }
-}
-
-impl Drop for Renderer {
-    fn drop(&mut self) {
-        if let Err(e) = self.terminal.cleanup() {
-            eprintln!("Error cleaning up terminal: {}", e);
+        match kind {
+            PanelKind::Switcher if index < MAX_LISTED_HITS => {
+                let Some(picker) = self.picker.as_mut() else {
+                    return Ok(());
+                };
+                let index = picker.first_listed() + index;
+                if index >= picker.hits().count() {
+                    return Ok(());
+                }
+                picker.select(index);
+                if double {
+                    let picked = picker.selected().cloned();
+                    self.close_picker();
+                    if let Some(item) = picked {
+                        self.apply_pick(&item)?;
+                    }
+                }
+            }
+            PanelKind::Favorites if double => {
+                let listed = self
+                    .favorites
+                    .as_ref()
+                    .map_or(0, |favorites| favorites.entries().len())
+                    .min(MAX_LISTED_FAVORITES);
+                if index >= listed {
+                    return Ok(());
+                }
+                self.select_favorite(index)?;
+                self.set_favorites_open(false);
+            }
+            _ => return Ok(()),
         }
+        self.draw_full_screen()
     }
+
+    /// Does what `action` does for `key`, returning false if the action
+    /// doesn't apply in the current state so the next binding is tried.
+    /// Scrolling and quitting are left to the scroll state.
+    fn run_key_action(&mut self, action: KeyAction, key: KeyEvent) -> Result<bool, RendererError> {
+        match action {
+            KeyAction::NextTheme => {
+                self.next_theme()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::NextPattern => {
+                self.next_pattern()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::Reseed if self.engine.uses_noise() => {
+                self.reseed();
+                self.draw_full_screen()?;
+            }
+            KeyAction::Surprise => {
+                self.surprise()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::NextPreset => {
+                self.next_preset()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::InspectParam => {
+                self.inspect_next_param()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::StopInspecting if self.sweep.is_some() => {
+                self.stop_inspecting()?;
+                self.draw_full_screen()?;
+            }
+            KeyAction::Screenshot => {
+                let message = match self.save_screenshot() {
+                    Ok(path) => format!("Saved {}", path.display()),
+                    Err(e) => format!("Screenshot failed: {}", e),
+                };
+                self.status_bar.set_custom_text(Some(&message));
+                self.draw_full_screen()?;
+            }
+            KeyAction::CopyCommand => {
+                self.copy_command()?;
+                self.draw_full_screen()?;
+            }
+            // Camera over the virtual canvas
+            KeyAction::PanCamera => {
+                let (dx, dy) = match key.code {
+                    KeyCode::Char('h') => (-1, 0),
+                    KeyCode::Char('l') => (1, 0),
+                    KeyCode::Char('k') => (0, -1),
+                    _ => (0, 1),
+                };
+                self.buffer.camera_mut().pan_by(dx, dy);
+                self.show_camera_status()?;
+            }
+            KeyAction::ZoomCamera => {
+                let steps = if key.code == KeyCode::Char('x') { -1 } else { 1 };
+                self.buffer.camera_mut().zoom_by(steps);
+                self.show_camera_status()?;
+            }
+            KeyAction::ResetCamera => {
+                self.buffer.camera_mut().reset();
+                self.show_camera_status()?;
+            }
+            KeyAction::ToggleDrift => {
+                self.buffer.camera_mut().toggle_drift();
+                self.show_camera_status()?;
+            }
+            // Pattern zoom and pan, for every pattern
+            KeyAction::PanPattern => {
+                let (dx, dy) = match key.code {
+                    KeyCode::Left => (-1.0, 0.0),
+                    KeyCode::Right => (1.0, 0.0),
+                    KeyCode::Up => (0.0, -1.0),
+                    _ => (0.0, 1.0),
+                };
+                self.pan_pattern(dx, dy);
+                self.redraw()?;
+            }
+            KeyAction::ZoomPattern => {
+                self.zoom_pattern(if key.code == KeyCode::Char('>') { 1 } else { -1 });
+                self.redraw()?;
+            }
+            KeyAction::ResetPattern => {
+                self.set_pattern_view(1.0, 0.0, 0.0);
+                self.redraw()?;
+            }
+            KeyAction::ToggleGrain => {
+                self.toggle_grain();
+                self.redraw()?;
+            }
+            // Favorites
+            KeyAction::Bookmark => {
+                self.bookmark();
+                self.draw_full_screen()?;
+            }
+            KeyAction::ToggleFavorites => {
+                self.set_favorites_open(!self.favorites_open);
+                self.draw_full_screen()?;
+            }
+            KeyAction::PickFavorite if self.favorites_open => {
+                if let KeyCode::Char(digit) = key.code {
+                    self.select_favorite(digit as usize - '1' as usize)?;
+                }
+                self.set_favorites_open(false);
+                self.draw_full_screen()?;
+            }
+            KeyAction::CloseFavorites if self.favorites_open => {
+                self.set_favorites_open(false);
+                self.draw_full_screen()?;
+            }
+            KeyAction::ToggleLegend => {
+                self.set_legend(!self.legend_shown());
+                self.draw_full_screen()?;
+            }
+            KeyAction::ToggleHistory => {
+                self.set_history_shown(!self.history_shown);
+                self.draw_full_screen()?;
+            }
+            KeyAction::MovePanels => {
+                self.move_panels();
+                self.draw_full_screen()?;
+            }
+            KeyAction::ToggleDebug => {
+                self.set_debug_overlay(!self.debug_overlay);
+                self.draw_full_screen()?;
+            }
+            KeyAction::ScrollSideways if self.buffer.wrap_mode().allows_horizontal_scroll() => {
+                let offset = if key.code == KeyCode::Right {
+                    self.buffer.h_offset() + HORIZONTAL_SCROLL_STEP
+                } else {
+                    self.buffer.h_offset().saturating_sub(HORIZONTAL_SCROLL_STEP)
+                };
+                self.buffer.set_h_offset(offset);
+                self.draw_full_screen()?;
+            }
+            // Animation clock
+            KeyAction::Speed => {
+                self.change_speed(key.code != KeyCode::Char('-'));
+                self.draw_full_screen()?;
+            }
+            KeyAction::Pause => {
+                self.set_paused(!self.is_paused());
+                self.show_clock_status();
+                self.draw_full_screen()?;
+            }
+            KeyAction::StepFrame => {
+                let frame = self.frame_duration().as_secs_f64();
+                self.set_paused(true);
+                self.step(if key.code == KeyCode::Char('.') { frame } else { -frame })?;
+            }
+            KeyAction::Scrub => {
+                let seconds = if key.code == KeyCode::Char(']') {
+                    SCRUB_SECONDS
+                } else {
+                    -SCRUB_SECONDS
+                };
+                self.step(seconds)?;
+            }
+            // Search
+            KeyAction::Search => {
+                self.search_prompt = Some(String::new());
+                self.status_bar.set_custom_text(Some("/"));
+                self.draw_full_screen()?;
+            }
+            KeyAction::NextMatch if self.search.is_some() => {
+                let target = self.search.as_mut().and_then(|search| {
+                    if key.code == KeyCode::Char('n') {
+                        search.next_match()
+                    } else {
+                        search.previous_match()
+                    }
+                });
+                self.show_match(target)?;
+            }
+            // Quick switcher
+            KeyAction::QuickSwitch => {
+                self.picker = Some(Picker::new(self.pick_items()));
+                self.close_panel();
+                self.draw_full_screen()?;
+            }
+            KeyAction::Help => {
+                self.set_help_open(true);
+                self.draw_full_screen()?;
+            }
+            // Playlist controls
+            KeyAction::ChangeScene if self.playlist_player.is_some() => {
+                if let Some(player) = &mut self.playlist_player {
+                    if key.code == KeyCode::Right {
+                        player.next_entry();
+                    } else {
+                        player.previous_entry();
+                    }
+                    self.update_playlist_entry()?;
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Returns whether the search prompt is open and taking key presses
+    pub fn search_open(&self) -> bool {
+        self.search_prompt.is_some()
+    }
+
+    /// Returns whether the quick switcher is open and taking key presses
+    pub fn picker_open(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    /// Returns whether the key help is open
+    pub fn help_open(&self) -> bool {
+        self.help_scroll.is_some()
+    }
+
+    /// Returns how many bindings the key help is scrolled down, while it
+    /// is open
+    pub fn help_scroll(&self) -> Option<usize> {
+        self.help_scroll
+    }
+
+    /// Opens the key help at the top, or closes it
+    pub fn set_help_open(&mut self, open: bool) {
+        if open != self.help_open() {
+            self.close_panel();
+        }
+        self.help_scroll = open.then_some(0);
+    }
+
+    /// Returns the quick switcher, while it is open
+    pub fn picker(&self) -> Option<&Picker> {
+        self.picker.as_ref()
+    }
+
+    /// Returns the last search, if any
+    pub fn last_search(&self) -> Option<&Search> {
+        self.search.as_ref()
+    }
+
+    /// Searches the text for `query` and scrolls to the first matching line
+    /// at or below the top of the screen
+    pub fn search(&mut self, query: &str) -> Result<(), RendererError> {
+        let top = self.buffer.source_line(self.scroll.top_line).unwrap_or(0);
+        let mut search = Search::new(query, self.buffer.text());
+        let target = search.first_from(top);
+        self.search = Some(search);
+        self.show_match(target)
+    }
+
+    /// Switches the active theme by name
+    pub fn set_theme(&mut self, name: &str) -> Result<(), RendererError> {
+        self.engine.set_theme(&themes::get_theme(name)?)?;
+
+        if let Some(index) = self.available_themes.iter().position(|t| t == name) {
+            self.current_theme_index = index;
+        }
+
+        // Update status bar
+        self.status_bar.set_theme(name);
+
+        Ok(())
+    }
+
+    /// Switches the active pattern by id, keeping the common parameters and
+    /// the layers blended on top
+    pub fn set_pattern(&mut self, id: &str) -> Result<(), RendererError> {
+        let params = crate::pattern::REGISTRY
+            .create_pattern_params(id)
+            .ok_or_else(|| RendererError::InvalidPattern(id.to_string()))?;
+        self.engine.update_pattern_config(PatternConfig {
+            common: self.engine.config().common.clone(),
+            params,
+            layers: self.engine.config().layers.clone(),
+        });
+
+        if let Some(index) = self.available_patterns.iter().position(|p| p == id) {
+            self.current_pattern_index = index;
+        }
+        self.status_bar.set_pattern(id);
+
+        Ok(())
+    }
+
+    /// Replaces the playlist and starts its first entry
+    pub fn load_playlist(&mut self, playlist: Playlist) -> Result<(), RendererError> {
+        self.playlist_player = Some(PlaylistPlayer::new(playlist));
+        self.update_playlist_entry()
+    }
+
+    /// Reloads the playlist from `path` at scene changes whenever the file
+    /// is edited
+    pub fn watch_playlist(&mut self, path: &std::path::Path) {
+        if let Some(player) = &mut self.playlist_player {
+            player.watch(path);
+        }
+    }
+
+    /// Advances to the next playlist entry
+    pub fn next_scene(&mut self) -> Result<(), RendererError> {
+        let player = self.playlist_player.as_mut().ok_or("No playlist is loaded")?;
+        player.next_entry();
+        self.update_playlist_entry()
+    }
+
+    /// Goes back to the previous playlist entry
+    pub fn previous_scene(&mut self) -> Result<(), RendererError> {
+        let player = self.playlist_player.as_mut().ok_or("No playlist is loaded")?;
+        player.previous_entry();
+        self.update_playlist_entry()
+    }
+
+    /// Returns the pattern animation time
+    pub fn animation_time(&self) -> f64 {
+        self.engine.time()
+    }
+
+    /// Sets the pattern animation time
+    pub fn set_animation_time(&mut self, time: f64) {
+        self.engine.set_time(time);
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.set_time(time);
+        }
+    }
+
+    /// Returns whether the animation is paused
+    pub fn is_paused(&self) -> bool {
+        self.engine.is_paused()
+    }
+
+    /// Pauses or resumes the animation clock and the playlist timer
+    pub fn set_paused(&mut self, paused: bool) {
+        self.engine.set_paused(paused);
+        if let Some(player) = &mut self.playlist_player {
+            if player.is_paused() != paused {
+                player.toggle_pause();
+            }
+        }
+    }
+
+    /// Returns the seconds of animation time per second of wall time
+    pub fn animation_rate(&self) -> f64 {
+        self.engine.clock().rate()
+    }
+
+    /// Returns the factor the animation speed is scaled by
+    pub fn speed_multiplier(&self) -> f64 {
+        self.engine.speed_multiplier()
+    }
+
+    /// Scales the animation speed of the pattern and every region, without
+    /// changing the frame rate
+    pub fn set_speed_multiplier(&mut self, multiplier: f64) {
+        self.engine.set_speed_multiplier(multiplier);
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.set_speed_multiplier(multiplier);
+        }
+    }
+
+    /// Sets the zoom and pan applied to every pattern and flashes them; see
+    /// [`PatternSpace`](crate::pattern::PatternSpace)
+    pub fn set_pattern_view(&mut self, zoom: f64, pan_x: f64, pan_y: f64) {
+        let (min, max) = PATTERN_ZOOM_RANGE;
+        let mut config = self.engine.config().clone();
+        config.common.zoom = zoom.clamp(min, max);
+        config.common.pan_x = pan_x.clamp(-MAX_PATTERN_PAN, MAX_PATTERN_PAN);
+        config.common.pan_y = pan_y.clamp(-MAX_PATTERN_PAN, MAX_PATTERN_PAN);
+        self.engine.update_pattern_config(config);
+
+        let space = self.engine.space();
+        let (pan_x, pan_y) = space.pan();
+        self.status_bar.show_toast(&format!(
+            "Pattern {:.2}× at {:+.2}, {:+.2}",
+            space.zoom(),
+            pan_x,
+            pan_y
+        ));
+    }
+
+    /// Moves the pattern by `dx`, `dy` pan steps, which cover the same part
+    /// of the screen at any zoom
+    pub fn pan_pattern(&mut self, dx: f64, dy: f64) {
+        let common = &self.engine.config().common;
+        let step = PATTERN_PAN_STEP / common.zoom;
+        let (zoom, pan_x, pan_y) = (common.zoom, common.pan_x + dx * step, common.pan_y + dy * step);
+        self.set_pattern_view(zoom, pan_x, pan_y);
+    }
+
+    /// Zooms the pattern in (positive) or out (negative) by `steps`
+    pub fn zoom_pattern(&mut self, steps: i32) {
+        let common = &self.engine.config().common;
+        let (zoom, pan_x, pan_y) = (
+            common.zoom * PATTERN_ZOOM_STEP.powi(steps),
+            common.pan_x,
+            common.pan_y,
+        );
+        self.set_pattern_view(zoom, pan_x, pan_y);
+    }
+
+    /// Returns the command line that shows the pattern, theme, parameters,
+    /// art, and pattern view on screen
+    pub fn command_line(&self) -> String {
+        let common = &self.engine.config().common;
+        let mut flags = Vec::new();
+        if common.zoom != 1.0 {
+            flags.push(format!("--pattern-zoom={}", common.zoom));
+        }
+        if common.pan_x != 0.0 || common.pan_y != 0.0 {
+            flags.push(format!("--pattern-pan={},{}", common.pan_x, common.pan_y));
+        }
+        self.current_scene().to_command(&flags)
+    }
+
+    /// Puts the command line for what is on screen on the clipboard, using
+    /// OSC 52, and shows it in the status bar
+    fn copy_command(&mut self) -> Result<(), RendererError> {
+        let command = self.command_line();
+        let mut frame = self.start_frame()?;
+        queue!(frame, Print(osc52_sequence(&command)))?;
+        self.finish_frame(frame)?;
+        self.status_bar.show_toast(&format!("Copied: {}", command));
+        Ok(())
+    }
+
+    /// Turns the grain overlay on or off for the pattern and every region
+    /// and flashes the change
+    pub fn toggle_grain(&mut self) {
+        let mut postprocess = self.engine.postprocess().clone();
+        let on = postprocess.grain.is_none();
+        postprocess.grain = Grain::new(if on { self.grain_amount } else { 0.0 });
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.set_postprocess(&postprocess);
+        }
+        self.engine.set_postprocess(postprocess);
+        self.status_bar
+            .show_toast(if on { "Grain on" } else { "Grain off" });
+    }
+
+    /// Moves to the next faster or slower speed step and flashes it
+    pub fn change_speed(&mut self, faster: bool) {
+        let current = self.speed_multiplier();
+        let next = if faster {
+            SPEED_STEPS.iter().copied().find(|&step| step > current + f64::EPSILON)
+        } else {
+            SPEED_STEPS.iter().copied().rev().find(|&step| step < current - f64::EPSILON)
+        };
+        let multiplier = next.unwrap_or(current);
+        self.set_speed_multiplier(multiplier);
+        self.status_bar.show_toast(&format!("Speed {}×", multiplier));
+    }
+
+    /// Moves the animation clock by `seconds`, even while paused, and
+    /// redraws; negative values go back in time
+    pub fn step(&mut self, seconds: f64) -> Result<(), RendererError> {
+        self.engine.step(seconds);
+        let time = self.engine.time();
+        if let Some(regions) = self.buffer.regions_mut() {
+            regions.set_time(time);
+        }
+        self.show_clock_status();
+        self.redraw()
+    }
+
+    /// Starts logging every scene shown from now on, for
+    /// [`save_session`](Self::save_session)
+    pub fn start_recording(&mut self) {
+        self.session = Some((SessionRecorder::new(), Instant::now()));
+    }
+
+    /// Returns the scenes logged since recording started, if it has
+    pub fn session(&self) -> Option<&SessionRecorder> {
+        self.session.as_ref().map(|(recorder, _)| recorder)
+    }
+
+    /// Writes the scenes logged so far as a playlist that replays them
+    pub fn save_session(&self, path: &std::path::Path) -> Result<(), RendererError> {
+        if let Some((recorder, started)) = &self.session {
+            recorder.save(started.elapsed(), path)?;
+        }
+        Ok(())
+    }
+
+    /// Asks the terminal what it can do before animating, as static output
+    /// gets by on what the environment says
+    pub fn probe_terminal(&mut self) {
+        self.terminal.probe_caps();
+    }
+
+    /// Starts counting the frames and scenes shown from now on, writing
+    /// them to `file`, if given, every [`STATS_SAVE_INTERVAL`]
+    pub fn start_stats(&mut self, file: Option<std::path::PathBuf>) {
+        self.stats = Some(SessionStats::new());
+        self.stats_file = file.map(|path| (path, Instant::now()));
+    }
+
+    /// Returns the counts kept since [`start_stats`](Self::start_stats)
+    pub fn stats(&self) -> Option<&SessionStats> {
+        self.stats.as_ref()
+    }
+
+    /// Uses `favorites` instead of the ones in the config directory
+    pub fn set_favorites(&mut self, favorites: Favorites) {
+        self.favorites = Some(favorites);
+    }
+
+    /// Returns the favorites, if they have been loaded
+    pub fn favorites(&self) -> Option<&Favorites> {
+        self.favorites.as_ref()
+    }
+
+    /// Returns true if the favorites panel is open
+    pub fn favorites_open(&self) -> bool {
+        self.favorites_open
+    }
+
+    /// Opens or closes the favorites panel
+    pub fn set_favorites_open(&mut self, open: bool) {
+        if open {
+            self.load_favorites();
+        }
+        if open != self.favorites_open {
+            self.close_panel();
+        }
+        self.favorites_open = open;
+    }
+
+    /// Returns whether the debug overlay is drawn
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    /// Shows or hides the debug overlay, keeping log events for it from
+    /// the first time it is shown
+    pub fn set_debug_overlay(&mut self, open: bool) {
+        if open {
+            events::start_capture();
+        }
+        if open != self.debug_overlay {
+            self.close_panel();
+        }
+        self.debug_overlay = open;
+    }
+
+    /// Returns whether the gradient legend is drawn
+    pub fn legend_shown(&self) -> bool {
+        self.legend_shown
+    }
+
+    /// Shows or hides the gradient legend
+    pub fn set_legend(&mut self, shown: bool) {
+        if shown != self.legend_shown {
+            self.close_panel();
+        }
+        self.legend_shown = shown;
+    }
+
+    /// Returns whether the message history is drawn
+    pub fn history_shown(&self) -> bool {
+        self.history_shown
+    }
+
+    /// Shows or hides the message history
+    pub fn set_history_shown(&mut self, shown: bool) {
+        if shown != self.history_shown {
+            self.close_panel();
+        }
+        self.history_shown = shown;
+    }
+
+    /// Returns the messages shown in the status bar and the history of them
+    pub fn notifications(&self) -> &Notifications {
+        self.status_bar.notifications()
+    }
+
+    /// Returns the corners the panels are drawn in
+    pub fn panel_layout(&self) -> &PanelLayout {
+        &self.panels
+    }
+
+    /// Sets the corners the panels are drawn in. A legend corner given on
+    /// the command line wins over the layout's.
+    pub fn set_panel_layout(&mut self, mut layout: PanelLayout) {
+        if let Some(corner) = self.config.legend {
+            layout.legend = corner;
+        }
+        self.panels = layout;
+        self.close_panel();
+    }
+
+    /// Returns how long each stage of the latest frames took
+    pub fn timings(&self) -> &FrameTimings {
+        &self.timings
+    }
+
+    /// Switches to the favorite at `index`, counting from zero
+    pub fn select_favorite(&mut self, index: usize) -> Result<(), RendererError> {
+        let Some(entry) = self
+            .load_favorites()
+            .and_then(|favorites| favorites.get(index))
+            .cloned()
+        else {
+            self.status_bar
+                .show_toast(&format!("No favorite {}", index + 1));
+            return Ok(());
+        };
+        self.apply_entry(&entry)?;
+        self.status_bar
+            .show_toast(&format!("Favorite {}: {}", index + 1, describe_favorite(&entry)));
+        Ok(())
+    }
+
+    /// Returns the playlist entry index and time spent in it, if a playlist
+    /// is playing
+    pub fn playlist_position(&self) -> Option<(usize, Duration)> {
+        self.playlist_player
+            .as_ref()
+            .map(|player| (player.current_index(), player.elapsed()))
+    }
+
+    /// Moves the playlist to an entry and position, switching the display
+    /// if the entry changes
+    pub fn seek_playlist(&mut self, index: usize, elapsed: Duration) -> Result<(), RendererError> {
+        let Some(player) = &mut self.playlist_player else {
+            return Ok(());
+        };
+        let previous = player.current_index();
+        player.seek(index, elapsed);
+        if player.current_index() != previous {
+            self.update_playlist_entry()?;
+        }
+        Ok(())
+    }
+
+    /// Sets whether the playlist advances on its own timer; turned off when
+    /// another instance drives it with [`seek_playlist`](Self::seek_playlist)
+    pub fn set_playlist_auto_advance(&mut self, enabled: bool) {
+        self.playlist_auto_advance = enabled;
+    }
+
+    /// Returns the id of the pattern being shown
+    pub fn pattern_id(&self) -> &str {
+        crate::pattern::REGISTRY
+            .get_pattern_id(&self.engine.config().params)
+            .unwrap_or("unknown")
+    }
+
+    /// Returns the name of the theme being shown
+    pub fn theme_name(&self) -> &str {
+        &self.available_themes[self.current_theme_index]
+    }
+
+    /// Returns the playlist entry's name and how far it has played, from 0
+    /// to 1, when a playlist is playing
+    pub fn scene_progress(&self) -> Option<(&str, f64)> {
+        let player = self.playlist_player.as_ref()?;
+        player
+            .current_entry()
+            .map(|entry| (entry.name.as_str(), player.current_progress()))
+    }
+
+    /// Describes the current pattern, theme and playlist entry
+    pub fn status(&self) -> String {
+        let mut status = format!("pattern={} theme={}", self.pattern_id(), self.theme_name());
+        if let Some((scene, _)) = self.scene_progress() {
+            let _ = write!(status, " scene=\"{}\"", scene);
+        }
+        status
+    }
+
+    /// Recolors and redraws the visible text, e.g. after a change made while paused
+    pub fn redraw(&mut self) -> Result<(), RendererError> {
+        if !self.buffer.has_content() {
+            return Ok(());
+        }
+        let visible_range = self.scroll.get_visible_range();
+        self.buffer.update_colors(&self.engine, visible_range.0)?;
+        self.draw_full_screen()
+    }
+
+    /// Switches to the next available theme
+    pub fn next_theme(&mut self) -> Result<(), RendererError> {
+        let next = (self.current_theme_index + 1) % self.available_themes.len();
+        let new_theme = self.available_themes[next].clone();
+        self.set_theme(&new_theme)
+    }
+
+    /// Switches to the next available pattern
+    pub fn next_pattern(&mut self) -> Result<(), RendererError> {
+        let next = (self.current_pattern_index + 1) % self.available_patterns.len();
+        let new_pattern = self.available_patterns[next].clone();
+        self.set_pattern(&new_pattern)
+    }
+
+    /// Applies the next preset of the current pattern, keeping the common
+    /// parameters and the layers blended on top
+    pub fn next_preset(&mut self) -> Result<(), RendererError> {
+        let registry = &crate::pattern::REGISTRY;
+        let Some(id) = registry.get_pattern_id(&self.engine.config().params) else {
+            return Ok(());
+        };
+        let presets = crate::pattern::presets::presets(id);
+        if presets.is_empty() {
+            self.status_bar.show_toast(&format!("No presets for {}", id));
+            return Ok(());
+        }
+
+        let index = match &self.current_preset {
+            Some((pattern, index)) if pattern == id => (index + 1) % presets.len(),
+            _ => 0,
+        };
+        let preset = &presets[index];
+        self.apply_params(id, &preset.params_string())?;
+        self.status_bar
+            .show_toast(&format!("Preset {}: {}", preset.name, preset.description));
+        self.current_preset = Some((id.to_string(), index));
+        Ok(())
+    }
+
+    /// Sweeps the next numeric parameter of the current pattern across its
+    /// range, or ends inspecting after the last one
+    pub fn inspect_next_param(&mut self) -> Result<(), RendererError> {
+        let after = match self.sweep.take() {
+            Some(sweep) => {
+                self.apply_params(sweep.pattern(), sweep.base())?;
+                Some(sweep.name())
+            }
+            None => None,
+        };
+
+        let registry = &crate::pattern::REGISTRY;
+        let params = &self.engine.config().params;
+        let Some(id) = registry.get_pattern_id(params) else {
+            return Ok(());
+        };
+        self.sweep = ParamSweep::next(id, &registry.format_params(params), after);
+
+        let message = match (&self.sweep, after) {
+            (Some(sweep), _) => {
+                let (min, max) = sweep.range();
+                format!(
+                    "Inspecting {} ({} to {}): {}",
+                    sweep.name(),
+                    min,
+                    max,
+                    sweep.description()
+                )
+            }
+            (None, Some(_)) => "Done inspecting".to_string(),
+            (None, None) => format!("No parameters to inspect for {}", id),
+        };
+        self.status_bar.show_toast(&message);
+        Ok(())
+    }
+
+    /// Ends a parameter sweep early, putting the parameter back
+    pub fn stop_inspecting(&mut self) -> Result<(), RendererError> {
+        if let Some(sweep) = self.sweep.take() {
+            self.apply_params(sweep.pattern(), sweep.base())?;
+            self.status_bar.show_toast("Done inspecting");
+        }
+        Ok(())
+    }
+
+    /// Returns the parameter being swept, if any
+    pub fn inspected_param(&self) -> Option<&ParamSweep> {
+        self.sweep.as_ref()
+    }
+
+    /// Moves a parameter sweep on and applies the swept value; returns
+    /// whether a sweep is holding the animation
+    fn advance_sweep(&mut self, delta_seconds: f64) -> Result<bool, RendererError> {
+        let Some(sweep) = self.sweep.as_mut() else {
+            return Ok(false);
+        };
+        // A scene change replaced the pattern being swept
+        let current = crate::pattern::REGISTRY.get_pattern_id(&self.engine.config().params);
+        if current != Some(sweep.pattern()) {
+            self.sweep = None;
+            return Ok(false);
+        }
+
+        let pattern = sweep.pattern().to_string();
+        if sweep.advance(delta_seconds) {
+            let params = sweep.params();
+            self.apply_params(&pattern, &params)?;
+        } else if let Some(sweep) = self.sweep.take() {
+            self.apply_params(&pattern, sweep.base())?;
+        }
+        Ok(true)
+    }
+
+    /// Replaces the pattern's parameters, keeping the common parameters and
+    /// the layers blended on top
+    fn apply_params(&mut self, pattern: &str, params: &str) -> Result<(), RendererError> {
+        let params = crate::pattern::REGISTRY
+            .parse_params(pattern, params)
+            .map_err(RendererError::PatternError)?;
+        self.engine.update_pattern_config(PatternConfig {
+            common: self.engine.config().common.clone(),
+            params,
+            layers: self.engine.config().layers.clone(),
+        });
+        Ok(())
+    }
+
+    /// Sets the random numbers [`surprise`](Self::surprise) and
+    /// [`reseed`](Self::reseed) draw from, e.g. seeded to repeat a run
+    pub fn set_rng(&mut self, rng: StdRng) {
+        self.rng = rng;
+    }
+
+    /// Switches to a random pattern and theme with random parameters
+    pub fn surprise(&mut self) -> Result<(), RendererError> {
+        let entry = ScenePool::default().surprise(&mut self.rng)?;
+        self.apply_entry(&entry)?;
+        let params = entry.params_string()?.unwrap_or_default();
+        info!("Surprise: {} {}", entry.description(), params);
+        self.status_bar
+            .show_toast(&format!("Surprise: {}", entry.description()));
+        Ok(())
+    }
+
+    /// Draws new noise for noise-based patterns, keeping everything else
+    pub fn reseed(&mut self) {
+        let seed = self.rng.gen();
+        self.engine.reseed(seed);
+        info!("Noise seed: {}", seed);
+        self.status_bar.show_toast(&format!("Noise seed {}", seed));
+    }
+
+    /// Renders a file header in static mode, shaded along the current gradient
+    pub fn render_file_header(&mut self, name: &str) -> Result<(), RendererError> {
+        let width = self.terminal.size().0 as usize;
+        let header = Renderer::file_header(name, width);
+
+        if !self.terminal.colors_enabled() {
+            writeln!(self.terminal, "{}", header)?;
+            return Ok(());
+        }
+
+        let support = self.terminal.caps().color;
+        let mut line = String::with_capacity(header.len() * 8);
+        line.push_str("\x1b[1m");
+        let total = header.chars().count().max(1) as f64;
+        for (i, ch) in header.chars().enumerate() {
+            let color = self.engine.color_at(i as f64 / total);
+            write!(
+                line,
+                "{}{}",
+                support.fg(
+                    (color.r * 255.0) as u8,
+                    (color.g * 255.0) as u8,
+                    (color.b * 255.0) as u8,
+                ),
+                ch
+            )?;
+        }
+        writeln!(self.terminal, "{}\x1b[0m", line)?;
+        self.terminal.flush()?;
+        Ok(())
+    }
+
+    // Private helper methods
+
+    /// Describes the pattern, theme, parameters, and art on screen
+    fn current_scene(&self) -> SessionScene {
+        let params = &self.engine.config().params;
+        SessionScene {
+            pattern: crate::pattern::REGISTRY
+                .get_pattern_id(params)
+                .unwrap_or("horizontal")
+                .to_string(),
+            theme: self.status_bar.current_theme().to_string(),
+            params: crate::pattern::REGISTRY.format_params(params),
+            art: self.live_art.as_ref().map(|live| live.art),
+        }
+    }
+
+    /// Logs the scene on screen into the session being recorded, if any
+    fn record_scene(&mut self) {
+        if self.session.is_none() {
+            return;
+        }
+        let scene = self.current_scene();
+        if let Some((recorder, started)) = &mut self.session {
+            recorder.record(started.elapsed(), scene);
+        }
+    }
+
+    /// Counts the frame being drawn in the stats, if they are being kept
+    fn record_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            let pattern = crate::pattern::REGISTRY
+                .get_pattern_id(&self.engine.config().params)
+                .unwrap_or("horizontal");
+            stats.record_frame(pattern, self.status_bar.current_theme());
+        }
+    }
+
+    /// Rewrites the stats file once [`STATS_SAVE_INTERVAL`] has passed since
+    /// it was last written, so it stays current while the animation runs
+    fn autosave_stats(&mut self, now: Instant) {
+        let (Some(stats), Some((path, saved))) = (&self.stats, &mut self.stats_file) else {
+            return;
+        };
+        if now.duration_since(*saved) < STATS_SAVE_INTERVAL {
+            return;
+        }
+        *saved = now;
+        if let Err(e) = stats.report().save(path) {
+            warn!("{}", e);
+        }
+    }
+
+    /// Returns the favorites, loading them from the config directory the
+    /// first time; a file that fails to load is reported as a toast
+    fn load_favorites(&mut self) -> Option<&mut Favorites> {
+        if self.favorites.is_none() {
+            match Favorites::load_default() {
+                Ok(favorites) => self.favorites = Some(favorites),
+                Err(e) => {
+                    self.status_bar
+                        .notify(&format!("Favorites failed to load: {}", e), Severity::Error);
+                    return None;
+                }
+            }
+        }
+        self.favorites.as_mut()
+    }
+
+    /// Moves the open panels to the next corner clockwise and saves the
+    /// layout, reporting the outcome as a toast
+    fn move_panels(&mut self) {
+        let open: Vec<PanelKind> = self.open_panels().iter().map(|&(kind, _)| kind).collect();
+        if open.is_empty() {
+            self.status_bar.show_toast("No panel open to move");
+            return;
+        }
+        self.panels.rotate(&open);
+        self.close_panel();
+        let corners: Vec<String> = open
+            .iter()
+            .map(|&kind| format!("{} {}", kind.as_str(), self.panels.corner(kind)))
+            .collect();
+        match self.panels.save() {
+            Ok(()) => self
+                .status_bar
+                .show_toast(&format!("Moved {}", corners.join(", "))),
+            Err(e) => self
+                .status_bar
+                .notify(&format!("Panel layout not saved: {}", e), Severity::Warn),
+        }
+    }
+
+    /// Bookmarks the combination on screen and saves the favorites,
+    /// reporting the outcome as a toast
+    fn bookmark(&mut self) {
+        let entry = self.current_scene().to_entry(crate::playlist::FAVORITE_SECONDS);
+        let description = describe_favorite(&entry);
+        let Some(favorites) = self.load_favorites() else {
+            return;
+        };
+        let (message, severity) = match favorites.add(entry) {
+            None => (
+                format!("Already a favorite: {}", description),
+                Severity::Info,
+            ),
+            Some(index) => match favorites.save() {
+                Ok(()) => (
+                    format!("Favorite {}: {}", index + 1, description),
+                    Severity::Info,
+                ),
+                Err(e) => (format!("Favorites failed to save: {}", e), Severity::Error),
+            },
+        };
+        self.status_bar.notify(&message, severity);
+    }
+
+    /// Shows whether the animation is paused in the status bar, with the
+    /// playlist entry or, without a playlist, the animation time
+    fn show_clock_status(&mut self) {
+        let state = if self.is_paused() { "Paused" } else { "Playing" };
+        let entry = self.playlist_player.as_ref().and_then(|player| {
+            player
+                .current_entry()
+                .map(|entry| (entry.name.clone(), player.current_progress()))
+        });
+        match entry {
+            Some((name, progress)) => self.status_bar.set_custom_text(Some(&format!(
+                "{} - {} [{:.0}%]",
+                state,
+                name,
+                progress * 100.0
+            ))),
+            None if self.is_paused() => self
+                .status_bar
+                .set_custom_text(Some(&format!("{} at {:.2}s", state, self.engine.time()))),
+            None => self.status_bar.set_custom_text(None),
+        }
+    }
+
+    /// Edits the query in the search prompt; Enter runs it, or repeats the
+    /// last search if it's empty, and Esc closes the prompt
+    fn handle_search_key(&mut self, key: KeyEvent) -> Result<bool, RendererError> {
+        let Some(prompt) = self.search_prompt.as_mut() else {
+            return Ok(true);
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let query = std::mem::take(prompt);
+                self.search_prompt = None;
+                if !query.is_empty() {
+                    return self.search(&query).map(|_| true);
+                }
+                let target = self.search.as_mut().and_then(Search::next_match);
+                self.show_match(target)?;
+                return Ok(true);
+            }
+            KeyCode::Esc => self.search_prompt = None,
+            KeyCode::Backspace => {
+                if prompt.pop().is_none() {
+                    self.search_prompt = None;
+                }
+            }
+            KeyCode::Char(c) => prompt.push(c),
+            _ => return Ok(true),
+        }
+        let text = self.search_prompt.as_ref().map(|prompt| format!("/{}", prompt));
+        self.status_bar.set_custom_text(text.as_deref());
+        self.draw_full_screen()?;
+        Ok(true)
+    }
+
+    /// Handles a key press while the quick switcher is open
+    fn handle_picker_key(&mut self, key: KeyEvent) -> Result<bool, RendererError> {
+        let Some(picker) = self.picker.as_mut() else {
+            return Ok(true);
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let picked = picker.selected().cloned();
+                let query = picker.query().to_string();
+                self.close_picker();
+                match picked {
+                    Some(item) => self.apply_pick(&item)?,
+                    None => self
+                        .status_bar
+                        .show_toast(&format!("Nothing matches: {}", query)),
+                }
+            }
+            KeyCode::Esc => self.close_picker(),
+            KeyCode::Backspace => {
+                if !picker.pop() {
+                    self.close_picker();
+                }
+            }
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::Char(c) => picker.push(c),
+            _ => return Ok(true),
+        }
+        self.draw_full_screen()?;
+        Ok(true)
+    }
+
+    /// Handles a key press while the key help is open: the arrows and page
+    /// keys scroll it, and `?` and `Esc` close it. Returns false for the
+    /// other keys, which work as usual.
+    fn handle_help_key(&mut self, key: KeyEvent) -> Result<bool, RendererError> {
+        let Some(scroll) = self.help_scroll else {
+            return Ok(false);
+        };
+        // Scrolling stops where the last binding is at the bottom of the panel
+        let rows = self
+            .panel_area(PanelKind::Help)
+            .map_or(0, |area| area.height);
+        let page = rows.saturating_sub(2).max(1);
+        let scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('?') => {
+                self.set_help_open(false);
+                self.draw_full_screen()?;
+                return Ok(true);
+            }
+            KeyCode::Up => scroll.saturating_sub(1),
+            KeyCode::Down => scroll + 1,
+            KeyCode::PageUp => scroll.saturating_sub(page),
+            KeyCode::PageDown => scroll + page,
+            KeyCode::Home => 0,
+            KeyCode::End => usize::MAX,
+            _ => return Ok(false),
+        };
+        self.help_scroll = Some(scroll.min(keys::max_help_scroll(rows)));
+        self.draw_full_screen()?;
+        Ok(true)
+    }
+
+    /// Closes the quick switcher, redrawing the text it covered
+    fn close_picker(&mut self) {
+        if self.picker.take().is_some() {
+            self.close_panel();
+        }
+    }
+
+    /// Returns the names the quick switcher offers: the patterns, the
+    /// themes, and the art in demo mode
+    fn pick_items(&self) -> Vec<PickItem> {
+        let patterns = self
+            .available_patterns
+            .iter()
+            .map(|id| PickItem::new(PickKind::Pattern, id));
+        let themes = self
+            .available_themes
+            .iter()
+            .map(|name| PickItem::new(PickKind::Theme, name));
+        let art = DemoArt::all_types()
+            .iter()
+            .filter(|_| self.demo_mode)
+            .map(|art| PickItem::new(PickKind::Art, art.as_str()));
+        patterns.chain(themes).chain(art).collect()
+    }
+
+    /// Switches to the pattern, theme, or art picked in the quick switcher
+    fn apply_pick(&mut self, item: &PickItem) -> Result<(), RendererError> {
+        match item.kind {
+            PickKind::Pattern => self.set_pattern(&item.name)?,
+            PickKind::Theme => self.set_theme(&item.name)?,
+            PickKind::Art => {
+                if let Some(art) = DemoArt::try_from_str(&item.name) {
+                    self.set_demo_art(art);
+                    self.refresh_live_art()?;
+                }
+            }
+        }
+        self.status_bar
+            .show_toast(&format!("Switched to {} {}", item.kind.as_str(), item.name));
+        Ok(())
+    }
+
+    /// Scrolls to the first row of a matching input line and describes the
+    /// match in the status bar; without a line, the view stays put
+    fn show_match(&mut self, line: Option<usize>) -> Result<(), RendererError> {
+        let Some(search) = &self.search else {
+            return Ok(());
+        };
+        let status = search.status();
+        match line.and_then(|line| self.buffer.first_row_of(line)) {
+            Some(row) => {
+                self.scroll.scroll_to(row);
+                self.status_bar.clear_toast();
+                self.status_bar.set_custom_text(Some(&status));
+            }
+            // A search that moved nowhere keeps its match on screen
+            None if search.current().is_some() => self
+                .status_bar
+                .show_toast(&format!("No more matches: {}", search.query())),
+            None => self.status_bar.show_toast(&status),
+        }
+        self.redraw()
+    }
+
+    /// Shows the camera position in the status bar and redraws the view
+    fn show_camera_status(&mut self) -> Result<(), RendererError> {
+        let camera = self.buffer.camera();
+        let (pan_x, pan_y) = camera.pan();
+        let message = if camera.is_drifting() {
+            "View drifting".to_string()
+        } else {
+            format!(
+                "View {:.2}x at {:.0}%, {:.0}%",
+                camera.zoom(),
+                pan_x * 100.0,
+                pan_y * 100.0
+            )
+        };
+        self.status_bar.set_custom_text(Some(&message));
+        self.redraw()
+    }
+
+    fn draw_full_screen(&mut self) -> Result<(), RendererError> {
+        self.record_scene();
+        let mut frame = self.start_frame()?;
+        let visible_range = self.scroll.get_visible_range();
+
+        self.buffer.render_region(
+            &mut frame,
+            visible_range.0,
+            visible_range.1,
+            self.terminal.colors_enabled(),
+            true,
+        )?;
+        self.render_overlays(&mut frame)?;
+        self.status_bar.render(&mut frame, &self.scroll)?;
+
+        self.finish_frame(frame)
+    }
+
+    /// Takes the assembler for a new frame, starting it with a clear screen
+    /// if a panel was closed
+    fn start_frame(&mut self) -> Result<FrameAssembler, RendererError> {
+        let mut frame = std::mem::take(&mut self.frame);
+        if std::mem::take(&mut self.clear_screen) {
+            queue!(frame, Clear(ClearType::All))?;
+        }
+        Ok(frame)
+    }
+
+    /// Redraws everything once a panel closes, or opens and moves the others
+    /// aside: the text a panel covered, and the blank rows below the text,
+    /// which are otherwise never drawn
+    fn close_panel(&mut self) {
+        self.buffer.mark_all_dirty();
+        self.clear_screen = true;
+    }
+
+    /// Writes an assembled frame to the terminal in one go and keeps the
+    /// assembler, with its allocation, for the next frame
+    fn finish_frame(&mut self, mut frame: FrameAssembler) -> Result<(), RendererError> {
+        let written = frame.finish(&mut self.terminal);
+        self.frame = frame;
+        Ok(written?)
+    }
+
+    /// Draws the panels open over the text, where the panel layout puts
+    /// them
+    fn render_overlays<W: Write>(&self, out: &mut W) -> Result<(), RendererError> {
+        let favorites = self.favorites.as_ref().map_or(&[][..], |f| f.entries());
+        let placed = self
+            .panels
+            .place(&self.open_panels(), self.panel_viewport());
+        // The switcher is first in the stack and takes key presses, so it is
+        // drawn last, over everything
+        for (kind, area) in placed.into_iter().rev() {
+            match kind {
+                PanelKind::Switcher => {
+                    if let Some(picker) = &self.picker {
+                        picker::render_picker_panel(out, picker, area)?;
+                    }
+                }
+                PanelKind::Help => {
+                    keys::render_help_panel(out, self.help_scroll.unwrap_or(0), area)?
+                }
+                PanelKind::Favorites => {
+                    favorites_panel::render_favorites_panel(out, favorites, area)?
+                }
+                PanelKind::Legend => {
+                    let theme = self.status_bar.current_theme();
+                    legend::render_legend(out, &self.engine, theme, area)?
+                }
+                PanelKind::History => {
+                    notifications::render_history_panel(out, self.status_bar.notifications(), area)?
+                }
+                PanelKind::Debug => debug_overlay::render_debug_overlay(
+                    out,
+                    &self.timings,
+                    self.frame_duration(),
+                    &self.transition_state(),
+                    &events::recent(DEBUG_EVENTS),
+                    area,
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the width and height panels are placed in
+    fn panel_viewport(&self) -> (usize, usize) {
+        let (width, height) = self.terminal.size();
+        (width as usize, height.saturating_sub(2) as usize)
+    }
+
+    /// Returns where `kind` is drawn, if it is open and has room
+    fn panel_area(&self, kind: PanelKind) -> Option<Rect> {
+        self.panels
+            .place(&self.open_panels(), self.panel_viewport())
+            .into_iter()
+            .find_map(|(placed, area)| (placed == kind).then_some(area))
+    }
+
+    /// Returns the panel drawn over the cell at `column`, `row`, and where
+    /// it is, if any
+    fn panel_at(&self, column: usize, row: usize) -> Option<(PanelKind, Rect)> {
+        // Panels earlier in the stack are drawn over later ones
+        self.panels
+            .place(&self.open_panels(), self.panel_viewport())
+            .into_iter()
+            .find(|(_, area)| area.contains(column, row))
+    }
+
+    /// Returns the panels that are open, in stacking order, with the width
+    /// and height each wants
+    fn open_panels(&self) -> Vec<(PanelKind, (usize, usize))> {
+        PanelKind::all()
+            .iter()
+            .filter_map(|&kind| {
+                let size = match kind {
+                    PanelKind::Switcher if self.picker.is_some() => picker::PICKER_PANEL_SIZE,
+                    PanelKind::Help if self.help_scroll.is_some() => keys::help_panel_size(),
+                    PanelKind::Favorites if self.favorites_open => {
+                        let favorites = self.favorites.as_ref().map_or(&[][..], |f| f.entries());
+                        favorites_panel::favorites_panel_size(favorites)
+                    }
+                    PanelKind::Legend if self.legend_shown => legend::LEGEND_SIZE,
+                    PanelKind::History if self.history_shown => notifications::HISTORY_PANEL_SIZE,
+                    PanelKind::Debug if self.debug_overlay => debug_overlay::DEBUG_PANEL_SIZE,
+                    _ => return None,
+                };
+                Some((kind, size))
+            })
+            .collect()
+    }
+
+    /// Describes the scene transitions for the debug overlay: the playlist
+    /// position, a scene change waiting to be retried, and art crossfades
+    fn transition_state(&self) -> Vec<String> {
+        let mut state = Vec::new();
+        let clock = if self.is_paused() { "paused" } else { "playing" };
+        match &self.playlist_player {
+            Some(player) => state.push(format!(
+                "Scene {}/{} {:.0}%, {}",
+                player.current_index() + 1,
+                player.entry_count(),
+                player.current_progress() * 100.0,
+                clock
+            )),
+            None => state.push(format!("No playlist, {} at {:.2}s", clock, self.engine.time())),
+        }
+        if self.pending_transition {
+            state.push("Scene change failed, retrying".to_string());
+        }
+        if let Some((_, elapsed)) = self.live_art.as_ref().and_then(|live| live.outgoing.as_ref()) {
+            state.push(format!(
+                "Art transition {:.0}%",
+                elapsed / self.art_transition_seconds() * 100.0
+            ));
+        }
+        state
+    }
+
+    /// Switches to the playlist's current entry. A scene that fails to load
+    /// is retried on the next frames and then skipped, while the previous
+    /// scene stays on screen.
+    fn change_scene(&mut self) {
+        let error = match self.update_playlist_entry() {
+            Ok(()) => {
+                self.pending_transition = false;
+                self.error_policy.on_success(LoopStage::Transition);
+                return;
+            }
+            Err(e) => e,
+        };
+
+        self.pending_transition = true;
+        if self.recover(LoopStage::Transition, &error) == Recovery::Skip {
+            if let Some(player) = &mut self.playlist_player {
+                let skipped = player
+                    .current_entry()
+                    .map(|entry| entry.description())
+                    .unwrap_or_default();
+                player.next_entry();
+                self.status_bar
+                    .notify(&format!("Skipped {}: {}", skipped, error), Severity::Error);
+            }
+        }
+    }
+
+    fn update_playlist_entry(&mut self) -> Result<(), RendererError> {
+        let Some(entry) = self
+            .playlist_player
+            .as_ref()
+            .and_then(|player| player.current_entry())
+            .cloned()
+        else {
+            return Ok(());
+        };
+        self.apply_entry(&entry)
+    }
+
+    /// Switches to the pattern, theme, parameters, and art of an entry
+    fn apply_entry(&mut self, entry: &PlaylistEntry) -> Result<(), RendererError> {
+        // Everything the scene needs is prepared before any of it is applied,
+        // so a scene that fails to load leaves the previous one running
+        let new_config = entry.to_pattern_config()?;
+        let new_theme = themes::get_theme(&entry.theme)?;
+        let entry_art = match entry.art {
+            // Update art type for demo mode
+            Some(art) if self.demo_mode => Some((art, entry.art_settings(&self.art_settings)?)),
+            _ => None,
+        };
+        let (pattern, theme) = (entry.pattern.clone(), entry.theme.clone());
+
+        self.engine.set_theme(&new_theme)?;
+        self.engine.update_pattern_config(new_config);
+
+        if let Some(index) = self.available_themes.iter().position(|t| *t == theme) {
+            self.current_theme_index = index;
+        }
+        if let Some(index) = self.available_patterns.iter().position(|p| *p == pattern) {
+            self.current_pattern_index = index;
+        }
+
+        // Update status bar
+        self.status_bar.set_pattern(&pattern);
+        self.status_bar.set_theme(&theme);
+
+        if let Some((art, settings)) = entry_art {
+            self.start_live_art(art, settings);
+            self.refresh_live_art()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: RenderTarget> Drop for Renderer<T> {
+    fn drop(&mut self) {
+        if let Err(e) = self.terminal.cleanup() {
+            eprintln!("Error cleaning up terminal: {}", e);
+        }
+    }
+}
+
+/// Returns the rows left for text in a terminal `height` rows tall, below
+/// the ruler and above the status bar
+fn text_rows(height: u16, config: &AnimationConfig) -> u16 {
+    height.saturating_sub(2 + config.gutter.header_rows() as u16)
 }
@@ -0,0 +1,46 @@
+//! Mouse support for the interactive viewer
+//!
+//! With `--mouse`, the wheel scrolls whatever is under the pointer: the
+//! quick switcher's hits, the key help, or otherwise the text. Clicking a
+//! hit in the switcher selects it and double-clicking applies it, while
+//! double-clicking a favorite loads it. Dragging over the animation pans
+//! the pattern so it follows the pointer. Capturing the mouse keeps the
+//! terminal from selecting text, which is why it is off by default.
+
+use std::time::{Duration, Instant};
+
+/// Longest time between the clicks of a double-click
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Lines the text scrolls per wheel notch
+pub const WHEEL_LINES: usize = 3;
+
+/// Tells double-clicks from single ones
+#[derive(Debug, Clone, Default)]
+pub struct ClickTracker {
+    /// When and where the last single click was
+    last: Option<(Instant, u16, u16)>,
+}
+
+impl ClickTracker {
+    /// Creates a tracker that has seen no clicks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a click on the cell at `column`, `row` at time `at`, and
+    /// returns whether it completes a double-click. A third click starts
+    /// over rather than making a second double-click.
+    pub fn click(&mut self, column: u16, row: u16, at: Instant) -> bool {
+        let double = self.last.is_some_and(|(last, last_column, last_row)| {
+            (last_column, last_row) == (column, row)
+                && at.saturating_duration_since(last) <= DOUBLE_CLICK_INTERVAL
+        });
+        self.last = if double {
+            None
+        } else {
+            Some((at, column, row))
+        };
+        double
+    }
+}
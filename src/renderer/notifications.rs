@@ -0,0 +1,239 @@
+//! Messages shown in the status bar, and the history of them
+//!
+//! Each message has a severity, which sets its color and how long it stays
+//! up: an error stays longer than a warning, and a warning longer than the
+//! info that answers a key press. A message replaces the one showing unless
+//! that one is more severe, in which case it waits its turn, so an error
+//! isn't hidden by the next key press. `E` opens a panel listing the latest
+//! messages, for those that went by too quickly.
+
+use crossterm::style::Color;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use super::error::RendererError;
+use super::favorites_panel::{ACCENT_COLOR, MUTED_COLOR};
+use super::panels::draw_lines;
+use super::regions::Rect;
+
+/// How long an info message stays in the status bar
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Most messages waiting behind the one showing; older ones are dropped
+pub const MAX_QUEUED: usize = 4;
+
+/// Messages kept in the history
+pub const MAX_HISTORY: usize = 50;
+
+/// Messages listed in the history panel
+pub const HISTORY_LISTED: usize = 10;
+
+/// Width and height of the history panel
+pub const HISTORY_PANEL_SIZE: (usize, usize) = (64, HISTORY_LISTED + 2);
+
+/// How serious a message is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The outcome of something asked for
+    Info,
+    /// Something didn't go as planned, but playback goes on
+    Warn,
+    /// Something failed
+    Error,
+}
+
+impl Severity {
+    /// Get string representation of the severity
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        }
+    }
+
+    /// Returns how long a message of this severity stays in the status bar
+    pub fn duration(&self) -> Duration {
+        match self {
+            Severity::Info => TOAST_DURATION,
+            Severity::Warn => TOAST_DURATION * 3 / 2,
+            Severity::Error => TOAST_DURATION * 2,
+        }
+    }
+
+    /// Returns the color messages of this severity are drawn in
+    pub fn color(&self) -> Color {
+        match self {
+            Severity::Info => Color::Rgb {
+                r: 229,
+                g: 192,
+                b: 123,
+            },
+            Severity::Warn => Color::Rgb {
+                r: 209,
+                g: 154,
+                b: 102,
+            },
+            Severity::Error => Color::Rgb {
+                r: 224,
+                g: 108,
+                b: 117,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A message and when it was posted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    /// What happened
+    pub message: String,
+    /// How serious it is
+    pub severity: Severity,
+    /// When it was posted
+    pub posted: Instant,
+}
+
+/// The message showing, those waiting behind it, and the latest ones posted
+#[derive(Debug, Clone, Default)]
+pub struct Notifications {
+    /// The message showing first, then those waiting
+    queue: VecDeque<Notice>,
+    /// When the first message in the queue started showing
+    shown_at: Option<Instant>,
+    /// Messages posted, latest first
+    history: VecDeque<Notice>,
+}
+
+impl Notifications {
+    /// Creates an empty queue and history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Posts `message`, showing it at once unless a more severe message is
+    /// showing
+    pub fn push(&mut self, message: &str, severity: Severity) {
+        let now = Instant::now();
+        let notice = Notice {
+            message: message.to_string(),
+            severity,
+            posted: now,
+        };
+        self.history.push_front(notice.clone());
+        self.history.truncate(MAX_HISTORY);
+
+        self.prune(now);
+        match self.queue.front() {
+            Some(showing) if showing.severity > severity => {
+                self.queue.push_back(notice);
+                if self.queue.len() > MAX_QUEUED + 1 {
+                    self.queue.remove(1);
+                }
+            }
+            _ => {
+                self.queue.pop_front();
+                self.queue.push_front(notice);
+                self.shown_at = Some(now);
+            }
+        }
+    }
+
+    /// Hides the message showing, moving on to the next one waiting
+    pub fn dismiss(&mut self) {
+        let now = Instant::now();
+        self.prune(now);
+        self.queue.pop_front();
+        self.shown_at = Some(now);
+    }
+
+    /// Returns the message showing now, if any
+    pub fn current(&self) -> Option<&Notice> {
+        self.current_at(Instant::now())
+    }
+
+    /// Returns the message showing at `now`, each waiting message taking
+    /// its turn as the one before it expires
+    pub fn current_at(&self, now: Instant) -> Option<&Notice> {
+        let mut start = self.shown_at?;
+        for notice in &self.queue {
+            let end = start + notice.severity.duration();
+            if now < end {
+                return Some(notice);
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// Returns the messages posted, latest first
+    pub fn history(&self) -> impl Iterator<Item = &Notice> {
+        self.history.iter()
+    }
+
+    /// Drops the messages that expired by `now`
+    fn prune(&mut self, now: Instant) {
+        let Some(mut start) = self.shown_at else {
+            return;
+        };
+        while let Some(notice) = self.queue.front() {
+            let end = start + notice.severity.duration();
+            if now < end {
+                break;
+            }
+            self.queue.pop_front();
+            start = end;
+        }
+        self.shown_at = Some(start);
+    }
+}
+
+/// Draws the latest messages in `area`, each with its severity and how
+/// long ago it was posted
+pub fn render_history_panel<W: Write>(
+    out: &mut W,
+    notifications: &Notifications,
+    area: Rect,
+) -> Result<(), RendererError> {
+    if area.width < 8 || area.height < 3 {
+        return Ok(());
+    }
+
+    let mut lines: Vec<(String, Color)> = vec![(" Messages".to_string(), ACCENT_COLOR)];
+    for notice in notifications.history().take(HISTORY_LISTED) {
+        lines.push((
+            format!(
+                " {:>4} {:<5} {}",
+                format_age(notice.posted.elapsed()),
+                notice.severity.as_str(),
+                notice.message
+            ),
+            notice.severity.color(),
+        ));
+    }
+    if lines.len() == 1 {
+        lines.push((" No messages yet".to_string(), MUTED_COLOR));
+    }
+    lines.resize(HISTORY_LISTED + 1, (String::new(), MUTED_COLOR));
+    lines.push((" E close".to_string(), MUTED_COLOR));
+
+    draw_lines(out, &lines, area)
+}
+
+/// Formats how long ago a message was posted, e.g. `12s`, `5m` or `2h`
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        _ => format!("{}h", seconds / 3600),
+    }
+}
@@ -0,0 +1,306 @@
+//! Where the panels drawn over the animation go
+//!
+//! The quick switcher, the key help, the favorites, the gradient legend,
+//! the message history, and the debug overlay each sit in a corner of the screen, and panels sharing a corner
+//! stack away from it. When more than one panel is open on a screen
+//! narrower than `stack_below` columns, or too narrow for the panels on the
+//! left and right to sit side by side, they are stacked down the left edge
+//! instead, so at 80x24 they don't cover each other. `O` moves the open panels to the next corner
+//! clockwise and saves the corners to `~/.config/chromacat/panels.yaml`:
+//!
+//! ```yaml
+//! switcher: top-left
+//! help: top-left
+//! favorites: top-left
+//! legend: bottom-right
+//! history: bottom-left
+//! debug: top-right
+//! stack_below: 100
+//! ```
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::error::RendererError;
+use super::favorites_panel::{fit, PANEL_BACKGROUND};
+use super::legend::LegendPosition;
+use super::regions::Rect;
+use crate::error::{ChromaCatError, Result};
+use crate::playlist::get_config_dir;
+
+/// Panel layout filename in the config directory
+pub const PANELS_FILE: &str = "panels.yaml";
+
+/// Screen width, in columns, below which two or more open panels are
+/// stacked
+pub const DEFAULT_STACK_BELOW: usize = 100;
+
+/// Returns the path to the user's panel layout file
+pub fn get_panels_path() -> PathBuf {
+    get_config_dir().join(PANELS_FILE)
+}
+
+/// A panel drawn over the animation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelKind {
+    /// The quick switcher opened with `:`
+    Switcher,
+    /// The key help opened with `?`
+    Help,
+    /// The favorites opened with `F`
+    Favorites,
+    /// The gradient legend shown with `M`
+    Legend,
+    /// The message history shown with `E`
+    History,
+    /// The debug overlay shown with `F12`
+    Debug,
+}
+
+impl PanelKind {
+    /// Returns every panel, in the order they are stacked
+    pub fn all() -> &'static [PanelKind] {
+        &[
+            PanelKind::Switcher,
+            PanelKind::Help,
+            PanelKind::Favorites,
+            PanelKind::Legend,
+            PanelKind::History,
+            PanelKind::Debug,
+        ]
+    }
+
+    /// Returns the key the panel's corner is set with in the layout file
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PanelKind::Switcher => "switcher",
+            PanelKind::Help => "help",
+            PanelKind::Favorites => "favorites",
+            PanelKind::Legend => "legend",
+            PanelKind::History => "history",
+            PanelKind::Debug => "debug",
+        }
+    }
+}
+
+/// The corner each panel goes in, and when they are stacked instead
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PanelLayout {
+    /// Corner of the quick switcher
+    pub switcher: LegendPosition,
+    /// Corner of the key help
+    pub help: LegendPosition,
+    /// Corner of the favorites
+    pub favorites: LegendPosition,
+    /// Corner of the gradient legend
+    pub legend: LegendPosition,
+    /// Corner of the message history
+    pub history: LegendPosition,
+    /// Corner of the debug overlay
+    pub debug: LegendPosition,
+    /// Screen width, in columns, below which two or more open panels are
+    /// stacked down the left edge
+    pub stack_below: usize,
+    /// File the layout is saved to, if any
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            switcher: LegendPosition::TopLeft,
+            help: LegendPosition::TopLeft,
+            favorites: LegendPosition::TopLeft,
+            legend: LegendPosition::BottomRight,
+            history: LegendPosition::BottomLeft,
+            debug: LegendPosition::TopRight,
+            stack_below: DEFAULT_STACK_BELOW,
+            path: None,
+        }
+    }
+}
+
+impl PanelLayout {
+    /// Loads the layout kept at `path`; a missing file holds the default
+    /// layout
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut layout = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_yaml::from_str::<PanelLayout>(&content).map_err(|e| {
+                ChromaCatError::InputError(format!(
+                    "Invalid panel layout in {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            PanelLayout::default()
+        };
+        layout.path = Some(path);
+        Ok(layout)
+    }
+
+    /// Loads the user's panel layout from the config directory
+    pub fn load_default() -> Result<Self> {
+        Self::load(get_panels_path())
+    }
+
+    /// Returns the file the layout is saved to, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Writes the layout back to the file it was loaded from; a layout not
+    /// loaded from a file isn't saved
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let yaml = serde_yaml::to_string(self).map_err(|e| {
+            ChromaCatError::Other(format!("Failed to serialize panel layout: {}", e))
+        })?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, yaml).map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to write panel layout to {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Returns the corner `kind` goes in
+    pub fn corner(&self, kind: PanelKind) -> LegendPosition {
+        match kind {
+            PanelKind::Switcher => self.switcher,
+            PanelKind::Help => self.help,
+            PanelKind::Favorites => self.favorites,
+            PanelKind::Legend => self.legend,
+            PanelKind::History => self.history,
+            PanelKind::Debug => self.debug,
+        }
+    }
+
+    /// Puts `kind` in `corner`
+    pub fn set_corner(&mut self, kind: PanelKind, corner: LegendPosition) {
+        match kind {
+            PanelKind::Switcher => self.switcher = corner,
+            PanelKind::Help => self.help = corner,
+            PanelKind::Favorites => self.favorites = corner,
+            PanelKind::Legend => self.legend = corner,
+            PanelKind::History => self.history = corner,
+            PanelKind::Debug => self.debug = corner,
+        }
+    }
+
+    /// Moves each of `kinds` to the next corner clockwise
+    pub fn rotate(&mut self, kinds: &[PanelKind]) {
+        for &kind in kinds {
+            let next = match self.corner(kind) {
+                LegendPosition::TopLeft => LegendPosition::TopRight,
+                LegendPosition::TopRight => LegendPosition::BottomRight,
+                LegendPosition::BottomRight => LegendPosition::BottomLeft,
+                LegendPosition::BottomLeft => LegendPosition::TopLeft,
+            };
+            self.set_corner(kind, next);
+        }
+    }
+
+    /// Returns whether `panels` are stacked down the left edge on a screen
+    /// `width` columns wide, rather than put in their corners
+    pub fn is_stacked(&self, panels: &[(PanelKind, (usize, usize))], width: usize) -> bool {
+        let widest = |right: bool| {
+            panels
+                .iter()
+                .filter(|(kind, _)| self.corner(*kind).is_right() == right)
+                .map(|(_, (panel_width, _))| *panel_width)
+                .max()
+        };
+        let crowded = match (widest(false), widest(true)) {
+            (Some(left), Some(right)) => left + right + 6 > width,
+            _ => false,
+        };
+        panels.len() > 1 && (width < self.stack_below || crowded)
+    }
+
+    /// Places `panels`, each with the width and height it wants, in a
+    /// `width` by `height` viewport. Panels earlier in the list are nearer
+    /// their corner; those left without a row to draw on are dropped.
+    pub fn place(
+        &self,
+        panels: &[(PanelKind, (usize, usize))],
+        (width, height): (usize, usize),
+    ) -> Vec<(PanelKind, Rect)> {
+        let max_width = width.saturating_sub(4);
+        let stacked = self.is_stacked(panels, width);
+        // Rows used so far from the top and from the bottom of each side
+        let mut top = [1usize; 2];
+        let mut bottom = [height.saturating_sub(1); 2];
+
+        let mut placed = Vec::new();
+        for &(kind, (panel_width, panel_height)) in panels {
+            let panel_width = panel_width.min(max_width);
+            let corner = if stacked {
+                LegendPosition::TopLeft
+            } else {
+                self.corner(kind)
+            };
+            let right = corner.is_right();
+            let side = right as usize;
+            let free = bottom[side].saturating_sub(top[side]);
+            let rows = panel_height.min(free);
+            if rows == 0 || panel_width == 0 {
+                continue;
+            }
+            let y = if corner.is_top() {
+                top[side] += rows;
+                top[side] - rows
+            } else {
+                bottom[side] -= rows;
+                bottom[side]
+            };
+            let x = if right { width - 2 - panel_width } else { 2 };
+            placed.push((
+                kind,
+                Rect {
+                    x,
+                    y,
+                    width: panel_width,
+                    height: rows,
+                },
+            ));
+        }
+        placed
+    }
+}
+
+/// Draws `lines` on the panel background, each fitted to the area's width,
+/// as many as there are rows for
+pub(super) fn draw_lines<W: Write>(
+    out: &mut W,
+    lines: &[(String, Color)],
+    area: Rect,
+) -> std::result::Result<(), RendererError> {
+    for (row, (line, color)) in lines.iter().take(area.height).enumerate() {
+        queue!(
+            out,
+            MoveTo(area.x as u16, (area.y + row) as u16),
+            SetBackgroundColor(PANEL_BACKGROUND),
+            SetForegroundColor(*color),
+            Print(fit(line, area.width)),
+        )?;
+    }
+    queue!(out, ResetColor)?;
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! Quick switcher drawn over the animation
+//!
+//! `:` opens a prompt that matches what is typed against the names of the
+//! patterns, the themes and, in demo mode, the art, and lists the best hits
+//! in a panel as the query grows. The letters of the query must appear in a
+//! name in order but not next to each other, so `nbl` finds `nebula`; hits
+//! where they run together or start words rank first. Enter applies the
+//! selected hit, the top one unless Up or Down picked another.
+
+use crossterm::style::Color;
+use std::io::Write;
+
+use super::error::RendererError;
+use super::favorites_panel::{ACCENT_COLOR, MUTED_COLOR, TEXT_COLOR};
+use super::panels::draw_lines;
+use super::regions::Rect;
+
+/// Number of hits listed in the panel
+pub const MAX_LISTED_HITS: usize = 8;
+
+/// Width and height of the panel, which keeps one size as the hits change
+pub const PICKER_PANEL_SIZE: (usize, usize) = (40, MAX_LISTED_HITS + 3);
+
+/// Score for each query letter found in a name
+const MATCH_SCORE: i32 = 1;
+
+/// Extra score for a letter right after the previous one
+const RUN_BONUS: i32 = 5;
+
+/// Extra score for a letter starting a name or a word in it
+const WORD_START_BONUS: i32 = 8;
+
+/// What a hit switches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickKind {
+    /// A pattern, by id
+    Pattern,
+    /// A theme, by name
+    Theme,
+    /// Demo art, by name
+    Art,
+}
+
+impl PickKind {
+    /// Get string representation of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PickKind::Pattern => "pattern",
+            PickKind::Theme => "theme",
+            PickKind::Art => "art",
+        }
+    }
+}
+
+/// A name the switcher can pick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickItem {
+    /// What the name switches
+    pub kind: PickKind,
+    /// Pattern id, theme name or art name
+    pub name: String,
+}
+
+impl PickItem {
+    /// Creates an item for `name`
+    pub fn new(kind: PickKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Scores how well `name` matches `query`, ignoring case, or returns None
+/// if the letters of the query don't all appear in it in order
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + name[next..].iter().position(|&c| c == wanted)?;
+        score += MATCH_SCORE;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += RUN_BONUS;
+        }
+        if found == 0 || matches!(name[found - 1], '-' | '_' | ' ') {
+            score += WORD_START_BONUS;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// The query typed into the switcher and the names matching it, best first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picker {
+    /// Text typed so far
+    query: String,
+    /// Names that can be picked
+    items: Vec<PickItem>,
+    /// Indexes into `items` of the names matching the query, best first
+    hits: Vec<usize>,
+    /// Index into `hits` of the selected hit
+    selected: usize,
+}
+
+impl Picker {
+    /// Opens the switcher on `items`, all of them listed in order
+    pub fn new(items: Vec<PickItem>) -> Self {
+        let hits = (0..items.len()).collect();
+        Self {
+            query: String::new(),
+            items,
+            hits,
+            selected: 0,
+        }
+    }
+
+    /// Returns the text typed so far
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Adds a letter to the query
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.rank();
+    }
+
+    /// Removes the last letter of the query, returning false if it was
+    /// already empty
+    pub fn pop(&mut self) -> bool {
+        let popped = self.query.pop().is_some();
+        self.rank();
+        popped
+    }
+
+    /// Returns the names matching the query, best first
+    pub fn hits(&self) -> impl Iterator<Item = &PickItem> {
+        self.hits.iter().map(|&index| &self.items[index])
+    }
+
+    /// Returns the selected hit, if anything matches
+    pub fn selected(&self) -> Option<&PickItem> {
+        self.hits
+            .get(self.selected)
+            .map(|&index| &self.items[index])
+    }
+
+    /// Moves the selection `delta` hits down, or up when negative, stopping
+    /// at either end
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.hits.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Selects the hit at `index`, or the last one if there are fewer
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.hits.len().saturating_sub(1));
+    }
+
+    /// Returns the index of the first hit listed in the panel, which
+    /// scrolls to keep the selected hit in view
+    pub fn first_listed(&self) -> usize {
+        (self.selected + 1).saturating_sub(MAX_LISTED_HITS)
+    }
+
+    /// Ranks the names against the query, better scores and then shorter
+    /// names first, and selects the top hit
+    fn rank(&mut self) {
+        let mut scored: Vec<(usize, i32)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| Some((index, fuzzy_score(&self.query, &item.name)?)))
+            .collect();
+        scored.sort_by_key(|&(index, score)| (-score, self.items[index].name.len()));
+        self.hits = scored.into_iter().map(|(index, _)| index).collect();
+        self.selected = 0;
+    }
+}
+
+/// Draws the prompt and the best hits in `area`. The panel keeps one size,
+/// so nothing it drew for an earlier query is left showing around it.
+pub fn render_picker_panel<W: Write>(
+    out: &mut W,
+    picker: &Picker,
+    area: Rect,
+) -> Result<(), RendererError> {
+    if area.width < 8 || area.height < 3 {
+        return Ok(());
+    }
+
+    let mut lines: Vec<(String, Color)> = vec![(format!(" :{}▏", picker.query), ACCENT_COLOR)];
+    let total = picker.hits.len();
+    let first = picker.first_listed();
+    for (offset, item) in picker.hits().skip(first).take(MAX_LISTED_HITS).enumerate() {
+        let (marker, color) = if first + offset == picker.selected {
+            ('▸', ACCENT_COLOR)
+        } else {
+            (' ', TEXT_COLOR)
+        };
+        lines.push((
+            format!(" {} {:<26} {}", marker, item.name, item.kind.as_str()),
+            color,
+        ));
+    }
+    if total == 0 {
+        lines.push((" Nothing matches".to_string(), MUTED_COLOR));
+    }
+    lines.resize(MAX_LISTED_HITS + 1, (String::new(), TEXT_COLOR));
+    let count = match total {
+        1 => " 1 match".to_string(),
+        _ => format!(" {} matches", total),
+    };
+    lines.push((count, MUTED_COLOR));
+    lines.push((
+        " Enter apply · ↑↓ choose · Esc close".to_string(),
+        MUTED_COLOR,
+    ));
+
+    draw_lines(out, &lines, area)
+}
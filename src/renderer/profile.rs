@@ -0,0 +1,148 @@
+//! Rendering profiles for slow terminals
+//!
+//! `--profile minimal` trades looks for speed, so the animation stays smooth
+//! on small machines and slow links such as a Raspberry Pi over serial: it
+//! caps the frame rate at 15 fps, swaps demo art without transitions, draws
+//! with the 256-color palette, and caps the `complexity` of the patterns
+//! that have one. Without `--profile`, the viewer switches to the minimal
+//! profile on its own once most frames overrun their time for a few seconds
+//! in a row; `--profile full` keeps it from doing so.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::demo::ArtTransition;
+use crate::termcaps::ColorSupport;
+
+/// Highest frame rate of the minimal profile
+pub const MINIMAL_FPS: u32 = 15;
+
+/// Highest pattern `complexity` of the minimal profile
+pub const MINIMAL_COMPLEXITY: f64 = 2.0;
+
+/// Seconds in a row that most frames must overrun their time before the
+/// minimal profile is picked
+pub const SLOW_SECONDS: u32 = 3;
+
+/// How much the animation gives up for speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderProfile {
+    /// Everything as asked for
+    #[default]
+    Full,
+    /// Fewer frames and colors, no transitions, and simpler patterns
+    Minimal,
+}
+
+impl RenderProfile {
+    /// Returns every profile
+    pub fn all() -> &'static [RenderProfile] {
+        &[RenderProfile::Full, RenderProfile::Minimal]
+    }
+
+    /// Returns the name used for this profile on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenderProfile::Full => "full",
+            RenderProfile::Minimal => "minimal",
+        }
+    }
+
+    /// Returns the frame rate to run at instead of `fps`
+    pub fn fps(&self, fps: u32) -> u32 {
+        match self {
+            RenderProfile::Full => fps,
+            RenderProfile::Minimal => fps.min(MINIMAL_FPS),
+        }
+    }
+
+    /// Returns the colors to draw with on a terminal that shows `support`
+    pub fn colors(&self, support: ColorSupport) -> ColorSupport {
+        match (self, support) {
+            (RenderProfile::Minimal, ColorSupport::TrueColor) => ColorSupport::Ansi256,
+            _ => support,
+        }
+    }
+
+    /// Returns how demo art replaces the art on screen instead of
+    /// `transition`
+    pub fn art_transition(&self, transition: ArtTransition) -> ArtTransition {
+        match self {
+            RenderProfile::Full => transition,
+            RenderProfile::Minimal => ArtTransition::Cut,
+        }
+    }
+
+    /// Returns the highest pattern `complexity`, if it is capped
+    pub fn max_complexity(&self) -> Option<f64> {
+        match self {
+            RenderProfile::Full => None,
+            RenderProfile::Minimal => Some(MINIMAL_COMPLEXITY),
+        }
+    }
+}
+
+impl fmt::Display for RenderProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RenderProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RenderProfile::all()
+            .iter()
+            .find(|profile| profile.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = RenderProfile::all().iter().map(|p| p.as_str()).collect();
+                format!(
+                    "Invalid profile '{}'. Valid profiles: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Tells when frames keep taking longer than the frame rate allows
+#[derive(Debug, Clone, Default)]
+pub struct SlowFrames {
+    /// Frames drawn so far this second
+    frames: u32,
+    /// Frames so far this second that took longer than their time
+    overruns: u32,
+    /// Seconds in a row in which most frames overran
+    slow_seconds: u32,
+}
+
+impl SlowFrames {
+    /// Starts watching with no frames seen
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes a frame that took `took` out of the `budget` it had
+    pub fn frame(&mut self, took: Duration, budget: Duration) {
+        self.frames += 1;
+        if took > budget {
+            self.overruns += 1;
+        }
+    }
+
+    /// Ends a second of frames, returning whether most frames overran for
+    /// the last [`SLOW_SECONDS`] seconds
+    pub fn roll(&mut self) -> bool {
+        if self.frames > 0 && self.overruns * 2 > self.frames {
+            self.slow_seconds += 1;
+        } else {
+            self.slow_seconds = 0;
+        }
+        self.frames = 0;
+        self.overruns = 0;
+        self.slow_seconds >= SLOW_SECONDS
+    }
+}
@@ -0,0 +1,144 @@
+//! Sub-cell rasterization for high-resolution pattern rendering
+//!
+//! A terminal cell can show more than one pattern sample: the upper half
+//! block `▀` with separate foreground and background colors gives two
+//! vertical pixels per cell, and braille characters give a 2x4 grid of dots.
+//! This module defines the available resolutions and turns a cell's grid of
+//! pattern samples into the glyph and colors that draw it.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Base code point of the braille patterns block (no dots raised)
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Braille dot bits indexed by `[row][column]` within the 2x4 cell grid
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Dot thresholds for braille cells, an ordered dither over the 2x4 grid so
+/// mid-range values raise about half the dots rather than all or none
+const BRAILLE_THRESHOLDS: [[f64; 2]; 4] = [
+    [0.5 / 8.0, 4.5 / 8.0],
+    [6.5 / 8.0, 2.5 / 8.0],
+    [1.5 / 8.0, 5.5 / 8.0],
+    [7.5 / 8.0, 3.5 / 8.0],
+];
+
+/// How many pattern samples each terminal cell displays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    /// One sample per cell, coloring the text as-is
+    #[default]
+    Cell,
+    /// Two samples per cell, stacked, drawn with `▀` in foreground and background colors
+    Half,
+    /// A 2x4 grid of samples per cell, drawn as braille dots
+    Braille,
+}
+
+/// The glyph and pattern values that draw one cell at sub-cell resolution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubCell {
+    /// Character drawn in the cell
+    pub glyph: char,
+    /// Pattern value for the foreground color
+    pub fg: f64,
+    /// Pattern value for the background color, if the cell paints one
+    pub bg: Option<f64>,
+}
+
+impl Resolution {
+    /// Returns a list of all resolutions
+    pub fn all() -> &'static [Resolution] {
+        &[Resolution::Cell, Resolution::Half, Resolution::Braille]
+    }
+
+    /// Get string representation of the resolution
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resolution::Cell => "cell",
+            Resolution::Half => "half",
+            Resolution::Braille => "braille",
+        }
+    }
+
+    /// Returns the sample grid within a cell as (columns, rows)
+    #[inline]
+    pub fn grid(&self) -> (usize, usize) {
+        match self {
+            Resolution::Cell => (1, 1),
+            Resolution::Half => (1, 2),
+            Resolution::Braille => (2, 4),
+        }
+    }
+
+    /// Returns true if cells show more than one sample
+    #[inline]
+    pub fn is_subcell(&self) -> bool {
+        !matches!(self, Resolution::Cell)
+    }
+
+    /// Draws a cell from its samples, given in row-major order over
+    /// [`grid`](Self::grid).
+    ///
+    /// Returns `None` at cell resolution, where the cell's own text is kept.
+    pub fn rasterize(&self, samples: &[f64]) -> Option<SubCell> {
+        match self {
+            Resolution::Cell => None,
+            Resolution::Half => Some(SubCell {
+                glyph: '▀',
+                fg: samples[0],
+                bg: Some(samples[1]),
+            }),
+            Resolution::Braille => {
+                let mut bits = 0;
+                let mut lit_sum = 0.0;
+                let mut lit = 0;
+                for (row, (dots, thresholds)) in BRAILLE_DOTS.iter().zip(&BRAILLE_THRESHOLDS).enumerate() {
+                    for column in 0..2 {
+                        let value = samples[row * 2 + column];
+                        if value > thresholds[column] {
+                            bits |= dots[column];
+                            lit_sum += value;
+                            lit += 1;
+                        }
+                    }
+                }
+
+                // Color by the raised dots so they keep their brightness
+                let fg = if lit > 0 {
+                    lit_sum / lit as f64
+                } else {
+                    samples.iter().sum::<f64>() / samples.len() as f64
+                };
+                Some(SubCell {
+                    glyph: char::from_u32(BRAILLE_BASE + bits).unwrap_or(' '),
+                    fg,
+                    bg: None,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cell" => Ok(Resolution::Cell),
+            "half" => Ok(Resolution::Half),
+            "braille" => Ok(Resolution::Braille),
+            _ => Err(format!(
+                "Invalid resolution '{}'. Valid resolutions: cell, half, braille",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Composite rendering of screen regions
+//!
+//! A region layout splits the screen into rectangles, such as a banner
+//! across the top, a column on the left and the main area, each colored by
+//! its own pattern and theme. All regions are drawn in the same pass over
+//! the frame. Layouts are read from YAML:
+//!
+//! ```yaml
+//! regions:
+//!   - name: banner
+//!     height: 3
+//!     pattern: wave
+//!     theme: ocean
+//!   - name: sidebar
+//!     y: 3
+//!     width: 25%
+//!     pattern: plasma
+//!     theme: neon
+//!     params:
+//!       complexity: 3.0
+//! ```
+//!
+//! Positions and sizes are in cells, or in percent of the screen when
+//! written as `25%`. `x` and `y` default to 0, and `width` and `height` to
+//! the rest of the screen. Later regions are drawn over earlier ones, and
+//! cells outside every region keep the main pattern and theme.
+//!
+//! Each region has its own [`PatternEngine`] sized to its rectangle, so its
+//! pattern fills the region the way it would fill a terminal of that size.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::error::RendererError;
+use crate::error::{ChromaCatError, Result};
+use crate::pattern::{LoopMode, PatternConfig, PatternEngine, PostProcess};
+use crate::playlist::PlaylistEntry;
+use crate::themes;
+
+/// A position or size along one axis of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "RawExtent")]
+pub enum Extent {
+    /// A number of cells
+    Cells(usize),
+    /// A share of the screen, from 0 to 100
+    Percent(f64),
+}
+
+/// An extent as written in YAML: a bare number of cells or a string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawExtent {
+    Cells(usize),
+    Text(String),
+}
+
+impl Extent {
+    /// Resolves the extent to cells on an axis `total` cells long
+    pub fn resolve(&self, total: usize) -> usize {
+        match *self {
+            Extent::Cells(cells) => cells.min(total),
+            Extent::Percent(percent) => ((percent / 100.0) * total as f64).round() as usize,
+        }
+    }
+
+    /// The start of an axis
+    fn zero() -> Self {
+        Extent::Cells(0)
+    }
+
+    /// The whole of an axis
+    fn full() -> Self {
+        Extent::Percent(100.0)
+    }
+}
+
+impl TryFrom<RawExtent> for Extent {
+    type Error = String;
+
+    fn try_from(raw: RawExtent) -> std::result::Result<Self, Self::Error> {
+        match raw {
+            RawExtent::Cells(cells) => Ok(Extent::Cells(cells)),
+            RawExtent::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl fmt::Display for Extent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extent::Cells(cells) => write!(f, "{}", cells),
+            Extent::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+impl FromStr for Extent {
+    type Err = String;
+
+    /// Parses a cell count such as `12` or a percentage such as `25%`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|p| (0.0..=100.0).contains(p))
+                .map(Extent::Percent)
+                .ok_or_else(|| format!("Invalid percentage '{}': must be from 0% to 100%", s)),
+            None => s
+                .parse::<usize>()
+                .map(Extent::Cells)
+                .map_err(|_| format!("Invalid extent '{}': use a cell count or a percentage", s)),
+        }
+    }
+}
+
+/// A rectangle of screen cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// Left column
+    pub x: usize,
+    /// Top row
+    pub y: usize,
+    /// Width in cells
+    pub width: usize,
+    /// Height in cells
+    pub height: usize,
+}
+
+impl Rect {
+    /// Returns true if the cell at (`x`, `y`) lies inside the rectangle
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Returns true if the rectangle covers no cells
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// One region of a layout, as written in the layout file
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegionSpec {
+    /// Optional name, shown in errors and the region list
+    #[serde(default)]
+    pub name: String,
+
+    /// Left edge
+    #[serde(default = "Extent::zero")]
+    pub x: Extent,
+
+    /// Top edge
+    #[serde(default = "Extent::zero")]
+    pub y: Extent,
+
+    /// Width, clipped to the screen
+    #[serde(default = "Extent::full")]
+    pub width: Extent,
+
+    /// Height, clipped to the screen
+    #[serde(default = "Extent::full")]
+    pub height: Extent,
+
+    /// Pattern type to use (must be a valid registered pattern)
+    pub pattern: String,
+
+    /// Theme to use (must be a valid theme name)
+    pub theme: String,
+
+    /// Pattern-specific parameters as key-value pairs
+    #[serde(default)]
+    pub params: Option<serde_yaml::Value>,
+}
+
+impl RegionSpec {
+    /// Creates a region covering the whole screen with the given pattern and theme
+    pub fn new(pattern: impl Into<String>, theme: impl Into<String>) -> Self {
+        Self {
+            name: String::new(),
+            x: Extent::zero(),
+            y: Extent::zero(),
+            width: Extent::full(),
+            height: Extent::full(),
+            pattern: pattern.into(),
+            theme: theme.into(),
+            params: None,
+        }
+    }
+
+    /// Adds a name to the region
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the top left corner
+    pub fn with_position(mut self, x: Extent, y: Extent) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Sets the width and height
+    pub fn with_size(mut self, width: Extent, height: Extent) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Adds pattern-specific parameters
+    pub fn with_params(mut self, params: serde_yaml::Value) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Resolves the region to cells on a screen of the given size
+    pub fn rect(&self, width: usize, height: usize) -> Rect {
+        let x = self.x.resolve(width);
+        let y = self.y.resolve(height);
+        Rect {
+            x,
+            y,
+            width: self.width.resolve(width).min(width - x),
+            height: self.height.resolve(height).min(height - y),
+        }
+    }
+
+    /// Validates that the pattern, theme and parameters exist and are valid
+    pub fn validate(&self) -> Result<()> {
+        self.as_entry().validate()
+    }
+
+    /// Converts the region's pattern settings into a pattern configuration
+    pub fn to_pattern_config(&self) -> Result<PatternConfig> {
+        self.as_entry().to_pattern_config()
+    }
+
+    /// Describes the region for errors and listings
+    fn label(&self, index: usize) -> String {
+        if self.name.is_empty() {
+            format!("region {}", index + 1)
+        } else {
+            format!("region {} ({})", index + 1, self.name)
+        }
+    }
+
+    /// A playlist entry with the same pattern settings, which knows how to
+    /// validate and convert them
+    fn as_entry(&self) -> PlaylistEntry {
+        let entry = PlaylistEntry::new(&self.pattern, &self.theme, 0);
+        match &self.params {
+            Some(params) => entry.with_params(params.clone()),
+            None => entry,
+        }
+    }
+}
+
+/// The regions of the screen, in drawing order
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RegionLayout {
+    /// Regions; later ones are drawn over earlier ones
+    pub regions: Vec<RegionSpec>,
+}
+
+impl RegionLayout {
+    /// Creates a layout with the given regions
+    pub fn with_regions(regions: Vec<RegionSpec>) -> Self {
+        Self { regions }
+    }
+
+    /// Loads and validates a layout file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            ChromaCatError::InputError(format!("Failed to read layout file: {}", e))
+        })?;
+
+        contents.parse()
+    }
+
+    /// Validates every region, naming the region that fails
+    pub fn validate(&self) -> Result<()> {
+        if self.regions.is_empty() {
+            return Err(ChromaCatError::InputError(
+                "Layout has no regions".to_string(),
+            ));
+        }
+        for (index, region) in self.regions.iter().enumerate() {
+            region.validate().map_err(|e| {
+                ChromaCatError::InputError(format!("Invalid {}: {}", region.label(index), e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RegionLayout {
+    type Err = ChromaCatError;
+
+    fn from_str(contents: &str) -> std::result::Result<Self, Self::Err> {
+        let layout: RegionLayout = serde_yaml::from_str(contents)
+            .map_err(|e| ChromaCatError::InputError(format!("Invalid layout format: {}", e)))?;
+        layout.validate()?;
+        Ok(layout)
+    }
+}
+
+/// A region placed on the screen with the engine that colors it
+pub struct Region {
+    /// Where the region sits and what it shows
+    spec: RegionSpec,
+    /// Cells covered on the current screen
+    rect: Rect,
+    /// Pattern engine sized to the region
+    engine: PatternEngine,
+}
+
+impl Region {
+    /// Returns the region's name
+    pub fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    /// Returns the cells the region covers
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the region's pattern engine
+    pub fn engine(&self) -> &PatternEngine {
+        &self.engine
+    }
+
+    /// Returns the pattern value at a screen position given in cells, which
+    /// may fall between cells for sub-cell sampling
+    #[inline]
+    pub fn value_at(&self, x: f64, y: f64) -> std::result::Result<f64, RendererError> {
+        let local_x = (x - self.rect.x as f64) / self.rect.width as f64 - 0.5;
+        let local_y = (y - self.rect.y as f64) / self.rect.height as f64 - 0.5;
+        Ok(self.engine.get_value_at_normalized(local_x, local_y)?)
+    }
+}
+
+/// Draws a region layout: places the regions on the screen and keeps their
+/// pattern engines animated
+pub struct Compositor {
+    /// Placed regions, in drawing order
+    regions: Vec<Region>,
+}
+
+impl Compositor {
+    /// Places a layout's regions on a screen of `width` x `height` cells
+    pub fn new(
+        layout: &RegionLayout,
+        width: usize,
+        height: usize,
+    ) -> std::result::Result<Self, RendererError> {
+        let regions = layout
+            .regions
+            .iter()
+            .map(|spec| {
+                let rect = spec.rect(width, height);
+                let engine = PatternEngine::from_theme(
+                    &themes::get_theme(&spec.theme)?,
+                    spec.to_pattern_config()?,
+                    rect.width.max(1),
+                    rect.height.max(1),
+                )?;
+                Ok(Region {
+                    spec: spec.clone(),
+                    rect,
+                    engine,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { regions })
+    }
+
+    /// Returns the placed regions in drawing order
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Returns the topmost region covering a cell, if any
+    #[inline]
+    pub fn region_at(&self, x: usize, y: usize) -> Option<&Region> {
+        self.regions.iter().rev().find(|r| r.rect.contains(x, y))
+    }
+
+    /// Advances every region's animation
+    pub fn update(&mut self, delta_seconds: f64) {
+        for region in &mut self.regions {
+            region.engine.update(delta_seconds);
+        }
+    }
+
+    /// Sets every region's animation time
+    pub fn set_time(&mut self, time: f64) {
+        for region in &mut self.regions {
+            region.engine.set_time(time);
+        }
+    }
+
+    /// Makes every region's time repeat; see [`PatternEngine::set_cycle`]
+    pub fn set_cycle(&mut self, cycle: Option<Duration>, mode: LoopMode) {
+        for region in &mut self.regions {
+            region.engine.set_cycle(cycle, mode);
+        }
+    }
+
+    /// Scales every region's speed; see [`PatternEngine::set_speed_multiplier`]
+    pub fn set_speed_multiplier(&mut self, multiplier: f64) {
+        for region in &mut self.regions {
+            region.engine.set_speed_multiplier(multiplier);
+        }
+    }
+
+    /// Applies value post-processing to every region
+    pub fn set_postprocess(&mut self, postprocess: &PostProcess) {
+        for region in &mut self.regions {
+            region.engine.set_postprocess(postprocess.clone());
+        }
+    }
+
+    /// Averages every region's values over `samples` evaluations across
+    /// `shutter` seconds; see [`PatternEngine::set_smoothing`]
+    pub fn set_smoothing(&mut self, samples: usize, shutter: f64) {
+        for region in &mut self.regions {
+            region.engine.set_smoothing(samples, shutter);
+        }
+    }
+
+    /// Places the regions again for a new screen size, keeping their
+    /// animation state
+    pub fn resize(&mut self, width: usize, height: usize) {
+        for region in &mut self.regions {
+            region.rect = region.spec.rect(width, height);
+            region.engine = region
+                .engine
+                .recreate(region.rect.width.max(1), region.rect.height.max(1));
+        }
+    }
+}
+
+impl fmt::Debug for Compositor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.regions.iter().map(|r| (&r.spec.name, r.rect)))
+            .finish()
+    }
+}
@@ -0,0 +1,163 @@
+//! Terminal resize detection outside the event loop
+//!
+//! The animation loop learns about new window sizes from crossterm's
+//! `Event::Resize`, but static rendering, streaming, and inline output never
+//! read events. They poll a [`ResizeWatcher`] between lines instead. On Unix
+//! a SIGWINCH handler bumps a counter, so polling costs one atomic load until
+//! the window actually changes; elsewhere the size is queried at most every
+//! [`CHECK_INTERVAL`].
+
+#[cfg(unix)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+#[cfg(not(unix))]
+use std::time::Instant;
+
+/// How often the terminal size is queried where SIGWINCH isn't available
+pub const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of SIGWINCH signals received
+#[cfg(unix)]
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Notices when the terminal stdout is attached to changes size
+#[derive(Debug)]
+pub struct ResizeWatcher {
+    /// Last size seen, or `None` when stdout isn't a terminal
+    size: Option<(u16, u16)>,
+    /// SIGWINCH count when the size was last checked
+    #[cfg(unix)]
+    generation: usize,
+    /// When the size was last queried
+    #[cfg(not(unix))]
+    last_check: Instant,
+}
+
+impl ResizeWatcher {
+    /// Creates a watcher for the current terminal; it never reports a change
+    /// when stdout isn't a terminal
+    pub fn new() -> Self {
+        let size = if atty::is(atty::Stream::Stdout) {
+            install_handler();
+            // Some terminals, such as bare ptys, report no size at all
+            crossterm::terminal::size()
+                .ok()
+                .filter(|&(width, height)| width > 0 && height > 0)
+        } else {
+            None
+        };
+        Self {
+            size,
+            #[cfg(unix)]
+            generation: GENERATION.load(Ordering::SeqCst),
+            #[cfg(not(unix))]
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Returns the last size seen, if stdout is a terminal
+    pub fn size(&self) -> Option<(u16, u16)> {
+        self.size
+    }
+
+    /// Returns the new size if the terminal changed size since the last poll
+    pub fn poll(&mut self) -> Option<(u16, u16)> {
+        self.size?;
+        if !self.due() {
+            return None;
+        }
+
+        let size = crossterm::terminal::size().ok()?;
+        if size.0 == 0 || size.1 == 0 || Some(size) == self.size {
+            return None;
+        }
+        self.size = Some(size);
+        Some(size)
+    }
+
+    /// Returns true if the size may have changed since it was last queried
+    #[cfg(unix)]
+    fn due(&mut self) -> bool {
+        let generation = GENERATION.load(Ordering::SeqCst);
+        let due = generation != self.generation;
+        self.generation = generation;
+        due
+    }
+
+    /// Returns true if the size may have changed since it was last queried
+    #[cfg(not(unix))]
+    fn due(&mut self) -> bool {
+        let due = self.last_check.elapsed() >= CHECK_INTERVAL;
+        if due {
+            self.last_check = Instant::now();
+        }
+        due
+    }
+}
+
+impl Default for ResizeWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handler that was installed for SIGWINCH before ours
+#[cfg(unix)]
+static PREVIOUS_HANDLER: AtomicUsize = AtomicUsize::new(libc::SIG_DFL);
+
+/// Whether the previous handler takes `siginfo_t`
+#[cfg(unix)]
+static PREVIOUS_SIGINFO: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Installs the SIGWINCH handler once, keeping any handler already installed
+/// (such as crossterm's) working
+#[cfg(unix)]
+fn install_handler() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        // SAFETY: the structs are zeroed plain data filled in before use,
+        // and the handler only touches atomics and calls the previous handler
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handle_winch as *const () as libc::sighandler_t;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+
+            let mut previous: libc::sigaction = std::mem::zeroed();
+            if libc::sigaction(libc::SIGWINCH, &action, &mut previous) == 0 {
+                PREVIOUS_HANDLER.store(previous.sa_sigaction, Ordering::SeqCst);
+                PREVIOUS_SIGINFO.store(previous.sa_flags & libc::SA_SIGINFO != 0, Ordering::SeqCst);
+            }
+        }
+    });
+}
+
+/// Without SIGWINCH the size is queried every [`CHECK_INTERVAL`] instead
+#[cfg(not(unix))]
+fn install_handler() {}
+
+/// Counts the signal and passes it on to the previous handler
+#[cfg(unix)]
+extern "C" fn handle_winch(
+    signal: libc::c_int,
+    info: *mut libc::siginfo_t,
+    context: *mut libc::c_void,
+) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+
+    let previous = PREVIOUS_HANDLER.load(Ordering::SeqCst);
+    if previous == libc::SIG_DFL || previous == libc::SIG_IGN {
+        return;
+    }
+    // SAFETY: `previous` was installed as a handler of the matching kind
+    unsafe {
+        if PREVIOUS_SIGINFO.load(Ordering::SeqCst) {
+            let handler: extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) =
+                std::mem::transmute(previous);
+            handler(signal, info, context);
+        } else {
+            let handler: extern "C" fn(libc::c_int) = std::mem::transmute(previous);
+            handler(signal);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Frame capture and image export
+//!
+//! A [`Frame`] is a snapshot of the visible cells with their colors. It can
+//! be encoded as a PNG, where each cell becomes a block of pixels, and the
+//! PNG can be wrapped in an inline image escape for terminals that display
+//! images (the iTerm2 protocol, also understood by WezTerm, or the kitty
+//! graphics protocol).
+//!
+//! Text glyphs are not rasterized: block, shade, and braille characters are
+//! drawn by shape, and other text is drawn as a solid mark in its color.
+
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use super::error::RendererError;
+use crate::termcaps::{Graphics, TermCaps};
+
+/// Pixel width of one cell in exported images
+pub const CELL_WIDTH: usize = 8;
+/// Pixel height of one cell in exported images
+pub const CELL_HEIGHT: usize = 16;
+
+/// Background of cells that have no background color
+const DEFAULT_BG: [u8; 3] = [0x10, 0x10, 0x14];
+/// Color of text that has no pattern color, e.g. when colors are disabled
+pub(super) const DEFAULT_FG: [u8; 3] = [0xd0, 0xd0, 0xd0];
+
+/// Largest payload chunk allowed by the kitty graphics protocol
+const KITTY_CHUNK: usize = 4096;
+
+/// One captured cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCell {
+    /// Character shown in the cell
+    pub ch: char,
+    /// Foreground color, if the cell has been colored
+    pub fg: Option<[u8; 3]>,
+    /// Background color painted by sub-cell rendering
+    pub bg: Option<[u8; 3]>,
+}
+
+impl Default for FrameCell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: None,
+            bg: None,
+        }
+    }
+}
+
+/// A snapshot of the visible cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Width in cells
+    pub width: usize,
+    /// Height in cells
+    pub height: usize,
+    /// Cells in row-major order
+    pub cells: Vec<FrameCell>,
+}
+
+/// Where a screenshot is sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenshotTarget {
+    /// Inline image using the kitty protocol when running in kitty, the
+    /// iTerm2 protocol otherwise
+    Inline,
+    /// Inline image using the iTerm2 protocol (iTerm2, WezTerm)
+    Iterm,
+    /// Inline image using the kitty graphics protocol
+    Kitty,
+    /// PNG file at the given path
+    File(std::path::PathBuf),
+}
+
+impl Frame {
+    /// Creates a blank frame
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![FrameCell::default(); width * height],
+        }
+    }
+
+    /// Returns the cell at a position
+    pub fn cell(&self, x: usize, y: usize) -> &FrameCell {
+        &self.cells[y * self.width + x]
+    }
+
+    /// Returns a mutable reference to the cell at a position
+    pub fn cell_mut(&mut self, x: usize, y: usize) -> &mut FrameCell {
+        &mut self.cells[y * self.width + x]
+    }
+
+    /// Returns the frame's text, one line per row with trailing spaces removed
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.cells.len() + self.height);
+        for row in self.cells.chunks(self.width.max(1)) {
+            let line: String = row.iter().map(|c| c.ch).collect();
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Draws the frame as RGB pixels, [`CELL_WIDTH`] x [`CELL_HEIGHT`] per cell
+    pub fn to_rgb(&self) -> Vec<u8> {
+        let row_bytes = self.width * CELL_WIDTH * 3;
+        let mut pixels = vec![0u8; row_bytes * self.height * CELL_HEIGHT];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self.cell(x, y);
+                let fg = cell.fg.unwrap_or(DEFAULT_FG);
+                let bg = cell.bg.unwrap_or(DEFAULT_BG);
+                for py in 0..CELL_HEIGHT {
+                    for px in 0..CELL_WIDTH {
+                        let coverage = coverage(cell.ch, px, py);
+                        let offset = (y * CELL_HEIGHT + py) * row_bytes + (x * CELL_WIDTH + px) * 3;
+                        for channel in 0..3 {
+                            pixels[offset + channel] = mix(bg[channel], fg[channel], coverage);
+                        }
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+
+    /// Encodes the frame as a PNG image
+    pub fn to_png(&self) -> Result<Vec<u8>, RendererError> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(
+            &mut png,
+            (self.width * CELL_WIDTH).max(1) as u32,
+            (self.height * CELL_HEIGHT).max(1) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut pixels = self.to_rgb();
+        if pixels.is_empty() {
+            pixels = DEFAULT_BG.to_vec();
+        }
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|e| RendererError::Other(format!("Failed to encode PNG: {}", e)))?;
+        Ok(png)
+    }
+}
+
+impl ScreenshotTarget {
+    /// Resolves [`Inline`](Self::Inline) to the protocol the current
+    /// terminal speaks, falling back to the iTerm2 protocol when it isn't
+    /// known to speak kitty's
+    pub fn resolve(self) -> Self {
+        match self {
+            ScreenshotTarget::Inline => match TermCaps::probed().graphics {
+                Graphics::Kitty => ScreenshotTarget::Kitty,
+                _ => ScreenshotTarget::Iterm,
+            },
+            other => other,
+        }
+    }
+
+    /// Sends a frame to this target: writes a PNG file, or prints an inline
+    /// image escape to `out`
+    pub fn deliver<W: Write>(&self, frame: &Frame, out: &mut W) -> Result<(), RendererError> {
+        let png = frame.to_png()?;
+        match self.clone().resolve() {
+            ScreenshotTarget::File(path) => std::fs::write(path, png)?,
+            ScreenshotTarget::Kitty => writeln!(out, "{}", kitty_escape(&png))?,
+            _ => writeln!(out, "{}", iterm_escape(&png))?,
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ScreenshotTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenshotTarget::Inline => write!(f, "inline"),
+            ScreenshotTarget::Iterm => write!(f, "iterm"),
+            ScreenshotTarget::Kitty => write!(f, "kitty"),
+            ScreenshotTarget::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for ScreenshotTarget {
+    type Err = String;
+
+    /// Parses `inline`, `iterm` or `kitty`; anything else is a PNG file path
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" => Err("Screenshot target cannot be empty".to_string()),
+            "inline" => Ok(ScreenshotTarget::Inline),
+            "iterm" | "iterm2" | "wezterm" => Ok(ScreenshotTarget::Iterm),
+            "kitty" => Ok(ScreenshotTarget::Kitty),
+            _ => Ok(ScreenshotTarget::File(s.into())),
+        }
+    }
+}
+
+/// Wraps a PNG in an iTerm2 inline image escape (OSC 1337)
+pub fn iterm_escape(png: &[u8]) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        base64(png)
+    )
+}
+
+/// Wraps a PNG in kitty graphics protocol escapes, chunked as the protocol requires
+pub fn kitty_escape(png: &[u8]) -> String {
+    let encoded = base64(png);
+    let chunks: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(KITTY_CHUNK)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    let mut out = String::with_capacity(encoded.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            out.push_str(&format!("\x1b_Gf=100,a=T,m={};{}\x1b\\", more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// Standard base64 with padding
+pub(super) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// How much of the pixel at (`px`, `py`) a character covers, from 0.0 to 1.0
+fn coverage(ch: char, px: usize, py: usize) -> f32 {
+    match ch {
+        ' ' | '\u{2800}' => 0.0,
+        '█' => 1.0,
+        '▓' => 0.75,
+        '▒' => 0.5,
+        '░' => 0.25,
+        '▀' => f32::from(u8::from(py < CELL_HEIGHT / 2)),
+        '▄' => f32::from(u8::from(py >= CELL_HEIGHT / 2)),
+        '▌' => f32::from(u8::from(px < CELL_WIDTH / 2)),
+        '▐' => f32::from(u8::from(px >= CELL_WIDTH / 2)),
+        '\u{2801}'..='\u{28FF}' => {
+            // Dots sit on a 2x4 grid; each is a small square in its slot
+            let bits = ch as u32 - 0x2800;
+            let (column, row) = (px * 2 / CELL_WIDTH, py * 4 / CELL_HEIGHT);
+            let bit = match (column, row) {
+                (0, 3) => 0x40,
+                (1, 3) => 0x80,
+                (0, r) => 1 << r,
+                (_, r) => 1 << (r + 3),
+            };
+            let (sx, sy) = (px % (CELL_WIDTH / 2), py % (CELL_HEIGHT / 4));
+            let in_dot = (1..3).contains(&sx) && (1..3).contains(&sy);
+            f32::from(u8::from(bits & bit != 0 && in_dot))
+        }
+        _ => {
+            // Other text becomes a solid mark within the cell's margins
+            let inside = (1..CELL_WIDTH - 1).contains(&px) && (3..CELL_HEIGHT - 3).contains(&py);
+            f32::from(u8::from(inside))
+        }
+    }
+}
+
+/// Blends a background and foreground channel by coverage
+#[inline]
+fn mix(bg: u8, fg: u8, coverage: f32) -> u8 {
+    (bg as f32 + (fg as f32 - bg as f32) * coverage).round() as u8
+}
@@ -76,6 +76,14 @@ impl ScrollState {
                 self.scroll_down(1);
                 Action::Continue
             }
+            KeyCode::Home => {
+                self.scroll_to(0);
+                Action::Continue
+            }
+            KeyCode::End => {
+                self.scroll_to(self.max_scroll());
+                Action::Continue
+            }
             KeyCode::Char('q') | KeyCode::Esc => Action::Exit,
             _ => Action::NoChange,
         }
@@ -98,6 +106,12 @@ impl ScrollState {
         self.top_line = (self.top_line + amount as usize).min(max_scroll);
     }
 
+    /// Scrolls so that `line` is at the top of the viewport, or as close to
+    /// it as the content allows
+    pub fn scroll_to(&mut self, line: usize) {
+        self.top_line = line.min(self.max_scroll());
+    }
+
     /// Returns the total number of lines
     pub fn total_lines(&self) -> usize {
         self.total_lines
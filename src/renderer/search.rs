@@ -0,0 +1,105 @@
+//! Text search in the viewer
+//!
+//! `/` opens a prompt in the status bar, and the query typed there is looked
+//! up in the input lines, with their colors stripped. The view jumps to the
+//! first matching line at or below the top of the screen, and `n` and `N`
+//! move to the next and previous ones. As in `less`, the search doesn't wrap
+//! around the ends of the text, and it ignores case unless the query has an
+//! uppercase letter.
+
+use crate::escape::strip_sgr;
+
+/// A query, the input lines that match it, and the match being shown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search {
+    /// Text searched for
+    query: String,
+    /// Input lines containing the query, in order
+    matches: Vec<usize>,
+    /// Index into `matches` of the match being shown
+    current: Option<usize>,
+}
+
+impl Search {
+    /// Finds the lines of `text` that contain `query`
+    pub fn new(query: &str, text: &str) -> Self {
+        let ignore_case = !query.chars().any(char::is_uppercase);
+        let needle = if ignore_case {
+            query.to_lowercase()
+        } else {
+            query.to_string()
+        };
+        let matches = text
+            .split('\n')
+            .map(strip_sgr)
+            .enumerate()
+            .filter(|(_, line)| {
+                if ignore_case {
+                    line.to_lowercase().contains(&needle)
+                } else {
+                    line.contains(&needle)
+                }
+            })
+            .map(|(index, _)| index)
+            .collect();
+        Self {
+            query: query.to_string(),
+            matches,
+            current: None,
+        }
+    }
+
+    /// Returns the text searched for
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the matching input lines, in order
+    pub fn matches(&self) -> &[usize] {
+        &self.matches
+    }
+
+    /// Returns the line of the match being shown
+    pub fn current(&self) -> Option<usize> {
+        self.current.map(|index| self.matches[index])
+    }
+
+    /// Moves to the first match at or after `line` and returns its line
+    pub fn first_from(&mut self, line: usize) -> Option<usize> {
+        self.select(self.matches.iter().position(|&m| m >= line))
+    }
+
+    /// Moves to the match after the one being shown and returns its line,
+    /// or returns None if it was the last
+    pub fn next_match(&mut self) -> Option<usize> {
+        let index = match self.current {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        self.select(Some(index).filter(|&index| index < self.matches.len()))
+    }
+
+    /// Moves to the match before the one being shown and returns its line,
+    /// or returns None if it was the first
+    pub fn previous_match(&mut self) -> Option<usize> {
+        self.select(self.current.and_then(|index| index.checked_sub(1)))
+    }
+
+    /// Describes the match being shown, like `/query: match 2/5`, or why
+    /// there is none
+    pub fn status(&self) -> String {
+        match self.current {
+            _ if self.matches.is_empty() => format!("Pattern not found: {}", self.query),
+            Some(index) => format!("/{}: match {}/{}", self.query, index + 1, self.matches.len()),
+            None => format!("No match below: {}", self.query),
+        }
+    }
+
+    /// Shows the match at `index`, if there is one, keeping the current
+    /// match otherwise
+    fn select(&mut self, index: Option<usize>) -> Option<usize> {
+        let index = index?;
+        self.current = Some(index);
+        Some(self.matches[index])
+    }
+}
@@ -0,0 +1,222 @@
+//! Session statistics
+//!
+//! Counts what an animation did while it ran: the frames drawn, the scenes
+//! shown, and how long each pattern and theme was on screen. `--summary`
+//! prints them once the animation ends, and `--stats` writes them as JSON,
+//! also every [`STATS_SAVE_INTERVAL`] along the way, so a display left
+//! running for days keeps an up-to-date file.
+
+use crate::error::{ChromaCatError, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often the stats file is rewritten while the animation runs
+pub const STATS_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Running counts for one animation
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    /// When the animation started
+    started: Instant,
+    /// Frames drawn
+    frames: u64,
+    /// Scenes shown, counting each change of pattern or theme
+    scenes: u64,
+    /// Pattern and theme of the latest frame, and when it was drawn
+    last: Option<(String, String, Instant)>,
+    /// Time on screen of each pattern
+    patterns: BTreeMap<String, Duration>,
+    /// Time on screen of each theme
+    themes: BTreeMap<String, Duration>,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionStats {
+    /// Starts counting from now
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            frames: 0,
+            scenes: 0,
+            last: None,
+            patterns: BTreeMap::new(),
+            themes: BTreeMap::new(),
+        }
+    }
+
+    /// Counts a frame showing `pattern` in `theme`, drawn now
+    pub fn record_frame(&mut self, pattern: &str, theme: &str) {
+        self.record_frame_at(Instant::now(), pattern, theme);
+    }
+
+    /// Counts a frame showing `pattern` in `theme`, drawn at `now`. The
+    /// time since the previous frame goes to what that frame showed.
+    pub fn record_frame_at(&mut self, now: Instant, pattern: &str, theme: &str) {
+        let changed = match &self.last {
+            Some((last_pattern, last_theme, at)) => {
+                let shown = now.saturating_duration_since(*at);
+                *self.patterns.entry(last_pattern.clone()).or_default() += shown;
+                *self.themes.entry(last_theme.clone()).or_default() += shown;
+                last_pattern != pattern || last_theme != theme
+            }
+            None => true,
+        };
+        if changed {
+            self.scenes += 1;
+        }
+        self.frames += 1;
+        self.last = Some((pattern.to_string(), theme.to_string(), now));
+    }
+
+    /// Returns the number of frames drawn
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Returns the number of scenes shown
+    pub fn scenes(&self) -> u64 {
+        self.scenes
+    }
+
+    /// Sums the counts up as of now
+    pub fn report(&self) -> StatsReport {
+        self.report_at(Instant::now())
+    }
+
+    /// Sums the counts up as of `now`, the scene on screen counting until
+    /// then
+    pub fn report_at(&self, now: Instant) -> StatsReport {
+        let mut patterns = self.patterns.clone();
+        let mut themes = self.themes.clone();
+        if let Some((pattern, theme, at)) = &self.last {
+            let shown = now.saturating_duration_since(*at);
+            *patterns.entry(pattern.clone()).or_default() += shown;
+            *themes.entry(theme.clone()).or_default() += shown;
+        }
+
+        let runtime = now.saturating_duration_since(self.started).as_secs_f64();
+        let average_fps = if runtime > 0.0 {
+            self.frames as f64 / runtime
+        } else {
+            0.0
+        };
+        StatsReport {
+            runtime_seconds: round(runtime),
+            frames: self.frames,
+            average_fps: round(average_fps),
+            scenes: self.scenes,
+            top_pattern: most_shown(&patterns),
+            top_theme: most_shown(&themes),
+            pattern_seconds: seconds(&patterns),
+            theme_seconds: seconds(&themes),
+        }
+    }
+}
+
+/// Session statistics as printed by `--summary` and written by `--stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsReport {
+    /// Seconds the animation ran
+    pub runtime_seconds: f64,
+    /// Frames drawn
+    pub frames: u64,
+    /// Frames drawn per second, on average
+    pub average_fps: f64,
+    /// Scenes shown, counting each change of pattern or theme
+    pub scenes: u64,
+    /// Pattern on screen the longest
+    pub top_pattern: Option<String>,
+    /// Theme on screen the longest
+    pub top_theme: Option<String>,
+    /// Seconds each pattern was on screen
+    pub pattern_seconds: BTreeMap<String, f64>,
+    /// Seconds each theme was on screen
+    pub theme_seconds: BTreeMap<String, f64>,
+}
+
+impl StatsReport {
+    /// Returns the lines `--summary` prints
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "Ran for {}: {} frames at {:.1} fps on average",
+                format_seconds(self.runtime_seconds),
+                self.frames,
+                self.average_fps
+            ),
+            format!("Scenes played: {}", self.scenes),
+        ];
+        let top = |name: &Option<String>, seconds: &BTreeMap<String, f64>| {
+            name.as_ref().map(|name| {
+                let shown = seconds.get(name).copied().unwrap_or_default();
+                format!("{} ({})", name, format_seconds(shown))
+            })
+        };
+        if let Some(pattern) = top(&self.top_pattern, &self.pattern_seconds) {
+            lines.push(format!("Most used pattern: {}", pattern));
+        }
+        if let Some(theme) = top(&self.top_theme, &self.theme_seconds) {
+            lines.push(format!("Most used theme: {}", theme));
+        }
+        lines.join("\n")
+    }
+
+    /// Returns the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ChromaCatError::Other(format!("Failed to serialize stats: {}", e)))
+    }
+
+    /// Writes the report to `path` as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_json()? + "\n").map_err(|e| {
+            ChromaCatError::Other(format!(
+                "Failed to write stats to {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Returns the name shown the longest, the first by name on a tie
+fn most_shown(times: &BTreeMap<String, Duration>) -> Option<String> {
+    times
+        .iter()
+        .rev()
+        .max_by_key(|(_, shown)| **shown)
+        .map(|(name, _)| name.clone())
+}
+
+/// Converts times on screen to seconds
+fn seconds(times: &BTreeMap<String, Duration>) -> BTreeMap<String, f64> {
+    times
+        .iter()
+        .map(|(name, shown)| (name.clone(), round(shown.as_secs_f64())))
+        .collect()
+}
+
+/// Rounds to hundredths, which is all the JSON needs
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Formats whole seconds as e.g. `1h 2m 5s`, `2m 5s` or `5s`
+fn format_seconds(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -9,9 +9,15 @@ use crossterm::{
     style::{Color, Print, SetForegroundColor},
 };
 
+use std::io::Write;
+
 use super::error::RendererError;
+use super::notifications::{Notice, Notifications, Severity};
 use super::scroll::ScrollState;
 
+/// Width of the cycle progress bar in cells
+const PROGRESS_CELLS: usize = 12;
+
 /// Renders status and control information at the bottom of the screen.
 #[derive(Debug)]
 pub struct StatusBar {
@@ -29,6 +35,10 @@ pub struct StatusBar {
     show_fps: bool,
     /// Custom status text (for playlists)
     custom_text: Option<String>,
+    /// Short-lived messages shown over the status text
+    notifications: Notifications,
+    /// Progress through the animation cycle, shown instead of the key hints
+    progress: Option<f64>,
 }
 
 impl StatusBar {
@@ -42,6 +52,8 @@ impl StatusBar {
             fps: 0.0,
             show_fps: true,
             custom_text: None,
+            notifications: Notifications::new(),
+            progress: None,
         }
     }
 
@@ -78,10 +90,54 @@ impl StatusBar {
         self.custom_text.as_deref()
     }
 
+    /// Sets the progress through the animation cycle, from 0 to 1, or
+    /// `None` to show the key hints
+    pub fn set_progress(&mut self, progress: Option<f64>) {
+        self.progress = progress.map(|p| p.clamp(0.0, 1.0));
+    }
+
+    /// Gets the progress through the animation cycle, if shown
+    pub fn progress(&self) -> Option<f64> {
+        self.progress
+    }
+
+    /// Shows an info message over the status text for
+    /// [`TOAST_DURATION`](super::notifications::TOAST_DURATION)
+    pub fn show_toast(&mut self, message: &str) {
+        self.notify(message, Severity::Info);
+    }
+
+    /// Shows a message over the status text, after any more severe one
+    /// showing
+    pub fn notify(&mut self, message: &str, severity: Severity) {
+        self.notifications.push(message, severity);
+    }
+
+    /// Hides the toast being shown, if any
+    pub fn clear_toast(&mut self) {
+        self.notifications.dismiss();
+    }
+
+    /// Gets the toast being shown, if it hasn't expired
+    pub fn toast(&self) -> Option<&str> {
+        self.notice().map(|notice| notice.message.as_str())
+    }
+
+    /// Gets the message being shown, with its severity, if it hasn't
+    /// expired
+    pub fn notice(&self) -> Option<&Notice> {
+        self.notifications.current()
+    }
+
+    /// Gets the messages shown and waiting, and the history of them
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+
     /// Renders the status bar to the terminal.
-    pub fn render(
+    pub fn render<W: Write>(
         &mut self,
-        stdout: &mut std::io::StdoutLock,
+        stdout: &mut W,
         scroll: &ScrollState,
     ) -> Result<(), RendererError> {
         // Define colors for different sections
@@ -105,7 +161,6 @@ impl StatusBar {
             g: 99,
             b: 112,
         };
-
         // Draw separator line
         queue!(
             stdout,
@@ -118,7 +173,10 @@ impl StatusBar {
         let (start, end) = scroll.get_visible_range();
 
         // Build status sections
-        let mut left_section = if let Some(text) = &self.custom_text {
+        let notice = self.notice();
+        let left_color = notice.map_or(accent_color, |notice| notice.severity.color());
+        let toast = notice.map(|notice| notice.message.as_str());
+        let mut left_section = if let Some(text) = toast.or(self.custom_text.as_deref()) {
             format!(" {} ", text)
         } else {
             format!(" {} • {}", self.current_theme, self.current_pattern)
@@ -127,7 +185,18 @@ impl StatusBar {
             left_section.push_str(&format!(" • {:.1} FPS", self.fps));
         }
 
-        let middle_section = "[T]heme [P]attern";
+        let middle_section = match self.progress {
+            Some(progress) => {
+                let filled = (progress * PROGRESS_CELLS as f64).round() as usize;
+                format!(
+                    "{}{} {:>3.0}%",
+                    "━".repeat(filled),
+                    "─".repeat(PROGRESS_CELLS - filled),
+                    progress * 100.0
+                )
+            }
+            None => "[T]heme [P]attern".to_string(),
+        };
         let right_section = format!(
             "Lines {}-{}/{}  [Q]uit ",
             start + 1,
@@ -151,7 +220,7 @@ impl StatusBar {
             // Full render
             queue!(
                 stdout,
-                SetForegroundColor(accent_color),
+                SetForegroundColor(left_color),
                 Print(&left_section),
                 SetForegroundColor(text_color),
                 MoveTo(
@@ -170,7 +239,7 @@ impl StatusBar {
             // Medium render - skip middle section
             queue!(
                 stdout,
-                SetForegroundColor(accent_color),
+                SetForegroundColor(left_color),
                 Print(&left_section),
                 SetForegroundColor(muted_color),
                 MoveTo(
@@ -0,0 +1,457 @@
+//! Render targets
+//!
+//! A [`Renderer`](super::Renderer) draws into a [`RenderTarget`]: normally
+//! the terminal, through [`TerminalState`], or a [`HeadlessTarget`], which
+//! keeps the screen in memory as a grid of characters and their colors.
+//! Integration tests use the headless target to check what was drawn, and
+//! programs embedding the renderer can use it to draw somewhere other than
+//! stdout.
+
+use std::io::{self, Write};
+
+use unicode_width::UnicodeWidthChar;
+
+use super::error::RendererError;
+use super::screenshot::DEFAULT_FG;
+use super::terminal::TerminalState;
+use crate::termcaps::TermCaps;
+
+/// A 24-bit color
+pub type Rgb = [u8; 3];
+
+/// The 16 basic colors, as xterm shows them
+const BASIC_COLORS: [Rgb; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// Where a renderer draws its output
+///
+/// The renderer writes text and escape sequences to the target, flushing
+/// after each block of static output and each animated frame.
+pub trait RenderTarget: Write {
+    /// Returns the size of the screen in cells
+    fn size(&self) -> (u16, u16);
+
+    /// Records a new size without touching the screen, for output that
+    /// scrolls rather than being redrawn
+    fn set_size(&mut self, width: u16, height: u16);
+
+    /// Adopts a new size, clearing the screen for a full redraw
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), RendererError>;
+
+    /// Returns whether output is colored
+    fn colors_enabled(&self) -> bool;
+
+    /// Returns what the target can show
+    fn caps(&self) -> &TermCaps;
+
+    /// Finds out what the target can show beyond what the environment
+    /// says, before animating
+    fn probe_caps(&mut self);
+
+    /// Returns whether animated frames are wrapped in synchronized updates
+    fn synchronized_output(&self) -> bool;
+
+    /// Returns whether the size follows a terminal window, which static
+    /// output watches for resizes
+    fn follows_window(&self) -> bool;
+
+    /// Prepares the screen for animation
+    fn enter_alternate_screen(&mut self) -> Result<(), RendererError>;
+
+    /// Restores the screen once rendering is done
+    fn cleanup(&mut self) -> Result<(), RendererError>;
+}
+
+impl RenderTarget for TerminalState {
+    fn size(&self) -> (u16, u16) {
+        TerminalState::size(self)
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) {
+        TerminalState::set_size(self, width, height)
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), RendererError> {
+        TerminalState::resize(self, width, height)
+    }
+
+    fn colors_enabled(&self) -> bool {
+        TerminalState::colors_enabled(self)
+    }
+
+    fn caps(&self) -> &TermCaps {
+        TerminalState::caps(self)
+    }
+
+    fn probe_caps(&mut self) {
+        TerminalState::probe_caps(self)
+    }
+
+    fn synchronized_output(&self) -> bool {
+        self.is_tty() && self.caps().synchronized_output
+    }
+
+    fn follows_window(&self) -> bool {
+        true
+    }
+
+    fn enter_alternate_screen(&mut self) -> Result<(), RendererError> {
+        TerminalState::enter_alternate_screen(self)
+    }
+
+    fn cleanup(&mut self) -> Result<(), RendererError> {
+        TerminalState::cleanup(self)
+    }
+}
+
+impl Write for TerminalState {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stdout().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        io::stdout().lock().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+/// A screen kept in memory, capturing what the renderer draws as a grid of
+/// characters and their foreground colors
+///
+/// Output is interpreted like a terminal would: cursor movement, clearing,
+/// and color changes are followed, and other escape sequences are skipped.
+/// Text without a color change shows in [`DEFAULT_FG`].
+#[derive(Debug, Clone)]
+pub struct HeadlessTarget {
+    /// Screen size (width, height)
+    size: (u16, u16),
+    /// Capabilities reported to the renderer
+    caps: TermCaps,
+    /// Cells in row-major order
+    cells: Vec<(char, Rgb)>,
+    /// Cursor column and row
+    cursor: (usize, usize),
+    /// Color of text written next
+    fg: Rgb,
+    /// Bytes of an escape sequence or character cut off at the end of a write
+    pending: Vec<u8>,
+    /// Whether anything was written since the last flush
+    dirty: bool,
+    /// Number of flushes that followed output
+    frames: usize,
+}
+
+impl HeadlessTarget {
+    /// Creates a blank screen of the given size
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            size: (width, height),
+            caps: TermCaps::default(),
+            cells: vec![(' ', DEFAULT_FG); width as usize * height as usize],
+            cursor: (0, 0),
+            fg: DEFAULT_FG,
+            pending: Vec::new(),
+            dirty: false,
+            frames: 0,
+        }
+    }
+
+    /// Returns the character and color of a cell
+    pub fn cell(&self, x: usize, y: usize) -> (char, Rgb) {
+        self.cells[y * self.size.0 as usize + x]
+    }
+
+    /// Returns the cells of a row
+    pub fn row(&self, y: usize) -> &[(char, Rgb)] {
+        let width = self.size.0 as usize;
+        &self.cells[y * width..(y + 1) * width]
+    }
+
+    /// Returns the screen as rows of cells
+    pub fn grid(&self) -> Vec<Vec<(char, Rgb)>> {
+        (0..self.size.1 as usize).map(|y| self.row(y).to_vec()).collect()
+    }
+
+    /// Returns the screen's text, one line per row with trailing spaces
+    /// removed
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.cells.len() + self.size.1 as usize);
+        for y in 0..self.size.1 as usize {
+            let line: String = self.row(y).iter().map(|&(ch, _)| ch).collect();
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Returns how many times output was flushed: once per animated frame,
+    /// and once per block of static output
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Replaces the screen with a blank one of a new size
+    fn resize_screen(&mut self, width: u16, height: u16) {
+        self.size = (width, height);
+        self.cells = vec![(' ', DEFAULT_FG); width as usize * height as usize];
+        self.cursor = (0, 0);
+    }
+
+    /// Blanks the cells from `start` up to `end`, in row-major order
+    fn clear(&mut self, start: usize, end: usize) {
+        let end = end.min(self.cells.len());
+        for cell in self.cells.get_mut(start..end).unwrap_or_default() {
+            *cell = (' ', DEFAULT_FG);
+        }
+    }
+
+    /// Moves to the start of the next row, scrolling at the bottom
+    fn newline(&mut self) {
+        let width = self.size.0 as usize;
+        self.cursor.0 = 0;
+        if self.cursor.1 + 1 < self.size.1 as usize {
+            self.cursor.1 += 1;
+        } else if !self.cells.is_empty() {
+            self.cells.drain(..width);
+            self.cells.resize(self.cells.len() + width, (' ', DEFAULT_FG));
+        }
+    }
+
+    /// Writes a visible character at the cursor
+    fn put(&mut self, ch: char) {
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        let cells = ch.width().unwrap_or(0);
+        if cells == 0 || cells > width || height == 0 {
+            return;
+        }
+        if self.cursor.0 + cells > width {
+            self.newline();
+        }
+        let index = self.cursor.1 * width + self.cursor.0;
+        self.cells[index] = (ch, self.fg);
+        // The second column of a wide character stays blank
+        if cells == 2 {
+            self.cells[index + 1] = (' ', self.fg);
+        }
+        self.cursor.0 += cells;
+    }
+
+    /// Follows a control sequence with the given parameters and final byte
+    fn control(&mut self, params: &str, action: char) {
+        let numbers: Vec<usize> = params
+            .split(';')
+            .map(|n| n.parse().unwrap_or(0))
+            .collect();
+        let arg = |i: usize| numbers.get(i).copied().filter(|&n| n > 0).unwrap_or(1);
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        let here = self.cursor.1 * width + self.cursor.0;
+        match action {
+            'H' | 'f' => {
+                self.cursor = (
+                    (arg(1) - 1).min(width.saturating_sub(1)),
+                    (arg(0) - 1).min(height.saturating_sub(1)),
+                )
+            }
+            'A' => self.cursor.1 = self.cursor.1.saturating_sub(arg(0)),
+            'B' => self.cursor.1 = (self.cursor.1 + arg(0)).min(height.saturating_sub(1)),
+            'C' => self.cursor.0 = (self.cursor.0 + arg(0)).min(width.saturating_sub(1)),
+            'D' => self.cursor.0 = self.cursor.0.saturating_sub(arg(0)),
+            'G' => self.cursor.0 = (arg(0) - 1).min(width.saturating_sub(1)),
+            'J' => match numbers[0] {
+                0 => self.clear(here, self.cells.len()),
+                1 => self.clear(0, here + 1),
+                _ => self.clear(0, self.cells.len()),
+            },
+            'K' => {
+                let line = self.cursor.1 * width;
+                match numbers[0] {
+                    0 => self.clear(here, line + width),
+                    1 => self.clear(line, here + 1),
+                    _ => self.clear(line, line + width),
+                }
+            }
+            'm' if !params.starts_with('?') => self.select_graphics(&numbers),
+            _ => {}
+        }
+    }
+
+    /// Follows the color changes of a select graphic rendition sequence
+    fn select_graphics(&mut self, numbers: &[usize]) {
+        let mut i = 0;
+        while i < numbers.len() {
+            match numbers[i] {
+                0 | 39 => self.fg = DEFAULT_FG,
+                n @ 30..=37 => self.fg = BASIC_COLORS[n - 30],
+                n @ 90..=97 => self.fg = BASIC_COLORS[n - 82],
+                n @ (38 | 48) => {
+                    let value = |j: usize| numbers.get(i + j).copied().unwrap_or(0) as u8;
+                    let (color, used) = match numbers.get(i + 1) {
+                        Some(2) => ([value(2), value(3), value(4)], 4),
+                        Some(5) => (palette_color(value(2)), 2),
+                        _ => (DEFAULT_FG, 1),
+                    };
+                    if n == 38 {
+                        self.fg = color;
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Interprets as much of `text` as is complete, returning the length of
+    /// an escape sequence cut off at its end
+    fn interpret(&mut self, text: &str) -> usize {
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, ch)) = chars.next() {
+            match ch {
+                '\x1b' => {
+                    let Some(&(_, kind)) = chars.peek() else {
+                        return text.len() - start;
+                    };
+                    chars.next();
+                    match kind {
+                        '[' => {
+                            let mut params = String::new();
+                            let action = loop {
+                                match chars.next() {
+                                    Some((_, c)) if ('\x40'..='\x7e').contains(&c) => break c,
+                                    Some((_, c)) => params.push(c),
+                                    None => return text.len() - start,
+                                }
+                            };
+                            self.control(&params, action);
+                        }
+                        // Strings such as OSC and APC end with ST or BEL
+                        ']' | '_' | 'P' | '^' => loop {
+                            match chars.next() {
+                                Some((_, '\x07')) => break,
+                                Some((_, '\x1b')) if chars.peek().map(|&(_, c)| c) == Some('\\') => {
+                                    chars.next();
+                                    break;
+                                }
+                                Some(_) => {}
+                                None => return text.len() - start,
+                            }
+                        },
+                        _ => {}
+                    }
+                }
+                '\r' => self.cursor.0 = 0,
+                // Output isn't in raw mode, so a newline also returns
+                '\n' => self.newline(),
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+        0
+    }
+}
+
+impl Write for HeadlessTarget {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        // A character cut off at the end waits for the next write, and
+        // invalid bytes show as replacement characters
+        let complete = match std::str::from_utf8(&self.pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => self.pending.len(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        let rest = self.interpret(&text);
+
+        let mut pending = text.as_bytes()[text.len() - rest..].to_vec();
+        pending.extend_from_slice(&self.pending[complete..]);
+        self.pending = pending;
+        self.dirty = true;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.dirty {
+            self.frames += 1;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+impl RenderTarget for HeadlessTarget {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) {
+        self.resize_screen(width, height);
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), RendererError> {
+        self.resize_screen(width, height);
+        Ok(())
+    }
+
+    fn colors_enabled(&self) -> bool {
+        true
+    }
+
+    fn caps(&self) -> &TermCaps {
+        &self.caps
+    }
+
+    fn probe_caps(&mut self) {}
+
+    fn synchronized_output(&self) -> bool {
+        false
+    }
+
+    fn follows_window(&self) -> bool {
+        false
+    }
+
+    fn enter_alternate_screen(&mut self) -> Result<(), RendererError> {
+        self.clear(0, self.cells.len());
+        self.cursor = (0, 0);
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> Result<(), RendererError> {
+        Ok(())
+    }
+}
+
+/// Returns the color of an entry in the 256-color xterm palette
+fn palette_color(index: u8) -> Rgb {
+    match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            [level(n / 36), level(n / 6 % 6), level(n % 6)]
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            [gray, gray, gray]
+        }
+    }
+}
@@ -14,15 +14,14 @@
 use crossterm::{
     cursor::{Hide, Show},
     execute, queue,
-    terminal::{
-        disable_raw_mode, enable_raw_mode, size as term_size, Clear, ClearType,
-        EnterAlternateScreen, LeaveAlternateScreen,
-    },
+    terminal::{disable_raw_mode, enable_raw_mode, size as term_size, Clear, ClearType},
     tty::IsTty,
 };
 use std::io::{stdout, StdoutLock, Write};
 
 use super::error::RendererError;
+use crate::guard;
+use crate::termcaps::{self, TermCaps};
 
 /// Manages terminal state and operations.
 /// Ensures proper terminal state management and cleanup.
@@ -40,6 +39,8 @@ pub struct TerminalState {
     cursor_hidden: bool,
     /// Whether stdout is a TTY
     is_tty: bool,
+    /// What the terminal can do
+    caps: TermCaps,
 }
 
 impl TerminalState {
@@ -55,16 +56,30 @@ impl TerminalState {
         let term_size = if Self::is_test_env() {
             (80, 24) // Default size for tests
         } else {
-            term_size().map_err(|e| {
-                RendererError::TerminalError(format!("Failed to get terminal size: {}", e))
-            })?
+            match term_size() {
+                Ok(size) => size,
+                // Redirected output may have no console to measure
+                Err(_) if !stdout().is_tty() => (80, 24),
+                Err(e) => {
+                    return Err(RendererError::TerminalError(format!(
+                        "Failed to get terminal size: {}",
+                        e
+                    )))
+                }
+            }
         };
 
         // Check if stdout is a TTY
         let is_tty = !Self::is_test_env() && stdout().is_tty();
 
-        // Enable colors by default for TTY
-        let colors_enabled = is_tty;
+        // Enable colors by default for a TTY that understands them, and when
+        // rendering headless so the output can be checked
+        let colors_enabled = (is_tty && termcaps::enable_ansi()) || termcaps::is_headless();
+        let caps = if is_tty {
+            TermCaps::current().clone()
+        } else {
+            TermCaps::default()
+        };
 
         Ok(Self {
             term_size,
@@ -73,6 +88,7 @@ impl TerminalState {
             raw_mode: false,
             cursor_hidden: false,
             is_tty,
+            caps,
         })
     }
 
@@ -138,7 +154,7 @@ impl TerminalState {
 
         // Leave alternate screen if active
         if self.alternate_screen {
-            execute!(stdout, LeaveAlternateScreen)?;
+            guard::leave_alternate_screen(&mut stdout)?;
             self.alternate_screen = false;
         }
 
@@ -160,7 +176,7 @@ impl TerminalState {
         }
 
         if !self.alternate_screen {
-            execute!(stdout(), EnterAlternateScreen)?;
+            guard::enter_alternate_screen(&mut stdout())?;
             self.alternate_screen = true;
         }
 
@@ -193,6 +209,12 @@ impl TerminalState {
         self.term_size
     }
 
+    /// Updates the stored terminal size without touching the screen, for
+    /// output that scrolls rather than being redrawn.
+    pub fn set_size(&mut self, width: u16, height: u16) {
+        self.term_size = (width, height);
+    }
+
     /// Updates stored terminal size and handles resize.
     ///
     /// # Effects
@@ -228,6 +250,20 @@ impl TerminalState {
         self.is_tty
     }
 
+    /// Returns what the terminal can do.
+    #[inline]
+    pub fn caps(&self) -> &TermCaps {
+        &self.caps
+    }
+
+    /// Asks the terminal what it can do, on top of what the environment
+    /// says. Only animation needs the answers, so this waits until then.
+    pub fn probe_caps(&mut self) {
+        if self.is_tty {
+            self.caps = TermCaps::probed().clone();
+        }
+    }
+
     /// Shows the cursor if currently hidden.
     pub fn show_cursor(&mut self) -> Result<(), RendererError> {
         if self.is_tty && self.cursor_hidden {
@@ -252,7 +288,7 @@ impl TerminalState {
             // Try to restore known good state
             let _ = execute!(stdout(), Show);
             let _ = disable_raw_mode();
-            let _ = execute!(stdout(), LeaveAlternateScreen);
+            let _ = guard::leave_alternate_screen(&mut stdout());
 
             // Reinitialize
             self.setup()?;
@@ -260,10 +296,11 @@ impl TerminalState {
         Ok(())
     }
 
-    /// Returns true if running in a test environment
+    /// Returns true if running in a test environment or headless
     #[inline]
     fn is_test_env() -> bool {
-        std::env::var("RUST_TEST").is_ok()
+        termcaps::is_headless()
+            || std::env::var("RUST_TEST").is_ok()
             || std::env::var("CARGO_TARGET_DIR").is_ok()
             || std::env::var("CI").is_ok()
             || std::env::var("TERM").map(|v| v == "dumb").unwrap_or(false)
@@ -278,3 +315,19 @@ impl Drop for TerminalState {
         }
     }
 }
+
+/// Returns whether key events can be read. They come from stdin when it is
+/// a terminal, and otherwise from the controlling terminal, `/dev/tty` or
+/// `CONIN$` on Windows, so piped input leaves the keyboard working as long
+/// as the process has a terminal at all.
+pub fn keyboard_available() -> bool {
+    if std::io::stdin().is_tty() {
+        return true;
+    }
+    let tty = if cfg!(windows) { "CONIN$" } else { "/dev/tty" };
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tty)
+        .is_ok()
+}
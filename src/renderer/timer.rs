@@ -0,0 +1,52 @@
+//! Countdown theme shifting
+//!
+//! While the clock art counts down, the colors can drift from the theme the
+//! countdown started with toward another one, so how much time is left shows
+//! at a glance: `chromacat timer 25m` starts calm and ends on a warning
+//! theme. The blend moves in small steps rather than every frame, since each
+//! step rebuilds the gradient.
+
+use crate::error::Result;
+use crate::themes::ThemeDefinition;
+
+/// Steps the shift from one theme to the other is divided into
+pub const SHIFT_STEPS: u32 = 100;
+
+/// Colors drifting between two themes as a countdown runs out
+#[derive(Debug, Clone)]
+pub struct CountdownShift {
+    /// Theme at the start of the countdown
+    from: ThemeDefinition,
+    /// Theme once time is up
+    to: ThemeDefinition,
+    /// Step last blended, if any
+    step: Option<u32>,
+}
+
+impl CountdownShift {
+    /// Creates a shift from theme `from` to theme `to`
+    pub fn new(from: ThemeDefinition, to: ThemeDefinition) -> Self {
+        Self {
+            from,
+            to,
+            step: None,
+        }
+    }
+
+    /// Returns the theme the shift ends on
+    pub fn target(&self) -> &ThemeDefinition {
+        &self.to
+    }
+
+    /// Returns the blended theme for `progress` through the countdown, from
+    /// 0 to 1, or `None` when it is still on the step last returned
+    pub fn theme_at(&mut self, progress: f64) -> Result<Option<ThemeDefinition>> {
+        let step = (progress.clamp(0.0, 1.0) * SHIFT_STEPS as f64).floor() as u32;
+        if self.step == Some(step) {
+            return Ok(None);
+        }
+        self.step = Some(step);
+        let amount = step as f32 / SHIFT_STEPS as f32;
+        self.from.blend(&self.to, amount).map(Some)
+    }
+}
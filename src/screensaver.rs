@@ -0,0 +1,106 @@
+//! Screen saver mode
+//!
+//! `--screensaver` plays random scenes full screen until any key is
+//! pressed. With `--idle` it first waits for the terminal to sit untouched
+//! for a while, and gives up if a key is pressed before then. The key that
+//! wakes it belongs to the screen saver: everything typed is read and thrown
+//! away before the terminal is handed back, so none of it reaches the shell.
+//! A `--lock-cmd` runs on the blanked screen after waking, and the screen
+//! saver only exits once the locker does.
+
+use crossterm::cursor::{MoveTo, Show};
+use crossterm::event::{self, Event};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, Clear, ClearType};
+use std::io::{self, stdout};
+use std::process::{Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+use crate::guard;
+
+/// How often the idle wait checks for signals while no input arrives
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Returns true if an input event should wake the screen saver. Resizes
+/// and focus changes happen without anyone touching the keyboard.
+pub fn is_wake_event(event: &Event) -> bool {
+    matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_))
+}
+
+/// Waits until the terminal has had no input for `idle`.
+///
+/// Returns false without waiting out the rest if input arrives or the run
+/// is interrupted first; the input is discarded.
+pub fn wait_for_idle(idle: Duration) -> io::Result<bool> {
+    // Keys must arrive one at a time and without being echoed
+    let was_raw = is_raw_mode_enabled()?;
+    if !was_raw {
+        enable_raw_mode()?;
+    }
+
+    let deadline = Instant::now() + idle;
+    let result = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break Ok(true);
+        }
+        if guard::interrupted() {
+            break Ok(false);
+        }
+        match event::poll(remaining.min(POLL_INTERVAL)) {
+            Ok(true) => match event::read() {
+                Ok(event) if is_wake_event(&event) => break discard_input().map(|_| false),
+                Ok(_) => {}
+                Err(e) => break Err(e),
+            },
+            Ok(false) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    if !was_raw {
+        disable_raw_mode()?;
+    }
+    result
+}
+
+/// Reads and drops all pending input, including bytes the terminal has
+/// queued but not yet delivered, so it isn't left for the shell
+pub fn discard_input() -> io::Result<()> {
+    while event::poll(Duration::ZERO)? {
+        event::read()?;
+    }
+
+    #[cfg(unix)]
+    if let Ok(tty) = std::fs::File::open("/dev/tty") {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: the descriptor belongs to `tty`, which outlives the call
+        unsafe {
+            libc::tcflush(tty.as_raw_fd(), libc::TCIFLUSH);
+        }
+    }
+    Ok(())
+}
+
+/// Blanks the screen and runs `command` through the shell with the terminal
+/// in normal mode, waiting for it to exit
+pub fn run_locker(command: &str) -> io::Result<ExitStatus> {
+    execute!(stdout(), Clear(ClearType::All), MoveTo(0, 0), Show)?;
+    if is_raw_mode_enabled()? {
+        disable_raw_mode()?;
+    }
+
+    #[cfg(unix)]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    #[cfg(not(unix))]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    shell.arg(command).status()
+}
@@ -0,0 +1,182 @@
+//! Single-line output for status bars
+//!
+//! `--status-line WIDTH` renders one frame of gradient-colored text that is
+//! exactly `WIDTH` columns wide, for embedding in tmux's `status-right` or a
+//! zellij plugin. The caller runs chromacat once per refresh and passes the
+//! animation time with `--time-offset`, so successive invocations animate.
+//!
+//! The output never contains cursor movement or other control sequences:
+//! only color changes, either as SGR escapes or as tmux `#[fg=...]` styles.
+
+use std::fmt::{self, Write as FmtWrite};
+use std::str::FromStr;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::error::Result;
+use crate::escape::{tokenize, Token};
+use crate::pattern::PatternEngine;
+
+/// How colors are written into a status line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFormat {
+    /// 24-bit SGR escapes, for zellij and other terminals that pass them through
+    #[default]
+    Ansi,
+    /// tmux style directives such as `#[fg=#ff8800]`
+    Tmux,
+}
+
+impl StatusFormat {
+    /// Returns a list of all status line formats
+    pub fn all() -> &'static [StatusFormat] {
+        &[StatusFormat::Ansi, StatusFormat::Tmux]
+    }
+
+    /// Get string representation of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusFormat::Ansi => "ansi",
+            StatusFormat::Tmux => "tmux",
+        }
+    }
+}
+
+impl fmt::Display for StatusFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for StatusFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ansi" => Ok(StatusFormat::Ansi),
+            "tmux" => Ok(StatusFormat::Tmux),
+            _ => Err(format!(
+                "Invalid status line format '{}'. Valid formats: ansi, tmux",
+                s
+            )),
+        }
+    }
+}
+
+/// Renders text as a fixed-width, gradient-colored status line
+pub struct StatusLine {
+    /// Pattern engine sized to the line, with its time already set
+    engine: PatternEngine,
+    /// Exact width of the output in columns
+    width: usize,
+    /// How colors are written
+    format: StatusFormat,
+    /// Whether to emit colors at all
+    colors_enabled: bool,
+}
+
+impl StatusLine {
+    /// Creates a status line renderer; the engine should be sized `width` x 1
+    pub fn new(engine: PatternEngine, width: usize) -> Self {
+        Self {
+            engine,
+            width,
+            format: StatusFormat::default(),
+            colors_enabled: true,
+        }
+    }
+
+    /// Sets how colors are written
+    pub fn with_format(mut self, format: StatusFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Enables or disables color output
+    pub fn set_colors_enabled(&mut self, enabled: bool) {
+        self.colors_enabled = enabled;
+    }
+
+    /// Renders text as one line of exactly the configured width, without a
+    /// trailing newline
+    pub fn render(&self, text: &str) -> Result<String> {
+        let text = fit_width(&sanitize(text), self.width);
+        let mut out = String::with_capacity(text.len() * 20);
+        let mut column = 0;
+        let mut last = None;
+
+        for grapheme in text.graphemes(true) {
+            if self.colors_enabled && grapheme != " " {
+                let value = self.engine.get_value_at(column, 0)?;
+                let light = self.engine.glyph_light_at(column, 0);
+                let color = self.engine.cell_color(value, light, column, 0);
+                let rgb = (
+                    (color.r * 255.0) as u8,
+                    (color.g * 255.0) as u8,
+                    (color.b * 255.0) as u8,
+                );
+                if last != Some(rgb) {
+                    self.push_color(&mut out, rgb);
+                    last = Some(rgb);
+                }
+            }
+            match (self.format, grapheme) {
+                (StatusFormat::Tmux, "#") => out.push_str("##"),
+                _ => out.push_str(grapheme),
+            }
+            column += grapheme.width();
+        }
+
+        if last.is_some() {
+            out.push_str(match self.format {
+                StatusFormat::Ansi => "\x1b[39m",
+                StatusFormat::Tmux => "#[default]",
+            });
+        }
+        Ok(out)
+    }
+
+    /// Writes a foreground color change in the configured format
+    fn push_color(&self, out: &mut String, (r, g, b): (u8, u8, u8)) {
+        let _ = match self.format {
+            StatusFormat::Ansi => write!(out, "\x1b[38;2;{};{};{}m", r, g, b),
+            StatusFormat::Tmux => write!(out, "#[fg=#{:02x}{:02x}{:02x}]", r, g, b),
+        };
+    }
+}
+
+/// Flattens text onto one line: removes escape sequences and control
+/// characters, and collapses whitespace runs, including newlines, into
+/// single spaces
+pub fn sanitize(text: &str) -> String {
+    let plain: String = tokenize(text)
+        .filter_map(|token| match token {
+            Token::Text(s) => Some(s),
+            _ => None,
+        })
+        .collect();
+    plain
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Truncates or pads text to exactly `width` columns.
+///
+/// A wide character that would straddle the edge is replaced by padding.
+pub fn fit_width(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(width);
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let w = grapheme.width();
+        if used + w > width {
+            break;
+        }
+        out.push_str(grapheme);
+        used += w;
+    }
+    out.extend(std::iter::repeat_n(' ', width - used));
+    out
+}
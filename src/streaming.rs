@@ -12,9 +12,19 @@ use std::time::{Duration, Instant};
 
 use crossterm::style::Color;
 use log::{debug, trace};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use crate::columns::{ColumnPalettes, Columns};
+use crate::data::{DataColumn, DataReader};
+use crate::diff::{DiffClassifier, DiffPalettes};
 use crate::error::{ChromaCatError, Result};
-use crate::pattern::{PatternConfig, PatternEngine};
+use crate::escape::{self, Token};
+use crate::guard;
+use crate::input::{BinaryPolicy, DecodedInput};
+use crate::pattern::{LightMap, PatternConfig, PatternEngine, PostProcess};
+use crate::renderer::{gutter, Gutter, ResizeWatcher, GUTTER_COLOR};
+use crate::termcaps::ColorSupport;
 use crate::themes;
 
 /// Default buffer capacity for streaming input
@@ -23,6 +33,10 @@ const DEFAULT_BUFFER_CAPACITY: usize = 8192;
 /// Minimum sleep duration when no data is available (milliseconds)
 const MIN_SLEEP_MS: u64 = 10;
 
+/// Highest line number the gutter is sized for. The number of lines isn't
+/// known ahead of time, so numbers are padded to six digits like `cat -n`.
+const GUTTER_LINES: usize = 999_999;
+
 /// Statistics for stream processing
 #[derive(Debug, Default)]
 struct StreamStats {
@@ -70,6 +84,30 @@ pub struct StreamingInput {
     buffer_capacity: usize,
     /// Processing statistics
     stats: StreamStats,
+    /// Current output column when processing raw chunks
+    column: usize,
+    /// Unterminated escape sequence carried over to the next chunk
+    pending: String,
+    /// Notices when the terminal changes size, so the gradient keeps
+    /// spanning the window
+    resize: ResizeWatcher,
+    /// Classifier following the diff being read, and the palettes of its
+    /// kinds of line, when coloring diffs
+    diff: Option<(DiffClassifier, DiffPalettes)>,
+    /// How table columns are found and colored, and the columns found from
+    /// the first line, when coloring by column
+    columns: Option<(Columns, Option<ColumnPalettes>)>,
+    /// Which field's numbers color the lines, and the reader set up from
+    /// the first line with text, when coloring by data
+    data: Option<(DataColumn, Option<DataReader>)>,
+    /// Line numbers and ruler drawn around the text
+    gutter: Gutter,
+    /// Number of input lines read so far
+    line_number: usize,
+    /// Colors the terminal can show, which colors are written as
+    color_support: ColorSupport,
+    /// What happens to binary chunks in the input
+    binary: BinaryPolicy,
 }
 
 impl StreamingInput {
@@ -85,11 +123,13 @@ impl StreamingInput {
         debug!("Creating StreamingInput with theme: {}", theme_name);
 
         let theme = themes::get_theme(theme_name)?;
-        let gradient = theme.create_gradient()?;
 
-        // Create pattern engine with default terminal size
-        // Actual dimensions don't matter much for streaming since we process line by line
-        let engine = PatternEngine::new(gradient, config, 80, 24);
+        // Size the pattern to the terminal, falling back to a default size
+        // when the output isn't one
+        let resize = ResizeWatcher::new();
+        let (width, height) = resize.size().unwrap_or((80, 24));
+        let engine =
+            PatternEngine::from_theme(&theme, config, width as usize, height as usize)?;
 
         Ok(Self {
             engine,
@@ -97,6 +137,16 @@ impl StreamingInput {
             stop_signal: Arc::new(AtomicBool::new(false)),
             buffer_capacity: DEFAULT_BUFFER_CAPACITY,
             stats: StreamStats::default(),
+            column: 0,
+            pending: String::new(),
+            resize,
+            diff: None,
+            columns: None,
+            data: None,
+            gutter: Gutter::default(),
+            line_number: 0,
+            color_support: ColorSupport::default(),
+            binary: BinaryPolicy::default(),
         })
     }
 
@@ -112,16 +162,21 @@ impl StreamingInput {
         self.stats.start();
 
         let mut stdout = io::stdout();
-        let buf_reader = BufReader::with_capacity(self.buffer_capacity, reader);
+        let mut buf_reader = DecodedInput::new(
+            BufReader::with_capacity(self.buffer_capacity, reader),
+            "input",
+        );
+        buf_reader.set_binary_policy(self.binary);
 
         for line in buf_reader.lines() {
             // Check stop signal
-            if self.stop_signal.load(Ordering::Relaxed) {
+            if self.stop_signal.load(Ordering::Relaxed) || guard::interrupted() {
                 debug!("Stop signal received, ending stream processing");
                 break;
             }
 
             let line = line?;
+            self.follow_resize();
             self.process_line(&line, &mut stdout)?;
 
             trace!("Processed line: {} characters", line.len());
@@ -146,6 +201,22 @@ impl StreamingInput {
     /// # Returns
     /// Ok(()) if successful, Error otherwise
     fn process_line<W: Write>(&mut self, line: &str, writer: &mut W) -> Result<()> {
+        // Every line moves the diff classifier along, even blank ones
+        let palette = self.diff.as_mut().and_then(|(classifier, palettes)| {
+            palettes.get(classifier.classify(line)).cloned()
+        });
+
+        // Blank lines are numbered too, so numbers match the input
+        self.line_number += 1;
+        let gutter_width = self.gutter.width(GUTTER_LINES);
+        if self.gutter.ruler && self.line_number == 1 {
+            let width = self.resize.size().map_or(80, |(width, _)| width as usize);
+            let ruler = " ".repeat(gutter_width)
+                + &gutter::ruler(0, width.saturating_sub(gutter_width).max(1));
+            self.write_gutter(&ruler, writer)?;
+            writeln!(writer)?;
+        }
+
         // Trim any trailing whitespace/newlines
         let line = line.trim_end();
 
@@ -154,24 +225,69 @@ impl StreamingInput {
             return Ok(());
         }
 
+        if gutter_width > 0 {
+            self.write_gutter(&gutter::label(Some(self.line_number), gutter_width), writer)?;
+        }
+
         if !self.colors_enabled {
             writeln!(writer, "{}", line)?;
             return Ok(());
         }
 
-        // Strip existing ANSI escape sequences
+        // Strip escaped color codes as written by some loggers
         let line = line
-            .replace("\x1B[33m", "") // Remove yellow color
-            .replace("\x1B[0m", "") // Remove reset
             .replace("#033[33m", "") // Remove yellow (alternate form)
             .replace("#033[0m", ""); // Remove reset (alternate form)
 
-        // Generate colors for each character
+        // Generate colors per grapheme, sampled at its starting column so
+        // wide characters don't shift the gradient for the rest of the line
         let mut current_color = None;
+        let mut x = 0;
+
+        // Existing colors are replaced, other escape sequences pass through
+        let mut pieces = Vec::with_capacity(line.len());
+        for token in escape::tokenize(&line) {
+            match token {
+                Token::Text(text) => pieces.extend(text.graphemes(true)),
+                Token::Sgr(_) => {}
+                Token::Passthrough(sequence) => pieces.push(sequence),
+            }
+        }
+
+        // Lines with a number in the data field take their color from it
+        let data_value = self.data.as_mut().and_then(|(data, reader)| {
+            reader
+                .get_or_insert_with(|| data.reader_for(&line))
+                .value(&line)
+        });
+
+        // The first line with text decides the columns
+        let mut columns = self.columns.as_mut().map(|(columns, found)| {
+            let found = &*found.get_or_insert_with(|| columns.palettes_for(&line));
+            (found, found.tracker())
+        });
+
+        for grapheme in pieces {
+            if escape::is_escape(grapheme) {
+                write!(writer, "{}", grapheme)?;
+                continue;
+            }
 
-        for (x, ch) in line.chars().enumerate() {
-            let pattern_value = self.engine.get_value_at(x, 0)?;
-            let gradient_color = self.engine.gradient().at(pattern_value as f32);
+            let cell_palette = match &mut columns {
+                Some((found, tracker)) => found.get(tracker.next(grapheme)).map(Arc::as_ref),
+                None => palette.as_deref(),
+            };
+            let (pattern_value, light) = match data_value {
+                Some(value) => (value, None),
+                None => (
+                    self.engine.get_value_at(x, 0)?,
+                    self.engine.glyph_light_at(x, 0),
+                ),
+            };
+            let gradient_color =
+                self.engine
+                    .cell_color_in(cell_palette, pattern_value, light, x, 0);
+            x += grapheme.width();
 
             // Convert to RGB
             let color = Color::Rgb {
@@ -184,15 +300,15 @@ impl StreamingInput {
             if current_color != Some(color) {
                 match color {
                     Color::Rgb { r, g, b } => {
-                        write!(writer, "\x1b[38;2;{};{};{}m", r, g, b)?;
+                        write!(writer, "{}", self.color_support.fg(r, g, b))?;
                     }
                     _ => unreachable!("We only use RGB colors"),
                 }
                 current_color = Some(color);
             }
 
-            // Write character
-            write!(writer, "{}", ch)?;
+            // Write grapheme
+            write!(writer, "{}", grapheme)?;
         }
 
         // Reset color and add newline
@@ -205,6 +321,95 @@ impl StreamingInput {
         Ok(())
     }
 
+    /// Processes a chunk of raw terminal output, such as data read from a PTY.
+    ///
+    /// Unlike [`process_line`](Self::process_line), chunks may end mid-line or
+    /// mid-escape-sequence. The cursor column is tracked across chunks so the
+    /// gradient stays continuous, and unterminated sequences are held back
+    /// until the next chunk completes them.
+    ///
+    /// # Arguments
+    /// * `chunk` - The text to process
+    /// * `writer` - The output writer
+    ///
+    /// # Returns
+    /// Ok(()) if successful, Error otherwise
+    pub fn process_chunk<W: Write>(&mut self, chunk: &str, writer: &mut W) -> Result<()> {
+        self.follow_resize();
+        let mut data = std::mem::take(&mut self.pending);
+        data.push_str(chunk);
+        let (complete, incomplete) = escape::split_incomplete(&data);
+
+        let mut current_color = None;
+        let mut colored = false;
+        for token in escape::tokenize(complete) {
+            match token {
+                Token::Text(text) => {
+                    for grapheme in text.graphemes(true) {
+                        match grapheme {
+                            "\n" | "\r\n" => {
+                                self.column = 0;
+                                self.engine.update(0.1);
+                                self.stats.update(0);
+                            }
+                            "\r" => self.column = 0,
+                            "\x08" => self.column = self.column.saturating_sub(1),
+                            _ if grapheme.chars().all(char::is_control) => {}
+                            _ if self.colors_enabled => {
+                                let pattern_value = self.engine.get_value_at(self.column, 0)?;
+                                let light = self.engine.glyph_light_at(self.column, 0);
+                                let gradient_color =
+                                    self.engine.cell_color(pattern_value, light, self.column, 0);
+                                let color = (
+                                    (gradient_color.r * 255.0) as u8,
+                                    (gradient_color.g * 255.0) as u8,
+                                    (gradient_color.b * 255.0) as u8,
+                                );
+                                if current_color != Some(color) {
+                                    write!(
+                                        writer,
+                                        "{}",
+                                        self.color_support.fg(color.0, color.1, color.2)
+                                    )?;
+                                    current_color = Some(color);
+                                    colored = true;
+                                }
+                                self.column += grapheme.width();
+                            }
+                            _ => self.column += grapheme.width(),
+                        }
+                        write!(writer, "{}", grapheme)?;
+                    }
+                }
+                // Keep the program's own colors when we aren't applying ours
+                Token::Sgr(sequence) if !self.colors_enabled => write!(writer, "{}", sequence)?,
+                Token::Sgr(_) => {}
+                Token::Passthrough(sequence) => {
+                    // Anything that moves the cursor invalidates the active color
+                    current_color = None;
+                    write!(writer, "{}", sequence)?;
+                }
+            }
+        }
+
+        if colored {
+            write!(writer, "\x1b[0m")?;
+        }
+        writer.flush()?;
+
+        self.stats.bytes_processed += complete.len();
+        self.pending = incomplete.to_string();
+        Ok(())
+    }
+
+    /// Resizes the pattern when the terminal has changed size
+    fn follow_resize(&mut self) {
+        if let Some((width, height)) = self.resize.poll() {
+            debug!("Terminal resized to {}x{}", width, height);
+            self.engine = self.engine.recreate(width as usize, height as usize);
+        }
+    }
+
     /// Sets the buffer capacity for reading
     ///
     /// # Arguments
@@ -221,6 +426,66 @@ impl StreamingInput {
         self.colors_enabled = enabled;
     }
 
+    /// Writes colors as near as a terminal with the given support can show
+    /// them
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+
+    /// Sets the value post-processing applied before gradient lookup
+    ///
+    /// # Arguments
+    /// * `postprocess` - Posterize and dither settings
+    pub fn set_postprocess(&mut self, postprocess: PostProcess) {
+        self.engine.set_postprocess(postprocess);
+    }
+
+    /// Sets the second theme that shades colors; see [`LightMap`]
+    pub fn set_light_map(&mut self, light: Option<LightMap>) {
+        self.engine.set_light_map(light);
+    }
+
+    /// Colors the lines of unified diffs by kind with `palettes`, or stops
+    /// when None; see [`crate::diff`]
+    pub fn set_diff(&mut self, palettes: Option<DiffPalettes>) {
+        self.diff = palettes.map(|palettes| (DiffClassifier::new(), palettes));
+    }
+
+    /// Colors table columns each with their own palette, or stops when None;
+    /// the columns are found from the first line that has any text. See
+    /// [`crate::columns`].
+    pub fn set_columns(&mut self, columns: Option<Columns>) {
+        self.columns = columns.map(|columns| (columns, None));
+    }
+
+    /// Colors each line by the number in one of its fields rather than by
+    /// the pattern, or stops when None; the delimiter is found from the
+    /// first line that has any text. See [`crate::data`].
+    pub fn set_data(&mut self, data: Option<DataColumn>) {
+        self.data = data.map(|data| (data, None));
+    }
+
+    /// Numbers lines and draws a ruler above them as `gutter` says
+    pub fn set_gutter(&mut self, gutter: Gutter) {
+        self.gutter = gutter;
+    }
+
+    /// Sets what happens to binary chunks in the input
+    pub fn set_binary_policy(&mut self, policy: BinaryPolicy) {
+        self.binary = policy;
+    }
+
+    /// Writes gutter text in the gutter color when colors are enabled
+    fn write_gutter<W: Write>(&self, text: &str, writer: &mut W) -> Result<()> {
+        match GUTTER_COLOR {
+            Color::Rgb { r, g, b } if self.colors_enabled => {
+                write!(writer, "{}{}\x1b[0m", self.color_support.fg(r, g, b), text)?
+            }
+            _ => write!(writer, "{}", text)?,
+        }
+        Ok(())
+    }
+
     /// Processes input from stdin with non-blocking reads
     ///
     /// # Returns
@@ -233,12 +498,16 @@ impl StreamingInput {
         let mut stdout = io::stdout();
 
         // Create non-blocking stdin reader
-        let mut reader = BufReader::with_capacity(self.buffer_capacity, stdin);
+        let mut reader = DecodedInput::new(
+            BufReader::with_capacity(self.buffer_capacity, stdin),
+            "stdin",
+        );
+        reader.set_binary_policy(self.binary);
         let mut buffer = String::with_capacity(self.buffer_capacity);
 
         loop {
             // Check stop signal
-            if self.stop_signal.load(Ordering::Relaxed) {
+            if self.stop_signal.load(Ordering::Relaxed) || guard::interrupted() {
                 debug!("Stop signal received, ending stdin processing");
                 break;
             }
@@ -309,6 +578,7 @@ mod tests {
         PatternConfig {
             common: CommonParams::default(),
             params: PatternParams::Horizontal(HorizontalParams::default()),
+            layers: Vec::new(),
         }
     }
 
@@ -368,6 +638,86 @@ mod tests {
         assert_eq!(bytes, input.len() - 1); // -1 for the newline
     }
 
+    #[test]
+    fn test_streaming_invalid_utf8() {
+        let reader = Cursor::new(b"caf\xe9\n\x00\x01binary\nok\n".to_vec());
+
+        let mut processor = StreamingInput::new(create_test_config(), "rainbow")
+            .expect("Failed to create processor");
+
+        processor.set_colors_enabled(false);
+        processor.set_binary_policy(BinaryPolicy::Skip);
+
+        processor
+            .process_stream(reader)
+            .expect("Failed to process stream");
+
+        let (lines, _, _) = processor.stats();
+        assert_eq!(lines, 2);
+    }
+
+    #[test]
+    fn test_streaming_keeps_graphemes_intact() {
+        let mut processor = StreamingInput::new(create_test_config(), "rainbow")
+            .expect("Failed to create processor");
+
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let accented = "e\u{301}";
+        let mut output = Vec::new();
+        processor
+            .process_line(&format!("{}{}世", family, accented), &mut output)
+            .expect("Failed to process line");
+
+        // Color codes may only appear between grapheme clusters
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(family));
+        assert!(output.contains(accented));
+        assert!(output.contains('世'));
+    }
+
+    #[test]
+    fn test_streaming_preserves_hyperlinks() {
+        let mut processor = StreamingInput::new(create_test_config(), "rainbow")
+            .expect("Failed to create processor");
+
+        let open = "\x1b]8;;https://example.com\x1b\\";
+        let close = "\x1b]8;;\x1b\\";
+        let mut output = Vec::new();
+        processor
+            .process_line(&format!("\x1b[1mgo {}here{}", open, close), &mut output)
+            .expect("Failed to process line");
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(open));
+        assert!(output.contains(close));
+        assert!(!output.contains("\x1b[1m"));
+    }
+
+    #[test]
+    fn test_process_chunk_handles_split_sequences() {
+        let mut processor = StreamingInput::new(create_test_config(), "rainbow")
+            .expect("Failed to create processor");
+
+        // A hyperlink split across two reads is emitted whole, after the split
+        let mut first = Vec::new();
+        processor
+            .process_chunk("ab\x1b]8;;https://exa", &mut first)
+            .expect("Failed to process chunk");
+        let first = String::from_utf8(first).unwrap();
+        assert!(!first.contains("\x1b]8"));
+        assert_eq!(processor.column, 2);
+
+        let mut second = Vec::new();
+        processor
+            .process_chunk("mple.com\x1b\\c\r\n", &mut second)
+            .expect("Failed to process chunk");
+        let second = String::from_utf8(second).unwrap();
+        assert!(second.contains("\x1b]8;;https://example.com\x1b\\"));
+        assert!(second.ends_with("\r\n\x1b[0m"));
+        assert_eq!(processor.column, 0);
+        assert!(processor.pending.is_empty());
+    }
+
     #[test]
     fn test_buffer_capacity() {
         let mut processor = StreamingInput::new(create_test_config(), "rainbow")
@@ -0,0 +1,242 @@
+//! Synchronizing animations across instances
+//!
+//! For video walls, one instance runs with `--sync-leader ADDR` and any
+//! number of others, possibly on other machines, connect with
+//! `--sync-follow HOST:PORT`. The leader streams its animation clock and
+//! playlist position over TCP several times a second; followers adopt them,
+//! so scene changes happen everywhere at once.
+//!
+//! Each update is one line of text:
+//! `chromacat-sync time=12.500 scene=2 elapsed=4.250 paused=0`, where `scene`
+//! and `elapsed` are omitted when the leader has no playlist. Followers should
+//! run the same playlist as the leader.
+//!
+//! The leader writes to each follower from a thread of its own, so a slow
+//! or stalled follower never holds up the animation; one that falls behind
+//! is dropped.
+
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+
+use crate::error::{ChromaCatError, Result};
+
+/// How often the leader sends its state, in addition to scene changes
+pub const SYNC_INTERVAL: Duration = Duration::from_millis(100);
+
+/// First word of every update line
+const SYNC_PREFIX: &str = "chromacat-sync";
+
+/// Delay between a follower's connection attempts
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest a follower's writer waits on a write before dropping it
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Updates waiting for a follower before it counts as fallen behind and is
+/// dropped
+const FOLLOWER_BACKLOG: usize = 8;
+
+/// Animation state shared by the leader
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncState {
+    /// Pattern animation time
+    pub time: f64,
+    /// Playlist entry index and seconds spent in it, if a playlist is playing
+    pub scene: Option<(usize, f64)>,
+    /// Whether the animation is paused
+    pub paused: bool,
+}
+
+impl fmt::Display for SyncState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} time={:.4}", SYNC_PREFIX, self.time)?;
+        if let Some((index, elapsed)) = self.scene {
+            write!(f, " scene={} elapsed={:.3}", index, elapsed)?;
+        }
+        write!(f, " paused={}", u8::from(self.paused))
+    }
+}
+
+impl FromStr for SyncState {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        if words.next() != Some(SYNC_PREFIX) {
+            return Err(format!("Not a sync update: '{}'", s));
+        }
+
+        let mut time = None;
+        let mut scene = None;
+        let mut elapsed = None;
+        let mut paused = false;
+        for word in words {
+            let (key, value) = word
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed sync field '{}'", word))?;
+            match key {
+                "time" => time = Some(parse_field::<f64>(key, value)?),
+                "scene" => scene = Some(parse_field::<usize>(key, value)?),
+                "elapsed" => elapsed = Some(parse_field::<f64>(key, value)?),
+                "paused" => paused = value == "1",
+                // Ignore fields added by newer versions
+                _ => {}
+            }
+        }
+
+        let time = time
+            .filter(|t| t.is_finite())
+            .ok_or("Sync update has no time")?;
+        Ok(Self {
+            time,
+            scene: scene.map(|index| (index, elapsed.unwrap_or(0.0).max(0.0))),
+            paused,
+        })
+    }
+}
+
+/// Parses the value of one `key=value` field
+fn parse_field<T: FromStr>(key: &str, value: &str) -> std::result::Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: '{}'", key, value))
+}
+
+/// Accepts followers and sends them the leader's state
+pub struct SyncLeader {
+    /// Address the leader listens on
+    addr: SocketAddr,
+    /// Update queues of the connected followers' writers; followers whose
+    /// queue fills up or whose writer stops are dropped
+    followers: Arc<Mutex<Vec<SyncSender<Arc<str>>>>>,
+}
+
+impl SyncLeader {
+    /// Listens for followers on an address such as `0.0.0.0:7878`
+    pub fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr).map_err(|e| {
+            ChromaCatError::Other(format!("Cannot listen for sync followers on {}: {}", addr, e))
+        })?;
+        let addr = listener.local_addr()?;
+        info!("Sync leader listening on {}", addr);
+
+        let followers = Arc::new(Mutex::new(Vec::new()));
+        let accepted = Arc::clone(&followers);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        info!("Sync follower connected: {:?}", stream.peer_addr());
+                        let _ = stream.set_nodelay(true);
+                        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                        if let Ok(mut followers) = accepted.lock() {
+                            followers.push(spawn_writer(stream));
+                        }
+                    }
+                    Err(e) => warn!("Sync accept failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self { addr, followers })
+    }
+
+    /// Returns the address the leader listens on, with the actual port when
+    /// bound to port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the number of connected followers
+    pub fn follower_count(&self) -> usize {
+        self.followers.lock().map(|f| f.len()).unwrap_or(0)
+    }
+
+    /// Queues a state update for every follower, without waiting for any
+    /// of them
+    pub fn broadcast(&self, state: &SyncState) {
+        let line: Arc<str> = format!("{}\n", state).into();
+        if let Ok(mut followers) = self.followers.lock() {
+            followers.retain(|follower| match follower.try_send(Arc::clone(&line)) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    info!("Sync follower dropped: it fell behind");
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+        }
+    }
+}
+
+/// Starts a thread writing the updates queued on the returned sender to a
+/// follower, until a write fails or the sender is dropped
+fn spawn_writer(mut stream: TcpStream) -> SyncSender<Arc<str>> {
+    let (sender, lines) = mpsc::sync_channel::<Arc<str>>(FOLLOWER_BACKLOG);
+    thread::spawn(move || {
+        for line in lines {
+            if let Err(e) = stream.write_all(line.as_bytes()) {
+                info!("Sync follower dropped: {}", e);
+                break;
+            }
+        }
+    });
+    sender
+}
+
+/// Receives state updates from a leader in the background, reconnecting
+/// whenever the connection is lost
+pub struct SyncFollower {
+    /// Updates in arrival order
+    updates: Receiver<SyncState>,
+}
+
+impl SyncFollower {
+    /// Starts following the leader at `HOST:PORT`
+    pub fn connect(addr: &str) -> Result<Self> {
+        // Catch typos now rather than retrying forever
+        addr.to_socket_addrs().map_err(|e| {
+            ChromaCatError::InputError(format!("Invalid sync leader address '{}': {}", addr, e))
+        })?;
+
+        let addr = addr.to_string();
+        let (sender, updates) = mpsc::channel();
+        thread::spawn(move || loop {
+            match TcpStream::connect(&addr) {
+                Ok(stream) => {
+                    info!("Following sync leader at {}", addr);
+                    let _ = stream.set_nodelay(true);
+                    for line in BufReader::new(stream).lines() {
+                        let Ok(line) = line else { break };
+                        match line.parse::<SyncState>() {
+                            Ok(state) => {
+                                if sender.send(state).is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => debug!("Ignoring sync line: {}", e),
+                        }
+                    }
+                    info!("Lost sync leader at {}", addr);
+                }
+                Err(e) => debug!("Sync leader {} unavailable: {}", addr, e),
+            }
+            thread::sleep(RECONNECT_DELAY);
+        });
+
+        Ok(Self { updates })
+    }
+
+    /// Returns the newest update received since the last call, if any
+    pub fn latest(&self) -> Option<SyncState> {
+        self.updates.try_iter().last()
+    }
+}
@@ -0,0 +1,648 @@
+//! Terminal capability detection
+//!
+//! ChromaCat draws with 24-bit colors and leans on features of modern
+//! terminals, which older terminals and consoles lack. The capabilities of
+//! the terminal on stdout are worked out once, from three sources in turn:
+//!
+//! - environment variables such as `COLORTERM`, `TERM` and `TERM_PROGRAM`
+//! - the terminfo entry for `TERM`, for the number of colors it supports
+//! - queries answered by the terminal itself: mode reports (DECRQM) for
+//!   synchronized output, bracketed paste and mouse tracking, a kitty
+//!   graphics query, and the primary device attributes (DA1), which list
+//!   sixel support and which every terminal answers
+//!
+//! Each source only overrides what the one before it inferred when it has
+//! something to say, and anything unknown is assumed to work, as it did
+//! before capabilities were detected.
+//!
+//! Asking the terminal means switching it to raw mode and waiting for its
+//! answers, so [`TermCaps::current`] only goes as far as terminfo, which is
+//! all static output needs. The queries are sent by [`TermCaps::probed`],
+//! once an animation or a screenshot needs to know what they answer.
+//!
+//! On Windows, escape sequences only work once the console has been asked
+//! to process them, which [`enable_ansi`] does. Legacy consoles that can't
+//! are left without colors. In headless mode the terminal is never touched
+//! at all, so output can be rendered and checked where there is none, such
+//! as on a CI runner.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// Whether the terminal is left alone and a fixed-size screen assumed
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+
+/// How long to wait for the terminal to answer the capability queries
+#[cfg(unix)]
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How long the terminal must stay quiet before the answers are taken to be
+/// over
+#[cfg(unix)]
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// Queries sent to the terminal: a kitty graphics query, mode reports for
+/// synchronized output, bracketed paste and mouse tracking, and the device
+/// attributes last, since every terminal answers them
+#[cfg(unix)]
+const QUERIES: &str = concat!(
+    "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\",
+    "\x1b[?2026$p",
+    "\x1b[?2004$p",
+    "\x1b[?1000$p",
+    "\x1b[c",
+);
+
+/// Longest answer to the queries read before giving up
+#[cfg(unix)]
+const MAX_REPLY: usize = 512;
+
+/// Terminal types known to show only the 16 basic colors
+const BASIC_TERMS: &[&str] = &["linux", "vt100", "vt220", "ansi", "cons25", "dumb"];
+
+/// Index of the `colors` number in a compiled terminfo entry
+const TERMINFO_COLORS: usize = 13;
+
+/// Colors a terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSupport {
+    /// Any 24-bit color
+    #[default]
+    TrueColor,
+    /// The 256-color xterm palette
+    Ansi256,
+    /// The 16 basic colors
+    Ansi16,
+}
+
+/// Inline image protocol a terminal understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Graphics {
+    /// No known image protocol
+    #[default]
+    None,
+    /// The kitty graphics protocol
+    Kitty,
+    /// The iTerm2 inline image protocol
+    Iterm,
+    /// Sixel images
+    Sixel,
+}
+
+/// What the terminal on stdout can do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermCaps {
+    /// Colors it can show
+    pub color: ColorSupport,
+    /// Whether it holds back output between synchronized update markers
+    pub synchronized_output: bool,
+    /// Inline image protocol it understands
+    pub graphics: Graphics,
+    /// Whether it reports mouse events
+    pub mouse: bool,
+    /// Whether it marks pasted text
+    pub bracketed_paste: bool,
+}
+
+impl Default for TermCaps {
+    /// Capabilities of a modern terminal, assumed when nothing is known
+    fn default() -> Self {
+        Self {
+            color: ColorSupport::TrueColor,
+            synchronized_output: true,
+            graphics: Graphics::None,
+            mouse: true,
+            bracketed_paste: true,
+        }
+    }
+}
+
+impl TermCaps {
+    /// Returns the capabilities of the terminal on stdout as the
+    /// environment and terminfo describe them, worked out the first time
+    /// they are needed. The terminal itself isn't asked. Output that isn't
+    /// a terminal, and headless runs, get the defaults.
+    pub fn current() -> &'static TermCaps {
+        static CAPS: OnceLock<TermCaps> = OnceLock::new();
+        CAPS.get_or_init(|| {
+            if !is_headless() && atty::is(atty::Stream::Stdout) {
+                Self::infer()
+            } else {
+                Self::default()
+            }
+        })
+    }
+
+    /// Returns the capabilities of the terminal on stdout, also asking the
+    /// terminal the first time they are needed. This holds things up for
+    /// as long as the terminal takes to answer, so it is left to the
+    /// features that need the answers.
+    pub fn probed() -> &'static TermCaps {
+        static CAPS: OnceLock<TermCaps> = OnceLock::new();
+        CAPS.get_or_init(|| {
+            let mut caps = Self::current().clone();
+            if !is_headless() && atty::is(atty::Stream::Stdout) {
+                if let Some(reply) = query_terminal() {
+                    caps.apply_replies(&reply);
+                }
+            }
+            caps
+        })
+    }
+
+    /// Infers the capabilities of the terminal from the environment and its
+    /// terminfo entry
+    pub fn infer() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        let mut caps = Self::from_env(var);
+        if let Some(term) = var("TERM") {
+            if let Some(entry) = read_terminfo(&term) {
+                caps.apply_terminfo(&entry, var("COLORTERM").as_deref());
+            }
+        }
+        caps
+    }
+
+    /// Infers capabilities from environment variables, looked up with `var`
+    pub fn from_env<F: Fn(&str) -> Option<String>>(var: F) -> Self {
+        let mut caps = Self::default();
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let truecolor = var("COLORTERM")
+            .is_some_and(|value| matches!(value.as_str(), "truecolor" | "24bit"));
+
+        if BASIC_TERMS.contains(&term.as_str()) {
+            caps.color = ColorSupport::Ansi16;
+            caps.synchronized_output = false;
+            caps.mouse = false;
+            caps.bracketed_paste = false;
+        } else if program == "Apple_Terminal" {
+            caps.color = ColorSupport::Ansi256;
+        }
+        if truecolor || term.ends_with("-direct") {
+            caps.color = ColorSupport::TrueColor;
+        }
+
+        caps.graphics = if var("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || program == "ghostty"
+        {
+            Graphics::Kitty
+        } else if matches!(program.as_str(), "iTerm.app" | "WezTerm") {
+            Graphics::Iterm
+        } else if term.starts_with("foot") || term.starts_with("mlterm") {
+            Graphics::Sixel
+        } else {
+            Graphics::None
+        };
+        caps
+    }
+
+    /// Takes the number of colors from a compiled terminfo entry. Fewer
+    /// than 256 colors, or direct colors, override what the environment
+    /// suggested, unless `colorterm` asks for 24-bit colors outright.
+    pub fn apply_terminfo(&mut self, entry: &[u8], colorterm: Option<&str>) {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return;
+        }
+        if terminfo_has_rgb(entry) {
+            self.color = ColorSupport::TrueColor;
+            return;
+        }
+        match terminfo_colors(entry) {
+            Some(colors) if colors >= 1 << 24 => self.color = ColorSupport::TrueColor,
+            Some(colors) if colors < 256 => self.color = ColorSupport::Ansi16,
+            _ => {}
+        }
+    }
+
+    /// Updates capabilities from the terminal's answers to the queries.
+    /// Modes it reports override what was inferred; modes it doesn't are
+    /// left as they were.
+    pub fn apply_replies(&mut self, reply: &str) {
+        if let Some(state) = mode_report(reply, 2026) {
+            self.synchronized_output = (1..=4).contains(&state);
+        }
+        if let Some(state) = mode_report(reply, 2004) {
+            self.bracketed_paste = (1..=4).contains(&state);
+        }
+        if let Some(state) = mode_report(reply, 1000) {
+            self.mouse = (1..=4).contains(&state);
+        }
+        if reply.contains("\x1b_Gi=31;OK") {
+            self.graphics = Graphics::Kitty;
+        } else if self.graphics == Graphics::None
+            && device_attributes(reply).is_some_and(|attrs| attrs.contains(&4))
+        {
+            self.graphics = Graphics::Sixel;
+        }
+    }
+}
+
+impl ColorSupport {
+    /// Returns the escape setting the foreground to a color, as near as
+    /// this terminal can show it
+    #[inline]
+    pub fn fg(self, r: u8, g: u8, b: u8) -> ColorEscape {
+        ColorEscape {
+            support: self,
+            background: false,
+            rgb: (r, g, b),
+        }
+    }
+
+    /// Returns the escape setting the background to a color, as near as
+    /// this terminal can show it
+    #[inline]
+    pub fn bg(self, r: u8, g: u8, b: u8) -> ColorEscape {
+        ColorEscape {
+            background: true,
+            ..self.fg(r, g, b)
+        }
+    }
+}
+
+/// SGR escape selecting a color at some color depth, written with `{}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorEscape {
+    /// Colors the terminal can show
+    support: ColorSupport,
+    /// Whether the background rather than the foreground is set
+    background: bool,
+    /// Color asked for
+    rgb: (u8, u8, u8),
+}
+
+impl fmt::Display for ColorEscape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r, g, b) = self.rgb;
+        let layer = if self.background { 48 } else { 38 };
+        match self.support {
+            ColorSupport::TrueColor => write!(f, "\x1b[{};2;{};{};{}m", layer, r, g, b),
+            ColorSupport::Ansi256 => write!(f, "\x1b[{};5;{}m", layer, ansi256(r, g, b)),
+            ColorSupport::Ansi16 => {
+                let index = ansi16(r, g, b);
+                let base = if self.background { 40 } else { 30 };
+                let bright = if index >= 8 { 60 } else { 0 };
+                write!(f, "\x1b[{}m", base + bright + index % 8)
+            }
+        }
+    }
+}
+
+/// Levels of each channel in the 6x6x6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 basic colors, as xterm shows them
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Returns the index of the 256-color palette entry nearest a color,
+/// choosing between the color cube and the gray ramp
+pub fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &l)| (l as i32 - c as i32).abs())
+            .map_or(0, |(i, _)| i)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    // Gray ramp from 8 to 238 in steps of 10
+    let mean = (r as u32 + g as u32 + b as u32) / 3;
+    let step = (mean.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + step * 10;
+
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        232 + step
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+/// Returns the index of the basic color nearest a color
+pub fn ansi16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|&i| distance((r, g, b), BASIC_COLORS[i as usize]))
+        .unwrap_or(0)
+}
+
+/// Squared distance between two colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Returns the state a DECRQM answer such as `ESC [ ? 2026 ; 2 $ y`
+/// reports for a private mode: 0 if the terminal doesn't know it, 1 to 4
+/// if it does
+pub fn mode_report(reply: &str, mode: u16) -> Option<u8> {
+    let prefix = format!("\x1b[?{};", mode);
+    let start = reply.find(&prefix)? + prefix.len();
+    let rest = &reply[start..];
+    let end = rest.find("$y")?;
+    rest[..end].parse().ok()
+}
+
+/// Returns the attributes listed in a DA1 answer such as
+/// `ESC [ ? 62 ; 4 ; 22 c`
+pub fn device_attributes(reply: &str) -> Option<Vec<u16>> {
+    let start = reply.rfind("\x1b[?")? + 3;
+    let rest = &reply[start..];
+    let end = rest.find('c')?;
+    rest[..end]
+        .split(';')
+        .map(|attr| attr.parse().ok())
+        .collect()
+}
+
+/// Returns the `colors` number of a compiled terminfo entry, in either the
+/// legacy or the 32-bit number format
+pub fn terminfo_colors(entry: &[u8]) -> Option<u32> {
+    let header = TerminfoHeader::parse(entry)?;
+    let at = header.numbers_start + TERMINFO_COLORS * header.number_size;
+    if TERMINFO_COLORS >= header.number_count {
+        return None;
+    }
+    let value = match header.number_size {
+        2 => i16::from_le_bytes([*entry.get(at)?, *entry.get(at + 1)?]) as i32,
+        _ => i32::from_le_bytes(entry.get(at..at + 4)?.try_into().ok()?),
+    };
+    u32::try_from(value).ok()
+}
+
+/// Returns whether a compiled terminfo entry sets the `Tc` or `RGB`
+/// extended flags that announce direct colors
+pub fn terminfo_has_rgb(entry: &[u8]) -> bool {
+    terminfo_extended_flags(entry)
+        .is_some_and(|flags| flags.iter().any(|flag| flag == "Tc" || flag == "RGB"))
+}
+
+/// Layout of the standard part of a compiled terminfo entry
+struct TerminfoHeader {
+    /// Bytes each number takes
+    number_size: usize,
+    /// Numbers in the entry
+    number_count: usize,
+    /// Where the numbers start
+    numbers_start: usize,
+    /// Where the standard part ends
+    end: usize,
+}
+
+impl TerminfoHeader {
+    /// Reads the header of a compiled terminfo entry
+    fn parse(entry: &[u8]) -> Option<Self> {
+        let short = |i: usize| -> Option<usize> {
+            let value = i16::from_le_bytes([*entry.get(i * 2)?, *entry.get(i * 2 + 1)?]);
+            usize::try_from(value.max(0)).ok()
+        };
+        let number_size = match short(0)? {
+            0o432 => 2,
+            0o1036 => 4,
+            _ => return None,
+        };
+        let (names, bools, numbers, strings, table) =
+            (short(1)?, short(2)?, short(3)?, short(4)?, short(5)?);
+        let numbers_start = (12 + names + bools + 1) & !1;
+        let strings_start = numbers_start + numbers * number_size;
+        Some(Self {
+            number_size,
+            number_count: numbers,
+            numbers_start,
+            end: strings_start + strings * 2 + table,
+        })
+    }
+}
+
+/// Returns the names of the extended flags a compiled terminfo entry sets
+fn terminfo_extended_flags(entry: &[u8]) -> Option<Vec<String>> {
+    let header = TerminfoHeader::parse(entry)?;
+    let start = (header.end + 1) & !1;
+    let short = |i: usize| -> Option<usize> {
+        let at = start + i * 2;
+        let value = i16::from_le_bytes([*entry.get(at)?, *entry.get(at + 1)?]);
+        usize::try_from(value.max(0)).ok()
+    };
+    let (bools, numbers, strings, _, table) =
+        (short(0)?, short(1)?, short(2)?, short(3)?, short(4)?);
+    let flags_start = start + 10;
+    let set: Vec<bool> = entry
+        .get(flags_start..flags_start + bools)?
+        .iter()
+        .map(|&flag| flag == 1)
+        .collect();
+
+    // The table holds the string values and then every name, booleans
+    // first, each ending in NUL
+    let offsets = ((flags_start + bools + 1) & !1) + numbers * header.number_size;
+    let table_start = offsets + (strings + bools + numbers + strings) * 2;
+    let table = entry.get(table_start..table_start + table)?;
+    let pieces: Vec<&[u8]> = table.split(|&b| b == 0).collect();
+    let names = bools + numbers + strings;
+    let names = pieces.get(pieces.len().checked_sub(names + 1)?..pieces.len() - 1)?;
+    Some(
+        names
+            .iter()
+            .zip(set)
+            .filter(|(_, set)| *set)
+            .map(|(name, _)| String::from_utf8_lossy(name).into_owned())
+            .collect(),
+    )
+}
+
+/// Switches to headless mode, in which the terminal is never queried or
+/// set up and output is rendered for a fixed-size screen
+pub fn set_headless() {
+    HEADLESS.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether running headless
+pub fn is_headless() -> bool {
+    HEADLESS.load(Ordering::SeqCst)
+}
+
+/// Makes sure escape sequences written to the console are interpreted,
+/// returning false if they can't be. Only Windows consoles need to be asked,
+/// and only those older than Windows 10 refuse.
+pub fn enable_ansi() -> bool {
+    #[cfg(windows)]
+    {
+        crossterm::ansi_support::supports_ansi()
+    }
+    #[cfg(not(windows))]
+    {
+        true
+    }
+}
+
+/// Reads the compiled terminfo entry for a terminal type from the usual
+/// places
+fn read_terminfo(term: &str) -> Option<Vec<u8>> {
+    let first = term.chars().next()?;
+    let mut places: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        places.push(dir.into());
+    }
+    if let Some(home) = dirs::home_dir() {
+        places.push(home.join(".terminfo"));
+    }
+    if let Some(list) = std::env::var_os("TERMINFO_DIRS") {
+        places.extend(std::env::split_paths(&list));
+    }
+    places.extend(
+        ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo", "/usr/lib/terminfo"]
+            .iter()
+            .map(PathBuf::from),
+    );
+
+    // Entries sit in a directory named by their first letter, or by its
+    // hex code on case-insensitive file systems
+    places.iter().find_map(|dir| {
+        [first.to_string(), format!("{:x}", first as u32)]
+            .iter()
+            .find_map(|sub| std::fs::read(dir.join(sub).join(term)).ok())
+    })
+}
+
+/// Sends the capability queries to the controlling terminal and returns
+/// its answers, up to the device attributes
+#[cfg(unix)]
+fn query_terminal() -> Option<String> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    // The answers arrive as input, which must not be echoed or line-buffered
+    let was_raw = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+    let reply = tty
+        .write_all(QUERIES.as_bytes())
+        .and_then(|_| tty.flush())
+        .ok()
+        .and_then(|_| read_replies(&tty));
+
+    // Answers arriving after the timeout would otherwise be echoed, or read
+    // as key presses
+    drain_replies(&tty);
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+    reply
+}
+
+/// Terminal queries need a terminal device
+#[cfg(not(unix))]
+fn query_terminal() -> Option<String> {
+    None
+}
+
+/// Reads answers until the device attributes arrive, giving up after the
+/// timeout
+#[cfg(unix)]
+fn read_replies(mut tty: &std::fs::File) -> Option<String> {
+    use std::io::Read;
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut reply = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fd` is a single valid pollfd that outlives the call
+        let ready = unsafe { libc::poll(&mut fd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 || tty.read(&mut byte).ok()? == 0 {
+            return None;
+        }
+        reply.push(byte[0]);
+
+        // The device attributes come last, ending in `c`
+        if byte[0] == b'c' {
+            let text = String::from_utf8_lossy(&reply).into_owned();
+            if device_attributes(&text).is_some() {
+                return Some(text);
+            }
+        }
+        if reply.len() > MAX_REPLY {
+            return None;
+        }
+    }
+}
+
+/// Throws away input until the terminal has been quiet for the drain
+/// timeout, then discards anything still unread
+#[cfg(unix)]
+fn drain_replies(mut tty: &std::fs::File) {
+    use std::io::Read;
+    use std::os::unix::io::AsRawFd;
+
+    let mut buf = [0u8; 64];
+    let mut drained = 0;
+    while drained < MAX_REPLY {
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fd` is a single valid pollfd that outlives the call
+        let ready = unsafe { libc::poll(&mut fd, 1, DRAIN_TIMEOUT.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        match tty.read(&mut buf) {
+            Ok(read) if read > 0 => drained += read,
+            _ => break,
+        }
+    }
+    // SAFETY: flushing the input queue of an open terminal has no other
+    // effect
+    unsafe {
+        libc::tcflush(tty.as_raw_fd(), libc::TCIFLUSH);
+    }
+}
+
+impl fmt::Display for ColorSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorSupport::TrueColor => write!(f, "truecolor"),
+            ColorSupport::Ansi256 => write!(f, "256"),
+            ColorSupport::Ansi16 => write!(f, "16"),
+        }
+    }
+}
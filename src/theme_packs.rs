@@ -0,0 +1,200 @@
+//! Theme packs installed for the user
+//!
+//! A theme pack is a YAML file of themes, the same as `--theme-file` takes.
+//! `chromacat themes install` checks a pack from a file or URL and copies it
+//! into `~/.config/chromacat/themes`; from then on its themes load on every
+//! run, listed under the `user` category. `themes remove` takes a pack out
+//! again, and `themes list --user` shows what is installed. URLs are
+//! downloaded with `curl`.
+
+use crate::error::{ChromaCatError, Result};
+use crate::playlist::get_config_dir;
+use crate::themes::{self, ThemeDefinition};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory in the config directory theme packs are installed to
+pub const THEMES_DIR: &str = "themes";
+
+/// Category installed themes are listed under
+pub const USER_CATEGORY: &str = "user";
+
+/// Longest a pack download may take, in seconds
+const DOWNLOAD_SECONDS: u32 = 30;
+
+/// Returns the directory theme packs are installed to
+pub fn get_user_themes_dir() -> PathBuf {
+    get_config_dir().join(THEMES_DIR)
+}
+
+/// An installed theme pack
+#[derive(Debug, Clone)]
+pub struct ThemePack {
+    /// Name the pack is installed and removed by
+    pub name: String,
+    /// File the pack is installed as
+    pub path: PathBuf,
+    /// Themes in the pack
+    pub themes: Vec<ThemeDefinition>,
+}
+
+/// Returns the packs installed in `dir`, sorted by name. Packs that no
+/// longer load are skipped with a warning.
+pub fn list_packs(dir: &Path) -> Result<Vec<ThemePack>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut packs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = pack_file_name(&path) else {
+            continue;
+        };
+        let content = std::fs::read_to_string(&path)?;
+        match themes::parse_themes(&content) {
+            Ok(themes) => packs.push(ThemePack { name, path, themes }),
+            Err(e) => log::warn!("Skipping theme pack {}: {}", path.display(), e),
+        }
+    }
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packs)
+}
+
+/// Checks the theme pack at `source`, a file or an http(s) URL, and
+/// installs it in `dir`, replacing an installed pack of the same name.
+/// Themes that would hide a built-in theme or one from another pack are
+/// refused.
+pub fn install(source: &str, dir: &Path) -> Result<ThemePack> {
+    let name = pack_name(source)?;
+    let content = if is_url(source) {
+        download(source)?
+    } else {
+        std::fs::read_to_string(source).map_err(|e| {
+            ChromaCatError::InputError(format!("Failed to read theme pack {}: {}", source, e))
+        })?
+    };
+
+    let themes = themes::parse_themes(&content)?;
+    if themes.is_empty() {
+        return Err(ChromaCatError::InvalidTheme(format!(
+            "Theme pack {} has no themes",
+            source
+        )));
+    }
+    let installed = list_packs(dir)?;
+    for (i, theme) in themes.iter().enumerate() {
+        if themes[..i].iter().any(|t| t.name == theme.name) {
+            return Err(ChromaCatError::InvalidTheme(format!(
+                "Theme '{}' is defined twice in the pack",
+                theme.name
+            )));
+        }
+        if is_builtin(&theme.name) {
+            return Err(ChromaCatError::InvalidTheme(format!(
+                "Theme '{}' would hide the built-in theme of that name",
+                theme.name
+            )));
+        }
+        let owner = installed
+            .iter()
+            .find(|pack| pack.name != name && pack.themes.iter().any(|t| t.name == theme.name));
+        if let Some(owner) = owner {
+            return Err(ChromaCatError::InvalidTheme(format!(
+                "Theme '{}' is already installed by pack '{}'",
+                theme.name, owner.name
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(dir)?;
+    // A pack installed before under the other extension is replaced too
+    let stale = dir.join(format!("{}.yml", name));
+    if stale.exists() {
+        std::fs::remove_file(stale)?;
+    }
+    let path = dir.join(format!("{}.yaml", name));
+    std::fs::write(&path, content)?;
+    Ok(ThemePack { name, path, themes })
+}
+
+/// Removes the pack named `name` from `dir`, returning the file removed.
+/// Names with path separators or `..` are refused, so nothing outside `dir`
+/// is removed.
+pub fn remove(name: &str, dir: &Path) -> Result<PathBuf> {
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(ChromaCatError::InputError(format!(
+            "'{}' is not a theme pack name; use the name `themes list --user` shows",
+            name
+        )));
+    }
+    let name = pack_name(name)?;
+    let path = ["yaml", "yml"]
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            ChromaCatError::InputError(format!("No theme pack named '{}' is installed", name))
+        })?;
+    std::fs::remove_file(&path)?;
+    Ok(path)
+}
+
+/// Returns the name a pack from `source` is installed by: its file name
+/// without the extension
+pub fn pack_name(source: &str) -> Result<String> {
+    let path = source.split(['?', '#']).next().unwrap_or_default();
+    let file = path.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name = file
+        .strip_suffix(".yaml")
+        .or_else(|| file.strip_suffix(".yml"))
+        .unwrap_or(file);
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if name.is_empty() || name.starts_with('.') || !valid {
+        return Err(ChromaCatError::InputError(format!(
+            "Can't name a theme pack after '{}'; use a file name of letters, digits, '-' and '_'",
+            source
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Returns the pack name of a file in the themes directory, if it is a
+/// YAML file
+fn pack_file_name(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+    if !path.is_file() || !matches!(extension, "yaml" | "yml") {
+        return None;
+    }
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// Returns true if a theme of this name comes with ChromaCat
+fn is_builtin(name: &str) -> bool {
+    let user = themes::list_category(USER_CATEGORY).unwrap_or_default();
+    themes::get_theme(name).is_ok() && !user.iter().any(|theme| theme == name)
+}
+
+/// Returns true if `source` is a URL rather than a file
+fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+/// Downloads a theme pack
+fn download(url: &str) -> Result<String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--max-time", &DOWNLOAD_SECONDS.to_string(), url])
+        .output()
+        .map_err(|e| ChromaCatError::InputError(format!("Failed to run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(ChromaCatError::InputError(format!(
+            "Failed to download {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| ChromaCatError::InputError(format!("{} is not a UTF-8 theme file", url)))
+}
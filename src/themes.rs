@@ -4,7 +4,9 @@
 //! colorization effects. Themes are loaded from YAML files at compile time and provide
 //! various distribution patterns, repeat modes, and easing functions.
 
+use crate::background::{luminance, Background};
 use crate::error::{ChromaCatError, Result};
+use crate::theme_packs;
 use colorgrad::{Color, Gradient, GradientBuilder, LinearGradient};
 use lazy_static::lazy_static;
 use serde::de::{self, Deserializer, SeqAccess, Visitor};
@@ -183,6 +185,22 @@ pub struct ThemeDefinition {
     pub speed: f32,
     #[serde(default = "default_easing")]
     pub ease: Easing,
+    /// Theme to use instead on light terminal backgrounds with `--adapt-bg`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub light: Option<String>,
+    /// Theme to use instead on dark terminal backgrounds with `--adapt-bg`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dark: Option<String>,
+    /// Who made the theme
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Words describing the theme, such as `warm` or `pastel`, that
+    /// `--theme-tag` and random scene pools select themes by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// License the theme may be shared under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
 }
 
 fn default_distribution() -> Distribution {
@@ -201,6 +219,12 @@ fn default_easing() -> Easing {
     Easing::Linear
 }
 
+/// Brightest color stop luminance kept on light backgrounds
+const LIGHT_BG_MAX_LUMINANCE: f32 = 0.45;
+
+/// Color stops a blend of two themes is sampled at
+const BLEND_STOPS: usize = 16;
+
 // Include theme files at compile time
 const SPACE_THEMES: &str = include_str!("../themes/space.yaml");
 const TECH_THEMES: &str = include_str!("../themes/tech.yaml");
@@ -220,6 +244,8 @@ lazy_static! {
 pub struct ThemeRegistry {
     themes: HashMap<String, ThemeDefinition>,
     categories: HashMap<String, Vec<String>>,
+    /// Terminal background themes are adapted to, if any
+    background: Option<Background>,
 }
 
 impl ThemeRegistry {
@@ -227,6 +253,7 @@ impl ThemeRegistry {
         let mut registry = Self {
             themes: HashMap::new(),
             categories: HashMap::new(),
+            background: None,
         };
 
         // Add default rainbow theme
@@ -281,6 +308,11 @@ impl ThemeRegistry {
             repeat: Repeat::Named(RepeatMode::None),
             speed: 1.0,
             ease: Easing::Linear,
+            light: None,
+            dark: None,
+            author: None,
+            tags: vec!["vivid".to_string()],
+            license: None,
         };
 
         registry.themes.insert("rainbow".to_string(), rainbow_theme);
@@ -301,6 +333,12 @@ impl ThemeRegistry {
         registry.load_category("pride", PRIDE_THEMES);
         registry.load_category("theory", THEORY_THEMES);
 
+        // Themes the user installed, left out where only the built-in themes
+        // should be seen, as in tests
+        if std::env::var_os("NO_EXTERNAL_THEMES").is_none() {
+            registry.load_user_packs(&theme_packs::get_user_themes_dir());
+        }
+
         registry
     }
 
@@ -311,7 +349,7 @@ impl ThemeRegistry {
 
                 for theme in themes {
                     if let Err(e) = theme.validate() {
-                        eprintln!("Warning: Invalid theme '{}': {}", theme.name, e);
+                        log::warn!("Invalid theme '{}': {}", theme.name, e);
                         continue;
                     }
                     category_themes.push(theme.name.clone());
@@ -322,7 +360,7 @@ impl ThemeRegistry {
                     .insert(category.to_string(), category_themes);
             }
             Err(e) => {
-                eprintln!("Warning: Failed to load {} themes: {}", category, e);
+                log::warn!("Failed to load {} themes: {}", category, e);
             }
         }
     }
@@ -332,24 +370,91 @@ impl ThemeRegistry {
         let content = std::fs::read_to_string(path)
             .map_err(|e| ChromaCatError::InputError(format!("Failed to read theme file: {}", e)))?;
 
-        let themes = from_str::<Vec<ThemeDefinition>>(&content)
-            .map_err(|e| ChromaCatError::InvalidTheme(format!("Invalid theme file format: {}", e)))?;
-
-        for theme in themes {
-            if let Err(e) = theme.validate() {
-                return Err(ChromaCatError::InvalidTheme(format!(
-                    "Invalid theme '{}': {}",
-                    theme.name, e
-                )));
-            }
+        for theme in parse_themes(&content)? {
             self.themes.insert(theme.name.clone(), theme);
         }
 
         Ok(())
     }
+
+    /// Loads the theme packs installed in `dir` under the `user` category,
+    /// skipping any that no longer load with a warning
+    fn load_user_packs(&mut self, dir: &Path) {
+        let packs = match theme_packs::list_packs(dir) {
+            Ok(packs) => packs,
+            Err(e) => {
+                log::warn!("Failed to load user themes: {}", e);
+                return;
+            }
+        };
+        let mut user_themes = Vec::new();
+        for pack in packs {
+            for theme in pack.themes {
+                if self.themes.contains_key(&theme.name) {
+                    log::warn!(
+                        "Theme '{}' from pack '{}' is already defined",
+                        theme.name,
+                        pack.name
+                    );
+                    continue;
+                }
+                user_themes.push(theme.name.clone());
+                self.themes.insert(theme.name.clone(), theme);
+            }
+        }
+        if !user_themes.is_empty() {
+            self.categories
+                .insert(theme_packs::USER_CATEGORY.to_string(), user_themes);
+        }
+    }
+
+    /// Returns a theme as it should look on the current background: its
+    /// variant for that background if it names one, or itself adapted
+    fn resolve(&self, theme: &ThemeDefinition) -> ThemeDefinition {
+        let Some(background) = self.background else {
+            return theme.clone();
+        };
+        theme
+            .variant(background)
+            .and_then(|name| self.themes.get(name))
+            .cloned()
+            .unwrap_or_else(|| theme.adapted_to(background))
+    }
 }
 
 impl ThemeDefinition {
+    /// Returns true if the theme is tagged `tag`, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Returns the name of the theme to use instead on a background
+    pub fn variant(&self, background: Background) -> Option<&str> {
+        match background {
+            Background::Light => self.light.as_deref(),
+            Background::Dark => self.dark.as_deref(),
+        }
+    }
+
+    /// Returns a copy of the theme that stays readable on a background.
+    /// Themes are designed for dark backgrounds, so on light ones color
+    /// stops too bright to stand out are darkened, keeping their hue.
+    pub fn adapted_to(&self, background: Background) -> ThemeDefinition {
+        let mut theme = self.clone();
+        if background == Background::Light {
+            for stop in &mut theme.colors {
+                let luma = luminance(stop.r, stop.g, stop.b);
+                if luma > LIGHT_BG_MAX_LUMINANCE {
+                    let scale = LIGHT_BG_MAX_LUMINANCE / luma;
+                    stop.r *= scale;
+                    stop.g *= scale;
+                    stop.b *= scale;
+                }
+            }
+        }
+        theme
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.colors.len() < 2 {
             return Err(ChromaCatError::GradientError(
@@ -414,6 +519,37 @@ impl ThemeDefinition {
         Ok(Box::new(gradient))
     }
 
+    /// Mixes this theme's colors with `other`'s, `amount` of the way from
+    /// this theme (0.0) to the other (1.0). Both gradients are sampled at
+    /// evenly spaced stops; the distribution, repeat, and easing stay this
+    /// theme's.
+    pub fn blend(&self, other: &ThemeDefinition, amount: f32) -> Result<ThemeDefinition> {
+        let (from, to) = (self.create_gradient()?, other.create_gradient()?);
+        let amount = amount.clamp(0.0, 1.0);
+        let colors = (0..BLEND_STOPS)
+            .map(|i| {
+                let position = i as f32 / (BLEND_STOPS - 1) as f32;
+                let (a, b) = (from.at(position), to.at(position));
+                ColorStop {
+                    r: a.r + (b.r - a.r) * amount,
+                    g: a.g + (b.g - a.g) * amount,
+                    b: a.b + (b.b - a.b) * amount,
+                    position: Some(position),
+                    name: None,
+                }
+            })
+            .collect();
+
+        Ok(ThemeDefinition {
+            name: format!("{}→{}", self.name, other.name),
+            desc: format!("{} blended toward {}", self.name, other.name),
+            colors,
+            light: None,
+            dark: None,
+            ..self.clone()
+        })
+    }
+
     pub fn apply_distribution(&self, t: f32) -> f32 {
         let t = t.clamp(0.0, 1.0);
         match self.dist {
@@ -446,7 +582,7 @@ impl ThemeDefinition {
                 RepeatMode::Repeat => t.fract(),
             },
             Repeat::Function(name, rate) => match name.as_str() {
-                "rotate" => (t + time * rate).fract(),
+                "rotate" => (t + time * rate).rem_euclid(1.0),
                 "pulse" => {
                     let phase = (time * rate * PI).sin();
                     (t + phase) * 0.5
@@ -456,6 +592,16 @@ impl ThemeDefinition {
         }
     }
 
+    /// Maps a pattern value to a gradient position at an animation time,
+    /// applying the theme's distribution, repeat mode at its own speed, and
+    /// easing, in that order
+    #[inline]
+    pub fn position_at(&self, value: f32, time: f32) -> f32 {
+        let t = self.apply_distribution(value);
+        let t = self.apply_repeat(t, time * self.speed);
+        self.apply_easing(t.clamp(0.0, 1.0)).clamp(0.0, 1.0)
+    }
+
     pub fn apply_easing(&self, t: f32) -> f32 {
         match self.ease {
             Easing::Linear => t,
@@ -487,15 +633,26 @@ impl ThemeDefinition {
 
 // Public interface for accessing themes
 pub fn get_theme(name: &str) -> Result<ThemeDefinition> {
-    THEME_REGISTRY
+    let registry = THEME_REGISTRY
         .read()
-        .map_err(|e| ChromaCatError::Other(format!("Failed to read theme registry: {}", e)))?
+        .map_err(|e| ChromaCatError::Other(format!("Failed to read theme registry: {}", e)))?;
+    registry
         .themes
         .get(name)
-        .cloned()
+        .map(|theme| registry.resolve(theme))
         .ok_or_else(|| ChromaCatError::InvalidTheme(name.to_string()))
 }
 
+/// Adapts every theme looked up from now on to a terminal background, or
+/// stops adapting them with `None`
+pub fn set_background(background: Option<Background>) -> Result<()> {
+    THEME_REGISTRY
+        .write()
+        .map_err(|e| ChromaCatError::Other(format!("Failed to lock theme registry: {}", e)))?
+        .background = background;
+    Ok(())
+}
+
 pub fn list_category(category: &str) -> Option<Vec<String>> {
     THEME_REGISTRY
         .read()
@@ -538,6 +695,21 @@ pub fn theme_count() -> usize {
         .unwrap_or(0)
 }
 
+/// Parses a theme file, failing on the first theme that isn't valid
+pub fn parse_themes(content: &str) -> Result<Vec<ThemeDefinition>> {
+    let themes = from_str::<Vec<ThemeDefinition>>(content)
+        .map_err(|e| ChromaCatError::InvalidTheme(format!("Invalid theme file format: {}", e)))?;
+    for theme in &themes {
+        if let Err(e) = theme.validate() {
+            return Err(ChromaCatError::InvalidTheme(format!(
+                "Invalid theme '{}': {}",
+                theme.name, e
+            )));
+        }
+    }
+    Ok(themes)
+}
+
 // Modify public interface
 pub fn load_theme_file(path: &Path) -> Result<()> {
     let mut registry = THEME_REGISTRY
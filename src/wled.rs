@@ -0,0 +1,415 @@
+//! LED strip output through WLED
+//!
+//! `--output wled://HOST --led-count N` drives a WLED controller instead of
+//! the terminal: every frame, the pattern is sampled along a line of `N`
+//! LEDs and the colors are sent to WLED's realtime UDP port. For a matrix or
+//! any other arrangement, `--led-map FILE` places each LED on a grid:
+//!
+//! ```yaml
+//! # A 16x16 panel wired in a zigzag, one row after another
+//! width: 16
+//! height: 16
+//! serpentine: true
+//! ```
+//!
+//! or, with LEDs wherever they are, one `[x, y]` cell per LED in wiring
+//! order:
+//!
+//! ```yaml
+//! width: 4
+//! height: 2
+//! leds: [[0, 0], [1, 0], [2, 0], [3, 0], [3, 1], [0, 1]]
+//! ```
+//!
+//! Colors go out as WARLS packets, which address single LEDs, DRGB packets
+//! for strips of up to 490 LEDs, or DNRGB packets for longer ones; the
+//! protocol is picked from the LED count unless the URL asks for one with
+//! `?protocol=NAME`. WLED returns to its own effects a couple of seconds
+//! after the stream stops.
+
+use std::fmt;
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::error::{ChromaCatError, Result};
+use crate::frames::PatternFrame;
+
+/// WLED's realtime UDP port
+pub const DEFAULT_WLED_PORT: u16 = 21324;
+
+/// Seconds WLED waits after the last packet before it goes back to its own
+/// effects
+pub const WLED_TIMEOUT_SECONDS: u8 = 2;
+
+/// Most LEDs a strip may have
+pub const MAX_LEDS: usize = 65_535;
+
+/// LEDs sent in one DNRGB packet
+const DNRGB_LEDS_PER_PACKET: usize = 489;
+
+/// Realtime protocol the colors are sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WledProtocol {
+    /// Index and color of each LED, for up to 255 LEDs
+    Warls,
+    /// Colors of the first 490 LEDs in order
+    Drgb,
+    /// Colors in order from a start index, 489 LEDs per packet
+    Dnrgb,
+}
+
+impl WledProtocol {
+    /// Returns a list of all protocols
+    pub fn all() -> &'static [WledProtocol] {
+        &[WledProtocol::Warls, WledProtocol::Drgb, WledProtocol::Dnrgb]
+    }
+
+    /// Get string representation of the protocol
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WledProtocol::Warls => "warls",
+            WledProtocol::Drgb => "drgb",
+            WledProtocol::Dnrgb => "dnrgb",
+        }
+    }
+
+    /// Returns the number WLED knows the protocol by, the first byte of
+    /// every packet
+    fn code(&self) -> u8 {
+        match self {
+            WledProtocol::Warls => 1,
+            WledProtocol::Drgb => 2,
+            WledProtocol::Dnrgb => 4,
+        }
+    }
+
+    /// Returns the most LEDs the protocol can address; DNRGB splits longer
+    /// strips over several packets
+    pub fn max_leds(&self) -> usize {
+        match self {
+            WledProtocol::Warls => 255,
+            WledProtocol::Drgb => 490,
+            WledProtocol::Dnrgb => MAX_LEDS,
+        }
+    }
+
+    /// Returns the protocol used for a strip of `count` LEDs when none is
+    /// asked for
+    pub fn for_count(count: usize) -> Self {
+        if count <= WledProtocol::Drgb.max_leds() {
+            WledProtocol::Drgb
+        } else {
+            WledProtocol::Dnrgb
+        }
+    }
+
+    /// Builds the packets that set the LEDs to `colors`, in wiring order
+    pub fn packets(&self, colors: &[[u8; 3]]) -> Vec<Vec<u8>> {
+        let header = [self.code(), WLED_TIMEOUT_SECONDS];
+        match self {
+            WledProtocol::Warls => {
+                let mut packet = header.to_vec();
+                for (index, rgb) in colors.iter().take(self.max_leds()).enumerate() {
+                    packet.push(index as u8);
+                    packet.extend_from_slice(rgb);
+                }
+                vec![packet]
+            }
+            WledProtocol::Drgb => {
+                let mut packet = header.to_vec();
+                packet.extend(colors.iter().take(self.max_leds()).flatten());
+                vec![packet]
+            }
+            WledProtocol::Dnrgb => colors
+                .chunks(DNRGB_LEDS_PER_PACKET)
+                .enumerate()
+                .map(|(chunk, rgb)| {
+                    let start = (chunk * DNRGB_LEDS_PER_PACKET) as u16;
+                    let mut packet = header.to_vec();
+                    packet.extend_from_slice(&start.to_be_bytes());
+                    packet.extend(rgb.iter().flatten());
+                    packet
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for WledProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for WledProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        WledProtocol::all()
+            .iter()
+            .find(|protocol| protocol.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "Invalid WLED protocol '{}'. Valid protocols: warls, drgb, dnrgb",
+                    s
+                )
+            })
+    }
+}
+
+/// A WLED controller from a `wled://HOST[:PORT][?protocol=NAME]` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WledTarget {
+    /// Host name or address
+    pub host: String,
+    /// UDP port
+    pub port: u16,
+    /// Protocol asked for, if any
+    pub protocol: Option<WledProtocol>,
+}
+
+impl WledTarget {
+    /// Returns the `HOST:PORT` address packets are sent to
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+impl fmt::Display for WledTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wled://{}", self.addr())
+    }
+}
+
+impl FromStr for WledTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let rest = s
+            .strip_prefix("wled://")
+            .ok_or_else(|| format!("Unsupported output '{}'; use wled://HOST[:PORT]", s))?;
+        let (address, query) = match rest.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (rest, None),
+        };
+
+        let address = address.trim_end_matches('/');
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse::<u16>()
+                    .ok()
+                    .filter(|&port| port > 0)
+                    .ok_or_else(|| format!("Invalid WLED port '{}'", port))?,
+            ),
+            None => (address, DEFAULT_WLED_PORT),
+        };
+        if host.is_empty() {
+            return Err(format!("Output '{}' has no host", s));
+        }
+
+        let protocol = match query {
+            Some(query) => match query.split_once('=') {
+                Some(("protocol", name)) => Some(name.parse()?),
+                _ => return Err(format!("Unknown WLED option '{}'", query)),
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            protocol,
+        })
+    }
+}
+
+/// Where each LED sits on the grid the pattern is sampled on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedMap {
+    /// Grid width in cells
+    pub width: usize,
+    /// Grid height in cells
+    pub height: usize,
+    /// Cell of each LED, in wiring order
+    pub positions: Vec<(usize, usize)>,
+}
+
+/// An LED map file as written
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LedMapFile {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    serpentine: bool,
+    #[serde(default)]
+    leds: Option<Vec<(usize, usize)>>,
+}
+
+impl LedMap {
+    /// Places `count` LEDs side by side along one row
+    pub fn line(count: usize) -> Self {
+        Self {
+            width: count,
+            height: 1,
+            positions: (0..count).map(|x| (x, 0)).collect(),
+        }
+    }
+
+    /// Places the LEDs of a `width` x `height` matrix row by row; with
+    /// `serpentine`, every other row runs right to left
+    pub fn matrix(width: usize, height: usize, serpentine: bool) -> Self {
+        let positions = (0..height)
+            .flat_map(|y| {
+                (0..width).map(move |x| {
+                    if serpentine && y % 2 == 1 {
+                        (width - 1 - x, y)
+                    } else {
+                        (x, y)
+                    }
+                })
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            positions,
+        }
+    }
+
+    /// Reads an LED map file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            ChromaCatError::InputError(format!("Failed to read LED map file: {}", e))
+        })?;
+
+        contents.parse()
+    }
+
+    /// Returns the number of LEDs
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns whether the map has no LEDs
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Picks each LED's color out of a frame sampled on the map's grid
+    pub fn colors(&self, frame: &PatternFrame) -> Vec<[u8; 3]> {
+        self.positions
+            .iter()
+            .map(|&(x, y)| {
+                frame
+                    .cells
+                    .get(y * frame.width + x)
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+impl FromStr for LedMap {
+    type Err = ChromaCatError;
+
+    fn from_str(contents: &str) -> std::result::Result<Self, Self::Err> {
+        let file: LedMapFile = serde_yaml::from_str(contents)
+            .map_err(|e| ChromaCatError::InputError(format!("Invalid LED map format: {}", e)))?;
+        if file.width == 0 || file.height == 0 {
+            return Err(ChromaCatError::InputError(
+                "LED map width and height must be at least 1".to_string(),
+            ));
+        }
+
+        let map = match file.leds {
+            Some(positions) => {
+                if let Some((index, (x, y))) = positions
+                    .iter()
+                    .enumerate()
+                    .find(|(_, &(x, y))| x >= file.width || y >= file.height)
+                {
+                    return Err(ChromaCatError::InputError(format!(
+                        "LED {} at [{}, {}] is outside the {}x{} grid",
+                        index, x, y, file.width, file.height
+                    )));
+                }
+                LedMap {
+                    width: file.width,
+                    height: file.height,
+                    positions,
+                }
+            }
+            None => LedMap::matrix(file.width, file.height, file.serpentine),
+        };
+        if map.is_empty() || map.len() > MAX_LEDS {
+            return Err(ChromaCatError::InputError(format!(
+                "LED map must have from 1 to {} LEDs",
+                MAX_LEDS
+            )));
+        }
+        Ok(map)
+    }
+}
+
+/// Sends LED colors to a WLED controller
+pub struct WledOutput {
+    /// Socket connected to the controller
+    socket: UdpSocket,
+    /// Protocol the colors are sent with
+    protocol: WledProtocol,
+}
+
+impl WledOutput {
+    /// Opens a socket to `target` for a strip of `count` LEDs
+    pub fn connect(target: &WledTarget, count: usize) -> Result<Self> {
+        let protocol = target
+            .protocol
+            .unwrap_or_else(|| WledProtocol::for_count(count));
+        if count > protocol.max_leds() {
+            return Err(ChromaCatError::InputError(format!(
+                "{} addresses at most {} LEDs, not {}",
+                protocol,
+                protocol.max_leds(),
+                count
+            )));
+        }
+
+        let addr = target
+            .addr()
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| {
+                ChromaCatError::InputError(format!("Cannot resolve WLED host '{}'", target.host))
+            })?;
+        let local = if addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(Self { socket, protocol })
+    }
+
+    /// Returns the protocol the colors are sent with
+    pub fn protocol(&self) -> WledProtocol {
+        self.protocol
+    }
+
+    /// Sets the LEDs to `colors`, in wiring order
+    pub fn send(&self, colors: &[[u8; 3]]) -> io::Result<()> {
+        for packet in self.protocol.packets(colors) {
+            self.socket.send(&packet)?;
+        }
+        Ok(())
+    }
+}
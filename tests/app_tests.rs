@@ -1,7 +1,8 @@
 //! Integration tests for the ChromaCat application core functionality
 
-use chromacat::cli::Cli;
+use chromacat::cli::{AnimationArgs, ArtArgs, Cli, TextArgs};
 use chromacat::ChromaCat;
+use clap::Parser;
 use std::env;
 use std::io::Write;
 use std::sync::Once;
@@ -16,6 +17,11 @@ fn create_test_file(content: &str) -> NamedTempFile {
     file
 }
 
+// Helper function providing CLI defaults for fields a test doesn't care about
+fn base_cli() -> Cli {
+    Cli::parse_from(["chromacat"])
+}
+
 fn setup_test_env() {
     INIT.call_once(|| {
         env::set_var("RUST_TEST", "1");
@@ -34,15 +40,18 @@ fn test_chromacat_basic() {
         files: vec![test_file.path().to_path_buf()],
         pattern: "horizontal".to_string(),
         theme: String::from("rainbow"),
-        animate: false,
-        fps: 30,
-        duration: 0,
+        animation: AnimationArgs {
+            animate: false,
+            fps: 30,
+            duration: 0,
+            smooth: false,
+            speed: 1.0,
+            ..base_cli().animation
+        },
         no_color: true,
         list_available: false,
-        smooth: false,
         frequency: 1.0,
         amplitude: 1.0,
-        speed: 1.0,
         params: vec![],
         theme_file: None,
         pattern_help: false,
@@ -50,9 +59,8 @@ fn test_chromacat_basic() {
         aspect_ratio: 0.5,
         buffer_size: None,
         demo: false,
-        playlist: None,
-        art: None,
         list_art: false,
+        ..base_cli()
     };
 
     let mut cat = ChromaCat::new(cli);
@@ -71,15 +79,18 @@ fn test_chromacat_invalid_angle() {
         files: vec![test_file.path().to_path_buf()],
         pattern: "diagonal".to_string(),
         theme: String::from("rainbow"),
-        animate: false,
-        fps: 30,
-        duration: 0,
+        animation: AnimationArgs {
+            animate: false,
+            fps: 30,
+            duration: 0,
+            smooth: false,
+            speed: 1.0,
+            ..base_cli().animation
+        },
         no_color: true,
         list_available: false,
-        smooth: false,
         frequency: 1.0,
         amplitude: 1.0,
-        speed: 1.0,
         params: vec!["angle=400".to_string()],
         theme_file: None,
         pattern_help: false,
@@ -87,9 +98,8 @@ fn test_chromacat_invalid_angle() {
         aspect_ratio: 0.5,
         buffer_size: None,
         demo: false,
-        playlist: None,
-        art: None,
         list_art: false,
+        ..base_cli()
     };
 
     let mut cat = ChromaCat::new(cli);
@@ -138,15 +148,18 @@ fn test_chromacat_pattern_params() {
             files: vec![test_file.path().to_path_buf()],
             pattern: pattern.to_string(),
             theme: String::from("rainbow"),
-            animate: false,
-            fps: 30,
-            duration: 0,
+            animation: AnimationArgs {
+                animate: false,
+                fps: 30,
+                duration: 0,
+                smooth: false,
+                speed: 1.0,
+                ..base_cli().animation
+            },
             no_color: true,
             list_available: false,
-            smooth: false,
             frequency: 1.0,
             amplitude: 1.0,
-            speed: 1.0,
             params: params.iter().map(|s| s.to_string()).collect(),
             theme_file: None,
             pattern_help: false,
@@ -154,9 +167,8 @@ fn test_chromacat_pattern_params() {
             aspect_ratio: 0.5,
             buffer_size: None,
             demo: false,
-            playlist: None,
-            art: None,
             list_art: false,
+            ..base_cli()
         };
 
         let mut cat = ChromaCat::new(cli);
@@ -179,15 +191,18 @@ fn test_chromacat_animation_settings() {
         files: vec![test_file.path().to_path_buf()],
         pattern: "horizontal".to_string(),
         theme: String::from("rainbow"),
-        animate: true,
-        fps: 60,
-        duration: 5,
+        animation: AnimationArgs {
+            animate: true,
+            fps: 60,
+            duration: 5,
+            smooth: true,
+            speed: 1.0,
+            ..base_cli().animation
+        },
         no_color: false,
         list_available: false,
-        smooth: true,
         frequency: 1.0,
         amplitude: 1.0,
-        speed: 1.0,
         params: vec![],
         theme_file: None,
         pattern_help: false,
@@ -195,9 +210,8 @@ fn test_chromacat_animation_settings() {
         aspect_ratio: 0.5,
         buffer_size: None,
         demo: false,
-        playlist: None,
-        art: None,
         list_art: false,
+        ..base_cli()
     };
 
     let mut cat = ChromaCat::new(cli);
@@ -217,15 +231,18 @@ fn test_streaming_mode() {
         files: vec![test_file.path().to_path_buf()],
         pattern: "horizontal".to_string(),
         theme: String::from("rainbow"),
-        animate: false,
-        fps: 30,
-        duration: 0,
+        animation: AnimationArgs {
+            animate: false,
+            fps: 30,
+            duration: 0,
+            smooth: false,
+            speed: 1.0,
+            ..base_cli().animation
+        },
         no_color: true,
         list_available: false,
-        smooth: false,
         frequency: 1.0,
         amplitude: 1.0,
-        speed: 1.0,
         params: vec![],
         theme_file: None,
         pattern_help: false,
@@ -233,9 +250,8 @@ fn test_streaming_mode() {
         aspect_ratio: 0.5,
         buffer_size: Some(4096),
         demo: false,
-        playlist: None,
-        art: None,
         list_art: false,
+        ..base_cli()
     };
 
     let mut cat = ChromaCat::new(cli);
@@ -259,15 +275,18 @@ fn test_demo_mode() {
         files: vec![],
         pattern: "horizontal".to_string(),
         theme: String::from("rainbow"),
-        animate: false,
-        fps: 30,
-        duration: 0,
+        animation: AnimationArgs {
+            animate: false,
+            fps: 30,
+            duration: 0,
+            smooth: false,
+            speed: 0.5,
+            ..base_cli().animation
+        },
         no_color: true,
         list_available: false,
-        smooth: false,
         frequency: 0.5,
         amplitude: 0.5,
-        speed: 0.5,
         params: vec![],
         theme_file: None,
         pattern_help: false,
@@ -275,9 +294,12 @@ fn test_demo_mode() {
         aspect_ratio: 1.0,
         buffer_size: Some(1024),
         demo: true,
-        playlist: None,
-        art: Some("matrix".to_string()),
+        art: ArtArgs {
+            name: Some("matrix".to_string()),
+            ..base_cli().art
+        },
         list_art: false,
+        ..base_cli()
     };
 
     let mut cat = ChromaCat::new(cli);
@@ -298,3 +320,101 @@ fn test_demo_mode() {
 
     println!("Demo mode test completed");
 }
+
+#[test]
+fn test_multiple_files_with_headers() {
+    setup_test_env();
+    let first = create_test_file("key: value\n");
+    let second = create_test_file("[section]\nname = \"chromacat\"\n");
+
+    let cli = Cli {
+        files: vec![first.path().to_path_buf(), second.path().to_path_buf()],
+        text: TextArgs {
+            file_headers: true,
+            theme_per_file: vec!["ocean".to_string(), "sunset".to_string()],
+            ..base_cli().text
+        },
+        ..base_cli()
+    };
+
+    let mut cat = ChromaCat::new(cli);
+    if let Err(e) = cat.run() {
+        panic!("Multi-file test failed with error: {:?}", e);
+    }
+}
+
+#[test]
+fn test_theme_per_file_rejects_unknown_theme() {
+    setup_test_env();
+    let test_file = create_test_file("content");
+
+    let cli = Cli {
+        files: vec![test_file.path().to_path_buf()],
+        text: TextArgs {
+            theme_per_file: vec!["ocean".to_string(), "not-a-theme".to_string()],
+            ..base_cli().text
+        },
+        ..base_cli()
+    };
+
+    let mut cat = ChromaCat::new(cli);
+    assert!(cat.run().is_err());
+}
+
+#[test]
+fn test_screensaver_mode() {
+    setup_test_env();
+
+    // The idle wait and the locker need a terminal, so tests skip them
+    let cli = Cli::parse_from([
+        "chromacat",
+        "--screensaver",
+        "--idle",
+        "600",
+        "--lock-cmd",
+        "false",
+    ]);
+    let mut cat = ChromaCat::new(cli);
+    cat.run().unwrap();
+    assert_eq!(cat.exit_code(), 0);
+}
+
+#[test]
+fn test_error_exit_codes() {
+    use chromacat::error::{EXIT_INPUT, EXIT_USAGE};
+
+    setup_test_env();
+
+    // Options that can't go together are usage errors
+    let cli = Cli::parse_from(["chromacat", "--art", "logo"]);
+    let err = ChromaCat::new(cli).run().unwrap_err();
+    assert_eq!(err.exit_code(), EXIT_USAGE);
+
+    // Input that isn't there is an input error
+    let cli = Cli::parse_from(["chromacat", "/no/such/input.txt"]);
+    let err = ChromaCat::new(cli).run().unwrap_err();
+    assert_eq!(err.exit_code(), EXIT_INPUT);
+}
+
+#[test]
+fn test_emit_frames_headless() {
+    setup_test_env();
+    let output = NamedTempFile::new().unwrap();
+    let cli = Cli::parse_from([
+        "chromacat",
+        "--emit-frames",
+        "json",
+        "--frame-size",
+        "2x2",
+        "--headless",
+        "--emit-to",
+        &output.path().to_string_lossy(),
+    ]);
+
+    // Headless runs write one frame and return
+    let mut cat = ChromaCat::new(cli);
+    cat.run().unwrap();
+    let written = std::fs::read_to_string(output.path()).unwrap();
+    assert_eq!(written.lines().count(), 1);
+    assert!(written.starts_with("{\"frame\":0,"));
+}
@@ -0,0 +1,72 @@
+use chromacat::background::{luminance, parse_osc11_reply, Background};
+use chromacat::themes::{self, ColorStop, ThemeDefinition};
+
+#[test]
+fn test_osc11_reply_parsing() {
+    let (r, g, b) = parse_osc11_reply("\x1b]11;rgb:ffff/ffff/dddd\x1b\\").unwrap();
+    assert_eq!((r, g), (1.0, 1.0));
+    assert!((b - 0xdddd as f32 / 0xffff as f32).abs() < 1e-6);
+
+    // Short channels and BEL terminators are common too
+    let (r, g, b) = parse_osc11_reply("\x1b]11;rgb:00/80/ff\x07").unwrap();
+    assert_eq!(r, 0.0);
+    assert!((g - 128.0 / 255.0).abs() < 1e-6);
+    assert_eq!(b, 1.0);
+
+    assert!(parse_osc11_reply("\x1b]11;?\x1b\\").is_none());
+    assert!(parse_osc11_reply("\x1b]11;rgb:ff/ff\x07").is_none());
+    assert!(parse_osc11_reply("garbage").is_none());
+}
+
+#[test]
+fn test_background_classification() {
+    assert_eq!(Background::from_rgb(1.0, 1.0, 0.9), Background::Light);
+    assert_eq!(Background::from_rgb(0.1, 0.1, 0.15), Background::Dark);
+
+    assert_eq!(Background::from_colorfgbg("0;15"), Some(Background::Light));
+    assert_eq!(Background::from_colorfgbg("15;default;0"), Some(Background::Dark));
+    assert_eq!(Background::from_colorfgbg("15;8"), Some(Background::Dark));
+    assert_eq!(Background::from_colorfgbg("default"), None);
+
+    assert_eq!("Light".parse::<Background>().unwrap(), Background::Light);
+    assert!("grey".parse::<Background>().is_err());
+}
+
+#[test]
+fn test_themes_adapt_to_background() {
+    let theme: ThemeDefinition = themes::get_theme("rainbow").unwrap();
+
+    // Bright stops are darkened on light backgrounds, keeping their hue
+    let adapted = theme.adapted_to(Background::Light);
+    for (before, after) in theme.colors.iter().zip(&adapted.colors) {
+        assert!(luminance(after.r, after.g, after.b) <= 0.45 + 1e-6);
+        if before.r > 0.0 && before.g > 0.0 {
+            assert!((after.r / after.g - before.r / before.g).abs() < 1e-5);
+        }
+    }
+    let dark = theme.adapted_to(Background::Dark);
+    assert_eq!(dark.colors.len(), theme.colors.len());
+    assert!(dark.colors.iter().zip(&theme.colors).all(|(a, b)| a.r == b.r && a.g == b.g));
+
+    // Themes can name a hand-tuned variant instead
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("themes.yaml");
+    std::fs::write(
+        &path,
+        "- name: day-test\n  desc: Day\n  light: night-test\n  colors:\n    - [1.0, 1.0, 1.0, 0.0]\n    - [1.0, 1.0, 0.0, 1.0]\n\
+         - name: night-test\n  desc: Night\n  colors:\n    - [0.2, 0.0, 0.4, 0.0]\n    - [0.0, 0.2, 0.4, 1.0]\n",
+    )
+    .unwrap();
+    themes::load_theme_file(&path).unwrap();
+
+    themes::set_background(Some(Background::Light)).unwrap();
+    assert_eq!(themes::get_theme("day-test").unwrap().name, "night-test");
+    let rainbow = themes::get_theme("rainbow").unwrap();
+    assert!(rainbow
+        .colors
+        .iter()
+        .all(|c: &ColorStop| luminance(c.r, c.g, c.b) <= 0.45 + 1e-6));
+
+    themes::set_background(None).unwrap();
+    assert_eq!(themes::get_theme("day-test").unwrap().name, "day-test");
+}
@@ -0,0 +1,701 @@
+use chromacat::pattern::{
+    CharRamp, CommonParams, HorizontalParams, PatternConfig, PatternEngine, PatternParams,
+};
+use chromacat::renderer::{
+    Frame, FrameCell, MaskMode, RenderBuffer, Resolution, ScreenshotTarget, TextEffect, TextMask,
+    WrapMode,
+};
+use colorgrad::{Color, Gradient};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Renders the buffer in static mode without colors and returns the output lines
+fn render_plain(buffer: &mut RenderBuffer) -> Vec<String> {
+    let mut out = Vec::new();
+    let total = buffer.total_lines();
+    buffer
+        .render_region(&mut out, 0, total, false, false)
+        .unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+#[test]
+fn test_wrap_mode_parsing() {
+    assert_eq!("wrap".parse::<WrapMode>().unwrap(), WrapMode::Wrap);
+    assert_eq!("TRUNCATE".parse::<WrapMode>().unwrap(), WrapMode::Truncate);
+    assert_eq!("scroll".parse::<WrapMode>().unwrap(), WrapMode::Scroll);
+    assert!("sideways".parse::<WrapMode>().is_err());
+
+    for mode in WrapMode::all() {
+        assert_eq!(mode.to_string().parse::<WrapMode>().unwrap(), *mode);
+    }
+}
+
+#[test]
+fn test_wrap_prefers_word_boundaries() {
+    let mut buffer = RenderBuffer::with_wrap_mode((10, 24), WrapMode::Wrap);
+    buffer.prepare_text("hello brave new world").unwrap();
+
+    assert_eq!(
+        render_plain(&mut buffer),
+        vec!["hello", "brave new", "world"]
+    );
+}
+
+#[test]
+fn test_wrap_hard_breaks_long_words() {
+    let mut buffer = RenderBuffer::with_wrap_mode((4, 24), WrapMode::Wrap);
+    buffer.prepare_text("abcdefghij").unwrap();
+
+    assert_eq!(render_plain(&mut buffer), vec!["abcd", "efgh", "ij"]);
+}
+
+#[test]
+fn test_rows_track_their_source_lines() {
+    let mut buffer = RenderBuffer::with_wrap_mode((10, 24), WrapMode::Wrap);
+    buffer.prepare_text("hello brave new world\nshort").unwrap();
+    assert_eq!(buffer.source_line(2), Some(0));
+    assert_eq!(buffer.source_line(3), Some(1));
+    assert_eq!(buffer.source_line(4), None);
+
+    // Resizing lays the new text out for the new width
+    buffer.resize_with_text((40, 24), "hello brave new world").unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["hello brave new world"]);
+    assert_eq!(buffer.source_line(0), Some(0));
+}
+
+#[test]
+fn test_truncate_adds_ellipsis() {
+    let mut buffer = RenderBuffer::with_wrap_mode((8, 24), WrapMode::Truncate);
+    buffer.prepare_text("short\na line that is too long").unwrap();
+
+    assert_eq!(buffer.line_count(), 2);
+    assert_eq!(render_plain(&mut buffer), vec!["short", "a line …"]);
+    assert!(buffer.max_line_length() <= 8);
+}
+
+#[test]
+fn test_crlf_line_endings() {
+    let mut buffer = RenderBuffer::with_wrap_mode((5, 24), WrapMode::Truncate);
+    buffer.prepare_text("abcde\r\nfghij").unwrap();
+
+    assert_eq!(buffer.line_count(), 2);
+    assert_eq!(render_plain(&mut buffer), vec!["abcde", "fghij"]);
+}
+
+#[test]
+fn test_scroll_keeps_lines_intact() {
+    let line = "x".repeat(30);
+    let mut buffer = RenderBuffer::with_wrap_mode((10, 24), WrapMode::Scroll);
+    buffer.prepare_text(&line).unwrap();
+
+    assert_eq!(buffer.line_count(), 1);
+    assert_eq!(buffer.max_line_length(), 30);
+    assert_eq!(render_plain(&mut buffer), vec![line]);
+}
+
+#[test]
+fn test_horizontal_offset_is_clamped() {
+    let mut buffer = RenderBuffer::with_wrap_mode((10, 24), WrapMode::Scroll);
+    buffer.prepare_text(&"x".repeat(30)).unwrap();
+
+    buffer.set_h_offset(5);
+    assert_eq!(buffer.h_offset(), 5);
+
+    buffer.set_h_offset(100);
+    assert_eq!(buffer.h_offset(), buffer.max_h_offset());
+    assert_eq!(buffer.h_offset(), 20);
+
+    // Offsets are ignored when the mode doesn't scroll
+    buffer.set_wrap_mode(WrapMode::Wrap).unwrap();
+    buffer.set_h_offset(5);
+    assert_eq!(buffer.h_offset(), 0);
+    assert_eq!(buffer.line_count(), 3);
+}
+
+#[test]
+fn test_wrap_uses_display_width() {
+    // Each CJK character is two columns wide
+    let mut buffer = RenderBuffer::with_wrap_mode((5, 24), WrapMode::Wrap);
+    buffer.prepare_text("你好世界").unwrap();
+
+    assert_eq!(buffer.line_count(), 2);
+    assert_eq!(buffer.max_line_length(), 4);
+}
+
+/// Grayscale gradient so every column maps to a distinct, predictable color
+#[derive(Clone)]
+struct MockGradient;
+
+impl Gradient for MockGradient {
+    fn at(&self, t: f32) -> Color {
+        Color::new(t, t, t, 1.0_f32)
+    }
+}
+
+fn create_engine(width: usize) -> PatternEngine {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    };
+    PatternEngine::new(Box::new(MockGradient), config, width, 1)
+}
+
+/// Renders a single line with colors and returns each visible grapheme paired
+/// with the escape sequence that was active when it was printed
+fn render_colored(text: &str, width: u16) -> Vec<(String, String)> {
+    let mut buffer = RenderBuffer::new((width, 1));
+    buffer.prepare_text(text).unwrap();
+    buffer
+        .update_colors_static(&create_engine(width as usize))
+        .unwrap();
+
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 1, true, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let mut cells = Vec::new();
+    let mut color = String::new();
+    let mut rest = out.as_str();
+    while !rest.is_empty() {
+        if rest.starts_with('\x1b') {
+            let end = rest.find('m').unwrap() + 1;
+            color = rest[..end].to_string();
+            rest = &rest[end..];
+        } else {
+            let grapheme = rest.graphemes(true).next().unwrap();
+            if grapheme != "\n" {
+                cells.push((grapheme.to_string(), color.clone()));
+            }
+            rest = &rest[grapheme.len()..];
+        }
+    }
+    cells
+}
+
+/// Returns the color assigned to the first occurrence of `grapheme`
+fn color_of(cells: &[(String, String)], grapheme: &str) -> String {
+    cells
+        .iter()
+        .find(|(g, _)| g == grapheme)
+        .map(|(_, c)| c.clone())
+        .unwrap_or_else(|| panic!("'{}' not rendered", grapheme))
+}
+
+#[test]
+fn test_cjk_colors_stay_column_aligned() {
+    // "你" spans columns 0-1, so "b" sits in column 2 just like in "xxb"
+    let wide = render_colored("你b", 20);
+    let narrow = render_colored("xxb", 20);
+
+    assert_eq!(wide[0].0, "你");
+    assert_eq!(color_of(&wide, "b"), color_of(&narrow, "b"));
+}
+
+#[test]
+fn test_emoji_zwj_sequence_is_preserved() {
+    let family = "👨\u{200d}👩\u{200d}👧";
+    let wide = render_colored(&format!("{}z", family), 20);
+    let narrow = render_colored("xxz", 20);
+
+    assert_eq!(wide.len(), 2);
+    assert_eq!(wide[0].0, family);
+    assert_eq!(color_of(&wide, "z"), color_of(&narrow, "z"));
+}
+
+#[test]
+fn test_combining_marks_are_preserved() {
+    // "e" + combining acute accent is a single column
+    let accented = "e\u{301}";
+    let cells = render_colored(&format!("{}b", accented), 20);
+    let narrow = render_colored("xb", 20);
+
+    assert_eq!(cells[0].0, accented);
+    assert_eq!(color_of(&cells, "b"), color_of(&narrow, "b"));
+}
+
+#[test]
+fn test_wide_glyph_clipped_at_scroll_edge() {
+    let mut buffer = RenderBuffer::with_wrap_mode((3, 1), WrapMode::Scroll);
+    buffer.prepare_text("a你好").unwrap();
+    buffer.set_h_offset(2);
+
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 1, false, true).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    // The viewport starts on the second half of "你", which is padded
+    assert!(out.contains(" 好"), "unexpected output: {:?}", out);
+}
+
+#[test]
+fn test_hyperlinks_survive_layout() {
+    let open = "\x1b]8;;https://example.com\x1b\\";
+    let close = "\x1b]8;;\x1b\\";
+    let text = format!("\x1b[31mvisit {}example{} now\x1b[0m", open, close);
+
+    let mut buffer = RenderBuffer::new((40, 1));
+    buffer.prepare_text(&text).unwrap();
+
+    // SGR codes are dropped, the hyperlink is emitted around its text
+    assert_eq!(buffer.max_line_length(), 17);
+    assert_eq!(
+        render_plain(&mut buffer),
+        vec![format!("visit {}example{} now", open, close)]
+    );
+}
+
+#[test]
+fn test_truncate_keeps_hyperlink_terminator() {
+    let open = "\x1b]8;;https://example.com\x1b\\";
+    let close = "\x1b]8;;\x1b\\";
+    let text = format!("{}a long link text{}", open, close);
+
+    let mut buffer = RenderBuffer::with_wrap_mode((8, 1), WrapMode::Truncate);
+    buffer.prepare_text(&text).unwrap();
+
+    assert_eq!(
+        render_plain(&mut buffer),
+        vec![format!("{}a long …{}", open, close)]
+    );
+}
+
+#[test]
+fn test_char_ramp_shades_blank_cells() {
+    let mut buffer = RenderBuffer::new((8, 1));
+    buffer.set_char_ramp(Some(CharRamp::new("#.")));
+    buffer.prepare_text("a      b").unwrap();
+    buffer.update_colors_static(&create_engine(8)).unwrap();
+
+    let line = render_plain(&mut buffer).remove(0);
+    assert_eq!(line.chars().count(), 8);
+    assert!(line.starts_with('a'));
+    assert!(line.ends_with('b'));
+    assert!(line[1..line.len() - 1].chars().all(|c| c == '#' || c == '.'));
+    assert!(!line.contains(' '));
+}
+
+#[test]
+fn test_char_ramp_leaves_text_untouched_when_disabled() {
+    let mut buffer = RenderBuffer::new((8, 1));
+    buffer.prepare_text("a  b").unwrap();
+    buffer.update_colors_static(&create_engine(8)).unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["a  b"]);
+
+    buffer.set_char_ramp(Some(CharRamp::new("#")));
+    buffer.update_colors_static(&create_engine(8)).unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["a##b"]);
+
+    buffer.set_char_ramp(None);
+    buffer.update_colors_static(&create_engine(8)).unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["a  b"]);
+}
+
+#[test]
+fn test_text_mask_strengths() {
+    assert_eq!("glow".parse::<MaskMode>().unwrap(), MaskMode::Glow);
+    assert_eq!("none".parse::<MaskMode>().unwrap(), MaskMode::Off);
+    assert!("blur".parse::<MaskMode>().is_err());
+
+    let mut row = vec![false; 10];
+    row[0] = true;
+    let occupancy = vec![row];
+
+    let off = TextMask::new(MaskMode::Off, &occupancy);
+    assert_eq!(off.at(7, 3), 1.0);
+
+    let stencil = TextMask::new(MaskMode::Stencil, &occupancy);
+    assert_eq!(stencil.at(0, 0), 1.0);
+    assert_eq!(stencil.at(1, 0), 0.0);
+    assert_eq!(stencil.at(0, 1), 0.0);
+
+    // Glow fades with distance from the glyph and stops past its radius
+    let glow = TextMask::new(MaskMode::Glow, &occupancy);
+    assert_eq!(glow.at(0, 0), 1.0);
+    assert!(glow.at(1, 0) > glow.at(3, 0));
+    assert!(glow.at(3, 0) > 0.0);
+    assert_eq!(glow.at(9, 0), 0.0);
+
+    // A lone glyph is sparse text
+    let density = TextMask::new(MaskMode::Density, &occupancy);
+    assert!(density.at(0, 0) > 0.0 && density.at(0, 0) < 0.5);
+    assert_eq!(density.at(9, 0), 0.0);
+}
+
+#[test]
+fn test_mask_limits_shading_to_text() {
+    let shade = |mode: MaskMode| {
+        let mut buffer = RenderBuffer::new((12, 1));
+        buffer.set_char_ramp(Some(CharRamp::new("#")));
+        buffer.set_mask_mode(mode);
+        buffer.prepare_text("ab").unwrap();
+        buffer.update_colors(&create_engine(12), 0).unwrap();
+        buffer.capture(0, 1).text()
+    };
+
+    assert_eq!(shade(MaskMode::Off), "ab##########\n");
+    assert_eq!(shade(MaskMode::Stencil), "ab\n");
+    assert_eq!(shade(MaskMode::Glow), "ab####\n");
+}
+
+#[test]
+fn test_text_effects_paint_neighbors() {
+    assert_eq!("Outline".parse::<TextEffect>().unwrap(), TextEffect::Outline);
+    assert!("sparkle".parse::<TextEffect>().is_err());
+
+    let painted = |effect: TextEffect| {
+        let mut buffer = RenderBuffer::new((6, 3));
+        buffer.set_effect(effect);
+        buffer.prepare_text("a\n\n").unwrap();
+        buffer.update_colors(&create_engine(6), 0).unwrap();
+        let frame = buffer.capture(0, 3);
+        (0..3)
+            .flat_map(|y| (0..6).map(move |x| (x, y)))
+            .filter(|&(x, y)| frame.cell(x, y).bg.is_some())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(painted(TextEffect::None).is_empty());
+    assert_eq!(painted(TextEffect::Shadow), vec![(1, 1)]);
+    assert_eq!(painted(TextEffect::Outline), vec![(1, 0), (0, 1), (1, 1)]);
+    assert_eq!(
+        painted(TextEffect::Glow),
+        vec![(1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+    );
+}
+
+#[test]
+fn test_static_shadow_extends_past_text() {
+    let mut buffer = RenderBuffer::new((6, 2));
+    buffer.set_effect(TextEffect::Shadow);
+    buffer.prepare_text("a\nb").unwrap();
+    buffer.update_colors_static(&create_engine(6)).unwrap();
+
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 2, true, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let second = out.lines().nth(1).unwrap();
+    assert!(second.contains("\x1b[48;2;"), "{:?}", second);
+    assert!(second.contains("b\x1b[48;2;"));
+}
+
+#[test]
+fn test_resolution_parsing() {
+    assert_eq!("half".parse::<Resolution>().unwrap(), Resolution::Half);
+    assert_eq!("BRAILLE".parse::<Resolution>().unwrap(), Resolution::Braille);
+    assert!("quarter".parse::<Resolution>().is_err());
+
+    for resolution in Resolution::all() {
+        assert_eq!(resolution.to_string().parse::<Resolution>().unwrap(), *resolution);
+    }
+}
+
+#[test]
+fn test_rasterize_subcells() {
+    assert_eq!(Resolution::Cell.rasterize(&[0.5]), None);
+
+    let half = Resolution::Half.rasterize(&[0.2, 0.8]).unwrap();
+    assert_eq!(half.glyph, '▀');
+    assert_eq!((half.fg, half.bg), (0.2, Some(0.8)));
+
+    // Braille raises more dots as values grow
+    let dots = |value: f64| {
+        let cell = Resolution::Braille.rasterize(&[value; 8]).unwrap();
+        (cell.glyph as u32 - 0x2800).count_ones()
+    };
+    assert_eq!(dots(0.0), 0);
+    assert_eq!(dots(0.5), 4);
+    assert_eq!(dots(1.0), 8);
+}
+
+#[test]
+fn test_half_resolution_paints_blank_cells() {
+    let mut buffer = RenderBuffer::new((8, 1));
+    buffer.set_resolution(Resolution::Half);
+    buffer.prepare_text("a      b").unwrap();
+    buffer.update_colors_static(&create_engine(8)).unwrap();
+
+    let line = render_plain(&mut buffer).remove(0);
+    assert_eq!(line, "a▀▀▀▀▀▀b");
+
+    // Blank cells get a background color, which is reset before the text
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 1, true, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("\x1b[48;2;").count(), 6);
+    assert!(out.contains("\x1b[49mb"));
+}
+
+#[test]
+fn test_braille_resolution_keeps_text() {
+    let mut buffer = RenderBuffer::new((8, 1));
+    buffer.set_resolution(Resolution::Braille);
+    buffer.prepare_text("a      b").unwrap();
+    buffer.update_colors(&create_engine(8), 0).unwrap();
+
+    let line = render_plain(&mut buffer).remove(0);
+    assert!(line.starts_with('a') && line.ends_with('b'));
+    assert!(line.chars().skip(1).take(6).all(|c| ('\u{2800}'..='\u{28FF}').contains(&c)));
+
+    // Back at cell resolution the blanks are plain spaces again
+    buffer.set_resolution(Resolution::Cell);
+    buffer.update_colors(&create_engine(8), 0).unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["a      b"]);
+}
+
+#[test]
+fn test_capture_frame() {
+    let mut buffer = RenderBuffer::new((6, 3));
+    buffer.prepare_text("ab\ncdef").unwrap();
+    buffer.update_colors(&create_engine(6), 0).unwrap();
+
+    let frame = buffer.capture(0, 3);
+    assert_eq!((frame.width, frame.height), (6, 3));
+    assert_eq!(frame.text(), "ab\ncdef\n\n");
+    assert!(frame.cell(0, 0).fg.is_some());
+    assert_eq!(*frame.cell(0, 2), FrameCell::default());
+}
+
+#[test]
+fn test_screenshot_export() {
+    assert_eq!("kitty".parse::<ScreenshotTarget>().unwrap(), ScreenshotTarget::Kitty);
+    assert_eq!("WezTerm".parse::<ScreenshotTarget>().unwrap(), ScreenshotTarget::Iterm);
+    assert_eq!(
+        "frame.png".parse::<ScreenshotTarget>().unwrap(),
+        ScreenshotTarget::File("frame.png".into())
+    );
+    assert!("".parse::<ScreenshotTarget>().is_err());
+
+    let mut frame = Frame::new(3, 2);
+    frame.cell_mut(1, 0).ch = '█';
+    frame.cell_mut(1, 0).fg = Some([255, 0, 0]);
+    let png = frame.to_png().unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    // A full block fills its cell with the foreground color
+    let rgb = frame.to_rgb();
+    let row_bytes = 3 * chromacat::renderer::CELL_WIDTH * 3;
+    let center = 8 * row_bytes + (chromacat::renderer::CELL_WIDTH + 4) * 3;
+    assert_eq!(&rgb[center..center + 3], &[255, 0, 0]);
+
+    let mut out = Vec::new();
+    ScreenshotTarget::Iterm.deliver(&frame, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("\x1b]1337;File=inline=1;"));
+
+    // Kitty payloads are split into chunks, all but the last marked m=1
+    let mut big = Frame::new(120, 40);
+    for (i, cell) in big.cells.iter_mut().enumerate() {
+        cell.ch = '▒';
+        cell.fg = Some([(i * 7) as u8, (i * 13) as u8, (i * 29) as u8]);
+    }
+    let mut out = Vec::new();
+    ScreenshotTarget::Kitty.deliver(&big, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("\x1b_Gf=100,a=T,m=1;"));
+    assert!(out.contains("\x1b_Gm=1;"));
+    assert!(out.trim_end().ends_with("\x1b\\"));
+    assert_eq!(out.matches("m=0;").count(), 1);
+}
+
+#[test]
+fn test_column_palettes() {
+    use chromacat::columns::{ColumnDelimiter, Columns};
+
+    let mut buffer = RenderBuffer::new((20, 5));
+    buffer.set_columns(Some(Columns::new(ColumnDelimiter::Auto, &[]).unwrap()));
+    buffer.prepare_text("ab,c\n,d\n").unwrap();
+
+    let range = |row, x| buffer.cell_palette(row, x).map(|palette| palette.range());
+    assert_eq!(range(0, 0), Some((0.0, 0.5)));
+    assert_eq!(range(0, 2), Some((0.0, 0.5)));
+    assert_eq!(range(0, 3), Some((0.5, 1.0)));
+    assert_eq!(range(1, 1), Some((0.5, 1.0)));
+    // Cells past the end of the text have no column
+    assert_eq!(range(0, 10), None);
+
+    buffer.set_columns(None);
+    buffer.prepare_text("ab,c\n").unwrap();
+    assert!(buffer.cell_palette(0, 0).is_none());
+}
+
+#[test]
+fn test_data_colors() {
+    use chromacat::data::DataColumn;
+
+    // Each line takes one color from its number, from the low end of the
+    // gradient to the high end
+    let mut buffer = RenderBuffer::new((12, 4));
+    buffer.set_data(Some(DataColumn::new(2, None)));
+    buffer.prepare_text("a 0\nb 10\nc 5\nnone\n").unwrap();
+    assert_eq!(buffer.data_reader().unwrap().scale(), Some((0.0, 10.0)));
+    buffer.update_colors_static(&create_engine(12)).unwrap();
+
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 3, true, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    for (line, gray) in lines.iter().zip(["0", "255", "127"]) {
+        assert_eq!(line.matches("\x1b[38;2;").count(), 1, "{:?}", line);
+        assert!(line.contains(&format!("\x1b[38;2;{0};{0};{0}m", gray)), "{:?}", line);
+    }
+}
+
+#[test]
+fn test_line_number_gutter() {
+    use chromacat::renderer::Gutter;
+
+    // Text wraps in the columns left beside the line numbers
+    let mut buffer = RenderBuffer::with_wrap_mode((16, 24), WrapMode::Wrap);
+    buffer.set_gutter(Gutter::new(true, true));
+    buffer.prepare_text("hello brave new world\nshort\n").unwrap();
+    assert_eq!(buffer.gutter_width(), 6);
+    assert_eq!(buffer.content_width(), 10);
+    assert_eq!(
+        render_plain(&mut buffer),
+        vec![
+            "      ----+----1",
+            "  1 │ hello",
+            "    │ brave new",
+            "    │ world",
+            "  2 │ short",
+            "",
+        ]
+    );
+
+    // Text continuing earlier output keeps counting and leaves out the ruler
+    buffer.set_line_offset(998);
+    buffer.prepare_text("a\nb").unwrap();
+    assert_eq!(buffer.gutter_width(), 7);
+    assert_eq!(render_plain(&mut buffer), vec![" 999 │ a", "1000 │ b"]);
+}
+
+#[test]
+fn test_continued_text() {
+    use chromacat::columns::{ColumnDelimiter, Columns};
+    use chromacat::renderer::Gutter;
+
+    // A chunk continuing the last one keeps its line numbers and columns
+    let mut buffer = RenderBuffer::new((20, 5));
+    buffer.set_gutter(Gutter::new(true, false));
+    buffer.set_columns(Some(Columns::new(ColumnDelimiter::Auto, &[]).unwrap()));
+    buffer.prepare_text("ab,c\nd,e").unwrap();
+    buffer.continue_text();
+    buffer.prepare_text("f\ng").unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["  3 │ f", "  4 │ g"]);
+    let range = |buffer: &RenderBuffer| buffer.cell_palette(0, 0).map(|p| p.range());
+    assert_eq!(range(&buffer), Some((0.0, 0.5)));
+
+    // Restarting forgets both
+    buffer.restart_text();
+    buffer.prepare_text("f\ng").unwrap();
+    assert_eq!(render_plain(&mut buffer), vec!["  1 │ f", "  2 │ g"]);
+    assert_eq!(range(&buffer), Some((0.0, 1.0)));
+}
+
+#[test]
+fn test_scrolled_colors_match_full_update() {
+    let text = "abcdef\nghijkl\nmnopqr\nstuvwx\nyz";
+    let mut engine = create_engine(6);
+    let colored = |engine: &PatternEngine, start: usize| {
+        let mut buffer = RenderBuffer::new((6, 3));
+        buffer.prepare_text(text).unwrap();
+        buffer.update_colors(engine, start).unwrap();
+        buffer.capture(start, 3)
+    };
+
+    // Scrolling reuses the samples of the last update
+    let mut buffer = RenderBuffer::new((6, 3));
+    buffer.prepare_text(text).unwrap();
+    buffer.update_colors(&engine, 0).unwrap();
+    buffer.update_colors_scrolled(&engine, 1).unwrap();
+    assert_eq!(buffer.capture(1, 3), colored(&engine, 1));
+
+    // and takes new ones once the animation has moved on
+    engine.step(0.5);
+    buffer.update_colors_scrolled(&engine, 2).unwrap();
+    assert_eq!(buffer.capture(2, 3), colored(&engine, 2));
+}
+
+#[test]
+fn test_loop_cache_replays_cycle() {
+    use chromacat::pattern::LoopMode;
+    use chromacat::renderer::{cycle_frames, LoopCache, MAX_LOOP_CACHE_CELLS};
+    use std::time::Duration;
+
+    let text = "abcdef\nghijkl\nmnopqr";
+    let mut engine = create_engine(6);
+    engine.set_cycle(Some(Duration::from_secs(1)), LoopMode::Loop);
+    let quarter = 0.25 / engine.clock().rate();
+    engine.step(quarter / 2.0);
+    let mut buffer = RenderBuffer::new((6, 3));
+    buffer.prepare_text(text).unwrap();
+    buffer.set_loop_cache(Some(4));
+
+    // The first cycle is recorded, one frame per slot,
+    let mut recorded = Vec::new();
+    for _ in 0..4 {
+        buffer.update_colors(&engine, 0).unwrap();
+        recorded.push(buffer.capture(0, 3));
+        engine.step(quarter);
+    }
+    assert_eq!(buffer.loop_cache_progress(), Some((4, 4)));
+
+    // replayed after,
+    for frame in &recorded {
+        buffer.update_colors(&engine, 0).unwrap();
+        assert_eq!(&buffer.capture(0, 3), frame);
+        engine.step(quarter);
+    }
+    assert_eq!(buffer.loop_cache_progress(), Some((4, 4)));
+
+    // and recorded again once the viewport changes size
+    buffer.resize((5, 3)).unwrap();
+    buffer.update_colors(&engine, 0).unwrap();
+    assert_eq!(buffer.loop_cache_progress(), Some((1, 4)));
+
+    // Frames past the memory limit aren't kept
+    let mut cache = LoopCache::new(2);
+    assert!(cache.store(0, (), MAX_LOOP_CACHE_CELLS));
+    assert!(!cache.store(1, (), 1));
+    assert_eq!(cache.take(0), Some(()));
+    assert!(cache.store(1, (), 1));
+    assert_eq!(cycle_frames(Duration::from_millis(2500), 30), 75);
+}
+
+#[test]
+fn test_interlaced_frames() {
+    let text = "abcdef\nghijkl\nmnopqr\nstuvwx";
+    let mut engine = create_engine(6);
+    let mut buffer = RenderBuffer::new((6, 4));
+    buffer.prepare_text(text).unwrap();
+    buffer.set_interlace(Some(3));
+    let frame = |buffer: &mut RenderBuffer, engine: &mut PatternEngine| {
+        engine.step(0.5);
+        buffer.update_colors(engine, 0).unwrap();
+        buffer.begin_interlaced_frame();
+        let mut out = Vec::new();
+        buffer.render_region(&mut out, 0, 4, true, true).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        (1..=4)
+            .filter(|row| out.contains(&format!("\x1b[{};1H", row)))
+            .collect::<Vec<_>>()
+    };
+
+    // Frames take turns drawing the odd and even rows, and every third
+    // frame draws them all
+    assert_eq!(frame(&mut buffer, &mut engine), vec![2, 4]);
+    assert_eq!(frame(&mut buffer, &mut engine), vec![1, 3]);
+    assert_eq!(frame(&mut buffer, &mut engine), vec![1, 2, 3, 4]);
+
+    // Renders outside the animation draw every changed row
+    engine.step(0.5);
+    buffer.update_colors(&engine, 0).unwrap();
+    let mut out = Vec::new();
+    buffer.render_region(&mut out, 0, 4, true, true).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("\x1b[1;1H"));
+}
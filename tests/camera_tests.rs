@@ -0,0 +1,81 @@
+use chromacat::renderer::{parse_pan, Camera, MAX_ZOOM};
+
+fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+    assert!(
+        (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_default_camera_is_identity() {
+    let camera = Camera::default();
+    assert!(camera.is_identity());
+    for point in [(-0.5, -0.5), (0.0, 0.25), (0.49, 0.3)] {
+        assert_eq!(camera.map(point.0, point.1), point);
+    }
+}
+
+#[test]
+fn test_canvas_windows_tile_the_canvas() {
+    // On a canvas twice the terminal size, pan corners show the quadrants
+    let top_left = Camera::new(2.0).with_pan(0.0, 0.0);
+    assert_close(top_left.map(-0.5, -0.5), (-0.5, -0.5));
+    assert_close(top_left.map(0.5, 0.5), (0.0, 0.0));
+
+    let bottom_right = Camera::new(2.0).with_pan(1.0, 1.0);
+    assert_close(bottom_right.map(-0.5, -0.5), (0.0, 0.0));
+    assert_close(bottom_right.map(0.5, 0.5), (0.5, 0.5));
+
+    let centered = Camera::new(4.0);
+    assert_close(centered.map(0.0, 0.0), (0.0, 0.0));
+    assert_close(centered.map(0.5, -0.5), (0.125, -0.125));
+}
+
+#[test]
+fn test_zoom_and_pan_limits() {
+    let mut camera = Camera::new(4.0);
+
+    // Zooming out stops once the whole canvas is visible
+    camera.zoom_by(-20);
+    assert_eq!(camera.zoom(), 0.25);
+    assert_close(camera.map(0.5, 0.5), (0.5, 0.5));
+    camera.zoom_by(40);
+    assert_eq!(camera.zoom(), MAX_ZOOM);
+
+    camera.reset();
+    camera.pan_by(1, 0);
+    assert!(camera.pan().0 > 0.5);
+    assert_eq!(camera.pan().1, 0.5);
+    camera.pan_by(-100, -100);
+    assert_eq!(camera.pan(), (0.0, 0.0));
+
+    // Without a larger canvas or zoom there is nowhere to pan
+    let mut plain = Camera::default();
+    plain.pan_by(3, 3);
+    assert_eq!(plain.pan(), (0.5, 0.5));
+}
+
+#[test]
+fn test_drift_moves_the_view() {
+    let mut camera = Camera::new(3.0).with_drift(true);
+    let start = camera.map(0.0, 0.0);
+    camera.advance(10.0);
+    assert_ne!(camera.map(0.0, 0.0), start);
+
+    // Time does not pass while drift is off
+    camera.toggle_drift();
+    let frozen = camera.clone();
+    camera.advance(10.0);
+    assert_eq!(camera, frozen);
+}
+
+#[test]
+fn test_parse_pan() {
+    assert_eq!(parse_pan("0.25, 1").unwrap(), (0.25, 1.0));
+    assert!(parse_pan("0.5").is_err());
+    assert!(parse_pan("1.5,0").is_err());
+    assert!(parse_pan("a,b").is_err());
+}
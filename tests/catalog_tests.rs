@@ -0,0 +1,75 @@
+use chromacat::catalog::{Catalog, ListFilter};
+
+fn filter(search: Option<&str>, category: Option<&str>) -> ListFilter {
+    ListFilter {
+        search: search.map(str::to_string),
+        category: category.map(str::to_string),
+        tag: None,
+    }
+}
+
+#[test]
+fn test_unfiltered_catalog() {
+    let catalog = Catalog::collect(&ListFilter::default()).unwrap();
+    assert_eq!(catalog.themes.len(), chromacat::themes::theme_count());
+
+    let ids: Vec<_> = catalog.patterns.iter().map(|p| p.id.as_str()).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(ids.contains(&"plasma"));
+
+    assert_eq!(catalog.patterns, chromacat::introspect().patterns);
+}
+
+#[test]
+fn test_catalog_filters() {
+    // Search matches names and descriptions, ignoring case
+    let catalog = Catalog::collect(&filter(Some("NEON"), None)).unwrap();
+    assert!(catalog.themes.iter().any(|t| t.name == "neon"));
+    assert!(catalog
+        .themes
+        .iter()
+        .all(|t| format!("{} {}", t.name, t.description).to_lowercase().contains("neon")));
+
+    // A category lists only its themes, and no patterns
+    let catalog = Catalog::collect(&filter(None, Some("Space"))).unwrap();
+    assert!(catalog.patterns.is_empty());
+    assert!(!catalog.themes.is_empty());
+    assert!(catalog.themes.iter().all(|t| t.category == "space"));
+
+    assert!(Catalog::collect(&filter(None, Some("nope"))).is_err());
+    assert!(Catalog::collect(&filter(Some("no such thing"), None))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_catalog_tags() {
+    // A tag lists only the themes carrying it, ignoring case, and no patterns
+    let catalog = Catalog::collect(&ListFilter {
+        tag: Some("Warm".to_string()),
+        ..ListFilter::default()
+    })
+    .unwrap();
+    assert!(catalog.patterns.is_empty());
+    assert!(catalog.themes.iter().any(|t| t.name == "fire"));
+    assert!(catalog
+        .themes
+        .iter()
+        .all(|t| t.tags.iter().any(|tag| tag == "warm")));
+    assert!(!catalog.themes.iter().any(|t| t.name == "ocean"));
+
+    let json = catalog.to_json().unwrap();
+    assert!(json.contains("\"tags\""));
+    // Unset metadata is left out
+    assert!(!json.contains("\"author\""));
+}
+
+#[test]
+fn test_catalog_json() {
+    let catalog = Catalog::collect(&filter(Some("ripple"), None)).unwrap();
+    let json = catalog.to_json().unwrap();
+    assert!(json.contains("\"id\": \"ripple\""));
+    assert!(json.contains("\"type\": \"number\""));
+    assert!(json.contains("\"min\""));
+    assert!(json.contains("\"themes\""));
+}
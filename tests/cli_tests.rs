@@ -1,4 +1,5 @@
 use chromacat::cli::Cli;
+use chromacat::renderer::{LegendPosition, MaskMode, TextEffect};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -9,7 +10,7 @@ fn test_basic_cli() {
     assert_eq!(cli.files, vec![PathBuf::from("input.txt")]);
     assert_eq!(cli.pattern, "diagonal");
     assert_eq!(cli.theme, "rainbow");
-    assert!(!cli.animate);
+    assert!(!cli.animation.animate);
 }
 
 #[test]
@@ -68,9 +69,9 @@ fn test_animation_settings() {
         "input.txt",
     ];
     let cli = Cli::try_parse_from(args).unwrap();
-    assert!(cli.animate);
-    assert_eq!(cli.fps, 60);
-    assert_eq!(cli.duration, 5);
+    assert!(cli.animation.animate);
+    assert_eq!(cli.animation.fps, 60);
+    assert_eq!(cli.animation.duration, 5);
 }
 
 #[test]
@@ -105,8 +106,8 @@ fn test_multiple_files() {
 fn test_animation_defaults() {
     let args = vec!["chromacat", "--animate"];
     let cli = Cli::try_parse_from(args).unwrap();
-    assert_eq!(cli.fps, 30); // Default FPS
-    assert_eq!(cli.duration, 0); // Infinite duration by default
+    assert_eq!(cli.animation.fps, 30); // Default FPS
+    assert_eq!(cli.animation.duration, 0); // Infinite duration by default
 }
 
 #[test]
@@ -198,3 +199,1025 @@ fn test_invalid_aspect_ratio() {
     let cli = Cli::try_parse_from(args).unwrap();
     assert!(cli.validate().is_err());
 }
+
+#[test]
+fn test_wrap_mode_flag() {
+    use chromacat::renderer::WrapMode;
+
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert_eq!(cli.text.wrap, WrapMode::Wrap);
+
+    let cli = Cli::try_parse_from(["chromacat", "--wrap", "scroll"]).unwrap();
+    assert_eq!(cli.text.wrap, WrapMode::Scroll);
+    assert_eq!(cli.create_animation_config().wrap_mode, WrapMode::Scroll);
+
+    assert!(Cli::try_parse_from(["chromacat", "--wrap", "bogus"]).is_err());
+}
+
+#[test]
+fn test_postprocess_flags() {
+    use chromacat::pattern::{CharRamp, DitherMode};
+
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert!(cli.create_postprocess().is_identity());
+    assert!(cli.create_animation_config().char_ramp.is_none());
+
+    let cli = Cli::try_parse_from(["chromacat", "--posterize", "6", "--dither", "ordered"]).unwrap();
+    assert!(cli.validate().is_ok());
+    let post = cli.create_postprocess();
+    assert_eq!(post.levels, Some(6));
+    assert_eq!(post.dither, DitherMode::Ordered);
+
+    // Bare --char-ramp uses the default ramp
+    let cli = Cli::try_parse_from(["chromacat", "--char-ramp"]).unwrap();
+    assert_eq!(cli.create_animation_config().char_ramp, Some(CharRamp::default()));
+
+    let cli = Cli::try_parse_from(["chromacat", "--char-ramp=@%#*+=-:. "]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.create_animation_config().char_ramp.unwrap().chars().len(), 10);
+
+    // A value must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "--char-ramp", "notes.txt"]).unwrap();
+    assert_eq!(
+        cli.create_animation_config().char_ramp,
+        Some(CharRamp::default())
+    );
+    assert_eq!(cli.files, vec![PathBuf::from("notes.txt")]);
+
+    assert!(Cli::try_parse_from(["chromacat", "--dither", "bogus"]).is_err());
+}
+
+#[test]
+fn test_postprocess_validation() {
+    let invalid = [
+        vec!["chromacat", "--posterize", "1"],
+        vec!["chromacat", "--posterize", "65"],
+        vec!["chromacat", "--dither", "ordered"],
+        vec!["chromacat", "--char-ramp=漢字"],
+        vec!["chromacat", "--char-ramp="],
+    ];
+    for args in invalid {
+        let cli = Cli::try_parse_from(&args).unwrap();
+        assert!(cli.validate().is_err(), "{:?} should be rejected", args);
+    }
+}
+
+#[test]
+fn test_theme_per_file_cycles() {
+    let cli = Cli::try_parse_from([
+        "chromacat",
+        "--file-headers",
+        "--theme-per-file",
+        "ocean,sunset",
+        "a.yaml",
+        "b.yaml",
+        "c.yaml",
+    ])
+    .unwrap();
+
+    assert!(cli.text.file_headers);
+    assert_eq!(cli.text.theme_per_file, vec!["ocean", "sunset"]);
+    assert_eq!(cli.theme_for_file(0), Some("ocean"));
+    assert_eq!(cli.theme_for_file(1), Some("sunset"));
+    assert_eq!(cli.theme_for_file(2), Some("ocean"));
+
+    let cli = Cli::try_parse_from(["chromacat", "a.yaml"]).unwrap();
+    assert_eq!(cli.theme_for_file(0), None);
+}
+
+#[test]
+fn test_exec_subcommand() {
+    use chromacat::cli::Command;
+
+    let cli = Cli::try_parse_from(["chromacat", "-t", "ocean", "exec", "--", "ls", "-la"]).unwrap();
+    assert_eq!(cli.theme, "ocean");
+    match cli.command {
+        Some(Command::Exec { command }) => assert_eq!(command, vec!["ls", "-la"]),
+        other => panic!("expected exec subcommand, got {:?}", other),
+    }
+
+    // Hyphenated arguments belong to the child even without `--`
+    let cli = Cli::try_parse_from(["chromacat", "exec", "git", "log", "--oneline"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Exec { ref command }) if command == &["git", "log", "--oneline"]
+    ));
+
+    assert!(Cli::try_parse_from(["chromacat", "exec"]).is_err());
+}
+
+#[test]
+fn test_progress_subcommand() {
+    use chromacat::error::ChromaCatError;
+
+    let cli = Cli::try_parse_from([
+        "chromacat", "--no-color", "progress", "--total", "10", "--label", "Copying", "--spinner",
+    ])
+    .unwrap();
+
+    let options = cli.create_progress_options().expect("progress options");
+    assert_eq!(options.total, 10.0);
+    assert_eq!(options.label.as_deref(), Some("Copying"));
+    assert!(options.spinner);
+    assert!(!options.colors_enabled);
+
+    let cli = Cli::try_parse_from(["chromacat", "file.txt"]).unwrap();
+    assert!(cli.create_progress_options().is_none());
+
+    // The total must be a positive number
+    for total in ["--total=0", "--total=-5", "--total=NaN"] {
+        let cli = Cli::try_parse_from(["chromacat", "progress", total]).unwrap();
+        assert!(matches!(cli.validate(), Err(ChromaCatError::InputError(_))));
+    }
+}
+
+#[test]
+fn test_countdown_flag() {
+    use std::time::Duration;
+
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--countdown", "10m"]).unwrap();
+    assert_eq!(cli.art.countdown, Some(Duration::from_secs(600)));
+    assert_eq!(cli.demo_art(), Some("clock"));
+    assert_eq!(cli.create_art_settings().unwrap().countdown, Some(Duration::from_secs(600)));
+    assert!(cli.validate().is_ok());
+
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--art", "clock", "--countdown", "90s"]).unwrap();
+    assert!(cli.validate().is_ok());
+
+    assert!(Cli::try_parse_from(["chromacat", "--demo", "--countdown", "soon"]).is_err());
+
+    for args in [
+        vec!["chromacat", "--countdown", "10m"],
+        vec!["chromacat", "--demo", "--art", "matrix", "--countdown", "10m"],
+    ] {
+        let cli = Cli::try_parse_from(&args).unwrap();
+        assert!(cli.validate().is_err(), "{:?} should be rejected", args);
+    }
+}
+
+#[test]
+fn test_timer_subcommand() {
+    use std::time::Duration;
+
+    let cli = Cli::try_parse_args(["chromacat", "timer", "25m"]).unwrap();
+    assert!(cli.demo && cli.animation.animate);
+    assert_eq!(cli.demo_art(), Some("clock"));
+    assert_eq!(cli.art.countdown, Some(Duration::from_secs(25 * 60)));
+    assert_eq!(cli.theme, "ocean");
+    assert_eq!(cli.art.countdown_theme.as_deref(), Some("fire"));
+    assert!(cli.validate().is_ok());
+
+    let cli =
+        Cli::try_parse_args(["chromacat", "timer", "90s", "--from", "forest", "--to", "sunset"]).unwrap();
+    assert_eq!(cli.theme, "forest");
+    assert_eq!(cli.art.countdown_theme.as_deref(), Some("sunset"));
+    assert!(cli.validate().is_ok());
+
+    let cli = Cli::try_parse_args(["chromacat", "timer", "5m", "--to", "no-such-theme"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    assert!(Cli::try_parse_from(["chromacat", "timer", "soon"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--countdown-theme", "fire"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_adaptive_flags() {
+    use chromacat::playlist::AdaptiveSource;
+
+    let cli = Cli::try_parse_from(["chromacat", "--adaptive"]).unwrap();
+    assert_eq!(cli.playlist.adaptive, Some(AdaptiveSource::Time));
+    assert!(cli.validate().is_ok());
+
+    let cli = Cli::try_parse_from(["chromacat", "--adaptive=weather", "--location", "Oslo"]).unwrap();
+    assert_eq!(cli.playlist.adaptive, Some(AdaptiveSource::Weather));
+    assert_eq!(cli.playlist.location.as_deref(), Some("Oslo"));
+    assert_eq!(cli.validate().is_ok(), cfg!(feature = "weather"));
+
+    let cli = Cli::try_parse_from(["chromacat", "--adaptive=time", "--location", "Oslo"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    assert!(Cli::try_parse_from(["chromacat", "--adaptive=tides"]).is_err());
+
+    // A source must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "--adaptive", "weather"]).unwrap();
+    assert_eq!(cli.playlist.adaptive, Some(AdaptiveSource::Time));
+    assert_eq!(cli.files, vec![PathBuf::from("weather")]);
+    assert!(Cli::try_parse_from(["chromacat", "--location", "Oslo"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--adaptive", "--random"]).is_err());
+}
+
+#[test]
+fn test_mqtt_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "--animate", "--mqtt", "mqtt://broker"]).unwrap();
+    assert_eq!(cli.animation.mqtt_topic, "chromacat");
+    assert_eq!(cli.validate().is_ok(), cfg!(all(unix, feature = "mqtt")));
+
+    let cli = Cli::try_parse_from(["chromacat", "--mqtt", "mqtt://broker"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    if cfg!(all(unix, feature = "mqtt")) {
+        let cli =
+            Cli::try_parse_from(["chromacat", "--animate", "--mqtt", "http://broker"]).unwrap();
+        assert!(cli.validate().is_err());
+    }
+
+    assert!(Cli::try_parse_from(["chromacat", "--mqtt-topic", "wall"]).is_err());
+}
+
+#[test]
+fn test_quote_file_flag() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "First quote.\n-- Someone\n%\nSecond quote.").unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--quote-file", path]).unwrap();
+    assert_eq!(cli.demo_art(), Some("quote"));
+    assert!(cli.validate().is_ok());
+    let settings = cli.create_art_settings().unwrap();
+    assert_eq!(settings.quotes.len(), 2);
+    assert_eq!(settings.quotes[0].author.as_deref(), Some("Someone"));
+
+    for args in [
+        vec!["chromacat", "--quote-file", path],
+        vec!["chromacat", "--demo", "--art", "matrix", "--quote-file", path],
+        vec!["chromacat", "--demo", "--quote-file", "/nonexistent/quotes.txt"],
+    ] {
+        let cli = Cli::try_parse_from(&args).unwrap();
+        assert!(cli.validate().is_err(), "{:?} should be rejected", args);
+    }
+
+    let empty = tempfile::NamedTempFile::new().unwrap();
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--quote-file", empty.path().to_str().unwrap()]).unwrap();
+    assert!(cli.create_art_settings().is_err());
+}
+
+#[test]
+fn test_art_param_flag() {
+    use chromacat::demo::DemoArt;
+
+    let cli = Cli::try_parse_from([
+        "chromacat", "--demo", "--art", "matrix", "--art-param", "density=0.5,speed=2", "--art-param", "speed=3",
+    ])
+    .unwrap();
+    assert!(cli.validate().is_ok());
+    let settings = cli.create_art_settings().unwrap();
+    assert_eq!(settings.number(DemoArt::Matrix, "density"), 0.5);
+    assert_eq!(settings.number(DemoArt::Matrix, "speed"), 3.0);
+
+    // Without an art, any art's parameters are accepted
+    let cli = Cli::try_parse_from(["chromacat", "--demo", "--art-param", "symmetry=8"]).unwrap();
+    assert!(cli.validate().is_ok());
+
+    for args in [
+        vec!["chromacat", "--art-param", "density=0.5"],
+        vec!["chromacat", "--demo", "--art", "matrix", "--art-param", "symmetry=8"],
+        vec!["chromacat", "--demo", "--art", "matrix", "--art-param", "density=2"],
+        vec!["chromacat", "--demo", "--art", "quote", "--art-param", "border=wavy"],
+        vec!["chromacat", "--demo", "--art", "logo", "--art-param", "density"],
+    ] {
+        let cli = Cli::try_parse_from(&args).unwrap();
+        assert!(cli.validate().is_err(), "{:?} should be rejected", args);
+    }
+}
+
+#[test]
+fn test_resolution_flag() {
+    use chromacat::renderer::Resolution;
+
+    let cli = Cli::try_parse_from(["chromacat", "-a"]).unwrap();
+    assert_eq!(cli.create_animation_config().resolution, Resolution::Cell);
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--resolution", "braille"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.create_animation_config().resolution, Resolution::Braille);
+
+    assert!(Cli::try_parse_from(["chromacat", "--resolution", "quarter"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "--resolution", "half", "--char-ramp"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_screenshot_flag() {
+    use chromacat::renderer::ScreenshotTarget;
+
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert_eq!(cli.screenshot, None);
+
+    let cli = Cli::try_parse_from(["chromacat", "--screenshot"]).unwrap();
+    assert_eq!(cli.screenshot, Some(ScreenshotTarget::Inline));
+
+    let cli = Cli::try_parse_from(["chromacat", "--screenshot=out.png", "-p", "plasma"]).unwrap();
+    assert_eq!(cli.screenshot, Some(ScreenshotTarget::File("out.png".into())));
+
+    // A target must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "--screenshot", "README.md"]).unwrap();
+    assert_eq!(cli.screenshot, Some(ScreenshotTarget::Inline));
+    assert_eq!(cli.files, vec![PathBuf::from("README.md")]);
+}
+
+#[test]
+fn test_emit_frames_flags() {
+    use chromacat::frames::FrameFormat;
+
+    let cli = Cli::try_parse_from([
+        "chromacat", "--emit-frames", "rgb", "--frame-size", "32x8", "--emit-to", "/tmp/leds",
+    ])
+    .unwrap();
+    assert_eq!(cli.emit_frames, Some(FrameFormat::Rgb));
+    assert_eq!(cli.frame_size, Some((32, 8)));
+    assert!(cli.validate().is_ok());
+
+    assert!(Cli::try_parse_from(["chromacat", "--emit-frames", "png"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--frame-size", "32x8"]).is_err());
+    assert!(
+        Cli::try_parse_from(["chromacat", "--emit-frames", "json", "--status-line", "20"]).is_err()
+    );
+}
+
+#[test]
+fn test_led_output_flags() {
+    let cli = Cli::try_parse_from([
+        "chromacat", "--output", "wled://192.168.1.50", "--led-count", "144", "-p", "plasma",
+    ])
+    .unwrap();
+    assert_eq!(cli.output.as_ref().unwrap().host, "192.168.1.50");
+    assert_eq!(cli.led_count, Some(144));
+    assert!(cli.validate().is_ok());
+
+    let cli = Cli::try_parse_from(["chromacat", "--output", "wled://strip"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    for args in [
+        vec!["chromacat", "--output", "hue://bridge", "--led-count", "10"],
+        vec!["chromacat", "--led-count", "10"],
+        vec!["chromacat", "--output", "wled://strip", "--led-count", "0"],
+        vec!["chromacat", "--output", "wled://strip", "--led-count", "8", "--led-map", "m.yaml"],
+    ] {
+        assert!(Cli::try_parse_from(&args).is_err(), "{:?} parsed", args);
+    }
+}
+
+#[test]
+fn test_status_line_flags() {
+    use chromacat::statusline::StatusFormat;
+
+    let cli = Cli::try_parse_from([
+        "chromacat", "--status-line", "30", "--status-format", "tmux", "--time-offset", "-2.5",
+    ])
+    .unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.status_line, Some(30));
+    assert_eq!(cli.status_format, StatusFormat::Tmux);
+    assert_eq!(cli.time_offset, -2.5);
+
+    assert!(Cli::try_parse_from(["chromacat", "--status-line", "0"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "--status-format", "tmux"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--time-offset", "10"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--screenshot", "--time-offset", "10"]).unwrap();
+    assert!(cli.validate().is_ok());
+    let cli = Cli::try_parse_from(["chromacat", "--status-line", "20", "--demo"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_daemon_and_ctl_subcommands() {
+    use chromacat::cli::Command;
+
+    let cli = Cli::try_parse_args(["chromacat", "daemon", "--socket", "/tmp/c.sock"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Daemon {
+            socket: Some(_),
+            ..
+        })
+    ));
+    assert!(cli.demo);
+
+    let cli = Cli::try_parse_from(["chromacat", "ctl", "set-theme", "ocean"]).unwrap();
+    match cli.command {
+        Some(Command::Ctl { socket, command }) => {
+            assert_eq!(socket, None);
+            assert_eq!(command, vec!["set-theme", "ocean"]);
+        }
+        _ => panic!("expected ctl subcommand"),
+    }
+
+    assert!(Cli::try_parse_from(["chromacat", "ctl"]).is_err());
+}
+
+#[test]
+fn test_sync_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--sync-leader", "0.0.0.0:7878"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.animation.sync_leader.as_deref(), Some("0.0.0.0:7878"));
+
+    let cli = Cli::try_parse_from(["chromacat", "--sync-follow", "wall-1:7878", "daemon"]).unwrap();
+    assert!(cli.validate().is_ok());
+
+    let cli = Cli::try_parse_from(["chromacat", "--sync-follow", "wall-1:7878"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    // Followers always step frames at fixed timestamps
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--fps", "24", "--sync-follow", "wall-1:7878"]).unwrap();
+    assert_eq!(cli.fixed_step().map(|step| step.fps()), Some(24));
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--fixed-step"]).unwrap();
+    assert!(cli.fixed_step().is_some());
+    let cli = Cli::try_parse_from(["chromacat", "-a"]).unwrap();
+    assert!(cli.fixed_step().is_none());
+
+    assert!(Cli::try_parse_from([
+        "chromacat", "-a", "--sync-leader", ":7878", "--sync-follow", "wall-1:7878"
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_canvas_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--canvas", "2", "--pan", "1,0"]).unwrap();
+    assert!(cli.validate().is_ok());
+    let camera = cli.create_animation_config().camera;
+    assert_eq!(camera.canvas_scale(), 2.0);
+    assert_eq!(camera.pan(), (1.0, 0.0));
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--drift"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert!(cli.create_camera().is_drifting());
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--canvas", "32"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--pan", "0,0"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--canvas", "2"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_layout_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--layout", "zones.yaml"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.animation.layout.as_deref(), Some(std::path::Path::new("zones.yaml")));
+
+    let cli = Cli::try_parse_from(["chromacat", "--layout", "zones.yaml"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_mask_mode_flag() {
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert_eq!(cli.create_animation_config().mask_mode, MaskMode::Off);
+
+    let cli = Cli::try_parse_from(["chromacat", "--mask-mode", "glow"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.create_animation_config().mask_mode, MaskMode::Glow);
+
+    assert!(Cli::try_parse_from(["chromacat", "--mask-mode", "blur"]).is_err());
+}
+
+#[test]
+fn test_effect_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "--effect", "shadow"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.create_animation_config().effect, TextEffect::Shadow);
+
+    // Effects paint the blank cells that ramps and sub-cell drawing fill
+    let cli = Cli::try_parse_from(["chromacat", "--effect", "glow", "--char-ramp=#"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli =
+        Cli::try_parse_from(["chromacat", "--effect", "glow", "--resolution", "half"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_legend_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "--legend"]).unwrap();
+    assert_eq!(cli.animation.legend, Some(LegendPosition::BottomRight));
+
+    let cli = Cli::try_parse_from(["chromacat", "--legend=top-left"]).unwrap();
+    assert_eq!(cli.animation.legend, Some(LegendPosition::TopLeft));
+
+    // A position must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "--legend", "notes.txt"]).unwrap();
+    assert_eq!(cli.animation.legend, Some(LegendPosition::BottomRight));
+    assert_eq!(cli.files, vec![PathBuf::from("notes.txt")]);
+}
+
+#[test]
+fn test_inline_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--inline", "--duration", "3"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert!(cli.animation.inline);
+
+    let cli = Cli::try_parse_from(["chromacat", "--inline"]).unwrap();
+    assert!(cli.validate().is_err());
+
+    // Inline output never takes over the screen, so it can't share it
+    let cli =
+        Cli::try_parse_from(["chromacat", "-a", "--inline", "--layout", "layout.yaml"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--inline", "--status-line", "40"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_mouse_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--mouse"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert!(cli.animation.mouse);
+
+    let cli = Cli::try_parse_from(["chromacat", "--mouse"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--mouse", "--inline"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_stats_flags() {
+    let cli =
+        Cli::try_parse_from(["chromacat", "-a", "--summary", "--stats", "stats.json"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert!(cli.animation.summary);
+    assert_eq!(cli.animation.stats, Some(PathBuf::from("stats.json")));
+
+    let cli = Cli::try_parse_from(["chromacat", "--summary"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--stats", "stats.json"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--summary", "--inline"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_profile_flags() {
+    use chromacat::renderer::RenderProfile;
+
+    let cli =
+        Cli::try_parse_from(["chromacat", "-a", "--fps", "60", "--profile", "minimal"]).unwrap();
+    assert_eq!(cli.profile, Some(RenderProfile::Minimal));
+    assert_eq!(cli.create_animation_config().fps, 15);
+
+    // Without a profile, one is picked while running, unless frames are
+    // fixed steps
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--fps", "60"]).unwrap();
+    assert_eq!(cli.create_animation_config().profile, None);
+    assert_eq!(cli.create_animation_config().fps, 60);
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--fixed-step"]).unwrap();
+    assert_eq!(
+        cli.create_animation_config().profile,
+        Some(RenderProfile::Full)
+    );
+    assert!(Cli::try_parse_from(["chromacat", "--profile", "tiny"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--cpu-budget", "20%"]).unwrap();
+    assert_eq!(cli.create_animation_config().cpu_budget, Some(20.0));
+    assert!(Cli::try_parse_from(["chromacat", "--cpu-budget", "20%", "--fixed-step"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--cycle", "4", "--loop-cache"]).unwrap();
+    assert!(cli.create_animation_config().loop_cache);
+    assert!(Cli::try_parse_from(["chromacat", "-a", "--loop-cache"]).is_err());
+
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--interlace"]).unwrap();
+    assert_eq!(cli.create_animation_config().interlace, Some(8));
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--interlace=4"]).unwrap();
+    assert_eq!(cli.animation.interlace, Some(4));
+    assert!(Cli::try_parse_from(["chromacat", "-a", "--interlace=1"]).is_err());
+
+    // A frame count must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "-a", "--interlace", "notes.txt"]).unwrap();
+    assert_eq!(cli.animation.interlace, Some(8));
+    assert_eq!(cli.files, vec![PathBuf::from("notes.txt")]);
+}
+
+#[test]
+fn test_verbosity_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "-vv", "--log-file", "run.log"]).unwrap();
+    assert_eq!(cli.verbose, 2);
+    assert_eq!(cli.log_file, Some(PathBuf::from("run.log")));
+    let cli = Cli::try_parse_from(["chromacat", "-q"]).unwrap();
+    assert!(cli.quiet);
+    assert!(Cli::try_parse_from(["chromacat", "-q", "-v"]).is_err());
+}
+
+#[test]
+fn test_playlist_from_args() {
+    use chromacat::pattern::BlendMode;
+
+    let line =
+        "chromacat -p plasma,ripple --blend screen -t neon --param 'complexity=3.2' demo logo";
+    let playlist = Cli::playlist_from_args(line, 20).unwrap();
+    let entry = &playlist.entries[0];
+    assert_eq!(
+        (entry.pattern.as_str(), entry.theme.as_str()),
+        ("plasma", "neon")
+    );
+    assert_eq!(entry.duration, 20);
+    assert_eq!(
+        entry.params_string().unwrap().as_deref(),
+        Some("complexity=3.2")
+    );
+    assert_eq!(entry.layers[0].blend, BlendMode::Screen);
+    assert!(entry.art.is_some());
+
+    // The entry's command line parses back into the same entry
+    let command = entry.to_command().unwrap();
+    assert_eq!(
+        command,
+        "chromacat -p plasma,ripple -t neon --blend screen --param complexity=3.2 demo logo"
+    );
+    let again = Cli::playlist_from_args(&command, 20).unwrap();
+    assert_eq!(again.entries[0].to_command().unwrap(), command);
+
+    assert!(Cli::playlist_from_args("-p plasma -t nope", 20).is_err());
+    assert!(Cli::playlist_from_args("-p plasma --bogus", 20).is_err());
+}
+
+#[test]
+fn test_color_adjust_flags() {
+    let cli = Cli::try_parse_from([
+        "chromacat",
+        "--brightness",
+        "0.8",
+        "--saturation",
+        "1.5",
+        "--contrast",
+        "1.2",
+        "--gamma",
+        "2.2",
+    ])
+    .unwrap();
+    assert!(cli.validate().is_ok());
+    let color = cli.create_postprocess().color;
+    assert_eq!(color.brightness, 0.8);
+    assert_eq!(color.saturation, 1.5);
+    assert_eq!(color.contrast, 1.2);
+    assert_eq!(color.gamma, 2.2);
+
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert!(cli.create_postprocess().color.is_identity());
+
+    for args in [["--brightness", "3.5"], ["--saturation", "4"], ["--gamma", "0"]] {
+        let cli = Cli::try_parse_from(["chromacat", args[0], args[1]]).unwrap();
+        assert!(cli.validate().is_err(), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_adapt_bg_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "--adapt-bg", "-t", "neon"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert!(cli.adapt_bg);
+    assert!(!Cli::try_parse_from(["chromacat"]).unwrap().adapt_bg);
+}
+
+#[test]
+fn test_min_contrast_flag() {
+    let cli = Cli::try_parse_from(["chromacat", "--min-contrast", "4.5"]).unwrap();
+    assert!(cli.validate().is_ok());
+    assert_eq!(cli.create_postprocess().color.min_contrast, Some(4.5));
+
+    let cli = Cli::try_parse_from(["chromacat", "--min-contrast", "30"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_themes_preview_command() {
+    use chromacat::cli::{Command, ThemesCommand};
+
+    let cli = Cli::try_parse_from(["chromacat", "themes", "preview", "ocean"]).unwrap();
+    assert!(cli.validate().is_ok());
+    match cli.command {
+        Some(Command::Themes {
+            command: ThemesCommand::Preview { theme, all, seconds },
+        }) => {
+            assert_eq!(theme.as_deref(), Some("ocean"));
+            assert!(!all);
+            assert_eq!(seconds, 3);
+        }
+        other => panic!("unexpected command: {:?}", other),
+    }
+
+    assert!(Cli::try_parse_from(["chromacat", "themes", "preview", "--all"]).is_ok());
+    assert!(Cli::try_parse_from(["chromacat", "themes", "preview"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "themes", "preview", "ocean", "--all"]).is_err());
+
+    let cli =
+        Cli::try_parse_from(["chromacat", "themes", "preview", "--all", "--seconds", "0"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_list_filter_flags() {
+    use chromacat::catalog::ListFormat;
+
+    let cli = Cli::try_parse_from([
+        "chromacat", "--list", "--filter", "neon", "--category", "space", "--format", "json",
+    ])
+    .unwrap();
+    assert_eq!(cli.list_format, ListFormat::Json);
+    let filter = cli.list_filter();
+    assert_eq!(filter.search.as_deref(), Some("neon"));
+    assert_eq!(filter.category.as_deref(), Some("space"));
+
+    // The filters only make sense with --list
+    assert!(Cli::try_parse_from(["chromacat", "--filter", "neon"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--list", "--format", "xml"]).is_err());
+}
+
+#[test]
+fn test_blend_flag() {
+    use chromacat::pattern::BlendMode;
+
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma, ripple,wave", "--blend", "screen"])
+        .unwrap();
+    cli.validate().unwrap();
+    assert_eq!(cli.base_pattern(), "plasma");
+    assert_eq!(cli.layer_patterns(), vec!["ripple", "wave"]);
+    let config = cli.create_pattern_config().unwrap();
+    assert_eq!(config.layers.len(), 2);
+    assert!(config.layers.iter().all(|layer| layer.blend == BlendMode::Screen));
+
+    // Multiply is the default, and --param applies to the base pattern
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma,ripple", "--param", "complexity=3"])
+        .unwrap();
+    cli.validate().unwrap();
+    assert_eq!(cli.create_pattern_config().unwrap().layers[0].blend, BlendMode::Multiply);
+
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma", "--blend", "add"]).unwrap();
+    assert!(cli.validate().is_err());
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma,nope"]).unwrap();
+    assert!(cli.create_pattern_config().is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--blend", "overlay"]).is_err());
+}
+
+#[test]
+fn test_preset_flag() {
+    use chromacat::pattern::PatternParams;
+
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma", "--preset", "lava"]).unwrap();
+    cli.validate().unwrap();
+    match cli.create_pattern_config().unwrap().params {
+        PatternParams::Plasma(params) => {
+            assert_eq!(params.complexity, 2.0);
+            assert_eq!(params.scale, 0.6);
+        }
+        _ => panic!("expected plasma params"),
+    }
+
+    // --param overrides what the preset sets
+    let cli = Cli::try_parse_from([
+        "chromacat", "-p", "plasma", "--preset", "lava", "--param", "scale=1.5",
+    ])
+    .unwrap();
+    match cli.create_pattern_config().unwrap().params {
+        PatternParams::Plasma(params) => {
+            assert_eq!(params.complexity, 2.0);
+            assert_eq!(params.scale, 1.5);
+        }
+        _ => panic!("expected plasma params"),
+    }
+
+    let cli = Cli::try_parse_from(["chromacat", "-p", "plasma", "--preset", "campfire"]).unwrap();
+    let err = cli.validate().unwrap_err().to_string();
+    assert!(err.contains("lava"), "{}", err);
+    assert!(Cli::try_parse_from(["chromacat", "--preset", "lava", "--randomize"]).is_err());
+}
+
+#[test]
+fn test_data_column_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "--data-column", "3", "--data-range", "0,50"])
+        .unwrap();
+    cli.validate().unwrap();
+    let data = cli.create_data_column().unwrap();
+    assert_eq!(data.column(), 3);
+    assert_eq!(data.range(), Some((0.0, 50.0)));
+    assert!(Cli::try_parse_from(["chromacat"]).unwrap().create_data_column().is_none());
+
+    assert!(Cli::try_parse_from(["chromacat", "--data-column", "0"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--data-range", "0,50"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--data-column", "1", "--columns"]).is_err());
+}
+
+#[test]
+fn test_light_theme_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "--light-theme", "ocean"]).unwrap();
+    cli.validate().unwrap();
+    let light = cli.create_light_map().unwrap().unwrap();
+    assert_eq!((light.theme_name(), light.source().name()), ("ocean", "perlin"));
+
+    let cli = Cli::try_parse_from(["chromacat", "--light-theme", "ocean", "--light-source", "mask"])
+        .unwrap();
+    assert!(cli.create_light_map().unwrap().unwrap().uses_mask());
+    assert!(Cli::try_parse_from(["chromacat"]).unwrap().create_light_map().unwrap().is_none());
+
+    assert!(Cli::try_parse_from(["chromacat", "--light-source", "mask"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--light-theme", "ocean", "--light-source", "x"]).is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--light-theme", "no-such-theme"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_screensaver_flags() {
+    use chromacat::screensaver::is_wake_event;
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let cli = Cli::try_parse_from(["chromacat", "--screensaver", "--idle", "300", "--lock-cmd", "vlock"])
+        .unwrap();
+    assert!(cli.screensaver);
+    assert_eq!(cli.idle, Some(300));
+    assert_eq!(cli.lock_cmd.as_deref(), Some("vlock"));
+
+    // Idle and lock settings only mean something to the screen saver
+    assert!(Cli::try_parse_from(["chromacat", "--idle", "300"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--lock-cmd", "vlock"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--screensaver", "--inline"]).is_err());
+
+    // Keys wake it, resizing the window doesn't
+    let key = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+    assert!(is_wake_event(&Event::Key(key)));
+    assert!(!is_wake_event(&Event::Resize(80, 24)));
+    assert!(!is_wake_event(&Event::FocusLost));
+}
+
+#[test]
+fn test_diff_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "--diff"]).unwrap();
+    assert!(cli.diff);
+    assert!(cli.create_diff_palettes().unwrap().is_some());
+    assert!(Cli::try_parse_from(["chromacat"]).unwrap().create_diff_palettes().unwrap().is_none());
+
+    // Rules only mean something when coloring a diff
+    assert!(Cli::try_parse_from(["chromacat", "--diff-rules", "rules.yaml"]).is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--diff", "--diff-rules", "/nonexistent/rules.yaml"]).unwrap();
+    assert!(cli.create_diff_palettes().is_err());
+}
+
+#[test]
+fn test_columns_flags() {
+    use chromacat::columns::ColumnDelimiter;
+
+    let cli = Cli::try_parse_from(["chromacat", "--columns"]).unwrap();
+    assert_eq!(cli.columns, Some(ColumnDelimiter::Auto));
+    assert!(cli.create_columns().unwrap().is_some());
+    let cli = Cli::try_parse_from(["chromacat", "--columns=tab", "--column-themes", "ocean,fire"]).unwrap();
+    assert_eq!(cli.columns, Some(ColumnDelimiter::Tab));
+    assert_eq!(cli.column_themes, ["ocean", "fire"]);
+    assert!(Cli::try_parse_from(["chromacat"]).unwrap().create_columns().unwrap().is_none());
+
+    // A delimiter must be attached, so a file after the flag stays a file
+    let cli = Cli::try_parse_from(["chromacat", "--columns", "data.csv"]).unwrap();
+    assert_eq!(cli.columns, Some(ColumnDelimiter::Auto));
+    assert_eq!(cli.files, vec![PathBuf::from("data.csv")]);
+
+    assert!(Cli::try_parse_from(["chromacat", "--column-themes", "ocean"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--columns", "--diff"]).is_err());
+    let cli = Cli::try_parse_from(["chromacat", "--columns", "--column-themes", "nope"]).unwrap();
+    assert!(cli.validate().is_err());
+}
+
+#[test]
+fn test_motd_command() {
+    use chromacat::cli::Command;
+
+    let cli = Cli::try_parse_from(["chromacat", "-t", "ocean", "motd", "--template", "banner.tmpl"])
+        .unwrap();
+    match cli.command {
+        Some(Command::Motd { template, width }) => {
+            assert_eq!(template, Some(PathBuf::from("banner.tmpl")));
+            assert_eq!(width, chromacat::motd::DEFAULT_MOTD_WIDTH);
+        }
+        other => panic!("expected motd, got {:?}", other),
+    }
+    assert!(Cli::try_parse_from(["chromacat", "motd", "--width", "wide"]).is_err());
+
+    // The time offset picks the frame the banner shows
+    let cli = Cli::try_parse_args(["chromacat", "--time-offset", "3", "motd"]).unwrap();
+    assert!(cli.validate().is_ok());
+}
+
+#[test]
+fn test_gutter_flags() {
+    use chromacat::renderer::Gutter;
+
+    let cli = Cli::try_parse_from(["chromacat", "--line-numbers", "--ruler"]).unwrap();
+    assert_eq!(cli.create_animation_config().gutter, Gutter::new(true, true));
+    let cli = Cli::try_parse_from(["chromacat"]).unwrap();
+    assert_eq!(cli.create_animation_config().gutter, Gutter::default());
+
+    assert!(Cli::try_parse_from(["chromacat", "--line-numbers", "--status-line", "40"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--ruler", "--inline"]).is_err());
+}
+
+#[test]
+fn test_pager_flags() {
+    let cli = Cli::try_parse_from(["chromacat", "--pager"]).unwrap();
+    assert!(cli.text.pager);
+    let cli = Cli::try_parse_from(["chromacat", "--no-pager"]).unwrap();
+    assert!(cli.text.no_pager && !cli.text.pager);
+
+    assert!(Cli::try_parse_from(["chromacat", "--pager", "--no-pager"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--pager", "--inline"]).is_err());
+    assert!(Cli::try_parse_from(["chromacat", "--pager", "--status-line", "40"]).is_err());
+}
+
+#[test]
+fn test_mode_subcommands_expand_to_flags() {
+    use chromacat::cli::Command;
+    use chromacat::renderer::ScreenshotTarget;
+
+    let parse = |args: &[&str]| Cli::try_parse_args(args).unwrap();
+
+    let cli = parse(&["chromacat", "-t", "ocean", "cat", "a.txt", "b.txt"]);
+    assert!(cli.command.is_none());
+    assert_eq!(cli.files, vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+    assert_eq!(cli.theme, "ocean");
+
+    let cli = parse(&["chromacat", "demo", "logo"]);
+    assert!(cli.demo && cli.animation.animate);
+    assert_eq!(cli.art.name.as_deref(), Some("logo"));
+    assert!(cli.validate().is_ok());
+    let cli = parse(&["chromacat", "demo", "--still"]);
+    assert!(cli.demo && !cli.animation.animate && cli.art.name.is_none());
+
+    let cli = parse(&["chromacat", "play", "show.yaml"]);
+    assert!(cli.animation.animate);
+    assert_eq!(cli.playlist.file, Some(PathBuf::from("show.yaml")));
+
+    let cli = parse(&["chromacat", "export", "out.png", "in.txt"]);
+    assert_eq!(cli.screenshot, Some(ScreenshotTarget::File("out.png".into())));
+    assert_eq!(cli.files, vec![PathBuf::from("in.txt")]);
+
+    assert!(parse(&["chromacat", "patterns", "params"]).pattern_help);
+
+    // Listing and the other subcommands stay as they are
+    let cli = parse(&["chromacat", "themes", "list", "--category", "space"]);
+    assert!(matches!(cli.command, Some(Command::Themes { .. })));
+    let cli = parse(&["chromacat", "ctl", "pause"]);
+    assert!(matches!(cli.command, Some(Command::Ctl { .. })));
+
+    // The bare form is unchanged
+    let cli = parse(&["chromacat", "-a", "notes.txt"]);
+    assert!(cli.command.is_none() && cli.animation.animate);
+    assert_eq!(cli.files, vec![PathBuf::from("notes.txt")]);
+}
+
+#[test]
+fn test_subcommand_options() {
+    use clap::CommandFactory;
+    Cli::command().debug_assert();
+
+    let parse = |args: &[&str]| Cli::try_parse_args(args).unwrap();
+
+    // Shared options go anywhere, mode options after their subcommand
+    let cli = parse(&["chromacat", "demo", "aquarium", "--headless"]);
+    assert!(cli.demo && cli.headless);
+    assert_eq!(cli.art.name.as_deref(), Some("aquarium"));
+    let cli = parse(&["chromacat", "-t", "ocean", "play", "x.yaml", "--fps", "60"]);
+    assert_eq!(cli.playlist.file, Some(PathBuf::from("x.yaml")));
+    assert_eq!(cli.theme, "ocean");
+    assert_eq!(cli.animation.fps, 60);
+    let cli = parse(&["chromacat", "cat", "a.txt", "--duration", "5", "--pager"]);
+    assert_eq!(cli.files, vec![PathBuf::from("a.txt")]);
+    assert_eq!(cli.animation.duration, 5);
+    assert!(cli.text.pager);
+    let cli = parse(&["chromacat", "daemon", "--art", "matrix", "--random"]);
+    assert_eq!(cli.art.name.as_deref(), Some("matrix"));
+    assert!(cli.demo && cli.playlist.random);
+
+    // Mode options before a subcommand, or on one they don't apply to, are
+    // rejected rather than dropped
+    for args in [
+        vec!["chromacat", "--fps", "60", "demo"],
+        vec!["chromacat", "--playlist", "x.yaml", "daemon"],
+        vec!["chromacat", "--screenshot", "export", "out.png"],
+        vec!["chromacat", "themes", "list", "--playlist", "x.yaml", "--art", "matrix"],
+        vec!["chromacat", "progress", "--interlace=2"],
+        vec!["chromacat", "play", "x.yaml", "--playlist", "y.yaml"],
+        vec!["chromacat", "demo", "logo", "--art", "matrix"],
+    ] {
+        assert!(Cli::try_parse_args(&args).is_err(), "{:?} parsed", args);
+    }
+    let mut command = Cli::command();
+    command.build();
+    let help = command
+        .find_subcommand_mut("progress")
+        .unwrap()
+        .render_long_help()
+        .to_string();
+    assert!(help.contains("--theme"));
+    for flag in ["--playlist", "--art", "--interlace", "--pager"] {
+        assert!(!help.contains(flag), "progress --help lists {}", flag);
+    }
+
+    // Subcommand options keep their own meaning
+    let cli = parse(&["chromacat", "themes", "list", "--filter", "sea"]);
+    assert!(cli.list_filter.is_none());
+    let cli = parse(&["chromacat", "playlist", "from-cli", "-p plasma", "--duration", "12"]);
+    assert_eq!(cli.animation.duration, 0);
+}
@@ -0,0 +1,63 @@
+use chromacat::columns::{ColumnDelimiter, ColumnTracker, Columns};
+use chromacat::themes;
+use std::str::FromStr;
+
+#[test]
+fn test_detect_delimiter() {
+    use ColumnDelimiter::*;
+    assert_eq!(ColumnDelimiter::detect("a\tb\tc\n1\t2\t3\n"), Tab);
+    assert_eq!(ColumnDelimiter::detect("name,age\n\"Smith, J\",42\n"), Comma);
+    assert_eq!(ColumnDelimiter::detect("a;b\n1;2\n"), Semicolon);
+    assert_eq!(ColumnDelimiter::detect("| a | b |\n| 1 | 2 |\n"), Pipe);
+    // Commas that don't line up across lines are prose, not fields
+    assert_eq!(ColumnDelimiter::detect("USER  PID  COMMAND\nroot  1    init, then more\n"), Space);
+    // Colors don't hide delimiters or add semicolons
+    assert_eq!(ColumnDelimiter::detect("\x1b[1;31ma\x1b[0m,b\n1,2\n"), Comma);
+    assert_eq!(ColumnDelimiter::detect(""), Space);
+
+    assert_eq!(ColumnDelimiter::from_str("TAB").unwrap(), Tab);
+    assert!(ColumnDelimiter::from_str("colon").unwrap_err().contains("Valid delimiters"));
+}
+
+#[test]
+fn test_column_tracker() {
+    let columns = |delimiter, count, line: &str| {
+        let mut tracker = ColumnTracker::new(delimiter, count);
+        line.chars()
+            .map(|c| tracker.next(&c.to_string()).to_string())
+            .collect::<String>()
+    };
+
+    // Delimiters belong to the field before them, quoted ones don't split
+    assert_eq!(columns(ColumnDelimiter::Comma, 3, "ab,\"c,d\",e"), "0001111112");
+    // Leading padding belongs to the first field and gaps to the field
+    // before them; fields past the last column join it
+    assert_eq!(columns(ColumnDelimiter::Space, 3, "  a  bb c d"), "00000111222");
+    assert_eq!(columns(ColumnDelimiter::Tab, 2, "a\tb\tc"), "00111");
+}
+
+#[test]
+fn test_column_palettes() {
+    // The first line sets the number of columns, each a slice of the theme
+    let columns = Columns::new(ColumnDelimiter::Auto, &[]).unwrap();
+    let palettes = columns.palettes_for("\nUSER   PID  COMMAND\nroot     1  /sbin/init splash\n");
+    assert_eq!(palettes.delimiter(), ColumnDelimiter::Space);
+    assert_eq!(palettes.count(), 3);
+    assert_eq!(palettes.get(0).unwrap().range(), (0.0, 1.0 / 3.0));
+    assert_eq!(palettes.get(2).unwrap().range(), (2.0 / 3.0, 1.0));
+    assert!(palettes.get(0).unwrap().theme_name().is_none());
+    assert_eq!(
+        palettes.columns_of("\x1b[1mroot\x1b[0m 1 a b"),
+        vec![0, 0, 0, 0, 0, 1, 1, 2, 2, 2]
+    );
+
+    // Themes cycle across columns
+    let themes = [themes::get_theme("ocean").unwrap(), themes::get_theme("fire").unwrap()];
+    let columns = Columns::new(ColumnDelimiter::Comma, &themes).unwrap();
+    let palettes = columns.palettes_for("a,b,c\n");
+    let names: Vec<_> = (0..palettes.count())
+        .map(|i| palettes.get(i).unwrap().theme_name().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["ocean", "fire", "ocean"]);
+    assert_eq!(palettes.get(1).unwrap().range(), (0.0, 1.0));
+}
@@ -0,0 +1,114 @@
+#![cfg(unix)]
+
+use chromacat::control::{send_command, ControlCommand, ControlServer};
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_command_parsing() {
+    assert_eq!(
+        "set-theme ocean".parse::<ControlCommand>().unwrap(),
+        ControlCommand::SetTheme("ocean".to_string())
+    );
+    assert_eq!(
+        ControlCommand::from_args(&["load-playlist", "my shows/a.yaml"]).unwrap(),
+        ControlCommand::LoadPlaylist("my shows/a.yaml".into())
+    );
+    assert_eq!("  next-scene \n".parse::<ControlCommand>().unwrap(), ControlCommand::NextScene);
+
+    assert!("set-theme".parse::<ControlCommand>().is_err());
+    assert!("pause now".parse::<ControlCommand>().is_err());
+    assert!("rewind".parse::<ControlCommand>().is_err());
+    assert!("".parse::<ControlCommand>().is_err());
+
+    // Every command survives a round trip through its request line
+    for (usage, _) in ControlCommand::usage() {
+        let line = usage.replace("NAME", "ocean").replace("ID", "plasma").replace("FILE", "/tmp/a.yaml");
+        let command = line.parse::<ControlCommand>().unwrap();
+        assert_eq!(command.to_string().parse::<ControlCommand>().unwrap(), command);
+    }
+}
+
+#[test]
+fn test_absolutize_playlist_path() {
+    let command = ControlCommand::LoadPlaylist("show.yaml".into()).absolutize();
+    let ControlCommand::LoadPlaylist(path) = command else {
+        panic!("expected load-playlist");
+    };
+    assert!(path.is_absolute());
+    assert!(path.ends_with("show.yaml"));
+
+    assert_eq!(ControlCommand::Pause.absolutize(), ControlCommand::Pause);
+}
+
+/// Answers requests until `quit`, the way the render loop does
+fn serve(server: ControlServer) -> Vec<ControlCommand> {
+    let mut seen = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        let Some(request) = server.try_recv() else {
+            thread::sleep(Duration::from_millis(5));
+            continue;
+        };
+        let command = request.command.clone();
+        seen.push(command.clone());
+        match command {
+            ControlCommand::SetTheme(name) if name == "nope" => {
+                request.reply(Err("Invalid theme: nope".to_string()))
+            }
+            ControlCommand::Status => request.reply(Ok("pattern=plasma theme=ocean".to_string())),
+            ControlCommand::Quit => {
+                request.reply(Ok(String::new()));
+                break;
+            }
+            _ => request.reply(Ok(String::new())),
+        }
+    }
+    seen
+}
+
+#[test]
+fn test_server_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("control.sock");
+    let server = ControlServer::bind(&path).unwrap();
+    assert_eq!(server.path(), path.as_path());
+
+    // A second daemon may not take over a live socket
+    assert!(ControlServer::bind(&path).is_err());
+
+    let handle = thread::spawn(move || serve(server));
+
+    assert_eq!(send_command(&path, &ControlCommand::SetTheme("ocean".into())).unwrap(), "");
+    assert_eq!(
+        send_command(&path, &ControlCommand::Status).unwrap(),
+        "pattern=plasma theme=ocean"
+    );
+    let err = send_command(&path, &ControlCommand::SetTheme("nope".into())).unwrap_err();
+    assert!(err.to_string().contains("Invalid theme: nope"));
+    send_command(&path, &ControlCommand::Quit).unwrap();
+
+    let seen = handle.join().unwrap();
+    assert_eq!(seen.len(), 4);
+
+    // The socket is removed once the server is dropped
+    assert!(!path.exists());
+    assert!(send_command(&path, &ControlCommand::Status).is_err());
+}
+
+#[test]
+fn test_stale_socket_is_replaced() {
+    let dir = tempfile::tempdir().unwrap();
+    let path: PathBuf = dir.path().join("stale.sock");
+
+    // A socket file nobody listens on, as left by a crashed daemon
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let server = ControlServer::bind(&path).unwrap();
+    assert!(path.exists());
+    drop(server);
+    assert!(!path.exists());
+}
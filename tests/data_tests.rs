@@ -0,0 +1,69 @@
+use chromacat::columns::{field, ColumnDelimiter};
+use chromacat::data::{parse_data_range, parse_number, DataColumn};
+
+#[test]
+fn test_parse_numbers() {
+    assert_eq!(parse_number("42"), Some(42.0));
+    assert_eq!(parse_number(" -3.5 "), Some(-3.5));
+    assert_eq!(parse_number("45ms"), Some(45.0));
+    assert_eq!(parse_number("12.5%"), Some(12.5));
+    assert_eq!(parse_number("1e3"), Some(1000.0));
+    // A trailing exponent marker isn't part of the number
+    assert_eq!(parse_number("7e"), Some(7.0));
+    assert_eq!(parse_number("n/a"), None);
+    assert_eq!(parse_number(""), None);
+    assert_eq!(parse_number("inf"), None);
+
+    assert_eq!(parse_data_range("0,100"), Ok((0.0, 100.0)));
+    assert_eq!(parse_data_range(" -1 , 1 "), Ok((-1.0, 1.0)));
+    assert!(parse_data_range("5,1").is_err());
+    assert!(parse_data_range("5").is_err());
+    assert!(parse_data_range("a,b").is_err());
+}
+
+#[test]
+fn test_fields() {
+    assert_eq!(
+        field("a,\"b,c\",d", ColumnDelimiter::Comma, 1).as_deref(),
+        Some("b,c")
+    );
+    assert_eq!(field("a,b", ColumnDelimiter::Comma, 2), None);
+    assert_eq!(
+        field("  GET  /index  200  13ms", ColumnDelimiter::Space, 3).as_deref(),
+        Some("13ms")
+    );
+    assert_eq!(
+        field("a\t\tc", ColumnDelimiter::Tab, 1).as_deref(),
+        Some("")
+    );
+}
+
+#[test]
+fn test_data_scaling() {
+    // The lowest and highest values found are the ends of the scale
+    let data = DataColumn::new(2, None);
+    let text = "host,latency\na,10ms\nb,\x1b[31m55ms\x1b[0m\nc,100ms\nd,n/a\n";
+    let mut reader = data.reader_for(text);
+    assert_eq!(reader.delimiter(), ColumnDelimiter::Comma);
+    assert_eq!(reader.scale(), Some((10.0, 100.0)));
+    assert_eq!(reader.value("a,10ms"), Some(0.0));
+    assert_eq!(reader.value("b,55ms"), Some(0.5));
+    assert_eq!(reader.value("c,100ms"), Some(1.0));
+    assert_eq!(reader.value("host,latency"), None);
+    assert_eq!(reader.value("d,n/a"), None);
+
+    // New values widen a scale that was found
+    assert_eq!(reader.value("e,190ms"), Some(1.0));
+    assert_eq!(reader.scale(), Some((10.0, 190.0)));
+
+    // A given range stays put, and values outside it are clamped
+    let mut reader = DataColumn::new(1, Some((0.0, 10.0))).reader_for("5\n");
+    assert_eq!(reader.value("5"), Some(0.5));
+    assert_eq!(reader.value("20"), Some(1.0));
+    assert_eq!(reader.scale(), Some((0.0, 10.0)));
+
+    // A single value sits in the middle of the gradient
+    let mut reader = DataColumn::new(1, None).reader_for("");
+    assert_eq!(reader.scale(), None);
+    assert_eq!(reader.value("3"), Some(0.5));
+}
@@ -24,3 +24,349 @@ fn test_art_settings() {
     assert!(settings.include_headers);
     assert_eq!(settings.seed, 42);
 }
+
+#[test]
+fn test_generate_at_is_deterministic() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(80, 24));
+    for art in DemoArt::all_types() {
+        let first = generator.generate_at(*art, 1.5);
+        let second = generator.generate_at(*art, 1.5);
+        assert_eq!(first, second, "{} differs for the same time", art);
+    }
+}
+
+#[test]
+fn test_animated_art_changes_over_time() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(80, 24));
+    for art in DemoArt::all_types().iter().filter(|a| a.is_animated()) {
+        let start = generator.generate_at(*art, 0.0);
+        let later = generator.generate_at(*art, 1.75);
+        assert_ne!(start, later, "{} does not animate", art);
+
+        // Frames keep the same shape so they can replace each other in place
+        assert_eq!(later.lines().count(), 24, "{} changed height", art);
+        assert!(later.lines().all(|l| l.chars().count() == 80));
+    }
+
+    let code = generator.generate_at(DemoArt::Code, 0.0);
+    assert_eq!(code, generator.generate_at(DemoArt::Code, 10.0));
+}
+
+#[test]
+fn test_matrix_rain_falls() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(40, 30));
+    let grid = |frame: String| -> Vec<Vec<bool>> {
+        frame
+            .lines()
+            .map(|l| l.chars().map(|c| c != ' ').collect())
+            .collect()
+    };
+
+    // The lowest lit row of a column moves down as time advances
+    let lowest = |g: &[Vec<bool>], x: usize| g.iter().rposition(|row| row[x]);
+    let before = grid(generator.generate_at(DemoArt::Matrix, 0.0));
+    let after = grid(generator.generate_at(DemoArt::Matrix, 0.1));
+    let fell = (0..40).any(|x| match (lowest(&before, x), lowest(&after, x)) {
+        (Some(a), Some(b)) => b > a,
+        _ => false,
+    });
+    assert!(fell);
+}
+
+#[test]
+fn test_aquarium_art() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    assert_eq!(demo::parse_art("aquarium").unwrap(), DemoArt::Aquarium);
+    assert!(DemoArt::all_types().contains(&DemoArt::Aquarium));
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(40, 10));
+    let frame = generator.generate_at(DemoArt::Aquarium, 2.0);
+    let lines: Vec<&str> = frame.lines().collect();
+
+    assert_eq!(lines.len(), 10);
+    assert!(lines.iter().all(|l| l.chars().count() == 40));
+    assert!(lines[0].chars().all(|c| "~≈-".contains(c)));
+    assert!(lines[1..9].iter().any(|l| l.contains('>') || l.contains('<')));
+}
+
+#[test]
+fn test_cityscape_rain() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    let settings = ArtSettings::new(80, 24);
+    let dry = DemoArtGenerator::new(settings.clone()).generate_at(DemoArt::Cityscape, 1.0);
+    assert!(!dry.contains('│'));
+
+    let mut generator = DemoArtGenerator::new(settings.with_rain(true));
+    let wet = generator.generate_at(DemoArt::Cityscape, 1.0);
+    assert!(wet.contains('│'));
+    assert_ne!(wet, generator.generate_at(DemoArt::Cityscape, 1.2));
+}
+
+#[test]
+fn test_parse_countdown() {
+    use std::time::Duration;
+
+    assert_eq!(demo::parse_countdown("90").unwrap(), Duration::from_secs(90));
+    assert_eq!(demo::parse_countdown("45s").unwrap(), Duration::from_secs(45));
+    assert_eq!(demo::parse_countdown("10m").unwrap(), Duration::from_secs(600));
+    assert_eq!(demo::parse_countdown("1h30m").unwrap(), Duration::from_secs(5400));
+    assert_eq!(demo::parse_countdown("2H5S").unwrap(), Duration::from_secs(7205));
+
+    for invalid in ["", "0", "0m", "10x", "m", "5m3", "-5"] {
+        assert!(demo::parse_countdown(invalid).is_err(), "{:?} accepted", invalid);
+    }
+}
+
+#[test]
+fn test_clock_and_countdown_art() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+    use std::time::Duration;
+
+    let mut clock = DemoArtGenerator::new(ArtSettings::new(80, 24));
+    let frame = clock.generate_at(DemoArt::Clock, 0.0);
+    assert_eq!(frame.lines().count(), 24);
+    assert!(frame.lines().all(|l| l.chars().count() == 80));
+    assert!(frame.contains('█'));
+    let weekdays = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+    assert!(weekdays.iter().any(|d| frame.contains(d)));
+
+    let settings = ArtSettings::new(80, 24).with_countdown(Some(Duration::from_secs(600)));
+    let mut countdown = DemoArtGenerator::new(settings);
+    let start = countdown.generate_at(DemoArt::Clock, 0.2);
+    assert!(start.contains("until"));
+
+    // The display only changes once per second
+    assert_eq!(start, countdown.generate_at(DemoArt::Clock, 0.9));
+    assert_ne!(start, countdown.generate_at(DemoArt::Clock, 1.5));
+
+    // Time's up: the caption changes and the digits blink
+    let done = countdown.generate_at(DemoArt::Clock, 600.0);
+    assert!(done.contains("time's up!"));
+    assert!(done.contains('█'));
+    assert!(!countdown.generate_at(DemoArt::Clock, 600.5).contains('█'));
+}
+
+#[test]
+fn test_sysmon_art() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    assert_eq!(demo::parse_art("sysmon").unwrap(), DemoArt::Sysmon);
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(80, 24));
+    let first = generator.generate_at(DemoArt::Sysmon, 0.0);
+    let lines: Vec<&str> = first.lines().collect();
+    assert_eq!(lines.len(), 24);
+    assert!(lines.iter().all(|l| l.chars().count() == 80));
+    for section in ["CPU", "MEMORY", "RAM", "LOAD"] {
+        assert!(first.contains(section), "missing {}", section);
+    }
+
+    // Regenerating at the same time reuses the sample
+    assert_eq!(first, generator.generate_at(DemoArt::Sysmon, 0.0));
+
+    // Each new sample scrolls into the sparkline on the bottom row
+    let samples = |frame: &str| frame.lines().last().unwrap().trim_start().chars().count();
+    let later = generator.generate_at(DemoArt::Sysmon, 1.0);
+    assert_eq!(samples(&first), 1);
+    assert_eq!(samples(&later), 2);
+}
+
+#[test]
+fn test_quote_layout() {
+    use chromacat::demo::quote::{frame, parse_quotes, wrap_text, BorderStyle};
+
+    let lines = wrap_text("the quick brown fox jumps over the lazy dog", 10);
+    assert_eq!(lines, ["the quick", "brown fox", "jumps over", "the lazy", "dog"]);
+    assert_eq!(wrap_text("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+
+    let framed = frame(&lines, 2, BorderStyle::Rounded);
+    assert_eq!(framed.len(), lines.len() + 4);
+    assert!(framed[0].starts_with('╭') && framed.last().unwrap().ends_with('╯'));
+    assert!(framed.iter().all(|l| l.chars().count() == 16));
+    assert!(frame(&lines, 2, BorderStyle::None).iter().all(|l| !l.contains('│')));
+
+    let quotes = parse_quotes("One\nline two\n— Author\n%\nTwo\n\nThree\n~ \n");
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(quotes[0].text, "One\nline two");
+    assert_eq!(quotes[0].author.as_deref(), Some("Author"));
+    assert_eq!(quotes[1].author, None);
+    assert_eq!(quotes[2].author, None);
+}
+
+#[test]
+fn test_quote_art() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator, Quote};
+
+    assert_eq!(demo::parse_art("quote").unwrap(), DemoArt::Quote);
+    assert!(!DemoArt::Quote.is_animated());
+
+    let settings = ArtSettings::new(80, 24).with_quotes(vec![Quote::new("Hello there", Some("Someone"))]);
+    let mut generator = DemoArtGenerator::new(settings.clone());
+    let art = generator.generate_at(DemoArt::Quote, 0.0);
+    let lines: Vec<&str> = art.lines().collect();
+    assert_eq!(lines.len(), 24);
+    assert!(lines.iter().all(|l| l.chars().count() == 80));
+    assert!(art.contains("Hello there") && art.contains("— Someone"));
+
+    // Centered horizontally and vertically inside a border
+    let top = lines.iter().position(|l| l.contains('╭')).unwrap();
+    let bottom = lines.iter().position(|l| l.contains('╰')).unwrap();
+    assert!(top.abs_diff(23 - bottom) <= 1);
+    let left = lines[top].find('╭').unwrap();
+    assert!(left.abs_diff(80 - lines[top].trim_end().chars().count()) <= 1);
+
+    // The same quote is kept for the life of a generator
+    assert_eq!(art, generator.generate_at(DemoArt::Quote, 5.0));
+
+    let mut plain = DemoArtGenerator::new(settings.with_quote_border(false));
+    assert!(!plain.generate_at(DemoArt::Quote, 0.0).contains('╭'));
+}
+
+#[test]
+fn test_art_params() {
+    use chromacat::demo::{parse_art_params, DemoArt, DemoArtGenerator};
+
+    // Defaults come from the art's metadata
+    let settings = ArtSettings::new(80, 24);
+    assert_eq!(settings.number(DemoArt::Mandala, "symmetry"), 6.0);
+    assert!(!settings.flag(DemoArt::Cityscape, "rain"));
+    assert_eq!(settings.param(DemoArt::Quote, "border"), "rounded");
+
+    let params = parse_art_params(DemoArt::Quote, "border=heavy, padding=1").unwrap();
+    assert_eq!(params["border"], "heavy");
+    assert!(parse_art_params(DemoArt::Quote, "border").is_err());
+    assert!(parse_art_params(DemoArt::Logo, "density=1").is_err());
+    assert!(parse_art_params(DemoArt::All, "rain=true,symmetry=4").is_ok());
+
+    // Parameters change the generated art
+    let count = |art: &str| art.chars().filter(|c| *c == '1' || *c == '0').count();
+    let full = DemoArtGenerator::new(settings.clone()).generate_at(DemoArt::Matrix, 1.0);
+    let sparse = DemoArtGenerator::new(settings.clone().with_param("density", "0.2"))
+        .generate_at(DemoArt::Matrix, 1.0);
+    assert!(count(&sparse) < count(&full));
+
+    let mandala = DemoArtGenerator::new(settings.clone()).generate_at(DemoArt::Mandala, 1.0);
+    let petals = DemoArtGenerator::new(settings.clone().with_param("symmetry", "12"))
+        .generate_at(DemoArt::Mandala, 1.0);
+    assert_ne!(mandala, petals);
+
+    let quote = DemoArtGenerator::new(settings.with_param("border", "double"))
+        .generate_at(DemoArt::Quote, 0.0);
+    assert!(quote.contains('╔') && !quote.contains('╭'));
+}
+
+#[test]
+fn test_generator_resize() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(80, 24));
+    let quote = generator.generate_at(DemoArt::Quote, 0.0);
+    let words = |art: &str| {
+        art.split_whitespace()
+            .filter(|w| w.chars().all(char::is_alphanumeric))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    generator.resize(100, 30);
+    for art in [DemoArt::Matrix, DemoArt::Cityscape, DemoArt::Quote] {
+        let frame = generator.generate_at(art, 1.0);
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines.len(), 30, "{}", art);
+        assert!(lines.iter().all(|l| l.chars().count() == 100), "{}", art);
+    }
+
+    // The same quote is shown after resizing, reflowed for the new size
+    assert_eq!(words(&quote), words(&generator.generate_at(DemoArt::Quote, 0.0)));
+
+    // Sizes are clamped to the same minimums as new settings
+    generator.resize(10, 2);
+    assert_eq!(generator.generate_at(DemoArt::Waves, 0.0).lines().count(), 10);
+}
+
+#[test]
+fn test_art_transitions() {
+    use chromacat::demo::ArtTransition;
+
+    let from = "aaaa\naaaa\naaaa\naaaa";
+    let to = "bbbb\nbbbb\nbbbb\nbbbb";
+    let count = |frame: &str, c: char| frame.chars().filter(|&ch| ch == c).count();
+
+    for transition in ArtTransition::all() {
+        assert_eq!(transition.frame(from, to, 1.0), to);
+        assert_eq!(transition.as_str().parse::<ArtTransition>(), Ok(*transition));
+    }
+    assert_eq!(ArtTransition::Cut.frame(from, to, 0.1), to);
+
+    // A dissolve starts from the old art and flips scattered cells
+    assert_eq!(ArtTransition::Dissolve.frame(from, to, 0.0), from);
+    let half = ArtTransition::Dissolve.frame(from, to, 0.5);
+    assert!((3..=13).contains(&count(&half, 'b')), "{}", half);
+    let later = ArtTransition::Dissolve.frame(from, to, 0.8);
+    assert!(count(&later, 'b') >= count(&half, 'b'));
+
+    // A wipe replaces whole lines from the top
+    assert_eq!(
+        ArtTransition::Wipe.frame(from, to, 0.5),
+        "bbbb\nbbbb\naaaa\naaaa"
+    );
+
+    // A typewriter clears the old art and types the new art in order
+    assert_eq!(ArtTransition::Typewriter.frame(from, to, 0.375), "bbbb\nbb\n\n");
+
+    assert!("fade".parse::<ArtTransition>().is_err());
+}
+
+#[test]
+fn test_text_layout_helpers() {
+    use chromacat::demo::text;
+
+    // Wide glyphs count as two columns, and one straddling the edge is cut
+    assert_eq!(text::clip("日本語", 5), "日本");
+    assert_eq!(text::clip("abc", 0), "");
+    assert_eq!(text::pad("日本語", 5), "日本 ");
+    assert_eq!(text::center("✨ hi ✨", 10), " ✨ hi ✨ ");
+    assert_eq!(text::center("too wide", 3), "too");
+
+    // A block too tall for its space keeps its middle lines
+    let block = text::center_block(&["a", "b", "c", "d"], 3, 2);
+    assert_eq!(block, " b \n c \n");
+    let block = text::center_block(&["日本"], 6, 3);
+    assert_eq!(block, "      \n 日本 \n      \n");
+}
+
+#[test]
+fn test_centered_art_at_narrow_widths() {
+    use chromacat::demo::{DemoArt, DemoArtGenerator};
+    use unicode_width::UnicodeWidthStr;
+
+    let (width, height) = (demo::MIN_TERMINAL_WIDTH, demo::MIN_TERMINAL_HEIGHT);
+    let mut generator = DemoArtGenerator::new(ArtSettings::new(width, height));
+    for art in [
+        DemoArt::Code,
+        DemoArt::Logo,
+        DemoArt::Quote,
+        DemoArt::Clock,
+        DemoArt::Sysmon,
+    ] {
+        let frame = generator.generate_at(art, 0.0);
+        assert_eq!(frame.lines().count(), height as usize, "{}", art);
+        assert!(
+            frame.lines().all(|l| l.width() == width as usize),
+            "{} doesn't fill the width",
+            art
+        );
+    }
+
+    // Art wider than the terminal is clipped to it
+    let ascii = generator.generate_at(DemoArt::Ascii, 0.0);
+    assert!(ascii.lines().all(|l| l.width() <= width as usize));
+}
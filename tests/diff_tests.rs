@@ -0,0 +1,62 @@
+use chromacat::diff::{classify_lines, DiffClassifier, DiffRules, LineKind};
+use std::str::FromStr;
+
+const SAMPLE: &str = "\
+commit 1a2b3c
++ not part of a diff
+diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@ fn main()
+ fn main() {
+-    println!(\"old\");
++    println!(\"new\");
+ }
++ after the hunk";
+
+#[test]
+fn test_classify_lines() {
+    use LineKind::*;
+    assert_eq!(
+        classify_lines(SAMPLE),
+        vec![Other, Other, Header, Header, Header, Header, Hunk, Context, Removed, Added, Context, Other]
+    );
+
+    // Headers inside a hunk are its own lines once the counts say so
+    let mut classifier = DiffClassifier::new();
+    classifier.classify("@@ -1 +1 @@");
+    assert_eq!(classifier.classify("--- removed"), Removed);
+    assert_eq!(classifier.classify("+++ added"), Added);
+    assert_eq!(classifier.classify("\\ No newline at end of file"), Header);
+    assert_eq!(classifier.classify("+++ b/next.rs"), Header);
+
+    // Colors from `git diff --color` don't hide the markers
+    let mut classifier = DiffClassifier::new();
+    assert_eq!(classifier.classify("\x1b[36m@@ -1 +1 @@\x1b[m"), Hunk);
+    assert_eq!(classifier.classify("\x1b[32m+new\x1b[m"), Added);
+}
+
+#[test]
+fn test_diff_rules() {
+    let palettes = DiffRules::default().palettes().unwrap();
+    assert!(palettes.get(LineKind::Added).is_some());
+    assert!(palettes.get(LineKind::Context).is_some());
+    assert!(palettes.get(LineKind::Header).is_none());
+    assert!(palettes.get(LineKind::Other).is_none());
+    let lines = palettes.for_text(SAMPLE);
+    assert_eq!(lines.len(), 12);
+    assert!(lines[0].is_none() && lines[9].is_some());
+
+    // Rules left out keep their defaults
+    let rules = DiffRules::from_str("added:\n  theme: ocean\n  brightness: 1.2\n").unwrap();
+    assert_eq!(rules.added.theme.as_deref(), Some("ocean"));
+    assert!(rules.removed.theme.is_none() && !rules.removed.colors.is_empty());
+    let palettes = rules.palettes().unwrap();
+    assert_eq!(palettes.get(LineKind::Added).unwrap().theme_name(), Some("ocean"));
+
+    assert!(DiffRules::from_str("added:\n  theme: nope\n").is_err());
+    assert!(DiffRules::from_str("context:\n  brightness: 9.0\n").is_err());
+    assert!(DiffRules::from_str("added:\n  brightnes: 1.0\n").is_err());
+    assert!(DiffRules::from_str("moved: {}\n").is_err());
+}
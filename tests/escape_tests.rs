@@ -0,0 +1,88 @@
+use chromacat::escape::{strip_sgr, tokenize, Token};
+
+const LINK_OPEN: &str = "\x1b]8;;file:///tmp/notes.txt\x1b\\";
+const LINK_CLOSE: &str = "\x1b]8;;\x1b\\";
+
+#[test]
+fn test_plain_text_is_single_token() {
+    let tokens: Vec<_> = tokenize("hello world").collect();
+    assert_eq!(tokens, vec![Token::Text("hello world")]);
+}
+
+#[test]
+fn test_sgr_sequences_are_classified() {
+    let tokens: Vec<_> = tokenize("\x1b[1;31mred\x1b[0m").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Sgr("\x1b[1;31m"),
+            Token::Text("red"),
+            Token::Sgr("\x1b[0m"),
+        ]
+    );
+}
+
+#[test]
+fn test_osc8_hyperlink_passthrough() {
+    let input = format!("{}notes.txt{}", LINK_OPEN, LINK_CLOSE);
+    let tokens: Vec<_> = tokenize(&input).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Passthrough(LINK_OPEN),
+            Token::Text("notes.txt"),
+            Token::Passthrough(LINK_CLOSE),
+        ]
+    );
+}
+
+#[test]
+fn test_osc_terminated_by_bel() {
+    let tokens: Vec<_> = tokenize("\x1b]0;title\x07rest").collect();
+    assert_eq!(
+        tokens,
+        vec![Token::Passthrough("\x1b]0;title\x07"), Token::Text("rest")]
+    );
+}
+
+#[test]
+fn test_other_sequences_passthrough() {
+    let tokens: Vec<_> = tokenize("\x1b[2Kline\x1b7").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Passthrough("\x1b[2K"),
+            Token::Text("line"),
+            Token::Passthrough("\x1b7"),
+        ]
+    );
+}
+
+#[test]
+fn test_unterminated_sequence_consumes_rest() {
+    let tokens: Vec<_> = tokenize("text\x1b]8;;http://exa").collect();
+    assert_eq!(
+        tokens,
+        vec![Token::Text("text"), Token::Passthrough("\x1b]8;;http://exa")]
+    );
+}
+
+#[test]
+fn test_strip_sgr_keeps_hyperlinks() {
+    let input = format!("\x1b[34m{}dir{}\x1b[0m", LINK_OPEN, LINK_CLOSE);
+    assert_eq!(strip_sgr(&input), format!("{}dir{}", LINK_OPEN, LINK_CLOSE));
+}
+
+#[test]
+fn test_split_incomplete() {
+    use chromacat::escape::split_incomplete;
+
+    assert_eq!(split_incomplete("plain"), ("plain", ""));
+    assert_eq!(split_incomplete("ab\x1b[3"), ("ab", "\x1b[3"));
+    assert_eq!(split_incomplete("ab\x1b"), ("ab", "\x1b"));
+    assert_eq!(
+        split_incomplete("\x1b]8;;url\x1b\\text"),
+        ("\x1b]8;;url\x1b\\text", "")
+    );
+    assert_eq!(split_incomplete("x\x1b]0;title"), ("x", "\x1b]0;title"));
+}
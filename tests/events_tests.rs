@@ -0,0 +1,74 @@
+use chromacat::events::{self, Event, MAX_EVENTS};
+use log::Level;
+use std::time::Duration;
+
+fn event(message: String) -> Event {
+    Event {
+        elapsed: Duration::ZERO,
+        level: Level::Info,
+        target: "chromacat::test".to_string(),
+        message,
+    }
+}
+
+#[test]
+fn test_recent_events() {
+    for i in 0..MAX_EVENTS + 10 {
+        events::push(event(format!("event {}", i)));
+    }
+
+    // Only the latest events are kept, oldest first
+    let recent = events::recent(3);
+    let messages: Vec<&str> = recent.iter().map(|e| e.message.as_str()).collect();
+    let last = MAX_EVENTS + 9;
+    assert_eq!(
+        messages,
+        [
+            format!("event {}", last - 2),
+            format!("event {}", last - 1),
+            format!("event {}", last)
+        ]
+    );
+    assert_eq!(events::recent(usize::MAX).len(), MAX_EVENTS);
+}
+
+#[test]
+fn test_verbosity_levels() {
+    use chromacat::events::Verbosity;
+    use log::LevelFilter;
+
+    assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+    assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+    assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Debug);
+    // --quiet wins over -v
+    assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+
+    assert_eq!(Verbosity::Quiet.level(), LevelFilter::Error);
+    assert_eq!(Verbosity::Normal.level(), LevelFilter::Warn);
+    assert_eq!(Verbosity::Debug.level(), LevelFilter::Debug);
+}
+
+#[test]
+fn test_held_diagnostics() {
+    events::hold();
+    events::report(Level::Warn, "theme skipped");
+    events::report(Level::Error, "pattern failed");
+    assert_eq!(
+        events::release(),
+        ["Warning: theme skipped", "Error: pattern failed"]
+    );
+    assert!(events::release().is_empty());
+
+    let (warnings, errors) = events::counts();
+    assert!(warnings >= 1 && errors >= 1);
+
+    assert_eq!(events::summary(0, 0, None), None);
+    assert_eq!(
+        events::summary(1, 0, None).as_deref(),
+        Some("chromacat: 1 warning")
+    );
+    assert_eq!(
+        events::summary(2, 1, Some("run.log")).as_deref(),
+        Some("chromacat: 2 warnings, 1 error (see run.log)")
+    );
+}
@@ -0,0 +1,42 @@
+//! Integration tests for the pseudo-terminal command runner
+
+#![cfg(unix)]
+
+use chromacat::exec::CommandRunner;
+use chromacat::pattern::{CommonParams, HorizontalParams, PatternConfig, PatternParams};
+
+fn create_test_config() -> PatternConfig {
+    PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    }
+}
+
+fn runner(argv: &[&str]) -> CommandRunner {
+    let argv = argv.iter().map(|s| s.to_string()).collect();
+    CommandRunner::new(argv, create_test_config(), "rainbow").expect("Failed to create runner")
+}
+
+#[test]
+fn test_exec_reports_exit_code() {
+    let mut runner = runner(&["sh", "-c", "echo colorized; exit 3"]);
+    assert_eq!(runner.run().expect("Failed to run command"), 3);
+}
+
+#[test]
+fn test_exec_success() {
+    let mut runner = runner(&["true"]);
+    assert_eq!(runner.run().expect("Failed to run command"), 0);
+}
+
+#[test]
+fn test_exec_missing_command() {
+    let mut runner = runner(&["chromacat-definitely-missing-command"]);
+    assert!(runner.run().is_err());
+}
+
+#[test]
+fn test_exec_requires_command() {
+    assert!(CommandRunner::new(vec![], create_test_config(), "rainbow").is_err());
+}
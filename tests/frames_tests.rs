@@ -0,0 +1,66 @@
+use chromacat::frames::{parse_frame_size, FrameFormat, PatternFrame};
+use chromacat::pattern::{
+    CommonParams, HorizontalParams, PatternConfig, PatternEngine, PatternParams,
+};
+use chromacat::themes;
+
+fn engine(width: usize, height: usize) -> PatternEngine {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    };
+    let theme = themes::get_theme("rainbow").unwrap();
+    PatternEngine::from_theme(&theme, config, width, height).unwrap()
+}
+
+#[test]
+fn test_frame_sizes_and_formats() {
+    assert_eq!(parse_frame_size("32x8"), Ok((32, 8)));
+    assert_eq!(parse_frame_size("144X1"), Ok((144, 1)));
+    for bad in ["32", "0x8", "32x", "ax8", "70000x1"] {
+        assert!(parse_frame_size(bad).is_err(), "{} parsed", bad);
+    }
+
+    assert_eq!("JSON".parse::<FrameFormat>(), Ok(FrameFormat::Json));
+    assert!("png".parse::<FrameFormat>().is_err());
+}
+
+#[test]
+fn test_rgb_frames() {
+    let frame = PatternFrame::sample(&engine(4, 2)).unwrap();
+    assert_eq!(frame.cells.len(), 8);
+    // A horizontal gradient changes along a row but not down a column
+    assert_ne!(frame.cells[0], frame.cells[3]);
+    assert_eq!(frame.cells[0], frame.cells[4]);
+
+    let mut out = Vec::new();
+    FrameFormat::Rgb.write(&frame, 0, &mut out).unwrap();
+    assert_eq!(out.len(), 4 + 8 * 3);
+    assert_eq!(out[..4], [0, 4, 0, 2]);
+    assert_eq!(out[4..7], frame.cells[0]);
+}
+
+#[test]
+fn test_json_frames() {
+    let frame = PatternFrame {
+        width: 2,
+        height: 1,
+        time: 1.5,
+        cells: vec![[255, 0, 0], [0, 0, 255]],
+    };
+    let mut out = Vec::new();
+    FrameFormat::Json.write(&frame, 7, &mut out).unwrap();
+    let line = String::from_utf8(out).unwrap();
+    assert_eq!(
+        line,
+        "{\"frame\":7,\"time\":1.500,\"width\":2,\"height\":1,\"cells\":[[[255,0,0],[0,0,255]]]}\n"
+    );
+
+    let frame = PatternFrame::sample(&engine(3, 2)).unwrap();
+    let mut out = Vec::new();
+    FrameFormat::Json.write(&frame, 0, &mut out).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(value["cells"].as_array().unwrap().len(), 2);
+    assert_eq!(value["cells"][1].as_array().unwrap().len(), 3);
+}
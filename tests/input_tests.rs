@@ -0,0 +1,81 @@
+use chromacat::input::{hex_dump, is_binary, BinaryPolicy, DecodedInput};
+use std::io::{BufRead, Read};
+
+/// Decodes `bytes` with the given policy, returning the text and the warning
+fn decode(bytes: &[u8], policy: BinaryPolicy) -> (String, Option<String>) {
+    let mut input = DecodedInput::new(bytes, "test");
+    input.set_binary_policy(policy);
+    let mut text = String::new();
+    input.read_to_string(&mut text).unwrap();
+    (text, input.warning())
+}
+
+#[test]
+fn test_binary_policy_parsing() {
+    assert_eq!("skip".parse::<BinaryPolicy>(), Ok(BinaryPolicy::Skip));
+    assert_eq!("HEX".parse::<BinaryPolicy>(), Ok(BinaryPolicy::Hex));
+    assert!("drop".parse::<BinaryPolicy>().is_err());
+    for policy in BinaryPolicy::all() {
+        assert_eq!(policy.to_string().parse::<BinaryPolicy>(), Ok(*policy));
+    }
+}
+
+#[test]
+fn test_utf8_passes_through() {
+    let (text, warning) = decode(
+        "héllo 世界\n\x1b[1mbold\x1b[0m\n".as_bytes(),
+        BinaryPolicy::Show,
+    );
+    assert_eq!(text, "héllo 世界\n\x1b[1mbold\x1b[0m\n");
+    assert_eq!(warning, None);
+
+    // A byte order mark is dropped
+    let (text, warning) = decode(b"\xEF\xBB\xBFhello\n", BinaryPolicy::Show);
+    assert_eq!(text, "hello\n");
+    assert_eq!(warning, None);
+}
+
+#[test]
+fn test_invalid_utf8_is_decoded_lossily() {
+    // "café" in Latin-1, then a valid line
+    let (text, warning) = decode(b"caf\xe9\nok\n", BinaryPolicy::Show);
+    assert_eq!(text, "caf\u{fffd}\nok\n");
+    assert_eq!(
+        warning.as_deref(),
+        Some("test: 1 line was not valid UTF-8, invalid bytes replaced")
+    );
+}
+
+#[test]
+fn test_binary_chunks() {
+    assert!(is_binary(b"\x7fELF\x02\x01\x01\x00"));
+    assert!(!is_binary(b"tab\tseparated\r\n"));
+
+    let input = b"text\n\x00\x01\x02binary\ntext\n";
+    let (text, warning) = decode(input, BinaryPolicy::Skip);
+    assert_eq!(text, "text\ntext\n");
+    assert_eq!(warning.as_deref(), Some("test: 1 binary chunk skipped"));
+
+    let (text, _) = decode(input, BinaryPolicy::Hex);
+    assert_eq!(
+        text,
+        format!("text\n{}text\n", hex_dump(b"\x00\x01\x02binary\n", 5))
+    );
+    assert!(decode(input, BinaryPolicy::Show).0.contains("binary"));
+
+    assert_eq!(
+        hex_dump(b"\x7fELF", 16),
+        "00000010  7f 45 4c 46                                       |.ELF|\n"
+    );
+}
+
+#[test]
+fn test_long_lines_keep_characters_whole() {
+    // A line longer than one chunk, with characters across the boundary
+    let line = "é".repeat(40_000);
+    let mut input = DecodedInput::new(line.as_bytes(), "test");
+    let mut text = String::new();
+    while input.read_line(&mut text).unwrap() > 0 {}
+    assert_eq!(text, line);
+    assert_eq!(input.lossy_lines(), 0);
+}
@@ -0,0 +1,57 @@
+use chromacat::pattern::REGISTRY;
+use chromacat::renderer::{ParamSweep, SWEEP_SECONDS};
+
+#[test]
+fn test_sweeps_cover_numeric_params() {
+    for id in REGISTRY.list_patterns() {
+        let metadata = REGISTRY.get_pattern(id).unwrap();
+        let base = metadata.params().default_value();
+
+        // Every swept value parses, from one parameter to the next
+        let mut sweep = ParamSweep::next(id, &base, None);
+        while let Some(mut current) = sweep {
+            let (min, max) = current.range();
+            for _ in 0..8 {
+                let value = current.value();
+                assert!((min..=max).contains(&value), "{}.{}", id, current.name());
+                let params = current.params();
+                assert!(
+                    REGISTRY.parse_params(id, &params).is_ok(),
+                    "{}: {}",
+                    id,
+                    params
+                );
+                current.advance(SWEEP_SECONDS / 8.0);
+            }
+            assert!(current.is_finished());
+            assert_eq!(current.base(), base);
+            sweep = ParamSweep::next(id, &base, Some(current.name()));
+        }
+    }
+}
+
+#[test]
+fn test_sweep_eases_across_range() {
+    let base = REGISTRY
+        .get_pattern("wave")
+        .unwrap()
+        .params()
+        .default_value();
+    let mut sweep = ParamSweep::next("wave", &base, None).unwrap();
+    assert_eq!(sweep.name(), "amplitude");
+    let (min, max) = sweep.range();
+
+    // Bottom of the range, the top halfway through, and back down
+    assert_eq!(sweep.value(), min);
+    assert!(sweep.advance(SWEEP_SECONDS / 2.0));
+    assert!((sweep.value() - max).abs() < 1e-9);
+    assert!(sweep
+        .params()
+        .contains(&format!("amplitude={}", sweep.value())));
+    assert!(!sweep.advance(SWEEP_SECONDS / 2.0));
+    assert!((sweep.value() - min).abs() < 1e-9);
+
+    // Unknown parameters and patterns have nothing to sweep
+    assert!(ParamSweep::next("wave", &base, Some("unknown")).is_none());
+    assert!(ParamSweep::next("nope", &base, None).is_none());
+}
@@ -0,0 +1,54 @@
+use chromacat::introspect::{ParamKind, ParamValue};
+use chromacat::pattern::REGISTRY;
+
+#[test]
+fn test_introspection_covers_registry() {
+    let info = chromacat::introspect();
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.patterns.len(), REGISTRY.list_patterns().len());
+
+    for pattern in &info.patterns {
+        let metadata = REGISTRY.get_pattern(&pattern.id).unwrap();
+        assert_eq!(pattern.description, metadata.description);
+        assert_eq!(pattern.params.len(), metadata.params().sub_params().len());
+
+        // Defaults have the parameter's type and lie within its range
+        for param in &pattern.params {
+            match (&param.kind, &param.default) {
+                (ParamKind::Number { min, max }, ParamValue::Number(value)) => {
+                    assert!((min..=max).contains(&value), "{}.{}", pattern.id, param.name)
+                }
+                (ParamKind::Boolean, ParamValue::Boolean(_)) => {}
+                (ParamKind::Enum { options }, ParamValue::Text(value)) => {
+                    assert!(options.contains(value), "{}.{}", pattern.id, param.name)
+                }
+                other => panic!("{}.{} has mismatched default: {:?}", pattern.id, param.name, other),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_introspection_json() {
+    let json = serde_json::to_value(chromacat::introspect()).unwrap();
+    let wave = json["patterns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == "wave")
+        .unwrap();
+    let amplitude = wave["params"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "amplitude")
+        .unwrap();
+    assert_eq!(amplitude["type"], "number");
+    assert!(amplitude["min"].is_number());
+    assert!(amplitude["max"].is_number());
+    assert!(amplitude["default"].is_number());
+
+    let swell = &wave["presets"][0];
+    assert_eq!(swell["name"], "swell");
+    assert!(swell["params"]["amplitude"].is_number());
+}
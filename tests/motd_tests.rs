@@ -0,0 +1,51 @@
+use chromacat::motd::{fill_template, format_uptime, SystemInfo, DEFAULT_TEMPLATE, PLACEHOLDERS};
+use std::time::Duration;
+
+fn sample_info() -> SystemInfo {
+    SystemInfo {
+        hostname: "atlas".to_string(),
+        user: "sam".to_string(),
+        os: "Linux".to_string(),
+        kernel: "6.8.0".to_string(),
+        uptime: Duration::from_secs(((2 * 24 + 3) * 60 + 1) * 60),
+        load: (0.5, 0.25, 1.0),
+        last_login: None,
+        date: "Fri Oct 16 09:00 UTC".to_string(),
+    }
+}
+
+#[test]
+fn test_fill_template() {
+    let info = sample_info();
+    assert_eq!(
+        fill_template("{user}@{hostname}: {load}", &info).unwrap(),
+        "sam@atlas: 0.50 0.25 1.00"
+    );
+    assert_eq!(
+        fill_template("Last login: { last_login }", &info).unwrap(),
+        "Last login: never"
+    );
+    assert_eq!(fill_template("{{hostname}} }}", &info).unwrap(), "{hostname} }");
+
+    // Every placeholder has a value, and the built-in template only uses them
+    for name in PLACEHOLDERS {
+        assert!(info.value(name).is_some(), "{}", name);
+    }
+    let banner = fill_template(DEFAULT_TEMPLATE, &info).unwrap();
+    assert!(banner.contains("Welcome to atlas") && banner.contains("Up 2 days, 3 hours, 1 minute"));
+
+    let error = fill_template("{uptme}", &info).unwrap_err();
+    assert!(error.contains("Invalid placeholder '{uptme}'") && error.contains("uptime"));
+    assert!(fill_template("{hostname", &info).is_err());
+    assert!(fill_template("oops }", &info).is_err());
+}
+
+#[test]
+fn test_format_uptime() {
+    assert_eq!(format_uptime(Duration::from_secs(30)), "0 minutes");
+    assert_eq!(format_uptime(Duration::from_secs(61 * 60)), "1 hour, 1 minute");
+    assert_eq!(
+        format_uptime(Duration::from_secs(24 * 3600 + 120)),
+        "1 day, 0 hours, 2 minutes"
+    );
+}
@@ -1,4 +1,4 @@
-use chromacat::pattern::{PatternParam, CheckerboardParams};
+use chromacat::pattern::{CheckerboardParams, PatternParam, PatternParams, Patterns, REGISTRY};
 
 #[test]
 fn test_checkerboard_params_validation() {
@@ -45,3 +45,22 @@ fn test_checkerboard_params_defaults() {
     assert_eq!(params.rotation, 0.0);
     assert_eq!(params.scale, 1.0);
 }
+
+#[test]
+fn test_checkerboard_aspect_override() {
+    let corrected = Patterns::new(80, 40, 0.0, 0);
+    let mut plain = Patterns::new(80, 40, 0.0, 0);
+    plain.set_aspect_correction(false);
+
+    // A pattern turning aspect correction off draws as if it were off
+    let square = REGISTRY
+        .parse_params("checkerboard", "correct_aspect=false")
+        .unwrap();
+    let default = PatternParams::Checkerboard(CheckerboardParams::default());
+    for (x, y) in [(3, 5), (40, 20), (71, 33)] {
+        assert_eq!(
+            corrected.generate(x, y, &square),
+            plain.generate(x, y, &default)
+        );
+    }
+}
@@ -24,9 +24,13 @@ fn create_test_config() -> PatternConfig {
             speed: 1.0,
             correct_aspect: true,
             aspect_ratio: 0.5,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
             theme_name: Some("test".to_string()),
         },
         params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
     }
 }
 
@@ -129,3 +133,41 @@ fn test_pattern_determinism() {
         );
     }
 }
+
+#[test]
+fn test_perlin_seed() {
+    let perlin = |seed| {
+        let config = PatternConfig::new(PatternParams::Perlin(PerlinParams {
+            seed,
+            ..PerlinParams::default()
+        }));
+        PatternEngine::new(create_test_gradient(), config, 40, 20)
+    };
+    let values = |engine: &PatternEngine| -> Vec<f64> {
+        (0..40)
+            .map(|x| engine.get_value_at(x, x / 2).unwrap())
+            .collect()
+    };
+
+    // The seed parameter picks the noise
+    let first = perlin(1);
+    assert_eq!(first.seed(), 1);
+    assert!(first.uses_noise());
+    assert_eq!(values(&first), values(&perlin(1)));
+    assert_ne!(values(&first), values(&perlin(2)));
+
+    // Reseeding draws the other seed's noise and updates the parameter,
+    // and resizing or cloning keeps it
+    let mut engine = perlin(1);
+    engine.reseed(2);
+    assert_eq!(values(&engine), values(&perlin(2)));
+    match &engine.config().params {
+        PatternParams::Perlin(params) => assert_eq!(params.seed, 2),
+        params => panic!("unexpected params {:?}", params),
+    }
+    assert_eq!(values(&engine.recreate(40, 20)), values(&perlin(2)));
+    assert_eq!(values(&engine.clone()), values(&perlin(2)));
+
+    let plain = PatternEngine::new(create_test_gradient(), create_test_config(), 40, 20);
+    assert!(!plain.uses_noise());
+}
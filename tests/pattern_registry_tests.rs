@@ -172,3 +172,87 @@ fn test_invalid_pattern_handling() {
         .parse_params(invalid_pattern, "param=value")
         .is_err());
 }
+
+#[test]
+fn test_random_params_stay_in_range() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    for pattern in REGISTRY.list_patterns() {
+        for _ in 0..20 {
+            let values = REGISTRY.random_params(pattern, &mut rng).unwrap();
+            let params = values
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(",");
+            REGISTRY.validate_params(pattern, &params).unwrap();
+            REGISTRY.parse_params(pattern, &params).unwrap();
+        }
+    }
+
+    // Whole-number ranges get whole values
+    let values = REGISTRY.random_params("aurora", &mut rng).unwrap();
+    let (_, layers) = values.iter().find(|(name, _)| name == "layers").unwrap();
+    assert!(layers.parse::<u32>().is_ok(), "{}", layers);
+
+    assert!(REGISTRY.random_params("nonexistent", &mut rng).is_err());
+}
+
+#[test]
+fn test_presets_parse_for_their_patterns() {
+    for id in REGISTRY.list_patterns() {
+        let metadata = REGISTRY.get_pattern(id).unwrap();
+        for preset in metadata.presets() {
+            let params = preset.params_string();
+            assert!(
+                REGISTRY.parse_params(id, &params).is_ok(),
+                "preset {} of {} has invalid params: {}",
+                preset.name,
+                id,
+                params
+            );
+        }
+    }
+
+    assert!(REGISTRY.get_pattern("plasma").unwrap().presets().len() >= 3);
+    assert_eq!(REGISTRY.find_preset("plasma", "LAVA").unwrap().name, "lava");
+    assert!(REGISTRY.find_preset("plasma", "campfire").is_none());
+    assert!(REGISTRY.get_pattern("horizontal").unwrap().presets().is_empty());
+}
+
+#[test]
+fn test_aspect_override_params() {
+    // Every pattern takes the aspect parameters next to its own
+    for id in REGISTRY.list_patterns() {
+        REGISTRY
+            .validate_params(id, "correct_aspect=false,aspect_ratio=1.0")
+            .unwrap();
+    }
+    assert!(REGISTRY
+        .validate_params("spiral", "aspect_ratio=3.0")
+        .is_err());
+    assert!(REGISTRY
+        .validate_params("spiral", "correct_aspect=maybe")
+        .is_err());
+
+    let params = REGISTRY
+        .parse_params("checkerboard", "size=3,correct_aspect=false")
+        .unwrap();
+    assert_eq!(params.aspect().correct_aspect, Some(false));
+    assert_eq!(params.aspect().aspect_ratio, None);
+
+    // Only the settings that are set are formatted, and they parse back
+    let formatted = REGISTRY.format_params(&params);
+    assert!(
+        formatted.ends_with(",correct_aspect=false"),
+        "{}",
+        formatted
+    );
+    assert!(!formatted.contains("aspect_ratio"));
+    let reparsed = REGISTRY.parse_params("checkerboard", &formatted).unwrap();
+    assert_eq!(reparsed.aspect(), params.aspect());
+    let plain = REGISTRY.create_pattern_params("checkerboard").unwrap();
+    assert!(!REGISTRY.format_params(&plain).contains("aspect"));
+}
@@ -1,4 +1,4 @@
-use chromacat::pattern::{PatternParam, SpiralParams};
+use chromacat::pattern::{PatternParam, PatternParams, Patterns, SpiralParams};
 
 #[test]
 fn test_spiral_params_validation() {
@@ -44,3 +44,28 @@ fn test_spiral_params_defaults() {
     assert_eq!(params.clockwise, true);
     assert_eq!(params.frequency, 1.0);
 }
+
+#[test]
+fn test_spiral_center() {
+    let patterns = Patterns::new(100, 50, 1.5, 0);
+    let centered = PatternParams::Spiral(SpiralParams::default());
+    let moved = PatternParams::Spiral(SpiralParams {
+        center_x: 0.7,
+        center_y: 0.4,
+        ..SpiralParams::default()
+    });
+
+    // Moving the center by a fraction of the area moves the whole spiral,
+    // horizontally scaled by the aspect correction
+    let (dx, dy) = (0.2 * 0.5, -0.1);
+    for (x, y) in [(0.05, 0.1), (0.1, -0.2), (-0.15, 0.3)] {
+        let expected = patterns.generate_at(x, y, &centered);
+        let actual = patterns.generate_at(x + dx, y + dy, &moved);
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "{} vs {}",
+            expected,
+            actual
+        );
+    }
+}
@@ -2,13 +2,14 @@
 //! Tests common behaviors and interactions between patterns and the engine.
 
 use chromacat::pattern::{
-    PatternConfig, PatternEngine, PatternParams,
+    BlendMode, LightMap, LightSource, PatternConfig, PatternLayer, PatternEngine, PatternParams,
     CheckerboardParams, DiagonalParams, DiamondParams, HorizontalParams,
-    PerlinParams, PlasmaParams, RippleParams, SpiralParams, WaveParams,
+    PatternSpace, PerlinParams, PlasmaParams, RippleParams, SpiralParams, WaveParams,
 };
 use colorgrad::{Color, Gradient};
 
 /// Mock gradient for testing
+#[derive(Clone)]
 struct MockGradient;
 
 impl Gradient for MockGradient {
@@ -137,3 +138,184 @@ fn test_pattern_determinism() {
         );
     }
 }
+
+#[test]
+fn test_pattern_space_conventions() {
+    let space = PatternSpace::new(80, 40);
+    assert_eq!(space.normalize(0.0, 0.0), (-0.5, -0.5));
+    assert_eq!(space.normalize(40.0, 20.0), (0.0, 0.0));
+    assert_eq!(space.normalize(60.0, 30.0), (0.25, 0.25));
+    assert_eq!(space.to_pixel(0.0, 0.0), (40, 20));
+    assert_eq!(space.to_pixel(0.5, 0.5), (79, 39));
+    assert_eq!(space.to_pixel(-0.7, -0.7), (0, 0));
+
+    // The horizontal axis is scaled by the character aspect ratio
+    assert_eq!(space.to_pattern(0.25, 0.25), (0.125, 0.25));
+    let uncorrected = space.with_aspect(false, 0.5);
+    assert_eq!(uncorrected.to_pattern(0.25, 0.25), (0.25, 0.25));
+    assert_eq!(space.with_aspect(true, 9.0).char_aspect_ratio(), 2.0);
+}
+
+#[test]
+fn test_aspect_settings_survive_updates() {
+    let test = PatternTest::new();
+    let mut config = PatternConfig::new(PatternParams::Spiral(SpiralParams::default()));
+    config.common.correct_aspect = false;
+    config.common.aspect_ratio = 0.8;
+    let mut engine = PatternEngine::new(create_test_gradient(), config, test.width, test.height);
+
+    let expected = PatternSpace::new(test.width, test.height).with_aspect(false, 0.8);
+    assert_eq!(engine.space(), expected);
+    let uncorrected = engine.get_value_at(10, 30).unwrap();
+
+    engine.update(0.0);
+    assert_eq!(engine.get_value_at(10, 30).unwrap(), uncorrected);
+    assert_eq!(engine.recreate(test.width, test.height).space(), expected);
+    assert_eq!(engine.clone().get_value_at(10, 30).unwrap(), uncorrected);
+
+    let mut config = engine.config().clone();
+    config.common.correct_aspect = true;
+    engine.update_pattern_config(config);
+    assert!(engine.space().corrects_aspect());
+    assert_ne!(engine.get_value_at(10, 30).unwrap(), uncorrected);
+}
+
+#[test]
+fn test_normalized_sampling_matches_pixels() {
+    let test = PatternTest::new();
+    let engine = test.create_engine(PatternParams::Plasma(PlasmaParams::default()));
+    let space = engine.space();
+
+    for (x, y) in [(0, 0), (13, 71), (50, 50), (99, 99)] {
+        let (nx, ny) = space.normalize(x as f64, y as f64);
+        assert_eq!(
+            engine.get_value_at_normalized(nx, ny).unwrap(),
+            engine.get_value_at(x, y).unwrap(),
+            "normalized and pixel sampling differ at ({}, {})",
+            x,
+            y
+        );
+    }
+
+    // Positions between pixels are sampled rather than snapped
+    let engine = test.create_engine(PatternParams::Horizontal(HorizontalParams::default()));
+    let (nx, ny) = space.normalize(13.5, 71.0);
+    let between = engine.get_value_at_normalized(nx, ny).unwrap();
+    let left = engine.get_value_at(13, 71).unwrap();
+    let right = engine.get_value_at(14, 71).unwrap();
+    assert!(
+        between > left.min(right) && between < left.max(right),
+        "{} should lie between {} and {}",
+        between,
+        left,
+        right
+    );
+}
+
+#[test]
+fn test_pattern_zoom_and_pan() {
+    let space = PatternSpace::new(100, 100).with_aspect(false, 0.5);
+    assert_eq!(space.with_view(2.0, 0.0, 0.0).to_pattern(0.25, -0.5), (0.125, -0.25));
+    assert_eq!(space.with_view(1.0, 0.1, -0.2).to_pattern(0.0, 0.0), (0.1, -0.2));
+    assert_eq!(space.with_view(50.0, 0.0, 0.0).zoom(), 10.0);
+
+    // Panning shows a point that was elsewhere at the center, for any pattern
+    let test = PatternTest::new();
+    let patterns = vec![
+        PatternParams::Diagonal(DiagonalParams::default()),
+        PatternParams::Plasma(PlasmaParams::default()),
+        PatternParams::Ripple(RippleParams::default()),
+    ];
+    for params in patterns {
+        let engine = test.create_engine(params.clone());
+        let offset_value = engine.get_value_at(75, 30).unwrap();
+
+        let mut config = PatternConfig::new(params.clone());
+        config.common.pan_x = 0.25;
+        config.common.pan_y = -0.2;
+        let panned = PatternEngine::new(create_test_gradient(), config, test.width, test.height);
+        assert_eq!(panned.get_value_at(50, 50).unwrap(), offset_value, "{:?}", params);
+    }
+}
+
+#[test]
+fn test_blend_modes() {
+    let cases = [
+        (BlendMode::Multiply, 0.5, 0.4, 0.2),
+        (BlendMode::Add, 0.7, 0.6, 1.0),
+        (BlendMode::Screen, 0.5, 0.5, 0.75),
+        (BlendMode::Min, 0.3, 0.8, 0.3),
+        (BlendMode::Max, 0.3, 0.8, 0.8),
+        (BlendMode::Mask, 0.3, 0.8, 0.3),
+        (BlendMode::Mask, 0.3, 0.2, 0.0),
+    ];
+    for (mode, below, layer, expected) in cases {
+        let value = mode.apply(below, layer);
+        assert!((value - expected).abs() < 1e-9, "{} gave {}", mode, value);
+    }
+    for mode in BlendMode::all() {
+        assert_eq!(mode.as_str().parse::<BlendMode>(), Ok(*mode));
+    }
+    assert!("overlay".parse::<BlendMode>().unwrap_err().contains("multiply, add"));
+
+    // Layers are evaluated at the same point and blended in order
+    let test = PatternTest::new();
+    let base = test.create_engine(PatternParams::Horizontal(HorizontalParams::default()));
+    let ripple = test.create_engine(PatternParams::Ripple(RippleParams::default()));
+    let config = PatternConfig::new(PatternParams::Horizontal(HorizontalParams::default()))
+        .with_layer(PatternLayer::new(
+            PatternParams::Ripple(RippleParams::default()),
+            BlendMode::Screen,
+        ))
+        .with_layer(PatternLayer::new(
+            PatternParams::Horizontal(HorizontalParams::default()),
+            BlendMode::Min,
+        ));
+    let layered = PatternEngine::new(create_test_gradient(), config, test.width, test.height);
+    for (x, y) in [(10, 20), (50, 50), (90, 5)] {
+        let below = base.get_value_at(x, y).unwrap();
+        let screened = BlendMode::Screen.apply(below, ripple.get_value_at(x, y).unwrap());
+        let expected = BlendMode::Min.apply(screened, below);
+        assert!((layered.get_value_at(x, y).unwrap() - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_light_map() {
+    let test = PatternTest::new();
+    let source: LightSource = "diagonal".parse().unwrap();
+    assert_eq!(source.name(), "diagonal");
+    assert_eq!("mask".parse::<LightSource>().unwrap().to_string(), "mask");
+    assert!("nope".parse::<LightSource>().unwrap_err().contains("mask, "));
+
+    // Lightness follows the light theme; hue stays with the main one
+    let theme = chromacat::themes::get_theme("monochrome").unwrap();
+    let light = LightMap::new(&theme, source).unwrap();
+    let gradient = theme.create_gradient().unwrap();
+    let red = colorgrad::Color::new(1.0, 0.0, 0.0, 1.0);
+    for value in [0.0, 0.3, 1.0] {
+        let shade = gradient.at(theme.position_at(value as f32, 0.0));
+        let lightness = (shade.r.max(shade.g).max(shade.b) + shade.r.min(shade.g).min(shade.b)) / 2.0;
+        let shaded = light.apply(red.clone(), value, 0.0);
+        let expected = if lightness <= 0.5 {
+            (lightness * 2.0, 0.0)
+        } else {
+            (1.0, lightness * 2.0 - 1.0)
+        };
+        assert!((shaded.r - expected.0).abs() < 1e-5, "{:?} at {}", shaded, value);
+        assert!((shaded.g - expected.1).abs() < 1e-5 && shaded.g == shaded.b);
+    }
+
+    // The engine samples the secondary pattern at the same point
+    let mut engine = test.create_engine(PatternParams::Horizontal(HorizontalParams::default()));
+    let diagonal = test.create_engine(PatternParams::Diagonal(DiagonalParams::default()));
+    assert_eq!(engine.light_at(30, 70), None);
+    assert_eq!(engine.color_with_light(0.4, Some(0.9)), engine.color_at(0.4));
+    engine.set_light_map(Some(light));
+    assert_eq!(engine.light_at(30, 70), Some(diagonal.get_value_at(30, 70).unwrap()));
+    assert_eq!(engine.clone().light_map().unwrap().theme_name(), "monochrome");
+
+    engine.set_light_map(Some(LightMap::new(&theme, LightSource::Mask).unwrap()));
+    assert_eq!(engine.light_at(30, 70), None);
+    assert_eq!(engine.glyph_light_at(30, 70), Some(1.0));
+}
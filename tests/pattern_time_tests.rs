@@ -16,9 +16,13 @@ fn test_time_consistency() {
             speed: 1.0,
             correct_aspect: true,
             aspect_ratio: 0.5,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
             theme_name: Some("test".to_string()),
         },
         params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
     };
 
     let mut engine = PatternEngine::new(Box::new(gradient), config, 100, 100);
@@ -77,9 +81,13 @@ fn test_consistent_animation_speed() {
             speed: 1.0,
             correct_aspect: true,
             aspect_ratio: 0.5,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
             theme_name: Some("test".to_string()),
         },
         params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
     };
 
     let mut engine = PatternEngine::new(Box::new(gradient), config, 100, 100);
@@ -149,3 +157,198 @@ fn test_consistent_animation_speed() {
         assert!(has_change, "Pattern values should change during animation");
     }
 }
+
+#[test]
+fn test_smoothing_averages_subframes() {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
+    };
+    let mut engine = PatternEngine::new(Box::new(greys()), config, 40, 20);
+    engine.set_time(2.0);
+    let sharp = engine.get_value_at(10, 10).unwrap();
+
+    // Four samples spread across the 0.1s before the current time
+    engine.set_smoothing(4, 0.1);
+    assert_eq!(engine.smoothing_samples(), 4);
+    let mut expected = 0.0;
+    let mut reference = engine.clone();
+    reference.set_smoothing(1, 0.0);
+    for i in 0..4 {
+        reference.set_time(2.0 - 0.1 * i as f64 / 4.0);
+        expected += reference.get_value_at(10, 10).unwrap();
+    }
+    let smooth = engine.get_value_at(10, 10).unwrap();
+    assert!((smooth - expected / 4.0).abs() < 1e-9);
+    assert!(smooth != sharp);
+
+    // Smoothing follows the engine through time changes and resizes
+    engine.update(0.5);
+    assert_eq!(engine.recreate(20, 10).smoothing_samples(), 4);
+    assert_eq!(engine.clone().smoothing_samples(), 4);
+
+    engine.set_smoothing(1, 0.1);
+    engine.set_time(2.0);
+    assert_eq!(engine.get_value_at(10, 10).unwrap(), sharp);
+}
+
+#[test]
+fn test_smoothing_follows_speed_multiplier() {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
+    };
+    let mut engine = PatternEngine::new(Box::new(greys()), config, 40, 20);
+    let mut reference = engine.clone();
+
+    // At double speed the shutter covers twice the animation time
+    engine.set_speed_multiplier(2.0);
+    engine.set_smoothing(4, 0.1);
+    engine.set_time(2.0);
+    let mut expected = 0.0;
+    for i in 0..4 {
+        reference.set_time(2.0 - 0.2 * i as f64 / 4.0);
+        expected += reference.get_value_at(10, 10).unwrap();
+    }
+    let smooth = engine.get_value_at(10, 10).unwrap();
+    assert!((smooth - expected / 4.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_animation_clock_accumulates_exactly() {
+    use chromacat::pattern::AnimationClock;
+    use std::time::Duration;
+
+    // Frames add up to exactly the wall time that passed
+    let mut clock = AnimationClock::new();
+    let frame = Duration::from_micros(16_667);
+    for _ in 0..600 {
+        clock.advance(frame);
+    }
+    assert_eq!(clock.elapsed(), frame * 600);
+    assert_eq!(clock.seconds(), 10.0002);
+
+    // Scaled steps carry their fractions instead of dropping them
+    let mut slow = AnimationClock::new();
+    slow.set_rate(1.0 / 3.0);
+    for _ in 0..3_000 {
+        slow.advance(Duration::from_nanos(1));
+    }
+    assert_eq!(slow.elapsed(), Duration::from_nanos(1_000));
+
+    // Pausing stops advances but not steps, which stop at zero
+    clock.set_paused(true);
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(clock.seconds(), 10.0002);
+    clock.step(-20.0);
+    assert_eq!(clock.seconds(), 0.0);
+
+    // Engines fed frames or one long step agree to the nanosecond, whatever
+    // the speed
+    let config = PatternConfig {
+        common: CommonParams {
+            speed: 0.7,
+            ..CommonParams::default()
+        },
+        params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
+    };
+    let mut engine = PatternEngine::new(Box::new(greys()), config.clone(), 10, 10);
+    let mut reference = PatternEngine::new(Box::new(greys()), config, 10, 10);
+    for _ in 0..600 {
+        engine.update(1.0 / 60.0);
+    }
+    reference.advance(Duration::from_secs_f64(1.0 / 60.0) * 600);
+    let (fed, stepped) = (engine.clock().elapsed(), reference.clock().elapsed());
+    assert!(fed.abs_diff(stepped) <= Duration::from_nanos(1));
+    assert!((engine.time() - 7.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_fixed_step_timing() {
+    use chromacat::pattern::FixedStep;
+    use std::time::Duration;
+
+    // Frame n lands at exactly n / fps, however the steps round
+    let mut step = FixedStep::new(30);
+    let mut total = Duration::ZERO;
+    for _ in 0..90 {
+        total += step.advance();
+    }
+    assert_eq!(step.frame(), 90);
+    assert_eq!(total, Duration::from_secs(3));
+    assert_eq!(step.wall_time(1), Duration::from_nanos(33_333_333));
+
+    // Engines stepped frame by frame agree with one long advance, whatever
+    // the speed
+    let config = PatternConfig {
+        common: CommonParams {
+            speed: 0.7,
+            ..CommonParams::default()
+        },
+        params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
+    };
+    let mut engine = PatternEngine::new(Box::new(greys()), config.clone(), 10, 10);
+    let mut reference = PatternEngine::new(Box::new(greys()), config, 10, 10);
+    let mut step = FixedStep::new(60);
+    for _ in 0..600 {
+        engine.advance(step.advance());
+    }
+    reference.advance(Duration::from_secs(10));
+    let (stepped, advanced) = (engine.clock().elapsed(), reference.clock().elapsed());
+    assert!(stepped.abs_diff(advanced) <= Duration::from_nanos(1));
+
+    // Snapping moves a time onto the nearest frame and steps on from there
+    let mut step = FixedStep::new(10);
+    assert!((step.snap(1.04, 1.0) - 1.0).abs() < 1e-9);
+    assert_eq!(step.frame(), 10);
+    assert!((step.snap(1.04, 2.0) - 1.0).abs() < 1e-9);
+    assert_eq!(step.frame(), 5);
+    step.advance();
+    assert_eq!(step.frame(), 6);
+}
+
+#[test]
+fn test_cycle_loop_modes() {
+    use chromacat::pattern::{AnimationClock, LoopMode};
+    use std::time::Duration;
+
+    let at = |mode: LoopMode, seconds: f64| {
+        let mut clock = AnimationClock::new();
+        clock.set_cycle(Some(Duration::from_secs(4)), mode);
+        clock.advance(Duration::from_secs_f64(seconds));
+        (clock.seconds(), clock.cycle_progress().unwrap())
+    };
+
+    assert_eq!(at(LoopMode::Loop, 1.0), (1.0, 0.25));
+    assert_eq!(at(LoopMode::Loop, 9.0), (1.0, 0.25));
+    assert_eq!(at(LoopMode::PingPong, 3.0), (3.0, 0.75));
+    assert_eq!(at(LoopMode::PingPong, 5.0), (3.0, 0.75));
+    assert_eq!(at(LoopMode::PingPong, 8.0), (0.0, 0.0));
+    assert_eq!(at(LoopMode::Once, 2.0), (2.0, 0.5));
+    assert_eq!(at(LoopMode::Once, 60.0), (4.0, 1.0));
+
+    // Without a cycle time runs on and there is no progress to report
+    let mut clock = AnimationClock::new();
+    clock.advance(Duration::from_secs(9));
+    assert_eq!(clock.seconds(), 9.0);
+    assert_eq!(clock.cycle_progress(), None);
+
+    // Engines report the time within the cycle, and keep accumulating
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Plasma(PlasmaParams::default()),
+        layers: Vec::new(),
+    };
+    let mut engine = PatternEngine::new(Box::new(greys()), config, 10, 10);
+    engine.set_cycle(Some(Duration::from_secs(2)), LoopMode::Loop);
+    for _ in 0..5 {
+        engine.update(0.5);
+    }
+    assert_eq!(engine.time(), 0.5);
+    assert_eq!(engine.clock().elapsed(), Duration::from_millis(2_500));
+    assert_eq!("ping-pong".parse::<LoopMode>(), Ok(LoopMode::PingPong));
+}
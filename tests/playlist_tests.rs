@@ -1,7 +1,9 @@
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
-use chromacat::playlist::{Playlist, PlaylistPlayer};
+use chromacat::playlist::{self, Playlist, PlaylistPlayer, PREVIEW_PATTERNS};
+use chromacat::renderer::Severity;
+use chromacat::themes;
 
 #[test]
 fn test_playlist_loading() {
@@ -72,6 +74,24 @@ entries:
       blend_mode: add
 "#;
     assert!(Playlist::from_str(yaml).is_ok());
+
+    // Every problem is reported at once, with the entry it is in
+    let yaml = r#"
+entries:
+  - pattern: plasma
+    theme: invalid_theme
+    duration: 30
+  - pattern: wave
+    theme: rainbow
+    duration: 30
+  - pattern: invalid_pattern
+    theme: rainbow
+    duration: 30
+"#;
+    let error = Playlist::from_str(yaml).unwrap_err().to_string();
+    assert!(error.contains("2 problems found"), "{}", error);
+    assert!(error.contains("line 4: entry 1:"), "{}", error);
+    assert!(error.contains("line 9: entry 3:"), "{}", error);
 }
 
 #[test]
@@ -142,4 +162,632 @@ entries:
     assert_eq!(player.current_index(), 2);
     player.previous_entry();
     assert_eq!(player.current_index(), 1);
+
+    // Seeking jumps to an entry and position, wrapping the index
+    player.seek(5, Duration::from_secs(10));
+    assert_eq!(player.current_index(), 2);
+    assert_eq!(player.elapsed(), Duration::from_secs(10));
+    assert!(player.update(Duration::from_secs(15)));
+    assert_eq!(player.current_index(), 0);
+}
+
+#[test]
+fn test_playlist_reloads_when_edited() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("show.yaml");
+    let write = |yaml: &str, seconds: u64| {
+        std::fs::write(&path, yaml).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    };
+    // A playlist of ten-second scenes
+    let show = |patterns: &[&str]| {
+        let mut yaml = "entries:\n".to_string();
+        for pattern in patterns {
+            yaml += &format!(
+                "  - pattern: {}\n    theme: rainbow\n    duration: 10\n",
+                pattern
+            );
+        }
+        yaml
+    };
+    write(&show(&["plasma", "wave"]), 1);
+
+    let mut player = PlaylistPlayer::new(Playlist::from_file(&path).unwrap());
+    player.watch(&path);
+    assert!(!player.reload_if_changed());
+
+    // Edits wait for the next scene change
+    write(&show(&["fire", "ripple"]), 2);
+    assert!(!player.update(Duration::from_secs(5)));
+    assert_eq!(player.current_entry().unwrap().pattern, "plasma");
+    assert!(player.update(Duration::from_secs(5)));
+    assert_eq!(player.current_entry().unwrap().pattern, "ripple");
+    assert_eq!(
+        player.take_notice().unwrap(),
+        ("Reloaded playlist: 2 scenes".to_string(), Severity::Info)
+    );
+
+    // An invalid file is reported once and the old playlist keeps playing
+    let invalid = "entries:\n  - pattern: nonexistent\n    theme: rainbow\n";
+    write(invalid, 3);
+    player.next_entry();
+    assert_eq!(player.current_entry().unwrap().pattern, "fire");
+    let (notice, severity) = player.take_notice().unwrap();
+    assert!(notice.starts_with("Playlist not reloaded: line 2"));
+    assert_eq!(severity, Severity::Warn);
+    player.next_entry();
+    assert!(player.take_notice().is_none());
+
+    // A shorter playlist starts over when the index no longer fits
+    write(&show(&["spiral"]), 4);
+    player.next_entry();
+    assert_eq!(player.current_index(), 0);
+    assert_eq!(player.current_entry().unwrap().pattern, "spiral");
+}
+
+#[test]
+fn test_demo_playlist_loads() {
+    use chromacat::demo::DemoArt;
+
+    let playlist = Playlist::from_file("docs/demo-playlist.yaml").unwrap();
+    let neon = playlist
+        .entries
+        .iter()
+        .find(|e| e.name == "Neon Dreams")
+        .expect("Neon Dreams scene");
+    assert_eq!(neon.art, Some(DemoArt::Cityscape));
+}
+
+#[test]
+fn test_playlist_art_params() {
+    use chromacat::demo::{ArtSettings, DemoArt};
+
+    let yaml = r#"
+entries:
+  - pattern: plasma
+    theme: rainbow
+    duration: 30
+    art: matrix
+    art_params:
+      density: 0.5
+"#;
+    let playlist = Playlist::from_str(yaml).unwrap();
+    let settings = playlist.entries[0]
+        .art_settings(&ArtSettings::default().with_param("speed", "2"))
+        .unwrap();
+    assert_eq!(settings.number(DemoArt::Matrix, "density"), 0.5);
+    assert_eq!(settings.number(DemoArt::Matrix, "speed"), 2.0);
+
+    // Parameters must belong to the entry's art and be in range
+    for art_params in ["symmetry: 8", "density: 5.0"] {
+        let yaml = format!(
+            "entries:\n  - pattern: plasma\n    theme: rainbow\n    duration: 30\n    art: matrix\n    art_params:\n      {}\n",
+            art_params
+        );
+        assert!(Playlist::from_str(&yaml).is_err(), "{} accepted", art_params);
+    }
+}
+
+#[test]
+fn test_theme_preview_playlists() {
+    // One theme runs through every preview pattern
+    let preview = playlist::theme_preview(Some("ocean"), 2).unwrap();
+    assert_eq!(preview.entries.len(), PREVIEW_PATTERNS.len());
+    assert!(preview.entries.iter().all(|e| e.theme == "ocean" && e.duration == 2));
+    assert!(preview.entries.iter().all(|e| e.validate().is_ok()));
+
+    // Every theme gets one entry, in name order
+    let all = playlist::theme_preview(None, 3).unwrap();
+    assert_eq!(all.entries.len(), themes::theme_count());
+    assert!(all.entries.windows(2).all(|w| w[0].theme < w[1].theme));
+    assert_ne!(all.entries[0].pattern, all.entries[1].pattern);
+
+    assert!(playlist::theme_preview(Some("no-such-theme"), 3).is_err());
+}
+
+#[test]
+fn test_check_playlist() {
+    let yaml = r#"entries:
+  - name: Intro
+    pattern: plasma
+    theme: oceann
+    duration: 10
+    params:
+      complexity: 30
+  - pattern: wave
+    theme: neon
+    duration: 0
+    art: matrix
+    art_params:
+      density: 0.5
+"#;
+    let issues = playlist::check_playlist(yaml);
+    let found: Vec<_> = issues.iter().map(|i| (i.line, i.entry)).collect();
+    assert_eq!(
+        found,
+        vec![(Some(4), Some(0)), (Some(7), Some(0)), (Some(10), Some(1))]
+    );
+    assert!(issues[0].message.contains("did you mean 'ocean'"), "{}", issues[0]);
+    assert_eq!(issues[0].to_string(), format!("line 4: entry 1: {}", issues[0].message));
+
+    // Syntax errors are reported where the parser found them
+    let issues = playlist::check_playlist("entries:\n  - pattern: wave\n    duration: x\n");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(3));
+
+    assert_eq!(playlist::check_playlist("entries: []\n").len(), 1);
+    let sample = std::fs::read_to_string("docs/sample-playlist.yaml").unwrap();
+    assert!(playlist::check_playlist(&sample).is_empty());
+}
+
+#[test]
+fn test_session_recording_replays_as_playlist() {
+    use chromacat::pattern::REGISTRY;
+    use chromacat::playlist::{Playlist, SessionRecorder, SessionScene};
+    use std::time::Duration;
+
+    let scene = |pattern: &str, theme: &str, params: Option<&str>| SessionScene {
+        pattern: pattern.to_string(),
+        theme: theme.to_string(),
+        params: match params {
+            Some(params) => {
+                let parsed = REGISTRY.parse_params(pattern, params).unwrap();
+                REGISTRY.format_params(&parsed)
+            }
+            None => REGISTRY.get_pattern(pattern).unwrap().params().default_value(),
+        },
+        art: None,
+    };
+
+    let mut recorder = SessionRecorder::new();
+    recorder.record(Duration::ZERO, scene("plasma", "ocean", None));
+    // Logging the scene already shown changes nothing
+    recorder.record(Duration::from_secs(2), scene("plasma", "ocean", None));
+    recorder.record(Duration::from_millis(4600), scene("plasma", "fire", None));
+    recorder.record(
+        Duration::from_secs(8),
+        scene("wave", "fire", Some("amplitude=1.5")),
+    );
+    assert_eq!(recorder.len(), 3);
+
+    let playlist = recorder.to_playlist(Duration::from_millis(8200));
+    let summary: Vec<_> = playlist
+        .entries
+        .iter()
+        .map(|e| (e.pattern.as_str(), e.theme.as_str(), e.duration))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("plasma", "ocean", 5), ("plasma", "fire", 3), ("wave", "fire", 1)]
+    );
+
+    // Only parameters that differ from the defaults are written, and the
+    // saved playlist loads back
+    assert!(playlist.entries[0].params.is_none());
+    let yaml = serde_yaml::to_string(&playlist).unwrap();
+    assert!(yaml.contains("amplitude: 1.5"));
+    let loaded: Playlist = yaml.parse().unwrap();
+    assert_eq!(loaded.entries.len(), 3);
+}
+
+#[test]
+fn test_scene_command_line() {
+    use chromacat::demo::DemoArt;
+    use chromacat::pattern::REGISTRY;
+    use chromacat::playlist::SessionScene;
+
+    let parsed = REGISTRY.parse_params("wave", "amplitude=1.5").unwrap();
+    let mut scene = SessionScene {
+        pattern: "wave".to_string(),
+        theme: "neon".to_string(),
+        params: REGISTRY.format_params(&parsed),
+        art: None,
+    };
+    // Only parameters that differ from the defaults are passed
+    assert_eq!(
+        scene.to_command(&[]),
+        "chromacat -a -p wave -t neon --param amplitude=1.5"
+    );
+
+    // Demo art comes last, as the subcommand, and odd flags are quoted
+    scene.art = Some(DemoArt::Logo);
+    assert_eq!(
+        scene.to_command(&["--pattern-pan=0.5,-0.25".to_string(), "it's".to_string()]),
+        "chromacat -p wave -t neon --param amplitude=1.5 --pattern-pan=0.5,-0.25 'it'\\''s' demo logo"
+    );
+}
+
+#[test]
+fn test_entry_command_line() {
+    use chromacat::pattern::BlendMode;
+    use chromacat::playlist::{split_args, LayerEntry, PlaylistEntry};
+
+    assert_eq!(
+        split_args(r#"-t 'my theme' --param "a=1,b=\"x\"" c\ d"#).unwrap(),
+        ["-t", "my theme", "--param", "a=1,b=\"x\"", "c d"]
+    );
+    assert!(split_args("-t 'open").is_err());
+
+    let entry = PlaylistEntry::new("wave", "ocean", 30)
+        .with_params(serde_yaml::from_str("amplitude: 1.5").unwrap())
+        .with_layer(LayerEntry::new("ripple", BlendMode::Multiply));
+    assert_eq!(
+        entry.to_command().unwrap(),
+        "chromacat -a -p wave,ripple -t ocean --param amplitude=1.5"
+    );
+
+    // Layers the command line can't describe are refused
+    let entry = entry.with_layer(LayerEntry::new("plasma", BlendMode::Add));
+    assert!(entry.to_command().is_err());
+}
+
+#[test]
+fn test_weighted_scene_pool() {
+    use chromacat::playlist::ScenePool;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let pool: ScenePool = "
+scene_seconds: 5
+patterns:
+  include: [plasma, wave, checkerboard]
+  exclude: [checkerboard]
+  weights:
+    plasma: 9
+themes:
+  include: [ocean, fire]
+  weights:
+    fire: 0
+"
+    .parse()
+    .unwrap();
+    assert_eq!(
+        pool.pattern_weights(),
+        vec![("plasma".to_string(), 9.0), ("wave".to_string(), 1.0)]
+    );
+    assert_eq!(pool.theme_weights(), vec![("ocean".to_string(), 1.0)]);
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let playlist = pool.to_playlist(&mut rng, 200).unwrap();
+    assert_eq!(playlist.entries.len(), 200);
+    assert!(playlist.entries.iter().all(|e| e.theme == "ocean" && e.duration == 5));
+    assert!(playlist.entries.iter().all(|e| e.pattern != "checkerboard"));
+    // With a single theme, consecutive scenes differ by pattern
+    assert!(playlist
+        .entries
+        .windows(2)
+        .all(|pair| pair[0].pattern != pair[1].pattern));
+
+    // Single draws follow the weights
+    let mut rng = StdRng::seed_from_u64(7);
+    let draws: Vec<_> = (0..200).map(|_| pool.pick(&mut rng).unwrap().0).collect();
+    let plasma = draws.iter().filter(|p| *p == "plasma").count();
+    assert!(plasma > 150, "plasma drawn {} times", plasma);
+
+    // Unknown names and empty pools are rejected when loading
+    assert!("patterns: {exclude: [nope]}".parse::<ScenePool>().is_err());
+    assert!("themes: {weights: {ocean: -1}}".parse::<ScenePool>().is_err());
+    assert!("patterns: {include: [plasma], exclude: [plasma]}"
+        .parse::<ScenePool>()
+        .is_err());
+    assert!("".parse::<ScenePool>().is_ok());
+}
+
+#[test]
+fn test_tagged_scene_pool() {
+    use chromacat::playlist::ScenePool;
+    use chromacat::themes;
+
+    let pool: ScenePool = "themes: {tags: [pastel], exclude: [dream]}"
+        .parse()
+        .unwrap();
+    let names: Vec<String> = pool.theme_weights().into_iter().map(|(n, _)| n).collect();
+    assert!(names.contains(&"serenity".to_string()));
+    assert!(!names.contains(&"dream".to_string()));
+    assert!(names
+        .iter()
+        .all(|name| themes::get_theme(name).unwrap().has_tag("pastel")));
+
+    // --theme-tag replaces the pool's tags
+    let mut pool = pool;
+    pool.set_theme_tag("flag").unwrap();
+    assert!(pool.theme_weights().iter().any(|(n, _)| n == "trans"));
+    assert!(pool.set_theme_tag("no-such-tag").is_err());
+
+    assert!("themes: {tags: [no-such-tag]}"
+        .parse::<ScenePool>()
+        .is_err());
+    assert!("patterns: {tags: [warm]}".parse::<ScenePool>().is_err());
+}
+
+#[test]
+fn test_surprise_scenes() {
+    use chromacat::playlist::ScenePool;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let pool = ScenePool::default();
+    let draw = |seed| pool.surprise(&mut StdRng::seed_from_u64(seed)).unwrap();
+
+    // A seed repeats the same scene
+    let entry = draw(3);
+    assert_eq!(entry.pattern, draw(3).pattern);
+    assert_eq!(entry.theme, draw(3).theme);
+    assert_eq!(entry.params_string().unwrap(), draw(3).params_string().unwrap());
+
+    // Every draw plays
+    for seed in 0..50 {
+        let entry = draw(seed);
+        assert!(entry.validate().is_ok(), "{:?}", entry);
+        assert!(entry.to_pattern_config().is_ok(), "{:?}", entry);
+    }
+}
+
+#[test]
+fn test_playlist_layers() {
+    use chromacat::pattern::BlendMode;
+    use chromacat::playlist::{LayerEntry, PlaylistEntry};
+
+    let yaml = r#"entries:
+  - pattern: plasma
+    theme: ocean
+    duration: 10
+    layers:
+      - pattern: ripple
+        blend: screen
+        params:
+          wavelength: 0.5
+      - pattern: wave
+"#;
+    let playlist = Playlist::from_str(yaml).unwrap();
+    let config = playlist.entries[0].to_pattern_config().unwrap();
+    assert_eq!(config.layers.len(), 2);
+    assert_eq!(config.layers[0].blend, BlendMode::Screen);
+    assert_eq!(config.layers[1].blend, BlendMode::Multiply);
+    assert!(playlist::check_playlist(yaml).is_empty());
+
+    // Layers survive a round trip, and bad ones are reported where they are
+    let entry = PlaylistEntry::new("plasma", "ocean", 10)
+        .with_layer(LayerEntry::new("spiral", BlendMode::Max));
+    let saved = serde_yaml::to_string(&entry).unwrap();
+    let loaded: PlaylistEntry = serde_yaml::from_str(&saved).unwrap();
+    assert_eq!(loaded.layers[0].pattern, "spiral");
+    assert_eq!(loaded.layers[0].blend, BlendMode::Max);
+
+    let bad = yaml.replace("wavelength: 0.5", "wavelength: 50").replace("- pattern: wave", "- pattern: wav");
+    let issues = playlist::check_playlist(&bad);
+    let found: Vec<_> = issues.iter().map(|i| i.line).collect();
+    assert_eq!(found, vec![Some(9), Some(10)]);
+    assert!(issues[0].message.starts_with("Layer 1: "), "{}", issues[0]);
+    assert!(issues[1].message.contains("did you mean 'wave'"), "{}", issues[1]);
+    assert!(Playlist::from_str(&yaml.replace("screen", "overlay")).is_err());
+}
+
+#[test]
+fn test_adaptive_sky_pools() {
+    use chromacat::playlist::{DayPart, ScenePool, Sky, Weather, ADAPTIVE_SCENE_SECONDS};
+
+    assert_eq!(Weather::from_description("Light rain shower"), Some(Weather::Rain));
+    assert_eq!(Weather::from_description("Patchy light rain with thunder"), Some(Weather::Storm));
+    assert_eq!(Weather::from_description("Partly cloudy\n"), Some(Weather::Cloudy));
+    assert_eq!(Weather::from_description("Sunny"), Some(Weather::Clear));
+    assert_eq!(Weather::from_description("Freezing fog"), Some(Weather::Fog));
+    assert_eq!(Weather::from_description("Unknown location"), None);
+
+    assert_eq!(DayPart::from_hour(6), DayPart::Dawn);
+    assert_eq!(DayPart::from_hour(12), DayPart::Day);
+    assert_eq!(DayPart::from_hour(19), DayPart::Dusk);
+    assert_eq!(DayPart::from_hour(23), DayPart::Night);
+    assert_eq!(DayPart::from_hour(2), DayPart::Night);
+
+    let rain = Sky { part: DayPart::Day, weather: Some(Weather::Rain) };
+    assert!(rain.scenes().0.contains(&"ripple") && rain.scenes().1.contains(&"ocean"));
+    let night = Sky { part: DayPart::Night, weather: Some(Weather::Clear) };
+    assert!(night.scenes().1.contains(&"galaxy"));
+    // Without weather, the time of day decides
+    assert_eq!(Sky { part: DayPart::Night, weather: None }.scenes(), night.scenes());
+
+    // Every sky's pool names real patterns and themes
+    let parts = [DayPart::Dawn, DayPart::Day, DayPart::Dusk, DayPart::Night];
+    let weathers = [
+        None,
+        Some(Weather::Clear),
+        Some(Weather::Cloudy),
+        Some(Weather::Rain),
+        Some(Weather::Snow),
+        Some(Weather::Storm),
+        Some(Weather::Fog),
+    ];
+    for part in parts {
+        for weather in weathers {
+            let pool = Sky { part, weather }.pool();
+            let yaml = serde_yaml::to_string(&pool).unwrap();
+            assert!(yaml.parse::<ScenePool>().is_ok(), "{:?} {:?}", part, weather);
+            assert_eq!(pool.scene_seconds, Some(ADAPTIVE_SCENE_SECONDS));
+        }
+    }
+}
+
+#[test]
+fn test_countdown_to_finale() {
+    use chromacat::playlist::MIN_PACE;
+    use chrono::{Local, TimeDelta};
+
+    let until = (Local::now() + TimeDelta::seconds(600)).to_rfc3339();
+    let yaml = format!(
+        r#"
+until: "{}"
+ramp: 300
+finale:
+  pattern: plasma
+  theme: rave
+  duration: 60
+entries:
+  - pattern: wave
+    theme: ocean
+    duration: 30
+  - pattern: ripple
+    theme: ice
+    duration: 30
+"#,
+        until
+    );
+    let playlist = Playlist::from_str(&yaml).unwrap();
+    let mut player = PlaylistPlayer::new(playlist);
+    let deadline = player.deadline().expect("countdown");
+    let before = |seconds: u64| deadline - Duration::from_secs(seconds);
+
+    // Before the ramp, scenes play for their full duration
+    assert!(!player.update_at(Duration::from_secs(29), before(400)));
+    assert_eq!(player.pace(), 1.0);
+    assert!(player.update_at(Duration::from_secs(1), before(399)));
+
+    // Halfway through the ramp, scenes last half as long
+    assert!(!player.update_at(Duration::from_secs(14), before(150)));
+    assert_eq!(player.pace(), 0.5);
+    assert!(player.update_at(Duration::from_secs(1), before(149)));
+
+    // Close to the end they are as short as they get
+    player.update_at(Duration::ZERO, before(1));
+    assert_eq!(player.pace(), MIN_PACE);
+
+    // The finale starts at the deadline, mid-scene, and holds
+    assert!(player.update_at(Duration::from_millis(10), deadline));
+    assert!(player.on_finale());
+    assert_eq!(player.current_entry().unwrap().pattern, "plasma");
+    assert!(player.deadline().is_none());
+    assert!(!player.update_at(Duration::from_secs(600), deadline + Duration::from_secs(600)));
+    assert!(player.on_finale());
+
+    // Moving on by hand leaves it for good
+    player.next_entry();
+    assert!(!player.on_finale());
+    assert!(!player.update_at(Duration::from_secs(1), deadline + Duration::from_secs(601)));
+    assert_eq!(player.pace(), 1.0);
+}
+
+#[test]
+fn test_playlist_until_times() {
+    use chromacat::playlist::{check_playlist, parse_until};
+    use chrono::{Local, TimeZone, Timelike};
+
+    let now = Local.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let at = |s: &str| parse_until(s, now).unwrap();
+    assert_eq!(at("2026-12-31 23:59"), Local.with_ymd_and_hms(2026, 12, 31, 23, 59, 0).unwrap());
+    assert_eq!(at("2026-12-31T23:59:30"), Local.with_ymd_and_hms(2026, 12, 31, 23, 59, 30).unwrap());
+    // Times of day are the next time the clock reads them
+    assert_eq!(at("18:30"), Local.with_ymd_and_hms(2026, 6, 1, 18, 30, 0).unwrap());
+    assert_eq!(at("09:00").hour(), 9);
+    assert!(at("09:00") > now);
+    let utc = chrono::Utc.with_ymd_and_hms(2026, 6, 1, 10, 0, 0).unwrap();
+    assert_eq!(at("2026-06-01T10:00:00Z"), utc.with_timezone(&Local));
+    assert!(parse_until("soon", now).is_err());
+
+    // Problems with the countdown are reported on their lines
+    let issues = check_playlist(
+        "until: tomorrow\nentries:\n  - pattern: wave\n    theme: ocean\n    duration: 10\n",
+    );
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, Some(1));
+
+    let issues = check_playlist(
+        "entries:\n  - pattern: wave\n    theme: ocean\n    duration: 10\nfinale:\n  pattern: wave\n  theme: oceann\n  duration: 10\n",
+    );
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].line, Some(5));
+    assert!(issues[0].message.contains("until"));
+    assert_eq!(issues[1].line, Some(7));
+    assert!(issues[1].message.contains("did you mean 'ocean'"));
+}
+
+/// Waits up to two seconds for a hook to leave a file with `lines` lines
+#[cfg(unix)]
+fn wait_for_lines(path: &std::path::Path, lines: usize) -> Vec<String> {
+    let start = std::time::Instant::now();
+    loop {
+        let found: Vec<String> = std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        if found.len() >= lines || start.elapsed() > Duration::from_secs(2) {
+            return found;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_playlist_hooks() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("hooks.log");
+    let yaml = format!(
+        r#"
+on_scene_change: 'echo "$CHROMACAT_INDEX $CHROMACAT_PATTERN $CHROMACAT_THEME" >> {log}'
+on_playlist_end: 'echo end >> {log}'
+entries:
+  - pattern: wave
+    theme: ocean
+    duration: 1
+  - pattern: ripple
+    theme: ice
+    duration: 1
+"#,
+        log = log.display()
+    );
+    let mut player = PlaylistPlayer::new(Playlist::from_str(&yaml).unwrap());
+
+    assert!(player.update(Duration::from_secs(1)));
+    assert_eq!(wait_for_lines(&log, 1), vec!["2 ripple ice"]);
+
+    // Starting over runs the end hook as well as the scene hook; the
+    // scene hook was rate limited above, so wait it out
+    std::thread::sleep(chromacat::playlist::MIN_HOOK_INTERVAL);
+    assert!(player.update(Duration::from_secs(1)));
+    let lines = wait_for_lines(&log, 3);
+    assert!(lines.contains(&"end".to_string()), "{:?}", lines);
+    assert!(lines.contains(&"1 wave ocean".to_string()), "{:?}", lines);
+    assert!(player.take_notice().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_rate_limit_and_failures() {
+    use chromacat::playlist::{
+        HookEvent, HookOutcome, HookRunner, PlaylistEntry, MAX_HOOK_FAILURES,
+    };
+    use std::time::Instant;
+
+    let entry = PlaylistEntry::new("wave".to_string(), "ocean".to_string(), 10);
+    let mut playlist = Playlist::with_entries(vec![entry.clone()]);
+    assert!(HookRunner::new(&playlist).is_empty());
+
+    playlist.on_scene_change = Some("true".to_string());
+    let mut hooks = HookRunner::new(&playlist);
+    let start = Instant::now();
+    assert_eq!(hooks.fire_at(HookEvent::SceneChange, &entry, 0, start), HookOutcome::Started);
+    let soon = start + Duration::from_millis(100);
+    assert_eq!(hooks.fire_at(HookEvent::SceneChange, &entry, 0, soon), HookOutcome::Skipped);
+    let later = start + Duration::from_secs(1);
+    assert_eq!(hooks.fire_at(HookEvent::SceneChange, &entry, 0, later), HookOutcome::Started);
+    assert_eq!(hooks.fire_at(HookEvent::PlaylistEnd, &entry, 0, later), HookOutcome::Unset);
+
+    // A hook that keeps failing is turned off
+    playlist.on_scene_change = Some("exit 3".to_string());
+    let mut hooks = HookRunner::new(&playlist);
+    for run in 0..MAX_HOOK_FAILURES {
+        let now = start + Duration::from_secs(run as u64 + 1);
+        assert_eq!(hooks.fire_at(HookEvent::SceneChange, &entry, 0, now), HookOutcome::Started);
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    let now = start + Duration::from_secs(10);
+    assert!(matches!(
+        hooks.fire_at(HookEvent::SceneChange, &entry, 0, now),
+        HookOutcome::Disabled(_)
+    ));
+    let now = start + Duration::from_secs(11);
+    assert_eq!(hooks.fire_at(HookEvent::SceneChange, &entry, 0, now), HookOutcome::Unset);
 }
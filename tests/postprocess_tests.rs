@@ -0,0 +1,215 @@
+use chromacat::pattern::color::contrast_ratio;
+use chromacat::pattern::{CharRamp, ColorAdjust, DitherMode, Grain, PostProcess};
+use colorgrad::Color;
+use std::str::FromStr;
+
+#[test]
+fn test_dither_mode_parsing() {
+    assert_eq!(DitherMode::from_str("none").unwrap(), DitherMode::None);
+    assert_eq!(DitherMode::from_str("ordered").unwrap(), DitherMode::Ordered);
+    assert_eq!(DitherMode::from_str("Bayer").unwrap(), DitherMode::Ordered);
+    assert_eq!(
+        DitherMode::from_str("blue-noise").unwrap(),
+        DitherMode::BlueNoise
+    );
+    assert!(DitherMode::from_str("floyd").is_err());
+
+    for mode in DitherMode::all() {
+        assert_eq!(DitherMode::from_str(mode.as_str()).unwrap(), *mode);
+    }
+}
+
+#[test]
+fn test_identity_passes_values_through() {
+    let post = PostProcess::default();
+    assert!(post.is_identity());
+    for &v in &[0.0, 0.123, 0.5, 0.999, 1.0] {
+        assert_eq!(post.apply(v, 3, 7), v);
+    }
+}
+
+#[test]
+fn test_posterize_snaps_to_levels() {
+    let post = PostProcess {
+        levels: Some(4),
+        dither: DitherMode::None,
+        ..Default::default()
+    };
+    let allowed = [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0];
+
+    for i in 0..=100 {
+        let value = post.apply(i as f64 / 100.0, 0, 0);
+        assert!(
+            allowed.iter().any(|a| (a - value).abs() < 1e-9),
+            "{} is not a posterized level",
+            value
+        );
+    }
+
+    assert_eq!(post.apply(0.0, 0, 0), 0.0);
+    assert_eq!(post.apply(1.0, 0, 0), 1.0);
+    assert_eq!(post.apply(0.3, 0, 0), 1.0 / 3.0);
+}
+
+#[test]
+fn test_dither_thresholds_are_centered() {
+    for mode in [DitherMode::Ordered, DitherMode::BlueNoise] {
+        let mut sum = 0.0;
+        for y in 0..16 {
+            for x in 0..16 {
+                let t = mode.threshold(x, y);
+                assert!((-0.5..=0.5).contains(&t), "{} threshold {} out of range", mode, t);
+                sum += t;
+            }
+        }
+        assert!((sum / 256.0).abs() < 0.05, "{} is biased: {}", mode, sum / 256.0);
+    }
+    assert_eq!(DitherMode::None.threshold(5, 9), 0.0);
+}
+
+#[test]
+fn test_ordered_dither_mixes_neighboring_levels() {
+    let post = PostProcess {
+        levels: Some(2),
+        dither: DitherMode::Ordered,
+        ..Default::default()
+    };
+
+    // A mid value should produce an even mix of both levels over a 4x4 tile
+    let high = (0..4)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .filter(|&(x, y)| post.apply(0.5, x, y) == 1.0)
+        .count();
+    assert_eq!(high, 8);
+
+    // Extremes stay put regardless of the threshold
+    for y in 0..4 {
+        for x in 0..4 {
+            assert_eq!(post.apply(0.0, x, y), 0.0);
+            assert_eq!(post.apply(1.0, x, y), 1.0);
+        }
+    }
+}
+
+#[test]
+fn test_char_ramp_maps_density() {
+    let ramp = CharRamp::new("#+. ");
+    assert_eq!(ramp.chars(), &['#', '+', '.', ' ']);
+    assert_eq!(ramp.glyph(1.0), '#');
+    assert_eq!(ramp.glyph(0.0), ' ');
+    assert_eq!(ramp.glyph(0.6), '+');
+    assert_eq!(ramp.glyph(2.0), '#');
+    assert_eq!(ramp.glyph(-1.0), ' ');
+
+    assert_eq!(CharRamp::new(""), CharRamp::default());
+    assert_eq!(CharRamp::new("@").glyph(0.3), '@');
+}
+
+#[test]
+fn test_color_adjust_identity() {
+    let adjust = ColorAdjust::default();
+    assert!(adjust.is_identity());
+    assert!(PostProcess::default().is_identity());
+
+    let color = Color::new(0.2, 0.5, 0.9, 1.0);
+    assert_eq!(adjust.apply(color.clone()), color);
+}
+
+#[test]
+fn test_color_adjustments() {
+    let color = Color::new(0.2, 0.5, 0.9, 0.5);
+    let adjust = |f: fn(&mut ColorAdjust)| {
+        let mut adjust = ColorAdjust::default();
+        f(&mut adjust);
+        adjust.apply(color.clone())
+    };
+
+    // Brightness scales every channel and clips at white
+    let bright = adjust(|a| a.brightness = 2.0);
+    assert!((bright.r - 0.4).abs() < 1e-6 && (bright.g - 1.0).abs() < 1e-6);
+    assert_eq!(bright.b, 1.0);
+    assert_eq!(bright.a, 0.5);
+
+    // No saturation leaves equal channels; no contrast leaves mid-grey
+    let grey = adjust(|a| a.saturation = 0.0);
+    assert!((grey.r - grey.g).abs() < 1e-6 && (grey.g - grey.b).abs() < 1e-6);
+    let flat = adjust(|a| a.contrast = 0.0);
+    assert!([flat.r, flat.g, flat.b].iter().all(|c| (c - 0.5).abs() < 1e-6));
+
+    // Gamma above one lifts midtones and keeps the ends in place
+    let light = adjust(|a| a.gamma = 2.0);
+    assert!(light.r > color.r && light.g > color.g);
+    let ends = ColorAdjust {
+        gamma: 2.0,
+        ..Default::default()
+    };
+    assert_eq!(ends.apply(Color::new(0.0, 1.0, 0.0, 1.0)), Color::new(0.0, 1.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_min_contrast() {
+    assert!((contrast_ratio([1.0; 3], [0.0; 3]) - 21.0).abs() < 1e-9);
+    assert_eq!(contrast_ratio([0.3, 0.6, 0.2], [0.3, 0.6, 0.2]), 1.0);
+
+    let dark = ColorAdjust {
+        min_contrast: Some(4.5),
+        ..Default::default()
+    };
+    assert!(!dark.is_identity());
+
+    // A dark blue is lifted just enough to read on black
+    let blue = dark.apply(Color::new(0.0, 0.0, 0.4, 1.0));
+    let ratio = contrast_ratio([blue.r as f64, blue.g as f64, blue.b as f64], [0.0; 3]);
+    assert!((4.5..4.6).contains(&ratio), "{}", ratio);
+    assert!(blue.b > blue.r);
+
+    // Colors that already contrast enough are left alone
+    let yellow = Color::new(1.0, 1.0, 0.0, 1.0);
+    assert_eq!(dark.apply(yellow.clone()), yellow);
+
+    // On a white background, light colors are darkened instead
+    let light = ColorAdjust {
+        background: (1.0, 1.0, 1.0),
+        ..dark
+    };
+    let shifted = light.apply(yellow);
+    let ratio = contrast_ratio([shifted.r as f64, shifted.g as f64, shifted.b as f64], [1.0; 3]);
+    assert!(ratio >= 4.5 && shifted.r < 1.0);
+}
+
+#[test]
+fn test_grain() {
+    let grey = Color::new(0.5, 0.5, 0.5, 1.0);
+    assert!(Grain::default().is_none());
+    assert_eq!(Grain::default().apply(grey.clone(), 3, 4, 1.0), grey);
+    assert_eq!(Grain::new(5.0).amount(), 1.0);
+
+    let post = PostProcess {
+        grain: Grain::new(0.5),
+        ..Default::default()
+    };
+    assert!(!post.is_identity());
+
+    // The same cell and moment always get the same grain
+    let grain = post.grain;
+    assert_eq!(
+        grain.apply(grey.clone(), 3, 4, 1.0),
+        grain.apply(grey.clone(), 3, 4, 1.0)
+    );
+
+    // Grain varies across cells and flickers over time
+    let frame: Vec<_> = (0..64)
+        .map(|x| grain.apply(grey.clone(), x, 0, 1.0))
+        .collect();
+    assert!(frame.iter().any(|color| *color != frame[0]));
+    let later: Vec<_> = (0..64)
+        .map(|x| grain.apply(grey.clone(), x, 0, 2.0))
+        .collect();
+    assert_ne!(frame, later);
+
+    // Noise only nudges brightness, keeping colors in range and alpha intact
+    for color in frame.iter().chain(&later) {
+        assert!((0.0..=1.0).contains(&color.r));
+        assert_eq!(color.a, 1.0);
+    }
+}
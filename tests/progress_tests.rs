@@ -0,0 +1,128 @@
+use chromacat::escape::strip_sgr;
+use chromacat::progress::{GradientBar, GradientSpinner, ProgressUpdate, DEFAULT_SPINNER_FRAMES};
+use chromacat::termcaps::ColorSupport;
+use colorgrad::{Color, Gradient};
+
+/// Mock gradient for testing
+#[derive(Clone)]
+struct MockGradient;
+
+impl Gradient for MockGradient {
+    fn at(&self, t: f32) -> Color {
+        Color::new(t, t, t, 1.0_f32)
+    }
+}
+
+#[test]
+fn test_bar_fraction_and_clamping() {
+    let mut bar = GradientBar::new(Box::new(MockGradient), 10).with_total(50.0);
+    assert_eq!(bar.fraction(), 0.0);
+
+    bar.set_position(25.0);
+    assert_eq!(bar.fraction(), 0.5);
+
+    bar.inc(100.0);
+    assert_eq!(bar.position(), 50.0);
+    assert!(bar.is_complete());
+
+    bar.set_position(-5.0);
+    assert_eq!(bar.position(), 0.0);
+}
+
+#[test]
+fn test_bar_render_plain() {
+    let mut bar = GradientBar::new(Box::new(MockGradient), 8);
+    bar.set_colors_enabled(false);
+    bar.set_label(Some("Copying"));
+    bar.set_position(50.0);
+
+    assert_eq!(bar.render(), "Copying ████░░░░  50%");
+
+    // Sub-cell progress uses partial blocks
+    bar.set_position(56.25);
+    assert_eq!(bar.render(), "Copying ████▌░░░  56%");
+}
+
+#[test]
+fn test_bar_render_colored_has_same_text() {
+    let mut bar = GradientBar::new(Box::new(MockGradient), 8);
+    bar.set_position(50.0);
+    let colored = bar.render();
+
+    assert!(colored.contains("\x1b[38;2;"));
+    assert_eq!(strip_sgr(&colored), "████░░░░  50%");
+}
+
+#[test]
+fn test_colors_follow_color_support() {
+    let mut bar = GradientBar::new(Box::new(MockGradient), 8);
+    bar.set_position(50.0);
+    bar.set_color_support(ColorSupport::Ansi256);
+    let colored = bar.render();
+    assert!(colored.contains("\x1b[38;5;"));
+    assert!(!colored.contains("\x1b[38;2;"));
+    assert_eq!(strip_sgr(&colored), "████░░░░  50%");
+
+    let mut spinner = GradientSpinner::new(Box::new(MockGradient));
+    spinner.set_color_support(ColorSupport::Ansi16);
+    let colored = spinner.render();
+    assert!(!colored.contains("\x1b[38;"));
+    assert_eq!(strip_sgr(&colored), DEFAULT_SPINNER_FRAMES[0]);
+}
+
+#[test]
+fn test_bar_animation_shifts_colors() {
+    let mut bar = GradientBar::new(Box::new(MockGradient), 8);
+    bar.set_position(100.0);
+    let before = bar.render();
+    bar.tick(0.5);
+    assert_ne!(before, bar.render());
+}
+
+#[test]
+fn test_spinner_cycles_frames() {
+    let mut spinner = GradientSpinner::new(Box::new(MockGradient));
+    spinner.set_colors_enabled(false);
+    assert_eq!(spinner.render(), DEFAULT_SPINNER_FRAMES[0]);
+
+    spinner.tick(1.0 / 12.0 + 0.001);
+    assert_eq!(spinner.frame_index(), 1);
+
+    let mut spinner = GradientSpinner::new(Box::new(MockGradient)).with_frames(&["-", "+"]);
+    spinner.set_colors_enabled(false);
+    spinner.set_label(Some("working"));
+    spinner.tick(1.0 / 12.0 + 0.001);
+    assert_eq!(spinner.render(), "+ working");
+}
+
+#[test]
+fn test_progress_update_parsing() {
+    assert_eq!(
+        ProgressUpdate::parse("42", 100.0),
+        ProgressUpdate {
+            value: Some(42.0),
+            label: None
+        }
+    );
+    assert_eq!(
+        ProgressUpdate::parse("50% halfway there", 200.0),
+        ProgressUpdate {
+            value: Some(100.0),
+            label: Some("halfway there".to_string())
+        }
+    );
+    assert_eq!(
+        ProgressUpdate::parse("Compiling chromacat", 100.0),
+        ProgressUpdate {
+            value: None,
+            label: Some("Compiling chromacat".to_string())
+        }
+    );
+    assert_eq!(
+        ProgressUpdate::parse("   ", 100.0),
+        ProgressUpdate {
+            value: None,
+            label: None
+        }
+    );
+}
@@ -0,0 +1,130 @@
+use chromacat::pattern::{CommonParams, HorizontalParams, PatternConfig, PatternEngine, PatternParams};
+use chromacat::renderer::{Compositor, Extent, Rect, RegionLayout, RegionSpec, RenderBuffer};
+use chromacat::themes;
+
+const LAYOUT: &str = r#"
+regions:
+  - name: banner
+    height: 3
+    pattern: wave
+    theme: ocean
+  - name: sidebar
+    y: 3
+    width: 25%
+    pattern: plasma
+    theme: neon
+    params:
+      complexity: 3.0
+"#;
+
+fn create_engine(theme: &str, width: usize, height: usize) -> PatternEngine {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    };
+    let gradient = themes::get_theme(theme).unwrap().create_gradient().unwrap();
+    PatternEngine::new(gradient, config, width, height)
+}
+
+#[test]
+fn test_extent_parsing() {
+    assert_eq!("12".parse::<Extent>().unwrap(), Extent::Cells(12));
+    assert_eq!(" 25% ".parse::<Extent>().unwrap(), Extent::Percent(25.0));
+    assert!("150%".parse::<Extent>().is_err());
+    assert!("-3".parse::<Extent>().is_err());
+    assert!("wide".parse::<Extent>().is_err());
+
+    assert_eq!(Extent::Percent(25.0).resolve(80), 20);
+    assert_eq!(Extent::Percent(50.0).resolve(5), 3);
+    assert_eq!(Extent::Cells(100).resolve(80), 80);
+}
+
+#[test]
+fn test_layout_from_yaml() {
+    let layout: RegionLayout = LAYOUT.parse().unwrap();
+    assert_eq!(layout.regions.len(), 2);
+
+    let banner = &layout.regions[0];
+    assert_eq!(banner.name, "banner");
+    assert_eq!(
+        banner.rect(80, 24),
+        Rect { x: 0, y: 0, width: 80, height: 3 }
+    );
+
+    // Sizes are clipped to the rest of the screen
+    let sidebar = &layout.regions[1];
+    assert_eq!(
+        sidebar.rect(80, 24),
+        Rect { x: 0, y: 3, width: 20, height: 21 }
+    );
+    assert!(sidebar.to_pattern_config().is_ok());
+}
+
+#[test]
+fn test_layout_validation() {
+    let err = "regions:\n  - name: top\n    pattern: nope\n    theme: ocean\n"
+        .parse::<RegionLayout>()
+        .unwrap_err();
+    assert!(err.to_string().contains("region 1 (top)"), "{}", err);
+
+    assert!("regions:\n  - pattern: wave\n    theme: nope\n"
+        .parse::<RegionLayout>()
+        .is_err());
+    assert!("regions:\n  - pattern: wave\n    theme: ocean\n    width: 120%\n"
+        .parse::<RegionLayout>()
+        .is_err());
+    assert!("regions: []\n".parse::<RegionLayout>().is_err());
+}
+
+#[test]
+fn test_compositor_region_lookup() {
+    let layout: RegionLayout = LAYOUT.parse().unwrap();
+    let mut compositor = Compositor::new(&layout, 40, 10).unwrap();
+
+    assert_eq!(compositor.region_at(30, 1).map(|r| r.name()), Some("banner"));
+    assert_eq!(compositor.region_at(5, 5).map(|r| r.name()), Some("sidebar"));
+    assert!(compositor.region_at(30, 5).is_none());
+
+    // Each region's engine is sized to its rectangle
+    assert_eq!(compositor.regions()[1].engine().size(), (10, 7));
+
+    compositor.update(1.5);
+    assert!(compositor.regions().iter().all(|r| r.engine().time() == 1.5));
+
+    compositor.resize(80, 20);
+    assert_eq!(
+        compositor.regions()[1].rect(),
+        Rect { x: 0, y: 3, width: 20, height: 17 }
+    );
+    assert_eq!(compositor.regions()[1].engine().time(), 1.5);
+}
+
+#[test]
+fn test_regions_color_their_cells() {
+    let text = "##########\n".repeat(4);
+    let main = create_engine("fire", 10, 4);
+
+    let mut plain = RenderBuffer::new((10, 4));
+    plain.prepare_text(&text).unwrap();
+    plain.update_colors(&main, 0).unwrap();
+    let before = plain.capture(0, 4);
+
+    // The left half of the screen gets a different theme
+    let layout = RegionLayout::with_regions(vec![RegionSpec::new("horizontal", "ocean")
+        .with_name("left")
+        .with_size(Extent::Percent(50.0), Extent::Percent(100.0))]);
+    let mut split = RenderBuffer::new((10, 4));
+    split.prepare_text(&text).unwrap();
+    split.set_regions(Some(Compositor::new(&layout, 10, 4).unwrap()));
+    split.update_colors(&main, 0).unwrap();
+    let after = split.capture(0, 4);
+
+    for y in 0..4 {
+        for x in 5..10 {
+            assert_eq!(after.cell(x, y), before.cell(x, y));
+        }
+    }
+    assert!((0..5).any(|x| after.cell(x, 0).fg != before.cell(x, 0).fg));
+    assert_eq!(after.text(), before.text());
+}
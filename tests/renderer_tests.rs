@@ -4,11 +4,15 @@
 //! terminal interaction, color handling, and performance.
 
 use chromacat::pattern::{CommonParams, PatternConfig, PatternEngine, PatternParams, HorizontalParams};
-use chromacat::renderer::{AnimationConfig, Renderer};
+use chromacat::renderer::{
+    actions_for, help_lines, AnimationConfig, FrameStage, FrameTimings, HeadlessTarget, KeyAction,
+    LegendPosition, PickKind, RenderTarget, Renderer, KEY_BINDINGS,
+};
 use colorgrad::{Color, Gradient};
 use std::time::Duration;
 
 /// Mock gradient for testing
+#[derive(Clone)]
 struct MockGradient;
 
 impl Gradient for MockGradient {
@@ -32,6 +36,7 @@ impl RendererTest {
         let pattern_config = PatternConfig {
             common: CommonParams::default(),
             params: PatternParams::Horizontal(HorizontalParams::default()),
+            layers: Vec::new(),
         };
 
         let engine = PatternEngine::new(
@@ -47,6 +52,7 @@ impl RendererTest {
             infinite: false,
             show_progress: true,
             smooth: false,
+            ..AnimationConfig::default()
         };
 
         Self { engine, config }
@@ -233,3 +239,993 @@ fn test_animation_performance() {
         max_allowed_duration
     );
 }
+
+#[test]
+fn test_file_header_fills_width() {
+    use unicode_width::UnicodeWidthStr;
+
+    let header = Renderer::file_header("config.yaml", 40);
+    assert!(header.contains("config.yaml"));
+    assert_eq!(header.width(), 40);
+
+    // Long names still get a trailing rule
+    let header = Renderer::file_header(&"x".repeat(50), 40);
+    assert!(header.ends_with("━━"));
+}
+
+#[test]
+fn test_error_policy() {
+    use chromacat::renderer::{ErrorPolicy, LoopStage, Recovery, MAX_FRAME_FAILURES, TRANSITION_RETRIES};
+
+    let mut policy = ErrorPolicy::new();
+    for _ in 0..TRANSITION_RETRIES {
+        assert_eq!(policy.on_error(LoopStage::Transition), Recovery::Retry);
+    }
+    assert_eq!(policy.on_error(LoopStage::Transition), Recovery::Skip);
+    // The next scene gets its own retries
+    assert_eq!(policy.on_error(LoopStage::Transition), Recovery::Retry);
+
+    // Only frames failing over and over end the loop
+    for _ in 1..MAX_FRAME_FAILURES {
+        assert_eq!(policy.on_error(LoopStage::Frame), Recovery::Skip);
+    }
+    policy.on_success(LoopStage::Frame);
+    assert_eq!(policy.on_error(LoopStage::Frame), Recovery::Skip);
+    for _ in 1..MAX_FRAME_FAILURES - 1 {
+        policy.on_error(LoopStage::Frame);
+    }
+    assert_eq!(policy.on_error(LoopStage::Frame), Recovery::Abort);
+    assert_eq!(policy.on_error(LoopStage::Key), Recovery::Skip);
+}
+
+#[test]
+fn test_failed_scene_is_skipped() {
+    use chromacat::playlist::{Playlist, PlaylistEntry};
+    use chromacat::renderer::TRANSITION_RETRIES;
+
+    // Entries aren't validated when built by hand, so the middle one can
+    // name a theme that doesn't exist
+    let playlist = Playlist::with_entries(vec![
+        PlaylistEntry::new("wave", "ocean", 1),
+        PlaylistEntry::new("wave", "no-such-theme", 1).with_name("Broken"),
+        PlaylistEntry::new("plasma", "fire", 1),
+    ]);
+    let test = RendererTest::new();
+    let mut renderer =
+        Renderer::new(test.engine.clone(), test.config.clone(), Some(playlist), false).unwrap();
+    renderer.render_frame("Test", 0.016).unwrap();
+
+    // The broken scene is tried, retried, and skipped without an error,
+    // and the previous scene keeps playing meanwhile
+    renderer.render_frame("Test", 1.5).unwrap();
+    assert_eq!(renderer.playlist_position().map(|(i, _)| i), Some(1));
+    assert!(renderer.status().contains("theme=ocean"));
+    for _ in 0..TRANSITION_RETRIES {
+        renderer.render_frame("Test", 0.016).unwrap();
+    }
+    assert!(renderer.toast().unwrap().contains("Skipped Broken"));
+
+    renderer.render_frame("Test", 0.016).unwrap();
+    assert_eq!(renderer.playlist_position().map(|(i, _)| i), Some(2));
+    assert!(renderer.status().contains("pattern=plasma theme=fire"));
+}
+
+#[test]
+fn test_pause_step_and_scrub() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Test", 0.016).unwrap();
+    renderer.render_frame("Test", 0.5).unwrap();
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    // A paused clock stands still while frames are drawn
+    renderer.handle_key_event(key(' ')).unwrap();
+    assert!(renderer.is_paused());
+    let paused_at = renderer.animation_time();
+    renderer.render_frame("Test", 0.5).unwrap();
+    assert_eq!(renderer.animation_time(), paused_at);
+
+    // Steps and scrubs move it anyway, and never before zero
+    renderer.handle_key_event(key(']')).unwrap();
+    let scrubbed = renderer.animation_time();
+    assert!(scrubbed > paused_at);
+    renderer.handle_key_event(key(',')).unwrap();
+    assert!(renderer.animation_time() < scrubbed);
+    for _ in 0..10 {
+        renderer.handle_key_event(key('[')).unwrap();
+    }
+    assert_eq!(renderer.animation_time(), 0.0);
+
+    renderer.handle_key_event(key(' ')).unwrap();
+    assert!(!renderer.is_paused());
+    renderer.render_frame("Test", 0.5).unwrap();
+    assert!(renderer.animation_time() > 0.0);
+}
+
+#[test]
+fn test_speed_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Test", 0.016).unwrap();
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    renderer.handle_key_event(key('+')).unwrap();
+    assert_eq!(renderer.speed_multiplier(), 1.5);
+    assert_eq!(renderer.toast(), Some("Speed 1.5×"));
+
+    // The multiplier scales animation time, not frames
+    let before = renderer.animation_time();
+    renderer.render_frame("Test", 0.5).unwrap();
+    let faster = renderer.animation_time() - before;
+    renderer.handle_key_event(key('-')).unwrap();
+    renderer.handle_key_event(key('-')).unwrap();
+    assert_eq!(renderer.speed_multiplier(), 0.75);
+    let before = renderer.animation_time();
+    renderer.render_frame("Test", 0.5).unwrap();
+    assert!((faster / (renderer.animation_time() - before) - 2.0).abs() < 1e-9);
+
+    // Speeds stop at the ends of the range
+    for _ in 0..20 {
+        renderer.handle_key_event(key('-')).unwrap();
+    }
+    assert_eq!(renderer.speed_multiplier(), 0.1);
+}
+
+#[test]
+fn test_favorites_keys() {
+    use chromacat::playlist::Favorites;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chromacat").join("favorites.yaml");
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.set_favorites(Favorites::load(&path).unwrap());
+    renderer.render_frame("Test", 0.016).unwrap();
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    let pattern = |renderer: &chromacat::renderer::Renderer| {
+        renderer.status().split_whitespace().next().unwrap().to_string()
+    };
+
+    // Bookmarking saves the combination once
+    let bookmarked = pattern(&renderer);
+    renderer.handle_key_event(key('b')).unwrap();
+    renderer.handle_key_event(key('b')).unwrap();
+    assert!(renderer.toast().unwrap().starts_with("Already a favorite"));
+    assert_eq!(Favorites::load(&path).unwrap().len(), 1);
+
+    // The panel picks favorites by number, then closes
+    renderer.handle_key_event(key('p')).unwrap();
+    assert_ne!(pattern(&renderer), bookmarked);
+    renderer.handle_key_event(key('f')).unwrap();
+    assert!(renderer.favorites_open());
+    renderer.handle_key_event(key('1')).unwrap();
+    assert_eq!(pattern(&renderer), bookmarked);
+    assert!(!renderer.favorites_open());
+
+    // Numbers without a favorite leave the scene alone
+    renderer.handle_key_event(key('f')).unwrap();
+    renderer.handle_key_event(key('2')).unwrap();
+    assert_eq!(renderer.toast(), Some("No favorite 2"));
+}
+
+#[test]
+fn test_pattern_view_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Test", 0.016).unwrap();
+    let press = |renderer: &mut Renderer, code, modifiers| {
+        renderer.handle_key_event(KeyEvent::new(code, modifiers)).unwrap();
+    };
+
+    press(&mut renderer, KeyCode::Right, KeyModifiers::SHIFT);
+    assert_eq!(renderer.toast(), Some("Pattern 1.00× at +0.05, +0.00"));
+
+    // Zooming in makes pan steps cover the same part of the screen
+    press(&mut renderer, KeyCode::Char('>'), KeyModifiers::NONE);
+    press(&mut renderer, KeyCode::Char('>'), KeyModifiers::NONE);
+    press(&mut renderer, KeyCode::Up, KeyModifiers::SHIFT);
+    assert_eq!(renderer.toast(), Some("Pattern 1.56× at +0.05, -0.03"));
+
+    // Zoom stops at the end of its range, and `)` resets the view
+    for _ in 0..30 {
+        press(&mut renderer, KeyCode::Char('<'), KeyModifiers::NONE);
+    }
+    assert_eq!(renderer.toast(), Some("Pattern 0.10× at +0.05, -0.03"));
+    press(&mut renderer, KeyCode::Char(')'), KeyModifiers::NONE);
+    assert_eq!(renderer.toast(), Some("Pattern 1.00× at +0.00, +0.00"));
+}
+
+#[test]
+fn test_grain_key() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Test", 0.016).unwrap();
+    let key = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+
+    renderer.handle_key_event(key).unwrap();
+    assert_eq!(renderer.toast(), Some("Grain on"));
+    renderer.handle_key_event(key).unwrap();
+    assert_eq!(renderer.toast(), Some("Grain off"));
+}
+
+#[test]
+fn test_search_matches() {
+    use chromacat::renderer::Search;
+
+    let text = "alpha\n\x1b[31mBeta\x1b[0m\ngamma beta\ndelta\nbeta";
+
+    // Lowercase queries ignore case and colors; uppercase ones don't
+    let mut search = Search::new("beta", text);
+    assert_eq!(search.matches(), &[1, 2, 4]);
+    assert_eq!(Search::new("Beta", text).matches(), &[1]);
+
+    // Moving through the matches stops at either end
+    assert_eq!(search.first_from(2), Some(2));
+    assert_eq!(search.next_match(), Some(4));
+    assert_eq!(search.next_match(), None);
+    assert_eq!(search.current(), Some(4));
+    assert_eq!(search.status(), "/beta: match 3/3");
+    assert_eq!(search.previous_match(), Some(2));
+    assert_eq!(search.previous_match(), Some(1));
+    assert_eq!(search.previous_match(), None);
+
+    assert_eq!(Search::new("beta", text).first_from(5), None);
+    assert_eq!(Search::new("omega", text).status(), "Pattern not found: omega");
+}
+
+#[test]
+fn test_search_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    let text: String = (1..=100)
+        .map(|i| if i % 30 == 0 { format!("line {} match\n", i) } else { format!("line {}\n", i) })
+        .collect();
+    renderer.render_frame(&text, 0.016).unwrap();
+    let press = |renderer: &mut Renderer, code| {
+        renderer.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE)).unwrap()
+    };
+    let current = |renderer: &Renderer| renderer.last_search().and_then(|s| s.current());
+
+    // Keys go to the prompt while it is open, and `q` doesn't quit
+    press(&mut renderer, KeyCode::Char('/'));
+    assert!(renderer.search_open());
+    for c in "matcq".chars() {
+        assert!(press(&mut renderer, KeyCode::Char(c)));
+    }
+    press(&mut renderer, KeyCode::Backspace);
+    press(&mut renderer, KeyCode::Char('h'));
+    press(&mut renderer, KeyCode::Enter);
+    assert!(!renderer.search_open());
+    assert_eq!(current(&renderer), Some(29));
+
+    // `n` and `N` move between matches, and an empty query repeats the search
+    press(&mut renderer, KeyCode::Char('n'));
+    assert_eq!(current(&renderer), Some(59));
+    press(&mut renderer, KeyCode::Char('/'));
+    press(&mut renderer, KeyCode::Enter);
+    assert_eq!(current(&renderer), Some(89));
+    press(&mut renderer, KeyCode::Char('n'));
+    assert_eq!(renderer.toast(), Some("No more matches: match"));
+    press(&mut renderer, KeyCode::Char('N'));
+    assert_eq!(current(&renderer), Some(59));
+
+    // Esc closes the prompt without searching
+    press(&mut renderer, KeyCode::Char('/'));
+    press(&mut renderer, KeyCode::Char('x'));
+    press(&mut renderer, KeyCode::Esc);
+    assert!(!renderer.search_open());
+    assert_eq!(renderer.last_search().unwrap().query(), "match");
+}
+
+#[test]
+fn test_fuzzy_picker_ranking() {
+    use chromacat::renderer::{fuzzy_score, PickItem, PickKind, Picker};
+
+    // Letters must appear in order; runs and word starts score higher
+    assert!(fuzzy_score("nbl", "nebula").is_some());
+    assert_eq!(fuzzy_score("lbn", "nebula"), None);
+    assert!(fuzzy_score("neb", "nebula") > fuzzy_score("nbl", "nebula"));
+    assert!(fuzzy_score("rain", "pixel-rain") > fuzzy_score("rain", "terrain"));
+    assert_eq!(fuzzy_score("", "plasma"), Some(0));
+
+    let mut picker = Picker::new(vec![
+        PickItem::new(PickKind::Pattern, "plasma"),
+        PickItem::new(PickKind::Theme, "pastel"),
+        PickItem::new(PickKind::Theme, "ocean"),
+        PickItem::new(PickKind::Art, "plasma"),
+    ]);
+    assert_eq!(picker.hits().count(), 4);
+    for c in "PLa".chars() {
+        picker.push(c);
+    }
+    let hits: Vec<_> = picker.hits().map(|item| item.kind).collect();
+    assert_eq!(hits, [PickKind::Pattern, PickKind::Art]);
+
+    // The selection stops at either end and resets as the query changes
+    picker.move_selection(5);
+    assert_eq!(picker.selected().unwrap().kind, PickKind::Art);
+    picker.move_selection(-5);
+    assert_eq!(picker.selected().unwrap().kind, PickKind::Pattern);
+    picker.push('z');
+    assert_eq!(picker.selected(), None);
+    assert!(picker.pop());
+    assert_eq!(picker.query(), "PLa");
+}
+
+#[test]
+fn test_picker_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Hello", 0.016).unwrap();
+    let press = |renderer: &mut Renderer, code| {
+        renderer.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE)).unwrap()
+    };
+    let type_query = |renderer: &mut Renderer, query: &str| {
+        press(renderer, KeyCode::Char(':'));
+        for c in query.chars() {
+            press(renderer, KeyCode::Char(c));
+        }
+    };
+
+    // Enter applies the top hit, and keys like `t` go to the query
+    type_query(&mut renderer, "plsma");
+    assert!(renderer.picker_open());
+    assert_eq!(renderer.picker().unwrap().query(), "plsma");
+    press(&mut renderer, KeyCode::Enter);
+    assert!(!renderer.picker_open());
+    assert_eq!(renderer.pattern_id(), "plasma");
+    assert_eq!(renderer.toast(), Some("Switched to pattern plasma"));
+
+    type_query(&mut renderer, "ocean");
+    press(&mut renderer, KeyCode::Enter);
+    assert_eq!(renderer.theme_name(), "ocean");
+
+    // Art is only offered in demo mode
+    type_query(&mut renderer, "matrix");
+    assert!(renderer
+        .picker()
+        .unwrap()
+        .hits()
+        .all(|item| item.kind != PickKind::Art));
+    press(&mut renderer, KeyCode::Esc);
+    assert!(!renderer.picker_open());
+    assert_eq!(renderer.pattern_id(), "plasma");
+
+    // A query matching nothing says so; Backspace on an empty query closes
+    type_query(&mut renderer, "qqqq");
+    press(&mut renderer, KeyCode::Enter);
+    assert_eq!(renderer.toast(), Some("Nothing matches: qqqq"));
+    press(&mut renderer, KeyCode::Char(':'));
+    press(&mut renderer, KeyCode::Backspace);
+    assert!(!renderer.picker_open());
+}
+
+#[test]
+fn test_key_bindings() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let actions =
+        |code, modifiers| actions_for(&KeyEvent::new(code, modifiers)).collect::<Vec<_>>();
+    // Bindings sharing a key are tried in order
+    assert_eq!(
+        actions(KeyCode::Esc, KeyModifiers::NONE),
+        [
+            KeyAction::StopInspecting,
+            KeyAction::CloseFavorites,
+            KeyAction::Quit
+        ]
+    );
+    assert_eq!(
+        actions(KeyCode::Up, KeyModifiers::SHIFT),
+        [KeyAction::PanPattern, KeyAction::Scroll]
+    );
+    assert_eq!(
+        actions(KeyCode::Up, KeyModifiers::NONE),
+        [KeyAction::Scroll]
+    );
+    assert_eq!(
+        actions(KeyCode::Char('T'), KeyModifiers::SHIFT),
+        [KeyAction::NextTheme]
+    );
+    assert!(actions(KeyCode::Char('c'), KeyModifiers::CONTROL).is_empty());
+
+    // The help lists every binding with its keys
+    let help = help_lines();
+    assert_eq!(help.len(), KEY_BINDINGS.len());
+    for (line, binding) in help.iter().zip(KEY_BINDINGS) {
+        assert!(line.contains(binding.label) && line.contains(binding.description));
+    }
+}
+
+#[test]
+fn test_keys_bound_once() {
+    use crossterm::event::KeyCode;
+
+    // Only Esc and the arrows do different things in different states;
+    // every other key does one thing
+    let shared = [
+        KeyCode::Esc,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Up,
+        KeyCode::Down,
+    ];
+    for (i, binding) in KEY_BINDINGS.iter().enumerate() {
+        for later in &KEY_BINDINGS[i + 1..] {
+            for key in binding.keys {
+                assert!(
+                    shared.contains(key) || !later.keys.contains(key),
+                    "{:?} is bound to both {:?} and {:?}",
+                    key,
+                    binding.action,
+                    later.action
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_help_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Hello", 0.016).unwrap();
+    let press = |renderer: &mut Renderer, code| {
+        renderer.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE)).unwrap()
+    };
+
+    press(&mut renderer, KeyCode::Char('?'));
+    assert!(renderer.help_open());
+    assert_eq!(renderer.help_scroll(), Some(0));
+
+    // The arrows scroll the help and stop with the last binding in view
+    press(&mut renderer, KeyCode::Down);
+    assert_eq!(renderer.help_scroll(), Some(1));
+    press(&mut renderer, KeyCode::End);
+    let last = renderer.help_scroll().unwrap();
+    assert!(last > 1 && last < KEY_BINDINGS.len());
+    press(&mut renderer, KeyCode::PageDown);
+    assert_eq!(renderer.help_scroll(), Some(last));
+    press(&mut renderer, KeyCode::Home);
+    assert_eq!(renderer.help_scroll(), Some(0));
+
+    // Other keys work as usual with the help open
+    let theme = renderer.theme_name().to_string();
+    press(&mut renderer, KeyCode::Char('t'));
+    assert_ne!(renderer.theme_name(), theme);
+    assert!(renderer.help_open());
+
+    // Esc closes the help rather than quitting, and `?` toggles it
+    assert!(press(&mut renderer, KeyCode::Esc));
+    assert!(!renderer.help_open());
+    press(&mut renderer, KeyCode::Char('?'));
+    press(&mut renderer, KeyCode::Char('?'));
+    assert!(!renderer.help_open());
+}
+
+#[test]
+fn test_message_history() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Hello", 0.016).unwrap();
+    let press = |renderer: &mut Renderer, code| {
+        renderer.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE)).unwrap()
+    };
+
+    press(&mut renderer, KeyCode::Char('o'));
+    press(&mut renderer, KeyCode::Char('e'));
+    assert!(renderer.history_shown());
+    press(&mut renderer, KeyCode::Char('o'));
+    assert_eq!(renderer.toast(), Some("Moved history top-left"));
+    let history: Vec<&str> = renderer
+        .notifications()
+        .history()
+        .map(|notice| notice.message.as_str())
+        .collect();
+    assert_eq!(history, ["Moved history top-left", "No panel open to move"]);
+    press(&mut renderer, KeyCode::Char('E'));
+    assert!(!renderer.history_shown());
+}
+
+#[test]
+fn test_copy_command() {
+    use chromacat::renderer::osc52_sequence;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    assert_eq!(osc52_sequence("chromacat"), "\x1b]52;c;Y2hyb21hY2F0\x07");
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    renderer.render_frame("Hello", 0.016).unwrap();
+    let command = renderer.command_line();
+    assert!(command.starts_with("chromacat -a -p "));
+    assert!(!command.contains("--pattern-zoom"));
+
+    renderer.zoom_pattern(1);
+    assert!(renderer.command_line().contains("--pattern-zoom="));
+    renderer
+        .handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE))
+        .unwrap();
+    let toast = renderer.toast().unwrap();
+    assert_eq!(toast, format!("Copied: {}", renderer.command_line()));
+}
+
+#[test]
+fn test_render_profile() {
+    use chromacat::pattern::REGISTRY;
+    use chromacat::renderer::{RenderProfile, SlowFrames, SLOW_SECONDS};
+
+    assert_eq!("minimal".parse(), Ok(RenderProfile::Minimal));
+    assert!("tiny".parse::<RenderProfile>().is_err());
+
+    // Most frames overrunning for a few seconds in a row calls for the
+    // minimal profile
+    let budget = Duration::from_millis(33);
+    let mut slow = SlowFrames::new();
+    for second in 1..=SLOW_SECONDS {
+        for took in [40, 50, 10] {
+            slow.frame(Duration::from_millis(took), budget);
+        }
+        assert_eq!(slow.roll(), second == SLOW_SECONDS);
+    }
+    slow.frame(Duration::from_millis(10), budget);
+    assert!(!slow.roll());
+
+    let test = RendererTest::new();
+    let pattern_config = PatternConfig {
+        common: CommonParams::default(),
+        params: REGISTRY.parse_params("plasma", "complexity=5").unwrap(),
+        layers: Vec::new(),
+    };
+    let engine = PatternEngine::new(create_test_gradient(), pattern_config, 80, 24);
+    let config = AnimationConfig {
+        profile: Some(RenderProfile::Minimal),
+        ..test.config.clone()
+    };
+    let mut renderer = Renderer::with_target(
+        HeadlessTarget::new(80, 24),
+        engine.clone(),
+        config,
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(renderer.profile(), RenderProfile::Minimal);
+    assert_eq!(
+        renderer.frame_duration(),
+        Duration::from_nanos(1_000_000_000 / 15)
+    );
+    assert!(renderer.command_line().contains("complexity=2"));
+    renderer.render_frame("Hello", 0.0).unwrap();
+
+    let renderer = Renderer::with_target(
+        HeadlessTarget::new(80, 24),
+        engine,
+        test.config.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(renderer.profile(), RenderProfile::Full);
+    assert!(renderer.command_line().contains("complexity=5"));
+}
+
+#[test]
+fn test_cpu_budget() {
+    use chromacat::renderer::{parse_cpu_budget, CpuBudget};
+
+    assert_eq!(parse_cpu_budget("20%"), Ok(20.0));
+    assert_eq!(parse_cpu_budget("35"), Ok(35.0));
+    assert!(parse_cpu_budget("0%").is_err());
+    assert!(parse_cpu_budget("lots").is_err());
+
+    // Usage over the budget steps the frame rate down, then caps
+    // complexity; usage well under it steps back up
+    let mut budget = CpuBudget::new(20.0);
+    assert_eq!(budget.fps(30), 30);
+    assert!(budget.adjust(45.0));
+    assert_eq!(budget.fps(30), 23);
+    assert!(!budget.adjust(15.0));
+    assert!(budget.adjust(30.0) && budget.adjust(30.0));
+    assert_eq!(budget.max_complexity(), Some(2.0));
+    assert!(budget.adjust(30.0) && !budget.adjust(30.0));
+    assert_eq!(budget.fps(30), 8);
+    assert!(budget.adjust(5.0));
+    assert_eq!(budget.step(), 3);
+
+    let test = RendererTest::new();
+    let config = AnimationConfig {
+        cpu_budget: Some(20.0),
+        ..test.config.clone()
+    };
+    let mut renderer = Renderer::with_target(
+        HeadlessTarget::new(80, 24),
+        test.engine.clone(),
+        config,
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(renderer.cpu_budget().map(|b| b.budget()), Some(20.0));
+    assert_eq!(
+        renderer.frame_duration(),
+        Duration::from_nanos(1_000_000_000 / 30)
+    );
+    renderer.render_frame("Hello", 0.0).unwrap();
+}
+
+#[test]
+fn test_mouse_events() {
+    use chromacat::renderer::ClickTracker;
+    use crossterm::event::{
+        KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use std::time::Instant;
+
+    // Only two quick clicks on the same cell make a double-click
+    let mut clicks = ClickTracker::new();
+    let now = Instant::now();
+    assert!(!clicks.click(3, 4, now));
+    assert!(clicks.click(3, 4, now + Duration::from_millis(200)));
+    assert!(!clicks.click(3, 4, now + Duration::from_millis(300)));
+    assert!(!clicks.click(3, 5, now + Duration::from_millis(400)));
+    assert!(!clicks.click(3, 5, now + Duration::from_secs(2)));
+
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+    let text: String = (0..100).map(|i| format!("Line {}\n", i)).collect();
+    renderer.render_frame(&text, 0.016).unwrap();
+    let mouse = |renderer: &mut Renderer, kind, column, row| {
+        renderer
+            .handle_mouse_event(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+            .unwrap()
+    };
+    let left = MouseEventKind::Down(MouseButton::Left);
+    let drag = MouseEventKind::Drag(MouseButton::Left);
+
+    // The wheel scrolls the key help under the pointer, and the text
+    // elsewhere
+    renderer
+        .handle_key_event(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE))
+        .unwrap();
+    mouse(&mut renderer, MouseEventKind::ScrollDown, 5, 5);
+    assert_eq!(renderer.help_scroll(), Some(3));
+    mouse(&mut renderer, MouseEventKind::ScrollDown, 70, 5);
+    assert_eq!(renderer.help_scroll(), Some(3));
+    renderer
+        .handle_key_event(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE))
+        .unwrap();
+
+    // A click selects a switcher hit and a double-click applies it; the
+    // hits start on the second row of the panel, at the top left
+    renderer
+        .handle_key_event(KeyEvent::new(KeyCode::Char(':'), KeyModifiers::NONE))
+        .unwrap();
+    let second = renderer.picker().unwrap().hits().nth(1).unwrap().clone();
+    mouse(&mut renderer, left, 5, 3);
+    assert_eq!(renderer.picker().unwrap().selected(), Some(&second));
+    mouse(&mut renderer, left, 5, 3);
+    assert!(!renderer.picker_open());
+    assert_eq!(
+        renderer.toast(),
+        Some(format!("Switched to {} {}", second.kind.as_str(), second.name).as_str())
+    );
+
+    // Dragging over the animation pans the pattern along with the pointer
+    mouse(&mut renderer, left, 40, 10);
+    mouse(&mut renderer, drag, 32, 10);
+    assert_eq!(renderer.toast(), Some("Pattern 1.00× at +0.10, +0.00"));
+    mouse(&mut renderer, MouseEventKind::Up(MouseButton::Left), 32, 10);
+    mouse(&mut renderer, drag, 20, 10);
+    assert_eq!(renderer.toast(), Some("Pattern 1.00× at +0.10, +0.00"));
+}
+
+#[test]
+fn test_static_stream_rendering() {
+    let test = RendererTest::new();
+    let mut renderer = test.create_renderer().unwrap();
+
+    // Enough lines to take several chunks
+    let text = (0..5000).map(|i| format!("Line {}\n", i)).collect::<String>();
+    let len = text.len() as u64;
+    assert!(renderer.render_static_stream(text.as_bytes(), len).is_ok());
+
+    // Input of unknown length, and empty input
+    assert!(renderer.render_static_stream(text.as_bytes(), 0).is_ok());
+    assert!(renderer.render_static_stream(&b""[..], 0).is_ok());
+}
+
+#[test]
+fn test_headless_static_rendering() {
+    let test = RendererTest::new();
+    let mut renderer = Renderer::with_target(
+        HeadlessTarget::new(20, 5),
+        test.engine.clone(),
+        test.config.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    renderer.render_static("Hello\nWorld").unwrap();
+    let screen = renderer.target();
+    assert_eq!(screen.size(), (20, 5));
+    assert!(screen.text().starts_with("Hello\nWorld\n"));
+    assert_eq!(screen.cell(0, 1).0, 'W');
+
+    // The gradient runs across the text
+    let (_, first) = screen.cell(0, 0);
+    let (_, last) = screen.cell(4, 0);
+    assert_ne!(first, last);
+}
+
+#[test]
+fn test_headless_animated_frames() {
+    let test = RendererTest::new();
+    let mut renderer = Renderer::with_target(
+        HeadlessTarget::new(40, 6),
+        test.engine.clone(),
+        test.config.clone(),
+        None,
+        false,
+    )
+    .unwrap();
+
+    renderer.render_frame("Hello", 0.0).unwrap();
+    renderer.render_frame("Hello", 0.1).unwrap();
+    let screen = renderer.target();
+    assert_eq!(screen.frames(), 2);
+    assert_eq!(screen.grid().len(), 6);
+    assert!(screen.text().starts_with("Hello"));
+
+    // The status bar fills the bottom row
+    assert!(screen.row(5).iter().any(|&(ch, _)| ch != ' '));
+}
+
+#[test]
+fn test_frame_timings() {
+    let mut timings = FrameTimings::default();
+    timings.record(FrameStage::Colors, Duration::from_millis(4));
+    timings.record(FrameStage::Colors, Duration::from_millis(14));
+    timings.record(FrameStage::Write, Duration::from_millis(1));
+
+    // Averages move a little toward each new frame; peaks show a second late
+    assert_eq!(timings.average(FrameStage::Colors), Duration::from_millis(5));
+    assert_eq!(timings.peak(FrameStage::Colors), Duration::ZERO);
+    timings.roll();
+    assert_eq!(timings.peak(FrameStage::Colors), Duration::from_millis(14));
+    assert_eq!(timings.total(), Duration::from_millis(6));
+}
+
+#[test]
+fn test_debug_overlay() {
+    let test = RendererTest::new();
+    let config = AnimationConfig {
+        debug_overlay: true,
+        ..test.config.clone()
+    };
+    let mut renderer =
+        Renderer::with_target(HeadlessTarget::new(100, 24), test.engine.clone(), config, None, false)
+            .unwrap();
+
+    renderer.render_frame("Hello", 0.0).unwrap();
+    renderer.render_frame("Hello", 0.1).unwrap();
+    let text = renderer.target().text();
+    assert!(text.contains("Frame"));
+    assert!(text.contains("colors"));
+    assert!(text.contains("No playlist, playing"));
+
+    // Closing it brings back the text underneath
+    renderer.set_debug_overlay(false);
+    renderer.render_frame("Hello", 0.1).unwrap();
+    assert!(!renderer.target().text().contains("Frame"));
+}
+
+#[test]
+fn test_gradient_legend() {
+    let test = RendererTest::new();
+    let config = AnimationConfig {
+        legend: Some(LegendPosition::TopLeft),
+        ..test.config.clone()
+    };
+    let mut renderer =
+        Renderer::with_target(HeadlessTarget::new(60, 12), test.engine.clone(), config, None, false)
+            .unwrap();
+
+    renderer.render_frame("Hello", 0.0).unwrap();
+    let screen = renderer.target();
+    assert!(screen.text().contains("pattern value"));
+
+    // The strip runs from the low end of the gradient to the high end
+    let strip: Vec<_> = screen.row(2).iter().filter(|&&(ch, _)| ch == '█').collect();
+    assert_eq!(strip.len(), 30);
+    assert!(strip[0].1[0] < strip[29].1[0]);
+
+    // M hides it, and shows it again in the same corner
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    let m = KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+    renderer.handle_key_event(m).unwrap();
+    assert!(!renderer.legend_shown());
+    renderer.render_frame("Hello", 0.1).unwrap();
+    assert!(!renderer.target().text().contains("pattern value"));
+    renderer.handle_key_event(m).unwrap();
+    renderer.render_frame("Hello", 0.1).unwrap();
+    assert!(renderer.target().row(3).iter().any(|&(ch, _)| ch == '0'));
+
+    assert_eq!("Bottom-Left".parse(), Ok(LegendPosition::BottomLeft));
+    assert!("middle".parse::<LegendPosition>().is_err());
+}
+
+#[test]
+fn test_panel_layout() {
+    use chromacat::renderer::{PanelKind, PanelLayout, Rect};
+
+    let layout = PanelLayout::default();
+    let open = [
+        (PanelKind::Favorites, (30, 5)),
+        (PanelKind::Legend, (32, 3)),
+        (PanelKind::Debug, (64, 17)),
+    ];
+
+    let areas = |placed: Vec<(PanelKind, Rect)>| {
+        placed
+            .into_iter()
+            .map(|(_, area)| (area.x, area.y, area.width, area.height))
+            .collect::<Vec<_>>()
+    };
+
+    // Wide screens put each panel in its corner
+    let placed = layout.place(&open, (160, 40));
+    assert_eq!(
+        areas(placed),
+        [(2, 1, 30, 5), (126, 36, 32, 3), (94, 1, 64, 17)]
+    );
+
+    // At 80x24 they stack down the left edge, cut short at the bottom
+    let placed = layout.place(&open, (80, 22));
+    assert_eq!(
+        areas(placed),
+        [(2, 1, 30, 5), (2, 6, 32, 3), (2, 9, 64, 12)]
+    );
+    // A panel open on its own keeps its corner
+    let placed = layout.place(&open[1..2], (80, 22));
+    assert_eq!(areas(placed), [(46, 18, 32, 3)]);
+
+    // Panels move clockwise and the layout survives a save and load
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chromacat").join("panels.yaml");
+    let mut layout = PanelLayout::load(&path).unwrap();
+    assert_eq!(layout, PanelLayout::load(&path).unwrap());
+    layout.rotate(&[PanelKind::Favorites, PanelKind::Legend]);
+    assert_eq!(
+        layout.corner(PanelKind::Favorites),
+        LegendPosition::TopRight
+    );
+    assert_eq!(layout.corner(PanelKind::Legend), LegendPosition::BottomLeft);
+    layout.save().unwrap();
+    assert_eq!(PanelLayout::load(&path).unwrap(), layout);
+
+    std::fs::write(&path, "legend: middle\n").unwrap();
+    assert!(PanelLayout::load(&path).is_err());
+    std::fs::write(&path, "stack_below: 60\nlegend: top-left\n").unwrap();
+    let layout = PanelLayout::load(&path).unwrap();
+    assert_eq!(layout.stack_below, 60);
+    assert_eq!(layout.corner(PanelKind::Debug), LegendPosition::TopRight);
+}
+
+#[test]
+fn test_panels_stack_on_small_screens() {
+    use chromacat::renderer::PanelKind;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let test = RendererTest::new();
+    let config = AnimationConfig {
+        debug_overlay: true,
+        ..test.config.clone()
+    };
+    let mut renderer =
+        Renderer::with_target(HeadlessTarget::new(80, 24), test.engine.clone(), config, None, false)
+            .unwrap();
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    // The debug overlay moves below the legend instead of covering it
+    renderer.handle_key_event(key('m')).unwrap();
+    renderer.render_frame("Hello", 0.1).unwrap();
+    let text = renderer.target().text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[2].contains('█'));
+    assert!(lines[3].contains("pattern value"));
+    assert!(lines[4].contains("Frame"));
+    assert!(lines[20].contains("F12 close"));
+
+    // O moves the open panels to the next corner; without a layout file
+    // nothing is saved
+    renderer.handle_key_event(key('o')).unwrap();
+    let layout = renderer.panel_layout();
+    assert_eq!(layout.corner(PanelKind::Debug), LegendPosition::BottomRight);
+    assert_eq!(layout.corner(PanelKind::Legend), LegendPosition::BottomLeft);
+    assert_eq!(
+        renderer.toast(),
+        Some("Moved legend bottom-left, debug bottom-right")
+    );
+    renderer.set_debug_overlay(false);
+    renderer.set_legend(false);
+    renderer.handle_key_event(key('o')).unwrap();
+    assert_eq!(renderer.toast(), Some("No panel open to move"));
+}
+
+#[test]
+fn test_countdown_theme_shift() {
+    use chromacat::demo::{ArtSettings, DemoArt};
+
+    let test = RendererTest::new();
+    let timer = |target: &str| {
+        let mut renderer = Renderer::with_target(
+            HeadlessTarget::new(60, 12),
+            test.engine.clone(),
+            test.config.clone(),
+            None,
+            true,
+        )
+        .unwrap();
+        renderer.set_art_settings(ArtSettings::default().with_countdown(Some(Duration::from_secs(2))));
+        renderer.set_demo_art(DemoArt::Clock);
+        renderer.set_countdown_theme(Some(target)).unwrap();
+        renderer.render_frame("", 0.0).unwrap();
+        renderer
+    };
+    let digits = |renderer: &Renderer<HeadlessTarget>| -> Vec<[u8; 3]> {
+        let screen = renderer.target();
+        (0..10)
+            .flat_map(|y| screen.row(y).to_vec())
+            .filter(|&(ch, _)| ch == '█')
+            .map(|(_, color)| color)
+            .collect()
+    };
+
+    // The renderers start alike, on the -t theme, and only the one shifting
+    // toward another theme drifts away as the countdown runs
+    let (mut shifting, mut steady) = (timer("fire"), timer("rainbow"));
+    for renderer in [&mut shifting, &mut steady] {
+        renderer.render_frame("", 0.0).unwrap();
+    }
+    assert_eq!(shifting.countdown_progress(), Some(0.0));
+    assert_eq!(digits(&shifting), digits(&steady));
+
+    for renderer in [&mut shifting, &mut steady] {
+        renderer.render_frame("", 1.0).unwrap();
+    }
+    assert_eq!(shifting.countdown_progress(), Some(0.5));
+    assert!(!digits(&shifting).is_empty());
+    assert_ne!(digits(&shifting), digits(&steady));
+    assert!(shifting.toast().is_none());
+
+    // Time's up is announced along with the bell
+    shifting.render_frame("", 1.5).unwrap();
+    assert_eq!(shifting.countdown_progress(), Some(1.0));
+    assert_eq!(shifting.toast(), Some("Time's up!"));
+
+    assert!(timer("fire").set_countdown_theme(Some("no-such-theme")).is_err());
+}
@@ -0,0 +1,71 @@
+use chromacat::renderer::SessionStats;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_stats_count_frames_and_scenes() {
+    let mut stats = SessionStats::new();
+    let start = Instant::now();
+    let at = |seconds: u64| start + Duration::from_secs(seconds);
+
+    // Two seconds of plasma in ocean, then a second of it in fire, then
+    // four seconds of wave in fire
+    stats.record_frame_at(at(0), "plasma", "ocean");
+    stats.record_frame_at(at(1), "plasma", "ocean");
+    stats.record_frame_at(at(2), "plasma", "fire");
+    stats.record_frame_at(at(3), "wave", "fire");
+    stats.record_frame_at(at(5), "wave", "fire");
+    assert_eq!(stats.frames(), 5);
+    assert_eq!(stats.scenes(), 3);
+
+    // The scene on screen counts until the report
+    let report = stats.report_at(at(7));
+    assert_eq!(report.frames, 5);
+    assert_eq!(report.scenes, 3);
+    assert_eq!(report.top_pattern.as_deref(), Some("wave"));
+    assert_eq!(report.top_theme.as_deref(), Some("fire"));
+    assert!((report.pattern_seconds["plasma"] - 3.0).abs() < 0.01);
+    assert!((report.pattern_seconds["wave"] - 4.0).abs() < 0.01);
+    assert!((report.theme_seconds["ocean"] - 2.0).abs() < 0.01);
+    assert!((report.theme_seconds["fire"] - 5.0).abs() < 0.01);
+    assert!(report.runtime_seconds >= 7.0);
+    assert!(report.average_fps > 0.0 && report.average_fps <= 5.0 / 7.0);
+
+    let summary = report.summary();
+    assert!(summary.contains("5 frames"), "{}", summary);
+    assert!(summary.contains("Scenes played: 3"), "{}", summary);
+    assert!(summary.contains("Most used pattern: wave (4s)"));
+    assert!(summary.contains("Most used theme: fire (5s)"));
+}
+
+#[test]
+fn test_stats_without_frames() {
+    let report = SessionStats::new().report();
+    assert_eq!(report.frames, 0);
+    assert_eq!(report.scenes, 0);
+    assert_eq!(report.top_pattern, None);
+    assert_eq!(report.top_theme, None);
+    assert!(!report.summary().contains("Most used"));
+}
+
+#[test]
+fn test_stats_save_json() {
+    let mut stats = SessionStats::new();
+    let start = Instant::now();
+    stats.record_frame_at(start, "spiral", "rainbow");
+    stats.record_frame_at(start + Duration::from_millis(500), "spiral", "rainbow");
+    let report = stats.report_at(start + Duration::from_secs(1));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stats.json");
+    report.save(&path).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["frames"], 2);
+    assert_eq!(json["scenes"], 1);
+    assert_eq!(json["top_pattern"], "spiral");
+    assert_eq!(json["top_theme"], "rainbow");
+    assert_eq!(json["pattern_seconds"]["spiral"], 1.0);
+
+    // A file that can't be written is an error, not a panic
+    assert!(report.save(&dir.path().join("missing/stats.json")).is_err());
+}
@@ -1,4 +1,4 @@
-use chromacat::renderer::StatusBar;
+use chromacat::renderer::{Notifications, Severity, StatusBar, MAX_QUEUED, TOAST_DURATION};
 
 
 #[test]
@@ -55,3 +55,73 @@ fn test_custom_text() {
     status_bar.set_custom_text(None);
     assert_eq!(status_bar.custom_text(), None);
 }
+
+#[test]
+fn test_toast() {
+    let mut status_bar = StatusBar::new((80, 24));
+    assert_eq!(status_bar.toast(), None);
+
+    status_bar.show_toast("Scene skipped");
+    assert_eq!(status_bar.toast(), Some("Scene skipped"));
+    // A toast doesn't replace the custom text underneath
+    assert_eq!(status_bar.custom_text(), None);
+}
+
+#[test]
+fn test_notification_queue() {
+    let mut notifications = Notifications::new();
+    notifications.push("Speed 2×", Severity::Info);
+    notifications.push("Speed 3×", Severity::Info);
+    assert_eq!(notifications.current().unwrap().message, "Speed 3×");
+
+    // A more severe message replaces the one showing; a less severe one
+    // waits until it expires
+    notifications.push("Transition failed", Severity::Error);
+    notifications.push("Grain on", Severity::Info);
+    let error = notifications.current().unwrap();
+    assert_eq!(error.message, "Transition failed");
+    let error_ends = error.posted + Severity::Error.duration();
+    let current = notifications.current_at(error_ends).unwrap();
+    assert_eq!(current.message, "Grain on");
+    assert_eq!(current.severity, Severity::Info);
+    assert!(notifications
+        .current_at(error_ends + TOAST_DURATION * 2)
+        .is_none());
+
+    // Dismissing moves on to the next message waiting
+    notifications.dismiss();
+    assert_eq!(notifications.current().unwrap().message, "Grain on");
+
+    // Older messages waiting are dropped, and the history keeps them all,
+    // latest first
+    notifications.push("Playlist not reloaded", Severity::Warn);
+    for i in 0..MAX_QUEUED + 2 {
+        notifications.push(&format!("Info {}", i), Severity::Info);
+    }
+    let history: Vec<&str> = notifications
+        .history()
+        .map(|notice| notice.message.as_str())
+        .collect();
+    assert_eq!(history.len(), MAX_QUEUED + 7);
+    assert_eq!(history[0], format!("Info {}", MAX_QUEUED + 1));
+    assert_eq!(history[history.len() - 1], "Speed 2×");
+
+    let mut status_bar = StatusBar::new((80, 24));
+    status_bar.notify("Favorites failed to save", Severity::Error);
+    assert_eq!(status_bar.notice().unwrap().severity, Severity::Error);
+    status_bar.show_toast("Grain off");
+    assert_eq!(status_bar.toast(), Some("Favorites failed to save"));
+}
+
+#[test]
+fn test_cycle_progress() {
+    let mut status_bar = StatusBar::new((80, 24));
+    assert_eq!(status_bar.progress(), None);
+
+    status_bar.set_progress(Some(0.25));
+    assert_eq!(status_bar.progress(), Some(0.25));
+    status_bar.set_progress(Some(1.5));
+    assert_eq!(status_bar.progress(), Some(1.0));
+    status_bar.set_progress(None);
+    assert_eq!(status_bar.progress(), None);
+}
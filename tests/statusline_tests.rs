@@ -0,0 +1,81 @@
+use chromacat::escape::strip_sgr;
+use chromacat::pattern::{CommonParams, HorizontalParams, PatternConfig, PatternEngine, PatternParams};
+use chromacat::statusline::{fit_width, sanitize, StatusFormat, StatusLine};
+use colorgrad::{Color, Gradient};
+use unicode_width::UnicodeWidthStr;
+
+/// Mock gradient for testing
+#[derive(Clone)]
+struct MockGradient;
+
+impl Gradient for MockGradient {
+    fn at(&self, t: f32) -> Color {
+        Color::new(t, 0.0, 1.0 - t, 1.0)
+    }
+}
+
+fn create_line(width: usize) -> StatusLine {
+    let config = PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    };
+    StatusLine::new(PatternEngine::new(Box::new(MockGradient), config, width, 1), width)
+}
+
+#[test]
+fn test_status_format_parsing() {
+    assert_eq!("TMUX".parse::<StatusFormat>().unwrap(), StatusFormat::Tmux);
+    assert!("screen".parse::<StatusFormat>().is_err());
+
+    for format in StatusFormat::all() {
+        assert_eq!(format.to_string().parse::<StatusFormat>().unwrap(), *format);
+    }
+}
+
+#[test]
+fn test_fit_width() {
+    assert_eq!(fit_width("abc", 5), "abc  ");
+    assert_eq!(fit_width("abcdef", 4), "abcd");
+
+    // A wide character that would cross the edge becomes padding
+    assert_eq!(fit_width("ab世界", 5), "ab世 ");
+    assert_eq!(fit_width("ab世界", 3), "ab ");
+    assert_eq!(fit_width("e\u{301}x", 2).width(), 2);
+}
+
+#[test]
+fn test_sanitize_flattens_input() {
+    assert_eq!(sanitize("\x1b[31mCPU\x1b[0m  12%\n\tmem\x1b[2J 3G\n"), "CPU 12% mem 3G");
+}
+
+#[test]
+fn test_status_line_width_and_codes() {
+    let line = create_line(20);
+    let out = line.render("load \x1b[1m0.42\x1b[0m\nup 3 days and counting").unwrap();
+
+    let plain = strip_sgr(&out);
+    assert_eq!(plain, "load 0.42 up 3 days ");
+    assert_eq!(plain.width(), 20);
+
+    // Only color escapes, ending with a foreground reset
+    assert!(out.ends_with("\x1b[39m"));
+    assert!(!out.contains('\n'));
+    let escapes: Vec<&str> = out.split('\x1b').skip(1).collect();
+    assert!(escapes.iter().all(|e| e.starts_with("[38;2;") || e.starts_with("[39m")));
+}
+
+#[test]
+fn test_status_line_tmux_format() {
+    let line = create_line(6).with_format(StatusFormat::Tmux);
+    let out = line.render("#1 ok").unwrap();
+
+    assert!(out.starts_with("#[fg=#"));
+    assert!(out.ends_with("#[default]"));
+    assert!(out.contains("##"));
+    assert!(!out.contains('\x1b'));
+
+    let mut plain = create_line(6);
+    plain.set_colors_enabled(false);
+    assert_eq!(plain.render("#1 ok").unwrap(), "#1 ok ");
+}
@@ -0,0 +1,87 @@
+use chromacat::sync::{SyncFollower, SyncLeader, SyncState};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_sync_state_lines() {
+    let state = SyncState {
+        time: 12.5,
+        scene: Some((2, 4.25)),
+        paused: false,
+    };
+    assert_eq!(state.to_string(), "chromacat-sync time=12.5000 scene=2 elapsed=4.250 paused=0");
+    assert_eq!(state.to_string().parse::<SyncState>().unwrap(), state);
+
+    let state: SyncState = "chromacat-sync time=3 paused=1 future=yes".parse().unwrap();
+    assert_eq!(state.scene, None);
+    assert!(state.paused);
+
+    assert!("hello time=3".parse::<SyncState>().is_err());
+    assert!("chromacat-sync paused=0".parse::<SyncState>().is_err());
+    assert!("chromacat-sync time=NaN".parse::<SyncState>().is_err());
+    assert!("chromacat-sync time=1 scene=-1".parse::<SyncState>().is_err());
+}
+
+#[test]
+fn test_leader_and_follower() {
+    let leader = SyncLeader::bind("127.0.0.1:0").unwrap();
+    let follower = SyncFollower::connect(&leader.local_addr().to_string()).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while leader.follower_count() == 0 {
+        assert!(Instant::now() < deadline, "follower never connected");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    for time in [1.0, 2.0, 3.0] {
+        leader.broadcast(&SyncState {
+            time,
+            scene: Some((1, 0.5)),
+            paused: false,
+        });
+    }
+
+    // The follower only reports the newest state
+    let mut latest = None;
+    while latest.as_ref().map(|s: &SyncState| s.time) != Some(3.0) {
+        assert!(Instant::now() < deadline, "no update received");
+        latest = follower.latest().or(latest);
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(latest.unwrap().scene, Some((1, 0.5)));
+    assert_eq!(follower.latest(), None);
+}
+
+#[test]
+fn test_stalled_follower_is_dropped() {
+    let leader = SyncLeader::bind("127.0.0.1:0").unwrap();
+    // Connects but never reads, so its socket buffers fill up
+    let _stalled = std::net::TcpStream::connect(leader.local_addr()).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while leader.follower_count() == 0 {
+        assert!(Instant::now() < deadline, "follower never connected");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Broadcasting never waits on the follower, which is dropped once it
+    // falls behind
+    let state = SyncState {
+        time: 1.0,
+        scene: Some((1, 0.5)),
+        paused: false,
+    };
+    let mut slowest = Duration::ZERO;
+    while leader.follower_count() > 0 {
+        assert!(Instant::now() < deadline, "stalled follower never dropped");
+        let started = Instant::now();
+        leader.broadcast(&state);
+        slowest = slowest.max(started.elapsed());
+    }
+    assert!(slowest < Duration::from_millis(100), "{:?}", slowest);
+}
+
+#[test]
+fn test_follower_rejects_bad_address() {
+    assert!(SyncFollower::connect("not an address").is_err());
+}
@@ -0,0 +1,112 @@
+use chromacat::termcaps::{
+    ansi16, ansi256, device_attributes, mode_report, terminfo_colors, ColorSupport, Graphics,
+    TermCaps,
+};
+
+/// Looks variables up in a fixed list
+fn env(vars: &[(&str, &str)]) -> TermCaps {
+    TermCaps::from_env(|name| {
+        vars.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    })
+}
+
+/// Builds a legacy compiled terminfo entry with the given `colors` number
+fn terminfo_entry(colors: i16) -> Vec<u8> {
+    let names = b"test|test terminal\0";
+    let numbers = 14;
+    let mut entry = Vec::new();
+    for short in [0o432, names.len() as i16, 0, numbers, 0, 0] {
+        entry.extend_from_slice(&short.to_le_bytes());
+    }
+    entry.extend_from_slice(names);
+    if entry.len() % 2 == 1 {
+        entry.push(0);
+    }
+    for i in 0..numbers {
+        let value: i16 = if i == 13 { colors } else { -1 };
+        entry.extend_from_slice(&value.to_le_bytes());
+    }
+    entry
+}
+
+#[test]
+fn test_capabilities_from_env() {
+    // Anything unknown is assumed to work
+    let caps = env(&[("TERM", "xterm-256color")]);
+    assert_eq!(caps, TermCaps::default());
+
+    let caps = env(&[("TERM", "linux")]);
+    assert_eq!(caps.color, ColorSupport::Ansi16);
+    assert!(!caps.synchronized_output && !caps.mouse && !caps.bracketed_paste);
+
+    let caps = env(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "Apple_Terminal")]);
+    assert_eq!(caps.color, ColorSupport::Ansi256);
+
+    // COLORTERM has the last word on colors
+    let caps = env(&[("TERM", "vt100"), ("COLORTERM", "truecolor")]);
+    assert_eq!(caps.color, ColorSupport::TrueColor);
+
+    assert_eq!(env(&[("TERM", "xterm-kitty")]).graphics, Graphics::Kitty);
+    assert_eq!(env(&[("TERM_PROGRAM", "WezTerm")]).graphics, Graphics::Iterm);
+    assert_eq!(env(&[("TERM", "foot")]).graphics, Graphics::Sixel);
+}
+
+#[test]
+fn test_capabilities_from_terminfo() {
+    let entry = terminfo_entry(8);
+    assert_eq!(terminfo_colors(&entry), Some(8));
+    assert_eq!(terminfo_colors(b"not terminfo"), None);
+
+    let mut caps = TermCaps::default();
+    caps.apply_terminfo(&entry, None);
+    assert_eq!(caps.color, ColorSupport::Ansi16);
+
+    // A 256-color entry says nothing about direct colors
+    let mut caps = TermCaps::default();
+    caps.apply_terminfo(&terminfo_entry(256), None);
+    assert_eq!(caps.color, ColorSupport::TrueColor);
+
+    let mut caps = TermCaps::default();
+    caps.apply_terminfo(&entry, Some("truecolor"));
+    assert_eq!(caps.color, ColorSupport::TrueColor);
+}
+
+#[test]
+fn test_capabilities_from_replies() {
+    let reply = "\x1b_Gi=31;OK\x1b\\\x1b[?2026;2$y\x1b[?2004;0$y\x1b[?62;4;22c";
+    assert_eq!(mode_report(reply, 2026), Some(2));
+    assert_eq!(mode_report(reply, 2004), Some(0));
+    assert_eq!(mode_report(reply, 1000), None);
+    assert_eq!(device_attributes(reply), Some(vec![62, 4, 22]));
+
+    let mut caps = TermCaps {
+        synchronized_output: false,
+        ..TermCaps::default()
+    };
+    caps.apply_replies(reply);
+    assert!(caps.synchronized_output);
+    assert!(!caps.bracketed_paste);
+    assert!(caps.mouse);
+    assert_eq!(caps.graphics, Graphics::Kitty);
+
+    // Sixel support shows in the device attributes
+    let mut caps = TermCaps::default();
+    caps.apply_replies("\x1b[?64;4c");
+    assert_eq!(caps.graphics, Graphics::Sixel);
+}
+
+#[test]
+fn test_color_escapes() {
+    assert_eq!(ansi256(0, 0, 0), 16);
+    assert_eq!(ansi256(255, 0, 0), 196);
+    assert_eq!(ansi256(128, 128, 128), 244);
+    assert_eq!(ansi16(250, 10, 10), 9);
+    assert_eq!(ansi16(0, 0, 0), 0);
+
+    assert_eq!(ColorSupport::TrueColor.fg(1, 2, 3).to_string(), "\x1b[38;2;1;2;3m");
+    assert_eq!(ColorSupport::Ansi256.bg(255, 0, 0).to_string(), "\x1b[48;5;196m");
+    assert_eq!(ColorSupport::Ansi16.fg(250, 10, 10).to_string(), "\x1b[91m");
+    assert_eq!(ColorSupport::Ansi16.bg(0, 0, 0).to_string(), "\x1b[40m");
+}
@@ -168,3 +168,93 @@ fn test_error_conditions() {
     // Test recovery after error
     assert!(term_state.try_recover().is_ok());
 }
+
+#[test]
+fn test_guard_tracks_alternate_screen() {
+    use chromacat::guard::{self, TerminalGuard};
+
+    let mut out = Vec::new();
+    guard::enter_alternate_screen(&mut out).unwrap();
+    assert!(guard::in_alternate_screen());
+    let entered = out.len();
+    assert!(entered > 0);
+
+    // Entering twice doesn't write again
+    guard::enter_alternate_screen(&mut out).unwrap();
+    assert_eq!(out.len(), entered);
+
+    guard::leave_alternate_screen(&mut out).unwrap();
+    assert!(!guard::in_alternate_screen());
+    let left = out.len();
+    assert!(left > entered);
+
+    // Leaving twice, or dropping a guard afterwards, is harmless
+    guard::leave_alternate_screen(&mut out).unwrap();
+    assert_eq!(out.len(), left);
+    drop(TerminalGuard::new());
+    assert!(!guard::in_alternate_screen());
+    assert_eq!(guard::interruption(), None);
+}
+
+#[test]
+fn test_resize_watcher_ignores_non_terminals() {
+    use chromacat::renderer::ResizeWatcher;
+
+    // Test output is captured, so there is no window to follow
+    if atty::is(atty::Stream::Stdout) {
+        return;
+    }
+    let mut watcher = ResizeWatcher::new();
+    assert_eq!(watcher.size(), None);
+    assert_eq!(watcher.poll(), None);
+}
+
+#[test]
+fn test_frame_assembler_writes_once() {
+    use chromacat::renderer::{FrameAssembler, BEGIN_SYNCHRONIZED_UPDATE, END_SYNCHRONIZED_UPDATE};
+    use std::io::Write;
+
+    /// Counts the writes it is given
+    #[derive(Default)]
+    struct Counter {
+        bytes: Vec<u8>,
+        writes: usize,
+    }
+    impl Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.writes += 1;
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // A frame drawn in pieces goes out in one write, inside a synchronized
+    // update
+    let mut frame = FrameAssembler::new((80, 24), true);
+    writeln!(frame, "line one").unwrap();
+    frame.flush().unwrap();
+    write!(frame, "line two").unwrap();
+    let mut out = Counter::default();
+    frame.finish(&mut out).unwrap();
+    assert_eq!(out.writes, 1);
+    let expected = [
+        BEGIN_SYNCHRONIZED_UPDATE,
+        b"line one\nline two",
+        END_SYNCHRONIZED_UPDATE,
+    ]
+    .concat();
+    assert_eq!(out.bytes, expected);
+    assert!(frame.pending().is_empty());
+
+    // Empty frames write nothing, and plain frames have no markers
+    frame.finish(&mut out).unwrap();
+    assert_eq!(out.writes, 1);
+    let mut plain = FrameAssembler::new((80, 24), false);
+    write!(plain, "text").unwrap();
+    let mut out = Counter::default();
+    plain.finish(&mut out).unwrap();
+    assert_eq!(out.bytes, b"text");
+}
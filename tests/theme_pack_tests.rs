@@ -0,0 +1,80 @@
+use chromacat::theme_packs::{self, pack_name};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_pack(dir: &Path, file: &str, names: &[&str]) -> String {
+    let mut yaml = String::new();
+    for name in names {
+        yaml.push_str(&format!(
+            "- name: {}\n  desc: Test theme\n  tags: [test]\n  colors:\n    - [1.0, 0.0, 0.0, 0.0]\n    - [0.0, 0.0, 1.0, 1.0]\n",
+            name
+        ));
+    }
+    let path = dir.join(file);
+    std::fs::write(&path, yaml).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_pack_names() {
+    assert_eq!(pack_name("packs/sunrise.yaml").unwrap(), "sunrise");
+    assert_eq!(
+        pack_name("https://example.com/a/dusk-tones.yml?raw=1").unwrap(),
+        "dusk-tones"
+    );
+    assert!(pack_name("https://example.com/").is_err());
+    assert!(pack_name("bad name.yaml").is_err());
+}
+
+#[test]
+fn test_install_and_remove_packs() {
+    let source = TempDir::new().unwrap();
+    let installed = TempDir::new().unwrap();
+    let dir = installed.path().join("themes");
+
+    let pack = write_pack(
+        source.path(),
+        "morning.yaml",
+        &["pack-test-dawn", "pack-test-noon"],
+    );
+    let pack = theme_packs::install(&pack, &dir).unwrap();
+    assert_eq!(pack.name, "morning");
+    assert_eq!(pack.themes.len(), 2);
+    assert_eq!(pack.themes[0].tags, ["test"]);
+
+    // Reinstalling replaces the pack
+    let update = write_pack(source.path(), "morning.yaml", &["pack-test-dawn"]);
+    theme_packs::install(&update, &dir).unwrap();
+    let packs = theme_packs::list_packs(&dir).unwrap();
+    assert_eq!(packs.len(), 1);
+    assert_eq!(packs[0].themes.len(), 1);
+
+    // Themes can't hide built-in themes or those of another pack
+    let builtin = write_pack(source.path(), "mine.yaml", &["ocean"]);
+    assert!(theme_packs::install(&builtin, &dir).is_err());
+    let taken = write_pack(source.path(), "other.yaml", &["pack-test-dawn"]);
+    assert!(theme_packs::install(&taken, &dir).is_err());
+    let twice = write_pack(source.path(), "twice.yaml", &["pack-test-x", "pack-test-x"]);
+    assert!(theme_packs::install(&twice, &dir).is_err());
+    let invalid = source.path().join("invalid.yaml");
+    std::fs::write(
+        &invalid,
+        "- name: broken\n  desc: One color\n  colors:\n    - [1.0, 0.0, 0.0, 0.0]\n",
+    )
+    .unwrap();
+    assert!(theme_packs::install(&invalid.to_string_lossy(), &dir).is_err());
+    assert_eq!(theme_packs::list_packs(&dir).unwrap().len(), 1);
+
+    let removed = theme_packs::remove("morning", &dir).unwrap();
+    assert!(!removed.exists());
+    assert!(theme_packs::list_packs(&dir).unwrap().is_empty());
+    assert!(theme_packs::remove("morning", &dir).is_err());
+
+    // Names can't reach outside the themes directory
+    let outside = dir.parent().unwrap().join("outside.yaml");
+    std::fs::write(&outside, "").unwrap();
+    for name in ["../outside", "../outside.yaml", "..\\outside", ".."] {
+        assert!(theme_packs::remove(name, &dir).is_err(), "{}", name);
+    }
+    assert!(outside.exists());
+}
@@ -1,4 +1,5 @@
 use chromacat::error::ChromaCatError;
+use chromacat::pattern::{CommonParams, HorizontalParams, PatternConfig, PatternEngine, PatternParams};
 use chromacat::themes::{
     self, ColorStop, Distribution, Easing, Repeat, RepeatMode, ThemeDefinition,
 };
@@ -31,6 +32,11 @@ fn create_test_theme() -> ThemeDefinition {
         repeat: Repeat::Named(RepeatMode::None),
         speed: 1.0,
         ease: Easing::Linear,
+        light: None,
+        dark: None,
+        author: None,
+        tags: Vec::new(),
+        license: None,
     }
 }
 
@@ -274,3 +280,70 @@ fn test_invalid_theme_file() {
 
     assert!(themes::load_theme_file(temp_file.path()).is_err());
 }
+
+#[test]
+fn test_theme_position_mapping() {
+    let mut theme = create_test_theme();
+    assert_eq!(theme.position_at(0.3, 5.0), 0.3);
+
+    // Rotation moves at the theme's own speed and wraps around
+    theme.repeat = Repeat::Function("rotate".to_string(), 0.5);
+    theme.speed = 2.0;
+    assert!((theme.position_at(0.3, 0.5) - 0.8).abs() < 1e-6);
+    assert!((theme.position_at(0.3, 1.0) - 0.3).abs() < 1e-6);
+    assert!((theme.position_at(0.3, -0.5) - 0.8).abs() < 1e-6);
+
+    // Distribution comes first, easing last
+    let mut theme = create_test_theme();
+    theme.dist = Distribution::Front;
+    theme.ease = Easing::Smooth;
+    let t: f32 = 0.5 * 0.5;
+    assert!((theme.position_at(0.5, 0.0) - t * t * (3.0 - 2.0 * t)).abs() < 1e-6);
+}
+
+#[test]
+fn test_engine_honors_theme_animation() {
+    let config = || PatternConfig {
+        common: CommonParams::default(),
+        params: PatternParams::Horizontal(HorizontalParams::default()),
+        layers: Vec::new(),
+    };
+    let mut theme = create_test_theme();
+    theme.repeat = Repeat::Function("rotate".to_string(), 0.25);
+
+    let mut engine = PatternEngine::from_theme(&theme, config(), 10, 1).unwrap();
+    let start = engine.color_at(0.2);
+    engine.set_time(1.0);
+    let rotated = engine.color_at(0.2);
+    assert_ne!(start, rotated);
+
+    // The rotated color is the one a quarter further along the gradient
+    engine.set_time(0.0);
+    assert_eq!(rotated, engine.color_at(0.45));
+
+    // Plain gradients are sampled as they are
+    let plain = PatternEngine::new(theme.create_gradient().unwrap(), config(), 10, 1);
+    assert_eq!(plain.color_at(0.2), start);
+}
+
+#[test]
+fn test_theme_blend() {
+    let from = create_test_theme();
+    let to = themes::get_theme("ocean").unwrap();
+    let (a, b) = (from.create_gradient().unwrap(), to.create_gradient().unwrap());
+
+    // The ends of the blend are the two themes
+    let start = from.blend(&to, 0.0).unwrap().create_gradient().unwrap();
+    let end = from.blend(&to, 1.0).unwrap().create_gradient().unwrap();
+    for t in [0.0, 0.5, 1.0] {
+        assert!((start.at(t).r - a.at(t).r).abs() < 1e-3);
+        assert!((end.at(t).b - b.at(t).b).abs() < 1e-3);
+    }
+
+    // Halfway, each color is between the two
+    let half = from.blend(&to, 0.5).unwrap();
+    let middle = half.create_gradient().unwrap().at(0.0);
+    assert!((middle.r - (a.at(0.0).r + b.at(0.0).r) / 2.0).abs() < 1e-3);
+    assert_eq!(half.speed, from.speed);
+    assert_eq!(half.name, "test→ocean");
+}
@@ -0,0 +1,106 @@
+use chromacat::frames::PatternFrame;
+use chromacat::wled::{LedMap, WledOutput, WledProtocol, WledTarget, DEFAULT_WLED_PORT};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+#[test]
+fn test_wled_targets() {
+    let target: WledTarget = "wled://192.168.1.50".parse().unwrap();
+    assert_eq!(target.addr(), format!("192.168.1.50:{}", DEFAULT_WLED_PORT));
+    assert_eq!(target.protocol, None);
+
+    let target: WledTarget = "wled://strip.local:4048/?protocol=WARLS".parse().unwrap();
+    assert_eq!(target.addr(), "strip.local:4048");
+    assert_eq!(target.protocol, Some(WledProtocol::Warls));
+
+    for bad in [
+        "192.168.1.50",
+        "udp://strip",
+        "wled://",
+        "wled://strip:0",
+        "wled://strip?speed=2",
+    ] {
+        assert!(bad.parse::<WledTarget>().is_err(), "{} parsed", bad);
+    }
+}
+
+#[test]
+fn test_wled_packets() {
+    let colors = [[255, 0, 0], [0, 255, 0]];
+    assert_eq!(
+        WledProtocol::Warls.packets(&colors),
+        [vec![1, 2, 0, 255, 0, 0, 1, 0, 255, 0]]
+    );
+    assert_eq!(
+        WledProtocol::Drgb.packets(&colors),
+        [vec![2, 2, 255, 0, 0, 0, 255, 0]]
+    );
+
+    assert_eq!(WledProtocol::for_count(144), WledProtocol::Drgb);
+    assert_eq!(WledProtocol::for_count(600), WledProtocol::Dnrgb);
+
+    // Long strips are split, each packet saying where it starts
+    let packets = WledProtocol::Dnrgb.packets(&vec![[1, 2, 3]; 600]);
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0][..4], [4, 2, 0, 0]);
+    assert_eq!(packets[0].len(), 4 + 489 * 3);
+    assert_eq!(packets[1][..4], [4, 2, 0x01, 0xe9]);
+    assert_eq!(packets[1].len(), 4 + 111 * 3);
+}
+
+#[test]
+fn test_led_maps() {
+    let map: LedMap = "width: 3\nheight: 2\nserpentine: true\n".parse().unwrap();
+    assert_eq!(
+        map.positions,
+        [(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)]
+    );
+
+    let map: LedMap = "width: 4\nheight: 2\nleds: [[3, 1], [0, 0]]\n"
+        .parse()
+        .unwrap();
+    assert_eq!(map.len(), 2);
+    let frame = PatternFrame {
+        width: 4,
+        height: 2,
+        time: 0.0,
+        cells: (0..8).map(|i| [i, 0, 0]).collect(),
+    };
+    assert_eq!(map.colors(&frame), [[7, 0, 0], [0, 0, 0]]);
+    assert_eq!(
+        LedMap::line(3).colors(&frame),
+        [[0, 0, 0], [1, 0, 0], [2, 0, 0]]
+    );
+
+    for bad in [
+        "width: 0\nheight: 2\n",
+        "width: 2\nheight: 2\nleds: [[2, 0]]\n",
+        "width: 2\nheight: 2\nleds: []\n",
+        "width: 2\nheight: 2\nshape: ring\n",
+    ] {
+        assert!(bad.parse::<LedMap>().is_err(), "{} parsed", bad);
+    }
+}
+
+#[test]
+fn test_wled_output() {
+    let controller = UdpSocket::bind("127.0.0.1:0").unwrap();
+    controller
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let port = controller.local_addr().unwrap().port();
+
+    let target: WledTarget = format!("wled://127.0.0.1:{}", port).parse().unwrap();
+    let output = WledOutput::connect(&target, 2).unwrap();
+    assert_eq!(output.protocol(), WledProtocol::Drgb);
+    output.send(&[[10, 20, 30], [40, 50, 60]]).unwrap();
+
+    let mut packet = [0u8; 64];
+    let length = controller.recv(&mut packet).unwrap();
+    assert_eq!(packet[..length], [2, 2, 10, 20, 30, 40, 50, 60]);
+
+    let target: WledTarget = format!("wled://127.0.0.1:{}?protocol=warls", port)
+        .parse()
+        .unwrap();
+    assert!(WledOutput::connect(&target, 300).is_err());
+}
@@ -126,11 +126,15 @@ impl WebPGenerator {
                         speed: 1.0,
                         correct_aspect: true,
                         aspect_ratio: 0.5,
+                        zoom: 1.0,
+                        pan_x: 0.0,
+                        pan_y: 0.0,
                         theme_name: Some(theme.to_string()),
                     },
                     params: chromacat::pattern::REGISTRY
                         .create_pattern_params(pattern)
                         .ok_or_else(|| anyhow::anyhow!("Invalid pattern: {}", pattern))?,
+                    layers: Vec::new(),
                 };
 
                 self.generate_pattern_webp(pattern, pattern_config)